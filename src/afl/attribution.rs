@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use std::fmt::Write;
+
+/// Amount of top contributors the unique region count is approximated for
+///
+/// Every approximation costs an extra merge of all other instances.
+pub const UNIQUE_TOP_CONTRIBUTORS: usize = 5;
+
+/// Region totals reported by `llvm-cov export -summary-only`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionSummary {
+    pub count: u64,
+    pub covered: u64,
+}
+
+impl RegionSummary {
+    /// Parses the region totals out of the JSON emitted by `llvm-cov export -summary-only`
+    ///
+    /// # Errors
+    /// * If the JSON is malformed or lacks the region totals
+    pub fn parse(json: &str) -> Result<Self> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).context("Failed to parse llvm-cov export output")?;
+        let regions = &value["data"][0]["totals"]["regions"];
+        let field = |name: &str| {
+            regions[name]
+                .as_u64()
+                .with_context(|| format!("llvm-cov export output lacks totals.regions.{name}"))
+        };
+
+        Ok(Self {
+            count: field("count")?,
+            covered: field("covered")?,
+        })
+    }
+}
+
+/// Coverage contributed by a single fuzzer instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceContribution {
+    /// Name of the instance directory, e.g. `m_fuzzer01`
+    pub name: String,
+    /// Regions covered by the queue of this instance alone
+    pub covered: u64,
+    /// Regions only this instance covers, approximated as total minus the merge of all others
+    pub unique: Option<u64>,
+}
+
+/// Formats the per-instance contributions as a table, sorted by covered regions
+pub fn format_contributions(contributions: &[InstanceContribution], total_regions: u64) -> String {
+    let mut sorted: Vec<_> = contributions.iter().collect();
+    sorted.sort_by(|a, b| b.covered.cmp(&a.covered).then(a.name.cmp(&b.name)));
+
+    let width = sorted
+        .iter()
+        .map(|c| c.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Instance".len());
+
+    let mut table = format!(
+        "{:<width$} | {:>18} | {:>8}\n",
+        "Instance", "Regions", "Unique"
+    );
+    table.push_str(&"-".repeat(width + 32));
+    for c in sorted {
+        #[allow(clippy::cast_precision_loss)]
+        let percent = if total_regions == 0 {
+            0.0
+        } else {
+            c.covered as f64 * 100.0 / total_regions as f64
+        };
+        let unique = c.unique.map_or_else(|| "-".to_string(), |u| u.to_string());
+        let _ = write!(
+            table,
+            "\n{:<width$} | {:>9} ({:>5.1}%) | {:>8}",
+            c.name, c.covered, percent, unique
+        );
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_region_summary() {
+        let json = r#"{"data":[{"totals":{"regions":{"count":200,"covered":50,"notcovered":150,"percent":25}}}],"type":"llvm.coverage.json.export"}"#;
+        assert_eq!(
+            RegionSummary::parse(json).unwrap(),
+            RegionSummary {
+                count: 200,
+                covered: 50
+            }
+        );
+
+        assert!(RegionSummary::parse(r#"{"data":[]}"#).is_err());
+        assert!(RegionSummary::parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_format_contributions() {
+        let contributions = [
+            InstanceContribution {
+                name: "s_fuzzer01".into(),
+                covered: 10,
+                unique: None,
+            },
+            InstanceContribution {
+                name: "m_fuzzer".into(),
+                covered: 50,
+                unique: Some(3),
+            },
+        ];
+
+        let table = format_contributions(&contributions, 200);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].starts_with("m_fuzzer  "));
+        assert!(lines[2].contains("50 ( 25.0%)"));
+        assert!(lines[2].ends_with('3'));
+        assert!(lines[3].starts_with("s_fuzzer01"));
+        assert!(lines[3].ends_with('-'));
+    }
+}
@@ -1,7 +1,23 @@
 use std::path::PathBuf;
 
+use crate::afl::llvm_env::LlvmEnv;
+use crate::afl::map_size::MapSize;
+use crate::afl::target_env::TargetEnv;
+use crate::utils::duration::DurationOpt;
+
 use crate::utils::system::create_ramdisk;
 
+/// Raw AFL++ flags from the config
+#[derive(Clone, Debug, Default)]
+pub struct RawAflFlags {
+    /// Flags applied to all instances
+    pub all: Option<String>,
+    /// Flags only applied to the main instance
+    pub master: Option<String>,
+    /// Flags only applied to the secondary instances
+    pub secondary: Option<String>,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Bcfg {
     /// Input directory for AFL
@@ -10,12 +26,42 @@ pub struct Bcfg {
     pub output_dir: PathBuf,
     /// Path to the dictionary file/directory
     pub dictionary: Option<String>,
+    /// Fraction of instances that get the dictionary, all of them if unset
+    pub dictionary_ratio: Option<f64>,
+    /// Never pass the dictionary to the CMPCOV instances
+    pub dictionary_skip_cmpcov: bool,
     /// Raw AFL++ flags
     pub raw_afl_flags: Option<String>,
+    /// Raw AFL++ flags for the main instance
+    pub raw_afl_flags_master: Option<String>,
+    /// Raw AFL++ flags for the secondary instances
+    pub raw_afl_flags_secondary: Option<String>,
     /// Path to the AFL++ binary
     pub afl_binary: Option<String>,
     /// Path to the `RAMDisk`
     pub ramdisk: Option<String>,
+    /// Requested `AFL_MAP_SIZE`
+    pub map_size: Option<MapSize>,
+    /// Minimum length of generated test cases, `-g`
+    pub min_len: Option<u32>,
+    /// Maximum length of generated test cases, `-G`
+    pub max_len: Option<u32>,
+    /// Time between two syncs of the secondary instances, `AFL_SYNC_TIME`
+    pub sync_time: Option<DurationOpt>,
+    /// Executable run by AFL++ via `-I` whenever a new crash is saved
+    pub crash_hook: Option<PathBuf>,
+    /// Attach the crash hook to all instances instead of the main instance only
+    pub crash_hook_all: bool,
+    /// Template of the per-instance `-f` input file, see `FIXED_INPUT_PLACEHOLDERS`
+    pub fixed_input_file: Option<String>,
+    /// Environment of the harness, handed over via `AFL_TARGET_ENV`
+    pub target_env: TargetEnv,
+    /// `;`-separated custom mutator libraries
+    pub custom_mutator: Option<String>,
+    /// Fraction of instances that load the custom mutator, all of them if unset
+    pub custom_mutator_ratio: Option<f64>,
+    /// `AFL_LLVM_*` variables exported to all instances
+    pub llvm_env: LlvmEnv,
 }
 
 impl Bcfg {
@@ -34,8 +80,17 @@ impl Bcfg {
         self
     }
 
-    pub fn with_raw_afl_flags(mut self, raw_afl_flags: Option<&String>) -> Self {
-        self.raw_afl_flags = raw_afl_flags.cloned();
+    pub fn with_dictionary_ratio(mut self, ratio: Option<f64>, skip_cmpcov: bool) -> Self {
+        self.dictionary_ratio = ratio;
+        self.dictionary_skip_cmpcov = skip_cmpcov;
+        self
+    }
+
+    pub fn with_raw_afl_flags(mut self, raw_afl_flags: &RawAflFlags) -> Self {
+        self.raw_afl_flags.clone_from(&raw_afl_flags.all);
+        self.raw_afl_flags_master.clone_from(&raw_afl_flags.master);
+        self.raw_afl_flags_secondary
+            .clone_from(&raw_afl_flags.secondary);
         self
     }
 
@@ -44,6 +99,49 @@ impl Bcfg {
         self
     }
 
+    pub fn with_map_size(mut self, map_size: Option<MapSize>) -> Self {
+        self.map_size = map_size;
+        self
+    }
+
+    pub fn with_input_len(mut self, min_len: Option<u32>, max_len: Option<u32>) -> Self {
+        self.min_len = min_len;
+        self.max_len = max_len;
+        self
+    }
+
+    pub fn with_sync_time(mut self, sync_time: Option<DurationOpt>) -> Self {
+        self.sync_time = sync_time;
+        self
+    }
+
+    pub fn with_crash_hook(mut self, crash_hook: Option<PathBuf>, all: bool) -> Self {
+        self.crash_hook = crash_hook;
+        self.crash_hook_all = all;
+        self
+    }
+
+    pub fn with_custom_mutator(mut self, libraries: Option<String>, ratio: Option<f64>) -> Self {
+        self.custom_mutator = libraries;
+        self.custom_mutator_ratio = ratio;
+        self
+    }
+
+    pub fn with_llvm_env(mut self, llvm_env: LlvmEnv) -> Self {
+        self.llvm_env = llvm_env;
+        self
+    }
+
+    pub fn with_fixed_input_file(mut self, fixed_input_file: Option<String>) -> Self {
+        self.fixed_input_file = fixed_input_file;
+        self
+    }
+
+    pub fn with_target_env(mut self, target_env: TargetEnv) -> Self {
+        self.target_env = target_env;
+        self
+    }
+
     pub fn with_ramdisk(mut self, is_ramdisk: bool) -> Self {
         let rdisk = is_ramdisk
             .then(|| create_ramdisk().map_err(|e| println!("[!] Failed to create RAMDisk: {e}")))
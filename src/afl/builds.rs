@@ -0,0 +1,159 @@
+use anyhow::{bail, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::utils::system::is_executable;
+
+/// Auxiliary build of the target that is picked up next to the main binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildRole {
+    Sanitizer,
+    Cmplog,
+    Cmpcov,
+    Coverage,
+}
+
+impl BuildRole {
+    pub const ALL: [Self; 4] = [Self::Sanitizer, Self::Cmplog, Self::Cmpcov, Self::Coverage];
+
+    /// File name suffixes appended to the main binary name
+    const fn suffixes(self) -> &'static [&'static str] {
+        match self {
+            Self::Sanitizer => &[".san", "_asan"],
+            Self::Cmplog => &[".cmplog", "_cmplog"],
+            Self::Cmpcov => &[".laf", "_laf"],
+            Self::Coverage => &[".cov", "_cov"],
+        }
+    }
+
+    /// Sibling directories that contain a binary with the same name
+    const fn directories(self) -> &'static [&'static str] {
+        match self {
+            Self::Cmplog => &["cmplog"],
+            Self::Cmpcov => &["laf"],
+            Self::Sanitizer | Self::Coverage => &[],
+        }
+    }
+
+    /// Candidate paths for this role next to `target`
+    fn candidates(self, target: &Path) -> Vec<PathBuf> {
+        let (Some(dir), Some(name)) = (target.parent(), target.file_name()) else {
+            return Vec::new();
+        };
+        let name = name.to_string_lossy();
+        self.suffixes()
+            .iter()
+            .map(|suffix| dir.join(format!("{name}{suffix}")))
+            .chain(
+                self.directories()
+                    .iter()
+                    .map(|sub| dir.join(sub).join(&*name)),
+            )
+            .collect()
+    }
+}
+
+impl fmt::Display for BuildRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Sanitizer => "sanitizer",
+            Self::Cmplog => "cmplog",
+            Self::Cmpcov => "cmpcov",
+            Self::Coverage => "coverage",
+        })
+    }
+}
+
+/// Auxiliary builds found next to the main target
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DetectedBuilds {
+    pub sanitizer: Option<PathBuf>,
+    pub cmplog: Option<PathBuf>,
+    pub cmpcov: Option<PathBuf>,
+    pub coverage: Option<PathBuf>,
+}
+
+impl DetectedBuilds {
+    fn slot(&mut self, role: BuildRole) -> &mut Option<PathBuf> {
+        match role {
+            BuildRole::Sanitizer => &mut self.sanitizer,
+            BuildRole::Cmplog => &mut self.cmplog,
+            BuildRole::Cmpcov => &mut self.cmpcov,
+            BuildRole::Coverage => &mut self.coverage,
+        }
+    }
+}
+
+/// Probes for auxiliary builds next to `target` following common naming conventions
+///
+/// Recognized are `target.cmplog`/`target_cmplog`, `target.laf`/`target_laf`,
+/// `target.san`/`target_asan`, `target.cov`/`target_cov` as well as `cmplog/target` and
+/// `laf/target`. Only executable files count, roles in `skip` are not probed.
+///
+/// # Errors
+/// * If more than one candidate exists for the same role
+pub fn detect_builds(target: &Path, skip: &[BuildRole]) -> Result<DetectedBuilds> {
+    let mut detected = DetectedBuilds::default();
+    for role in BuildRole::ALL.into_iter().filter(|r| !skip.contains(r)) {
+        let found: Vec<PathBuf> = role
+            .candidates(target)
+            .into_iter()
+            .filter(|p| is_executable(p))
+            .collect();
+        match found.as_slice() {
+            [] => {}
+            [path] => *detected.slot(role) = Some(path.clone()),
+            _ => bail!(
+                "Ambiguous {role} build for {}, found {}. Set it explicitly",
+                target.display(),
+                found
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            ),
+        }
+    }
+    Ok(detected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn create_binary(path: &Path, mode: u32) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn test_detect_builds() {
+        let tmp = TempDir::new().unwrap();
+        let target = tmp.path().join("target");
+        create_binary(&target, 0o755);
+        create_binary(&tmp.path().join("target.cmplog"), 0o755);
+        create_binary(&tmp.path().join("laf/target"), 0o755);
+        create_binary(&tmp.path().join("target_asan"), 0o755);
+        // Not executable, e.g. a coverage report rather than a build
+        create_binary(&tmp.path().join("target.cov"), 0o644);
+
+        let detected = detect_builds(&target, &[]).unwrap();
+        assert_eq!(detected.cmplog, Some(tmp.path().join("target.cmplog")));
+        assert_eq!(detected.cmpcov, Some(tmp.path().join("laf/target")));
+        assert_eq!(detected.sanitizer, Some(tmp.path().join("target_asan")));
+        assert_eq!(detected.coverage, None);
+
+        let detected = detect_builds(&target, &[BuildRole::Cmplog]).unwrap();
+        assert_eq!(detected.cmplog, None);
+
+        create_binary(&tmp.path().join("cmplog/target"), 0o755);
+        let err = detect_builds(&target, &[]).unwrap_err().to_string();
+        assert!(err.contains("Ambiguous cmplog build"), "{err}");
+        assert!(err.contains("target.cmplog") && err.contains("cmplog/target"));
+        assert!(detect_builds(&target, &[BuildRole::Cmplog]).is_ok());
+    }
+}
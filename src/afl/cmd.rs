@@ -71,6 +71,15 @@ impl AFLCmd {
         self.misc_afl_flags.push(flag);
     }
 
+    /// Name of the instance as passed via `-M` or `-S`
+    pub fn instance_name(&self) -> Option<&str> {
+        self.misc_afl_flags.iter().find_map(|flag| {
+            flag.strip_prefix("-M ")
+                .or_else(|| flag.strip_prefix("-S "))
+                .map(str::trim)
+        })
+    }
+
     /// Assembles the AFL++ command into a string
     pub fn assemble(&self) -> String {
         let mut cmd_parts = Vec::new();
@@ -91,6 +100,16 @@ impl AFLCmd {
     pub fn print(&self) {
         println!("{}", self.assemble());
     }
+
+    /// Returns the environment variables of this command as a single line
+    pub fn env_block(&self) -> String {
+        self.env
+            .iter()
+            .map(|e| e.trim())
+            .filter(|e| !e.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
 impl std::fmt::Display for AFLCmd {
@@ -101,12 +120,19 @@ impl std::fmt::Display for AFLCmd {
 
 pub trait Printable {
     fn print(&self);
+    /// Prints the command(s) together with the environment block of each instance
+    fn print_with_env(&self);
 }
 
 impl Printable for AFLCmd {
     fn print(&self) {
         println!("{self}");
     }
+
+    fn print_with_env(&self) {
+        println!("{self}");
+        println!("       env: {}", self.env_block());
+    }
 }
 
 impl Printable for Vec<AFLCmd> {
@@ -117,6 +143,14 @@ impl Printable for Vec<AFLCmd> {
             cmd.print();
         }
     }
+
+    fn print_with_env(&self) {
+        println!("Generated commands:");
+        for (i, cmd) in self.iter().enumerate() {
+            print!("  {i:3}. ");
+            cmd.print_with_env();
+        }
+    }
 }
 
 pub trait ToStringVec {
@@ -128,3 +162,27 @@ impl ToStringVec for Vec<AFLCmd> {
         self.iter().map(std::string::ToString::to_string).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_block() {
+        let mut cmd = AFLCmd::new(PathBuf::from("afl-fuzz"), PathBuf::from("/bin/target"));
+        assert!(cmd.env_block().is_empty());
+
+        cmd.with_env(
+            vec![
+                "AFL_FINAL_SYNC=1".to_string(),
+                "AFL_DISABLE_TRIM=1".to_string(),
+                "AFL_TESTCACHE_SIZE=50 ".to_string(),
+            ],
+            false,
+        );
+        assert_eq!(
+            cmd.env_block(),
+            "AFL_FINAL_SYNC=1 AFL_DISABLE_TRIM=1 AFL_TESTCACHE_SIZE=50"
+        );
+    }
+}
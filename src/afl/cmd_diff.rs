@@ -0,0 +1,603 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::afl::env::AFLFlag;
+use crate::afl::flags::{self, Arity};
+use crate::tui::data_collection::instance_dirs;
+use crate::utils::campaign::CampaignRecord;
+
+/// File AFL++ stores the arguments of an instance in, one per line
+const CMDLINE_FILE: &str = "cmdline";
+/// Flags that name the instance and are therefore not compared as flags
+const ROLE_FLAGS: [&str; 2] = ["-M", "-S"];
+
+/// A generated `afl-fuzz` command split into the parts that are compared
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstanceCommand {
+    /// Value of `-M`/`-S`, `#<index>` for commands without one
+    pub name: String,
+    /// `None` if read from an AFL++ `cmdline` file, which lacks the environment
+    pub env: Option<BTreeMap<String, String>>,
+    pub afl_binary: String,
+    /// Flags besides `-M`/`-S` along with their value, in order
+    pub flags: Vec<(String, Option<String>)>,
+    /// Target binary and its arguments
+    pub target: String,
+    /// The complete command as it was read
+    pub command: String,
+}
+
+/// A single difference of an instance present in both command sets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    /// All occurrences of `flag`, e.g. `-p fast`, `None` if absent
+    Flag {
+        flag: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    Env {
+        key: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    AflBinary {
+        old: String,
+        new: String,
+    },
+    Target {
+        old: String,
+        new: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstanceDiff {
+    pub name: String,
+    pub changes: Vec<Change>,
+}
+
+/// An instance only present in one of the command sets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NamedCommand {
+    pub name: String,
+    pub command: String,
+}
+
+/// Differences between two command sets, instances are matched by name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CommandDiff {
+    pub added: Vec<NamedCommand>,
+    pub removed: Vec<NamedCommand>,
+    pub changed: Vec<InstanceDiff>,
+    pub unchanged: usize,
+    /// Whether environment variables were compared, old sets from `cmdline` files lack them
+    pub env_compared: bool,
+}
+
+impl InstanceCommand {
+    /// Parses a generated command line like `AFL_FINAL_SYNC=1 afl-fuzz -i in -o out -M m -- ./t`
+    pub fn parse(cmd: &str, index: usize) -> Option<Self> {
+        let tokens = split_words(cmd);
+        let env_len = tokens.iter().take_while(|t| is_assignment(t)).count();
+        let env = tokens[..env_len]
+            .iter()
+            .filter_map(|t| t.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let mut parsed = Self::from_argv(&tokens[env_len..], index)?;
+        parsed.env = Some(env);
+        parsed.command = cmd.trim().to_string();
+        Some(parsed)
+    }
+
+    /// Like `parse`, but rejects hand-written commands with unterminated quotes instead of
+    /// guessing where the quote ends
+    ///
+    /// # Errors
+    /// * If a quote is not closed or the line holds no `afl-fuzz` invocation
+    pub fn parse_strict(cmd: &str, index: usize) -> Result<Self> {
+        if let Some(quote) = unterminated_quote(cmd) {
+            bail!("unterminated {quote} quote");
+        }
+        Self::parse(cmd, index).context("no afl-fuzz invocation, only environment variables")
+    }
+
+    /// The command line with `AFL_AUTORESUME=1` added unless it is set already, so AFL++ picks
+    /// up the existing instance directory instead of refusing to start
+    pub fn with_autoresume(&self) -> String {
+        let key = AFLFlag::AutoResume.to_string();
+        if self.env.as_ref().is_some_and(|env| env.contains_key(&key)) {
+            self.command.clone()
+        } else {
+            format!("{key}=1 {}", self.command)
+        }
+    }
+
+    /// Builds the command from the arguments of `afl-fuzz`, starting with the binary
+    pub fn from_argv(argv: &[String], index: usize) -> Option<Self> {
+        let (afl_binary, args) = argv.split_first()?;
+        let separator = args.iter().position(|arg| arg == "--");
+        let (afl_args, target) = match separator {
+            Some(idx) => (&args[..idx], &args[idx + 1..]),
+            None => (args, &[][..]),
+        };
+
+        let mut name = None;
+        let mut flags = Vec::new();
+        let mut idx = 0;
+        while let Some(flag) = afl_args.get(idx) {
+            idx += 1;
+            let takes_value = flags::arity(flag) == Some(Arity::Value)
+                || (flags::arity(flag).is_none()
+                    && afl_args.get(idx).is_some_and(|next| !flags::is_flag(next)));
+            let value = afl_args.get(idx).filter(|_| takes_value).cloned();
+            if value.is_some() {
+                idx += 1;
+            }
+            if ROLE_FLAGS.contains(&flag.as_str()) {
+                name = value;
+            } else {
+                flags.push((flag.clone(), value));
+            }
+        }
+
+        Some(Self {
+            name: name.unwrap_or_else(|| format!("#{index}")),
+            env: None,
+            afl_binary: afl_binary.clone(),
+            flags,
+            target: target.join(" "),
+            command: argv.join(" "),
+        })
+    }
+
+    /// All occurrences of every flag, rendered like `-x a.dict -x b.dict`
+    fn flag_map(&self) -> BTreeMap<&str, String> {
+        let mut map: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for (flag, value) in &self.flags {
+            map.entry(flag.as_str()).or_default().push(
+                value
+                    .as_ref()
+                    .map_or_else(|| flag.clone(), |value| format!("{flag} {value}")),
+            );
+        }
+        map.into_iter()
+            .map(|(flag, occurrences)| (flag, occurrences.join(" ")))
+            .collect()
+    }
+
+    /// Changes from `self` to `new`, empty if they are equal
+    fn changes(&self, new: &Self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if self.afl_binary != new.afl_binary {
+            changes.push(Change::AflBinary {
+                old: self.afl_binary.clone(),
+                new: new.afl_binary.clone(),
+            });
+        }
+        if self.target != new.target {
+            changes.push(Change::Target {
+                old: self.target.clone(),
+                new: new.target.clone(),
+            });
+        }
+
+        let (old_flags, new_flags) = (self.flag_map(), new.flag_map());
+        let flag_names: BTreeSet<&str> =
+            old_flags.keys().chain(new_flags.keys()).copied().collect();
+        for flag in flag_names {
+            let (old, new) = (old_flags.get(flag), new_flags.get(flag));
+            if old != new {
+                changes.push(Change::Flag {
+                    flag: flag.to_string(),
+                    old: old.cloned(),
+                    new: new.cloned(),
+                });
+            }
+        }
+
+        if let (Some(old_env), Some(new_env)) = (&self.env, &new.env) {
+            let keys: BTreeSet<&String> = old_env.keys().chain(new_env.keys()).collect();
+            for key in keys {
+                let (old, new) = (old_env.get(key), new_env.get(key));
+                if old != new {
+                    changes.push(Change::Env {
+                        key: key.clone(),
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    });
+                }
+            }
+        }
+        changes
+    }
+}
+
+impl CommandDiff {
+    /// Compares `old` and `new`, matching instances by name so renumbering is no change
+    pub fn new(old: &[InstanceCommand], new: &[InstanceCommand]) -> Self {
+        let named = |cmd: &InstanceCommand| NamedCommand {
+            name: cmd.name.clone(),
+            command: cmd.command.clone(),
+        };
+        let old_by_name: BTreeMap<&str, &InstanceCommand> =
+            old.iter().map(|cmd| (cmd.name.as_str(), cmd)).collect();
+        let new_names: BTreeSet<&str> = new.iter().map(|cmd| cmd.name.as_str()).collect();
+
+        let mut diff = Self {
+            removed: old
+                .iter()
+                .filter(|cmd| !new_names.contains(cmd.name.as_str()))
+                .map(named)
+                .collect(),
+            env_compared: old.iter().chain(new).all(|cmd| cmd.env.is_some()),
+            ..Self::default()
+        };
+        for cmd in new {
+            let Some(previous) = old_by_name.get(cmd.name.as_str()) else {
+                diff.added.push(named(cmd));
+                continue;
+            };
+            let changes = previous.changes(cmd);
+            if changes.is_empty() {
+                diff.unchanged += 1;
+            } else {
+                diff.changed.push(InstanceDiff {
+                    name: cmd.name.clone(),
+                    changes,
+                });
+            }
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Renders the diff like a unified diff, colored with ANSI escapes if `color` is set
+    pub fn render(&self, color: bool) -> String {
+        let paint = |code: &str, line: String| {
+            if color {
+                format!("\x1b[{code}m{line}\x1b[0m")
+            } else {
+                line
+            }
+        };
+        let removed = |line: String| paint("31", format!("-{line}"));
+        let added = |line: String| paint("32", format!("+{line}"));
+
+        let mut lines = Vec::new();
+        for cmd in &self.removed {
+            lines.push(paint("1", format!("@@ {} (removed)", cmd.name)));
+            lines.push(removed(format!(" {}", cmd.command)));
+        }
+        for cmd in &self.added {
+            lines.push(paint("1", format!("@@ {} (added)", cmd.name)));
+            lines.push(added(format!(" {}", cmd.command)));
+        }
+        for instance in &self.changed {
+            lines.push(paint("1", format!("@@ {}", instance.name)));
+            for change in &instance.changes {
+                let (old, new) = match change {
+                    Change::Flag { old, new, .. } => (old.clone(), new.clone()),
+                    Change::Env { key, old, new } => (
+                        old.as_ref().map(|v| format!("{key}={v}")),
+                        new.as_ref().map(|v| format!("{key}={v}")),
+                    ),
+                    Change::AflBinary { old, new } => (Some(old.clone()), Some(new.clone())),
+                    Change::Target { old, new } => {
+                        (Some(format!("-- {old}")), Some(format!("-- {new}")))
+                    }
+                };
+                lines.extend(old.map(|old| removed(format!(" {old}"))));
+                lines.extend(new.map(|new| added(format!(" {new}"))));
+            }
+        }
+        lines.push(format!(
+            "{} added, {} removed, {} changed, {} unchanged instance(s)",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len(),
+            self.unchanged
+        ));
+        lines.join("\n")
+    }
+}
+
+/// Loads a previously generated command set
+///
+/// `path` is either a JSON array of commands, a campaign record (`.aflr/campaign.json`) or an
+/// output directory. For output directories without recorded commands, e.g. of campaigns
+/// launched by older versions, the `cmdline` files AFL++ left in the instance directories are
+/// used, without the environment.
+///
+/// # Errors
+/// * If `path` cannot be read or holds no commands
+pub fn load_commands(path: &Path) -> Result<Vec<InstanceCommand>> {
+    let lines = if path.is_dir() {
+        let recorded = CampaignRecord::load(path)
+            .map(|record| record.commands)
+            .unwrap_or_default();
+        if recorded.is_empty() {
+            return load_cmdlines(path);
+        }
+        recorded
+    } else {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str::<Vec<String>>(&content)
+            .or_else(|_| {
+                serde_json::from_str::<CampaignRecord>(&content).map(|record| record.commands)
+            })
+            .with_context(|| {
+                format!(
+                    "{} is neither a JSON array of commands nor a campaign record",
+                    path.display()
+                )
+            })?
+    };
+    let commands: Vec<InstanceCommand> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| InstanceCommand::parse(line, idx))
+        .collect();
+    if commands.is_empty() {
+        bail!("No commands found in {}", path.display());
+    }
+    Ok(commands)
+}
+
+/// Commands of the instances in `output_dir` as recorded by AFL++ itself
+fn load_cmdlines(output_dir: &Path) -> Result<Vec<InstanceCommand>> {
+    let mut instances = instance_dirs(output_dir);
+    instances.sort();
+    let commands: Vec<InstanceCommand> = instances
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, (_, path))| {
+            let content = fs::read_to_string(path.join(CMDLINE_FILE)).ok()?;
+            let argv: Vec<String> = content.lines().map(str::to_string).collect();
+            InstanceCommand::from_argv(&argv, idx)
+        })
+        .collect();
+    if commands.is_empty() {
+        bail!(
+            "No recorded commands or AFL++ cmdline files found in {}",
+            output_dir.display()
+        );
+    }
+    Ok(commands)
+}
+
+fn is_assignment(token: &str) -> bool {
+    token.split_once('=').is_some_and(|(key, _)| {
+        key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Splits a command line into words, honoring the quotes `shell_quote` emits
+fn split_words(cmd: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = cmd.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '"'));
+            }
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// The quote `split_words` would run past the end of `cmd` with, if any
+fn unterminated_quote(cmd: &str) -> Option<char> {
+    let mut chars = cmd.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' if !chars.by_ref().any(|next| next == c) => return Some(c),
+            '\\' => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commands(lines: &[&str]) -> Vec<InstanceCommand> {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| InstanceCommand::parse(line, idx).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_command() {
+        let cmd = InstanceCommand::parse(
+            "AFL_FINAL_SYNC=1 AFL_CUSTOM_MUTATOR_LIBRARY='/lib/my mut.so' afl-fuzz -i in -o out -p fast -Z -M m_t -- ./t --in @@",
+            0,
+        )
+        .unwrap();
+        assert_eq!(cmd.name, "m_t");
+        assert_eq!(
+            cmd.env.as_ref().unwrap()["AFL_CUSTOM_MUTATOR_LIBRARY"],
+            "/lib/my mut.so"
+        );
+        assert_eq!(cmd.afl_binary, "afl-fuzz");
+        assert!(cmd
+            .flags
+            .contains(&("-p".to_string(), Some("fast".to_string()))));
+        assert!(cmd.flags.contains(&("-Z".to_string(), None)));
+        assert_eq!(cmd.target, "./t --in @@");
+        assert_eq!(
+            InstanceCommand::parse("afl-fuzz -i in -- ./t", 3)
+                .unwrap()
+                .name,
+            "#3"
+        );
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        assert!(InstanceCommand::parse_strict("A='x y' afl-fuzz -i \"in\" -- ./t \\'", 0).is_ok());
+        let err =
+            InstanceCommand::parse_strict("afl-fuzz -i in -x 'my.dict -- ./t", 0).unwrap_err();
+        assert_eq!(err.to_string(), "unterminated ' quote");
+        assert!(InstanceCommand::parse_strict("AFL_DEBUG=1", 0).is_err());
+    }
+
+    #[test]
+    fn test_diff_matches_instances_by_name() {
+        let old = commands(&[
+            "AFL_FINAL_SYNC=1 afl-fuzz -i in -o out -p fast -M m_t -- ./t",
+            "AFL_DISABLE_TRIM=1 afl-fuzz -i in -o out -p explore -Z -S s0_t -- ./t",
+            "afl-fuzz -i in -o out -S s1_t -- ./t",
+        ]);
+        // The secondaries swapped places and s1_t got a new power schedule
+        let new = commands(&[
+            "AFL_FINAL_SYNC=1 afl-fuzz -i in -o out -p fast -M m_t -- ./t",
+            "afl-fuzz -i in -o out -p rare -S s1_t -- ./t",
+            "AFL_DISABLE_TRIM=1 afl-fuzz -i in -o out -p explore -Z -S s0_t -- ./t",
+        ]);
+        let diff = CommandDiff::new(&old, &new);
+        assert_eq!(diff.unchanged, 2);
+        assert_eq!(
+            diff.changed,
+            [InstanceDiff {
+                name: "s1_t".to_string(),
+                changes: vec![Change::Flag {
+                    flag: "-p".to_string(),
+                    old: None,
+                    new: Some("-p rare".to_string()),
+                }],
+            }]
+        );
+        assert!(CommandDiff::new(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_changes() {
+        let old = commands(&[
+            "AFL_FINAL_SYNC=1 AFL_MAP_SIZE=65536 /opt/afl/afl-fuzz -i in -o out -x a.dict -M m_t -- ./t @@",
+            "afl-fuzz -i in -o out -S s0_t -- ./t @@",
+        ]);
+        let new = commands(&[
+            "AFL_FINAL_SYNC=1 AFL_MAP_SIZE=262144 AFL_IMPORT_FIRST=1 afl-fuzz -i in -o out -x a.dict -x b.dict -M m_t -- ./t_v2 @@",
+            "afl-fuzz -i in -o out -S s2_t -- ./t @@",
+        ]);
+        let diff = CommandDiff::new(&old, &new);
+        assert_eq!(
+            diff.removed,
+            [NamedCommand {
+                name: "s0_t".to_string(),
+                command: "afl-fuzz -i in -o out -S s0_t -- ./t @@".to_string(),
+            }]
+        );
+        assert_eq!(diff.added[0].name, "s2_t");
+        assert_eq!(
+            diff.changed[0].changes,
+            [
+                Change::AflBinary {
+                    old: "/opt/afl/afl-fuzz".to_string(),
+                    new: "afl-fuzz".to_string(),
+                },
+                Change::Target {
+                    old: "./t @@".to_string(),
+                    new: "./t_v2 @@".to_string(),
+                },
+                Change::Flag {
+                    flag: "-x".to_string(),
+                    old: Some("-x a.dict".to_string()),
+                    new: Some("-x a.dict -x b.dict".to_string()),
+                },
+                Change::Env {
+                    key: "AFL_IMPORT_FIRST".to_string(),
+                    old: None,
+                    new: Some("1".to_string()),
+                },
+                Change::Env {
+                    key: "AFL_MAP_SIZE".to_string(),
+                    old: Some("65536".to_string()),
+                    new: Some("262144".to_string()),
+                },
+            ]
+        );
+
+        let rendered = diff.render(false);
+        assert!(
+            rendered
+                .contains("@@ m_t\n- /opt/afl/afl-fuzz\n+ afl-fuzz\n- -- ./t @@\n+ -- ./t_v2 @@"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("- AFL_MAP_SIZE=65536\n+ AFL_MAP_SIZE=262144"));
+        assert!(rendered.ends_with("1 added, 1 removed, 1 changed, 0 unchanged instance(s)"));
+    }
+
+    #[test]
+    fn test_load_commands() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("commands.json");
+        fs::write(&file, r#"["afl-fuzz -i in -o out -M m_t -- ./t"]"#).unwrap();
+        assert_eq!(load_commands(&file).unwrap()[0].name, "m_t");
+
+        // Output directories of older campaigns only have the AFL++ cmdline files
+        let instance = tmp.path().join("out/s0_t");
+        fs::create_dir_all(&instance).unwrap();
+        fs::write(instance.join("fuzzer_stats"), "").unwrap();
+        fs::write(
+            instance.join(CMDLINE_FILE),
+            "afl-fuzz\n-i\nin\n-o\nout\n-S\ns0_t\n--\n./t\n",
+        )
+        .unwrap();
+        let old = load_commands(&tmp.path().join("out")).unwrap();
+        assert_eq!(old[0].name, "s0_t");
+        assert!(old[0].env.is_none());
+        let diff = CommandDiff::new(
+            &old,
+            &commands(&["AFL_FINAL_SYNC=1 afl-fuzz -i in -o out -S s0_t -- ./t"]),
+        );
+        assert!(diff.is_empty());
+        assert!(!diff.env_compared);
+
+        fs::write(&file, "{}").unwrap();
+        assert!(load_commands(&file).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+use tempfile::Builder;
+
+use crate::afl::cmd_diff::InstanceCommand;
+
+/// Prefix of the lines that report why the edited commands were rejected
+const ERROR_PREFIX: &str = "# ERROR: ";
+/// Explanation on top of the file opened in the editor
+const HEADER: &str = "\
+# Edit the generated commands below, one instance per line.
+# Lines starting with '#' are ignored. Delete all commands to abort the launch.
+";
+
+/// Editor to open, `$VISUAL` or `$EDITOR` with `vi` as the fallback
+pub fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|e| !e.trim().is_empty()))
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Lets the user hand-tweak `commands` in `editor` and returns the edited set
+///
+/// Edits that fail to parse or validate reopen the editor with the errors on top, so nothing
+/// the user typed is lost. `editor` may hold arguments, e.g. `code --wait`.
+///
+/// # Errors
+/// * If the editor cannot be spawned or exits with an error
+/// * If the user deleted all commands
+pub fn edit_commands(commands: &[String], editor: &str) -> Result<Vec<String>> {
+    let mut file = Builder::new().suffix(".sh").tempfile()?;
+    file.write_all(render(&commands.join("\n"), &[]).as_bytes())?;
+    file.flush()?;
+
+    loop {
+        open_editor(editor, file.path())?;
+        let content = fs::read_to_string(file.path())?;
+        match parse(&content) {
+            Ok(edited) if edited.is_empty() => bail!("Aborting, the edited file holds no commands"),
+            Ok(edited) => return Ok(edited),
+            Err(errors) => {
+                println!("[!] Warning: The edited commands are invalid, reopening the editor");
+                fs::write(file.path(), render(&strip_comments(&content), &errors))?;
+            }
+        }
+    }
+}
+
+fn open_editor(editor: &str, path: &Path) -> Result<()> {
+    // The shell splits editors with arguments, the path is passed as a positional parameter
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("sh")
+        .arg(path)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .with_context(|| format!("Failed to spawn editor '{editor}'"))?;
+    if !status.success() {
+        bail!(
+            "Editor '{editor}' exited with code {}, aborting",
+            status.code().unwrap_or(-1)
+        );
+    }
+    Ok(())
+}
+
+/// The file content shown in the editor, `errors` are listed above the commands
+fn render(body: &str, errors: &[String]) -> String {
+    let mut content = String::new();
+    for error in errors {
+        content.push_str(&format!("{ERROR_PREFIX}{error}\n"));
+    }
+    content.push_str(HEADER);
+    content.push_str(body.trim_end());
+    content.push('\n');
+    content
+}
+
+/// The content without the header and error banner, keeping the user's own lines
+fn strip_comments(content: &str) -> String {
+    let header: BTreeSet<&str> = HEADER.lines().collect();
+    content
+        .lines()
+        .filter(|line| !line.starts_with(ERROR_PREFIX) && !header.contains(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parses the edited commands, ignoring comments and blank lines
+///
+/// Every command needs `-i` and `-o` and a name distinct from the other instances.
+///
+/// # Errors
+/// * All problems found, one per line
+fn parse(content: &str) -> Result<Vec<String>, Vec<String>> {
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+    let mut names = BTreeSet::new();
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_no = line_no + 1;
+        let command = match InstanceCommand::parse_strict(line, commands.len()) {
+            Ok(command) => command,
+            Err(e) => {
+                errors.push(format!("line {line_no}: {e}"));
+                continue;
+            }
+        };
+        for flag in ["-i", "-o"] {
+            if !command
+                .flags
+                .iter()
+                .any(|(f, value)| f == flag && value.is_some())
+            {
+                errors.push(format!("line {line_no}: missing {flag} <dir>"));
+            }
+        }
+        if !names.insert(command.name.clone()) {
+            errors.push(format!(
+                "line {line_no}: instance name '{}' is used more than once",
+                command.name
+            ));
+        }
+        commands.push(line.to_string());
+    }
+    if errors.is_empty() {
+        Ok(commands)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "AFL_FINAL_SYNC=1 afl-fuzz -i in -o out -M m_t -- ./t @@";
+    const SECONDARY: &str = "afl-fuzz -i in -o out -S s1_t -- ./t @@";
+
+    #[test]
+    fn test_parse() {
+        let content = render(&[MAIN, SECONDARY].join("\n"), &[]);
+        assert_eq!(parse(&content).unwrap(), [MAIN, SECONDARY]);
+        assert!(parse(HEADER).unwrap().is_empty());
+
+        let errors = parse(&format!(
+            "{MAIN}\nafl-fuzz -o out -M m_t -- ./t\n\nafl-fuzz -i in -o 'out -S s2_t"
+        ))
+        .unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                "line 2: missing -i <dir>",
+                "line 2: instance name 'm_t' is used more than once",
+                "line 4: unterminated ' quote"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_banner_keeps_edits() {
+        let edited = format!("{MAIN}\n# strace for this one\nstrace -f {SECONDARY}");
+        let errors = vec!["line 5: missing -i <dir>".to_string()];
+        let content = render(&edited, &errors);
+        assert!(content.starts_with("# ERROR: line 5: missing -i <dir>\n# Edit"));
+        // A second round replaces the banner instead of stacking it
+        let again = render(&strip_comments(&content), &[]);
+        assert_eq!(again, render(&edited, &[]));
+    }
+
+    #[test]
+    fn test_edit_commands() {
+        let commands = vec![MAIN.to_string(), SECONDARY.to_string()];
+        let edited = edit_commands(&commands, "sed -i 's/^afl-fuzz/strace -f afl-fuzz/'").unwrap();
+        assert_eq!(edited, [MAIN.to_string(), format!("strace -f {SECONDARY}")]);
+
+        assert!(edit_commands(&commands, "sed -i '/afl-fuzz/d'").is_err());
+        assert!(edit_commands(&commands, "false").is_err());
+    }
+}
@@ -1,19 +1,74 @@
 use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use crate::afl::deps::check_harness_deps;
+use crate::afl::dictionary::check_dictionary;
 use crate::afl::env::AFLEnv;
-use crate::afl::harness::Harness;
+use crate::afl::flags::{self, Severity};
+use crate::afl::harness::{Harness, HarnessBinary};
+use crate::afl::map_size::{MapSize, MapSizeProbe};
 use crate::afl::mode::Mode;
-use crate::afl::strategies::{AFLStrategy, CmpcovConfig, CmplogConfig};
+use crate::afl::strategies::{
+    AFLStrategy, CmpcovConfig, CmplogConfig, CmplogMode, FlagBlacklist, InstanceFilter,
+};
+use crate::afl::target_env::{DEFAULT_ENV_INPUT_FILE, INPUT_PLACEHOLDER};
+use crate::afl::warnings::Warnings;
 use crate::afl::{base_cfg::Bcfg, cmd::AFLCmd};
 use crate::utils::seed::Xorshift64;
-use crate::utils::system::find_binary_in_path;
-use anyhow::{Context, Result};
+use crate::utils::system::{
+    binary_kind, find_binary_in_path, is_executable, network_fs_type, shell_quote, BinaryKind,
+};
+use anyhow::{bail, Context, Result};
 use rand::rngs::StdRng;
-use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
 
 const RUNNER_THRESH: u32 = 32;
+/// Largest `AFL_SYNC_TIME` AFL++ accepts, it keeps the sync time in milliseconds in a `u32`
+const MAX_SYNC_MINUTES: u64 = u32::MAX as u64 / 60_000;
+
+/// Timing and determinism of repeated generations, see `AFLCmdGenerator::bench`
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    /// Seed all runs were generated with
+    pub seed: u64,
+    /// Time every run took, in order
+    pub durations: Vec<Duration>,
+    /// Runs whose commands differ from the first run, along with the first differing instance
+    pub mismatches: Vec<(usize, usize)>,
+}
+
+impl BenchReport {
+    pub fn is_deterministic(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Summary of the timings and, if any, the runs that differ
+    pub fn render(&self) -> String {
+        let min = self.durations.iter().min().copied().unwrap_or_default();
+        let max = self.durations.iter().max().copied().unwrap_or_default();
+        let total: Duration = self.durations.iter().sum();
+        let mean = total / u32::try_from(self.durations.len().max(1)).unwrap_or(u32::MAX);
+        let mut lines = vec![
+            format!(
+                "[*] Generated the commands {} times with seed {}",
+                self.durations.len(),
+                self.seed
+            ),
+            format!("    time: min {min:.2?}, mean {mean:.2?}, max {max:.2?}, total {total:.2?}"),
+        ];
+        if self.is_deterministic() {
+            lines.push("[+] All runs produced identical commands".to_string());
+        } else {
+            lines.extend(self.mismatches.iter().map(|(run, instance)| {
+                format!("[!] Run {run} differs from run 0, starting at instance {instance}")
+            }));
+        }
+        lines.join("\n")
+    }
+}
 
 /// Generates AFL++ commands based on the provided configuration
 pub struct AFLCmdGenerator {
@@ -25,122 +80,483 @@ pub struct AFLCmdGenerator {
     pub runners: u32,
     /// The mode that determines the amount of parameters applied to the generated commands
     pub mode: Mode,
-    /// Seed for AFL++
+    /// Seed for the internal PRNG, a random one is generated and printed if unset
     pub seed: Option<u64>,
+    /// Relay the seed to AFL++ via `-s`
+    pub relay_seed: bool,
+    /// Fraction of instances that get `-s`, the others keep a random AFL++ seed
+    pub seed_ratio: Option<f64>,
+    /// Skip all randomized strategies so the commands only depend on the inputs
+    pub deterministic: bool,
+    /// Strategy flags that are never emitted
+    pub forbidden: FlagBlacklist,
+    /// Instances that receive strategy flags
+    pub instances: InstanceFilter,
+    /// CMPLOG level applied to all CMPLOG instances instead of the randomized distribution
+    pub cmplog_level: Option<CmplogMode>,
+    /// Reject unknown flags in the raw AFL++ flags instead of warning about them
+    pub strict_flags: bool,
+    /// Harness binaries whose shared library dependencies are not checked
+    pub skip_dep_check: Vec<HarnessBinary>,
+    /// Run the sanitizer binary on a secondary instead of the main instance
+    pub san_on_secondary: bool,
 }
 
 impl AFLCmdGenerator {
     /// Creates a new `AFLCmdGenerator` instance
     pub fn new(harness: Harness, runners: u32, meta: &Bcfg, mode: Mode, seed: Option<u64>) -> Self {
-        if runners > RUNNER_THRESH {
-            println!("[!] Warning: Performance degradation may occur with more than 32 runners. Observe campaign results carefully.");
-        }
-
         Self {
             harness,
             base_cfg: meta.clone(),
             runners,
             mode,
             seed,
+            relay_seed: false,
+            seed_ratio: None,
+            deterministic: false,
+            forbidden: FlagBlacklist::default(),
+            instances: InstanceFilter::default(),
+            cmplog_level: None,
+            strict_flags: false,
+            skip_dep_check: Vec::new(),
+            san_on_secondary: false,
         }
     }
 
-    /// Retrieves AFL++ environment variables
-    fn get_afl_env_vars() -> Vec<String> {
+    /// Relays the seed to AFL++ via `-s` as well
+    pub fn with_seed_relay(mut self, relay_seed: bool) -> Self {
+        self.relay_seed = relay_seed;
+        self
+    }
+
+    /// Only relays the seed to the first `ratio` of the instances, rounded up
+    ///
+    /// The remaining instances keep a random AFL++ seed, trading some reproducibility for
+    /// diversity between the instances
+    pub fn with_seed_ratio(mut self, ratio: Option<f64>) -> Self {
+        self.seed_ratio = ratio;
+        self
+    }
+
+    /// Runs the sanitizer binary on a plain secondary and keeps the target on the main instance
+    ///
+    /// A slow sanitizer build as `-M` delays queue culling and syncing for the whole campaign
+    pub fn with_san_on_secondary(mut self, san_on_secondary: bool) -> Self {
+        self.san_on_secondary = san_on_secondary;
+        self
+    }
+
+    /// Skips the shared library check of `skip`, e.g. for static or exotic builds
+    pub fn with_dep_check_skip(mut self, skip: Vec<HarnessBinary>) -> Self {
+        self.skip_dep_check = skip;
+        self
+    }
+
+    /// Returns the user provided seed or draws a fresh one from OS entropy
+    ///
+    /// A drawn seed is printed so that the generated commands can be reproduced after the fact
+    pub(crate) fn resolve_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| {
+            let seed = rand::rngs::OsRng.next_u64();
+            if !self.deterministic {
+                println!("[*] No seed provided, using {seed}. Pass '--seed {seed}' to reproduce the commands");
+            }
+            seed
+        })
+    }
+
+    /// Disables all randomized strategies and environment variables
+    ///
+    /// This is meant for reproducible, diffable command output and not for performance
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Never emits the strategy flags of `forbidden`
+    pub fn with_forbidden_flags(mut self, forbidden: FlagBlacklist) -> Self {
+        self.forbidden = forbidden;
+        self
+    }
+
+    /// Only places strategy flags on the instances of `instances`
+    pub fn with_instance_filter(mut self, instances: InstanceFilter) -> Self {
+        self.instances = instances;
+        self
+    }
+
+    /// Applies `level` to all CMPLOG instances for deterministic CMPLOG behavior
+    pub fn with_cmplog_level(mut self, level: Option<CmplogMode>) -> Self {
+        self.cmplog_level = level;
+        self
+    }
+
+    /// Rejects unknown flags in the raw AFL++ flags instead of passing them through
+    pub fn with_strict_flags(mut self, strict_flags: bool) -> Self {
+        self.strict_flags = strict_flags;
+        self
+    }
+
+    /// Retrieves the exported AFL++ environment variables that are inherited by the commands
+    pub fn get_afl_env_vars(warnings: &mut Warnings) -> Vec<String> {
         let gl_afl_env = std::env::vars()
             .filter(|(k, _)| k.starts_with("AFL_"))
             .map(|(k, v)| format!("{k}={v}"))
             .collect::<Vec<String>>();
         if !gl_afl_env.is_empty() {
-            println!("[!] Warning: Exported AFL++ environment variables found... Check generated commands!");
+            warnings
+                .warn("Exported AFL++ environment variables found... Check generated commands!");
         }
         gl_afl_env
     }
 
-    /// Generates AFL++ commands based on the configuration
+    /// Generates AFL++ commands based on the configuration and prints a summary of all warnings
     ///
     /// # Errors
     /// * If the set of intial commands cannot be constructed
     /// * If dictionary path cannot be resolved
     pub fn run(&self) -> Result<Vec<AFLCmd>> {
-        let seed = Xorshift64::new(self.seed.unwrap_or(0)).rand();
-        let mut rng = StdRng::seed_from_u64(seed);
-
-        let afl_envs = AFLEnv::new(
-            self.mode,
-            self.runners,
-            self.base_cfg.ramdisk.as_ref(),
-            &mut rng,
-        );
-
-        let mut cmds = self.create_initial_cmds(&afl_envs)?;
+        let mut warnings = Warnings::default();
+        let cmds = self.run_collecting(&mut warnings)?;
+        warnings.print();
+        Ok(cmds)
+    }
 
-        let afl_env_vars: Vec<String> = Self::get_afl_env_vars();
-        let is_using_custom_mutator = afl_env_vars
-            .iter()
-            .any(|e| e.starts_with("AFL_CUSTOM_MUTATOR_LIBRARY"));
+    /// Generates AFL++ commands and records warnings in `warnings` instead of printing them
+    ///
+    /// # Errors
+    /// * If the set of intial commands cannot be constructed
+    /// * If dictionary path cannot be resolved
+    pub fn run_collecting(&self, warnings: &mut Warnings) -> Result<Vec<AFLCmd>> {
+        self.generate(self.resolve_seed(), warnings)
+    }
 
-        let mut afl_strategy_builder = AFLStrategy::builder(self.mode);
+    /// Generates the commands `runs` times with the same seed, timing every generation and
+    /// comparing the commands and their environment to the first run
+    ///
+    /// Only the warnings of the first run are recorded in `warnings`.
+    ///
+    /// # Errors
+    /// * If a generation fails, see `run_collecting`
+    pub fn bench(&self, runs: usize, warnings: &mut Warnings) -> Result<BenchReport> {
+        let seed = self.resolve_seed();
+        let render = |cmds: &[AFLCmd]| -> Vec<String> {
+            cmds.iter()
+                .map(|cmd| format!("{cmd} {}", cmd.env_block()))
+                .collect()
+        };
+        let mut report = BenchReport {
+            seed,
+            durations: Vec::with_capacity(runs),
+            mismatches: Vec::new(),
+        };
+        let mut first = None;
+        for run in 0..runs.max(1) {
+            let mut scratch = Warnings::default();
+            let start = Instant::now();
+            let cmds = self.generate(
+                seed,
+                if run == 0 {
+                    &mut *warnings
+                } else {
+                    &mut scratch
+                },
+            )?;
+            report.durations.push(start.elapsed());
+
+            let cmds = render(&cmds);
+            let Some(first) = &first else {
+                first = Some(cmds);
+                continue;
+            };
+            if let Some(instance) =
+                (0..first.len().max(cmds.len())).find(|&idx| first.get(idx) != cmds.get(idx))
+            {
+                report.mismatches.push((run, instance));
+            }
+        }
+        Ok(report)
+    }
 
-        // Enable CMPLOG if requested
-        if let Some(ref cmplog_bin) = self.harness.cmplog_bin {
-            afl_strategy_builder.with_cmplog(CmplogConfig::new(cmplog_bin.clone()));
+    /// Generates AFL++ commands with the PRNG seeded by `seed`
+    fn generate(&self, seed: u64, warnings: &mut Warnings) -> Result<Vec<AFLCmd>> {
+        if self.runners > RUNNER_THRESH {
+            warnings.warn(format!("Performance degradation may occur with more than {RUNNER_THRESH} runners. Observe campaign results carefully."));
+        }
+        if let Some(fs_type) = network_fs_type(&self.base_cfg.output_dir) {
+            warnings.warn(format!(
+                "Output directory {} is on a network filesystem ({fs_type})! \
+                 AFL++ syncs its files constantly, which severely degrades performance. \
+                 Consider 'aflr run --local-work-dir <path>'.",
+                self.base_cfg.output_dir.display()
+            ));
         }
 
-        // Enable CMPCOV if requested
-        if let Some(ref cmpcov_bin) = self.harness.cmpcov_bin {
-            afl_strategy_builder.with_cmpcov(CmpcovConfig::new(cmpcov_bin.clone()));
+        for warning in check_harness_deps(&self.harness, &self.skip_dep_check) {
+            warnings.warn(warning);
         }
+        self.validate_raw_afl_flags(warnings)?;
+        self.validate_input_len()?;
+        let sync_minutes = self.sync_minutes()?;
+        self.base_cfg.target_env.check_target_args(
+            self.harness
+                .target_args
+                .iter()
+                .flat_map(|args| args.split_whitespace()),
+        )?;
+        if let Some(level) = self
+            .cmplog_level
+            .filter(|level| self.forbidden.is_forbidden(&level.to_string()))
+        {
+            bail!("The cmplog level '{level}' is a forbidden strategy flag");
+        }
+
+        let seed = Xorshift64::new(seed).rand();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let afl_envs = if self.deterministic {
+            AFLEnv::new_deterministic(self.runners, self.base_cfg.ramdisk.as_ref())
+        } else {
+            AFLEnv::new(
+                self.mode,
+                self.runners,
+                self.base_cfg.ramdisk.as_ref(),
+                &mut rng,
+            )
+        };
+
+        let mut cmds = self.create_initial_cmds(&afl_envs, warnings)?;
+
+        let afl_env_vars: Vec<String> = Self::get_afl_env_vars(warnings);
+        let is_using_custom_mutator = self.base_cfg.custom_mutator.is_some()
+            || afl_env_vars
+                .iter()
+                .any(|e| e.starts_with("AFL_CUSTOM_MUTATOR_LIBRARY"));
+
+        let cmpcov_idxs = if self.deterministic {
+            HashSet::new()
+        } else {
+            let mut afl_strategy_builder = AFLStrategy::builder(self.mode);
+
+            // Enable CMPLOG if requested
+            if let Some(ref cmplog_bin) = self.harness.cmplog_bin {
+                afl_strategy_builder.with_cmplog(
+                    CmplogConfig::new(cmplog_bin.clone()).with_level(self.cmplog_level),
+                );
+            } else if self.cmplog_level.is_some() {
+                warnings.warn("The cmplog level has no effect without a CMPLOG binary");
+            }
+
+            // Enable CMPCOV if requested
+            if let Some(ref cmpcov_bin) = self.harness.cmpcov_bin {
+                afl_strategy_builder.with_cmpcov(CmpcovConfig::new(cmpcov_bin.clone()));
+            }
 
-        // Properly initialize the set of cmds
-        let afl_strategy =
+            if let Some(exclusions) = self.forbidden.describe() {
+                println!("[*] Excluding forbidden strategy flags: {exclusions}");
+            }
+            afl_strategy_builder.with_forbidden_flags(self.forbidden.clone());
+            if let Some(instances) = self.instances.describe() {
+                println!("[*] Placing strategy flags only on {instances}");
+                if !(0..self.runners as usize).any(|idx| self.instances.allows(idx)) {
+                    warnings.warn(format!(
+                        "The instance filter matches none of the {} instances, no strategy flags are placed",
+                        self.runners
+                    ));
+                }
+            }
+            afl_strategy_builder.with_instance_filter(self.instances.clone());
+
+            // Properly initialize the set of cmds
             afl_strategy_builder
                 .build()
-                .apply(&mut cmds, &mut rng, is_using_custom_mutator);
+                .apply(&mut cmds, &mut rng, is_using_custom_mutator)
+                .get_cmpcov_indices()
+                .clone()
+        };
 
         // Apply -s
-        if self.seed.is_some() {
-            Self::apply_afl_seed(&mut cmds, seed);
+        if self.relay_seed {
+            Self::apply_afl_seed(&mut cmds, seed, self.seed_ratio);
+        } else if self.seed_ratio.is_some() {
+            warnings.warn("The seed ratio has no effect as the seed is not relayed to AFL++");
         }
 
         // Apply -i and -o
         self.apply_directory(&mut cmds);
         // Apply -x
-        self.apply_dictionary(&mut cmds)?;
-        // Apply sanitizer binary to first command if present
-        self.apply_sanitizer_or_target_binary(&mut cmds);
+        let dictionary_idxs = self.apply_dictionary(&mut cmds, &cmpcov_idxs, &mut rng, warnings)?;
+        // Apply sanitizer binary to the first command or a secondary if present
+        self.apply_sanitizer_or_target_binary(&mut cmds, &cmpcov_idxs, warnings);
 
         // Apply harness arguments
         self.apply_target_args(&mut cmds);
 
         // Apply -S/-M
         // NOTE: Needs to called last as it relies on cmpcov/cmplog being already set
-        self.apply_fuzzer_roles(&mut cmds, afl_strategy.get_cmpcov_indices(), self.mode);
+        self.apply_fuzzer_roles(&mut cmds, &cmpcov_idxs, self.mode);
+        Self::report_dictionary(&cmds, &dictionary_idxs);
+        self.apply_role_afl_flags(&mut cmds);
+        // Apply -I
+        self.apply_crash_hook(&mut cmds)?;
+        // Apply -g/-G
+        self.apply_input_len(&mut cmds);
+        // Apply AFL_SYNC_TIME, relies on the roles being assigned
+        Self::apply_sync_time(&mut cmds, sync_minutes);
+        // Apply -f, relies on the roles being assigned for `{instance_name}`
+        self.apply_fixed_input_file(&mut cmds)?;
+        // Apply AFL_TARGET_ENV, relies on the -f input file
+        self.apply_target_env(&mut cmds);
+        Self::check_shared_afl_seed(&cmds, warnings);
+
+        // Apply AFL_MAP_SIZE
+        self.apply_map_size(&mut cmds, warnings);
+        // Apply AFL_CUSTOM_MUTATOR_LIBRARY
+        self.apply_custom_mutator(&mut cmds, warnings)?;
+        // Apply AFL_LLVM_*
+        self.apply_llvm_env(&mut cmds);
 
         // Apply global environment variables that are not yet part of the commands
-        Self::apply_global_env_vars(&mut cmds, &afl_env_vars);
+        Self::apply_global_env_vars(&mut cmds, &afl_env_vars, warnings);
 
         Ok(cmds)
     }
 
     // Inherit global AFL++ environment variables that are not already set
-    fn apply_global_env_vars(cmds: &mut [AFLCmd], afl_env_vars: &[String]) {
-        for cmd in cmds {
-            let to_apply: Vec<_> = afl_env_vars
-                .iter()
-                .filter(|env| {
-                    let key = env.split('=').next().unwrap();
-                    !cmd.env.iter().any(|e| e.split('=').next().unwrap() == key)
-                })
-                .cloned()
-                .collect();
+    fn apply_global_env_vars(
+        cmds: &mut [AFLCmd],
+        afl_env_vars: &[String],
+        warnings: &mut Warnings,
+    ) {
+        for (idx, cmd) in cmds.iter_mut().enumerate() {
+            let mut to_apply = Vec::new();
+            for env in afl_env_vars {
+                let key = env.split('=').next().unwrap();
+                match cmd.env.iter().find(|e| e.split('=').next().unwrap() == key) {
+                    None => to_apply.push(env.clone()),
+                    Some(existing) if existing != env => {
+                        warnings.warn_instance(
+                            idx,
+                            format!("Exported {env} is overridden by the generated {key}"),
+                        );
+                    }
+                    Some(_) => {}
+                }
+            }
             cmd.with_env(to_apply, true);
         }
     }
 
+    /// Applies the configured `AFL_MAP_SIZE` to all AFL++ commands
+    ///
+    /// In `auto` mode the target is probed once with `afl-showmap`. If probing fails,
+    /// a warning is recorded and AFL++ falls back to its own defaults.
+    fn apply_map_size(&self, cmds: &mut [AFLCmd], warnings: &mut Warnings) {
+        let map_size = match self.base_cfg.map_size {
+            None => return,
+            Some(MapSize::Fixed(size)) => size,
+            Some(MapSize::Auto) => {
+                let Some(afl_binary) = cmds.first().map(|c| c.afl_binary.clone()) else {
+                    return;
+                };
+                let probe = MapSizeProbe::new(&afl_binary)
+                    .with_afl_flags(self.base_cfg.raw_afl_flags.as_ref());
+                match probe.run(
+                    &self.harness.target_bin,
+                    self.harness.target_args.as_deref(),
+                    &self.base_cfg.input_dir,
+                ) {
+                    Ok(size) => {
+                        println!("[+] Detected map size: {size}");
+                        size
+                    }
+                    Err(e) => {
+                        warnings.warn(format!(
+                            "Failed to detect map size, using AFL++ defaults: {e}"
+                        ));
+                        return;
+                    }
+                }
+            }
+        };
+
+        for cmd in cmds {
+            cmd.with_env(vec![format!("AFL_MAP_SIZE={map_size}")], false);
+        }
+    }
+
+    /// Checks the raw AFL++ flags against the known `afl-fuzz` flags and the ones set by aflr
+    ///
+    /// # Errors
+    /// * If a flag misses its value, a value has no flag or a flag conflicts with a generated one
+    /// * If a flag is unknown and `strict_flags` is set
+    fn validate_raw_afl_flags(&self, warnings: &mut Warnings) -> Result<()> {
+        let mut managed = vec!["-i", "-o", "-M", "-S"];
+        for (flag, is_set) in [
+            ("-x", self.base_cfg.dictionary.is_some()),
+            ("-s", self.relay_seed),
+            ("-c", self.harness.cmplog_bin.is_some()),
+            ("-I", self.base_cfg.crash_hook.is_some()),
+            ("-g", self.base_cfg.min_len.is_some()),
+            ("-G", self.base_cfg.max_len.is_some()),
+            (
+                "-f",
+                self.base_cfg.fixed_input_file.is_some() || self.base_cfg.target_env.takes_input(),
+            ),
+        ] {
+            if is_set {
+                managed.push(flag);
+            }
+        }
+
+        // The role specific flags may add dictionaries on top of the generated one
+        let role_managed: Vec<&str> = managed.iter().copied().filter(|f| *f != "-x").collect();
+        let mut errors = Vec::new();
+        for (key, raw, managed) in [
+            ("afl_flags", &self.base_cfg.raw_afl_flags, &managed),
+            (
+                "afl_flags_master",
+                &self.base_cfg.raw_afl_flags_master,
+                &role_managed,
+            ),
+            (
+                "afl_flags_secondary",
+                &self.base_cfg.raw_afl_flags_secondary,
+                &role_managed,
+            ),
+        ] {
+            let Some(raw) = raw else {
+                continue;
+            };
+            for issue in flags::validate(key, raw, managed, self.strict_flags) {
+                match issue.severity {
+                    Severity::Warning => warnings.warn(issue.to_string()),
+                    Severity::Error => errors.push(issue.to_string()),
+                }
+            }
+        }
+        if !errors.is_empty() {
+            bail!("Invalid raw AFL++ flags:\n{}", errors.join("\n"));
+        }
+        Ok(())
+    }
+
     /// Creates initial AFL++ commands
-    fn create_initial_cmds(&self, afl_envs: &[AFLEnv]) -> Result<Vec<AFLCmd>> {
+    fn create_initial_cmds(
+        &self,
+        afl_envs: &[AFLEnv],
+        warnings: &mut Warnings,
+    ) -> Result<Vec<AFLCmd>> {
         let afl_binary = find_binary_in_path(self.base_cfg.afl_binary.clone())?;
+        // CMPLOG and QEMU mode locate their helpers relative to the real binary
+        if let Ok((resolved, BinaryKind::Script { interpreter })) = binary_kind(&afl_binary) {
+            warnings.warn(format!(
+                "{} resolves to {}, a script run by '{interpreter}' rather than the afl-fuzz binary. \
+                 Some AFL++ features assume the real binary, point --afl-binary or AFL_PATH to it if instances fail to start.",
+                afl_binary.display(),
+                resolved.display()
+            ));
+        }
         let target_binary = &self.harness.target_bin;
         Ok(afl_envs
             .iter()
@@ -208,27 +624,317 @@ impl AFLCmdGenerator {
         }
     }
 
-    /// Applies dictionary to AFL++ commands
-    fn apply_dictionary(&self, cmds: &mut [AFLCmd]) -> Result<()> {
-        if let Some(dict) = &self.base_cfg.dictionary {
-            let dict_path = fs::canonicalize(dict).context("Failed to resolve dictionary path")?;
-            for cmd in cmds {
-                cmd.add_flag(format!("-x {}", dict_path.display()));
+    /// Appends the role specific raw AFL++ flags, relies on the roles being assigned
+    fn apply_role_afl_flags(&self, cmds: &mut [AFLCmd]) {
+        for cmd in cmds {
+            let is_master = cmd.misc_afl_flags.iter().any(|f| f.starts_with("-M "));
+            let flags = if is_master {
+                &self.base_cfg.raw_afl_flags_master
+            } else {
+                &self.base_cfg.raw_afl_flags_secondary
+            };
+            if let Some(flags) = flags {
+                flags
+                    .split_whitespace()
+                    .for_each(|flag| cmd.add_flag(flag.to_string()));
             }
         }
+    }
+
+    /// Checks that the configured test case length limits leave room for test cases
+    ///
+    /// # Errors
+    /// * If the maximum length is zero or below the minimum length
+    fn validate_input_len(&self) -> Result<()> {
+        match (self.base_cfg.min_len, self.base_cfg.max_len) {
+            (_, Some(0)) => bail!("The maximum test case length must be at least 1 byte"),
+            (Some(min), Some(max)) if min > max => bail!(
+                "The minimum test case length ({min}) exceeds the maximum test case length ({max})"
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// The sync time in minutes as `AFL_SYNC_TIME` expects it
+    ///
+    /// # Errors
+    /// * If the sync time is not a whole number of minutes or exceeds what AFL++ accepts
+    fn sync_minutes(&self) -> Result<Option<u64>> {
+        let Some(sync_time) = self.base_cfg.sync_time else {
+            return Ok(None);
+        };
+        let secs = sync_time.as_duration().as_secs();
+        if secs % 60 != 0 || sync_time.as_duration().subsec_nanos() != 0 {
+            bail!("The sync time ({sync_time}) must be a whole number of minutes");
+        }
+        let minutes = secs / 60;
+        if !(1..=MAX_SYNC_MINUTES).contains(&minutes) {
+            bail!("The sync time ({sync_time}) must be between 1m and {MAX_SYNC_MINUTES}m");
+        }
+        Ok(Some(minutes))
+    }
+
+    /// Sets `AFL_SYNC_TIME` on the secondary instances, the main instance keeps syncing at the
+    /// AFL++ default so new finds still spread through the campaign
+    fn apply_sync_time(cmds: &mut [AFLCmd], minutes: Option<u64>) {
+        let Some(minutes) = minutes else {
+            return;
+        };
+        for cmd in cmds
+            .iter_mut()
+            .filter(|cmd| cmd.misc_afl_flags.iter().any(|f| f.starts_with("-S ")))
+        {
+            cmd.with_env(vec![format!("AFL_SYNC_TIME={minutes}")], false);
+        }
+    }
+
+    /// Limits the length of the test cases of all instances via `-g` and `-G`
+    fn apply_input_len(&self, cmds: &mut [AFLCmd]) {
+        for cmd in cmds {
+            if let Some(min) = self.base_cfg.min_len {
+                cmd.add_flag(format!("-g {min}"));
+            }
+            if let Some(max) = self.base_cfg.max_len {
+                cmd.add_flag(format!("-G {max}"));
+            }
+        }
+    }
+
+    /// Attaches the crash hook to the main instance or all instances
+    ///
+    /// `afl-fuzz` hands the `-I` argument to a shell again, hence it is quoted twice.
+    ///
+    /// # Errors
+    /// * If the hook does not exist or is not executable
+    fn apply_crash_hook(&self, cmds: &mut [AFLCmd]) -> Result<()> {
+        let Some(hook) = &self.base_cfg.crash_hook else {
+            return Ok(());
+        };
+        let hook = fs::canonicalize(hook)
+            .with_context(|| format!("Failed to resolve crash hook {}", hook.display()))?;
+        if !is_executable(&hook) {
+            bail!("Crash hook {} is not an executable file", hook.display());
+        }
+        let flag = format!("-I {}", shell_quote(&shell_quote(&hook.to_string_lossy())));
+        let hooked = if self.base_cfg.crash_hook_all {
+            cmds.len()
+        } else {
+            1
+        };
+        for cmd in cmds.iter_mut().take(hooked) {
+            cmd.add_flag(flag.clone());
+        }
         Ok(())
     }
 
-    /// Applies sanitizer or target binary to AFL++ commands
-    fn apply_sanitizer_or_target_binary(&self, cmds: &mut [AFLCmd]) {
-        if let Some(cmd) = cmds.first_mut() {
-            let binary = self
-                .harness
-                .sanitizer_bin
-                .as_ref()
-                .unwrap_or(&self.harness.target_bin);
-            cmd.target_binary.clone_from(binary);
+    /// Loads the configured custom mutator libraries on the first `custom_mutator_ratio` of the
+    /// instances, rounded up
+    ///
+    /// # Errors
+    /// * If a library does not exist
+    fn apply_custom_mutator(&self, cmds: &mut [AFLCmd], warnings: &mut Warnings) -> Result<()> {
+        let Some(libraries) = &self.base_cfg.custom_mutator else {
+            if self.base_cfg.custom_mutator_ratio.is_some() {
+                warnings.warn("The custom mutator ratio has no effect without a custom mutator");
+            }
+            return Ok(());
+        };
+        let libraries = libraries
+            .split(';')
+            .filter(|lib| !lib.trim().is_empty())
+            .map(|lib| {
+                let path = fs::canonicalize(lib.trim())
+                    .with_context(|| format!("Failed to resolve custom mutator {lib}"))?;
+                if !path.is_file() {
+                    bail!("Custom mutator {} is not a file", path.display());
+                }
+                Ok(path.to_string_lossy().into_owned())
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join(";");
+        let env = format!("AFL_CUSTOM_MUTATOR_LIBRARY={}", shell_quote(&libraries));
+        let loading = self
+            .base_cfg
+            .custom_mutator_ratio
+            .map_or(cmds.len(), |ratio| instances_for_ratio(cmds.len(), ratio));
+        for cmd in cmds.iter_mut().take(loading) {
+            cmd.with_env(vec![env.clone()], false);
+        }
+        Ok(())
+    }
+
+    /// Exports the configured `AFL_LLVM_*` variables to all instances
+    fn apply_llvm_env(&self, cmds: &mut [AFLCmd]) {
+        if self.base_cfg.llvm_env.is_empty() {
+            return;
+        }
+        let assignments = self.base_cfg.llvm_env.assignments();
+        for cmd in cmds {
+            cmd.with_env(assignments.clone(), false);
+        }
+    }
+
+    /// Gives every instance its own `-f` input file rendered from the configured template
+    ///
+    /// Relative templates are placed inside the `RAMDisk` if there is one and inside the output
+    /// directory otherwise. `@@` in the target arguments is replaced with the file as well.
+    /// Harnesses reading the test case path from the environment always get an input file,
+    /// `DEFAULT_ENV_INPUT_FILE` if no template is configured.
+    ///
+    /// # Errors
+    /// * If two instances would share the same input file
+    fn apply_fixed_input_file(&self, cmds: &mut [AFLCmd]) -> Result<()> {
+        let Some(template) = self.base_cfg.fixed_input_file.as_deref().or_else(|| {
+            self.base_cfg
+                .target_env
+                .takes_input()
+                .then_some(DEFAULT_ENV_INPUT_FILE)
+        }) else {
+            return Ok(());
+        };
+        let base_dir = self
+            .base_cfg
+            .ramdisk
+            .as_deref()
+            .map_or(self.base_cfg.output_dir.as_path(), Path::new);
+        let mut seen = HashSet::new();
+        for (idx, cmd) in cmds.iter_mut().enumerate() {
+            let rendered =
+                render_fixed_input_file(template, idx, cmd.instance_name().unwrap_or_default());
+            let path = base_dir.join(rendered).display().to_string();
+            if !seen.insert(path.clone()) {
+                bail!(
+                    "Fixed input file '{template}' resolves to {path} for several instances, \
+                     add one of {} to tell them apart",
+                    FIXED_INPUT_PLACEHOLDERS.join(", ")
+                );
+            }
+            if let Some(args) = &cmd.target_args {
+                let args = args.replace("@@", &path);
+                cmd.with_target_args(Some(args));
+            }
+            cmd.add_flag(format!("-f {path}"));
+        }
+        Ok(())
+    }
+
+    /// Hands the configured target environment to the harness via `AFL_TARGET_ENV`
+    ///
+    /// `@@` in the values is replaced with the `-f` input file of the instance.
+    fn apply_target_env(&self, cmds: &mut [AFLCmd]) {
+        if self.base_cfg.target_env.is_empty() {
+            return;
+        }
+        for cmd in cmds {
+            let input = cmd
+                .misc_afl_flags
+                .iter()
+                .find_map(|flag| flag.strip_prefix("-f "))
+                .unwrap_or(INPUT_PLACEHOLDER)
+                .to_string();
+            cmd.with_env(vec![self.base_cfg.target_env.afl_target_env(&input)], false);
+        }
+    }
+
+    /// Applies dictionary to AFL++ commands and returns the indices of the instances that got it
+    ///
+    /// With a `dictionary_ratio` only the main instance and a random subset of the others get
+    /// it, optionally sparing the CMPCOV instances. Lines that `afl-fuzz` would reject are only
+    /// recorded as warnings so that newer dictionary syntax keeps working.
+    fn apply_dictionary<R: Rng>(
+        &self,
+        cmds: &mut [AFLCmd],
+        cmpcov_idxs: &HashSet<usize>,
+        rng: &mut R,
+        warnings: &mut Warnings,
+    ) -> Result<Vec<usize>> {
+        let Some(dict) = &self.base_cfg.dictionary else {
+            if self.base_cfg.dictionary_ratio.is_some() {
+                warnings.warn("The dictionary ratio has no effect without a dictionary");
+            }
+            return Ok(Vec::new());
+        };
+        let dict_path = fs::canonicalize(dict).context("Failed to resolve dictionary path")?;
+        for warning in check_dictionary(&dict_path) {
+            warnings.warn(warning);
         }
+
+        let skip_cmpcov = self.base_cfg.dictionary_skip_cmpcov;
+        let candidates: Vec<usize> = (1..cmds.len())
+            .filter(|idx| !(skip_cmpcov && cmpcov_idxs.contains(idx)))
+            .collect();
+        let mut idxs = match self.base_cfg.dictionary_ratio {
+            // Drawing from the PRNG only with a ratio keeps the commands of existing seeds intact
+            Some(ratio) if ratio < 1.0 => {
+                let others = instances_for_ratio(cmds.len(), ratio).saturating_sub(1);
+                candidates
+                    .choose_multiple(rng, others)
+                    .copied()
+                    .collect::<Vec<_>>()
+            }
+            _ => candidates,
+        };
+        if !cmds.is_empty() {
+            idxs.push(0);
+        }
+        idxs.sort_unstable();
+
+        for &idx in &idxs {
+            cmds[idx].add_flag(format!("-x {}", dict_path.display()));
+        }
+        Ok(idxs)
+    }
+
+    /// Names the instances that got the dictionary if not all of them did
+    fn report_dictionary(cmds: &[AFLCmd], dictionary_idxs: &[usize]) {
+        if dictionary_idxs.is_empty() || dictionary_idxs.len() == cmds.len() {
+            return;
+        }
+        let names: Vec<&str> = dictionary_idxs
+            .iter()
+            .filter_map(|&idx| cmds[idx].instance_name())
+            .collect();
+        println!(
+            "[*] Dictionary passed to {} of {} instances: {}",
+            dictionary_idxs.len(),
+            cmds.len(),
+            names.join(", ")
+        );
+    }
+
+    /// Applies sanitizer or target binary to AFL++ commands
+    ///
+    /// The sanitizer binary replaces the target of the first command, or with `san_on_secondary`
+    /// the one of the first secondary that runs neither CMPLOG nor CMPCOV.
+    fn apply_sanitizer_or_target_binary(
+        &self,
+        cmds: &mut [AFLCmd],
+        cmpcov_idxs: &HashSet<usize>,
+        warnings: &mut Warnings,
+    ) {
+        let Some(first) = cmds.first_mut() else {
+            return;
+        };
+        first.target_binary.clone_from(&self.harness.target_bin);
+        let Some(sanitizer) = &self.harness.sanitizer_bin else {
+            return;
+        };
+
+        let idx = if self.san_on_secondary {
+            let secondary = (1..cmds.len()).find(|idx| {
+                !cmpcov_idxs.contains(idx)
+                    && !cmds[*idx].misc_afl_flags.iter().any(|f| f.contains("-c"))
+            });
+            if secondary.is_none() {
+                warnings.warn(
+                    "No secondary without CMPLOG or CMPCOV is left for the sanitizer binary, it runs on the main instance",
+                );
+            }
+            secondary.unwrap_or(0)
+        } else {
+            0
+        };
+        cmds[idx].target_binary.clone_from(sanitizer);
     }
 
     /// Applies target arguments to AFL++ commands
@@ -240,16 +946,61 @@ impl AFLCmdGenerator {
         }
     }
 
-    fn apply_afl_seed(cmds: &mut [AFLCmd], seed: u64) {
-        for cmd in cmds {
-            cmd.add_flag(format!("-s {seed}"));
+    /// Relays a distinct seed derived from `seed` and the instance index to the instances
+    ///
+    /// Identical seeds would make the instances explore identically
+    fn apply_afl_seed(cmds: &mut [AFLCmd], seed: u64, ratio: Option<f64>) {
+        let seeded = ratio.map_or(cmds.len(), |ratio| instances_for_ratio(cmds.len(), ratio));
+        for (idx, cmd) in cmds.iter_mut().take(seeded).enumerate() {
+            cmd.add_flag(format!("-s {}", seed.wrapping_add(idx as u64)));
+        }
+    }
+
+    /// Warns if all instances end up with the same `-s`, e.g. through raw AFL++ flags
+    fn check_shared_afl_seed(cmds: &[AFLCmd], warnings: &mut Warnings) {
+        let afl_seed = |cmd: &AFLCmd| {
+            let flags = cmd.misc_afl_flags.join(" ");
+            let mut words = flags.split_whitespace();
+            words.position(|w| w == "-s")?;
+            words.next().map(ToString::to_string)
+        };
+        let mut seeds = cmds.iter().map(afl_seed);
+        if let (true, Some(Some(first))) = (cmds.len() > 1, seeds.next()) {
+            if seeds.all(|s| s.as_ref() == Some(&first)) {
+                warnings.warn(format!(
+                    "All instances use the AFL++ seed '-s {first}' and will explore identically. \
+                     Use --use-seed-afl to derive a distinct seed per instance instead"
+                ));
+            }
         }
     }
 }
 
+/// Placeholders of the `fixed_input_file` template
+pub const FIXED_INPUT_PLACEHOLDERS: &[&str] = &["{instance_index}", "{instance_name}"];
+
+/// Renders the `fixed_input_file` template for the instance at `idx` named `name`
+fn render_fixed_input_file(template: &str, idx: usize, name: &str) -> String {
+    template
+        .replace("{instance_index}", &idx.to_string())
+        .replace("{instance_name}", name)
+}
+
+/// Amount of instances a per-instance option like `-s` is applied to for the given ratio, rounded up
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn instances_for_ratio(instances: usize, ratio: f64) -> usize {
+    ((instances as f64 * ratio.clamp(0.0, 1.0)).ceil() as usize).min(instances)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::afl::base_cfg::RawAflFlags;
+    use crate::afl::target_env::TargetEnv;
     use tempfile::TempDir;
 
     fn create_test_harness() -> Harness {
@@ -274,7 +1025,11 @@ mod tests {
         fs::create_dir(&input_dir).unwrap();
         fs::create_dir(&output_dir).unwrap();
 
-        let afl_base = Bcfg::new(input_dir.clone(), output_dir.clone());
+        // Keeps the generator independent of the afl-fuzz installed on the machine
+        let afl_fuzz = temp_dir.path().join("afl-fuzz");
+        fs::write(&afl_fuzz, b"\x7fELF").unwrap();
+        let afl_base = Bcfg::new(input_dir.clone(), output_dir.clone())
+            .with_afl_binary(Some(afl_fuzz.to_string_lossy().into_owned()));
 
         let generator = AFLCmdGenerator::new(
             create_test_harness(),
@@ -296,6 +1051,28 @@ mod tests {
         assert!(generator.base_cfg.ramdisk.is_none());
     }
 
+    #[test]
+    fn test_bench() {
+        let (_temp, generator) = setup_test_generator();
+        let mut warnings = Warnings::default();
+        let report = generator.bench(3, &mut warnings).unwrap();
+        assert_eq!(report.seed, 42);
+        assert_eq!(report.durations.len(), 3);
+        assert!(report.is_deterministic());
+        assert!(report
+            .render()
+            .contains("All runs produced identical commands"));
+
+        let report = BenchReport {
+            seed: 1,
+            durations: vec![Duration::from_millis(2); 2],
+            mismatches: vec![(1, 3)],
+        };
+        assert!(report
+            .render()
+            .contains("Run 1 differs from run 0, starting at instance 3"));
+    }
+
     #[test]
     fn test_generator_with_too_many_runners() {
         let harness = create_test_harness();
@@ -328,18 +1105,316 @@ mod tests {
 
         assert!(generator.base_cfg.dictionary.is_some());
 
-        let cmds = generator.run().unwrap();
+        // The malformed dictionary is only warned about
+        let mut warnings = Warnings::default();
+        let cmds = generator.run_collecting(&mut warnings).unwrap();
         assert!(cmds.iter().all(|cmd| cmd.to_string().contains("-x")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.message.contains("dict.txt line 1")));
+    }
+
+    #[test]
+    fn test_dictionary_ratio() {
+        let temp_dir = TempDir::new().unwrap();
+        let dict_path = temp_dir.path().join("dict.txt");
+        fs::write(&dict_path, "kw=\"fuzz\"\n").unwrap();
+        let mut harness = create_test_harness();
+        harness.cmpcov_bin = Some(PathBuf::from("/bin/cmpcov-binary"));
+
+        let generate = |ratio: Option<f64>, skip_cmpcov: bool| {
+            let afl_base = create_afl_base_cfg()
+                .with_dictionary(Some(dict_path.clone()))
+                .with_dictionary_ratio(ratio, skip_cmpcov)
+                .with_raw_afl_flags(&RawAflFlags {
+                    master: Some("-x /extra.dict".to_string()),
+                    ..RawAflFlags::default()
+                });
+            AFLCmdGenerator::new(harness.clone(), 8, &afl_base, Mode::MultipleCores, Some(42))
+                .run_collecting(&mut Warnings::default())
+                .unwrap()
+        };
+        let with_dictionary = |cmds: &[AFLCmd]| {
+            cmds.iter()
+                .filter(|cmd| cmd.misc_afl_flags.iter().any(|f| f.contains("dict.txt")))
+                .filter_map(|cmd| cmd.instance_name().map(String::from))
+                .collect::<Vec<_>>()
+        };
+
+        let names = |names: &[&str]| names.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let all = generate(None, false);
+        assert_eq!(with_dictionary(&all).len(), 8);
+        assert_eq!(
+            with_dictionary(&generate(Some(0.5), false)),
+            names(&[
+                "m_test-target",
+                "s0_test-target",
+                "s1_test-target",
+                "s4_test-target"
+            ])
+        );
+        assert_eq!(
+            with_dictionary(&generate(Some(0.25), false)),
+            names(&["m_test-target", "s1_test-target"])
+        );
+        // s3 and s5 run the CMPCOV binary
+        assert_eq!(
+            with_dictionary(&generate(Some(0.5), true)),
+            names(&[
+                "m_test-target",
+                "s0_test-target",
+                "s2_test-target",
+                "s4_test-target"
+            ])
+        );
+        assert!(with_dictionary(&generate(Some(1.0), true))
+            .iter()
+            .all(|name| !name.contains("cmpcov")));
+        // The main instance gets the extra dictionary of afl_flags_master on top
+        assert!(all[0]
+            .to_string()
+            .contains("dict.txt -M m_test-target -x /extra.dict"));
+    }
+
+    #[test]
+    fn test_afl_fuzz_wrapper_warning() {
+        let (temp, generator) = setup_test_generator();
+        let mut warnings = Warnings::default();
+        generator
+            .create_initial_cmds(&[AFLEnv::default()], &mut warnings)
+            .unwrap();
+        assert!(warnings.is_empty());
+
+        fs::write(
+            temp.path().join("afl-fuzz"),
+            "#!/bin/bash\nexec /opt/AFLplusplus/afl-fuzz \"$@\"\n",
+        )
+        .unwrap();
+        generator
+            .create_initial_cmds(&[AFLEnv::default()], &mut warnings)
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
     }
 
     #[test]
     fn test_generator_with_raw_flags() {
         let (_temp, generator) = setup_test_generator();
 
-        let result = generator.create_initial_cmds(&[AFLEnv::default()]);
+        let result = generator.create_initial_cmds(&[AFLEnv::default()], &mut Warnings::default());
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_sync_time() {
+        let (_temp, mut generator) = setup_test_generator();
+        generator.runners = 3;
+        generator.base_cfg = generator
+            .base_cfg
+            .clone()
+            .with_sync_time(Some("1h30m".parse().unwrap()));
+        let cmds = generator.run().unwrap();
+        assert!(!cmds[0].env.iter().any(|e| e.starts_with("AFL_SYNC_TIME")));
+        for cmd in &cmds[1..] {
+            assert!(cmd.env.contains(&"AFL_SYNC_TIME=90".to_string()), "{cmd}");
+        }
+
+        for invalid in ["90s", "0", "100000m"] {
+            generator.base_cfg = generator
+                .base_cfg
+                .clone()
+                .with_sync_time(Some(invalid.parse().unwrap()));
+            let err = generator.run().unwrap_err().to_string();
+            assert!(err.contains("The sync time"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_input_len() {
+        let (_temp, mut generator) = setup_test_generator();
+        generator.runners = 3;
+        generator.base_cfg = generator
+            .base_cfg
+            .clone()
+            .with_input_len(Some(4), Some(1024));
+        for cmd in generator.run().unwrap() {
+            assert!(cmd.misc_afl_flags.contains(&"-g 4".to_string()), "{cmd}");
+            assert!(cmd.misc_afl_flags.contains(&"-G 1024".to_string()), "{cmd}");
+        }
+
+        generator.base_cfg.raw_afl_flags = Some("-G 512".to_string());
+        let err = generator.run().unwrap_err().to_string();
+        assert!(err.contains("-G"), "{err}");
+
+        generator.base_cfg.raw_afl_flags = None;
+        generator.base_cfg = generator
+            .base_cfg
+            .clone()
+            .with_input_len(Some(64), Some(16));
+        let err = generator.run().unwrap_err().to_string();
+        assert!(err.contains("minimum test case length (64)"), "{err}");
+        generator.base_cfg = generator.base_cfg.clone().with_input_len(None, Some(0));
+        assert!(generator.run().is_err());
+
+        // Equal limits pin the length
+        generator.base_cfg = generator.base_cfg.clone().with_input_len(Some(8), Some(8));
+        assert!(generator.run().is_ok());
+    }
+
+    #[test]
+    fn test_crash_hook() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (temp, mut generator) = setup_test_generator();
+        generator.runners = 3;
+        let hook = temp.path().join("my hooks/notify.sh");
+        fs::create_dir(hook.parent().unwrap()).unwrap();
+        fs::write(&hook, "#!/bin/sh\n").unwrap();
+
+        generator.base_cfg = generator
+            .base_cfg
+            .clone()
+            .with_crash_hook(Some(hook.clone()), false);
+        let err = generator.run().unwrap_err().to_string();
+        assert!(err.contains("not an executable"), "{err}");
+
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        let hook = fs::canonicalize(&hook).unwrap();
+        let cmds = generator.run().unwrap();
+        // Quoted once for the session shell and once for the shell afl-fuzz spawns
+        let expected = format!(r"-I ''\''{}'\'''", hook.display());
+        assert!(cmds[0].misc_afl_flags.contains(&expected), "{:?}", cmds[0]);
+        assert!(cmds[0].misc_afl_flags.iter().any(|f| f.starts_with("-M ")));
+        assert!(cmds[1..].iter().all(|cmd| !cmd.to_string().contains("-I ")));
+
+        generator.base_cfg.crash_hook_all = true;
+        let cmds = generator.run().unwrap();
+        assert!(cmds
+            .iter()
+            .all(|cmd| cmd.misc_afl_flags.contains(&expected)));
+
+        generator.base_cfg.crash_hook = Some(temp.path().join("missing.sh"));
+        assert!(generator.run().is_err());
+    }
+
+    #[test]
+    fn test_custom_mutator() {
+        let (temp, mut generator) = setup_test_generator();
+        generator.runners = 16;
+        let lib = temp.path().join("my mutator.so");
+        generator.base_cfg = generator
+            .base_cfg
+            .clone()
+            .with_custom_mutator(Some(lib.display().to_string()), Some(0.25));
+        let err = generator.run().unwrap_err().to_string();
+        assert!(err.contains("Failed to resolve custom mutator"), "{err}");
+
+        fs::write(&lib, "").unwrap();
+        let lib = fs::canonicalize(&lib).unwrap();
+        let cmds = generator.run().unwrap();
+        let env = format!("AFL_CUSTOM_MUTATOR_LIBRARY='{}'", lib.display());
+        assert!(
+            cmds[..4].iter().all(|cmd| cmd.env.contains(&env)),
+            "{cmds:?}"
+        );
+        assert!(cmds[4..]
+            .iter()
+            .all(|cmd| !cmd.to_string().contains("AFL_CUSTOM_MUTATOR_LIBRARY")));
+        // MOpt is not combined with custom mutators
+        assert!(cmds.iter().all(|cmd| !cmd.to_string().contains("-L 0")));
+    }
+
+    #[test]
+    fn test_llvm_env() {
+        let (_temp, mut generator) = setup_test_generator();
+        generator.base_cfg.llvm_env = toml::from_str::<crate::cli::AflArgs>(
+            "[llvm_env]\nAFL_LLVM_LAF_ALL = true\nAFL_LLVM_INSTRUMENT = \"PCGUARD\"\n",
+        )
+        .unwrap()
+        .llvm_env
+        .unwrap();
+        for cmd in generator.run().unwrap() {
+            assert!(cmd.env.contains(&"AFL_LLVM_INSTRUMENT=PCGUARD".to_string()));
+            assert!(cmd.env.contains(&"AFL_LLVM_LAF_ALL=1".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_fixed_input_file() {
+        let (temp, mut generator) = setup_test_generator();
+        generator.runners = 3;
+        generator.harness.target_args = Some("--in @@ -v".to_string());
+        generator.base_cfg.fixed_input_file = Some("/tmp/aflr_in_{instance_index}.bin".into());
+        let cmds = generator.run().unwrap();
+        for (idx, cmd) in cmds.iter().enumerate() {
+            let path = format!("/tmp/aflr_in_{idx}.bin");
+            assert!(cmd.misc_afl_flags.contains(&format!("-f {path}")), "{cmd}");
+            assert_eq!(
+                cmd.target_args.as_deref(),
+                Some(format!("--in {path} -v").as_str())
+            );
+        }
+
+        generator.base_cfg.fixed_input_file = Some("{instance_name}.cur".into());
+        let cmds = generator.run().unwrap();
+        let expected = temp.path().join("output/m_test-target.cur");
+        assert!(cmds[0]
+            .misc_afl_flags
+            .contains(&format!("-f {}", expected.display())));
+
+        generator.base_cfg.ramdisk = Some("/mnt/ramdisk".into());
+        let cmds = generator.run().unwrap();
+        assert!(cmds[0]
+            .misc_afl_flags
+            .contains(&"-f /mnt/ramdisk/m_test-target.cur".to_string()));
+
+        generator.base_cfg.fixed_input_file = Some("/tmp/aflr_in.bin".into());
+        let err = generator.run().unwrap_err().to_string();
+        assert!(err.contains("for several instances"), "{err}");
+    }
+
+    #[test]
+    fn test_env_driven_harness() {
+        let (temp, mut generator) = setup_test_generator();
+        generator.runners = 2;
+        generator.base_cfg.target_env =
+            TargetEnv::from_vars(&["INPUT_FILE=@@".into(), "LOG_LEVEL=0".into()]).unwrap();
+        let cmds = generator.run().unwrap();
+        for cmd in &cmds {
+            let name = cmd.instance_name().unwrap();
+            let path = temp.path().join(format!("output/{name}.cur"));
+            assert!(
+                cmd.misc_afl_flags
+                    .contains(&format!("-f {}", path.display())),
+                "{cmd}"
+            );
+            assert!(cmd.env.contains(&format!(
+                "AFL_TARGET_ENV='INPUT_FILE={} LOG_LEVEL=0'",
+                path.display()
+            )));
+            assert_eq!(cmd.target_args, None);
+        }
+
+        // The template of the fixed input file is used if there is one
+        generator.base_cfg.fixed_input_file = Some("/tmp/aflr_in_{instance_index}.bin".into());
+        let cmds = generator.run().unwrap();
+        assert!(cmds[1]
+            .env
+            .contains(&"AFL_TARGET_ENV='INPUT_FILE=/tmp/aflr_in_1.bin LOG_LEVEL=0'".to_string()));
+
+        generator.harness.target_args = Some("--in @@".to_string());
+        let err = generator.run().unwrap_err().to_string();
+        assert!(err.contains("target env (INPUT_FILE)"), "{err}");
+
+        // Plain variables do not turn the harness file-based
+        generator.base_cfg.fixed_input_file = None;
+        generator.base_cfg.target_env = TargetEnv::from_vars(&["LOG_LEVEL=0".into()]).unwrap();
+        let cmds = generator.run().unwrap();
+        assert!(cmds[0]
+            .env
+            .contains(&"AFL_TARGET_ENV=LOG_LEVEL=0".to_string()));
+        assert!(cmds[0].misc_afl_flags.iter().all(|f| !f.starts_with("-f")));
+    }
+
     #[test]
     fn test_fuzzer_roles() {
         let (_temp, generator) = setup_test_generator();
@@ -359,6 +1434,31 @@ mod tests {
         assert!(cmds[1].misc_afl_flags[0].contains("test-target"));
     }
 
+    #[test]
+    fn test_role_afl_flags() {
+        let (_temp, mut generator) = setup_test_generator();
+        generator.base_cfg = generator.base_cfg.clone().with_raw_afl_flags(&RawAflFlags {
+            all: None,
+            master: Some("-D -t 1000".to_string()),
+            secondary: Some("-t 500".to_string()),
+        });
+
+        let new_cmds =
+            || vec![AFLCmd::new(PathBuf::from("afl-fuzz"), PathBuf::from("/bin/test-target")); 3];
+        let mut cmds = new_cmds();
+        generator.apply_fuzzer_roles(&mut cmds, &HashSet::new(), Mode::MultipleCores);
+        generator.apply_role_afl_flags(&mut cmds);
+        assert_eq!(cmds[0].misc_afl_flags[1..], ["-D", "-t", "1000"]);
+        assert_eq!(cmds[1].misc_afl_flags[1..], ["-t", "500"]);
+        assert_eq!(cmds[2].misc_afl_flags[1..], ["-t", "500"]);
+
+        // CI fuzzing has no main instance
+        let mut cmds = new_cmds();
+        generator.apply_fuzzer_roles(&mut cmds, &HashSet::new(), Mode::CIFuzzing);
+        generator.apply_role_afl_flags(&mut cmds);
+        assert!(cmds.iter().all(|c| c.misc_afl_flags[1..] == ["-t", "500"]));
+    }
+
     #[test]
     fn test_sanitizer_binary() {
         let mut harness = create_test_harness();
@@ -373,10 +1473,53 @@ mod tests {
             PathBuf::from("/bin/test-target"),
         )];
 
-        generator.apply_sanitizer_or_target_binary(&mut cmds);
+        generator.apply_sanitizer_or_target_binary(
+            &mut cmds,
+            &HashSet::new(),
+            &mut Warnings::default(),
+        );
         assert_eq!(cmds[0].target_binary, PathBuf::from("/bin/sanitizer"));
     }
 
+    #[test]
+    fn test_sanitizer_on_secondary() {
+        let mut harness = create_test_harness();
+        harness.sanitizer_bin = Some(PathBuf::from("/bin/sanitizer"));
+        harness.cmplog_bin = Some(PathBuf::from("/bin/cmplog"));
+        let generator = AFLCmdGenerator::new(
+            harness,
+            4,
+            &create_afl_base_cfg(),
+            Mode::MultipleCores,
+            None,
+        )
+        .with_san_on_secondary(true);
+
+        let mut cmds: Vec<AFLCmd> = (0..4)
+            .map(|_| AFLCmd::new(PathBuf::from("afl-fuzz"), PathBuf::from("/bin/test-target")))
+            .collect();
+        cmds[1].add_flag("-c /bin/cmplog".to_string());
+        let mut warnings = Warnings::default();
+        generator.apply_sanitizer_or_target_binary(&mut cmds, &HashSet::from([2]), &mut warnings);
+        let binaries: Vec<&Path> = cmds.iter().map(|c| c.target_binary.as_path()).collect();
+        assert_eq!(
+            binaries,
+            [
+                Path::new("/bin/test-target"),
+                Path::new("/bin/test-target"),
+                Path::new("/bin/test-target"),
+                Path::new("/bin/sanitizer")
+            ]
+        );
+        assert!(warnings.is_empty());
+
+        // Without a plain secondary the sanitizer stays on the main instance
+        let mut cmds = cmds[..3].to_vec();
+        generator.apply_sanitizer_or_target_binary(&mut cmds, &HashSet::from([2]), &mut warnings);
+        assert_eq!(cmds[0].target_binary, PathBuf::from("/bin/sanitizer"));
+        assert_eq!(warnings.len(), 1);
+    }
+
     #[test]
     fn test_cmpcov_integration() {
         let mut harness = create_test_harness();
@@ -416,6 +1559,35 @@ mod tests {
         assert!(cmds.iter().any(|cmd| cmd.to_string().contains("-c")));
     }
 
+    #[test]
+    fn test_cmplog_level() {
+        let mut harness = create_test_harness();
+        harness.cmplog_bin = Some(PathBuf::from("/bin/cmplog-binary"));
+        let afl_base = create_afl_base_cfg();
+
+        let generator = AFLCmdGenerator::new(
+            harness.clone(),
+            16,
+            &afl_base,
+            Mode::MultipleCores,
+            Some(42),
+        )
+        .with_cmplog_level(Some(CmplogMode::Transforms));
+        let cmds = generator.run().unwrap();
+        let cmplog_cmds: Vec<String> = cmds
+            .iter()
+            .map(ToString::to_string)
+            .filter(|cmd| cmd.contains("-c /bin/cmplog-binary"))
+            .collect();
+        assert!(!cmplog_cmds.is_empty());
+        assert!(cmplog_cmds.iter().all(|cmd| cmd.contains("-l 2AT")));
+
+        let forbidden = AFLCmdGenerator::new(harness, 16, &afl_base, Mode::MultipleCores, Some(42))
+            .with_cmplog_level(Some(CmplogMode::Transforms))
+            .with_forbidden_flags(FlagBlacklist::new(&["-l 2AT".to_string()], false));
+        assert!(forbidden.run().is_err());
+    }
+
     #[test]
     fn test_target_args_handling() {
         let mut harness = create_test_harness();
@@ -441,11 +1613,40 @@ mod tests {
     #[test]
     fn test_environment_variables() {
         std::env::set_var("AFL_TEST_VAR", "test_value");
-        let env_vars = AFLCmdGenerator::get_afl_env_vars();
+        let mut warnings = Warnings::default();
+        let env_vars = AFLCmdGenerator::get_afl_env_vars(&mut warnings);
         assert!(env_vars.iter().any(|v| v == "AFL_TEST_VAR=test_value"));
+        assert_eq!(warnings.len(), 1);
         std::env::remove_var("AFL_TEST_VAR");
     }
 
+    #[test]
+    fn test_env_override_warnings() {
+        let mut cmds: Vec<AFLCmd> = (0..4)
+            .map(|_| AFLCmd::new(PathBuf::from("afl-fuzz"), PathBuf::from("/bin/target")))
+            .collect();
+        for cmd in &mut cmds[1..3] {
+            cmd.with_env(vec!["AFL_FINAL_SYNC=0".to_string()], false);
+        }
+
+        let mut warnings = Warnings::default();
+        AFLCmdGenerator::apply_global_env_vars(
+            &mut cmds,
+            &["AFL_FINAL_SYNC=1".to_string()],
+            &mut warnings,
+        );
+
+        assert_eq!(warnings.len(), 1);
+        let warning = warnings.iter().next().unwrap();
+        assert_eq!(warning.count, 2);
+        assert_eq!(
+            warning.instances.iter().copied().collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert!(cmds[0].env.contains(&"AFL_FINAL_SYNC=1".to_string()));
+        assert!(cmds[1].env.contains(&"AFL_FINAL_SYNC=0".to_string()));
+    }
+
     #[test]
     fn test_complete_generation() {
         let (_temp, generator) = setup_test_generator();
@@ -469,29 +1670,160 @@ mod tests {
     #[test]
     fn test_afl_defaults() {
         let (_temp_dir, generator) = setup_test_generator();
-        let cmds_no_defaults = generator.run().unwrap();
-
-        let generator_with_defaults = AFLCmdGenerator::new(
-            create_test_harness(),
-            2,
-            &generator.base_cfg.clone(),
-            Mode::MultipleCores,
-            None,
-        );
-
-        let cmds_with_defaults = generator_with_defaults.run().unwrap();
+        let cmds_with_defaults = generator.run().unwrap();
+        let cmds_no_defaults = generator.with_seed_relay(true).run().unwrap();
 
         // Commands with defaults should be simpler
         assert!(cmds_with_defaults[0].to_string().len() < cmds_no_defaults[0].to_string().len());
     }
 
+    #[test]
+    fn test_deterministic_generation() {
+        let mut harness = create_test_harness();
+        harness.cmplog_bin = Some(PathBuf::from("/bin/cmplog-binary"));
+        harness.cmpcov_bin = Some(PathBuf::from("/bin/cmpcov-binary"));
+
+        let gen_cmds = |seed| {
+            AFLCmdGenerator::new(
+                harness.clone(),
+                8,
+                &create_afl_base_cfg(),
+                Mode::MultipleCores,
+                seed,
+            )
+            .with_deterministic(true)
+            .run()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+        };
+
+        let cmds = gen_cmds(None);
+        assert_eq!(cmds, gen_cmds(None));
+        assert!(cmds[0].contains("AFL_FINAL_SYNC=1"));
+        assert!(cmds[0].contains("-M m_test-target"));
+        assert!(cmds.iter().skip(1).all(|cmd| cmd.contains("-S s")));
+        assert!(cmds.iter().all(|cmd| !cmd.contains("-p ")
+            && !cmd.contains("-c ")
+            && !cmd.contains("cmpcov-binary")
+            && !cmd.contains("AFL_DISABLE_TRIM")));
+    }
+
+    #[test]
+    fn test_map_size() {
+        let (_temp_dir, generator) = setup_test_generator();
+        let mut fixed = generator;
+        fixed.base_cfg = fixed.base_cfg.with_map_size(Some(MapSize::Fixed(262_144)));
+        let cmds = fixed.run().unwrap();
+        assert!(cmds
+            .iter()
+            .all(|c| c.env.contains(&"AFL_MAP_SIZE=262144".into())));
+
+        // The input directory is empty so probing fails and AFL++ defaults are kept
+        let (_temp_dir, generator) = setup_test_generator();
+        let mut auto = generator;
+        auto.base_cfg = auto.base_cfg.with_map_size(Some(MapSize::Auto));
+        let cmds = auto.run().unwrap();
+        assert!(cmds
+            .iter()
+            .all(|c| !c.env.iter().any(|e| e.starts_with("AFL_MAP_SIZE="))));
+    }
+
     #[test]
     fn test_afl_relay_seed() {
         let (_temp_dir, generator) = setup_test_generator();
+        assert!(!generator.run().unwrap()[0].to_string().contains(" -s "));
+
+        let generator = generator.with_seed_relay(true);
         let cmds = generator.run().unwrap();
         let expected_seed = Xorshift64::new(generator.seed.unwrap()).rand();
 
         assert!(cmds[0].to_string().contains("-s"));
         assert!(cmds[0].to_string().contains(&format!("{}", expected_seed)));
+        assert!(cmds[1]
+            .to_string()
+            .contains(&format!("-s {}", expected_seed.wrapping_add(1))));
+    }
+
+    #[test]
+    fn test_shared_afl_seed_warning() {
+        let (_temp_dir, mut generator) = setup_test_generator();
+        generator.runners = 4;
+        generator.base_cfg = generator.base_cfg.clone().with_raw_afl_flags(&RawAflFlags {
+            all: Some("-s 1234".to_string()),
+            ..RawAflFlags::default()
+        });
+        let mut warnings = Warnings::default();
+        generator.run_collecting(&mut warnings).unwrap();
+        assert!(warnings
+            .iter()
+            .any(|w| w.message.contains("'-s 1234' and will explore identically")));
+
+        let (_temp_dir, mut generator) = setup_test_generator();
+        generator.runners = 4;
+        let mut warnings = Warnings::default();
+        generator
+            .with_seed_relay(true)
+            .run_collecting(&mut warnings)
+            .unwrap();
+        assert!(!warnings
+            .iter()
+            .any(|w| w.message.contains("explore identically")));
+    }
+
+    #[test]
+    fn test_afl_seed_ratio() {
+        let (_temp_dir, mut generator) = setup_test_generator();
+        generator.runners = 8;
+        let generator = generator.with_seed_relay(true).with_seed_ratio(Some(0.3));
+        let seeded: Vec<bool> = generator
+            .run()
+            .unwrap()
+            .iter()
+            .map(|cmd| cmd.misc_afl_flags.iter().any(|f| f.starts_with("-s ")))
+            .collect();
+        assert_eq!(
+            seeded,
+            [true, true, true, false, false, false, false, false]
+        );
+
+        assert_eq!(instances_for_ratio(8, 0.0), 0);
+        assert_eq!(instances_for_ratio(8, 1.0), 8);
+        assert_eq!(instances_for_ratio(3, 0.5), 2);
+
+        let mut warnings = Warnings::default();
+        generator
+            .with_seed_relay(false)
+            .run_collecting(&mut warnings)
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_seed_reproducibility() {
+        let (_temp_dir, mut generator) = setup_test_generator();
+        generator.runners = 16;
+        generator.seed = None;
+        let render = |cmds: Vec<AFLCmd>| cmds.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        // Unseeded runs draw a fresh seed each time
+        let first = generator.resolve_seed();
+        let second = generator.resolve_seed();
+        assert_ne!(first, second);
+        assert_ne!(
+            render(generator.generate(first, &mut Warnings::default()).unwrap()),
+            render(
+                generator
+                    .generate(second, &mut Warnings::default())
+                    .unwrap()
+            )
+        );
+
+        // Re-running with the printed seed reproduces the commands
+        let unseeded = render(generator.generate(first, &mut Warnings::default()).unwrap());
+        generator.seed = Some(first);
+        assert_eq!(render(generator.run().unwrap()), unseeded);
+        assert_eq!(render(generator.run().unwrap()), unseeded);
     }
 }
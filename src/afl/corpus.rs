@@ -0,0 +1,267 @@
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs,
+    hash::{DefaultHasher, Hasher},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use tempfile::TempDir;
+
+/// Queue directory of a single fuzzer instance
+#[derive(Debug)]
+pub struct QueueDirectory {
+    pub path: PathBuf,
+    pub instance_name: OsString,
+}
+
+/// Finds the queue directories of all instances in an AFL++ output directory
+///
+/// # Errors
+/// * If the output directory cannot be read or contains no queue directories
+pub fn find_queue_directories(afl_out: &Path) -> Result<Vec<QueueDirectory>> {
+    let dirs: Vec<_> = fs::read_dir(afl_out)
+        .with_context(|| {
+            format!(
+                "Failed to read AFL++ output directory: {}",
+                afl_out.display()
+            )
+        })?
+        .filter_map(std::result::Result::ok)
+        .filter_map(|entry| {
+            let queue_path = entry.path().join("queue");
+            if queue_path.is_dir() {
+                Some(QueueDirectory {
+                    path: queue_path,
+                    instance_name: entry.file_name(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if dirs.is_empty() {
+        anyhow::bail!("No queue directories found in {}", afl_out.display());
+    }
+    Ok(dirs)
+}
+
+/// Collects all regular files of a queue directory
+pub fn collect_queue_files(queue_path: &Path) -> Vec<PathBuf> {
+    fs::read_dir(queue_path)
+        .into_iter()
+        .flatten()
+        .filter_map(std::result::Result::ok)
+        .filter_map(|entry| {
+            if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                Some(entry.path())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Result of merging several corpora
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergeStats {
+    /// Number of queue files that were inspected
+    pub scanned: usize,
+    /// Number of unique test cases in the destination
+    pub unique: usize,
+}
+
+/// Combines the queues of multiple AFL++ output directories into a single seed set
+#[derive(Debug, Clone)]
+pub struct CorpusMerger {
+    /// AFL++ output directories to take the queues from
+    out_dirs: Vec<PathBuf>,
+    /// Destination seed directory
+    into: PathBuf,
+    /// Target (and its arguments) to minimize the merged corpus with `afl-cmin`
+    cmin_target: Option<(PathBuf, Vec<String>)>,
+}
+
+impl CorpusMerger {
+    pub fn new(out_dirs: Vec<PathBuf>, into: PathBuf) -> Self {
+        Self {
+            out_dirs,
+            into,
+            cmin_target: None,
+        }
+    }
+
+    /// Minimizes the merged corpus with `afl-cmin` against `target`
+    pub fn with_cmin(mut self, target: PathBuf, target_args: Vec<String>) -> Self {
+        self.cmin_target = Some((target, target_args));
+        self
+    }
+
+    /// Merges all queue files into the destination, dropping duplicates by content
+    ///
+    /// Test cases already present in the destination are kept and taken into account
+    ///
+    /// # Errors
+    /// * If an output directory contains no queue directories
+    /// * If files cannot be read or written
+    /// * If `afl-cmin` fails
+    pub fn merge(&self) -> Result<MergeStats> {
+        let queue_files = self
+            .out_dirs
+            .iter()
+            .map(|dir| find_queue_directories(dir))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .flat_map(|dir| collect_queue_files(&dir.path))
+            .collect::<Vec<_>>();
+
+        let Some((target, target_args)) = &self.cmin_target else {
+            fs::create_dir_all(&self.into)
+                .with_context(|| format!("Failed to create {}", self.into.display()))?;
+            let unique = Self::dedup_into(&queue_files, &self.into)?;
+            return Ok(MergeStats {
+                scanned: queue_files.len(),
+                unique,
+            });
+        };
+
+        if fs::read_dir(&self.into).is_ok_and(|mut entries| entries.next().is_some()) {
+            bail!(
+                "{} is not empty, afl-cmin needs an empty destination",
+                self.into.display()
+            );
+        }
+        let staging = TempDir::new().context("Failed to create staging directory")?;
+        Self::dedup_into(&queue_files, staging.path())?;
+        Self::run_cmin(staging.path(), &self.into, target, target_args)?;
+
+        Ok(MergeStats {
+            scanned: queue_files.len(),
+            unique: collect_queue_files(&self.into).len(),
+        })
+    }
+
+    /// Copies every file with unseen content to `dst` and returns the number of files in `dst`
+    fn dedup_into(files: &[PathBuf], dst: &Path) -> Result<usize> {
+        let mut seen: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        for existing in collect_queue_files(dst) {
+            let content = fs::read(&existing)?;
+            seen.entry(Self::hash(&content)).or_default().push(existing);
+        }
+
+        for file in files {
+            let content =
+                fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+            let hash = Self::hash(&content);
+            let candidates = seen.entry(hash).or_default();
+            // Compare the contents on hash collisions to never drop a distinct test case
+            let duplicate = candidates
+                .iter()
+                .any(|path| fs::read(path).is_ok_and(|c| c == content));
+            if duplicate {
+                continue;
+            }
+
+            let dst_path = dst.join(format!("{hash:016x}_{}", candidates.len()));
+            fs::write(&dst_path, &content)
+                .with_context(|| format!("Failed to write {}", dst_path.display()))?;
+            candidates.push(dst_path);
+        }
+        Ok(seen.values().map(Vec::len).sum())
+    }
+
+    fn hash(content: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(content);
+        hasher.finish()
+    }
+
+    fn run_cmin(input: &Path, output: &Path, target: &Path, target_args: &[String]) -> Result<()> {
+        let status = Command::new("afl-cmin")
+            .arg("-i")
+            .arg(input)
+            .arg("-o")
+            .arg(output)
+            .arg("--")
+            .arg(target)
+            .args(target_args)
+            .stdout(Stdio::null())
+            .status()
+            .context("Failed to execute afl-cmin")?;
+
+        if !status.success() {
+            bail!(
+                "afl-cmin failed with exit code: {}",
+                status.code().unwrap_or(-1)
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campaign(root: &Path, name: &str, instances: &[(&str, &[&str])]) -> PathBuf {
+        let out = root.join(name);
+        for (instance, seeds) in instances {
+            let queue = out.join(instance).join("queue");
+            fs::create_dir_all(queue.join(".state")).unwrap();
+            for (i, seed) in seeds.iter().enumerate() {
+                fs::write(queue.join(format!("id:{i:06}")), seed).unwrap();
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_merge_dedups_by_content() {
+        let tmp = TempDir::new().unwrap();
+        let first = campaign(
+            tmp.path(),
+            "first",
+            &[("m_t", &["a", "b"]), ("s0_t", &["b", "c"])],
+        );
+        let second = campaign(tmp.path(), "second", &[("m_t", &["c", "d"])]);
+        let into = tmp.path().join("seeds");
+
+        let stats = CorpusMerger::new(vec![first.clone(), second], into.clone())
+            .merge()
+            .unwrap();
+        assert_eq!(
+            stats,
+            MergeStats {
+                scanned: 6,
+                unique: 4
+            }
+        );
+
+        let mut contents: Vec<_> = collect_queue_files(&into)
+            .iter()
+            .map(|f| fs::read_to_string(f).unwrap())
+            .collect();
+        contents.sort();
+        assert_eq!(contents, ["a", "b", "c", "d"]);
+
+        // Merging again must not duplicate what is already in the destination
+        let stats = CorpusMerger::new(vec![first], into).merge().unwrap();
+        assert_eq!(
+            stats,
+            MergeStats {
+                scanned: 4,
+                unique: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_requires_queues() {
+        let tmp = TempDir::new().unwrap();
+        let merger = CorpusMerger::new(vec![tmp.path().to_path_buf()], tmp.path().join("seeds"));
+        assert!(merger.merge().is_err());
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
 use glob::glob;
 use rayon::prelude::*;
+use serde::Deserialize;
 use std::{
-    ffi::OsString,
+    fmt::Write as _,
     fs,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     process::{Command, Stdio},
     time::Instant,
@@ -11,13 +14,23 @@ use std::{
 use tempfile::TempDir;
 use uuid::Uuid;
 
-use crate::utils::system::get_user_input;
-
-#[derive(Debug)]
-struct QueueDirectory {
-    path: PathBuf,
-    instance_name: OsString,
-}
+use crate::afl::attribution::{
+    format_contributions, InstanceContribution, RegionSummary, UNIQUE_TOP_CONTRIBUTORS,
+};
+use crate::afl::corpus::{collect_queue_files, find_queue_directories, QueueDirectory};
+use crate::afl::coverage_index::{write_index, CoverageSummary, IndexEntry};
+use crate::afl::executor::{InputExecutor, Isolation, ResourceLimits, Sandbox};
+use crate::afl::target_env::TargetEnv;
+use crate::utils::{clock::Clock, system::get_user_input};
+
+/// Format of the creation time llvm-cov prints in HTML reports
+const CREATED_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+/// Default number of profraw files passed to a single `llvm-profdata merge`
+pub const MERGE_BATCH_SIZE: usize = 1000;
+/// Directory below the artifact directory corrupt profraw files are moved to
+const QUARANTINE_DIR: &str = "quarantined_profraw";
+/// Quarantined profraw files listed by name before the rest is only counted
+const MAX_LISTED_QUARANTINED: usize = 5;
 
 #[derive(Debug)]
 enum ReportType {
@@ -26,12 +39,30 @@ enum ReportType {
         instance: Option<usize>,
     },
     Text,
+    /// Raw `llvm-cov export` JSON written to `path`
+    Json {
+        path: PathBuf,
+    },
+}
+
+/// How test cases are passed to the target during coverage collection
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum InputMode {
+    /// Pipe the test case to the target's stdin
+    Stdin,
+    /// Pass the path of the test case in place of `@@` (or as last argument)
+    File,
+    /// Pass the path of the test case in the target env variables holding `@@`
+    Env,
 }
 
 #[derive(Clone, Debug)]
 pub struct CoverageCollector {
     target: PathBuf,
     afl_out: PathBuf,
+    /// Where the profdata and reports are written, `afl_out` unless it is read-only
+    artifact_dir: PathBuf,
     config: CollectorConfig,
     merged_profdata: Option<PathBuf>,
 }
@@ -43,6 +74,33 @@ struct CollectorConfig {
     is_html: bool,
     show_args: Vec<String>,
     report_args: Vec<String>,
+    input_mode: Option<InputMode>,
+    target_env: TargetEnv,
+    /// Isolation of the replayed test cases, detected once when the sandbox is enabled
+    sandbox: Option<Isolation>,
+    attribute_instances: bool,
+    attribute_unique: bool,
+    /// Profraw files merged per `llvm-profdata` invocation, bounds the memory of each merge
+    merge_batch_size: usize,
+    /// Where the raw `llvm-cov export` JSON is written in addition to the report
+    json_export: Option<PathBuf>,
+    /// Replaces the creation time llvm-cov embeds in HTML reports if fixed
+    clock: Clock,
+}
+
+impl CollectorConfig {
+    /// Returns the input mode, inferring it from the presence of `@@` if not set explicitly
+    fn input_mode(&self) -> InputMode {
+        self.input_mode.unwrap_or_else(|| {
+            if self.target_env.takes_input() {
+                InputMode::Env
+            } else if self.target_args.iter().any(|arg| arg == "@@") {
+                InputMode::File
+            } else {
+                InputMode::Stdin
+            }
+        })
+    }
 }
 
 impl Default for CollectorConfig {
@@ -53,11 +111,22 @@ impl Default for CollectorConfig {
             is_html: true,
             show_args: Vec::new(),
             report_args: Vec::new(),
+            input_mode: None,
+            target_env: TargetEnv::default(),
+            sandbox: None,
+            attribute_instances: false,
+            attribute_unique: false,
+            merge_batch_size: MERGE_BATCH_SIZE,
+            json_export: None,
+            clock: Clock::System,
         }
     }
 }
 
 impl CoverageCollector {
+    /// Tools coverage collection and report generation shell out to
+    pub const TOOLS: [&'static str; 4] = ["llvm-profdata", "llvm-cov", "genhtml", "lcov"];
+
     /// Creates a new coverage collector for the given target binary and AFL++ output directory
     ///
     /// # Arguments
@@ -74,12 +143,12 @@ impl CoverageCollector {
     /// - The readelf command fails to execute
     pub fn new<P: AsRef<Path>>(target: P, afl_out: P) -> Result<Self> {
         Self::is_target_cov_compiled(&target)?;
-        let progs = vec!["llvm-profdata", "llvm-cov", "genhtml", "lcov"];
-        Self::are_reqs_met(&progs)?;
+        Self::are_reqs_met(&Self::TOOLS)?;
 
         Ok(Self {
             target: target.as_ref().to_path_buf(),
             afl_out: afl_out.as_ref().to_path_buf(),
+            artifact_dir: afl_out.as_ref().to_path_buf(),
             config: CollectorConfig::default(),
             merged_profdata: None,
         })
@@ -106,7 +175,11 @@ impl CoverageCollector {
         }
     }
 
-    fn are_reqs_met(progs: &[&str]) -> Result<()> {
+    /// Checks that every program in `progs` runs with `--version`
+    ///
+    /// # Errors
+    /// * If a program is missing or fails to execute
+    pub fn are_reqs_met(progs: &[&str]) -> Result<()> {
         for prog in progs {
             let output = Command::new(prog)
                 .arg("--version")
@@ -174,6 +247,90 @@ impl CoverageCollector {
         self
     }
 
+    /// Forces how test cases are passed to the target instead of inferring it from `@@`
+    ///
+    /// # Arguments
+    /// * `mode` - Input mode to use for all test cases
+    pub fn with_input_mode(&mut self, mode: InputMode) -> &mut Self {
+        self.config.input_mode = Some(mode);
+        self
+    }
+
+    /// Sets the environment of the target, `@@` in the values is replaced with each test case
+    ///
+    /// # Arguments
+    /// * `env` - Variables from `[target] env` or `--target-env`
+    pub fn with_target_env(&mut self, env: TargetEnv) -> &mut Self {
+        self.config.target_env = env;
+        self
+    }
+
+    /// Runs the target inside a sandbox when replaying test cases
+    ///
+    /// The available isolation is detected once here and reused by every executor.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether to isolate the target executions
+    pub fn with_sandbox(&mut self, enabled: bool) -> &mut Self {
+        self.config.sandbox = enabled.then(Isolation::detect);
+        self
+    }
+
+    /// Reports the regions covered by every instance in unified mode
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether to merge and summarize the queue of every instance separately
+    pub fn with_attribute_instances(&mut self, enabled: bool) -> &mut Self {
+        self.config.attribute_instances = enabled;
+        self
+    }
+
+    /// Approximates the regions only the top contributors cover, costs one merge per contributor
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether to compute the unique regions, implies `with_attribute_instances`
+    pub fn with_attribute_unique(&mut self, enabled: bool) -> &mut Self {
+        self.config.attribute_unique = enabled;
+        self.config.attribute_instances |= enabled;
+        self
+    }
+
+    /// Sets how many profraw files are merged by a single `llvm-profdata` invocation
+    ///
+    /// # Arguments
+    /// * `size` - Smaller batches need less memory per merge, larger ones merge faster
+    pub fn with_merge_batch_size(&mut self, size: NonZeroUsize) -> &mut Self {
+        self.config.merge_batch_size = size.get();
+        self
+    }
+
+    /// Writes the unmodified `llvm-cov export` JSON of the merged profdata to a file
+    ///
+    /// # Arguments
+    /// * `path` - Target file, split reports get one file per instance with the index appended
+    pub fn with_json_export(&mut self, path: PathBuf) -> &mut Self {
+        self.config.json_export = Some(path);
+        self
+    }
+
+    /// Writes the merged profdata and the reports to a separate directory
+    ///
+    /// # Arguments
+    /// * `dir` - Directory used instead of the AFL++ output directory, e.g. if it is read-only
+    pub fn with_artifact_dir(&mut self, dir: PathBuf) -> &mut Self {
+        self.artifact_dir = dir;
+        self
+    }
+
+    /// Sets the clock for the creation time embedded in HTML reports
+    ///
+    /// # Arguments
+    /// * `clock` - A fixed clock makes reports of the same coverage identical
+    pub fn with_clock(&mut self, clock: Clock) -> &mut Self {
+        self.config.clock = clock;
+        self
+    }
+
     /// Collects coverage information for the target binary
     ///
     /// This function processes all queue files, generates raw coverage data,
@@ -181,28 +338,70 @@ impl CoverageCollector {
     /// based on the configuration.
     ///
     /// # Errors
+    /// * If `@@` is used in both the target arguments and the target env
     /// * If the AFL++ output directory cannot be read
     pub fn collect(&mut self) -> Result<()> {
-        let queue_dirs = self.find_queue_directories()?;
+        self.config
+            .target_env
+            .check_target_args(self.config.target_args.iter().map(String::as_str))?;
+        let queue_dirs = find_queue_directories(&self.afl_out)?;
+        let executor = self.input_executor();
 
         if self.config.split_reporting {
-            self.process_split_reports(queue_dirs)
+            if self.config.attribute_instances {
+                println!("[!] Warning: Instance attribution is only available for unified reports");
+            }
+            self.process_split_reports(queue_dirs, &executor)
         } else {
-            self.process_unified_report(queue_dirs)
+            self.process_unified_report(queue_dirs, &executor)
         }
     }
 
-    fn process_split_reports(&mut self, queue_dirs: Vec<QueueDirectory>) -> Result<()> {
-        for (idx, dir) in queue_dirs.into_iter().enumerate() {
-            let tmp_dir = self.process_queue_directory(&dir)?;
-            let output_file = self.afl_out.join(format!("merged_{idx}.profdata"));
+    /// Creates the executor replaying test cases, sandboxed if requested and possible
+    fn input_executor(&self) -> InputExecutor {
+        let sandbox = self.config.sandbox.clone().and_then(|isolation| {
+            Sandbox::new(isolation, ResourceLimits::default())
+                .map_err(|e| {
+                    println!("[!] Warning: Failed to set up sandbox, running unsandboxed: {e}")
+                })
+                .ok()
+        });
+        InputExecutor::new(
+            self.target.clone(),
+            self.config.target_args.clone(),
+            self.config.input_mode(),
+        )
+        .with_target_env(self.config.target_env.clone())
+        .with_sandbox(sandbox)
+    }
 
-            Self::merge_raw_coverage(&tmp_dir, &output_file)?;
-            self.merged_profdata = Some(output_file);
+    fn process_split_reports(
+        &mut self,
+        queue_dirs: Vec<QueueDirectory>,
+        executor: &InputExecutor,
+    ) -> Result<()> {
+        let mut entries = Vec::with_capacity(queue_dirs.len());
+        let mut profdata = Vec::with_capacity(queue_dirs.len());
+        for (idx, dir) in queue_dirs.into_iter().enumerate() {
+            let tmp_dir = Self::process_queue_directory(&dir, executor)?;
+            let output_file = self.artifact_dir.join(format!("merged_{idx}.profdata"));
+
+            self.merge_raw_coverage(&tmp_dir, &output_file)?;
+            println!("[*] Merged coverage profile: {}", output_file.display());
+            self.merged_profdata = Some(output_file.clone());
+            if self.config.is_html {
+                entries.push(IndexEntry {
+                    name: dir.instance_name.to_string_lossy().into_owned(),
+                    queue_entries: collect_queue_files(&dir.path).len(),
+                    report_dir: format!("instance_{idx}"),
+                    summary: self.summary_or_warn(&output_file),
+                });
+            }
+            profdata.push(output_file);
 
             let report_type = if self.config.is_html {
                 ReportType::Html {
-                    base_dir: self.afl_out.join("coverage_html"),
+                    base_dir: self.artifact_dir.join("coverage_html"),
                     instance: Some(idx),
                 }
             } else {
@@ -210,6 +409,9 @@ impl CoverageCollector {
             };
 
             self.generate_report(report_type)?;
+            if let Some(path) = self.json_export_path(Some(idx)) {
+                self.generate_report(ReportType::Json { path })?;
+            }
             fs::remove_dir_all(&tmp_dir).with_context(|| {
                 format!(
                     "Failed to remove temporary directory: {}",
@@ -217,9 +419,39 @@ impl CoverageCollector {
                 )
             })?;
         }
+
+        if self.config.is_html && !entries.is_empty() {
+            // The totals row merges all instances, just like a unified report
+            let merged = self.artifact_dir.join("merged.profdata");
+            let totals = Self::merge_profdata(&profdata, &merged)
+                .map_err(|e| {
+                    println!("[!] Warning: Failed to merge the instances for the totals: {e}")
+                })
+                .ok()
+                .and_then(|()| self.summary_or_warn(&merged));
+            let index = write_index(
+                &self.artifact_dir.join("coverage_html"),
+                &entries,
+                totals.as_ref(),
+            )?;
+            println!("[*] Generated the report index: {}", index.display());
+        }
         Ok(())
     }
 
+    /// Line and region totals of `profdata`, `None` with a warning if they cannot be exported
+    fn summary_or_warn(&self, profdata: &Path) -> Option<CoverageSummary> {
+        self.export_summary(profdata)
+            .and_then(|json| CoverageSummary::parse(&json))
+            .map_err(|e| {
+                println!(
+                    "[!] Warning: No coverage summary for {}: {e}",
+                    profdata.display()
+                );
+            })
+            .ok()
+    }
+
     fn is_base_dir_remove(bdir: &Path) -> Result<()> {
         if bdir.exists() {
             println!(
@@ -237,26 +469,49 @@ impl CoverageCollector {
         Ok(())
     }
 
-    fn process_unified_report(&mut self, queue_dirs: Vec<QueueDirectory>) -> Result<()> {
+    fn process_unified_report(
+        &mut self,
+        queue_dirs: Vec<QueueDirectory>,
+        executor: &InputExecutor,
+    ) -> Result<()> {
         let tmp_dir = Self::create_persistent_tmpdir()?;
 
-        let queue_files: Vec<_> = queue_dirs
-            .into_iter()
-            .flat_map(|dir| Self::collect_queue_files(&dir.path))
-            .collect();
-
-        println!("[*] Processing {} queue files", queue_files.len());
-        self.process_queue_files(&queue_files, &tmp_dir);
+        let instances = if self.config.attribute_instances {
+            // Tag the profraw files with the index of their instance for the sub-merges
+            for (idx, dir) in queue_dirs.iter().enumerate() {
+                let queue_files = collect_queue_files(&dir.path);
+                println!(
+                    "[*] Processing {} queue files of instance {}",
+                    queue_files.len(),
+                    dir.instance_name.to_string_lossy()
+                );
+                Self::process_queue_files(&queue_files, &tmp_dir, &format!("{idx}_"), executor);
+            }
+            queue_dirs
+                .iter()
+                .map(|dir| dir.instance_name.to_string_lossy().into_owned())
+                .collect()
+        } else {
+            let queue_files: Vec<_> = queue_dirs
+                .into_iter()
+                .flat_map(|dir| collect_queue_files(&dir.path))
+                .collect();
+
+            println!("[*] Processing {} queue files", queue_files.len());
+            Self::process_queue_files(&queue_files, &tmp_dir, "", executor);
+            Vec::new()
+        };
 
-        let output_file = self.afl_out.join("merged.profdata");
-        Self::merge_raw_coverage(&tmp_dir, &output_file)?;
+        let output_file = self.artifact_dir.join("merged.profdata");
+        self.merge_raw_coverage(&tmp_dir, &output_file)?;
+        println!("[*] Merged coverage profile: {}", output_file.display());
         self.merged_profdata = Some(output_file);
 
         let report_type = if self.config.is_html {
-            let base_dir = self.afl_out.join("coverage_html");
+            let base_dir = self.artifact_dir.join("coverage_html");
             Self::is_base_dir_remove(&base_dir)?;
             ReportType::Html {
-                base_dir: self.afl_out.join("coverage_html"),
+                base_dir: self.artifact_dir.join("coverage_html"),
                 instance: None,
             }
         } else {
@@ -264,6 +519,12 @@ impl CoverageCollector {
         };
 
         self.generate_report(report_type)?;
+        if let Some(path) = self.json_export_path(None) {
+            self.generate_report(ReportType::Json { path })?;
+        }
+        if !instances.is_empty() {
+            self.report_instance_contributions(&instances, &tmp_dir)?;
+        }
         fs::remove_dir_all(&tmp_dir).with_context(|| {
             format!(
                 "Failed to remove temporary directory: {}",
@@ -273,6 +534,81 @@ impl CoverageCollector {
         Ok(())
     }
 
+    /// Prints the regions covered by every instance based on per-instance sub-merges
+    ///
+    /// `tmp_dir` has to contain the profraw files tagged with the instance index.
+    fn report_instance_contributions(&self, instances: &[String], tmp_dir: &Path) -> Result<()> {
+        let total = self.region_summary(self.get_merged_profdata()?)?;
+
+        let mut profdata = Vec::with_capacity(instances.len());
+        let mut contributions = Vec::with_capacity(instances.len());
+        for (idx, name) in instances.iter().enumerate() {
+            let output_file = tmp_dir.join(format!("instance_{idx}.profdata"));
+            let pattern = format!("cov_{idx}_*.profraw");
+            if let Err(e) = self.merge_raw_coverage_matching(tmp_dir, &pattern, &output_file) {
+                println!("[!] Warning: Skipping instance {name}: {e}");
+                continue;
+            }
+            contributions.push(InstanceContribution {
+                name: name.clone(),
+                covered: self.region_summary(&output_file)?.covered,
+                unique: None,
+            });
+            profdata.push(output_file);
+        }
+
+        if self.config.attribute_unique && profdata.len() > 1 {
+            let mut by_coverage: Vec<usize> = (0..contributions.len()).collect();
+            by_coverage.sort_by_key(|&i| std::cmp::Reverse(contributions[i].covered));
+
+            for &i in by_coverage.iter().take(UNIQUE_TOP_CONTRIBUTORS) {
+                let others: Vec<_> = profdata
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, p)| p.clone())
+                    .collect();
+                let output_file = tmp_dir.join(format!("others_{i}.profdata"));
+                Self::merge_profdata(&others, &output_file)?;
+                let others_covered = self.region_summary(&output_file)?.covered;
+                contributions[i].unique = Some(total.covered.saturating_sub(others_covered));
+            }
+        }
+
+        println!(
+            "[*] Per-instance contribution ({} of {} regions covered overall):\n{}",
+            total.covered,
+            total.count,
+            format_contributions(&contributions, total.count)
+        );
+        Ok(())
+    }
+
+    /// Region totals of `profdata` via `llvm-cov export -summary-only`
+    fn region_summary(&self, profdata: &Path) -> Result<RegionSummary> {
+        RegionSummary::parse(&self.export_summary(profdata)?)
+    }
+
+    /// JSON summary of `profdata` emitted by `llvm-cov export -summary-only`
+    fn export_summary(&self, profdata: &Path) -> Result<String> {
+        let output = Command::new("llvm-cov")
+            .arg("export")
+            .arg(&self.target)
+            .arg("-instr-profile")
+            .arg(profdata)
+            .arg("-summary-only")
+            .output()
+            .context("Failed to run llvm-cov export")?;
+
+        if !output.status.success() {
+            bail!(
+                "llvm-cov export failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
     fn generate_report(&self, report_type: ReportType) -> Result<()> {
         let merged_profdata = self.get_merged_profdata()?;
 
@@ -287,9 +623,48 @@ impl CoverageCollector {
                 self.run_llvm_cov_show(merged_profdata, &output_dir)
             }
             ReportType::Text => self.run_llvm_cov_report(merged_profdata),
+            ReportType::Json { path } => self.run_llvm_cov_export(merged_profdata, &path),
         }
     }
 
+    /// The JSON export file of the instance at index `instance`, or of the unified report
+    fn json_export_path(&self, instance: Option<usize>) -> Option<PathBuf> {
+        let path = self.config.json_export.as_ref()?;
+        let Some(idx) = instance else {
+            return Some(path.clone());
+        };
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let file_name = match path.extension() {
+            Some(ext) => format!("{stem}_{idx}.{}", ext.to_string_lossy()),
+            None => format!("{stem}_{idx}"),
+        };
+        Some(path.with_file_name(file_name))
+    }
+
+    /// Streams the JSON of `llvm-cov export` into `path` without touching it
+    fn run_llvm_cov_export(&self, profdata: &Path, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let status = Command::new("llvm-cov")
+            .arg("export")
+            .arg(&self.target)
+            .arg("-instr-profile")
+            .arg(profdata)
+            .arg("-format=text")
+            .stdout(Stdio::from(file))
+            .status()
+            .context("Failed to run llvm-cov export")?;
+
+        if !status.success() {
+            anyhow::bail!("llvm-cov export failed");
+        }
+        println!("[*] Exported llvm-cov JSON to: {}", path.display());
+        Ok(())
+    }
+
     fn run_llvm_cov_show(&self, profdata: &Path, output_dir: &Path) -> Result<()> {
         self.run_llvm_command(
             "show",
@@ -303,6 +678,12 @@ impl CoverageCollector {
             ],
             &self.config.show_args,
         )?;
+        if self.config.clock.fixed().is_some() {
+            pin_created_time(
+                output_dir,
+                &self.config.clock.format_utc(CREATED_TIME_FORMAT),
+            )?;
+        }
 
         println!(
             "[*] Generated HTML coverage report in: {}",
@@ -350,52 +731,9 @@ impl CoverageCollector {
         Ok(())
     }
 
-    fn find_queue_directories(&self) -> Result<Vec<QueueDirectory>> {
-        let dirs: Vec<_> = fs::read_dir(&self.afl_out)
-            .with_context(|| {
-                format!(
-                    "Failed to read AFL++ output directory: {}",
-                    self.afl_out.display()
-                )
-            })?
-            .filter_map(std::result::Result::ok)
-            .filter_map(|entry| {
-                let queue_path = entry.path().join("queue");
-                if queue_path.is_dir() {
-                    Some(QueueDirectory {
-                        path: queue_path,
-                        instance_name: entry.file_name(),
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        if dirs.is_empty() {
-            anyhow::bail!("No queue directories found in {}", self.afl_out.display());
-        }
-        Ok(dirs)
-    }
-
-    fn collect_queue_files(queue_path: &Path) -> Vec<PathBuf> {
-        fs::read_dir(queue_path)
-            .into_iter()
-            .flatten()
-            .filter_map(std::result::Result::ok)
-            .filter_map(|entry| {
-                if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
-                    Some(entry.path())
-                } else {
-                    None
-                }
-            })
-            .collect()
-    }
-
-    fn process_queue_directory(&self, dir: &QueueDirectory) -> Result<PathBuf> {
+    fn process_queue_directory(dir: &QueueDirectory, executor: &InputExecutor) -> Result<PathBuf> {
         let tmp_dir = Self::create_persistent_tmpdir()?;
-        let queue_files = Self::collect_queue_files(&dir.path);
+        let queue_files = collect_queue_files(&dir.path);
 
         println!(
             "[+] Processing queue directory for instance: {} with {} entries",
@@ -403,21 +741,27 @@ impl CoverageCollector {
             queue_files.len()
         );
 
-        self.process_queue_files(&queue_files, &tmp_dir);
+        Self::process_queue_files(&queue_files, &tmp_dir, "", executor);
 
         Ok(tmp_dir)
     }
 
+    /// Replays `queue_files` into `cov_<tag><file_name>_.profraw` files in `tmp_dir`
     #[allow(clippy::cast_precision_loss)]
-    fn process_queue_files(&self, queue_files: &[PathBuf], tmp_dir: &Path) {
+    fn process_queue_files(
+        queue_files: &[PathBuf],
+        tmp_dir: &Path,
+        tag: &str,
+        executor: &InputExecutor,
+    ) {
         let start_time = Instant::now();
         let total_files = queue_files.len();
 
         queue_files.par_iter().for_each(|file_path| {
             let file_name = file_path.file_name().unwrap().to_str().unwrap();
-            let dst_path = tmp_dir.join(format!("cov_{file_name}_.profraw"));
+            let dst_path = tmp_dir.join(format!("cov_{tag}{file_name}_.profraw"));
 
-            if let Err(e) = self.run_target_with_input(file_path, &dst_path) {
+            if let Err(e) = executor.run(file_path, &dst_path) {
                 eprintln!("[-] Failed to process {file_name}: {e}");
             }
         });
@@ -430,80 +774,18 @@ impl CoverageCollector {
         );
     }
 
-    fn run_target_with_input(&self, input_path: &Path, output_path: &Path) -> Result<()> {
-        if self.is_file_based_harness() {
-            self.run_file_based_target(input_path, output_path)
-        } else {
-            self.run_stdin_based_target(input_path, output_path)
-        }
-    }
-
-    fn is_file_based_harness(&self) -> bool {
-        self.config.target_args.iter().any(|arg| arg == "@@")
+    fn merge_raw_coverage(&self, raw_cov_dir: &Path, output_file: &Path) -> Result<()> {
+        self.merge_raw_coverage_matching(raw_cov_dir, "cov_*.profraw", output_file)
     }
 
-    fn run_file_based_target(&self, input_path: &Path, output_path: &Path) -> Result<()> {
-        // Filter out @@ from arguments and replace with actual input file
-        let args: Vec<_> = self
-            .config
-            .target_args
-            .iter()
-            .filter(|&arg| arg != "@@")
-            .collect();
-
-        Command::new(&self.target)
-            .args(args)
-            .arg(input_path)
-            .env("LLVM_PROFILE_FILE", output_path)
-            .stderr(Stdio::null())
-            .stdout(Stdio::null())
-            .status()
-            .with_context(|| {
-                format!(
-                    "Failed to execute file-based target with input: {}",
-                    input_path.display()
-                )
-            })?;
-        Ok(())
-    }
-
-    fn run_stdin_based_target(&self, input_path: &Path, output_path: &Path) -> Result<()> {
-        let input_content = fs::read(input_path)
-            .with_context(|| format!("Failed to read input file: {}", input_path.display()))?;
-
-        let mut child = Command::new(&self.target)
-            .args(&self.config.target_args)
-            .env("LLVM_PROFILE_FILE", output_path)
-            .stdin(Stdio::piped())
-            .stderr(Stdio::null())
-            .stdout(Stdio::null())
-            .spawn()
-            .with_context(|| "Failed to spawn stdin-based target")?;
-
-        if let Some(mut stdin) = child.stdin.take() {
-            use std::io::Write;
-            stdin
-                .write_all(&input_content)
-                .with_context(|| "Failed to write to target's stdin")?;
-            // Stdin will be closed when dropped
-        }
-
-        let status = child
-            .wait()
-            .with_context(|| "Failed to wait for target completion")?;
-
-        if !status.success() {
-            // This is expected for some inputs during fuzzing, so we just return Ok
-            println!(
-                "Note: Target exited with non-zero status for input: {}",
-                input_path.display()
-            );
-        }
-        Ok(())
-    }
-
-    fn merge_raw_coverage(raw_cov_dir: &Path, output_file: &Path) -> Result<()> {
-        let pattern = raw_cov_dir.join("cov_*.profraw");
+    /// Merges all profraw files in `raw_cov_dir` matching the glob `pattern`
+    fn merge_raw_coverage_matching(
+        &self,
+        raw_cov_dir: &Path,
+        pattern: &str,
+        output_file: &Path,
+    ) -> Result<()> {
+        let pattern = raw_cov_dir.join(pattern);
         let profraw_files: Vec<_> = glob(pattern.to_str().unwrap())?
             .filter_map(Result::ok)
             .collect();
@@ -514,43 +796,158 @@ impl CoverageCollector {
 
         // Create temporary directory for batch processing
         let temp_dir = TempDir::new()?;
-        
-        // Process files in parallel batches
-        let temp_merged_files: Result<Vec<_>> = profraw_files
-            .par_chunks(1000)
+
+        // Process files in parallel batches, a failing batch is bisected to skip corrupt files
+        let batches: Result<Vec<_>> = profraw_files
+            .par_chunks(self.config.merge_batch_size)
             .enumerate()
             .map(|(i, chunk)| {
                 let temp_output = temp_dir.path().join(format!("temp_merged_{i}.profdata"));
-                
-                let output = Command::new("llvm-profdata")
-                    .arg("merge")
-                    .arg("-sparse")
-                    .args(chunk)
-                    .arg("-o")
-                    .arg(&temp_output)
-                    .output()?;
-
-                if !output.status.success() {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    anyhow::bail!(
-                        "Failed to merge coverage files (batch {}):\nCommand: llvm-profdata merge -sparse [...] -o {}\nError: {}",
-                        i,
-                        temp_output.display(),
-                        stderr
-                    );
+                let mut corrupt = Vec::new();
+                match Self::merge_profraw_batch(chunk, &temp_output)? {
+                    Ok(()) => Ok((Some(temp_output), corrupt, None)),
+                    Err(error) => {
+                        let merged =
+                            Self::merge_isolating_corrupt(chunk, &temp_output, &mut corrupt)?;
+                        let error = format!(
+                            "Failed to merge coverage files (batch {i}):\nCommand: llvm-profdata merge -sparse [...] -o {}\nError: {error}",
+                            temp_output.display()
+                        );
+                        Ok((merged.then_some(temp_output), corrupt, Some(error)))
+                    }
                 }
-
-                Ok(temp_output)
             })
             .collect();
 
-        let temp_merged_files = temp_merged_files?;
+        let mut temp_merged_files = Vec::new();
+        let mut corrupt = Vec::new();
+        let mut first_error = None;
+        for (merged, batch_corrupt, error) in batches? {
+            temp_merged_files.extend(merged);
+            corrupt.extend(batch_corrupt);
+            first_error = first_error.or(error);
+        }
+        if temp_merged_files.is_empty() {
+            // Nothing merged at all points at llvm-profdata itself rather than at the files
+            anyhow::bail!(first_error.unwrap_or_default());
+        }
+        if !corrupt.is_empty() {
+            self.quarantine_profraw(&corrupt);
+        }
 
         // Final merge of temporary files
+        Self::merge_profdata(&temp_merged_files, output_file)
+    }
+
+    /// Merges `files` into `output_file`, the inner error holds the stderr of a failed merge
+    ///
+    /// # Errors
+    /// * If `llvm-profdata` cannot be executed
+    fn merge_profraw_batch(
+        files: &[PathBuf],
+        output_file: &Path,
+    ) -> Result<std::result::Result<(), String>> {
         let output = Command::new("llvm-profdata")
             .arg("merge")
             .arg("-sparse")
-            .args(&temp_merged_files)
+            .args(files)
+            .arg("-o")
+            .arg(output_file)
+            .output()?;
+        if output.status.success() {
+            Ok(Ok(()))
+        } else {
+            Ok(Err(String::from_utf8_lossy(&output.stderr).into_owned()))
+        }
+    }
+
+    /// Bisects a batch that failed to merge until the offending profraw files are isolated
+    ///
+    /// The files that merge fine end up in `output_file`, the ones that fail on their own are
+    /// collected in `corrupt`. Returns whether `output_file` was written.
+    fn merge_isolating_corrupt(
+        files: &[PathBuf],
+        output_file: &Path,
+        corrupt: &mut Vec<PathBuf>,
+    ) -> Result<bool> {
+        // Only called after `files` failed to merge as a whole
+        if let [file] = files {
+            corrupt.push(file.clone());
+            return Ok(false);
+        }
+
+        let (left, right) = files.split_at(files.len() / 2);
+        let mut halves = Vec::with_capacity(2);
+        for (idx, half) in [left, right].into_iter().enumerate() {
+            let half_output = output_file.with_extension(format!("{idx}.profdata"));
+            let merged = match Self::merge_profraw_batch(half, &half_output)? {
+                Ok(()) => true,
+                Err(_) => Self::merge_isolating_corrupt(half, &half_output, corrupt)?,
+            };
+            if merged {
+                halves.push(half_output);
+            }
+        }
+        match halves.as_slice() {
+            [] => Ok(false),
+            [half] => {
+                fs::rename(half, output_file)?;
+                Ok(true)
+            }
+            _ => Self::merge_profdata(&halves, output_file).map(|()| true),
+        }
+    }
+
+    /// Moves corrupt profraw files out of the way and reports them
+    ///
+    /// Later sub-merges of the same directory, e.g. for instance attribution, skip them that way.
+    fn quarantine_profraw(&self, corrupt: &[PathBuf]) {
+        let quarantine_dir = self.artifact_dir.join(QUARANTINE_DIR);
+        if let Err(e) = fs::create_dir_all(&quarantine_dir) {
+            println!(
+                "[!] Warning: Failed to create {}: {e}",
+                quarantine_dir.display()
+            );
+        }
+        let mut names = Vec::with_capacity(corrupt.len());
+        for file in corrupt {
+            let Some(name) = file.file_name() else {
+                continue;
+            };
+            let dst = quarantine_dir.join(name);
+            let moved = fs::rename(file, &dst)
+                .or_else(|_| fs::copy(file, &dst).and_then(|_| fs::remove_file(file)));
+            if let Err(e) = moved {
+                println!("[!] Warning: Failed to quarantine {}: {e}", file.display());
+                // Still skipped by later merges
+                let _ = fs::remove_file(file);
+            }
+            names.push(name.to_string_lossy().into_owned());
+        }
+        names.sort();
+        let mut listed = names
+            .iter()
+            .take(MAX_LISTED_QUARANTINED)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if names.len() > MAX_LISTED_QUARANTINED {
+            let _ = write!(listed, " and {} more", names.len() - MAX_LISTED_QUARANTINED);
+        }
+        println!(
+            "[!] Warning: Skipped {} corrupt profraw file{} ({listed}), quarantined in {}",
+            names.len(),
+            if names.len() == 1 { "" } else { "s" },
+            quarantine_dir.display()
+        );
+    }
+
+    /// Merges already indexed profdata files into `output_file`
+    fn merge_profdata(profdata_files: &[PathBuf], output_file: &Path) -> Result<()> {
+        let output = Command::new("llvm-profdata")
+            .arg("merge")
+            .arg("-sparse")
+            .args(profdata_files)
             .arg("-o")
             .arg(output_file)
             .output()?;
@@ -580,6 +977,41 @@ impl CoverageCollector {
     }
 }
 
+/// Rewrites the `Created: ...` time llvm-cov puts on every page of the HTML report in `dir`
+fn pin_created_time(dir: &Path, created: &str) -> Result<()> {
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            pin_created_time(&path, created)?;
+        } else if path.extension().is_some_and(|ext| ext == "html") {
+            let html = fs::read_to_string(&path)?;
+            if let Some(pinned) = replace_created_time(&html, created) {
+                fs::write(&path, pinned)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `html` with the text after every `Created: ` up to the closing tag replaced by `created`
+fn replace_created_time(html: &str, created: &str) -> Option<String> {
+    const MARKER: &str = "Created: ";
+    if !html.contains(MARKER) {
+        return None;
+    }
+    let mut pinned = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find(MARKER) {
+        let (head, tail) = rest.split_at(start + MARKER.len());
+        pinned.push_str(head);
+        pinned.push_str(created);
+        rest = &tail[tail.find('<').unwrap_or(tail.len())..];
+    }
+    pinned.push_str(rest);
+    Some(pinned)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -633,7 +1065,7 @@ mod tests {
 
         let binary_path = test_dir.join("test_binary");
         Command::new("clang")
-            .args(&["-fprofile-instr-generate", "-fcoverage-mapping"])
+            .args(["-fprofile-instr-generate", "-fcoverage-mapping"])
             .arg("-o")
             .arg(&binary_path)
             .arg(&source_path)
@@ -649,7 +1081,7 @@ mod tests {
         let (test_dir, afl_dir) = setup_test_dir()?;
         let collector = CoverageCollector::new(binary_path, afl_dir)?;
 
-        let queue_dirs = collector.find_queue_directories()?;
+        let queue_dirs = find_queue_directories(&collector.afl_out)?;
         assert_eq!(queue_dirs.len(), 3); // We now create 3 fuzzer instances
         assert!(queue_dirs.iter().all(|dir| dir.path.ends_with("queue")));
 
@@ -661,13 +1093,33 @@ mod tests {
     fn test_is_file_based_harness() -> Result<()> {
         let binary_path = create_mock_binary()?;
         let mut collector = CoverageCollector::new(binary_path, "/tmp".into())?;
-        assert!(!collector.is_file_based_harness());
+        assert_eq!(collector.config.input_mode(), InputMode::Stdin);
 
         collector.with_target_args(vec!["@@".to_string()]);
-        assert!(collector.is_file_based_harness());
+        assert_eq!(collector.config.input_mode(), InputMode::File);
         Ok(())
     }
 
+    #[test]
+    fn test_input_mode() {
+        let mut config = CollectorConfig::default();
+        assert_eq!(config.input_mode(), InputMode::Stdin);
+
+        config.target_args = vec!["--flag".to_string(), "@@".to_string()];
+        assert_eq!(config.input_mode(), InputMode::File);
+
+        config.input_mode = Some(InputMode::Stdin);
+        assert_eq!(config.input_mode(), InputMode::Stdin);
+
+        config.target_args = vec!["--flag".to_string()];
+        config.input_mode = Some(InputMode::File);
+        assert_eq!(config.input_mode(), InputMode::File);
+
+        config.input_mode = None;
+        config.target_env = TargetEnv::from_vars(&["INPUT_FILE=@@".into()]).unwrap();
+        assert_eq!(config.input_mode(), InputMode::Env);
+    }
+
     #[test]
     fn test_collector_config() -> Result<()> {
         let binary_path = create_mock_binary()?;
@@ -677,9 +1129,24 @@ mod tests {
             .with_split_report(true)
             .with_target_args(vec!["arg1".to_string()])
             .with_misc_show_args(vec!["--show-branches".to_string()])
-            .with_misc_report_args(vec!["--show-functions".to_string()]);
-
+            .with_misc_report_args(vec!["--show-functions".to_string()])
+            .with_attribute_unique(true);
+
+        assert_eq!(collector.config.merge_batch_size, MERGE_BATCH_SIZE);
+        collector.with_merge_batch_size(NonZeroUsize::new(250).unwrap());
+        assert_eq!(collector.config.merge_batch_size, 250);
+        collector.with_json_export(PathBuf::from("/tmp/cov.json"));
+        assert_eq!(
+            collector.json_export_path(None),
+            Some(PathBuf::from("/tmp/cov.json"))
+        );
+        assert_eq!(
+            collector.json_export_path(Some(2)),
+            Some(PathBuf::from("/tmp/cov_2.json"))
+        );
         assert!(!collector.config.is_html);
+        assert!(collector.config.attribute_instances);
+        assert!(collector.config.attribute_unique);
         assert!(collector.config.split_reporting);
         assert_eq!(collector.config.target_args, vec!["arg1"]);
         assert_eq!(collector.config.show_args, vec!["--show-branches"]);
@@ -694,8 +1161,8 @@ mod tests {
         let (test_dir, afl_dir) = setup_test_dir()?;
         let collector = CoverageCollector::new(binary_path, afl_dir)?;
 
-        let queue_dirs = collector.find_queue_directories()?;
-        let files = CoverageCollector::collect_queue_files(&queue_dirs[0].path);
+        let queue_dirs = find_queue_directories(&collector.afl_out)?;
+        let files = collect_queue_files(&queue_dirs[0].path);
 
         assert_eq!(files.len(), 3); // Each queue directory has 3 files
         assert!(files.iter().all(|f| f.to_str().unwrap().contains("id:")));
@@ -712,8 +1179,11 @@ mod tests {
         let mut collector = CoverageCollector::new(&binary_path, &afl_dir)?;
         collector.with_target_args(vec!["@@".to_string()]);
 
-        let queue_dirs = collector.find_queue_directories()?;
-        let tmp_dir = collector.process_queue_directory(&queue_dirs[0])?;
+        let queue_dirs = find_queue_directories(&collector.afl_out)?;
+        let tmp_dir = CoverageCollector::process_queue_directory(
+            &queue_dirs[0],
+            &collector.input_executor(),
+        )?;
 
         // Check that profraw files were created
         let profraw_count = glob(tmp_dir.join("cov_*.profraw").to_str().unwrap())?.count();
@@ -729,7 +1199,7 @@ mod tests {
     fn test_invalid_afl_directory() -> Result<()> {
         let binary_path = create_mock_binary()?;
         let collector = CoverageCollector::new(binary_path, "/nonexistent".into())?;
-        assert!(collector.find_queue_directories().is_err());
+        assert!(find_queue_directories(&collector.afl_out).is_err());
         Ok(())
     }
 
@@ -740,9 +1210,60 @@ mod tests {
         fs::create_dir(&test_dir)?;
 
         let collector = CoverageCollector::new(binary_path, test_dir.clone())?;
-        assert!(collector.find_queue_directories().is_err());
+        assert!(find_queue_directories(&collector.afl_out).is_err());
 
         fs::remove_dir_all(test_dir)?;
         Ok(())
     }
+
+    #[test]
+    fn test_merge_isolating_corrupt() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        let mut files = Vec::new();
+        for idx in 0..5 {
+            let file = dir.path().join(format!("cov_{idx}_.profraw"));
+            // llvm-profdata accepts the text format as input as well
+            let content = if idx == 3 {
+                "garbage".to_string()
+            } else {
+                format!("# IR level Instrumentation Flag\n:ir\nfunc_{idx}\n# Func Hash:\n1\n# Num Counters:\n1\n# Counter Values:\n{idx}\n")
+            };
+            fs::write(&file, content)?;
+            files.push(file);
+        }
+
+        let output = dir.path().join("merged.profdata");
+        assert!(CoverageCollector::merge_profraw_batch(&files, &output)?.is_err());
+        let mut corrupt = Vec::new();
+        assert!(CoverageCollector::merge_isolating_corrupt(
+            &files,
+            &output,
+            &mut corrupt
+        )?);
+        assert_eq!(corrupt, [files[3].clone()]);
+        assert!(output.is_file());
+
+        let mut corrupt = Vec::new();
+        let output = dir.path().join("none.profdata");
+        assert!(!CoverageCollector::merge_isolating_corrupt(
+            &files[3..4],
+            &output,
+            &mut corrupt
+        )?);
+        assert_eq!(corrupt.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_created_time() {
+        let html = "<h2>Coverage Report</h2><h4>Created: 2024-06-03 09:41</h4><p>x</p>";
+        assert_eq!(
+            replace_created_time(html, "2024-05-01 12:00").as_deref(),
+            Some("<h2>Coverage Report</h2><h4>Created: 2024-05-01 12:00</h4><p>x</p>")
+        );
+        assert_eq!(
+            replace_created_time("<p>no time</p>", "2024-05-01 12:00"),
+            None
+        );
+    }
 }
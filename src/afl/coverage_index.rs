@@ -0,0 +1,232 @@
+use anyhow::{Context, Result};
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// File name of the overview page next to the per-instance reports
+pub const INDEX_FILE: &str = "index.html";
+
+/// Covered and total amount of one kind of coverage, e.g. lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub count: u64,
+    pub covered: u64,
+}
+
+impl Totals {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn percent(self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.covered as f64 * 100.0 / self.count as f64
+        }
+    }
+}
+
+/// Line and region totals reported by `llvm-cov export -summary-only`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoverageSummary {
+    pub lines: Totals,
+    pub regions: Totals,
+}
+
+impl CoverageSummary {
+    /// Parses the line and region totals out of the JSON emitted by `llvm-cov export -summary-only`
+    ///
+    /// # Errors
+    /// * If the JSON is malformed or lacks the totals
+    pub fn parse(json: &str) -> Result<Self> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).context("Failed to parse llvm-cov export output")?;
+        let totals = &value["data"][0]["totals"];
+        let field = |kind: &str, name: &str| {
+            totals[kind][name]
+                .as_u64()
+                .with_context(|| format!("llvm-cov export output lacks totals.{kind}.{name}"))
+        };
+        let kind = |kind: &str| -> Result<Totals> {
+            Ok(Totals {
+                count: field(kind, "count")?,
+                covered: field(kind, "covered")?,
+            })
+        };
+
+        Ok(Self {
+            lines: kind("lines")?,
+            regions: kind("regions")?,
+        })
+    }
+}
+
+/// A row of the index page, one per split report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// Name of the instance directory, e.g. `m_fuzzer01`
+    pub name: String,
+    pub queue_entries: usize,
+    /// Report directory relative to the index page
+    pub report_dir: String,
+    /// `None` if the summary could not be exported
+    pub summary: Option<CoverageSummary>,
+}
+
+/// Renders the self-contained overview page of the split reports
+///
+/// `totals` is the summary of all instances merged and becomes the last row.
+pub fn render_index(entries: &[IndexEntry], totals: Option<&CoverageSummary>) -> String {
+    let mut rows = String::new();
+    for entry in entries {
+        let _ = writeln!(
+            rows,
+            "<tr><td><a href=\"{dir}/{INDEX_FILE}\">{name}</a></td><td>{queue}</td>{cells}</tr>",
+            dir = escape(&entry.report_dir),
+            name = escape(&entry.name),
+            queue = entry.queue_entries,
+            cells = summary_cells(entry.summary.as_ref())
+        );
+    }
+    if let Some(totals) = totals {
+        let queue: usize = entries.iter().map(|e| e.queue_entries).sum();
+        let _ = writeln!(
+            rows,
+            "<tr class=\"totals\"><td>All instances merged</td><td>{queue}</td>{}</tr>",
+            summary_cells(Some(totals))
+        );
+    }
+
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Coverage per instance</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: right; }}
+th:first-child, td:first-child {{ text-align: left; }}
+th {{ background: #eee; }}
+tr.totals {{ font-weight: bold; background: #f5f5f5; }}
+</style>
+</head>
+<body>
+<h2>Coverage per instance</h2>
+<table>
+<tr><th>Instance</th><th>Queue entries</th><th>Lines</th><th>Line coverage</th><th>Regions</th><th>Region coverage</th></tr>
+{rows}</table>
+</body>
+</html>
+"
+    )
+}
+
+/// Writes the index page into `base_dir`, replacing the one of an earlier run
+///
+/// # Errors
+/// * If the page cannot be written
+pub fn write_index(
+    base_dir: &Path,
+    entries: &[IndexEntry],
+    totals: Option<&CoverageSummary>,
+) -> Result<PathBuf> {
+    let path = base_dir.join(INDEX_FILE);
+    fs::write(&path, render_index(entries, totals))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+fn summary_cells(summary: Option<&CoverageSummary>) -> String {
+    let Some(summary) = summary else {
+        return "<td>-</td><td>-</td><td>-</td><td>-</td>".to_string();
+    };
+    let mut cells = String::new();
+    for totals in [summary.lines, summary.regions] {
+        let _ = write!(
+            cells,
+            "<td>{}/{}</td><td>{:.2}%</td>",
+            totals.covered,
+            totals.count,
+            totals.percent()
+        );
+    }
+    cells
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn summary(lines: (u64, u64), regions: (u64, u64)) -> CoverageSummary {
+        CoverageSummary {
+            lines: Totals {
+                covered: lines.0,
+                count: lines.1,
+            },
+            regions: Totals {
+                covered: regions.0,
+                count: regions.1,
+            },
+        }
+    }
+
+    #[test]
+    fn test_parse_coverage_summary() {
+        let json = r#"{"data":[{"totals":{"lines":{"count":400,"covered":100,"percent":25},"regions":{"count":200,"covered":50,"notcovered":150,"percent":25}}}],"type":"llvm.coverage.json.export"}"#;
+        assert_eq!(
+            CoverageSummary::parse(json).unwrap(),
+            summary((100, 400), (50, 200))
+        );
+        let no_lines = r#"{"data":[{"totals":{"regions":{"count":200,"covered":50}}}]}"#;
+        assert!(CoverageSummary::parse(no_lines).is_err());
+        assert!(CoverageSummary::parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_render_index() {
+        let entries = [
+            IndexEntry {
+                name: "m_<fuzzer>".to_string(),
+                queue_entries: 120,
+                report_dir: "instance_0".to_string(),
+                summary: Some(summary((100, 400), (50, 200))),
+            },
+            IndexEntry {
+                name: "s1_fuzzer".to_string(),
+                queue_entries: 80,
+                report_dir: "instance_1".to_string(),
+                summary: None,
+            },
+        ];
+        let totals = summary((150, 400), (75, 200));
+
+        let html = render_index(&entries, Some(&totals));
+        assert!(html.contains(
+            "<tr><td><a href=\"instance_0/index.html\">m_&lt;fuzzer&gt;</a></td><td>120</td>\
+             <td>100/400</td><td>25.00%</td><td>50/200</td><td>25.00%</td></tr>"
+        ));
+        assert!(html.contains("<td>80</td><td>-</td><td>-</td><td>-</td><td>-</td>"));
+        assert!(html
+            .contains("<td>All instances merged</td><td>200</td><td>150/400</td><td>37.50%</td>"));
+        assert!(
+            !html.contains("http"),
+            "the page must not load external assets"
+        );
+        assert!(!render_index(&entries, None).contains("All instances merged"));
+
+        let dir = TempDir::new().unwrap();
+        let path = write_index(dir.path(), &entries, Some(&totals)).unwrap();
+        write_index(dir.path(), &entries, Some(&totals)).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), html);
+    }
+}
@@ -0,0 +1,471 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::afl::harness::{Harness, HarnessBinary};
+use crate::utils::elf::{parse_ident, Reader};
+
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+const PT_INTERP: u32 = 3;
+const DT_NULL: u64 = 0;
+const DT_NEEDED: u64 = 1;
+const DT_STRTAB: u64 = 5;
+const DT_RPATH: u64 = 15;
+const DT_RUNPATH: u64 = 29;
+
+/// Maximum nesting of `include` directives followed in `/etc/ld.so.conf`
+const MAX_CONF_DEPTH: usize = 4;
+/// Directories relative to a binary that commonly hold the libraries shipped with it
+const SIBLING_LIB_DIRS: &[&str] = &[".", "lib", "libs", "../lib", "../libs"];
+
+/// Runtime dependencies declared in the program headers of an ELF binary
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElfDeps {
+    /// Program interpreter, e.g. `/lib64/ld-linux-x86-64.so.2`
+    pub interpreter: Option<PathBuf>,
+    /// `DT_NEEDED` entries
+    pub needed: Vec<String>,
+    /// `DT_RPATH` and `DT_RUNPATH` entries with `$ORIGIN` not yet expanded
+    pub run_paths: Vec<String>,
+}
+
+impl ElfDeps {
+    /// Reads the dependencies from the headers without executing the binary
+    ///
+    /// Returns `None` for files that are no ELF binaries, e.g. scripts
+    pub fn parse(elf: &[u8]) -> Option<Self> {
+        let (is_64, reader) = parse_ident(elf)?;
+        let (phoff, phentsize, phnum) = if is_64 {
+            (reader.u64(0x20)?, reader.u16(0x36)?, reader.u16(0x38)?)
+        } else {
+            (
+                u64::from(reader.u32(0x1c)?),
+                reader.u16(0x2a)?,
+                reader.u16(0x2c)?,
+            )
+        };
+        let phoff = usize::try_from(phoff).ok()?;
+        let segments: Vec<Segment> = (0..usize::from(phnum))
+            .filter_map(|idx| {
+                let header = phoff.checked_add(idx * usize::from(phentsize))?;
+                Segment::read(&reader, is_64, header)
+            })
+            .collect();
+
+        let mut deps = Self {
+            interpreter: segments
+                .iter()
+                .find(|s| s.kind == PT_INTERP)
+                .and_then(|s| s.bytes(elf))
+                .map(|bytes| PathBuf::from(c_str(bytes))),
+            ..Self::default()
+        };
+        let Some(dynamic) = segments
+            .iter()
+            .find(|s| s.kind == PT_DYNAMIC)
+            .and_then(|s| s.bytes(elf))
+        else {
+            return Some(deps);
+        };
+
+        let dynamic = Reader {
+            data: dynamic,
+            le: reader.le,
+        };
+        let entry_size = if is_64 { 16 } else { 8 };
+        let mut strtab = None;
+        let mut entries = Vec::new();
+        for pos in (0..dynamic.data.len() / entry_size).map(|idx| idx * entry_size) {
+            let (Some(tag), Some(value)) = (
+                dynamic.word(is_64, pos),
+                dynamic.word(is_64, pos + entry_size / 2),
+            ) else {
+                break;
+            };
+            match tag {
+                DT_NULL => break,
+                DT_STRTAB => strtab = Some(value),
+                DT_NEEDED | DT_RPATH | DT_RUNPATH => entries.push((tag, value)),
+                _ => {}
+            }
+        }
+
+        // DT_STRTAB holds a virtual address that is mapped by one of the loadable segments
+        let Some(strtab) = strtab.and_then(|addr| {
+            segments
+                .iter()
+                .filter(|s| s.kind == PT_LOAD)
+                .find_map(|s| s.file_offset(addr))
+        }) else {
+            return Some(deps);
+        };
+        for (tag, value) in entries {
+            let Some(string) = strtab
+                .checked_add(value)
+                .and_then(|offset| usize::try_from(offset).ok())
+                .and_then(|offset| elf.get(offset..))
+                .map(c_str)
+            else {
+                continue;
+            };
+            if tag == DT_NEEDED {
+                deps.needed.push(string);
+            } else {
+                deps.run_paths.extend(
+                    string
+                        .split(':')
+                        .filter(|path| !path.is_empty())
+                        .map(String::from),
+                );
+            }
+        }
+        Some(deps)
+    }
+}
+
+/// A program header
+struct Segment {
+    kind: u32,
+    offset: u64,
+    vaddr: u64,
+    filesz: u64,
+}
+
+impl Segment {
+    fn read(reader: &Reader<'_>, is_64: bool, header: usize) -> Option<Self> {
+        let field = |offset_64: usize, offset_32: usize| {
+            let offset = if is_64 { offset_64 } else { offset_32 };
+            reader.word(is_64, header.checked_add(offset)?)
+        };
+        Some(Self {
+            kind: reader.u32(header)?,
+            offset: field(0x08, 0x04)?,
+            vaddr: field(0x10, 0x08)?,
+            filesz: field(0x20, 0x10)?,
+        })
+    }
+
+    fn bytes<'a>(&self, elf: &'a [u8]) -> Option<&'a [u8]> {
+        let start = usize::try_from(self.offset).ok()?;
+        elf.get(start..start.checked_add(usize::try_from(self.filesz).ok()?)?)
+    }
+
+    fn file_offset(&self, addr: u64) -> Option<u64> {
+        (self.vaddr..self.vaddr.checked_add(self.filesz)?)
+            .contains(&addr)
+            .then(|| addr - self.vaddr + self.offset)
+    }
+}
+
+fn c_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Runtime dependencies of a binary the dynamic loader will not find
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepReport {
+    /// The program interpreter if it does not exist
+    pub missing_interpreter: Option<PathBuf>,
+    /// `DT_NEEDED` entries not found in any search path
+    pub unresolved: Vec<String>,
+    /// Directories next to the binary that contain unresolved libraries
+    pub lib_dirs: Vec<PathBuf>,
+}
+
+impl DepReport {
+    /// Resolves the dependencies of `binary` like the dynamic loader
+    ///
+    /// The run paths of the binary, `LD_LIBRARY_PATH` and `system_dirs` are searched. Files
+    /// that cannot be read or are no ELF binaries have nothing to report.
+    pub fn check(binary: &Path, system_dirs: &[PathBuf]) -> Self {
+        let Some(deps) = fs::read(binary)
+            .ok()
+            .and_then(|content| ElfDeps::parse(&content))
+        else {
+            return Self::default();
+        };
+        let origin = binary.parent().unwrap_or_else(|| Path::new("."));
+        let search_dirs: Vec<PathBuf> = deps
+            .run_paths
+            .iter()
+            .map(|path| {
+                PathBuf::from(
+                    path.replace("${ORIGIN}", &origin.to_string_lossy())
+                        .replace("$ORIGIN", &origin.to_string_lossy()),
+                )
+            })
+            .chain(
+                env::var_os("LD_LIBRARY_PATH")
+                    .map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+                    .unwrap_or_default(),
+            )
+            .chain(system_dirs.iter().cloned())
+            .collect();
+
+        let unresolved: Vec<String> = deps
+            .needed
+            .into_iter()
+            .filter(|lib| {
+                if lib.contains('/') {
+                    !Path::new(lib).exists()
+                } else {
+                    !search_dirs.iter().any(|dir| dir.join(lib).exists())
+                }
+            })
+            .collect();
+        let mut lib_dirs: Vec<PathBuf> = Vec::new();
+        for dir in SIBLING_LIB_DIRS.iter().map(|dir| origin.join(dir)) {
+            if unresolved.iter().any(|lib| dir.join(lib).is_file()) {
+                let dir = fs::canonicalize(&dir).unwrap_or(dir);
+                if !lib_dirs.contains(&dir) {
+                    lib_dirs.push(dir);
+                }
+            }
+        }
+
+        Self {
+            missing_interpreter: deps.interpreter.filter(|interp| !interp.exists()),
+            unresolved,
+            lib_dirs,
+        }
+    }
+
+    /// Human readable warnings about the `kind` binary at `binary`
+    pub fn warnings(&self, kind: HarnessBinary, binary: &Path) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(interp) = &self.missing_interpreter {
+            warnings.push(format!(
+                "The {kind} binary {} needs the program interpreter {}, which does not exist. \
+                 It will fail with 'No such file or directory'",
+                binary.display(),
+                interp.display()
+            ));
+        }
+        if !self.unresolved.is_empty() {
+            let mut warning = format!(
+                "The {kind} binary {} needs shared libraries the loader cannot find: {}",
+                binary.display(),
+                self.unresolved.join(", ")
+            );
+            if !self.lib_dirs.is_empty() {
+                let dirs: Vec<String> = self
+                    .lib_dirs
+                    .iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect();
+                warning.push_str(&format!(
+                    ". Found next to the binary, try 'export LD_LIBRARY_PATH={}'",
+                    dirs.join(":")
+                ));
+            }
+            warnings.push(warning);
+        }
+        warnings
+    }
+}
+
+/// Directories the dynamic loader searches by default, read from `/etc/ld.so.conf`
+pub fn system_lib_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    read_ld_so_conf(Path::new("/etc/ld.so.conf"), &mut dirs, 0);
+    dirs.extend(["/lib", "/usr/lib", "/lib64", "/usr/lib64"].map(PathBuf::from));
+    dirs
+}
+
+fn read_ld_so_conf(conf: &Path, dirs: &mut Vec<PathBuf>, depth: usize) {
+    let Ok(content) = fs::read_to_string(conf) else {
+        return;
+    };
+    let lines = content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty());
+    for line in lines {
+        if let Some(pattern) = line.strip_prefix("include ") {
+            if depth >= MAX_CONF_DEPTH {
+                continue;
+            }
+            let pattern = conf
+                .parent()
+                .unwrap_or_else(|| Path::new("/"))
+                .join(pattern.trim());
+            for nested in glob::glob(&pattern.to_string_lossy())
+                .into_iter()
+                .flatten()
+                .flatten()
+            {
+                read_ld_so_conf(&nested, dirs, depth + 1);
+            }
+        } else if !line.starts_with("hwcap ") {
+            dirs.push(PathBuf::from(line));
+        }
+    }
+}
+
+/// Checks the interpreter and shared libraries of every harness binary not in `skip`
+pub fn check_harness_deps(harness: &Harness, skip: &[HarnessBinary]) -> Vec<String> {
+    let system_dirs = system_lib_dirs();
+    harness
+        .binaries()
+        .into_iter()
+        .filter(|(kind, _)| !skip.contains(kind))
+        .flat_map(|(kind, binary)| DepReport::check(binary, &system_dirs).warnings(kind, binary))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Builds a minimal 64-bit little endian ELF file with an interpreter and a dynamic segment
+    fn fixture_elf(interp: &str, needed: &[&str], runpath: Option<&str>) -> Vec<u8> {
+        const PHOFF: usize = 0x40;
+        const PHENTSIZE: usize = 0x38;
+        let data_start = PHOFF + 3 * PHENTSIZE;
+
+        let mut strtab = vec![0u8];
+        let mut add_str = |s: &str| {
+            let offset = strtab.len() as u64;
+            strtab.extend_from_slice(s.as_bytes());
+            strtab.push(0);
+            offset
+        };
+        let mut dynamic: Vec<(u64, u64)> =
+            needed.iter().map(|lib| (DT_NEEDED, add_str(lib))).collect();
+        if let Some(runpath) = runpath {
+            dynamic.push((DT_RUNPATH, add_str(runpath)));
+        }
+        let interp = [interp.as_bytes(), b"\0"].concat();
+        let strtab_off = data_start + interp.len();
+        // Loaded at a different virtual address to exercise the address translation
+        let vaddr = 0x40_0000_u64;
+        dynamic.push((DT_STRTAB, vaddr + strtab_off as u64));
+        dynamic.push((DT_NULL, 0));
+        let dynamic_off = strtab_off + strtab.len();
+
+        let mut elf = vec![0u8; PHOFF];
+        elf[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        elf[0x20..0x28].copy_from_slice(&(PHOFF as u64).to_le_bytes());
+        elf[0x36..0x38].copy_from_slice(&(PHENTSIZE as u16).to_le_bytes());
+        elf[0x38..0x3a].copy_from_slice(&3u16.to_le_bytes());
+        let total = (dynamic_off + dynamic.len() * 16) as u64;
+        for (kind, offset, addr, size) in [
+            (PT_INTERP, data_start as u64, 0, interp.len() as u64),
+            (PT_LOAD, 0, vaddr, total),
+            (
+                PT_DYNAMIC,
+                dynamic_off as u64,
+                0,
+                (dynamic.len() * 16) as u64,
+            ),
+        ] {
+            let mut header = vec![0u8; PHENTSIZE];
+            header[..4].copy_from_slice(&kind.to_le_bytes());
+            header[0x08..0x10].copy_from_slice(&offset.to_le_bytes());
+            header[0x10..0x18].copy_from_slice(&addr.to_le_bytes());
+            header[0x20..0x28].copy_from_slice(&size.to_le_bytes());
+            elf.extend(header);
+        }
+        elf.extend(interp);
+        elf.extend(strtab);
+        for (tag, value) in dynamic {
+            elf.extend(tag.to_le_bytes());
+            elf.extend(value.to_le_bytes());
+        }
+        elf
+    }
+
+    #[test]
+    fn test_parse_elf_deps() {
+        let elf = fixture_elf(
+            "/lib/ld.so",
+            &["libc.so.6", "libfoo.so"],
+            Some("$ORIGIN/x:/y"),
+        );
+        let deps = ElfDeps::parse(&elf).unwrap();
+        assert_eq!(deps.interpreter, Some(PathBuf::from("/lib/ld.so")));
+        assert_eq!(deps.needed, ["libc.so.6", "libfoo.so"]);
+        assert_eq!(deps.run_paths, ["$ORIGIN/x", "/y"]);
+
+        assert!(ElfDeps::parse(b"#!/bin/sh\necho hi\n").is_none());
+        assert!(ElfDeps::parse(&elf[..0x50]).is_some_and(|deps| deps.needed.is_empty()));
+    }
+
+    #[test]
+    fn test_dep_report() {
+        let tmp = TempDir::new().unwrap();
+        let bin_dir = tmp.path().join("bin");
+        let system = tmp.path().join("system");
+        for dir in [
+            &bin_dir,
+            &system,
+            &tmp.path().join("lib"),
+            &bin_dir.join("rp"),
+        ] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let interp = system.join("ld.so");
+        for lib in [
+            &interp,
+            &system.join("libc.so.6"),
+            &bin_dir.join("rp/librp.so"),
+        ] {
+            fs::write(lib, "").unwrap();
+        }
+        fs::write(tmp.path().join("lib/libfoo.so"), "").unwrap();
+
+        let binary = bin_dir.join("target");
+        let libs = ["libc.so.6", "librp.so", "libfoo.so", "libgone.so"];
+        fs::write(
+            &binary,
+            fixture_elf(&interp.to_string_lossy(), &libs, Some("$ORIGIN/rp")),
+        )
+        .unwrap();
+        let report = DepReport::check(&binary, std::slice::from_ref(&system));
+        assert_eq!(report.missing_interpreter, None);
+        assert_eq!(report.unresolved, ["libfoo.so", "libgone.so"]);
+        assert_eq!(
+            report.lib_dirs,
+            [fs::canonicalize(tmp.path().join("lib")).unwrap()]
+        );
+        let warnings = report.warnings(HarnessBinary::Cmplog, &binary);
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].starts_with("The cmplog binary"),
+            "{}",
+            warnings[0]
+        );
+        assert!(warnings[0].contains("libfoo.so, libgone.so"));
+        assert!(warnings[0].contains("export LD_LIBRARY_PATH="));
+
+        fs::write(&binary, fixture_elf("/nonexistent/ld.so", &[], None)).unwrap();
+        let report = DepReport::check(&binary, &[system]);
+        assert_eq!(
+            report.missing_interpreter,
+            Some(PathBuf::from("/nonexistent/ld.so"))
+        );
+        assert!(report.warnings(HarnessBinary::Target, &binary)[0]
+            .contains("program interpreter /nonexistent/ld.so"));
+    }
+
+    #[test]
+    fn test_check_harness_deps_skips_binaries() {
+        let tmp = TempDir::new().unwrap();
+        let binary = tmp.path().join("target");
+        fs::write(&binary, fixture_elf("/nonexistent/ld.so", &[], None)).unwrap();
+        let harness = Harness::new(&binary, None)
+            .unwrap()
+            .with_cmplog(Some(&binary))
+            .unwrap();
+
+        assert_eq!(check_harness_deps(&harness, &[]).len(), 2);
+        assert_eq!(
+            check_harness_deps(&harness, &[HarnessBinary::Target]).len(),
+            1
+        );
+    }
+}
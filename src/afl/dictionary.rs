@@ -0,0 +1,171 @@
+use std::{fs, path::Path};
+
+/// Amount of malformed lines reported individually per dictionary
+const MAX_REPORTED_LINES: usize = 5;
+
+/// A line of an AFL++ dictionary that `afl-fuzz` will not accept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictIssue {
+    /// 1-based line number
+    pub line: usize,
+    /// Why the line does not parse
+    pub reason: String,
+}
+
+/// Checks the `token="..."` / `keyword@N="..."` syntax of a dictionary file
+///
+/// Directories are skipped as `afl-fuzz` reads their files as raw tokens.
+/// Returns a human readable warning per malformed line, summarized beyond a few lines.
+pub fn check_dictionary(path: &Path) -> Vec<String> {
+    if path.is_dir() {
+        return Vec::new();
+    }
+    let content = match fs::read(path) {
+        Ok(content) => content,
+        Err(e) => return vec![format!("Failed to read dictionary {}: {e}", path.display())],
+    };
+
+    let issues = validate_dictionary(&String::from_utf8_lossy(&content));
+    let mut warnings: Vec<String> = issues
+        .iter()
+        .take(MAX_REPORTED_LINES)
+        .map(|issue| {
+            format!(
+                "Dictionary {} line {}: {}",
+                path.display(),
+                issue.line,
+                issue.reason
+            )
+        })
+        .collect();
+    if issues.len() > MAX_REPORTED_LINES {
+        warnings.push(format!(
+            "Dictionary {} has {} more malformed lines",
+            path.display(),
+            issues.len() - MAX_REPORTED_LINES
+        ));
+    }
+    warnings
+}
+
+/// Returns every line of `content` that `afl-fuzz` would reject
+pub fn validate_dictionary(content: &str) -> Vec<DictIssue> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            check_line(line).err().map(|reason| DictIssue {
+                line: idx + 1,
+                reason,
+            })
+        })
+        .collect()
+}
+
+fn check_line(line: &str) -> Result<(), String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(());
+    }
+
+    let value = if line.starts_with('"') {
+        line
+    } else {
+        let (keyword, value) = line
+            .split_once('=')
+            .ok_or("expected 'keyword=\"value\"' or '\"value\"'")?;
+        check_keyword(keyword.trim_end())?;
+        value.trim_start()
+    };
+
+    let value = value
+        .strip_prefix('"')
+        .ok_or("value must start with '\"'")?
+        .strip_suffix('"')
+        .ok_or("value must end with '\"'")?;
+    if value.is_empty() {
+        return Err("empty value".to_string());
+    }
+    check_value(value)
+}
+
+fn check_keyword(keyword: &str) -> Result<(), String> {
+    let (name, level) = match keyword.split_once('@') {
+        Some((name, level)) => (name, Some(level)),
+        None => (keyword, None),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("invalid keyword '{keyword}'"));
+    }
+    if level.is_some_and(|level| level.is_empty() || !level.chars().all(|c| c.is_ascii_digit())) {
+        return Err(format!(
+            "invalid level in '{keyword}', expected 'keyword@N'"
+        ));
+    }
+    Ok(())
+}
+
+fn check_value(value: &str) -> Result<(), String> {
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\\' | '"') => {}
+                Some('x') => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return Err("'\\x' must be followed by two hex digits".to_string());
+                    }
+                }
+                _ => return Err("invalid escape sequence".to_string()),
+            },
+            '"' => return Err("unescaped '\"' in value".to_string()),
+            c if !(' '..='~').contains(&c) => {
+                return Err("non-printable character in value, use '\\xNN'".to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_dictionary() {
+        let dict = r#"# comment
+
+"plain"
+kw="value"
+kw_2@3 = "esc\"aped\\ \x41"
+missing_quotes=value
+bad-name="x"
+kw@="x"
+"unterminated
+"in"ner"
+"\q"
+"\x4"
+"""#;
+        let lines: Vec<usize> = validate_dictionary(dict).iter().map(|i| i.line).collect();
+        assert_eq!(lines, [6, 7, 8, 9, 10, 11, 12, 13]);
+        assert!(validate_dictionary("kw=\"tab\there\"")[0]
+            .reason
+            .contains("non-printable"));
+    }
+
+    #[test]
+    fn test_check_dictionary() {
+        let tmp = TempDir::new().unwrap();
+        assert!(check_dictionary(tmp.path()).is_empty());
+
+        let path = tmp.path().join("bad.dict");
+        fs::write(&path, "broken\n".repeat(MAX_REPORTED_LINES + 2)).unwrap();
+        let warnings = check_dictionary(&path);
+        assert_eq!(warnings.len(), MAX_REPORTED_LINES + 1);
+        assert!(warnings[0].ends_with("line 1: expected 'keyword=\"value\"' or '\"value\"'"));
+        assert!(warnings[MAX_REPORTED_LINES].contains("2 more malformed lines"));
+    }
+}
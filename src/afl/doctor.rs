@@ -0,0 +1,333 @@
+use anyhow::{bail, Result};
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::afl::coverage::CoverageCollector;
+use crate::utils::system::{binary_kind, find_binary_in_path, BinaryKind};
+
+/// Where the kernel reads the core dump handler from
+pub const CORE_PATTERN: &str = "/proc/sys/kernel/core_pattern";
+/// Command restoring the core pattern AFL++ expects
+const CORE_PATTERN_FIX: &str = "echo core | sudo tee /proc/sys/kernel/core_pattern";
+/// Directory with one `cpu<N>` entry per core exposing its frequency governor
+pub const CPU_SYSFS: &str = "/sys/devices/system/cpu";
+
+/// Outcome of a single health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    /// Fuzzing works, but slower or with a feature missing
+    Warn,
+    /// AFL++ refuses to start or the campaign cannot be launched
+    Fail,
+}
+
+/// A health check result along with how to fix it
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        name: &'static str,
+        status: CheckStatus,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.status {
+            CheckStatus::Ok => "[+]",
+            CheckStatus::Warn => "[!]",
+            CheckStatus::Fail => "[-]",
+        };
+        write!(f, "{prefix} {}: {}", self.name, self.detail)?;
+        if let Some(fix) = &self.fix {
+            write!(f, "\n    Fix: {fix}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every check against the local machine
+pub fn run_checks() -> Vec<Check> {
+    let mut checks = vec![
+        check_afl_fuzz(),
+        check_core_pattern(fs::read_to_string(CORE_PATTERN).ok().as_deref()),
+        check_cpu_governors(&read_cpu_governors(Path::new(CPU_SYSFS))),
+        check_session_managers(tool_available("tmux", "-V"), tool_available("screen", "-v")),
+    ];
+    checks.extend(CoverageCollector::TOOLS.iter().map(|&tool| {
+        match CoverageCollector::are_reqs_met(&[tool]) {
+            Ok(()) => Check::ok("coverage", format!("{tool} found")),
+            Err(_) => Check::problem(
+                "coverage",
+                CheckStatus::Warn,
+                format!("{tool} is missing, `cov` will not work"),
+                if tool.starts_with("llvm-") {
+                    format!("Install the LLVM tools matching the clang of the coverage build and add {tool} to PATH")
+                } else {
+                    "Install lcov, it ships genhtml as well".to_string()
+                },
+            ),
+        }
+    }));
+    checks
+}
+
+fn check_afl_fuzz() -> Check {
+    let Ok(afl_fuzz) = find_binary_in_path(None::<PathBuf>) else {
+        return Check::problem(
+            "afl-fuzz",
+            CheckStatus::Fail,
+            "not found in AFL_PATH or PATH",
+            "Install AFL++ or point AFL_PATH to the afl-fuzz binary",
+        );
+    };
+    // afl-fuzz prints its banner for `-h` and exits non-zero, so only the output matters
+    let version = Command::new(&afl_fuzz)
+        .arg("-h")
+        .output()
+        .ok()
+        .and_then(|output| {
+            parse_afl_version(&String::from_utf8_lossy(
+                &[output.stdout, output.stderr].concat(),
+            ))
+        });
+    let found = format!(
+        "{} ({})",
+        afl_fuzz.display(),
+        version.as_deref().unwrap_or("unknown version")
+    );
+    match binary_kind(&afl_fuzz) {
+        Ok((resolved, BinaryKind::Script { interpreter })) => Check::problem(
+            "afl-fuzz",
+            CheckStatus::Warn,
+            format!(
+                "{found} is a wrapper script ({}, run by '{interpreter}')",
+                resolved.display()
+            ),
+            "Point AFL_PATH or --afl-binary to the real afl-fuzz, CMPLOG and QEMU mode assume it",
+        ),
+        _ => Check::ok("afl-fuzz", found),
+    }
+}
+
+/// The AFL++ version from the `afl-fuzz++4.21c based on afl by ...` banner
+fn parse_afl_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("afl-fuzz++"))
+        .map(|version| format!("++{version}"))
+}
+
+/// AFL++ refuses to start if crashes are piped to a handler like apport or systemd-coredump
+fn check_core_pattern(pattern: Option<&str>) -> Check {
+    match pattern.map(str::trim) {
+        None => Check::problem(
+            "core_pattern",
+            CheckStatus::Warn,
+            format!("{CORE_PATTERN} is not readable"),
+            CORE_PATTERN_FIX,
+        ),
+        Some(pattern) if pattern.starts_with('|') => Check::problem(
+            "core_pattern",
+            CheckStatus::Fail,
+            format!("core dumps are piped to '{pattern}', afl-fuzz aborts on startup"),
+            format!("{CORE_PATTERN_FIX} (or export AFL_I_DONT_CARE_ABOUT_MISSING_CRASHES=1 and miss crashes)"),
+        ),
+        Some(pattern) => Check::ok("core_pattern", pattern.to_string()),
+    }
+}
+
+/// Makes sure crashes produce plain core files as AFL++ requires before a session is launched
+///
+/// Otherwise every instance aborts right after starting. With `fix` a piped core pattern is
+/// replaced by `core`, which needs root. Exporting `AFL_I_DONT_CARE_ABOUT_MISSING_CRASHES`
+/// skips the check just like it makes AFL++ skip its own.
+///
+/// # Errors
+/// * If the core pattern pipes crashes to a handler and is not or cannot be fixed
+pub fn ensure_core_pattern(fix: bool) -> Result<()> {
+    if env::var_os("AFL_I_DONT_CARE_ABOUT_MISSING_CRASHES").is_some() {
+        return Ok(());
+    }
+    ensure_core_pattern_at(Path::new(CORE_PATTERN), fix)
+}
+
+fn ensure_core_pattern_at(path: &Path, fix: bool) -> Result<()> {
+    let check = check_core_pattern(fs::read_to_string(path).ok().as_deref());
+    if check.status != CheckStatus::Fail {
+        return Ok(());
+    }
+    if !fix {
+        bail!(
+            "{}. Fix it with `{CORE_PATTERN_FIX}` or rerun with --fix-env as root",
+            check.detail
+        );
+    }
+    match fs::write(path, "core") {
+        Ok(()) => {
+            println!("[+] Set {} to 'core'", path.display());
+            Ok(())
+        }
+        Err(e) => bail!(
+            "Failed to set {} to 'core' ({e}). Rerun as root or fix it with `{CORE_PATTERN_FIX}`",
+            path.display()
+        ),
+    }
+}
+
+/// Frequency governors of all cores, empty if the kernel does not expose them (e.g. in VMs)
+fn read_cpu_governors(cpu_sysfs: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(cpu_sysfs) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("cpu"))
+                .is_some_and(|idx| !idx.is_empty() && idx.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|entry| {
+            fs::read_to_string(entry.path().join("cpufreq/scaling_governor"))
+                .ok()
+                .map(|governor| governor.trim().to_string())
+        })
+        .collect()
+}
+
+/// AFL++ warns about, and without `AFL_SKIP_CPUFREQ` aborts on, cores that scale down
+fn check_cpu_governors(governors: &[String]) -> Check {
+    if governors.is_empty() {
+        return Check::ok("cpu governor", "frequency scaling is not exposed");
+    }
+    let slow = governors.iter().filter(|g| *g != "performance").count();
+    if slow == 0 {
+        return Check::ok(
+            "cpu governor",
+            format!("performance on all {} cores", governors.len()),
+        );
+    }
+    Check::problem(
+        "cpu governor",
+        CheckStatus::Warn,
+        format!(
+            "{slow} of {} cores do not use the performance governor",
+            governors.len()
+        ),
+        "echo performance | sudo tee /sys/devices/system/cpu/cpu*/cpufreq/scaling_governor (or export AFL_SKIP_CPUFREQ=1)",
+    )
+}
+
+fn check_session_managers(tmux: bool, screen: bool) -> Check {
+    match (tmux, screen) {
+        (false, false) => Check::problem(
+            "session manager",
+            CheckStatus::Fail,
+            "neither tmux nor screen found, `run` cannot launch a session",
+            "Install tmux (default) or screen and pass --session-runner screen",
+        ),
+        (true, true) => Check::ok("session manager", "tmux and screen found"),
+        (true, false) => Check::ok("session manager", "tmux found"),
+        (false, true) => Check::problem(
+            "session manager",
+            CheckStatus::Warn,
+            "only screen found",
+            "Pass --session-runner screen (or set [session] runner = \"screen\") or install tmux",
+        ),
+    }
+}
+
+fn tool_available(tool: &str, version_flag: &str) -> bool {
+    Command::new(tool)
+        .arg(version_flag)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_afl_version() {
+        let banner =
+            "afl-fuzz++4.21c based on afl by Michal Zalewski and a large online community\n";
+        assert_eq!(parse_afl_version(banner).as_deref(), Some("++4.21c"));
+        assert_eq!(parse_afl_version("usage: afl-fuzz"), None);
+    }
+
+    #[test]
+    fn test_core_pattern() {
+        assert_eq!(check_core_pattern(Some("core\n")).status, CheckStatus::Ok);
+        let piped = check_core_pattern(Some("|/usr/share/apport/apport %p"));
+        assert_eq!(piped.status, CheckStatus::Fail);
+        assert!(piped.to_string().contains("Fix: echo core"));
+        assert_eq!(check_core_pattern(None).status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn test_ensure_core_pattern() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("core_pattern");
+        fs::write(&path, "|/usr/lib/systemd/systemd-coredump %P\n").unwrap();
+
+        let err = ensure_core_pattern_at(&path, false).unwrap_err();
+        assert!(err.to_string().contains("--fix-env"));
+        ensure_core_pattern_at(&path, true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "core");
+        ensure_core_pattern_at(&path, false).unwrap();
+    }
+
+    #[test]
+    fn test_cpu_governors() {
+        let tmp = TempDir::new().unwrap();
+        for (cpu, governor) in [("cpu0", "performance"), ("cpu1", "powersave")] {
+            let dir = tmp.path().join(cpu).join("cpufreq");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("scaling_governor"), format!("{governor}\n")).unwrap();
+        }
+        fs::create_dir_all(tmp.path().join("cpufreq")).unwrap();
+
+        let governors = read_cpu_governors(tmp.path());
+        assert_eq!(governors.len(), 2);
+        let check = check_cpu_governors(&governors);
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.starts_with("1 of 2 cores"));
+        assert_eq!(check_cpu_governors(&[]).status, CheckStatus::Ok);
+        assert_eq!(
+            check_session_managers(false, false).status,
+            CheckStatus::Fail
+        );
+    }
+}
@@ -81,6 +81,21 @@ impl FromStr for AFLFlag {
     }
 }
 
+/// Variables AFLR sets outside of [`AFLFlag`]
+const GENERATED_VARS: &[&str] = &[
+    "AFL_TMPDIR",
+    "AFL_TESTCACHE_SIZE",
+    "AFL_MAP_SIZE",
+    "AFL_CUSTOM_MUTATOR_LIBRARY",
+];
+
+/// Returns whether AFLR may set `key` itself when generating commands
+///
+/// Where it does, the generated value takes precedence over an exported variable of the same name.
+pub fn is_generated_env_var(key: &str) -> bool {
+    AFLFlag::from_str(key).is_ok() || GENERATED_VARS.contains(&key)
+}
+
 #[derive(Debug, Clone)]
 pub struct AFLEnv {
     flags: HashSet<AFLFlag>,
@@ -153,6 +168,20 @@ impl AFLEnv {
         envs
     }
 
+    /// Creates a new vector of `AFLEnv` instances without any randomized or host dependent settings
+    ///
+    /// Only `AFL_FINAL_SYNC` for the first configuration (-M) and the `RAMDisk` are applied.
+    pub fn new_deterministic(runners: u32, ramdisk: Option<&String>) -> Vec<Self> {
+        let mut envs = vec![Self::default(); runners as usize];
+        for env in &mut envs {
+            env.ramdisk = ramdisk.cloned();
+        }
+        if let Some(first) = envs.first_mut() {
+            first.enable_flag(AFLFlag::FinalSync);
+        }
+        envs
+    }
+
     /// Enables the specified AFL++ flag
     #[inline]
     pub fn enable_flag(&mut self, flag: AFLFlag) -> &mut Self {
@@ -273,6 +302,14 @@ mod tests {
         assert_eq!(AFLFlag::ImportFirst.to_string(), "AFL_IMPORT_FIRST");
     }
 
+    #[test]
+    fn test_is_generated_env_var() {
+        assert!(is_generated_env_var("AFL_DISABLE_TRIM"));
+        assert!(is_generated_env_var("AFL_TMPDIR"));
+        assert!(is_generated_env_var("AFL_MAP_SIZE"));
+        assert!(!is_generated_env_var("AFL_SKIP_CPUFREQ"));
+    }
+
     #[test]
     fn test_default_env() {
         let env = AFLEnv::default();
@@ -381,6 +418,19 @@ mod tests {
             .any(|env| env.flags.contains(&AFLFlag::ImportFirst)));
     }
 
+    #[test]
+    fn test_new_deterministic() {
+        let envs = AFLEnv::new_deterministic(4, Some(&"/ramdisk".to_string()));
+        assert_eq!(envs.len(), 4);
+        assert_eq!(envs[0].flags.len(), 1);
+        assert!(envs[0].flags.contains(&AFLFlag::FinalSync));
+        assert!(envs.iter().skip(1).all(|env| env.flags.is_empty()));
+        assert!(envs.iter().all(|env| env.testcache_size == 50));
+        assert!(envs
+            .iter()
+            .all(|env| env.ramdisk.as_deref() == Some("/ramdisk")));
+    }
+
     #[test]
     fn test_apply_flags() {
         let mut rng = get_test_rng();
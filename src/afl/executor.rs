@@ -0,0 +1,431 @@
+use anyhow::{Context, Result};
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use tempfile::TempDir;
+
+use crate::afl::coverage::InputMode;
+use crate::afl::target_env::TargetEnv;
+
+/// Resource limits applied to every sandboxed target execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// CPU time in seconds
+    pub cpu_secs: u64,
+    /// Maximum size of written files in blocks as interpreted by `ulimit -f`
+    pub fsize_blocks: u64,
+    /// Maximum number of open file descriptors
+    pub nofile: u64,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            cpu_secs: 10,
+            fsize_blocks: 131_072,
+            nofile: 64,
+        }
+    }
+}
+
+/// Isolation mechanism wrapping the target execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Isolation {
+    /// Run inside `bwrap` with a read-only root and no network
+    Bwrap(PathBuf),
+    /// Run inside `firejail` with a read-only root and no network
+    Firejail(PathBuf),
+    /// Run inside a new user and network namespace via `unshare`
+    Unshare,
+    /// Only resource limits and a throwaway working directory
+    None,
+}
+
+impl Isolation {
+    /// Strongest isolation available on this system
+    ///
+    /// Falls back to weaker isolation with a warning if privileges are insufficient.
+    pub fn detect() -> Self {
+        let isolation = if let Some(bwrap) = find_in_path("bwrap") {
+            Self::Bwrap(bwrap)
+        } else if let Some(firejail) = find_in_path("firejail") {
+            Self::Firejail(firejail)
+        } else if Self::can_unshare() {
+            Self::Unshare
+        } else {
+            println!("[!] Warning: Neither bwrap, firejail nor unprivileged namespaces are available. Sandbox only applies resource limits and a temporary working directory");
+            Self::None
+        };
+        println!("[+] Sandboxing coverage runs with: {isolation:?}");
+        isolation
+    }
+
+    /// Checks whether unprivileged user and network namespaces can be created
+    fn can_unshare() -> bool {
+        Command::new("unshare")
+            .args(["-rn", "true"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+}
+
+/// Restricted environment for executing untrusted inputs against the target
+#[derive(Debug)]
+pub struct Sandbox {
+    isolation: Isolation,
+    limits: ResourceLimits,
+    work_dir: TempDir,
+}
+
+impl Sandbox {
+    /// Creates a sandbox with the given isolation and resource limits
+    ///
+    /// # Errors
+    /// * If the throwaway working directory cannot be created
+    pub fn new(isolation: Isolation, limits: ResourceLimits) -> Result<Self> {
+        let work_dir = TempDir::new().context("Failed to create sandbox working directory")?;
+        Ok(Self {
+            isolation,
+            limits,
+            work_dir,
+        })
+    }
+
+    /// Wraps `program` with `args` into the sandbox, `writable` paths stay writable
+    fn wrap(&self, program: &Path, args: &[String], writable: &[&Path]) -> Command {
+        let work_dir = self.work_dir.path();
+        let limits = format!(
+            "ulimit -t {}; ulimit -f {}; ulimit -n {}; exec \"$@\"",
+            self.limits.cpu_secs, self.limits.fsize_blocks, self.limits.nofile
+        );
+        let shell = ["sh", "-c", limits.as_str(), "sh"].map(String::from);
+
+        let mut cmd = match &self.isolation {
+            Isolation::Bwrap(bwrap) => {
+                let mut cmd = Command::new(bwrap);
+                cmd.args(["--die-with-parent", "--unshare-net", "--ro-bind", "/", "/"])
+                    .args(["--dev", "/dev", "--proc", "/proc"]);
+                for path in std::iter::once(work_dir).chain(writable.iter().copied()) {
+                    cmd.arg("--bind").arg(path).arg(path);
+                }
+                cmd.arg("--chdir").arg(work_dir).arg("--").args(&shell);
+                cmd
+            }
+            Isolation::Firejail(firejail) => {
+                let mut cmd = Command::new(firejail);
+                cmd.args(["--quiet", "--noprofile", "--net=none", "--read-only=/"]);
+                for path in std::iter::once(work_dir).chain(writable.iter().copied()) {
+                    cmd.arg(format!("--read-write={}", path.display()));
+                }
+                cmd.arg("--").args(&shell);
+                cmd
+            }
+            Isolation::Unshare => {
+                let mut cmd = Command::new("unshare");
+                cmd.args(["-rn", "--"]).args(&shell);
+                cmd
+            }
+            Isolation::None => {
+                let mut cmd = Command::new(&shell[0]);
+                cmd.args(&shell[1..]);
+                cmd
+            }
+        };
+        cmd.arg(program).args(args).current_dir(work_dir);
+        cmd
+    }
+}
+
+/// Executes single inputs against the coverage target
+#[derive(Debug)]
+pub struct InputExecutor {
+    target: PathBuf,
+    target_args: Vec<String>,
+    input_mode: InputMode,
+    target_env: TargetEnv,
+    sandbox: Option<Sandbox>,
+}
+
+impl InputExecutor {
+    pub fn new(target: PathBuf, target_args: Vec<String>, input_mode: InputMode) -> Self {
+        Self {
+            target,
+            target_args,
+            input_mode,
+            target_env: TargetEnv::default(),
+            sandbox: None,
+        }
+    }
+
+    /// Sets the environment of the target, `@@` in the values is replaced with each input
+    pub fn with_target_env(mut self, target_env: TargetEnv) -> Self {
+        self.target_env = target_env;
+        self
+    }
+
+    /// Runs every execution inside the provided sandbox
+    pub fn with_sandbox(mut self, sandbox: Option<Sandbox>) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Builds the command executing the target with `input_path` writing coverage to `output_path`
+    fn build_command(&self, input_path: &Path, output_path: &Path) -> Command {
+        // The sandbox runs the target from its throwaway directory, relative paths would miss
+        let (input_path, output_path) = if self.sandbox.is_some() {
+            (absolute(input_path), absolute(output_path))
+        } else {
+            (input_path.to_path_buf(), output_path.to_path_buf())
+        };
+        // @@ is never handed to the target literally, file inputs are appended instead
+        let mut args: Vec<String> = self
+            .target_args
+            .iter()
+            .filter(|&arg| arg != "@@")
+            .cloned()
+            .collect();
+        if self.input_mode == InputMode::File {
+            args.push(input_path.to_string_lossy().into_owned());
+        }
+
+        let mut cmd = match &self.sandbox {
+            Some(sandbox) => {
+                // A bare name is looked up in PATH just like without the sandbox
+                let target = if self.target.components().count() > 1 {
+                    absolute(&self.target)
+                } else {
+                    self.target.clone()
+                };
+                let profile_dir = output_path.parent().unwrap_or_else(|| Path::new("/"));
+                sandbox.wrap(&target, &args, &[profile_dir])
+            }
+            None => {
+                let mut cmd = Command::new(&self.target);
+                cmd.args(&args);
+                cmd
+            }
+        };
+        let input = input_path.to_string_lossy();
+        cmd.envs(self.target_env.resolve(&input))
+            .env("LLVM_PROFILE_FILE", &output_path)
+            .stderr(Stdio::null())
+            .stdout(Stdio::null());
+        cmd
+    }
+
+    /// Executes the target with a single input
+    ///
+    /// # Errors
+    /// * If the input cannot be read or the target cannot be spawned
+    pub fn run(&self, input_path: &Path, output_path: &Path) -> Result<()> {
+        let mut cmd = self.build_command(input_path, output_path);
+
+        if self.input_mode != InputMode::Stdin {
+            cmd.stdin(Stdio::null()).status().with_context(|| {
+                format!(
+                    "Failed to execute file-based target with input: {}",
+                    input_path.display()
+                )
+            })?;
+            return Ok(());
+        }
+
+        let input_content = fs::read(input_path)
+            .with_context(|| format!("Failed to read input file: {}", input_path.display()))?;
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .spawn()
+            .with_context(|| "Failed to spawn stdin-based target")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(&input_content)
+                .with_context(|| "Failed to write to target's stdin")?;
+            // Stdin will be closed when dropped
+        }
+
+        let status = child
+            .wait()
+            .with_context(|| "Failed to wait for target completion")?;
+
+        if !status.success() {
+            // This is expected for some inputs during fuzzing, so we just return Ok
+            println!(
+                "Note: Target exited with non-zero status for input: {}",
+                input_path.display()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `path` resolved against the current directory, unchanged if that fails
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Finds an executable named `name` in `PATH`
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    env::var_os("PATH").and_then(|paths| {
+        env::split_paths(&paths)
+            .map(|dir| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args_of(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn executor(mode: InputMode, isolation: Option<Isolation>) -> InputExecutor {
+        InputExecutor::new(
+            PathBuf::from("/bin/target"),
+            vec!["-v".to_string(), "@@".to_string()],
+            mode,
+        )
+        .with_sandbox(isolation.map(|i| Sandbox::new(i, ResourceLimits::default()).unwrap()))
+    }
+
+    #[test]
+    fn test_unsandboxed_command() {
+        let exec = executor(InputMode::File, None);
+        let cmd = exec.build_command(Path::new("/q/id:1"), Path::new("/tmp/p/cov.profraw"));
+        assert_eq!(cmd.get_program(), "/bin/target");
+        assert_eq!(args_of(&cmd), vec!["-v", "/q/id:1"]);
+
+        let exec = executor(InputMode::Stdin, None);
+        let cmd = exec.build_command(Path::new("/q/id:1"), Path::new("/tmp/p/cov.profraw"));
+        assert_eq!(args_of(&cmd), vec!["-v"]);
+    }
+
+    #[test]
+    fn test_bwrap_command() {
+        let exec = executor(
+            InputMode::File,
+            Some(Isolation::Bwrap("/usr/bin/bwrap".into())),
+        );
+        let cmd = exec.build_command(Path::new("/q/id:1"), Path::new("/tmp/p/cov.profraw"));
+        let args = args_of(&cmd);
+        let work_dir = exec.sandbox.as_ref().unwrap().work_dir.path();
+
+        assert_eq!(cmd.get_program(), "/usr/bin/bwrap");
+        assert_eq!(cmd.get_current_dir(), Some(work_dir));
+        assert!(args.starts_with(&["--die-with-parent".to_string(), "--unshare-net".to_string()]));
+        assert!(args.windows(3).any(|w| w == ["--bind", "/tmp/p", "/tmp/p"]));
+        let shell = args.iter().position(|a| a == "sh").unwrap();
+        assert!(args[shell + 2].contains("ulimit -t 10; ulimit -f 131072; ulimit -n 64"));
+        assert_eq!(&args[shell + 3..], ["sh", "/bin/target", "-v", "/q/id:1"]);
+    }
+
+    #[test]
+    fn test_firejail_and_unshare_commands() {
+        let exec = executor(
+            InputMode::Stdin,
+            Some(Isolation::Firejail("/usr/bin/firejail".into())),
+        );
+        let cmd = exec.build_command(Path::new("/q/id:1"), Path::new("/tmp/p/cov.profraw"));
+        let args = args_of(&cmd);
+        assert!(args.contains(&"--net=none".to_string()));
+        assert!(args.contains(&"--read-write=/tmp/p".to_string()));
+        assert_eq!(args.last().unwrap(), "-v");
+
+        let exec = executor(InputMode::File, Some(Isolation::Unshare));
+        let cmd = exec.build_command(Path::new("/q/id:1"), Path::new("/tmp/p/cov.profraw"));
+        assert_eq!(cmd.get_program(), "unshare");
+        assert_eq!(&args_of(&cmd)[..3], ["-rn", "--", "sh"]);
+    }
+
+    #[test]
+    fn test_env_driven_run() {
+        let tmp = TempDir::new().unwrap();
+        let input = tmp.path().join("id:000001");
+        fs::write(&input, "data").unwrap();
+        let out = tmp.path().join("out");
+
+        // The harness fixture only learns about the input through $INPUT_FILE
+        let exec = InputExecutor::new(
+            PathBuf::from("/bin/sh"),
+            vec![
+                "-c".to_string(),
+                format!(
+                    "cat \"$INPUT_FILE\" > {}; echo \"$@\" >> {0}",
+                    out.display()
+                ),
+            ],
+            InputMode::Env,
+        )
+        .with_target_env(
+            TargetEnv::from_vars(&["INPUT_FILE=@@".into(), "LOG_LEVEL=0".into()]).unwrap(),
+        );
+        let cmd = exec.build_command(&input, Path::new("/tmp/p/cov.profraw"));
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert!(envs.contains(&("INPUT_FILE".as_ref(), Some(input.as_os_str()))));
+        assert!(envs.contains(&("LOG_LEVEL".as_ref(), Some("0".as_ref()))));
+
+        exec.run(&input, &tmp.path().join("cov.profraw")).unwrap();
+        // Neither piped to stdin nor appended to the arguments
+        assert_eq!(fs::read_to_string(&out).unwrap(), "data\n");
+    }
+
+    #[test]
+    fn test_sandboxed_run() {
+        let tmp = TempDir::new().unwrap();
+        let input = tmp.path().join("input");
+        fs::write(&input, "data").unwrap();
+
+        // Writes into the CWD must end up in the throwaway working directory
+        let exec = InputExecutor::new(
+            PathBuf::from("/bin/sh"),
+            vec!["-c".to_string(), "cat > out".to_string()],
+            InputMode::Stdin,
+        )
+        .with_sandbox(Some(
+            Sandbox::new(Isolation::None, ResourceLimits::default()).unwrap(),
+        ));
+        exec.run(&input, &tmp.path().join("cov.profraw")).unwrap();
+
+        let work_dir = exec.sandbox.as_ref().unwrap().work_dir.path();
+        assert_eq!(fs::read_to_string(work_dir.join("out")).unwrap(), "data");
+        assert!(!tmp.path().join("out").exists());
+    }
+
+    #[test]
+    fn test_sandboxed_run_relative_paths() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Relative to the current directory like `aflr cov -t ./target -i out/`
+        let tmp = TempDir::new_in(".").unwrap();
+        let dir = Path::new(tmp.path().file_name().unwrap());
+        let target = dir.join("target");
+        fs::write(&target, "#!/bin/sh\ncp \"$1\" \"$0.out\"\n").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o755)).unwrap();
+        let input = dir.join("id:000000");
+        fs::write(&input, "data").unwrap();
+
+        let exec = InputExecutor::new(target.clone(), vec!["@@".to_string()], InputMode::File)
+            .with_sandbox(Some(
+                Sandbox::new(Isolation::None, ResourceLimits::default()).unwrap(),
+            ));
+        let profile = dir.join("cov.profraw");
+        let cmd = exec.build_command(&input, &profile);
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert!(envs.contains(&(
+            "LLVM_PROFILE_FILE".as_ref(),
+            Some(absolute(&profile).as_os_str())
+        )));
+
+        exec.run(&input, &profile).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("target.out")).unwrap(), "data");
+    }
+}
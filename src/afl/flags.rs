@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+use std::fmt;
+
+/// Whether an `afl-fuzz` flag is followed by a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Switch,
+    Value,
+}
+
+/// The `afl-fuzz` flags as of AFL++ 4.x and their arity
+///
+/// Everything that parses or emits raw AFL++ flags should look them up here.
+pub const AFL_FLAGS: &[(&str, Arity)] = &[
+    // Required
+    ("-i", Arity::Value),
+    ("-o", Arity::Value),
+    // Execution control
+    ("-P", Arity::Value),
+    ("-p", Arity::Value),
+    ("-f", Arity::Value),
+    ("-t", Arity::Value),
+    ("-m", Arity::Value),
+    ("-O", Arity::Switch),
+    ("-Q", Arity::Switch),
+    ("-U", Arity::Switch),
+    ("-W", Arity::Switch),
+    ("-X", Arity::Switch),
+    ("-Y", Arity::Switch),
+    ("-g", Arity::Value),
+    ("-G", Arity::Value),
+    // Mutators
+    ("-a", Arity::Value),
+    ("-c", Arity::Value),
+    ("-l", Arity::Value),
+    ("-L", Arity::Value),
+    ("-D", Arity::Switch),
+    ("-d", Arity::Switch),
+    ("-z", Arity::Switch),
+    ("-x", Arity::Value),
+    ("-Z", Arity::Switch),
+    // Testing and limits
+    ("-s", Arity::Value),
+    ("-V", Arity::Value),
+    ("-E", Arity::Value),
+    ("-C", Arity::Switch),
+    ("-n", Arity::Switch),
+    ("-N", Arity::Switch),
+    // Other
+    ("-M", Arity::Value),
+    ("-S", Arity::Value),
+    ("-F", Arity::Value),
+    ("-b", Arity::Value),
+    ("-e", Arity::Value),
+    ("-T", Arity::Value),
+    ("-I", Arity::Value),
+];
+
+/// Flags `afl-fuzz` accepts more than once
+const REPEATABLE: [&str; 2] = ["-x", "-F"];
+
+/// Arity of a known `afl-fuzz` flag
+pub fn arity(flag: &str) -> Option<Arity> {
+    AFL_FLAGS
+        .iter()
+        .find(|(known, _)| *known == flag)
+        .map(|&(_, arity)| arity)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem with a single token of a raw flag string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagIssue {
+    pub severity: Severity,
+    /// Config key the flags were read from, e.g. `afl_flags`
+    pub key: &'static str,
+    pub message: String,
+    flags: String,
+    /// Byte offset and length of the offending token in `flags`
+    span: (usize, usize),
+}
+
+impl fmt::Display for FlagIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (start, len) = self.span;
+        let indent = self.flags[..start].chars().count();
+        let width = self.flags[start..start + len].chars().count().max(1);
+        write!(
+            f,
+            "{}: {}\n    {}\n    {}{}",
+            self.key,
+            self.message,
+            self.flags,
+            " ".repeat(indent),
+            "^".repeat(width)
+        )
+    }
+}
+
+/// Splits `flags` at whitespace like the command generation does, keeping the byte offsets
+fn tokenize(flags: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (idx, c) in flags.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                tokens.push((s, &flags[s..idx]));
+                start = None;
+            }
+            (false, None) => start = Some(idx),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, &flags[s..]));
+    }
+    tokens
+}
+
+/// Whether `token` looks like a flag rather than a value, e.g. `-p` but not `-1`
+pub fn is_flag(token: &str) -> bool {
+    token.len() > 1
+        && token.starts_with('-')
+        && !token[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Checks the raw `flags` of config `key` before they end up in every generated command
+///
+/// Flags in `managed` are set by aflr itself and must not be repeated. Values missing after
+/// a flag, stray values and managed flags are errors, repeated flags are warnings. Unknown
+/// flags are passed through with a warning so newer AFL++ releases keep working, unless
+/// `strict` is set.
+pub fn validate(key: &'static str, flags: &str, managed: &[&str], strict: bool) -> Vec<FlagIssue> {
+    let issue = |severity, message: String, (start, token): (usize, &str)| FlagIssue {
+        severity,
+        key,
+        message,
+        flags: flags.to_string(),
+        span: (start, token.len()),
+    };
+
+    let tokens = tokenize(flags);
+    let mut issues = Vec::new();
+    let mut seen = HashSet::new();
+    let mut idx = 0;
+    while let Some(&(start, token)) = tokens.get(idx) {
+        idx += 1;
+        let next_is_value = tokens.get(idx).is_some_and(|(_, next)| !is_flag(next));
+        if !is_flag(token) {
+            issues.push(issue(
+                Severity::Error,
+                format!("unexpected value '{token}' without a flag"),
+                (start, token),
+            ));
+            continue;
+        }
+
+        match arity(token) {
+            Some(_) if managed.contains(&token) => issues.push(issue(
+                Severity::Error,
+                format!("'{token}' is set by aflr itself and conflicts with the generated one"),
+                (start, token),
+            )),
+            Some(_) if !seen.insert(token) && !REPEATABLE.contains(&token) => issues.push(issue(
+                Severity::Warning,
+                format!("'{token}' is given more than once, afl-fuzz only uses one of them"),
+                (start, token),
+            )),
+            Some(Arity::Value) if !next_is_value => issues.push(issue(
+                Severity::Error,
+                format!("'{token}' expects a value"),
+                (start, token),
+            )),
+            Some(_) => {}
+            None => {
+                let hint = token
+                    .get(..2)
+                    .filter(|prefix| arity(prefix) == Some(Arity::Value))
+                    .map(|prefix| format!(", did you mean '{prefix} {}'?", &token[2..]))
+                    .unwrap_or_default();
+                issues.push(issue(
+                    if strict {
+                        Severity::Error
+                    } else {
+                        Severity::Warning
+                    },
+                    format!("'{token}' is not a known afl-fuzz flag{hint}"),
+                    (start, token),
+                ));
+            }
+        }
+        // The value of an unknown flag is passed through along with it
+        if next_is_value && arity(token) != Some(Arity::Switch) {
+            idx += 1;
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(issues: &[FlagIssue]) -> Vec<(Severity, &str)> {
+        issues
+            .iter()
+            .map(|i| (i.severity, i.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_flags() {
+        assert!(validate("afl_flags", "-T MyFuzzer -s 1234 -t 1000+ -Q", &[], false).is_empty());
+        assert!(validate("afl_flags", "-x a.dict -x b.dict -V 300", &[], true).is_empty());
+        assert!(validate("afl_flags", "", &[], true).is_empty());
+    }
+
+    #[test]
+    fn test_arity_mistakes() {
+        let issues = validate("afl_flags", "-Q -t -m none 1000", &[], false);
+        assert_eq!(
+            messages(&issues),
+            [
+                (Severity::Error, "'-t' expects a value"),
+                (Severity::Error, "unexpected value '1000' without a flag"),
+            ]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "afl_flags: '-t' expects a value\n    -Q -t -m none 1000\n       ^^"
+        );
+        assert_eq!(
+            messages(&validate("afl_flags", "-Q 2", &[], false)),
+            [(Severity::Error, "unexpected value '2' without a flag")]
+        );
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let managed = ["-i", "-o", "-M", "-S", "-s"];
+        let issues = validate(
+            "afl_flags_master",
+            "-D -s 1234 -t 500 -t 1000",
+            &managed,
+            false,
+        );
+        assert_eq!(
+            messages(&issues),
+            [
+                (
+                    Severity::Error,
+                    "'-s' is set by aflr itself and conflicts with the generated one"
+                ),
+                (
+                    Severity::Warning,
+                    "'-t' is given more than once, afl-fuzz only uses one of them"
+                ),
+            ]
+        );
+        assert!(issues[1]
+            .to_string()
+            .ends_with("\n                      ^^"));
+    }
+
+    #[test]
+    fn test_unknown_flags_pass_through() {
+        let issues = validate("afl_flags", "-Q -l2 -y value -t 500", &[], false);
+        assert_eq!(
+            messages(&issues),
+            [
+                (
+                    Severity::Warning,
+                    "'-l2' is not a known afl-fuzz flag, did you mean '-l 2'?"
+                ),
+                (Severity::Warning, "'-y' is not a known afl-fuzz flag"),
+            ]
+        );
+        let strict = validate("afl_flags", "-y value", &[], true);
+        assert_eq!(strict[0].severity, Severity::Error);
+    }
+}
@@ -1,3 +1,5 @@
+use clap::ValueEnum;
+use serde::Deserialize;
 use std::error::Error;
 use std::fmt;
 use std::fs;
@@ -29,6 +31,29 @@ impl Error for HarnessError {
     }
 }
 
+/// One of the binaries a harness consists of
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum HarnessBinary {
+    Target,
+    Sanitizer,
+    Cmplog,
+    Cmpcov,
+    Coverage,
+}
+
+impl fmt::Display for HarnessBinary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Target => "target",
+            Self::Sanitizer => "sanitizer",
+            Self::Cmplog => "cmplog",
+            Self::Cmpcov => "cmpcov",
+            Self::Coverage => "coverage",
+        })
+    }
+}
+
 /// Represents a harness configuration for AFL++ fuzzing
 #[derive(Debug, Clone)]
 pub struct Harness {
@@ -131,6 +156,23 @@ impl Harness {
         Ok(self)
     }
 
+    /// All configured binaries of the harness
+    pub fn binaries(&self) -> Vec<(HarnessBinary, &Path)> {
+        let optional = [
+            (HarnessBinary::Sanitizer, &self.sanitizer_bin),
+            (HarnessBinary::Cmplog, &self.cmplog_bin),
+            (HarnessBinary::Cmpcov, &self.cmpcov_bin),
+            (HarnessBinary::Coverage, &self.cov_bin),
+        ];
+        std::iter::once((HarnessBinary::Target, self.target_bin.as_path()))
+            .chain(
+                optional
+                    .into_iter()
+                    .filter_map(|(kind, path)| path.as_deref().map(|path| (kind, path))),
+            )
+            .collect()
+    }
+
     /// Resolves a binary path to its canonical form
     ///
     /// # Arguments
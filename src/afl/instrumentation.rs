@@ -0,0 +1,411 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{bail, Context, Result};
+use rand::{seq::SliceRandom, Rng};
+use tempfile::TempDir;
+
+use crate::afl::map_size::{feed_seed, seed_files, showmap_binary};
+
+/// Tuples every seed has to cover unless configured otherwise
+pub const DEFAULT_MIN_TUPLES: usize = 16;
+/// Seeds the target is traced with
+const SAMPLE_SEEDS: usize = 3;
+/// Message `afl-showmap` aborts with if the target lacks the instrumentation
+const NO_INSTRUMENTATION_MSG: &str = "No instrumentation detected";
+/// Mode switches of `afl-fuzz` that `afl-showmap` needs as well, Nyx is `-X` in both cases
+const MODE_FLAGS: [(&str, &str); 6] = [
+    ("-Q", "-Q"),
+    ("-O", "-O"),
+    ("-U", "-U"),
+    ("-W", "-W"),
+    ("-X", "-X"),
+    ("-Y", "-X"),
+];
+
+/// Coverage map `afl-showmap` recorded for a single seed, tuple id to hit count bucket
+type TupleMap = BTreeMap<u32, u32>;
+
+/// Reason why the target does not look properly instrumented
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstrumentationIssue {
+    /// A seed covered fewer than the minimum number of tuples
+    Missing {
+        seed: PathBuf,
+        tuples: usize,
+        min_tuples: usize,
+    },
+    /// All seeds, despite their different contents, covered exactly the same tuples
+    ConstantMap { seeds: usize, tuples: usize },
+}
+
+impl fmt::Display for InstrumentationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing {
+                seed,
+                tuples,
+                min_tuples,
+            } => write!(
+                f,
+                "No instrumentation: {} covered {tuples} tuples, expected at least {min_tuples}. \
+                 The target was likely built without afl-cc or the instrumentation was stripped, \
+                 e.g. by LTO. Rebuild it with afl-clang-lto/afl-clang-fast, or pass \
+                 --verify-instrumentation=false if this is intended",
+                seed.display()
+            ),
+            Self::ConstantMap { seeds, tuples } => write!(
+                f,
+                "Constant map: {seeds} different seeds covered the very same {tuples} tuples. \
+                 The input likely never reaches the instrumented code, e.g. the fork server \
+                 starts before the target reads its input (check __AFL_INIT/__AFL_LOOP \
+                 placement) or the target ignores @@/stdin"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InstrumentationIssue {}
+
+/// Preflight check that traces a few seeds with `afl-showmap` to confirm the target is
+/// instrumented before hours of fuzzing are wasted on a misbuilt binary
+#[derive(Debug, Clone)]
+pub struct InstrumentationCheck {
+    /// Path to the `afl-showmap` binary
+    showmap: PathBuf,
+    /// Mode, timeout and memory limit flags relayed from the raw AFL++ flags
+    showmap_flags: Vec<String>,
+    /// Tuples every seed has to cover
+    min_tuples: usize,
+}
+
+impl InstrumentationCheck {
+    /// Creates a new check using the `afl-showmap` binary next to `afl_binary` or from `PATH`
+    pub fn new(afl_binary: &Path) -> Self {
+        Self {
+            showmap: showmap_binary(afl_binary),
+            showmap_flags: Vec::new(),
+            min_tuples: DEFAULT_MIN_TUPLES,
+        }
+    }
+
+    /// Overrides the `afl-showmap` binary to use
+    pub fn with_showmap(mut self, showmap: PathBuf) -> Self {
+        self.showmap = showmap;
+        self
+    }
+
+    /// Relays the QEMU/FRIDA/Unicorn/Wine/Nyx mode as well as `-t` and `-m` from `afl_flags`
+    pub fn with_afl_flags(mut self, afl_flags: Option<&str>) -> Self {
+        let tokens: Vec<&str> = afl_flags.unwrap_or_default().split_whitespace().collect();
+        self.showmap_flags.clear();
+        for (idx, token) in tokens.iter().enumerate() {
+            if let Some((_, relayed)) = MODE_FLAGS.iter().find(|(flag, _)| flag == token) {
+                self.showmap_flags.push((*relayed).to_string());
+            } else if let Some(value) = tokens
+                .get(idx + 1)
+                .filter(|_| matches!(*token, "-t" | "-m"))
+            {
+                // afl-fuzz accepts `-t 1000+` to skip slow seeds, afl-showmap only the number
+                self.showmap_flags.push((*token).to_string());
+                self.showmap_flags
+                    .push(value.trim_end_matches('+').to_string());
+            }
+        }
+        self
+    }
+
+    /// Sets the tuples every seed has to cover
+    pub fn with_min_tuples(mut self, min_tuples: usize) -> Self {
+        self.min_tuples = min_tuples;
+        self
+    }
+
+    /// Picks up to three random seeds from `input_dir`
+    ///
+    /// # Errors
+    /// * If `input_dir` cannot be read
+    pub fn sample_seeds<R: Rng>(input_dir: &Path, rng: &mut R) -> Result<Vec<PathBuf>> {
+        let seeds = seed_files(input_dir)?;
+        let mut sample: Vec<PathBuf> = seeds.choose_multiple(rng, SAMPLE_SEEDS).cloned().collect();
+        sample.sort();
+        Ok(sample)
+    }
+
+    /// Traces `seeds` with `afl-showmap` and returns the number of tuples each one covered
+    ///
+    /// # Errors
+    /// * If `afl-showmap` could not be executed or did not write a map
+    /// * With an `InstrumentationIssue` if a seed covers too few tuples or the map is the same
+    ///   for all seeds
+    pub fn run(
+        &self,
+        target: &Path,
+        target_args: Option<&str>,
+        seeds: &[PathBuf],
+    ) -> Result<Vec<usize>> {
+        if seeds.is_empty() {
+            bail!("The instrumentation check needs at least one seed");
+        }
+        let tmp = TempDir::new().context("Failed to create a directory for the coverage maps")?;
+        let mut maps = Vec::new();
+        for (idx, seed) in seeds.iter().enumerate() {
+            let map_file = tmp.path().join(idx.to_string());
+            maps.push(self.trace(target, target_args, seed, &map_file)?);
+        }
+        self.evaluate(seeds, &maps)?;
+        Ok(maps.iter().map(BTreeMap::len).collect())
+    }
+
+    /// Runs `afl-showmap` once with `seed` and reads the resulting map
+    fn trace(
+        &self,
+        target: &Path,
+        target_args: Option<&str>,
+        seed: &Path,
+        map_file: &Path,
+    ) -> Result<TupleMap> {
+        let mut cmd = Command::new(&self.showmap);
+        cmd.arg("-q").arg("-o").arg(map_file);
+        cmd.args(&self.showmap_flags);
+        cmd.arg("--").arg(target);
+        feed_seed(&mut cmd, target_args, seed)?;
+
+        let output = cmd
+            .output()
+            .with_context(|| format!("Failed to execute {}", self.showmap.display()))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // afl-showmap aborts before writing a map if it finds no instrumentation at all
+        if stderr.contains(NO_INSTRUMENTATION_MSG) {
+            return Ok(TupleMap::new());
+        }
+        let Ok(content) = fs::read_to_string(map_file) else {
+            let reason = stderr
+                .lines()
+                .rev()
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or("no error output");
+            bail!(
+                "afl-showmap wrote no coverage map for {} (exit code {}): {}",
+                seed.display(),
+                output.status.code().unwrap_or(-1),
+                reason.trim()
+            );
+        };
+        Ok(parse_map(&content))
+    }
+
+    /// Checks the maps of `seeds` for missing instrumentation and a constant map
+    fn evaluate(&self, seeds: &[PathBuf], maps: &[TupleMap]) -> Result<(), InstrumentationIssue> {
+        if let Some((seed, map)) = seeds
+            .iter()
+            .zip(maps)
+            .find(|(_, map)| map.len() < self.min_tuples)
+        {
+            return Err(InstrumentationIssue::Missing {
+                seed: seed.clone(),
+                tuples: map.len(),
+                min_tuples: self.min_tuples,
+            });
+        }
+
+        // Identical seeds legitimately produce identical maps, so only distinct ones count
+        let distinct = seeds
+            .iter()
+            .filter_map(|seed| fs::read(seed).ok())
+            .collect::<BTreeSet<_>>()
+            .len();
+        if distinct > 1 && maps.windows(2).all(|pair| pair[0] == pair[1]) {
+            return Err(InstrumentationIssue::ConstantMap {
+                seeds: distinct,
+                tuples: maps[0].len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Parses the `<tuple id>:<count>` lines `afl-showmap` writes
+fn parse_map(content: &str) -> TupleMap {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (id, count) = line.trim().split_once(':')?;
+            Some((id.parse().ok()?, count.parse().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Creates a stub `afl-showmap` that records its arguments and writes a map of `tuples`
+    /// tuples, offset by the size of the traced seed if `varying` is set
+    fn create_stub_showmap(dir: &Path, tuples: usize, varying: bool) -> PathBuf {
+        let stub = dir.join("afl-showmap");
+        // The seed is either the last argument (@@) or stdin
+        let offset = if varying {
+            "$(if [ -f \"$seed\" ]; then wc -c < \"$seed\"; else wc -c; fi)"
+        } else {
+            "0"
+        };
+        fs::write(
+            &stub,
+            format!(
+                "#!/bin/sh\necho \"$@\" >> {args}\nout=$3\nfor a; do seed=$a; done\n\
+                 offset={offset}\ni=0\n\
+                 while [ $i -lt {tuples} ]; do echo \"$((i + offset)):1\"; i=$((i + 1)); done > \"$out\"\n",
+                args = dir.join("args.txt").display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+        stub
+    }
+
+    fn create_seeds(dir: &Path, contents: &[&str]) -> Vec<PathBuf> {
+        contents
+            .iter()
+            .enumerate()
+            .map(|(idx, content)| {
+                let seed = dir.join(format!("seed{idx}"));
+                fs::write(&seed, content).unwrap();
+                seed
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_instrumented_target() {
+        let tmp = TempDir::new().unwrap();
+        let stub = create_stub_showmap(tmp.path(), 40, true);
+        let seeds = create_seeds(tmp.path(), &["a", "bb", "ccc"]);
+
+        let tuples = InstrumentationCheck::new(Path::new("/nonexistent/afl-fuzz"))
+            .with_showmap(stub)
+            .with_afl_flags(Some("-Q -t 1000+ -m none -p fast"))
+            .run(Path::new("/bin/target"), Some("--in @@"), &seeds)
+            .unwrap();
+        assert_eq!(tuples, [40, 40, 40]);
+
+        let args = fs::read_to_string(tmp.path().join("args.txt")).unwrap();
+        let first = args.lines().next().unwrap();
+        assert!(first.starts_with("-q -o "), "{first}");
+        assert!(
+            first.contains(&format!(
+                "-Q -t 1000 -m none -- /bin/target --in {}",
+                seeds[0].display()
+            )),
+            "{first}"
+        );
+        assert!(!first.contains("-p"));
+    }
+
+    #[test]
+    fn test_missing_instrumentation() {
+        let tmp = TempDir::new().unwrap();
+        let stub = create_stub_showmap(tmp.path(), 3, true);
+        let seeds = create_seeds(tmp.path(), &["a", "bb"]);
+
+        let err = InstrumentationCheck::new(Path::new("afl-fuzz"))
+            .with_showmap(stub)
+            .run(Path::new("/bin/target"), None, &seeds)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InstrumentationIssue>(),
+            Some(&InstrumentationIssue::Missing {
+                seed: seeds[0].clone(),
+                tuples: 3,
+                min_tuples: DEFAULT_MIN_TUPLES,
+            })
+        );
+        assert!(err.to_string().starts_with("No instrumentation"));
+
+        // afl-showmap refuses uninstrumented targets without writing a map
+        let stub = tmp.path().join("afl-showmap");
+        fs::write(
+            &stub,
+            format!("#!/bin/sh\necho '[-] PROGRAM ABORT : {NO_INSTRUMENTATION_MSG}' >&2\nexit 1\n"),
+        )
+        .unwrap();
+        let err = InstrumentationCheck::new(Path::new("afl-fuzz"))
+            .with_showmap(stub)
+            .with_min_tuples(1)
+            .run(Path::new("/bin/target"), None, &seeds)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<InstrumentationIssue>(),
+            Some(InstrumentationIssue::Missing { tuples: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_constant_map() {
+        let tmp = TempDir::new().unwrap();
+        let stub = create_stub_showmap(tmp.path(), 40, false);
+        let check = InstrumentationCheck::new(Path::new("afl-fuzz")).with_showmap(stub);
+
+        let seeds = create_seeds(tmp.path(), &["a", "bb", "ccc"]);
+        let err = check
+            .run(Path::new("/bin/target"), None, &seeds)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InstrumentationIssue>(),
+            Some(&InstrumentationIssue::ConstantMap {
+                seeds: 3,
+                tuples: 40
+            })
+        );
+        assert!(err.to_string().contains("fork server"));
+
+        // Duplicate seeds and a single seed cannot tell a constant map apart
+        let dupes_dir = tmp.path().join("dupes");
+        fs::create_dir(&dupes_dir).unwrap();
+        let dupes = create_seeds(&dupes_dir, &["same", "same"]);
+        assert!(check.run(Path::new("/bin/target"), None, &dupes).is_ok());
+        assert!(check
+            .run(Path::new("/bin/target"), None, &seeds[..1])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_showmap_failures() {
+        let tmp = TempDir::new().unwrap();
+        let seeds = create_seeds(tmp.path(), &["a"]);
+        let check = InstrumentationCheck::new(Path::new("afl-fuzz"))
+            .with_showmap(tmp.path().join("missing-showmap"));
+        assert!(check.run(Path::new("/bin/target"), None, &seeds).is_err());
+        assert!(check.run(Path::new("/bin/target"), None, &[]).is_err());
+    }
+
+    #[test]
+    fn test_sample_seeds() {
+        let tmp = TempDir::new().unwrap();
+        create_seeds(tmp.path(), &["a", "b", "c", "d", "e"]);
+        let mut rng = StdRng::seed_from_u64(1);
+        let sample = InstrumentationCheck::sample_seeds(tmp.path(), &mut rng).unwrap();
+        assert_eq!(sample.len(), SAMPLE_SEEDS);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let empty = tmp.path().join("empty");
+        fs::create_dir(&empty).unwrap();
+        assert!(InstrumentationCheck::sample_seeds(&empty, &mut rng)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_map() {
+        assert_eq!(
+            parse_map("000001:1\n004242:128\ngarbage\n"),
+            TupleMap::from([(1, 1), (4242, 128)])
+        );
+    }
+}
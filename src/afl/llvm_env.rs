@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::utils::system::shell_quote;
+
+/// Prefix of the variables configuring AFL++'s LLVM instrumentation
+pub const LLVM_ENV_PREFIX: &str = "AFL_LLVM_";
+
+/// `AFL_LLVM_*` variables from `[afl_cfg.llvm_env]` that are exported to every instance
+///
+/// They describe how the harness was instrumented, e.g. `AFL_LLVM_LAF_ALL`, so they have to
+/// match the build. Booleans are exported as `1` when true and left out when false, since
+/// AFL++ only checks whether most of them are set.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(try_from = "BTreeMap<String, RawEnvValue>")]
+pub struct LlvmEnv(BTreeMap<String, String>);
+
+/// Helper to accept strings, numbers and booleans in the TOML config
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawEnvValue {
+    Flag(bool),
+    Number(i64),
+    Text(String),
+}
+
+impl TryFrom<BTreeMap<String, RawEnvValue>> for LlvmEnv {
+    type Error = String;
+
+    fn try_from(raw: BTreeMap<String, RawEnvValue>) -> Result<Self, Self::Error> {
+        let mut vars = BTreeMap::new();
+        for (key, value) in raw {
+            let is_valid = key.strip_prefix(LLVM_ENV_PREFIX).is_some_and(|name| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            });
+            if !is_valid {
+                return Err(format!(
+                    "invalid llvm_env key '{key}', expected an {LLVM_ENV_PREFIX}* variable like AFL_LLVM_LAF_ALL"
+                ));
+            }
+            let value = match value {
+                RawEnvValue::Flag(false) => continue,
+                RawEnvValue::Flag(true) => "1".to_string(),
+                RawEnvValue::Number(n) => n.to_string(),
+                RawEnvValue::Text(text) => text,
+            };
+            vars.insert(key, value);
+        }
+        Ok(Self(vars))
+    }
+}
+
+impl LlvmEnv {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The variables as `KEY=value` assignments, sorted by key
+    pub fn assignments(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(key, value)| format!("{key}={}", shell_quote(value)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Config {
+        llvm_env: LlvmEnv,
+    }
+
+    fn parse(table: &str) -> Result<LlvmEnv, toml::de::Error> {
+        toml::from_str::<Config>(&format!("[llvm_env]\n{table}")).map(|c| c.llvm_env)
+    }
+
+    #[test]
+    fn test_llvm_env() {
+        let env = parse(
+            "AFL_LLVM_LAF_ALL = true\nAFL_LLVM_CMPLOG = false\nAFL_LLVM_NGRAM_SIZE = 4\nAFL_LLVM_ALLOWLIST = \"/src/allow list.txt\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            env.assignments(),
+            [
+                "AFL_LLVM_ALLOWLIST='/src/allow list.txt'",
+                "AFL_LLVM_LAF_ALL=1",
+                "AFL_LLVM_NGRAM_SIZE=4",
+            ]
+        );
+        assert!(parse("").unwrap().is_empty());
+
+        for key in ["AFL_MAP_SIZE", "AFL_LLVM_", "afl_llvm_laf_all"] {
+            let err = parse(&format!("{key} = 1\n")).unwrap_err();
+            assert!(err.to_string().contains("invalid llvm_env key"), "{key}");
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Requested `AFL_MAP_SIZE` for all generated commands
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "RawMapSize")]
+pub enum MapSize {
+    /// Probe the target with `afl-showmap` to determine the required map size
+    Auto,
+    /// Use the provided map size as is
+    Fixed(u64),
+}
+
+/// Helper to accept both `"auto"` and plain numbers in the TOML config
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawMapSize {
+    Number(u64),
+    Text(String),
+}
+
+impl TryFrom<RawMapSize> for MapSize {
+    type Error = String;
+
+    fn try_from(raw: RawMapSize) -> Result<Self, Self::Error> {
+        match raw {
+            RawMapSize::Number(size) => Ok(Self::Fixed(size)),
+            RawMapSize::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl FromStr for MapSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        s.parse::<u64>()
+            .map(Self::Fixed)
+            .map_err(|_| format!("Invalid map size '{s}': expected 'auto' or a number"))
+    }
+}
+
+impl fmt::Display for MapSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Fixed(size) => write!(f, "{size}"),
+        }
+    }
+}
+
+/// Probes the required map size of a target by running `afl-showmap` once
+#[derive(Debug, Clone)]
+pub struct MapSizeProbe {
+    /// Path to the `afl-showmap` binary
+    showmap: PathBuf,
+    /// Instrumentation mode flag (`-Q` or `-O`) that has to be relayed to `afl-showmap`
+    mode_flag: Option<String>,
+}
+
+impl MapSizeProbe {
+    /// Creates a new probe using the `afl-showmap` binary next to `afl_binary` or from `PATH`
+    pub fn new(afl_binary: &Path) -> Self {
+        Self {
+            showmap: showmap_binary(afl_binary),
+            mode_flag: None,
+        }
+    }
+
+    /// Overrides the `afl-showmap` binary to use
+    pub fn with_showmap(mut self, showmap: PathBuf) -> Self {
+        self.showmap = showmap;
+        self
+    }
+
+    /// Relays QEMU (`-Q`) or FRIDA (`-O`) mode to `afl-showmap` if present in `afl_flags`
+    pub fn with_afl_flags(mut self, afl_flags: Option<&String>) -> Self {
+        self.mode_flag = afl_flags.and_then(|flags| {
+            flags
+                .split_whitespace()
+                .find(|f| *f == "-Q" || *f == "-O")
+                .map(String::from)
+        });
+        self
+    }
+
+    /// Runs `afl-showmap` against the target with the first seed in `input_dir`
+    /// and returns the reported map size rounded up to the next power of two
+    ///
+    /// # Errors
+    /// * If no seed could be found in `input_dir`
+    /// * If `afl-showmap` could not be executed
+    /// * If no map size could be parsed from the `afl-showmap` output
+    pub fn run(&self, target: &Path, target_args: Option<&str>, input_dir: &Path) -> Result<u64> {
+        let Some(seed) = seed_files(input_dir)?.into_iter().next() else {
+            bail!("No seed found in {}", input_dir.display());
+        };
+
+        let mut cmd = Command::new(&self.showmap);
+        cmd.arg("-o").arg("/dev/null");
+        if let Some(flag) = &self.mode_flag {
+            cmd.arg(flag);
+        }
+        cmd.arg("--").arg(target);
+        feed_seed(&mut cmd, target_args, &seed)?;
+
+        let output = cmd
+            .output()
+            .with_context(|| format!("Failed to execute {}", self.showmap.display()))?;
+
+        let combined = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        Self::parse_map_size(&combined)
+            .map(u64::next_power_of_two)
+            .context("afl-showmap did not report a map size")
+    }
+
+    /// Extracts the largest number from all lines mentioning the map size
+    fn parse_map_size(output: &str) -> Option<u64> {
+        output
+            .lines()
+            .filter(|line| {
+                let line = line.to_lowercase();
+                line.contains("map size") || line.contains("map_size")
+            })
+            .flat_map(|line| {
+                line.split(|c: char| !c.is_ascii_digit())
+                    .filter_map(|n| n.parse::<u64>().ok())
+                    .collect::<Vec<_>>()
+            })
+            .max()
+    }
+}
+
+/// The `afl-showmap` binary next to `afl_binary`, or the one from `PATH`
+pub fn showmap_binary(afl_binary: &Path) -> PathBuf {
+    let sibling = afl_binary.with_file_name("afl-showmap");
+    if sibling.is_file() {
+        sibling
+    } else {
+        PathBuf::from("afl-showmap")
+    }
+}
+
+/// All seed files in `input_dir`, sorted by path
+///
+/// # Errors
+/// * If `input_dir` cannot be read
+pub fn seed_files(input_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut seeds: Vec<_> = fs::read_dir(input_dir)
+        .with_context(|| format!("Failed to read seed directory {}", input_dir.display()))?
+        .filter_map(std::result::Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    seeds.sort();
+    Ok(seeds)
+}
+
+/// Appends `target_args` to `cmd` and passes `seed` in place of `@@`, or via stdin without it
+///
+/// # Errors
+/// * If `seed` cannot be opened for stdin
+pub fn feed_seed(cmd: &mut Command, target_args: Option<&str>, seed: &Path) -> Result<()> {
+    let args: Vec<&str> = target_args.unwrap_or_default().split_whitespace().collect();
+    if args.contains(&"@@") {
+        cmd.args(args.iter().map(|&a| {
+            if a == "@@" {
+                seed.as_os_str()
+            } else {
+                a.as_ref()
+            }
+        }));
+        cmd.stdin(Stdio::null());
+    } else {
+        cmd.args(&args);
+        cmd.stdin(File::open(seed)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    /// Creates a stub `afl-showmap` that records its arguments and prints `output`
+    fn create_stub_showmap(dir: &Path, output: &str) -> PathBuf {
+        let stub = dir.join("afl-showmap");
+        let args_file = dir.join("args.txt");
+        fs::write(
+            &stub,
+            format!(
+                "#!/bin/sh\necho \"$@\" > {}\necho '{output}'\n",
+                args_file.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+        stub
+    }
+
+    fn create_seed_dir(dir: &Path) -> PathBuf {
+        let seeds = dir.join("seeds");
+        fs::create_dir(&seeds).unwrap();
+        fs::write(seeds.join("seed1"), "fuzz").unwrap();
+        seeds
+    }
+
+    #[test]
+    fn test_map_size_from_str() {
+        assert_eq!("auto".parse::<MapSize>().unwrap(), MapSize::Auto);
+        assert_eq!("AUTO".parse::<MapSize>().unwrap(), MapSize::Auto);
+        assert_eq!("65536".parse::<MapSize>().unwrap(), MapSize::Fixed(65536));
+        assert!("foo".parse::<MapSize>().is_err());
+    }
+
+    #[test]
+    fn test_map_size_deserialize() {
+        #[derive(Deserialize)]
+        struct Cfg {
+            map_size: MapSize,
+        }
+
+        let auto: Cfg = toml::from_str("map_size = \"auto\"").unwrap();
+        assert_eq!(auto.map_size, MapSize::Auto);
+        let fixed: Cfg = toml::from_str("map_size = 131072").unwrap();
+        assert_eq!(fixed.map_size, MapSize::Fixed(131_072));
+        assert!(toml::from_str::<Cfg>("map_size = \"big\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_map_size() {
+        assert_eq!(
+            MapSizeProbe::parse_map_size("[+] Target map size: 70000\n"),
+            Some(70000)
+        );
+        assert_eq!(
+            MapSizeProbe::parse_map_size("foo\n[-] Set AFL_MAP_SIZE to at least 123456\n"),
+            Some(123_456)
+        );
+        assert_eq!(MapSizeProbe::parse_map_size("Captured 42 tuples"), None);
+    }
+
+    #[test]
+    fn test_probe_with_stub_showmap() {
+        let tmp = TempDir::new().unwrap();
+        let stub = create_stub_showmap(tmp.path(), "[+] Target map size: 70000");
+        let seeds = create_seed_dir(tmp.path());
+
+        let size = MapSizeProbe::new(Path::new("/nonexistent/afl-fuzz"))
+            .with_showmap(stub)
+            .run(Path::new("/bin/target"), Some("--arg @@"), &seeds)
+            .unwrap();
+        assert_eq!(size, 131_072);
+
+        let args = fs::read_to_string(tmp.path().join("args.txt")).unwrap();
+        assert!(args.contains("-- /bin/target --arg"));
+        assert!(args.contains(&seeds.join("seed1").display().to_string()));
+        assert!(!args.contains("-Q"));
+    }
+
+    #[test]
+    fn test_probe_relays_qemu_and_frida_mode() {
+        for flag in ["-Q", "-O"] {
+            let tmp = TempDir::new().unwrap();
+            let stub = create_stub_showmap(tmp.path(), "[+] Target map size: 65536");
+            let seeds = create_seed_dir(tmp.path());
+            let afl_flags = format!("-t 1000+ {flag}");
+
+            let size = MapSizeProbe::new(Path::new("afl-fuzz"))
+                .with_showmap(stub)
+                .with_afl_flags(Some(&afl_flags))
+                .run(Path::new("/bin/target"), None, &seeds)
+                .unwrap();
+            assert_eq!(size, 65536);
+
+            let args = fs::read_to_string(tmp.path().join("args.txt")).unwrap();
+            assert!(args.starts_with(&format!("-o /dev/null {flag} --")));
+        }
+    }
+
+    #[test]
+    fn test_probe_failures() {
+        let tmp = TempDir::new().unwrap();
+        let stub = create_stub_showmap(tmp.path(), "no useful output");
+        let seeds = create_seed_dir(tmp.path());
+
+        let probe = MapSizeProbe::new(Path::new("afl-fuzz")).with_showmap(stub);
+        assert!(probe.run(Path::new("/bin/target"), None, &seeds).is_err());
+
+        let empty = tmp.path().join("empty");
+        fs::create_dir(&empty).unwrap();
+        assert!(probe.run(Path::new("/bin/target"), None, &empty).is_err());
+    }
+}
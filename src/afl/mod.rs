@@ -1,8 +1,28 @@
+pub mod attribution;
 pub mod base_cfg;
+pub mod builds;
 pub mod cmd;
+pub mod cmd_diff;
+pub mod cmd_edit;
 pub mod cmd_gen;
+pub mod corpus;
 pub mod coverage;
+pub mod coverage_index;
+pub mod deps;
+pub mod dictionary;
+pub mod doctor;
 pub mod env;
+pub mod executor;
+pub mod flags;
 pub mod harness;
+pub mod instrumentation;
+pub mod llvm_env;
+pub mod map_size;
 pub mod mode;
+pub mod preflight;
+pub mod replay;
+pub mod startup;
 pub mod strategies;
+pub mod target_env;
+pub mod triage;
+pub mod warnings;
@@ -0,0 +1,358 @@
+use anyhow::{bail, Result};
+use std::{
+    fmt::{self, Write as _},
+    fs,
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use crate::tui::control::RUNTIME_DIR;
+use crate::utils::system::is_dir_writable;
+
+/// Seeds listed by name in a report before the rest is only counted
+const MAX_LISTED_SEEDS: usize = 3;
+
+/// How the instances use a directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    /// Seeds are read from it, an empty one receives a placeholder seed
+    Input,
+    /// Instances create their state in it, along with the `.aflr` metadata
+    Output,
+    /// Instances create files in it, but it is not created on demand
+    Mount,
+}
+
+#[derive(Debug, Clone)]
+struct DirCheck {
+    path: PathBuf,
+    /// Config key or CLI option the path comes from, named in the report
+    option: String,
+    access: Access,
+}
+
+/// A directory that cannot be used as configured
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub option: String,
+    pub path: PathBuf,
+    pub detail: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} {}",
+            self.option,
+            self.path.display(),
+            self.detail
+        )
+    }
+}
+
+/// Checks the directories of a campaign before any instance is started
+///
+/// afl-fuzz only fails late on missing parents and the instances of a campaign die one by one on
+/// directories left owned by root by an earlier `sudo` run. All problems are collected so a
+/// single report names every config key that needs fixing.
+#[derive(Debug, Clone, Default)]
+pub struct Preflight {
+    checks: Vec<DirCheck>,
+}
+
+impl Preflight {
+    /// Creates `path` and its `.aflr` metadata directory if needed, both have to be writable
+    pub fn with_output_dir(self, path: &Path, option: &str) -> Self {
+        self.with_check(path, option, Access::Output)
+    }
+
+    /// Seeds in `path` have to be readable, a missing or empty directory has to be writable
+    pub fn with_input_dir(self, path: &Path, option: &str) -> Self {
+        self.with_check(path, option, Access::Input)
+    }
+
+    /// `path` has to exist and be writable, e.g. the mount point of a `RAMDisk`
+    pub fn with_mount_point(self, path: &Path, option: &str) -> Self {
+        self.with_check(path, option, Access::Mount)
+    }
+
+    fn with_check(mut self, path: &Path, option: &str, access: Access) -> Self {
+        self.checks.push(DirCheck {
+            path: path.to_path_buf(),
+            option: option.to_string(),
+            access,
+        });
+        self
+    }
+
+    /// Runs all checks, creating missing input and output directories on the way
+    pub fn problems(&self) -> Vec<Problem> {
+        self.checks
+            .iter()
+            .flat_map(|check| {
+                let details = match check.access {
+                    Access::Input => check_input_dir(&check.path),
+                    Access::Output => check_output_dir(&check.path),
+                    Access::Mount => check_writable_dir(&check.path),
+                };
+                details.into_iter().map(|detail| Problem {
+                    option: check.option.clone(),
+                    path: check.path.clone(),
+                    detail,
+                })
+            })
+            .collect()
+    }
+
+    /// Runs all checks and reports every problem at once
+    ///
+    /// # Errors
+    /// * If any directory is missing and cannot be created, or lacks the required access
+    pub fn run(&self) -> Result<()> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            return Ok(());
+        }
+        let report = problems
+            .iter()
+            .map(|p| format!("  - {p}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!(
+            "{} director{} cannot be used, fix the options below before launching:\n{report}",
+            problems.len(),
+            if problems.len() == 1 { "y" } else { "ies" }
+        )
+    }
+}
+
+fn check_output_dir(path: &Path) -> Vec<String> {
+    if path.exists() && !path.is_dir() {
+        return vec!["exists but is not a directory".to_string()];
+    }
+    let runtime_dir = path.join(RUNTIME_DIR);
+    if let Err(e) = fs::create_dir_all(&runtime_dir) {
+        return vec![format!(
+            "cannot be created ({e}){}",
+            access_hint(nearest_existing(&runtime_dir))
+        )];
+    }
+
+    let mut details = check_writable_dir(path);
+    if details.is_empty() {
+        details.extend(
+            check_writable_dir(&runtime_dir)
+                .into_iter()
+                .map(|d| format!("has a metadata directory {RUNTIME_DIR} that {d}")),
+        );
+    }
+    // Instance directories of an earlier run are reused when resuming
+    if let Ok(entries) = path.read_dir() {
+        let mut instances = entries
+            .flatten()
+            .filter(|e| e.file_name() != RUNTIME_DIR && e.path().is_dir())
+            .map(|e| e.path())
+            .filter(|dir| !is_dir_writable(dir))
+            .collect::<Vec<_>>();
+        instances.sort();
+        details.extend(instances.iter().map(|dir| {
+            format!(
+                "has an instance directory {} that is not writable{}",
+                dir.file_name().unwrap_or_default().to_string_lossy(),
+                access_hint(dir)
+            )
+        }));
+    }
+    details
+}
+
+fn check_input_dir(path: &Path) -> Vec<String> {
+    if path.exists() && !path.is_dir() {
+        return vec!["exists but is not a directory".to_string()];
+    }
+    if let Err(e) = fs::create_dir_all(path) {
+        return vec![format!(
+            "cannot be created ({e}){}",
+            access_hint(nearest_existing(path))
+        )];
+    }
+    let entries = match path.read_dir() {
+        Ok(entries) => entries.flatten().map(|e| e.path()).collect::<Vec<_>>(),
+        Err(e) => return vec![format!("is not readable ({e}){}", access_hint(path))],
+    };
+    if entries.is_empty() {
+        // A placeholder seed is written into empty input directories
+        return if is_dir_writable(path) {
+            Vec::new()
+        } else {
+            vec![format!(
+                "is empty and not writable for a placeholder seed{}",
+                access_hint(path)
+            )]
+        };
+    }
+
+    let mut unreadable = entries
+        .iter()
+        .filter(|seed| seed.is_file() && fs::File::open(seed).is_err())
+        .filter_map(|seed| seed.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    if unreadable.is_empty() {
+        return Vec::new();
+    }
+    unreadable.sort();
+    let count = unreadable.len();
+    let mut listed = unreadable
+        .into_iter()
+        .take(MAX_LISTED_SEEDS)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if count > MAX_LISTED_SEEDS {
+        let _ = write!(listed, " and {} more", count - MAX_LISTED_SEEDS);
+    }
+    vec![format!(
+        "has {count} unreadable seed{} ({listed})",
+        if count == 1 { "" } else { "s" }
+    )]
+}
+
+fn check_writable_dir(path: &Path) -> Vec<String> {
+    if !path.is_dir() {
+        return vec!["does not exist or is not a directory".to_string()];
+    }
+    if is_dir_writable(path) {
+        Vec::new()
+    } else {
+        vec![format!("is not writable{}", access_hint(path))]
+    }
+}
+
+/// The closest ancestor of `path` that exists, i.e. the one a `create_dir_all` fails in
+fn nearest_existing(path: &Path) -> &Path {
+    path.ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(Path::new("/"))
+}
+
+/// Explains why `path` denies access, naming the owner if it is not the current user
+fn access_hint(path: &Path) -> String {
+    let Ok(meta) = path.metadata() else {
+        return String::new();
+    };
+    match current_uid() {
+        Some(uid) if uid != meta.uid() => format!(
+            ", {} is owned by uid {} while aflr runs as uid {uid} (left over from a run with sudo?)",
+            path.display(),
+            meta.uid()
+        ),
+        _ => format!(
+            ", {} has mode {:o}",
+            path.display(),
+            meta.permissions().mode() & 0o7777
+        ),
+    }
+}
+
+/// Effective uid of this process, the owner of its procfs entry
+fn current_uid() -> Option<u32> {
+    fs::metadata("/proc/self").ok().map(|m| m.uid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_preflight_creates_directories() {
+        let dir = TempDir::new().unwrap();
+        let output = dir.path().join("campaign/out");
+        let input = dir.path().join("seeds");
+        let preflight = Preflight::default()
+            .with_output_dir(&output, "solution_dir")
+            .with_input_dir(&input, "seed_dir")
+            .with_mount_point(dir.path(), "is_ramdisk");
+
+        assert!(preflight.run().is_ok());
+        assert!(output.join(RUNTIME_DIR).is_dir());
+        assert!(input.is_dir());
+        // Probe files are cleaned up again
+        assert_eq!(output.read_dir().unwrap().count(), 1);
+        assert_eq!(output.join(RUNTIME_DIR).read_dir().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_preflight_report() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file");
+        fs::write(&file, "").unwrap();
+
+        // Not even root can create directories in procfs
+        let err = Preflight::default()
+            .with_output_dir(Path::new("/proc/aflr/out"), "[afl_cfg] solution_dir")
+            .with_input_dir(&file, "[afl_cfg] seed_dir")
+            .with_mount_point(&dir.path().join("missing"), "[misc] is_ramdisk")
+            .run()
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("3 directories cannot be used"), "{err}");
+        assert!(
+            err.contains("  - [afl_cfg] solution_dir: /proc/aflr/out cannot be created"),
+            "{err}"
+        );
+        assert!(err.contains(", /proc "), "{err}");
+        assert!(
+            err.contains(&format!(
+                "  - [afl_cfg] seed_dir: {} exists but is not a directory",
+                file.display()
+            )),
+            "{err}"
+        );
+        assert!(err.contains("[misc] is_ramdisk: "), "{err}");
+    }
+
+    #[test]
+    fn test_preflight_permissions() {
+        let dir = TempDir::new().unwrap();
+        let output = dir.path().join("out");
+        let instance = output.join("m_target");
+        fs::create_dir_all(&instance).unwrap();
+        fs::set_permissions(&instance, fs::Permissions::from_mode(0o555)).unwrap();
+        let input = dir.path().join("in");
+        fs::create_dir(&input).unwrap();
+        for seed in ["a", "b"] {
+            fs::write(input.join(seed), "seed").unwrap();
+        }
+        fs::set_permissions(input.join("b"), fs::Permissions::from_mode(0o000)).unwrap();
+
+        let problems = Preflight::default()
+            .with_output_dir(&output, "solution_dir")
+            .with_input_dir(&input, "seed_dir")
+            .problems();
+        // Root ignores the permission bits
+        if current_uid() != Some(0) {
+            assert_eq!(problems.len(), 2, "{problems:?}");
+            assert!(problems[0]
+                .detail
+                .starts_with("has an instance directory m_target that is not writable"));
+            assert!(problems[0].detail.ends_with("has mode 555"));
+            assert_eq!(problems[1].detail, "has 1 unreadable seed (b)");
+        } else {
+            assert!(problems.is_empty(), "{problems:?}");
+        }
+        fs::set_permissions(&instance, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_access_hint() {
+        let dir = TempDir::new().unwrap();
+        fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o750)).unwrap();
+        assert!(access_hint(dir.path()).ends_with("has mode 750"));
+        assert_eq!(nearest_existing(&dir.path().join("a/b/c")), dir.path());
+        if current_uid() != Some(0) {
+            assert!(access_hint(Path::new("/")).contains("is owned by uid 0"));
+        }
+    }
+}
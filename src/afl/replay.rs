@@ -0,0 +1,320 @@
+use anyhow::{Context, Result};
+use std::{
+    fmt, fs,
+    os::unix::process::ExitStatusExt,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Directories of an instance that hold replayable test cases
+const ENTRY_DIRS: &[&str] = &["queue", "crashes", "hangs"];
+/// File in which AFL++ archives the environment and command line of each instance
+const FUZZER_SETUP: &str = "fuzzer_setup";
+/// Interval for polling a replayed target for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Finds all queue, crash and hang entries with the given `id` in an AFL++ output directory
+///
+/// The id may be given with or without leading zeros, e.g. `123` or `000123`
+///
+/// # Errors
+/// * If the output directory cannot be read
+pub fn find_entries(output_dir: &Path, id: &str) -> Result<Vec<PathBuf>> {
+    let prefix = format!("id:{:0>6},", id.trim_start_matches("id:"));
+    let mut entries = Vec::new();
+    for instance in fs::read_dir(output_dir)
+        .with_context(|| format!("Failed to read {}", output_dir.display()))?
+        .filter_map(std::result::Result::ok)
+    {
+        for dir in ENTRY_DIRS {
+            let Ok(files) = fs::read_dir(instance.path().join(dir)) else {
+                continue;
+            };
+            entries.extend(
+                files
+                    .filter_map(std::result::Result::ok)
+                    .filter(|f| f.file_name().to_string_lossy().starts_with(&prefix))
+                    .map(|f| f.path()),
+            );
+        }
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+/// Environment of the campaign that produced `entry` as archived by AFL++ in `fuzzer_setup`
+///
+/// Returns an empty list if the entry is not part of an instance directory or the file is missing
+pub fn archived_env(entry: &Path) -> Vec<(String, String)> {
+    let Some(setup) = entry
+        .parent()
+        .and_then(Path::parent)
+        .map(|instance| instance.join(FUZZER_SETUP))
+    else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(setup) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .skip_while(|line| !line.starts_with("# environment variables"))
+        .skip(1)
+        .take_while(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// How a replayed execution ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitKind {
+    Exited(i32),
+    Signaled(i32),
+    TimedOut,
+}
+
+impl From<ExitStatus> for ExitKind {
+    fn from(status: ExitStatus) -> Self {
+        status
+            .signal()
+            .map_or_else(|| Self::Exited(status.code().unwrap_or(-1)), Self::Signaled)
+    }
+}
+
+impl fmt::Display for ExitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exited(code) => write!(f, "exited with code {code}"),
+            Self::Signaled(signal) => match signal_name(*signal) {
+                Some(name) => write!(f, "killed by signal {signal} ({name})"),
+                None => write!(f, "killed by signal {signal}"),
+            },
+            Self::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+fn signal_name(signal: i32) -> Option<&'static str> {
+    match signal {
+        4 => Some("SIGILL"),
+        5 => Some("SIGTRAP"),
+        6 => Some("SIGABRT"),
+        7 => Some("SIGBUS"),
+        8 => Some("SIGFPE"),
+        9 => Some("SIGKILL"),
+        11 => Some("SIGSEGV"),
+        _ => None,
+    }
+}
+
+/// Result of a single replay
+#[derive(Debug, Clone, Copy)]
+pub struct ReplayOutcome {
+    pub exit: ExitKind,
+    pub runtime: Duration,
+}
+
+/// Reruns a single test case against a target binary
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    binary: PathBuf,
+    target_args: Vec<String>,
+    env: Vec<(String, String)>,
+    timeout: Duration,
+}
+
+impl Replayer {
+    pub fn new(binary: PathBuf, target_args: Vec<String>) -> Self {
+        Self {
+            binary,
+            target_args,
+            env: Vec::new(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Sets additional environment variables for the target
+    pub fn with_env(mut self, env: Vec<(String, String)>) -> Self {
+        self.env = env;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether the input is passed as a file in place of `@@`
+    fn is_file_input(&self) -> bool {
+        self.target_args.iter().any(|arg| arg == "@@")
+    }
+
+    /// Target arguments with `@@` substituted by `input`
+    fn args_for(&self, input: &Path) -> Vec<String> {
+        self.target_args
+            .iter()
+            .map(|arg| {
+                if arg == "@@" {
+                    input.to_string_lossy().into_owned()
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Builds the command executing the target with `input`
+    pub fn command(&self, input: &Path) -> Result<Command> {
+        let mut cmd = Command::new(&self.binary);
+        cmd.args(self.args_for(input))
+            .envs(self.env.iter().map(|(k, v)| (k, v)));
+        if self.is_file_input() {
+            cmd.stdin(Stdio::null());
+        } else {
+            cmd.stdin(
+                fs::File::open(input)
+                    .with_context(|| format!("Failed to open {}", input.display()))?,
+            );
+        }
+        Ok(cmd)
+    }
+
+    /// Builds the command running the target with `input` under `gdb`
+    pub fn gdb_command(&self, input: &Path) -> Command {
+        let mut cmd = Command::new("gdb");
+        cmd.envs(self.env.iter().map(|(k, v)| (k, v)));
+        if !self.is_file_input() {
+            cmd.arg("-ex").arg(format!("run < '{}'", input.display()));
+        }
+        cmd.arg("--args")
+            .arg(&self.binary)
+            .args(self.args_for(input));
+        cmd
+    }
+
+    /// Runs the target with `input` and waits for it to finish or time out
+    ///
+    /// # Errors
+    /// * If the input cannot be opened or the target cannot be spawned
+    pub fn run(&self, input: &Path) -> Result<ReplayOutcome> {
+        let start = Instant::now();
+        let mut child = self
+            .command(input)?
+            .spawn()
+            .with_context(|| format!("Failed to execute {}", self.binary.display()))?;
+
+        let exit = loop {
+            if let Some(status) = child.try_wait()? {
+                break ExitKind::from(status);
+            }
+            if start.elapsed() >= self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                break ExitKind::TimedOut;
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        Ok(ReplayOutcome {
+            exit,
+            runtime: start.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        for (instance, dir, name) in [
+            ("m_t", "queue", "id:000123,time:0,execs:0,orig:seed"),
+            ("m_t", "queue", "id:000012,src:000001,op:havoc"),
+            ("s0_t", "crashes", "id:000123,sig:11,src:000001,op:havoc"),
+        ] {
+            let path = tmp.path().join(instance).join(dir);
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join(name), "input").unwrap();
+        }
+        fs::write(
+            tmp.path().join("m_t").join(FUZZER_SETUP),
+            "# environment variables:\nAFL_SKIP_CPUFREQ=1\nASAN_OPTIONS=abort_on_error=1:symbolize=0\n\
+             # command line:\n'afl-fuzz' '-i' 'in' '-o' 'out'\n",
+        )
+        .unwrap();
+        tmp
+    }
+
+    #[test]
+    fn test_find_entries() {
+        let tmp = setup();
+        let entries = find_entries(tmp.path(), "123").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].ends_with("m_t/queue/id:000123,time:0,execs:0,orig:seed"));
+        assert!(entries[1].ends_with("s0_t/crashes/id:000123,sig:11,src:000001,op:havoc"));
+
+        assert_eq!(find_entries(tmp.path(), "000012").unwrap().len(), 1);
+        assert!(find_entries(tmp.path(), "1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_archived_env() {
+        let tmp = setup();
+        let entries = find_entries(tmp.path(), "123").unwrap();
+        assert_eq!(
+            archived_env(&entries[0]),
+            [
+                ("AFL_SKIP_CPUFREQ".to_string(), "1".to_string()),
+                (
+                    "ASAN_OPTIONS".to_string(),
+                    "abort_on_error=1:symbolize=0".to_string()
+                ),
+            ]
+        );
+        assert!(archived_env(&entries[1]).is_empty());
+    }
+
+    #[test]
+    fn test_replay_run() {
+        let tmp = setup();
+        let input = tmp.path().join("m_t/queue/id:000012,src:000001,op:havoc");
+
+        let stdin = Replayer::new(
+            PathBuf::from("/bin/sh"),
+            vec!["-c".into(), "read x; [ \"$x\" = input ] && exit 3".into()],
+        );
+        assert_eq!(stdin.run(&input).unwrap().exit, ExitKind::Exited(3));
+
+        let file = Replayer::new(PathBuf::from("/bin/cat"), vec!["@@".into()]);
+        assert_eq!(file.run(&input).unwrap().exit, ExitKind::Exited(0));
+
+        let signaled = Replayer::new(
+            PathBuf::from("/bin/sh"),
+            vec!["-c".into(), "kill -SEGV $$".into()],
+        );
+        let exit = signaled.run(&input).unwrap().exit;
+        assert_eq!(exit, ExitKind::Signaled(11));
+        assert_eq!(exit.to_string(), "killed by signal 11 (SIGSEGV)");
+
+        let hang = Replayer::new(PathBuf::from("/bin/sleep"), vec!["5".into()])
+            .with_timeout(Duration::from_millis(50));
+        assert_eq!(hang.run(&input).unwrap().exit, ExitKind::TimedOut);
+    }
+
+    #[test]
+    fn test_gdb_command() {
+        let replayer = Replayer::new(PathBuf::from("/bin/target"), vec!["-x".into()]);
+        let cmd = replayer.gdb_command(Path::new("/q/id:1"));
+        let args: Vec<_> = cmd.get_args().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(
+            args,
+            ["-ex", "run < '/q/id:1'", "--args", "/bin/target", "-x"]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Write},
+    fs,
+    path::Path,
+};
+
+use crate::afl::warnings::format_instances;
+
+/// Root cause of an instance that died right after being spawned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupCause {
+    /// The target needs a larger coverage map than AFL++ was started with
+    MapSize,
+    /// Shared memory for the coverage map could not be set up
+    SharedMemory,
+    /// Any other abort reported by AFL++, keyed by its message
+    Abort(String),
+    /// The instance died without a recognizable message
+    Unknown,
+}
+
+/// Aggregated startup failures of a session, raised instead of one error per instance
+#[derive(Debug, Clone, Default)]
+pub struct StartupFailures {
+    /// Number of instances in the session
+    pub total: usize,
+    /// Distinct causes along with the affected instance indices
+    pub causes: Vec<(StartupCause, BTreeSet<usize>)>,
+    /// Largest `AFL_MAP_SIZE` any instance asked for
+    pub map_size: Option<u64>,
+    /// First AFL++ message seen for shared memory failures
+    shm_message: Option<String>,
+}
+
+impl StartupFailures {
+    /// Classifies the output the session captured in `<dir>/<index>.log` for every failed
+    /// instance out of `total`
+    pub fn collect(dir: &Path, total: usize) -> Self {
+        let mut failures = Self {
+            total,
+            ..Self::default()
+        };
+        for idx in 0..total {
+            if let Ok(output) = fs::read(dir.join(format!("{idx}.log"))) {
+                failures.record(idx, &String::from_utf8_lossy(&output));
+            }
+        }
+        failures
+    }
+
+    /// Adds the failure of the instance at `idx` that printed `output`
+    pub fn record(&mut self, idx: usize, output: &str) {
+        let output = strip_ansi(output);
+        let cause = if let Some(size) = required_map_size(&output) {
+            self.map_size = self.map_size.max(Some(size));
+            StartupCause::MapSize
+        } else if let Some(line) = output.lines().find(|line| is_shm_error(line)) {
+            self.shm_message
+                .get_or_insert_with(|| abort_message(line).unwrap_or(line).trim().to_string());
+            StartupCause::SharedMemory
+        } else if let Some(message) = output.lines().find_map(abort_message) {
+            StartupCause::Abort(message.to_string())
+        } else {
+            StartupCause::Unknown
+        };
+
+        if let Some((_, instances)) = self.causes.iter_mut().find(|(c, _)| *c == cause) {
+            instances.insert(idx);
+        } else {
+            self.causes.push((cause, BTreeSet::from([idx])));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.causes.is_empty()
+    }
+
+    /// Number of instances that failed to start
+    pub fn failed(&self) -> usize {
+        self.causes
+            .iter()
+            .map(|(_, instances)| instances.len())
+            .sum()
+    }
+
+    /// The map size to retry with if every failure is explained by it
+    pub fn remediable_map_size(&self) -> Option<u64> {
+        self.causes
+            .iter()
+            .all(|(cause, _)| *cause == StartupCause::MapSize)
+            .then_some(self.map_size)
+            .flatten()
+    }
+
+    fn describe(&self, cause: &StartupCause) -> String {
+        match cause {
+            StartupCause::MapSize => self.map_size.map_or_else(
+                || "The target needs a larger coverage map, set --map-size to the size AFL++ reports".to_string(),
+                |size| {
+                    format!(
+                        "The target needs AFL_MAP_SIZE={size}. Rerun with --map-size {size} or --auto-remediate"
+                    )
+                },
+            ),
+            StartupCause::SharedMemory => format!(
+                "Shared memory setup failed ({}). Check `ipcs -m` for leaked segments and the kernel.shmmax/shmall/shmmni limits",
+                self.shm_message.as_deref().unwrap_or("shmget")
+            ),
+            StartupCause::Abort(message) => message.clone(),
+            StartupCause::Unknown => {
+                "No AFL++ error message was captured, attach to the session to inspect the instances".to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for StartupFailures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut message = format!(
+            "{} of {} instances failed to start:",
+            self.failed(),
+            self.total
+        );
+        for (cause, instances) in &self.causes {
+            let _ = write!(
+                message,
+                "\n    - {} (instances {})",
+                self.describe(cause),
+                format_instances(instances)
+            );
+        }
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for StartupFailures {}
+
+/// The `AFL_MAP_SIZE` AFL++ asks for, either spelled out or as the map size of the target
+fn required_map_size(output: &str) -> Option<u64> {
+    if let Some((_, rest)) = output.split_once("AFL_MAP_SIZE=") {
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        if let Ok(size) = digits.parse() {
+            return Some(size);
+        }
+    }
+    output
+        .lines()
+        .filter(|line| {
+            let line = line.to_lowercase();
+            line.contains("map size") && (line.contains("larger") || line.contains("too big"))
+        })
+        .flat_map(|line| {
+            line.split(|c: char| !c.is_ascii_digit())
+                .filter_map(|n| n.parse::<u64>().ok())
+        })
+        .max()
+}
+
+fn is_shm_error(line: &str) -> bool {
+    ["shmget", "shmat", "shm_open", "shared memory"]
+        .iter()
+        .any(|needle| line.to_lowercase().contains(needle))
+}
+
+/// The message of an AFL++ `PROGRAM ABORT` or `SYSTEM ERROR` line
+fn abort_message(line: &str) -> Option<&str> {
+    ["PROGRAM ABORT :", "SYSTEM ERROR :"]
+        .iter()
+        .find_map(|marker| line.split_once(marker))
+        .map(|(_, message)| message.trim())
+        .filter(|message| !message.is_empty())
+}
+
+/// Removes the color escape sequences AFL++ prints
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                chars.by_ref().find(char::is_ascii_alphabetic);
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const MAP_SIZE_ABORT: &str = "[-] \x1b[1;91mPROGRAM ABORT : \x1b[0mTarget's coverage map size of 262144 is larger than the one this AFL++ is set with (65536). Either set AFL_MAP_SIZE=262144 and restart afl-fuzz\n";
+    const SHM_ABORT: &str =
+        "[-]  SYSTEM ERROR : shmget() failed\n    Stop location : afl_shm_init()";
+
+    #[test]
+    fn test_startup_failures_are_aggregated() {
+        let tmp = TempDir::new().unwrap();
+        for idx in [0, 1, 2, 5] {
+            fs::write(tmp.path().join(format!("{idx}.log")), MAP_SIZE_ABORT).unwrap();
+        }
+        let failures = StartupFailures::collect(tmp.path(), 8);
+        assert_eq!(failures.failed(), 4);
+        assert_eq!(failures.causes.len(), 1);
+        assert_eq!(failures.remediable_map_size(), Some(262_144));
+        let message = failures.to_string();
+        assert!(message.starts_with("4 of 8 instances failed to start:"));
+        assert!(message.contains("--map-size 262144") && message.contains("instances 0-2, 5"));
+
+        let mut mixed = failures.clone();
+        mixed.record(6, SHM_ABORT);
+        mixed.record(
+            7,
+            "[-] PROGRAM ABORT : Program 'fuzz' not found or not executable",
+        );
+        mixed.record(3, "bash: afl-fuzz: command not found");
+        assert_eq!(mixed.causes.len(), 4);
+        assert_eq!(mixed.remediable_map_size(), None);
+        let message = mixed.to_string();
+        assert!(message.contains("Shared memory setup failed (shmget() failed)"));
+        assert!(message.contains("Program 'fuzz' not found or not executable (instances 7)"));
+    }
+
+    #[test]
+    fn test_required_map_size_without_env_hint() {
+        let output = "[-] Target map size (131072) is larger than the maximum (65536)";
+        assert_eq!(required_map_size(output), Some(131_072));
+        assert_eq!(required_map_size("all good"), None);
+    }
+}
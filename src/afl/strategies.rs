@@ -2,8 +2,9 @@ use crate::afl::cmd::AFLCmd;
 use crate::afl::mode::Mode;
 use once_cell::sync::Lazy;
 use rand::seq::SliceRandom;
+use serde::Deserialize;
 use std::collections::HashSet;
-use std::{fmt, path::PathBuf};
+use std::{fmt, ops::RangeInclusive, path::PathBuf, str::FromStr};
 
 /// These structs contain the AFL++ strategies and their probabilities of being applied in the command generation.
 ///
@@ -50,13 +51,37 @@ impl CmpcovConfig {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "String")]
 pub enum CmplogMode {
     Standard,   // -l 2
     Extended,   // -l 3
     Transforms, // -l 2AT
 }
 
+impl FromStr for CmplogMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "2" => Ok(Self::Standard),
+            "3" => Ok(Self::Extended),
+            "2AT" => Ok(Self::Transforms),
+            _ => Err(format!(
+                "Invalid cmplog level '{s}': expected '2', '3' or '2AT'"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for CmplogMode {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
 impl fmt::Display for CmplogMode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -76,6 +101,8 @@ pub struct CmplogConfig {
     pub runner_ratio: f64,
     /// Distribution of CMPLOG modes
     pub mode_distribution: Vec<(CmplogMode, f64)>,
+    /// Applied to all CMPLOG instances instead of drawing from `mode_distribution`
+    pub level: Option<CmplogMode>,
 }
 
 impl Default for CmplogConfig {
@@ -88,6 +115,7 @@ impl Default for CmplogConfig {
                 (CmplogMode::Extended, 0.1),
                 (CmplogMode::Transforms, 0.2),
             ],
+            level: None,
         }
     }
 }
@@ -103,8 +131,15 @@ impl CmplogConfig {
                 (CmplogMode::Extended, 0.1),
                 (CmplogMode::Transforms, 0.2),
             ],
+            level: None,
         }
     }
+
+    /// Forces `level` on all CMPLOG instances, the randomized distribution is kept if `None`
+    pub fn with_level(mut self, level: Option<CmplogMode>) -> Self {
+        self.level = level;
+        self
+    }
 }
 
 /// Represents different types of AFL++ mutation modes
@@ -176,6 +211,162 @@ pub enum ApplicationMode {
     Multiple,
 }
 
+/// Strategy flags that must never be emitted, see `[afl_cfg.strategy] forbid_flags`
+///
+/// Flags are matched on their logical form, i.e. `"-a  text"` forbids `-a text`.
+#[derive(Debug, Clone, Default)]
+pub struct FlagBlacklist {
+    flags: HashSet<String>,
+    /// Redistribute the probability of a forbidden option onto the remaining options of its
+    /// category instead of dropping it
+    pub redistribute: bool,
+}
+
+impl FlagBlacklist {
+    pub fn new(flags: &[String], redistribute: bool) -> Self {
+        Self {
+            flags: flags
+                .iter()
+                .map(|f| Self::normalize(f))
+                .filter(|f| !f.is_empty())
+                .collect(),
+            redistribute,
+        }
+    }
+
+    fn normalize(flag: &str) -> String {
+        flag.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flags.is_empty()
+    }
+
+    pub fn is_forbidden(&self, flag: &str) -> bool {
+        self.flags.contains(&Self::normalize(flag))
+    }
+
+    /// Removes forbidden options from a category of mutually exclusive options
+    ///
+    /// With `redistribute` the remaining options are scaled proportionally so that the category
+    /// keeps its total probability mass.
+    pub fn filter<T: fmt::Display + Copy>(&self, options: &[(T, f64)]) -> Vec<(T, f64)> {
+        let allowed: Vec<(T, f64)> = options
+            .iter()
+            .copied()
+            .filter(|(option, _)| !self.is_forbidden(&option.to_string()))
+            .collect();
+        if !self.redistribute || allowed.len() == options.len() {
+            return allowed;
+        }
+
+        let total: f64 = options.iter().map(|(_, p)| p).sum();
+        let remaining: f64 = allowed.iter().map(|(_, p)| p).sum();
+        if remaining <= 0.0 {
+            return allowed;
+        }
+        allowed
+            .into_iter()
+            .map(|(option, p)| (option, p * total / remaining))
+            .collect()
+    }
+
+    /// Human readable summary of the configured exclusions, `None` if nothing is forbidden
+    pub fn describe(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut flags: Vec<&str> = self.flags.iter().map(String::as_str).collect();
+        flags.sort_unstable();
+        let handling = if self.redistribute {
+            "probability redistributed within their category"
+        } else {
+            "probability dropped"
+        };
+        Some(format!("{} ({handling})", flags.join(", ")))
+    }
+}
+
+/// Instances the strategy flags are placed on, see `[afl_cfg.strategy] include_instances`
+///
+/// Instances outside the filter run with the AFL++ defaults for every strategy flag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstanceFilter {
+    /// Index ranges that receive strategy flags, all instances if empty
+    include: Vec<RangeInclusive<usize>>,
+    /// Index ranges that never receive strategy flags
+    exclude: Vec<RangeInclusive<usize>>,
+}
+
+impl InstanceFilter {
+    /// Parses index ranges like `"3"` or `"1-4"`
+    ///
+    /// # Errors
+    /// * If a range is malformed or its start is past its end
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        let parse = |ranges: &[String]| -> Result<Vec<_>, String> {
+            ranges.iter().map(|r| Self::parse_range(r)).collect()
+        };
+        Ok(Self {
+            include: parse(include)?,
+            exclude: parse(exclude)?,
+        })
+    }
+
+    fn parse_range(range: &str) -> Result<RangeInclusive<usize>, String> {
+        let index = |s: &str| {
+            s.trim().parse::<usize>().map_err(|_| {
+                format!("invalid instance range '{range}', expected e.g. '3' or '1-4'")
+            })
+        };
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (index(start)?, index(end)?),
+            None => (index(range)?, index(range)?),
+        };
+        if start > end {
+            return Err(format!("instance range '{range}' ends before it starts"));
+        }
+        Ok(start..=end)
+    }
+
+    pub fn is_unrestricted(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether the instance at `index` receives strategy flags
+    pub fn allows(&self, index: usize) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|r| r.contains(&index)))
+            && !self.exclude.iter().any(|r| r.contains(&index))
+    }
+
+    /// Human readable summary of the filter, `None` if it allows all instances
+    pub fn describe(&self) -> Option<String> {
+        let render = |ranges: &[RangeInclusive<usize>]| {
+            ranges
+                .iter()
+                .map(|r| {
+                    if r.start() == r.end() {
+                        r.start().to_string()
+                    } else {
+                        format!("{}-{}", r.start(), r.end())
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match (self.include.is_empty(), self.exclude.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(format!("instances {}", render(&self.include))),
+            (true, false) => Some(format!("all instances but {}", render(&self.exclude))),
+            (false, false) => Some(format!(
+                "instances {} but {}",
+                render(&self.include),
+                render(&self.exclude)
+            )),
+        }
+    }
+}
+
 /// Configuration for optional AFL++ features
 #[derive(Debug, Clone)]
 pub struct MiscFeatures {
@@ -212,6 +403,10 @@ pub struct AFLStrategy {
     pub cmplog_config: Option<CmplogConfig>,
     /// CMPCOV configuration
     pub cmpcov_config: Option<CmpcovConfig>,
+    /// Flags that are never emitted
+    pub forbidden: FlagBlacklist,
+    /// Instances that receive strategy flags
+    pub instances: InstanceFilter,
     /// internal state to check where to apply some configurations
     /// (e.g. in CI mode we apply all configurations to all commands as we do not have a -M fuzzer)
     is_ci_fuzzing: bool,
@@ -259,12 +454,48 @@ impl AFLStrategy {
     }
 
     /// Applies the strategy to a slice of AFL++ commands
+    ///
+    /// Only the instances of the filter receive flags. The strategy sees the main instance
+    /// followed by the allowed instances, so ratios and the special role of the main instance
+    /// work as for a fleet of that size.
     pub fn apply<R: rand::Rng>(
         &mut self,
         cmds: &mut [AFLCmd],
         rng: &mut R,
         is_using_custom_mutator: bool,
     ) -> Self {
+        if cmds.is_empty() {
+            return self.clone();
+        }
+        let positions: Vec<usize> = std::iter::once(0)
+            .chain((1..cmds.len()).filter(|&idx| self.instances.allows(idx)))
+            .collect();
+        let mut subset: Vec<AFLCmd> = positions.iter().map(|&idx| cmds[idx].clone()).collect();
+        self.apply_all(&mut subset, rng, is_using_custom_mutator);
+
+        for (&idx, cmd) in positions.iter().zip(subset) {
+            if self.instances.allows(idx) {
+                cmds[idx] = cmd;
+            }
+        }
+        if let Some(config) = self.cmpcov_config.as_mut() {
+            config.applied_indices = config
+                .applied_indices
+                .iter()
+                .map(|&idx| positions[idx])
+                .filter(|&idx| self.instances.allows(idx))
+                .collect();
+        }
+        self.clone()
+    }
+
+    /// Applies the strategy to every command in `cmds`
+    fn apply_all<R: rand::Rng>(
+        &mut self,
+        cmds: &mut [AFLCmd],
+        rng: &mut R,
+        is_using_custom_mutator: bool,
+    ) {
         // Applies to ALL instances
 
         // Apply power schedules
@@ -321,8 +552,6 @@ impl AFLStrategy {
 
         // Apply optional features
         self.apply_optional_features(target_cmds, rng, is_using_custom_mutator);
-
-        self.clone()
     }
 
     /// Applies mutually exclusive arguments to commands
@@ -385,6 +614,9 @@ impl AFLStrategy {
             optional_args.push(("-Z".to_string(), prob));
         }
 
+        // Optional features are independent toggles, a forbidden one is simply dropped
+        optional_args.retain(|(arg, _)| !self.forbidden.is_forbidden(arg));
+
         // Apply according to mode
         match mode {
             ApplicationMode::Exclusive => Self::apply_exclusive_args(cmds, &optional_args, rng),
@@ -450,17 +682,28 @@ impl AFLStrategy {
         #[allow(clippy::cast_precision_loss)]
         #[allow(clippy::cast_sign_loss)]
         let num_cmplog_cfgs = (cmds.len() as f64 * config.runner_ratio) as usize;
+        // A fixed level replaces the hand-picked modes of small campaigns as well
+        let allowed = |modes: &[CmplogMode]| -> Vec<CmplogMode> {
+            if let Some(level) = config.level {
+                return vec![level; modes.len()];
+            }
+            modes
+                .iter()
+                .copied()
+                .filter(|mode| !self.forbidden.is_forbidden(&mode.to_string()))
+                .collect()
+        };
 
         match num_cmplog_cfgs {
             0 => {}
             1 => {
-                Self::apply_cmplog_1_to_3(cmds, config, &[CmplogMode::Transforms], rng);
+                Self::apply_cmplog_1_to_3(cmds, config, &allowed(&[CmplogMode::Transforms]), rng);
             }
             2 => {
                 Self::apply_cmplog_1_to_3(
                     cmds,
                     config,
-                    &[CmplogMode::Standard, CmplogMode::Transforms],
+                    &allowed(&[CmplogMode::Standard, CmplogMode::Transforms]),
                     rng,
                 );
             }
@@ -468,11 +711,11 @@ impl AFLStrategy {
                 Self::apply_cmplog_1_to_3(
                     cmds,
                     config,
-                    &[
+                    &allowed(&[
                         CmplogMode::Standard,
                         CmplogMode::Transforms,
                         CmplogMode::Extended,
-                    ],
+                    ]),
                     rng,
                 );
             }
@@ -514,11 +757,14 @@ impl AFLStrategy {
         }
 
         // Convert CmplogMode to string arguments with probabilities
-        let mode_args: Vec<(String, f64)> = config
-            .mode_distribution
-            .iter()
-            .map(|(mode, prob)| (mode.to_string(), *prob))
-            .collect();
+        let mode_args: Vec<(String, f64)> = match config.level {
+            Some(level) => vec![(level.to_string(), 1.0)],
+            None => config
+                .mode_distribution
+                .iter()
+                .map(|(mode, prob)| (mode.to_string(), *prob))
+                .collect(),
+        };
 
         // Apply modes exclusively to the selected range
         Self::apply_exclusive_args(&mut cmds[1..=num_cmplog_cfgs], &mode_args, rng);
@@ -577,6 +823,8 @@ pub struct AFLStrategyBuilder {
     optional_features: MiscFeatures,
     cmplog_config: Option<CmplogConfig>,
     cmpcov_config: Option<CmpcovConfig>,
+    forbidden: FlagBlacklist,
+    instances: InstanceFilter,
     is_ci_fuzzing: bool,
 }
 
@@ -621,6 +869,16 @@ impl AFLStrategyBuilder {
         self.cmpcov_config = Some(config);
     }
 
+    /// Never emits the flags of `forbidden`
+    pub fn with_forbidden_flags(&mut self, forbidden: FlagBlacklist) {
+        self.forbidden = forbidden;
+    }
+
+    /// Only places strategy flags on the instances of `instances`
+    pub fn with_instance_filter(&mut self, instances: InstanceFilter) {
+        self.instances = instances;
+    }
+
     fn with_ci(mut self) -> Self {
         self.is_ci_fuzzing = true;
         self
@@ -628,13 +886,25 @@ impl AFLStrategyBuilder {
 
     /// Build the final `AFLStrategy`
     pub fn build(self) -> AFLStrategy {
+        let forbidden = self.forbidden;
+        let cmplog_config = self.cmplog_config.map(|config| CmplogConfig {
+            mode_distribution: forbidden.filter(&config.mode_distribution),
+            ..config
+        });
+
         AFLStrategy {
-            mutation_modes: self.mutation_modes,
-            format_modes: self.format_modes,
-            power_schedules: self.power_schedules,
+            mutation_modes: forbidden.filter(&self.mutation_modes),
+            format_modes: forbidden.filter(&self.format_modes),
+            power_schedules: self
+                .power_schedules
+                .into_iter()
+                .filter(|schedule| !forbidden.is_forbidden(&schedule.to_string()))
+                .collect(),
             optional_features: self.optional_features,
-            cmplog_config: self.cmplog_config,
+            cmplog_config,
             cmpcov_config: self.cmpcov_config,
+            forbidden,
+            instances: self.instances,
             is_ci_fuzzing: self.is_ci_fuzzing,
         }
     }
@@ -975,6 +1245,7 @@ mod tests {
                 binary: PathBuf::from("/bin/cmplog"),
                 runner_ratio: 0.2,
                 mode_distribution: vec![(CmplogMode::Transforms, 1.0)],
+                level: None,
             });
             let mut strat = strategy_bld.build();
 
@@ -999,6 +1270,7 @@ mod tests {
                     (CmplogMode::Extended, 0.3),
                     (CmplogMode::Transforms, 0.3),
                 ],
+                level: None,
             });
             let mut strat = strategy_bld.build();
             strat.apply(&mut cmds, &mut rng, false);
@@ -1009,6 +1281,31 @@ mod tests {
                 .count();
             assert_eq!(cmplog_count, 6); // 60% of 10
         }
+
+        #[test]
+        fn test_cmplog_level_override() {
+            assert_eq!("2at".parse::<CmplogMode>(), Ok(CmplogMode::Transforms));
+            assert!("4".parse::<CmplogMode>().is_err());
+
+            for (runners, expected) in [(4, 1), (10, 3), (20, 6)] {
+                let mut rng = get_test_rng();
+                let mut cmds = create_test_cmds(runners);
+                let mut strategy_bld = AFLStrategy::builder(Mode::MultipleCores);
+                strategy_bld.with_cmplog(
+                    CmplogConfig::new(PathBuf::from("/bin/cmplog"))
+                        .with_level(Some(CmplogMode::Extended)),
+                );
+                strategy_bld.build().apply(&mut cmds, &mut rng, false);
+
+                let levels: Vec<&String> = cmds
+                    .iter()
+                    .flat_map(|cmd| &cmd.misc_afl_flags)
+                    .filter(|f| f.starts_with("-l "))
+                    .collect();
+                assert_eq!(levels.len(), expected, "{runners} runners");
+                assert!(levels.iter().all(|f| f.starts_with("-l 3")));
+            }
+        }
     }
 
     mod cmpcov_tests {
@@ -1051,6 +1348,7 @@ mod tests {
                 binary: PathBuf::from("/bin/cmplog"),
                 runner_ratio: 0.5,
                 mode_distribution: vec![(CmplogMode::Standard, 1.0)],
+                level: None,
             });
             strategy_bld.with_cmpcov(CmpcovConfig::new(PathBuf::from("/bin/cmpcov")));
             let mut strat = strategy_bld.build();
@@ -1102,4 +1400,153 @@ mod tests {
             assert_eq!(CmplogMode::Transforms.to_string(), "-l 2AT");
         }
     }
+
+    mod blacklist_tests {
+        use super::*;
+
+        fn blacklist(flags: &[&str], redistribute: bool) -> FlagBlacklist {
+            let flags: Vec<String> = flags.iter().map(ToString::to_string).collect();
+            FlagBlacklist::new(&flags, redistribute)
+        }
+
+        #[test]
+        fn test_matching_normalizes_whitespace() {
+            let forbidden = blacklist(&["  -a   text ", "-L 0"], false);
+            assert!(forbidden.is_forbidden("-a text"));
+            assert!(forbidden.is_forbidden("-L  0"));
+            assert!(!forbidden.is_forbidden("-a binary"));
+            assert!(!forbidden.is_forbidden("-a"));
+            assert!(blacklist(&["   "], false).is_empty());
+        }
+
+        #[test]
+        fn test_filter_drops_probability() {
+            let forbidden = blacklist(&["-a text"], false);
+            let filtered = forbidden.filter(&[(FormatMode::Binary, 0.3), (FormatMode::Text, 0.3)]);
+            assert_eq!(filtered.len(), 1);
+            assert!((filtered[0].1 - 0.3).abs() < f64::EPSILON);
+        }
+
+        #[test]
+        fn test_filter_redistributes_probability() {
+            let forbidden = blacklist(&["-l 3"], true);
+            let filtered = forbidden.filter(&CmplogConfig::default().mode_distribution);
+            assert_eq!(filtered.len(), 2);
+            // 0.7 and 0.2 are scaled by 1.0 / 0.9 to keep the total mass of the category
+            assert!((filtered[0].1 - 0.7 / 0.9).abs() < 1e-9);
+            assert!((filtered[1].1 - 0.2 / 0.9).abs() < 1e-9);
+            let total: f64 = filtered.iter().map(|(_, p)| p).sum();
+            assert!((total - 1.0).abs() < 1e-9);
+
+            // Nothing left to redistribute to
+            let all = blacklist(&["-P explore", "-P exploit"], true);
+            assert!(all
+                .filter(&[(MutationMode::Explore, 0.4), (MutationMode::Exploit, 0.2)])
+                .is_empty());
+        }
+
+        #[test]
+        fn test_forbidden_flags_are_never_emitted() {
+            let mut builder = AFLStrategy::builder(Mode::MultipleCores);
+            builder.with_forbidden_flags(blacklist(&["-a text", "-L 0", "-p rare"], true));
+            let mut strategy = builder.build();
+            assert_eq!(strategy.power_schedules.len(), 6);
+            assert!((strategy.format_modes[0].1 - 0.6).abs() < f64::EPSILON);
+
+            let mut cmds = create_test_cmds(32);
+            strategy.apply(&mut cmds, &mut get_test_rng(), false);
+            let flags: Vec<&String> = cmds.iter().flat_map(|c| &c.misc_afl_flags).collect();
+            assert!(flags.iter().any(|f| *f == "-a binary"));
+            assert!(flags.iter().any(|f| *f == "-Z"));
+            for forbidden in ["-a text", "-L 0", "-p rare"] {
+                assert!(
+                    !flags.iter().any(|f| *f == forbidden),
+                    "{forbidden} emitted"
+                );
+            }
+        }
+
+        #[test]
+        fn test_describe() {
+            assert!(FlagBlacklist::default().describe().is_none());
+            assert_eq!(
+                blacklist(&["-a text", "-L 0"], false).describe().unwrap(),
+                "-L 0, -a text (probability dropped)"
+            );
+        }
+    }
+
+    mod instance_filter_tests {
+        use super::*;
+
+        fn filter(include: &[&str], exclude: &[&str]) -> InstanceFilter {
+            let owned =
+                |ranges: &[&str]| ranges.iter().map(ToString::to_string).collect::<Vec<_>>();
+            InstanceFilter::new(&owned(include), &owned(exclude)).unwrap()
+        }
+
+        #[test]
+        fn test_parse_and_allows() {
+            let instances = filter(&["1-4", "7"], &["3"]);
+            let allowed: Vec<usize> = (0..9).filter(|&i| instances.allows(i)).collect();
+            assert_eq!(allowed, [1, 2, 4, 7]);
+            assert_eq!(instances.describe().unwrap(), "instances 1-4, 7 but 3");
+            assert!(filter(&[], &[]).is_unrestricted());
+            assert_eq!(
+                filter(&[], &["0"]).describe().unwrap(),
+                "all instances but 0"
+            );
+
+            for invalid in ["4-1", "a", "1-", "-3"] {
+                assert!(
+                    InstanceFilter::new(&[invalid.to_string()], &[]).is_err(),
+                    "{invalid}"
+                );
+            }
+        }
+
+        #[test]
+        fn test_flags_only_on_allowed_instances() {
+            let mut builder = AFLStrategy::builder(Mode::MultipleCores);
+            builder.with_cmplog(CmplogConfig::new(PathBuf::from("/bin/cmplog")));
+            builder.with_cmpcov(CmpcovConfig::new(PathBuf::from("/bin/cmpcov")));
+            builder.with_instance_filter(filter(&["8-15"], &["10"]));
+            let mut strategy = builder.build();
+
+            let mut cmds = create_test_cmds(16);
+            strategy.apply(&mut cmds, &mut get_test_rng(), false);
+            for (idx, cmd) in cmds.iter().enumerate() {
+                let has_flags = !cmd.misc_afl_flags.is_empty();
+                assert_eq!(has_flags, (8..16).contains(&idx) && idx != 10, "{idx}");
+            }
+            assert!(cmds.iter().any(|cmd| cmd
+                .misc_afl_flags
+                .iter()
+                .any(|f| f.contains("-c /bin/cmplog"))));
+            let cmpcov = strategy.get_cmpcov_indices();
+            assert!(!cmpcov.is_empty());
+            for &idx in cmpcov {
+                assert!((8..16).contains(&idx) && idx != 10, "{idx}");
+                assert_eq!(cmds[idx].target_binary, PathBuf::from("/bin/cmpcov"));
+            }
+        }
+
+        #[test]
+        fn test_unrestricted_filter_keeps_the_commands() {
+            let build = |instances: InstanceFilter| {
+                let mut builder = AFLStrategy::builder(Mode::MultipleCores);
+                builder.with_cmplog(CmplogConfig::new(PathBuf::from("/bin/cmplog")));
+                builder.with_instance_filter(instances);
+                let mut cmds = create_test_cmds(12);
+                builder.build().apply(&mut cmds, &mut get_test_rng(), false);
+                cmds.into_iter()
+                    .map(|cmd| cmd.misc_afl_flags)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                build(InstanceFilter::default()),
+                build(filter(&["0-11"], &[]))
+            );
+        }
+    }
 }
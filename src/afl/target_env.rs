@@ -0,0 +1,182 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::utils::system::shell_quote;
+
+/// Placeholder for the path of the current test case
+pub const INPUT_PLACEHOLDER: &str = "@@";
+/// `-f` template of harnesses that read the test case path from the environment if none is set
+pub const DEFAULT_ENV_INPUT_FILE: &str = "{instance_name}.cur";
+
+/// Environment of the harness from `[target] env` or `--target-env`
+///
+/// AFL++ hands the variables to the target via `AFL_TARGET_ENV`. A value containing `@@` makes
+/// the harness file-based: it is replaced with the `-f` input file of the instance while
+/// fuzzing and with the queue entry while collecting coverage.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(try_from = "BTreeMap<String, String>")]
+pub struct TargetEnv(BTreeMap<String, String>);
+
+impl TryFrom<BTreeMap<String, String>> for TargetEnv {
+    type Error = String;
+
+    fn try_from(vars: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        for (key, value) in &vars {
+            validate_var(key, value)?;
+        }
+        Ok(Self(vars))
+    }
+}
+
+/// Checks a single `KEY=VALUE` assignment, used as the clap value parser of `--target-env`
+///
+/// # Errors
+/// * If the assignment lacks a `=`, the key is no valid variable name or the value has whitespace
+pub fn parse_var(var: &str) -> Result<String, String> {
+    let (key, value) = var
+        .split_once('=')
+        .ok_or_else(|| format!("invalid target env '{var}', expected KEY=VALUE"))?;
+    validate_var(key, value)?;
+    Ok(var.to_string())
+}
+
+fn validate_var(key: &str, value: &str) -> Result<(), String> {
+    let is_valid_key = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_valid_key {
+        return Err(format!("invalid target env key '{key}'"));
+    }
+    // AFL_TARGET_ENV separates the variables by whitespace
+    if value.chars().any(char::is_whitespace) {
+        return Err(format!(
+            "the value of target env {key} must not contain whitespace"
+        ));
+    }
+    Ok(())
+}
+
+impl TargetEnv {
+    /// Builds the environment from `KEY=VALUE` assignments, later ones win
+    ///
+    /// # Errors
+    /// * If an assignment is invalid, see `parse_var`
+    pub fn from_vars(vars: &[String]) -> Result<Self> {
+        let mut env = BTreeMap::new();
+        for var in vars {
+            parse_var(var).map_err(|e| anyhow!(e))?;
+            let (key, value) = var.split_once('=').unwrap_or_default();
+            env.insert(key.to_string(), value.to_string());
+        }
+        Ok(Self(env))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The variables as unquoted `KEY=VALUE` assignments, sorted by key
+    pub fn vars(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect()
+    }
+
+    /// Whether the harness reads the test case path from the environment
+    pub fn takes_input(&self) -> bool {
+        self.0.values().any(|v| v.contains(INPUT_PLACEHOLDER))
+    }
+
+    /// The variables with `@@` replaced by `input`
+    pub fn resolve(&self, input: &str) -> Vec<(String, String)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.clone(), value.replace(INPUT_PLACEHOLDER, input)))
+            .collect()
+    }
+
+    /// The `AFL_TARGET_ENV` assignment handing the variables to the target, `@@` set to `input`
+    pub fn afl_target_env(&self, input: &str) -> String {
+        let vars = self
+            .resolve(input)
+            .into_iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("AFL_TARGET_ENV={}", shell_quote(&vars))
+    }
+
+    /// Rejects harnesses that take `@@` both as an argument and from the environment
+    ///
+    /// # Errors
+    /// * If `@@` is part of the environment and of `args`
+    pub fn check_target_args<'a>(&self, args: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        if !self.takes_input() || !args.into_iter().any(|a| a.contains(INPUT_PLACEHOLDER)) {
+            return Ok(());
+        }
+        let keys = self
+            .0
+            .iter()
+            .filter(|(_, value)| value.contains(INPUT_PLACEHOLDER))
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        bail!(
+            "{INPUT_PLACEHOLDER} is used in the target arguments and in the target env ({keys}), \
+             keep it in one place to tell how the harness receives test cases"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Config {
+        env: TargetEnv,
+    }
+
+    #[test]
+    fn test_target_env() {
+        let env = toml::from_str::<Config>("[env]\nINPUT_FILE = \"@@\"\nLOG_LEVEL = \"0\"\n")
+            .unwrap()
+            .env;
+        assert!(env.takes_input());
+        assert_eq!(
+            env.afl_target_env("/out/m_t.cur"),
+            "AFL_TARGET_ENV='INPUT_FILE=/out/m_t.cur LOG_LEVEL=0'"
+        );
+        assert_eq!(
+            env,
+            TargetEnv::from_vars(&[
+                "LOG_LEVEL=1".into(),
+                "INPUT_FILE=@@".into(),
+                "LOG_LEVEL=0".into()
+            ])
+            .unwrap()
+        );
+        assert!(!TargetEnv::from_vars(&["LOG_LEVEL=0".into()])
+            .unwrap()
+            .takes_input());
+
+        for invalid in ["[env]\n\"1X\" = \"a\"\n", "[env]\nX = \"a b\"\n"] {
+            assert!(toml::from_str::<Config>(invalid).is_err(), "{invalid}");
+        }
+        for invalid in ["INPUT_FILE", "=@@", "MY-VAR=1", "X=a b"] {
+            assert!(parse_var(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_input() {
+        let env = TargetEnv::from_vars(&["INPUT_FILE=@@".into()]).unwrap();
+        assert!(env.check_target_args(["-v"]).is_ok());
+        let err = env.check_target_args(["-v", "@@"]).unwrap_err().to_string();
+        assert!(err.contains("target env (INPUT_FILE)"), "{err}");
+
+        let env = TargetEnv::from_vars(&["LOG_LEVEL=0".into()]).unwrap();
+        assert!(env.check_target_args(["@@"]).is_ok());
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::tui::data_collection::{instance_dirs, DataFetcher};
+
+/// How the crashes are placed into the triage directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LinkMode {
+    /// Hardlink the crashes, copies them where the directories are on different filesystems
+    #[default]
+    Hardlink,
+    /// Symlink the crashes, the triage directory then depends on the campaign
+    Symlink,
+    /// Copy the crashes
+    Copy,
+}
+
+/// Result of collecting the crashes of a campaign
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CollectStats {
+    /// Number of crashes placed into the triage directory
+    pub collected: usize,
+    /// Number of crashes already present from an earlier run
+    pub existing: usize,
+}
+
+/// Name of a crash in the flat triage directory, prefixed with its instance
+///
+/// Instances of multi-target campaigns are named `<target>/<instance>`, the slash is replaced.
+pub fn triage_name(instance: &str, file_name: &str) -> String {
+    format!("{}_{file_name}", instance.replace('/', "_"))
+}
+
+/// Places the crashes of all instances in `output_dir` into the flat directory `into`
+///
+/// Crashes collected earlier are kept, so repeated runs only add the new ones. Files that are
+/// no crashes, like the `README.txt` AFL++ writes, are skipped.
+///
+/// # Errors
+/// * If `output_dir` holds no fuzzer instances
+/// * If `into` cannot be created or a crash cannot be linked or copied
+pub fn collect_crashes(output_dir: &Path, into: &Path, mode: LinkMode) -> Result<CollectStats> {
+    if instance_dirs(output_dir).is_empty() {
+        bail!("No fuzzer instances found in {}", output_dir.display());
+    }
+    fs::create_dir_all(into).with_context(|| format!("Failed to create {}", into.display()))?;
+
+    let mut stats = CollectStats::default();
+    for crash in DataFetcher::solutions(output_dir, "crashes") {
+        let Some(file_name) = crash.file_path.file_name() else {
+            continue;
+        };
+        let dest = into.join(triage_name(
+            &crash.fuzzer_name,
+            &file_name.to_string_lossy(),
+        ));
+        if dest.symlink_metadata().is_ok() {
+            stats.existing += 1;
+            continue;
+        }
+        place(&crash.file_path, &dest, mode).with_context(|| {
+            format!(
+                "Failed to place {} into {}",
+                crash.file_path.display(),
+                into.display()
+            )
+        })?;
+        stats.collected += 1;
+    }
+    Ok(stats)
+}
+
+fn place(src: &Path, dest: &Path, mode: LinkMode) -> Result<()> {
+    match mode {
+        LinkMode::Hardlink => {
+            // Hardlinks cannot cross filesystems
+            if fs::hard_link(src, dest).is_err() {
+                fs::copy(src, dest)?;
+            }
+        }
+        LinkMode::Symlink => {
+            let target: PathBuf = src.canonicalize()?;
+            std::os::unix::fs::symlink(target, dest)?;
+        }
+        LinkMode::Copy => {
+            fs::copy(src, dest)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::MetadataExt;
+    use tempfile::TempDir;
+
+    const CRASH: &str = "id:000000,sig:11,src:000001,time:42,execs:100,op:havoc,rep:2";
+
+    fn instance(output_dir: &Path, name: &str, crashes: &[&str]) {
+        let dir = output_dir.join(name);
+        fs::create_dir_all(dir.join("crashes")).unwrap();
+        fs::write(dir.join("fuzzer_stats"), "").unwrap();
+        fs::write(
+            dir.join("crashes/README.txt"),
+            "Command line used to find this crash",
+        )
+        .unwrap();
+        for crash in crashes {
+            fs::write(dir.join("crashes").join(crash), *crash).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_collect_crashes() {
+        let out = TempDir::new().unwrap();
+        instance(out.path(), "m_target", &[CRASH]);
+        instance(&out.path().join("libpng"), "s1_libpng", &[CRASH]);
+        instance(out.path(), "s2_target", &[]);
+        let triage = TempDir::new().unwrap();
+        let into = triage.path().join("flat");
+
+        let stats = collect_crashes(out.path(), &into, LinkMode::Hardlink).unwrap();
+        assert_eq!(
+            stats,
+            CollectStats {
+                collected: 2,
+                existing: 0
+            }
+        );
+        let linked = into.join(format!("m_target_{CRASH}"));
+        let original = out.path().join("m_target/crashes").join(CRASH);
+        assert_eq!(
+            fs::metadata(&linked).unwrap().ino(),
+            fs::metadata(&original).unwrap().ino()
+        );
+        assert!(into.join(format!("libpng_s1_libpng_{CRASH}")).is_file());
+        assert_eq!(fs::read_dir(&into).unwrap().count(), 2);
+
+        // New crashes are added to the existing ones
+        fs::write(
+            out.path().join("s2_target/crashes").join(CRASH),
+            "another crash",
+        )
+        .unwrap();
+        let stats = collect_crashes(out.path(), &into, LinkMode::Symlink).unwrap();
+        assert_eq!(
+            stats,
+            CollectStats {
+                collected: 1,
+                existing: 2
+            }
+        );
+        assert!(into
+            .join(format!("s2_target_{CRASH}"))
+            .symlink_metadata()
+            .unwrap()
+            .is_symlink());
+
+        assert!(collect_crashes(triage.path(), &into, LinkMode::Copy).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::{bail, Result};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// A warning raised during command generation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// The warning text, identical warnings are merged
+    pub message: String,
+    /// How often the warning was raised
+    pub count: usize,
+    /// Indices of the instances that raised the warning, empty for global warnings
+    pub instances: BTreeSet<usize>,
+}
+
+/// Collects the warnings of a generation run so each one is reported once
+#[derive(Debug, Default, Clone)]
+pub struct Warnings {
+    entries: Vec<Warning>,
+}
+
+impl Warnings {
+    /// Records a warning that applies to the whole generation
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.record(message.into(), None);
+    }
+
+    /// Records a warning raised by the instance at `idx`
+    pub fn warn_instance(&mut self, idx: usize, message: impl Into<String>) {
+        self.record(message.into(), Some(idx));
+    }
+
+    fn record(&mut self, message: String, idx: Option<usize>) {
+        let entry = if let Some(pos) = self.entries.iter().position(|w| w.message == message) {
+            &mut self.entries[pos]
+        } else {
+            self.entries.push(Warning {
+                message,
+                count: 0,
+                instances: BTreeSet::new(),
+            });
+            self.entries.last_mut().unwrap()
+        };
+        entry.count += 1;
+        entry.instances.extend(idx);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of distinct warnings
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Distinct warnings in the order they were first raised
+    pub fn iter(&self) -> impl Iterator<Item = &Warning> {
+        self.entries.iter()
+    }
+
+    /// Grouped summary of all warnings, `None` if there are none
+    pub fn summary(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut summary = format!(
+            "[!] Warning: {} distinct warning(s) during generation:",
+            self.len()
+        );
+        for warning in &self.entries {
+            // Continuation lines, e.g. a highlighted flag, are aligned with the message
+            let _ = write!(
+                summary,
+                "\n    - {}",
+                warning.message.replace('\n', "\n      ")
+            );
+            match (warning.count, warning.instances.is_empty()) {
+                (1, true) => {}
+                (count, true) => {
+                    let _ = write!(summary, " (x{count})");
+                }
+                (count, false) => {
+                    let _ = write!(
+                        summary,
+                        " (x{count}, instances {})",
+                        format_instances(&warning.instances)
+                    );
+                }
+            }
+        }
+        Some(summary)
+    }
+
+    /// Prints the grouped summary if any warning was raised
+    pub fn print(&self) {
+        if let Some(summary) = self.summary() {
+            println!("{summary}");
+        }
+    }
+
+    /// Fails if `strict` is set and any warning was raised
+    ///
+    /// # Errors
+    /// * If `strict` is set and the collector is not empty
+    pub fn check(&self, strict: bool) -> Result<()> {
+        if strict && !self.is_empty() {
+            bail!(
+                "{} warning(s) raised during generation and --fail-on-warnings is set",
+                self.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Formats instance indices with consecutive runs collapsed, e.g. `0-3, 7`
+pub(crate) fn format_instances(instances: &BTreeSet<usize>) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &idx in instances {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == idx => *end = idx,
+            _ => ranges.push((idx, idx)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warnings_are_deduplicated() {
+        let mut warnings = Warnings::default();
+        assert!(warnings.summary().is_none());
+        assert!(warnings.check(true).is_ok());
+
+        warnings.warn("global");
+        for idx in [0, 1, 2, 3, 7] {
+            warnings.warn_instance(idx, "per instance");
+        }
+        warnings.warn("global");
+
+        assert_eq!(warnings.len(), 2);
+        let first = warnings.iter().next().unwrap();
+        assert_eq!(first.message, "global");
+        assert_eq!(first.count, 2);
+        assert!(first.instances.is_empty());
+
+        let summary = warnings.summary().unwrap();
+        assert!(summary.contains("2 distinct warning(s)"));
+        assert!(summary.contains("- global (x2)"));
+        assert!(summary.contains("- per instance (x5, instances 0-3, 7)"));
+
+        assert!(warnings.check(false).is_ok());
+        assert!(warnings.check(true).is_err());
+    }
+}
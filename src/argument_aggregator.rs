@@ -1,12 +1,57 @@
-use crate::cli::{AddSeedArgs, ArgMerge, Args, CovArgs, GenArgs, RunArgs};
+use crate::afl::base_cfg::RawAflFlags;
+use crate::cli::{
+    legacy, preset, AddSeedArgs, ArgMerge, Args, CovArgs, GenArgs, ListArgs, ReplayArgs, RunArgs,
+    TargetEntry,
+};
+use crate::tui::{retention::RetentionPolicy, StabilityThresholds, TuiOptions};
+use crate::utils::{clock::Clock, duration::DurationOpt, snapshot::SnapshotPolicy};
 use anyhow::{bail, Context, Result};
-use std::{env, fs, path::PathBuf};
+use std::{
+    env, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
 
 static DEFAULT_AFL_CONFIG: &str = "aflr_cfg.toml";
+/// Environment variable holding either a config path or inline TOML
+static AFLR_CONFIG_ENV: &str = "AFLR_CONFIG";
+/// Config path that requests reading the config from stdin
+static STDIN_CONFIG: &str = "-";
+
+/// Origin of the loaded config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Path provided via `--config`
+    File(PathBuf),
+    /// TOML read from stdin via `--config -`
+    Stdin,
+    /// Path provided via `AFLR_CONFIG`
+    EnvPath(PathBuf),
+    /// Inline TOML provided via `AFLR_CONFIG`
+    EnvInline,
+    /// The default config file in the current working directory
+    Default(PathBuf),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "config file {}", path.display()),
+            Self::Stdin => write!(f, "stdin"),
+            Self::EnvPath(path) => write!(f, "{AFLR_CONFIG_ENV} path {}", path.display()),
+            Self::EnvInline => write!(f, "inline TOML in {AFLR_CONFIG_ENV}"),
+            Self::Default(path) => write!(f, "default config file {}", path.display()),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct ArgumentAggregator {
     config: Option<Args>,
+    /// The loaded config as TOML, kept to layer a preset underneath it
+    raw_config: Option<toml::Table>,
+    /// Where the loaded config came from, recorded along with the campaign
+    source: Option<ConfigSource>,
     default_config_path: PathBuf,
 }
 
@@ -17,6 +62,8 @@ impl Default for ArgumentAggregator {
             .join(DEFAULT_AFL_CONFIG);
         Self {
             config: None,
+            raw_config: None,
+            source: None,
             default_config_path: default_path,
         }
     }
@@ -27,59 +74,154 @@ impl ArgumentAggregator {
         Self::default()
     }
 
-    /// Load the config from the provided path
+    /// Load the config
+    ///
+    /// The config is taken from the first available source in this order:
+    /// `--config <path>`, `--config -` (stdin), `AFLR_CONFIG` and the default config file.
     ///
     /// # Errors
-    /// * If the config file cannot be read or parsed
+    /// * If the config cannot be read or parsed
     pub fn load(&mut self, config_path: Option<&PathBuf>) -> Result<()> {
-        let path = config_path.unwrap_or(&self.default_config_path);
-        if path.exists() {
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-            self.config = Some(
-                toml::from_str(&content)
-                    .with_context(|| format!("Failed to parse config file: {}", path.display()))?,
-            );
-        } else if config_path.is_some() {
-            bail!("Config file not found: {}", path.display());
+        self.load_from(
+            config_path,
+            env::var(AFLR_CONFIG_ENV).ok(),
+            &mut io::stdin().lock(),
+        )
+    }
+
+    /// Load the config from the provided sources
+    fn load_from(
+        &mut self,
+        config_path: Option<&PathBuf>,
+        env_config: Option<String>,
+        stdin: &mut dyn Read,
+    ) -> Result<()> {
+        let (source, content) = match config_path {
+            Some(path) if path.as_os_str() == STDIN_CONFIG => {
+                let mut content = String::new();
+                stdin
+                    .read_to_string(&mut content)
+                    .context("Failed to read config from stdin")?;
+                (ConfigSource::Stdin, content)
+            }
+            Some(path) => (ConfigSource::File(path.clone()), Self::read_file(path)?),
+            None => match env_config.filter(|c| !c.trim().is_empty()) {
+                Some(inline) if inline.trim_start().starts_with('[') => {
+                    (ConfigSource::EnvInline, inline)
+                }
+                Some(path) => {
+                    let path = PathBuf::from(path);
+                    let content = Self::read_file(&path)?;
+                    (ConfigSource::EnvPath(path), content)
+                }
+                None if self.default_config_path.exists() => (
+                    ConfigSource::Default(self.default_config_path.clone()),
+                    Self::read_file(&self.default_config_path)?,
+                ),
+                None => return Ok(()),
+            },
+        };
+
+        self.config = Some(
+            toml::from_str(&content)
+                .with_context(|| format!("Failed to parse config from {source}"))?,
+        );
+        self.raw_config = toml::from_str(&content).ok();
+        // Kept off stdout, which may carry generated commands or JSON
+        eprintln!("[+] Loaded config from {source}");
+        if legacy::is_legacy(&content) {
+            eprintln!("[*] Hint: the config uses a legacy layout, upgrade it with `aflr config migrate <path>`");
         }
+        self.source = Some(source);
+        Ok(())
+    }
+
+    /// Where the loaded config came from, `None` without a config
+    pub fn config_source(&self) -> Option<&ConfigSource> {
+        self.source.as_ref()
+    }
+
+    /// The loaded config as TOML, `None` without a config
+    pub fn raw_config(&self) -> Option<&toml::Table> {
+        self.raw_config.as_ref()
+    }
+
+    /// Layer the named preset underneath the loaded config
+    ///
+    /// Values of the preset only apply where neither the config nor the command line set
+    /// them. Works without a loaded config as well.
+    ///
+    /// # Errors
+    /// * If the preset does not exist or does not form a valid config
+    pub fn apply_preset(&mut self, name: Option<&str>) -> Result<()> {
+        let Some(name) = name else {
+            return Ok(());
+        };
+        let mut table = self.raw_config.clone().unwrap_or_default();
+        preset::apply(&mut table, name)?;
+        self.config = Some(
+            toml::Value::Table(table)
+                .try_into()
+                .with_context(|| format!("Failed to apply preset '{name}'"))?,
+        );
+        println!("[+] Applied preset '{name}'");
         Ok(())
     }
 
+    fn read_file(path: &Path) -> Result<String> {
+        if !path.exists() {
+            bail!("Config file not found: {}", path.display());
+        }
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))
+    }
+
     /// Merge the provided general arguments with the config
     ///
     /// # Errors
     /// * If the config cannot be merged
-    pub fn merge_gen_args(&self, args: &GenArgs) -> Result<(GenArgs, Option<String>)> {
+    pub fn merge_gen_args(&self, args: &GenArgs) -> Result<(GenArgs, RawAflFlags)> {
         let merged = self
             .config
             .as_ref()
             .map_or_else(|| args.clone(), |config| args.merge_with_config(config));
 
-        let raw_afl_flags = self
-            .config
-            .as_ref()
-            .and_then(|c| c.afl_cfg.afl_flags.clone());
-
-        Ok((merged, raw_afl_flags))
+        Ok((merged, self.raw_afl_flags()))
     }
 
     /// Merge the provided run arguments with the config
     ///
     /// # Errors
     /// * If the config cannot be merged
-    pub fn merge_run_args(&self, args: &RunArgs) -> Result<(RunArgs, Option<String>)> {
+    pub fn merge_run_args(&self, args: &RunArgs) -> Result<(RunArgs, RawAflFlags)> {
         let merged = self
             .config
             .as_ref()
             .map_or_else(|| args.clone(), |config| args.merge_with_config(config));
 
-        let raw_afl_flags = self
-            .config
+        Ok((merged, self.raw_afl_flags()))
+    }
+
+    /// The `[[targets]]` of a multi-target campaign, empty for a single target
+    pub fn targets(&self) -> &[TargetEntry] {
+        self.config
             .as_ref()
-            .and_then(|c| c.afl_cfg.afl_flags.clone());
+            .and_then(|config| config.targets.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Raw AFL++ flags of the config, empty fields are ignored
+    fn raw_afl_flags(&self) -> RawAflFlags {
+        let Some(config) = &self.config else {
+            return RawAflFlags::default();
+        };
+        let non_empty = |flags: &Option<String>| flags.clone().filter(|f| !f.trim().is_empty());
 
-        Ok((merged, raw_afl_flags))
+        RawAflFlags {
+            all: non_empty(&config.afl_cfg.afl_flags),
+            master: non_empty(&config.afl_cfg.afl_flags_master),
+            secondary: non_empty(&config.afl_cfg.afl_flags_secondary),
+        }
     }
 
     /// Merge the provided coverage arguments with the config
@@ -93,6 +235,69 @@ impl ArgumentAggregator {
             .map_or_else(|| args.clone(), |config| args.merge_with_config(config)))
     }
 
+    /// Merge the provided list arguments with the config
+    pub fn merge_list_args(&self, args: &ListArgs) -> ListArgs {
+        self.config
+            .as_ref()
+            .map_or_else(|| args.clone(), |config| args.merge_with_config(config))
+    }
+
+    /// Merge the provided replay arguments with the config
+    pub fn merge_replay_args(&self, args: &ReplayArgs) -> ReplayArgs {
+        self.config
+            .as_ref()
+            .map_or_else(|| args.clone(), |config| args.merge_with_config(config))
+    }
+
+    /// TUI options from the config, callers fill in their CLI specific options
+    ///
+    /// # Errors
+    /// * If the configured stability thresholds are not in descending order
+    /// * If the configured starvation ratio is not within (0, 1)
+    /// * If the configured stability drop is not within (0, 100]
+    pub fn tui_options(&self) -> Result<TuiOptions> {
+        let Some(config) = &self.config else {
+            return Ok(TuiOptions::default());
+        };
+        let starvation_ratio = config.misc.starvation_ratio;
+        if let Some(ratio) = starvation_ratio.filter(|ratio| !(*ratio > 0.0 && *ratio < 1.0)) {
+            bail!("[misc] starvation_ratio must be within (0, 1), got {ratio}");
+        }
+        let stability_drop = config.misc.stability_drop;
+        if let Some(drop) = stability_drop.filter(|drop| !(*drop > 0.0 && *drop <= 100.0)) {
+            bail!("[misc] stability_drop must be within (0, 100] percentage points, got {drop}");
+        }
+
+        Ok(TuiOptions {
+            retention: RetentionPolicy {
+                max_crashes: config.afl_cfg.max_crashes_per_instance,
+                max_hangs: config.afl_cfg.max_hangs_per_instance,
+                archive: config.afl_cfg.archive_pruned.unwrap_or(false),
+                clock: Clock::from_env(),
+            },
+            snapshots: SnapshotPolicy {
+                interval: config.misc.snapshot_interval.map(DurationOpt::as_duration),
+                keep: config.misc.snapshot_keep,
+                compress: config.misc.snapshot_compress.unwrap_or(false),
+            },
+            slow_exec_threshold: config.misc.slow_exec_threshold,
+            stall_threshold: config.misc.stall_threshold.map(DurationOpt::as_duration),
+            startup_grace: config.misc.startup_grace.map(DurationOpt::as_duration),
+            starvation_ratio,
+            stability_drop,
+            stability: StabilityThresholds::with_overrides(
+                config.misc.stability_caution,
+                config.misc.stability_warn,
+                config.misc.stability_error,
+            )?,
+            control_socket: config.misc.control_socket.unwrap_or(false),
+            log_capacity: config.misc.log_capacity,
+            follow_new_crashes: config.misc.follow_new_crashes.unwrap_or(false),
+            crash_viewer: config.misc.crash_viewer.clone().filter(|v| !v.is_empty()),
+            ..TuiOptions::default()
+        })
+    }
+
     /// Merge the provided adding seeds arguments with the config
     ///
     /// # Errors
@@ -104,3 +309,174 @@ impl ArgumentAggregator {
             .map_or_else(|| args.clone(), |config| args.merge_with_config(config)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner_count::RunnerCount;
+    use tempfile::TempDir;
+
+    fn config(runners: &str) -> String {
+        format!("[target]\n[coverage]\n[afl_cfg]\nrunners = {runners}\n[session]\n[misc]\n")
+    }
+
+    fn aggregator(dir: &TempDir) -> ArgumentAggregator {
+        ArgumentAggregator {
+            config: None,
+            raw_config: None,
+            source: None,
+            default_config_path: dir.path().join(DEFAULT_AFL_CONFIG),
+        }
+    }
+
+    fn runners(aggregator: &ArgumentAggregator) -> Option<u32> {
+        aggregator
+            .config
+            .as_ref()
+            .and_then(|c| c.afl_cfg.runners)
+            .map(RunnerCount::resolve)
+    }
+
+    #[test]
+    fn test_load_from_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("cfg.toml");
+        fs::write(&path, config("4")).unwrap();
+
+        let mut agg = aggregator(&tmp);
+        agg.load_from(Some(&path), Some(config("2")), &mut io::empty())
+            .unwrap();
+        assert_eq!(runners(&agg), Some(4));
+
+        let missing = tmp.path().join("missing.toml");
+        assert!(agg
+            .load_from(Some(&missing), None, &mut io::empty())
+            .is_err());
+    }
+
+    #[test]
+    fn test_load_from_stdin() {
+        let tmp = TempDir::new().unwrap();
+        let mut agg = aggregator(&tmp);
+        agg.load_from(
+            Some(&PathBuf::from(STDIN_CONFIG)),
+            Some(config("2")),
+            &mut config("4").as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(runners(&agg), Some(4));
+        assert_eq!(agg.config_source(), Some(&ConfigSource::Stdin));
+
+        let err = agg
+            .load_from(
+                Some(&PathBuf::from(STDIN_CONFIG)),
+                None,
+                &mut "[afl_cfg".as_bytes(),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("stdin"));
+    }
+
+    #[test]
+    fn test_load_from_env() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("env.toml");
+        fs::write(&path, config("4")).unwrap();
+        // The default file must lose against the environment variable
+        fs::write(tmp.path().join(DEFAULT_AFL_CONFIG), config("8")).unwrap();
+
+        let mut agg = aggregator(&tmp);
+        agg.load_from(None, Some(path.display().to_string()), &mut io::empty())
+            .unwrap();
+        assert_eq!(runners(&agg), Some(4));
+        assert_eq!(agg.config_source(), Some(&ConfigSource::EnvPath(path)));
+
+        let mut agg = aggregator(&tmp);
+        agg.load_from(None, Some(format!("  {}", config("4"))), &mut io::empty())
+            .unwrap();
+        assert_eq!(runners(&agg), Some(4));
+        assert_eq!(agg.config_source(), Some(&ConfigSource::EnvInline));
+
+        let err = aggregator(&tmp)
+            .load_from(None, Some(config("")), &mut io::empty())
+            .unwrap_err();
+        assert!(err.to_string().contains(AFLR_CONFIG_ENV));
+
+        let mut agg = aggregator(&tmp);
+        agg.load_from(None, None, &mut io::empty()).unwrap();
+        assert_eq!(runners(&agg), Some(8));
+        assert!(matches!(
+            agg.config_source(),
+            Some(ConfigSource::Default(_))
+        ));
+    }
+
+    #[test]
+    fn test_tui_options() {
+        let tmp = TempDir::new().unwrap();
+        let mut agg = aggregator(&tmp);
+        assert!(agg.tui_options().unwrap().slow_exec_threshold.is_none());
+
+        let cfg = format!(
+            "{}slow_exec_threshold = 50.0\nstability_caution = 70.0\nstability_warn = 65.0\nsnapshot_interval = \"6h\"\n",
+            config("1")
+        );
+        agg.load_from(None, Some(cfg), &mut io::empty()).unwrap();
+        let options = agg.tui_options().unwrap();
+        assert_eq!(options.slow_exec_threshold, Some(50.0));
+        assert!((options.stability.caution - 70.0).abs() < f64::EPSILON);
+        assert!(!options.retention.is_enabled());
+        assert_eq!(options.log_capacity, None);
+        assert_eq!(
+            options.snapshots.interval,
+            Some(std::time::Duration::from_secs(6 * 3600))
+        );
+        assert_eq!(options.snapshots.keep(), 4);
+
+        let cfg = format!("{}stability_warn = 95.0\n", config("1"));
+        agg.load_from(None, Some(cfg), &mut io::empty()).unwrap();
+        assert!(agg.tui_options().is_err());
+    }
+
+    #[test]
+    fn test_apply_preset() {
+        let tmp = TempDir::new().unwrap();
+        let mut agg = aggregator(&tmp);
+        agg.apply_preset(Some("quick-smoke")).unwrap();
+        assert_eq!(runners(&agg), Some(2));
+
+        let mut agg = aggregator(&tmp);
+        let cfg = format!(
+            "{}[presets.big.afl_cfg]\nrunners = 32\nmap_size = 65536\n",
+            config("4")
+        );
+        agg.load_from(None, Some(cfg), &mut io::empty()).unwrap();
+        agg.apply_preset(None).unwrap();
+        assert_eq!(runners(&agg), Some(4));
+        agg.apply_preset(Some("big")).unwrap();
+        assert_eq!(runners(&agg), Some(4));
+        assert!(agg.config.as_ref().unwrap().afl_cfg.map_size.is_some());
+
+        let merged = agg.merge_gen_args(&GenArgs::default()).unwrap().0;
+        assert_eq!(merged.runners, Some(RunnerCount::Fixed(4)));
+        let cli = GenArgs {
+            runners: Some(RunnerCount::Fixed(8)),
+            ..GenArgs::default()
+        };
+        assert_eq!(
+            agg.merge_gen_args(&cli).unwrap().0.runners,
+            Some(RunnerCount::Fixed(8))
+        );
+
+        assert!(agg.apply_preset(Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_load_without_config() {
+        let tmp = TempDir::new().unwrap();
+        let mut agg = aggregator(&tmp);
+        agg.load_from(None, Some(String::new()), &mut io::empty())
+            .unwrap();
+        assert!(agg.config.is_none());
+    }
+}
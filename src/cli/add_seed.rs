@@ -17,7 +17,7 @@ pub struct AddSeedArgs {
     )]
     pub output_dir: Option<PathBuf>,
     /// Path to a TOML config file
-    #[arg(long, help = "Path to TOML config file")]
+    #[arg(long, help = "Path to TOML config file, '-' reads it from stdin")]
     pub config: Option<PathBuf>,
     /// Seed(s) to add to the corpus
     #[arg(long, help = "Seed(s) to add to the corpus", value_name = "SEED(S)")]
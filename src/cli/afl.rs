@@ -1,11 +1,12 @@
 use serde::Deserialize;
 
-use crate::afl::mode::Mode;
+use crate::afl::{llvm_env::LlvmEnv, map_size::MapSize, mode::Mode, strategies::CmplogMode};
+use crate::utils::{duration::DurationOpt, runner_count::RunnerCount};
 
 #[derive(Deserialize, Default, Debug, Clone)]
 pub struct AflArgs {
-    /// Number of AFL++ runners
-    pub runners: Option<u32>,
+    /// Number of AFL++ runners or a percentage of the available cores
+    pub runners: Option<RunnerCount>,
     /// Path to the AFL++ binary
     pub afl_binary: Option<String>,
     /// Path to the seed directory
@@ -14,8 +15,59 @@ pub struct AflArgs {
     pub solution_dir: Option<String>,
     /// Path to the dictionary
     pub dictionary: Option<String>,
+    /// Fraction of instances that get the dictionary, all of them if unset
+    pub dictionary_ratio: Option<f64>,
+    /// Never pass the dictionary to the CMPCOV instances
+    pub dictionary_skip_cmpcov: Option<bool>,
     /// Additional AFL++ flags
     pub afl_flags: Option<String>,
+    /// Additional AFL++ flags for the main instance only
+    pub afl_flags_master: Option<String>,
+    /// Additional AFL++ flags for the secondary instances only
+    pub afl_flags_secondary: Option<String>,
+    /// Fail on unknown flags in the raw AFL++ flags instead of warning about them
+    pub strict_flags: Option<bool>,
     /// Mode to generate commands
     pub mode: Option<Mode>,
+    /// Global `AFL_MAP_SIZE` (`"auto"` or a number)
+    pub map_size: Option<MapSize>,
+    /// Minimum length of generated test cases in bytes, passed as `-g`
+    pub min_len: Option<u32>,
+    /// Maximum length of generated test cases in bytes, passed as `-G`
+    pub max_len: Option<u32>,
+    /// Time between two syncs of the secondary instances, whole minutes (`AFL_SYNC_TIME`)
+    pub sync_time: Option<DurationOpt>,
+    /// Maximum amount of crashes kept per instance, older ones are pruned
+    pub max_crashes_per_instance: Option<usize>,
+    /// Maximum amount of hangs kept per instance, older ones are pruned
+    pub max_hangs_per_instance: Option<usize>,
+    /// Archive pruned crashes/hangs into a tarball before deleting them
+    pub archive_pruned: Option<bool>,
+    /// Executable that AFL++ runs via `-I` whenever a new crash is saved
+    pub on_new_crash_cmd: Option<String>,
+    /// Attach the crash hook to all instances instead of the main instance only
+    pub on_new_crash_all: Option<bool>,
+    /// Custom mutator library (or `;`-separated libraries) exported as `AFL_CUSTOM_MUTATOR_LIBRARY`
+    pub custom_mutator: Option<String>,
+    /// Fraction of instances that load the custom mutator, all of them if unset
+    pub custom_mutator_ratio: Option<f64>,
+    /// CMPLOG level (`"2"`, `"3"` or `"2AT"`) applied to all CMPLOG instances
+    pub cmplog_level: Option<CmplogMode>,
+    /// `AFL_LLVM_*` variables matching the instrumentation of the harness
+    pub llvm_env: Option<LlvmEnv>,
+    /// Adjustments to the command generation strategy
+    pub strategy: Option<StrategyArgs>,
+}
+
+/// `[afl_cfg.strategy]` configuration
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct StrategyArgs {
+    /// Strategy flags that are never emitted, e.g. `"-a text"`
+    pub forbid_flags: Option<Vec<String>>,
+    /// Redistribute the probability of forbidden flags onto the remaining options of their category
+    pub redistribute: Option<bool>,
+    /// Index ranges of the instances that receive strategy flags, e.g. `["1-4", "7"]`
+    pub include_instances: Option<Vec<String>>,
+    /// Index ranges of the instances that never receive strategy flags
+    pub exclude_instances: Option<Vec<String>>,
 }
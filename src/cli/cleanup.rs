@@ -0,0 +1,18 @@
+use clap::Args;
+
+use crate::utils::duration::DurationOpt;
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct CleanupArgs {
+    /// Minimum age of untracked leftovers
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Only remove untagged RAMDisks and temporary files older than this, e.g. '30m' or '2h' [default: 24h]"
+    )]
+    pub older_than: Option<DurationOpt>,
+
+    /// Only list the leftovers
+    #[arg(long, help = "List what would be removed without removing it")]
+    pub dry_run: bool,
+}
@@ -0,0 +1,20 @@
+use clap::{Args, ValueHint};
+use std::path::PathBuf;
+
+use super::constants::AFL_OUTPUT;
+use crate::afl::triage::LinkMode;
+
+#[derive(Args, Clone, Debug)]
+pub struct CollectCrashesArgs {
+    /// Output directory of the campaign
+    #[arg(default_value = AFL_OUTPUT, value_hint = ValueHint::DirPath)]
+    pub output_dir: PathBuf,
+
+    /// Flat directory to place the crashes of all instances in
+    #[arg(long, required = true, value_hint = ValueHint::DirPath)]
+    pub into: PathBuf,
+
+    /// How the crashes are placed into the directory
+    #[arg(long, value_enum, default_value_t = LinkMode::Hardlink)]
+    pub mode: LinkMode,
+}
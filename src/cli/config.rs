@@ -0,0 +1,30 @@
+use clap::{Args, Subcommand, ValueHint};
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub cmd: ConfigCommands,
+}
+
+/// Config subcommands
+#[derive(Subcommand, Clone, Debug)]
+pub enum ConfigCommands {
+    /// Upgrades a config of an older layout to the current schema
+    Migrate(MigrateArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct MigrateArgs {
+    /// Config file to migrate
+    #[arg(required = true, value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+
+    /// Write the migrated config here instead of replacing the input (a `.bak` copy is kept)
+    #[arg(short, long, value_hint = ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+
+    /// Only print the migrated config, don't write anything
+    #[arg(long, conflicts_with = "output")]
+    pub dry_run: bool,
+}
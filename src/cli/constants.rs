@@ -1,4 +1,14 @@
+use crate::utils::duration::DurationOpt;
+
 /// Default corpus directory
 pub const AFL_CORPUS: &str = "/tmp/afl_input";
 /// Default output directory
 pub const AFL_OUTPUT: &str = "/tmp/afl_output";
+/// Default interval between two mirror passes
+pub const MIRROR_INTERVAL: DurationOpt = DurationOpt::from_secs(60);
+/// Directory on the `RAMDisk` the instances write to with `run --output-on-ramdisk`
+pub const RAMDISK_OUTPUT_DIR: &str = "afl_output";
+/// Default minimum age of untagged leftovers `cleanup` removes
+pub const CLEANUP_MIN_AGE: DurationOpt = DurationOpt::from_secs(24 * 60 * 60);
+/// Default timeout for a single replay execution
+pub const REPLAY_TIMEOUT: DurationOpt = DurationOpt::from_secs(10);
@@ -1,5 +1,8 @@
 use clap::{ArgAction, Args};
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
+
+use crate::afl::coverage::InputMode;
+use crate::afl::target_env::parse_var;
 
 #[derive(Args, Clone, Debug, Default)]
 pub struct CovArgs {
@@ -35,7 +38,71 @@ pub struct CovArgs {
     #[arg(short = 'r', long, help = "Miscellaneous llvm-cov report arguments")]
     pub report_args: Option<Vec<String>>,
 
+    /// Force how test cases are passed to the target
+    #[arg(
+        long,
+        value_enum,
+        help = "Force passing test cases via stdin, file or target env (default: inferred from @@)"
+    )]
+    pub input_mode: Option<InputMode>,
+
+    /// Environment variables of the harness, `@@` is replaced with each test case
+    #[arg(
+        long,
+        value_name = "KEY=VALUE",
+        value_parser = parse_var,
+        help = "Environment variable for the harness (repeatable), 'INPUT_FILE=@@' passes the test case path in INPUT_FILE"
+    )]
+    pub target_env: Vec<String>,
+
+    /// Isolate the target while replaying test cases
+    #[arg(
+        long,
+        help = "Run the target in a sandbox (bwrap/firejail/unshare + rlimits, no network)"
+    )]
+    pub sandbox: bool,
+
+    /// Report the regions covered by every instance in unified mode
+    #[arg(
+        long,
+        help = "Print the regions covered by every instance (unified report only)",
+        conflicts_with = "split_report"
+    )]
+    pub attribute_instances: bool,
+
+    /// Approximate the regions only the top contributors cover
+    #[arg(
+        long,
+        help = "Also approximate the regions unique to the top contributors (one extra merge each)",
+        conflicts_with = "split_report"
+    )]
+    pub attribute_unique: bool,
+
+    /// Profraw files merged per llvm-profdata invocation
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Profraw files merged per llvm-profdata invocation, lower it if merging runs out of memory [default: 1000]"
+    )]
+    pub merge_batch_size: Option<NonZeroUsize>,
+
+    /// Write the raw llvm-cov export JSON for custom tooling
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Also write the raw `llvm-cov export` JSON of the merged profile to PATH (split reports append the instance index)"
+    )]
+    pub json_export: Option<PathBuf>,
+
+    /// Directory for the derived artifacts if the output directory is read-only
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Where profdata and reports go if the output directory is read-only [default: new temp dir]"
+    )]
+    pub work_dir: Option<PathBuf>,
+
     /// Path to a TOML config file
-    #[arg(long, help = "Path to TOML config file")]
+    #[arg(long, help = "Path to TOML config file, '-' reads it from stdin")]
     pub config: Option<PathBuf>,
 }
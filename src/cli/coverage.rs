@@ -1,4 +1,7 @@
 use serde::Deserialize;
+use std::num::NonZeroUsize;
+
+use crate::afl::coverage::InputMode;
 
 #[derive(Deserialize, Default, Debug, Clone)]
 pub struct CoverageArgs {
@@ -10,4 +13,14 @@ pub struct CoverageArgs {
     pub misc_show_args: Option<Vec<String>>,
     /// Misc llvm-cov report arguments
     pub misc_report_args: Option<Vec<String>>,
+    /// Force passing test cases via stdin or file
+    pub input_mode: Option<InputMode>,
+    /// Run the target in a sandbox while replaying test cases
+    pub sandbox: Option<bool>,
+    /// Profraw files merged per llvm-profdata invocation
+    pub merge_batch_size: Option<NonZeroUsize>,
+    /// File the raw llvm-cov export JSON is written to
+    pub json_export: Option<String>,
+    /// Directory for the derived artifacts if the output directory is read-only
+    pub work_dir: Option<String>,
 }
@@ -1,7 +1,40 @@
 use clap::{ArgAction, Args};
 use std::path::PathBuf;
 
-use crate::afl::mode::Mode;
+use crate::afl::{
+    harness::HarnessBinary, llvm_env::LlvmEnv, map_size::MapSize, mode::Mode,
+    strategies::CmplogMode, target_env::parse_var,
+};
+use crate::utils::{duration::DurationOpt, runner_count::RunnerCount};
+
+/// Arguments of `aflr gen`, the generation arguments plus what only `gen` offers
+#[derive(Args, Clone, Debug, Default)]
+pub struct GenCommandArgs {
+    /// Arguments for generating the commands
+    #[command(flatten)]
+    pub gen_args: GenArgs,
+
+    /// Previous commands to compare the generated ones against
+    #[arg(
+        long,
+        value_name = "COMMANDS_JSON|OUTPUT_DIR",
+        help = "Show how the generated commands differ from a JSON array of commands, a campaign record or the output directory of a previous campaign"
+    )]
+    pub diff: Option<PathBuf>,
+
+    /// Print the diff as JSON
+    #[arg(long, requires = "diff", help = "Print the --diff result as JSON")]
+    pub json: bool,
+
+    /// Generate the commands repeatedly and report timing and determinism
+    #[arg(
+        long,
+        value_name = "RUNS",
+        conflicts_with = "diff",
+        help = "Generate the commands RUNS times with the same seed, report how long it takes and whether every run is identical"
+    )]
+    pub bench: Option<usize>,
+}
 
 #[derive(Args, Clone, Debug, Default)]
 pub struct GenArgs {
@@ -25,6 +58,22 @@ pub struct GenArgs {
     )]
     pub cmpc_target: Option<PathBuf>,
 
+    /// Detect the auxiliary builds next to the target
+    #[arg(
+        long,
+        help = "Pick up unset CMPLOG/CMPCOV/sanitizer builds next to the target, e.g. 'target.cmplog' or 'laf/target'",
+        action = ArgAction::SetTrue
+    )]
+    pub auto_detect_builds: bool,
+
+    /// Run the sanitizer binary on a secondary instance
+    #[arg(
+        long,
+        help = "Run the sanitizer binary on a secondary instead of the main instance, which keeps the plain target on -M",
+        action = ArgAction::SetTrue
+    )]
+    pub san_on_secondary: bool,
+
     /// Target binary arguments
     #[arg(help = "Target binary arguments, including @@ if needed", raw = true)]
     pub target_args: Option<Vec<String>>,
@@ -34,9 +83,9 @@ pub struct GenArgs {
         short = 'n',
         long,
         value_name = "NUM_PROCS",
-        help = "Amount of processes to spin up"
+        help = "Amount of processes to spin up, or a percentage of the available cores like '75%'"
     )]
-    pub runners: Option<u32>,
+    pub runners: Option<RunnerCount>,
 
     /// Corpus directory
     #[arg(short = 'i', long, help = "Seed corpus directory")]
@@ -55,14 +104,38 @@ pub struct GenArgs {
     )]
     pub dictionary: Option<PathBuf>,
 
+    /// Fraction of instances that get the dictionary
+    #[arg(
+        long,
+        value_name = "RATIO",
+        help = "Only pass the dictionary to this fraction (0.0-1.0) of the instances, the main instance always gets it [default: 1.0]"
+    )]
+    pub dictionary_ratio: Option<f64>,
+
+    /// Keep the dictionary away from the CMPCOV instances
+    #[arg(
+        long,
+        help = "Never pass the dictionary to the CMPCOV instances",
+        action = ArgAction::SetTrue
+    )]
+    pub dictionary_skip_cmpcov: bool,
+
     /// AFL-Fuzz binary
     #[arg(short = 'b', long, help = "Custom path to 'afl-fuzz' binary")]
     pub afl_binary: Option<String>,
 
     /// Path to a TOML config file
-    #[arg(long, help = "Path to TOML config file")]
+    #[arg(long, help = "Path to TOML config file, '-' reads it from stdin")]
     pub config: Option<PathBuf>,
 
+    /// Named preset the config is layered on top of
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Preset that fills in unset config values: quick-smoke, deep-overnight, ci or a [presets.<name>] table"
+    )]
+    pub preset: Option<String>,
+
     /// Select the mode that is used for command generation
     #[arg(
         value_enum,
@@ -76,14 +149,177 @@ pub struct GenArgs {
     /// Seed to seed `AFL_Runners` internal PRNG
     #[arg(
         long,
-        help = "Seed for AFL_Runners PRNG for deterministic command generation",
+        help = "Seed for AFL_Runners PRNG for deterministic command generation (random and printed if unset)",
         value_name = "AFLR_SEED"
     )]
     pub seed: Option<u64>,
 
     /// Toggle to relay the seed to AFL++ as well
-    #[arg(long, help = "Forward AFLR seed to AFL++", action = ArgAction::SetTrue, requires="seed")]
+    #[arg(long, help = "Forward AFLR seed to AFL++, each instance gets a distinct seed derived from it", action = ArgAction::SetTrue, requires="seed")]
     pub use_seed_afl: bool,
+
+    /// Fraction of instances that receive the AFL++ seed
+    #[arg(
+        long,
+        value_name = "RATIO",
+        help = "Only relay the seed to this fraction (0.0-1.0) of the instances, the others keep a random AFL++ seed",
+        requires = "use_seed_afl"
+    )]
+    pub seed_afl_ratio: Option<f64>,
+
+    /// Disable all randomized strategies for reproducible command generation
+    #[arg(
+        long,
+        help = "Generate commands that only depend on the inputs (for reproducibility, not performance)",
+        action = ArgAction::SetTrue
+    )]
+    pub deterministic: bool,
+
+    /// Global `AFL_MAP_SIZE` applied to all commands
+    #[arg(
+        long,
+        value_name = "auto|SIZE",
+        help = "AFL_MAP_SIZE for all instances, 'auto' probes the target with afl-showmap"
+    )]
+    pub map_size: Option<MapSize>,
+
+    /// Minimum length of generated test cases
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Minimum length of generated test cases for all instances (-g)"
+    )]
+    pub min_len: Option<u32>,
+
+    /// Maximum length of generated test cases
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Maximum length of generated test cases for all instances (-G)"
+    )]
+    pub max_len: Option<u32>,
+
+    /// Time between two syncs of the secondary instances
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "AFL_SYNC_TIME of the secondary instances in whole minutes, e.g. '60m' or '2h', bare numbers are seconds [AFL++ default: 30m]"
+    )]
+    pub sync_time: Option<DurationOpt>,
+
+    /// Strategy flags that are never emitted
+    #[arg(
+        long = "forbid-flag",
+        value_name = "FLAG",
+        allow_hyphen_values = true,
+        help = "Strategy flag that is never emitted, e.g. '-a text' (repeatable)"
+    )]
+    pub forbid_flags: Vec<String>,
+
+    /// Redistribute the probability of forbidden flags within their category
+    #[arg(
+        long,
+        help = "Redistribute the probability of forbidden flags onto the remaining options of their category",
+        action = ArgAction::SetTrue
+    )]
+    pub redistribute_forbidden: bool,
+
+    /// CMPLOG level forced on all CMPLOG instances
+    #[arg(
+        long,
+        value_name = "2|3|2AT",
+        help = "Apply this -l level to all CMPLOG instances instead of the randomized distribution"
+    )]
+    pub cmplog_level: Option<CmplogMode>,
+
+    /// Reject unknown flags in the raw AFL++ flags
+    #[arg(
+        long,
+        help = "Fail on unknown flags in afl_flags instead of passing them through with a warning",
+        action = ArgAction::SetTrue
+    )]
+    pub strict_flags: bool,
+
+    /// Script AFL++ runs whenever a new crash is saved
+    #[arg(
+        long,
+        value_name = "SCRIPT",
+        help = "Executable that AFL++ runs via -I whenever the main instance saves a new crash"
+    )]
+    pub on_new_crash_cmd: Option<PathBuf>,
+
+    /// Attach the crash hook to every instance instead of the main one
+    #[arg(
+        long,
+        help = "Attach the --on-new-crash-cmd hook to all instances instead of the main instance only",
+        action = ArgAction::SetTrue,
+        requires = "on_new_crash_cmd"
+    )]
+    pub on_new_crash_all: bool,
+
+    /// Custom mutator library loaded by the instances
+    #[arg(
+        long,
+        value_name = "LIB",
+        help = "Custom mutator library (or ';'-separated libraries) set as AFL_CUSTOM_MUTATOR_LIBRARY, disables MOpt"
+    )]
+    pub custom_mutator: Option<String>,
+
+    /// Fraction of instances that load the custom mutator
+    #[arg(
+        long,
+        value_name = "RATIO",
+        help = "Only load the custom mutator on this fraction (0.0-1.0) of the instances [default: 1.0]"
+    )]
+    pub custom_mutator_ratio: Option<f64>,
+
+    /// Template of a per-instance input file AFL++ writes test cases to via `-f`
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        help = "Per-instance -f input file, e.g. '/tmp/in_{instance_index}.bin', also supports {instance_name}. Relative paths go into the RAMDisk or output directory"
+    )]
+    pub fixed_input_file: Option<String>,
+
+    /// Environment variables of the harness, `@@` is replaced with the input file
+    #[arg(
+        long,
+        value_name = "KEY=VALUE",
+        value_parser = parse_var,
+        help = "Environment variable for the harness (repeatable), e.g. 'INPUT_FILE=@@' for harnesses reading the test case path from the environment"
+    )]
+    pub target_env: Vec<String>,
+
+    /// Harness binaries whose shared library dependencies are not checked
+    #[arg(
+        long,
+        value_enum,
+        value_name = "BINARY",
+        value_delimiter = ',',
+        help = "Skip the interpreter and shared library check of these binaries, e.g. for static builds"
+    )]
+    pub skip_dep_check: Vec<HarnessBinary>,
+
+    /// Instances that receive strategy flags, `[afl_cfg.strategy] include_instances`, config only
+    #[arg(skip)]
+    pub include_instances: Vec<String>,
+
+    /// Instances that never receive strategy flags, `[afl_cfg.strategy] exclude_instances`,
+    /// config only
+    #[arg(skip)]
+    pub exclude_instances: Vec<String>,
+
+    /// `AFL_LLVM_*` variables of the `[afl_cfg.llvm_env]` table, config only
+    #[arg(skip)]
+    pub llvm_env: LlvmEnv,
+
+    /// Abort if any warning was raised during generation
+    #[arg(
+        long,
+        help = "Fail if any warning is raised during command generation (for strict CI usage)",
+        action = ArgAction::SetTrue
+    )]
+    pub fail_on_warnings: bool,
 }
 
 #[cfg(test)]
@@ -103,13 +339,13 @@ mod tests {
     fn test_gen_args_with_values() {
         let args = GenArgs {
             target: Some(PathBuf::from("/path/to/target")),
-            runners: Some(4),
+            runners: Some(RunnerCount::Fixed(4)),
             use_seed_afl: true,
             ..GenArgs::default()
         };
 
         assert_eq!(args.target.unwrap(), PathBuf::from("/path/to/target"));
-        assert_eq!(args.runners.unwrap(), 4);
+        assert_eq!(args.runners.unwrap(), RunnerCount::Fixed(4));
         assert!(args.use_seed_afl);
     }
 }
@@ -9,4 +9,12 @@ pub struct KillArgs {
         value_hint = ValueHint::Other
     )]
     pub session_name: String,
+
+    /// Only interrupt the fuzzers (SIGINT) and keep the session alive for inspection
+    #[arg(long)]
+    pub fuzzers_only: bool,
+
+    /// Print a JSON summary of the terminated session and fuzzer PIDs instead of log lines
+    #[arg(long)]
+    pub json: bool,
 }
@@ -0,0 +1,346 @@
+use anyhow::{bail, Context, Result};
+use serde::{
+    de::{self, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use std::fmt::Write;
+use toml::{Table, Value};
+
+use super::{
+    afl::AflArgs, coverage::CoverageArgs, misc::MiscArgs, preset::PRESETS_KEY,
+    session::SessionArgs, target::TargetArgs, Args,
+};
+
+/// Version of the config schema written by this release
+pub const CONFIG_VERSION: i64 = 1;
+
+/// Top-level array of `[[targets]]` tables
+pub const TARGETS_KEY: &str = "targets";
+
+/// Sections of the current schema in the order they are written
+pub(crate) const SECTIONS: &[&str] = &["target", "coverage", "afl_cfg", "session", "misc"];
+
+/// A key that moved between two schema versions
+struct KeyMove {
+    from: (&'static str, &'static str),
+    to: (&'static str, &'static str),
+}
+
+/// Moves from the unversioned layout (version 0) to version 1
+///
+/// Version 0 kept the coverage report options in `[misc]` and used plural/short key names
+/// which newer releases silently ignored.
+const V0_MOVES: &[KeyMove] = &[
+    KeyMove {
+        from: ("coverage", "split_reports"),
+        to: ("coverage", "split_report"),
+    },
+    KeyMove {
+        from: ("misc", "split_reports"),
+        to: ("coverage", "split_report"),
+    },
+    KeyMove {
+        from: ("misc", "split_report"),
+        to: ("coverage", "split_report"),
+    },
+    KeyMove {
+        from: ("misc", "report_type"),
+        to: ("coverage", "report_type"),
+    },
+    KeyMove {
+        from: ("misc", "misc_show_args"),
+        to: ("coverage", "misc_show_args"),
+    },
+    KeyMove {
+        from: ("misc", "misc_report_args"),
+        to: ("coverage", "misc_report_args"),
+    },
+    KeyMove {
+        from: ("misc", "ramdisk"),
+        to: ("misc", "is_ramdisk"),
+    },
+];
+
+/// Key moves to apply on top of a config of the given version, oldest first
+fn moves_since(version: i64) -> Vec<&'static KeyMove> {
+    let mut moves = Vec::new();
+    if version < 1 {
+        moves.extend(V0_MOVES);
+    }
+    moves
+}
+
+/// Result of migrating a config to the current schema
+#[derive(Debug)]
+pub struct Migration {
+    /// Version of the input config
+    pub from_version: i64,
+    /// Upgraded config, including comments on renamed and unmapped keys
+    pub content: String,
+    /// Human readable `old -> new` notes for every moved key
+    pub renamed: Vec<String>,
+    /// Keys that could not be mapped onto the current schema
+    pub unmapped: Vec<String>,
+}
+
+impl Migration {
+    /// Whether the input differs from the current schema
+    pub fn is_needed(&self) -> bool {
+        self.from_version < CONFIG_VERSION || !self.renamed.is_empty() || !self.unmapped.is_empty()
+    }
+}
+
+/// Returns whether `content` uses a layout that `config migrate` would change
+///
+/// Unparseable configs are not considered legacy, the regular loader reports them.
+pub fn is_legacy(content: &str) -> bool {
+    migrate(content).is_ok_and(|m| !m.renamed.is_empty() || !m.unmapped.is_empty())
+}
+
+/// Migrates a config of any known layout onto the current schema
+///
+/// # Errors
+/// * If the config is not valid TOML
+/// * If the config is newer than the current schema
+/// * If the migrated config still fails to parse
+pub fn migrate(content: &str) -> Result<Migration> {
+    let mut table: Table = toml::from_str(content).context("Failed to parse config")?;
+    let from_version = match table.remove("config_version") {
+        None => 0,
+        Some(Value::Integer(version)) => version,
+        Some(other) => bail!("config_version must be an integer, got {other}"),
+    };
+    if from_version > CONFIG_VERSION {
+        bail!("config_version {from_version} is newer than the supported {CONFIG_VERSION}");
+    }
+
+    // Presets and the target list are carried over as they are
+    let passthrough: Table = [PRESETS_KEY, TARGETS_KEY]
+        .into_iter()
+        .filter_map(|key| table.remove(key).map(|value| (key.to_string(), value)))
+        .collect();
+
+    let mut sections: Vec<(String, Table)> = SECTIONS
+        .iter()
+        .map(|&name| {
+            let section = match table.remove(name) {
+                Some(Value::Table(section)) => section,
+                _ => Table::new(),
+            };
+            (name.to_string(), section)
+        })
+        .collect();
+    let mut unmapped: Vec<String> = table
+        .iter()
+        .map(|(key, value)| format!("{key} = {value}"))
+        .collect();
+
+    let mut renamed = Vec::new();
+    let mut comments: Vec<(String, String, String)> = Vec::new();
+    for KeyMove { from, to } in moves_since(from_version) {
+        let Some(value) = section_mut(&mut sections, from.0).remove(from.1) else {
+            continue;
+        };
+        let note = format!("[{}] {} -> [{}] {}", from.0, from.1, to.0, to.1);
+        let target = section_mut(&mut sections, to.0);
+        if target.contains_key(to.1) {
+            unmapped.push(format!(
+                "[{}] {} = {value} (conflicts with {note})",
+                from.0, from.1
+            ));
+            continue;
+        }
+        target.insert(to.1.to_string(), value);
+        comments.push((
+            to.0.to_string(),
+            to.1.to_string(),
+            format!("renamed from [{}] {}", from.0, from.1),
+        ));
+        renamed.push(note);
+    }
+
+    let mut content = format!(
+        "# Migrated by `aflr config migrate` from config_version {from_version}\nconfig_version = {CONFIG_VERSION}\n"
+    );
+    for (name, section) in &mut sections {
+        let known = section_fields(name);
+        let unknown: Vec<String> = section
+            .keys()
+            .filter(|key| !known.contains(&key.as_str()))
+            .cloned()
+            .collect();
+        for key in unknown {
+            if let Some(value) = section.remove(&key) {
+                unmapped.push(format!("[{name}] {key} = {value}"));
+            }
+        }
+
+        let _ = write!(content, "\n[{name}]\n");
+        for (key, value) in section.iter() {
+            if let Some((_, _, comment)) = comments.iter().find(|(s, k, _)| s == name && k == key) {
+                let _ = writeln!(content, "# {comment}");
+            }
+            let _ = writeln!(content, "{key} = {value}");
+        }
+    }
+
+    if !passthrough.is_empty() {
+        let _ = write!(
+            content,
+            "\n{}",
+            toml::to_string(&passthrough).context("Failed to write presets and targets")?
+        );
+    }
+
+    if !unmapped.is_empty() {
+        content.push_str("\n# Could not be mapped onto the current schema:\n");
+        for entry in &unmapped {
+            let _ = writeln!(content, "# {entry}");
+        }
+    }
+
+    toml::from_str::<Args>(&content).context("Migrated config does not parse")?;
+    Ok(Migration {
+        from_version,
+        content,
+        renamed,
+        unmapped,
+    })
+}
+
+fn section_mut<'a>(sections: &'a mut [(String, Table)], name: &str) -> &'a mut Table {
+    &mut sections
+        .iter_mut()
+        .find(|(n, _)| n == name)
+        .expect("Key moves only reference known sections")
+        .1
+}
+
+/// Keys accepted by a section of the current schema
+fn section_fields(section: &str) -> &'static [&'static str] {
+    match section {
+        "target" => struct_fields::<TargetArgs>(),
+        "coverage" => struct_fields::<CoverageArgs>(),
+        "afl_cfg" => struct_fields::<AflArgs>(),
+        "session" => struct_fields::<SessionArgs>(),
+        "misc" => struct_fields::<MiscArgs>(),
+        _ => &[],
+    }
+}
+
+/// Field names of a struct, captured from its derived `Deserialize` implementation
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldNames<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("only struct field names are collected"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("only struct field names are collected"))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+            byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+            identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runner_count::RunnerCount;
+
+    #[test]
+    fn test_struct_fields() {
+        assert!(struct_fields::<TargetArgs>().contains(&"cov_path"));
+        assert!(struct_fields::<MiscArgs>().contains(&"is_ramdisk"));
+        assert!(section_fields("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_migrate_v0() {
+        let legacy = r#"
+[target]
+path = "/bin/target"
+[afl_cfg]
+runners = 4
+[misc]
+ramdisk = true
+report_type = "TEXT"
+bogus = 1
+[coverage]
+split_reports = true
+"#;
+        assert!(is_legacy(legacy));
+
+        let migration = migrate(legacy).unwrap();
+        assert_eq!(migration.from_version, 0);
+        assert!(migration.is_needed());
+        assert_eq!(migration.renamed.len(), 3);
+        assert_eq!(migration.unmapped, ["[misc] bogus = 1"]);
+
+        let args: Args = toml::from_str(&migration.content).unwrap();
+        assert_eq!(args.misc.is_ramdisk, Some(true));
+        assert_eq!(args.coverage.split_report, Some(true));
+        assert_eq!(args.coverage.report_type.as_deref(), Some("TEXT"));
+        assert_eq!(args.afl_cfg.runners, Some(RunnerCount::Fixed(4)));
+        assert!(migration
+            .content
+            .contains("# renamed from [misc] ramdisk\nis_ramdisk = true"));
+        assert!(migration.content.contains("# [misc] bogus = 1"));
+
+        // Migrating the result again is a no-op
+        let again = migrate(&migration.content).unwrap();
+        assert!(!again.is_needed());
+        assert!(!is_legacy(&migration.content));
+    }
+
+    #[test]
+    fn test_migrate_conflicts_and_versions() {
+        let conflicting = "[misc]\nramdisk = true\nis_ramdisk = false\n";
+        let migration = migrate(conflicting).unwrap();
+        assert!(migration.renamed.is_empty());
+        assert_eq!(migration.unmapped.len(), 1);
+        assert!(migration.unmapped[0].contains("conflicts"));
+
+        assert!(migrate("config_version = 99\n").is_err());
+        assert!(migrate("config_version = \"1\"\n").is_err());
+        assert!(!is_legacy("not toml ["));
+    }
+
+    #[test]
+    fn test_migrate_keeps_presets_and_targets() {
+        let config =
+            "config_version = 1\n[afl_cfg]\nrunners = 2\n[presets.nightly.afl_cfg]\nrunners = 8\n";
+        assert!(!is_legacy(config));
+
+        let migration = migrate(
+            "[misc]\nramdisk = true\n[presets.nightly.afl_cfg]\nrunners = 8\n[[targets]]\npath = \"/bin/a\"\n",
+        )
+        .unwrap();
+        assert!(migration.unmapped.is_empty());
+        let table: Table = toml::from_str(&migration.content).unwrap();
+        assert_eq!(
+            table[PRESETS_KEY]["nightly"]["afl_cfg"]["runners"].as_integer(),
+            Some(8)
+        );
+        let args: Args = toml::from_str(&migration.content).unwrap();
+        assert_eq!(args.targets.unwrap()[0].path, "/bin/a");
+    }
+}
@@ -0,0 +1,24 @@
+use clap::{Args, ValueHint};
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct ListArgs {
+    /// Directories searched for campaigns besides the current one
+    #[arg(
+        help = "Directories searched for campaigns in addition to the current directory and `[misc] campaign_roots`",
+        value_hint = ValueHint::DirPath
+    )]
+    pub roots: Vec<PathBuf>,
+
+    /// Only list campaigns with live fuzzers
+    #[arg(long)]
+    pub running: bool,
+
+    /// Print the campaigns as JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+
+    /// Path to a TOML config file
+    #[arg(long, help = "Path to TOML config file, '-' reads it from stdin")]
+    pub config: Option<PathBuf>,
+}
@@ -0,0 +1,32 @@
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug)]
+pub struct MergeCorporaArgs {
+    /// AFL++ output directories to merge
+    #[arg(
+        required = true,
+        help = "AFL++ output directories to merge the queues of"
+    )]
+    pub out_dirs: Vec<PathBuf>,
+
+    /// Destination seed directory
+    #[arg(long, help = "Directory to write the merged seed set to")]
+    pub into: PathBuf,
+
+    /// Minimize the merged corpus with afl-cmin
+    #[arg(
+        long,
+        help = "Minimize the merged corpus with afl-cmin",
+        requires = "target"
+    )]
+    pub cmin: bool,
+
+    /// Target binary used by afl-cmin
+    #[arg(short, long, help = "Instrumented target binary for afl-cmin")]
+    pub target: Option<PathBuf>,
+
+    /// Target binary arguments
+    #[arg(help = "Target binary arguments, including @@ if needed", last = true)]
+    pub target_args: Vec<String>,
+}
@@ -0,0 +1,63 @@
+use clap::{Args, Subcommand, ValueHint};
+use std::path::PathBuf;
+
+use super::constants::AFL_OUTPUT;
+use crate::cli::SessionRunner;
+
+#[derive(Args, Clone, Debug)]
+pub struct MigrationArgs {
+    #[command(subcommand)]
+    pub cmd: MigrationCommands,
+}
+
+/// Migration subcommands
+#[derive(Subcommand, Clone, Debug)]
+pub enum MigrationCommands {
+    /// Pauses a running campaign and bundles its queues, solutions, stats and config
+    Export(MigrationExportArgs),
+    /// Unpacks a bundle, rewrites its paths for this machine and resumes the campaign
+    Import(MigrationImportArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct MigrationExportArgs {
+    /// Output directory of the campaign
+    #[arg(default_value = AFL_OUTPUT, value_hint = ValueHint::DirPath)]
+    pub output_dir: PathBuf,
+
+    /// Bundle to write, the suffix picks the compression, e.g. `campaign.tar.zst`
+    #[arg(long, required = true, value_hint = ValueHint::FilePath)]
+    pub to: PathBuf,
+
+    /// Config file the campaign was started with, bundled for the destination
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct MigrationImportArgs {
+    /// Bundle written by `migrate export`
+    #[arg(required = true, value_hint = ValueHint::FilePath)]
+    pub bundle: PathBuf,
+
+    /// New output directory, must be empty or missing
+    #[arg(short, long, required = true, value_hint = ValueHint::DirPath)]
+    pub output_dir: PathBuf,
+
+    /// Moves absolute paths of the source machine, e.g. `--map /home/a/proj=/srv/proj`
+    #[arg(long = "map", value_name = "OLD=NEW")]
+    pub maps: Vec<String>,
+
+    /// Keep paths without a `--map` rule instead of asking for their new location
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Only unpack and rewrite the campaign, don't relaunch it
+    #[arg(long)]
+    pub no_launch: bool,
+
+    /// Session runner to relaunch the campaign in
+    #[clap(value_enum)]
+    #[arg(long = "session-runner", default_value_t = SessionRunner::Tmux)]
+    pub session_runner: SessionRunner,
+}
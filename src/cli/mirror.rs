@@ -0,0 +1,20 @@
+use clap::Args;
+use std::path::PathBuf;
+
+use crate::cli::constants::MIRROR_INTERVAL;
+use crate::utils::duration::DurationOpt;
+
+#[derive(Args, Clone, Debug)]
+pub struct MirrorArgs {
+    /// Local output directory the fuzzers write to
+    #[arg(long, required = true)]
+    pub from: PathBuf,
+
+    /// Destination directory the results are mirrored to
+    #[arg(long, required = true)]
+    pub to: PathBuf,
+
+    /// Time between two mirror passes, bare numbers are seconds
+    #[arg(long, value_name = "DURATION", default_value_t = MIRROR_INTERVAL)]
+    pub interval: DurationOpt,
+}
@@ -1,9 +1,13 @@
 use serde::Deserialize;
 
+use crate::utils::duration::DurationOpt;
+
 #[derive(Deserialize, Default, Debug, Clone)]
 pub struct MiscArgs {
     /// Enable TUI mode
     pub tui: Option<bool>,
+    /// Print a progress line instead of attaching to the session
+    pub progress: Option<bool>,
     /// Enabled detached mode
     pub detached: Option<bool>,
     /// Use a Ramdisk for AFL++ to store `.cur_input`
@@ -12,4 +16,44 @@ pub struct MiscArgs {
     pub seed: Option<u64>,
     /// Use seed for AFL++ as well
     pub use_seed_afl: Option<bool>,
+    /// Fraction of instances that receive the seed when `use_seed_afl` is set
+    pub seed_afl_ratio: Option<f64>,
+    /// Disable all randomized strategies
+    pub deterministic: Option<bool>,
+    /// Fail if any warning is raised during command generation
+    pub fail_on_warnings: Option<bool>,
+    /// Timeout of a single replay execution
+    pub replay_timeout: Option<DurationOpt>,
+    /// Execs/s below which the TUI highlights an instance as slow
+    pub slow_exec_threshold: Option<f64>,
+    /// Stability in percent below which the TUI shows it in yellow
+    pub stability_caution: Option<f64>,
+    /// Stability in percent below which the TUI shows it in orange
+    pub stability_warn: Option<f64>,
+    /// Stability in percent below which the TUI shows it in red
+    pub stability_error: Option<f64>,
+    /// Serve the campaign data on a UNIX socket while the TUI runs
+    pub control_socket: Option<bool>,
+    /// Log lines retained by the TUI
+    pub log_capacity: Option<usize>,
+    /// Highlight new crashes in the TUI
+    pub follow_new_crashes: Option<bool>,
+    /// Shell command new crashes are opened with while following them
+    pub crash_viewer: Option<String>,
+    /// Time between two corpus snapshots taken while the TUI runs
+    pub snapshot_interval: Option<DurationOpt>,
+    /// Corpus snapshots kept per campaign
+    pub snapshot_keep: Option<usize>,
+    /// Store corpus snapshots as tarballs
+    pub snapshot_compress: Option<bool>,
+    /// Time without a stats update after which the TUI flags a live instance as stalled
+    pub stall_threshold: Option<DurationOpt>,
+    /// Time the TUI waits for the first fuzzer to start before reporting none alive
+    pub startup_grace: Option<DurationOpt>,
+    /// Fraction of the secondaries' median execs/s below which the TUI warns about the main
+    pub starvation_ratio: Option<f64>,
+    /// Percentage points the average stability may drop below its rolling baseline in the TUI
+    pub stability_drop: Option<f64>,
+    /// Directories `aflr list` searches for campaigns besides the current one
+    pub campaign_roots: Option<Vec<String>>,
 }
@@ -1,32 +1,62 @@
 use clap::{Parser, Subcommand};
 use serde::Deserialize;
 
+use crate::afl::target_env::TargetEnv;
+use crate::utils::runner_count::RunnerCount;
+
 mod add_seed;
 mod afl;
+mod cleanup;
+mod collect_crashes;
+mod config;
 pub mod constants;
 mod cov;
 mod coverage;
 mod gen;
 mod kill;
+pub mod legacy;
+mod list;
+mod merge_corpora;
+mod migration;
+mod mirror;
 mod misc;
+mod pause;
+pub mod preset;
+mod replay;
+mod restart;
 mod run;
 pub mod session;
+mod snapshots;
 mod target;
+mod top;
 mod tui;
 mod utils;
 
 pub use add_seed::AddSeedArgs;
 pub use afl::AflArgs;
+pub use cleanup::CleanupArgs;
+pub use collect_crashes::CollectCrashesArgs;
+pub use config::{ConfigArgs, ConfigCommands, MigrateArgs};
 use constants::{AFL_CORPUS, AFL_OUTPUT};
 pub use cov::CovArgs;
 use coverage::CoverageArgs;
-pub use gen::GenArgs;
+pub use gen::{GenArgs, GenCommandArgs};
 pub use kill::KillArgs;
+pub use list::ListArgs;
+pub use merge_corpora::MergeCorporaArgs;
+pub use migration::{MigrationArgs, MigrationCommands, MigrationExportArgs, MigrationImportArgs};
+pub use mirror::MirrorArgs;
 use misc::MiscArgs;
+pub use pause::PauseArgs;
+pub use replay::{BinaryVariant, ReplayArgs};
+pub use restart::RestartArgs;
 pub use run::RunArgs;
 use session::SessionArgs;
-pub use session::SessionRunner;
+pub use session::{DockerMode, SessionLayout, SessionRunner};
+pub use snapshots::{SnapshotsArgs, SnapshotsCommands, SnapshotsListArgs, SnapshotsRestoreArgs};
 use target::TargetArgs;
+pub use target::{validate_targets, TargetEntry};
+pub use top::TopArgs;
 pub use tui::TuiArgs;
 
 /// Command-line interface for the `AFL++` Campaign Toolbelt
@@ -38,27 +68,71 @@ pub struct Cli {
     /// Subcommand to execute
     #[command(subcommand)]
     pub cmd: Commands,
+
+    /// Fixed time for the timestamps embedded in records, reports and archives
+    #[arg(
+        long,
+        global = true,
+        value_name = "RFC3339",
+        value_parser = crate::utils::clock::parse_rfc3339,
+        help = "Embed this time, e.g. '2024-05-01T12:00:00Z', in records, reports and archives instead of the current one (overrides SOURCE_DATE_EPOCH)"
+    )]
+    pub timestamp: Option<u64>,
 }
 
 /// Available subcommands
 #[derive(Subcommand, Clone, Debug)]
 pub enum Commands {
     /// Only generate commands for fuzzing campaign, don't run them
-    Gen(GenArgs),
+    Gen(GenCommandArgs),
     /// Generate fuzzing campaign and run it
     Run(RunArgs),
     /// Collect and visualize fuzzing coverage
     Cov(CovArgs),
     /// Show stats TUI for a running campaign
     Tui(TuiArgs),
+    /// Prints a compact live table of the instances of a campaign, like `afl-whatsup -s`
+    Top(TopArgs),
     /// Kills a running session and all spawned processes inside
     Kill(KillArgs),
+    /// Pauses single instances of a running session (SIGSTOP) to free their cores
+    Pause(PauseArgs),
+    /// Resumes instances paused with `pause` (SIGCONT)
+    Continue(PauseArgs),
+    /// Restarts a single instance of a running session in its window, resuming its progress
+    Restart(RestartArgs),
+    /// Lists the campaigns found below the current directory and the configured search roots
+    List(ListArgs),
+    /// Lists and restores the corpus snapshots taken while the TUI monitors a campaign
+    Snapshots(SnapshotsArgs),
+    /// Moves a running campaign to another machine via an exported bundle
+    Migrate(MigrationArgs),
     /// Allows adding new seeds to a running campaign
     AddSeed(AddSeedArgs),
+    /// Links the crashes of all instances into one flat directory for external triage tools
+    CollectCrashes(CollectCrashesArgs),
+    /// Merges the queues of multiple output directories into a deduplicated seed set
+    MergeCorpora(MergeCorporaArgs),
+    /// Manages aflr config files
+    Config(ConfigArgs),
+    /// Reruns a single queue, crash or hang entry against a target binary
+    Replay(ReplayArgs),
+    /// Prints the exported AFL_* variables and whether generated commands inherit them
+    #[command(alias = "print-env")]
+    EnvCheck,
+    /// Checks AFL++ and the tools aflr relies on, printing a fix for every problem
+    Doctor,
+    /// Removes the RAMDisks and temporary files finished or crashed campaigns left behind
+    Cleanup(CleanupArgs),
+    /// Mirrors a local output directory to shared storage (spawned by `run --local-work-dir`)
+    #[command(hide = true)]
+    Mirror(MirrorArgs),
 }
 
 #[derive(Deserialize, Default, Debug, Clone)]
 pub struct Args {
+    /// Version of the config schema, missing in configs predating `config migrate`
+    pub config_version: Option<i64>,
     /// Target configuration
     pub target: TargetArgs,
     /// Coverage configuration
@@ -69,6 +143,8 @@ pub struct Args {
     pub session: SessionArgs,
     /// Miscellaneous configuration
     pub misc: MiscArgs,
+    /// Harnesses fuzzed side by side, each in its own output subdirectory
+    pub targets: Option<Vec<TargetEntry>>,
 }
 
 pub trait ArgMerge<T> {
@@ -84,22 +160,34 @@ impl ArgMerge<Self> for GenArgs {
                     .map(std::path::PathBuf::from)
             })
         };
+        let strategy = args.afl_cfg.strategy.as_ref();
 
         Self {
             target: merge_path(self.target.clone(), args.target.path.clone()),
             san_target: merge_path(self.san_target.clone(), args.target.san_path.clone()),
             cmpl_target: merge_path(self.cmpl_target.clone(), args.target.cmpl_path.clone()),
             cmpc_target: merge_path(self.cmpc_target.clone(), args.target.cmpc_path.clone()),
+            auto_detect_builds: self.auto_detect_builds || args.target.auto_detect.unwrap_or(false),
+            san_on_secondary: self.san_on_secondary
+                || args.target.san_on_secondary.unwrap_or(false),
             target_args: self
                 .target_args
                 .clone()
                 .or_else(|| args.target.args.clone().filter(|args| !args.is_empty())),
-            runners: Some(self.runners.or(args.afl_cfg.runners).unwrap_or(1)),
+            // Percentages are resolved here, so the merged count is always fixed
+            runners: Some(RunnerCount::Fixed(
+                self.runners
+                    .or(args.afl_cfg.runners)
+                    .map_or(1, RunnerCount::resolve),
+            )),
             input_dir: merge_path(self.input_dir.clone(), args.afl_cfg.seed_dir.clone())
                 .or_else(|| Some(std::path::PathBuf::from(AFL_CORPUS))),
             output_dir: merge_path(self.output_dir.clone(), args.afl_cfg.solution_dir.clone())
                 .or_else(|| Some(std::path::PathBuf::from(AFL_OUTPUT))),
             dictionary: merge_path(self.dictionary.clone(), args.afl_cfg.dictionary.clone()),
+            dictionary_ratio: self.dictionary_ratio.or(args.afl_cfg.dictionary_ratio),
+            dictionary_skip_cmpcov: self.dictionary_skip_cmpcov
+                || args.afl_cfg.dictionary_skip_cmpcov.unwrap_or(false),
             afl_binary: self
                 .afl_binary
                 .clone()
@@ -107,7 +195,68 @@ impl ArgMerge<Self> for GenArgs {
             mode: args.afl_cfg.mode.unwrap_or(self.mode),
             seed: self.seed.or(args.misc.seed),
             use_seed_afl: args.misc.use_seed_afl.unwrap_or(self.use_seed_afl),
+            seed_afl_ratio: self.seed_afl_ratio.or(args.misc.seed_afl_ratio),
+            deterministic: self.deterministic || args.misc.deterministic.unwrap_or(false),
+            map_size: self.map_size.or(args.afl_cfg.map_size),
+            min_len: self.min_len.or(args.afl_cfg.min_len),
+            max_len: self.max_len.or(args.afl_cfg.max_len),
+            sync_time: self.sync_time.or(args.afl_cfg.sync_time),
             config: self.config.clone(),
+            preset: self.preset.clone(),
+            forbid_flags: if self.forbid_flags.is_empty() {
+                strategy
+                    .and_then(|s| s.forbid_flags.clone())
+                    .unwrap_or_default()
+            } else {
+                self.forbid_flags.clone()
+            },
+            redistribute_forbidden: self.redistribute_forbidden
+                || strategy.and_then(|s| s.redistribute).unwrap_or(false),
+            include_instances: strategy
+                .and_then(|s| s.include_instances.clone())
+                .unwrap_or_default(),
+            exclude_instances: strategy
+                .and_then(|s| s.exclude_instances.clone())
+                .unwrap_or_default(),
+            on_new_crash_cmd: merge_path(
+                self.on_new_crash_cmd.clone(),
+                args.afl_cfg.on_new_crash_cmd.clone(),
+            ),
+            on_new_crash_all: self.on_new_crash_all
+                || args.afl_cfg.on_new_crash_all.unwrap_or(false),
+            custom_mutator: self.custom_mutator.clone().or_else(|| {
+                args.afl_cfg
+                    .custom_mutator
+                    .clone()
+                    .filter(|lib| !lib.is_empty())
+            }),
+            custom_mutator_ratio: self
+                .custom_mutator_ratio
+                .or(args.afl_cfg.custom_mutator_ratio),
+            cmplog_level: self.cmplog_level.or(args.afl_cfg.cmplog_level),
+            strict_flags: self.strict_flags || args.afl_cfg.strict_flags.unwrap_or(false),
+            llvm_env: args.afl_cfg.llvm_env.clone().unwrap_or_default(),
+            fixed_input_file: self.fixed_input_file.clone().or_else(|| {
+                args.target
+                    .fixed_input_file
+                    .clone()
+                    .filter(|f| !f.is_empty())
+            }),
+            target_env: if self.target_env.is_empty() {
+                args.target
+                    .env
+                    .as_ref()
+                    .map(TargetEnv::vars)
+                    .unwrap_or_default()
+            } else {
+                self.target_env.clone()
+            },
+            skip_dep_check: if self.skip_dep_check.is_empty() {
+                args.target.skip_dep_check.clone().unwrap_or_default()
+            } else {
+                self.skip_dep_check.clone()
+            },
+            fail_on_warnings: self.fail_on_warnings || args.misc.fail_on_warnings.unwrap_or(false),
         }
     }
 }
@@ -124,17 +273,26 @@ impl ArgMerge<Self> for RunArgs {
         Self {
             gen_args,
             dry_run: self.dry_run || args.session.dry_run.unwrap_or(false),
+            edit: self.edit,
             session_runner,
+            layout: self.layout.or(args.session.layout),
             session_name: self
                 .session_name
                 .clone()
                 .or_else(|| args.session.name.clone().filter(|s| !s.is_empty())),
-            tui: if self.dry_run {
+            tui: if self.dry_run || self.calibrate.is_some() {
                 false
             } else {
                 self.tui || args.misc.tui.unwrap_or(false)
             },
-            detached: if self.dry_run {
+            control_socket: self.control_socket,
+            snapshot_compress: self.snapshot_compress,
+            progress: if self.dry_run || self.calibrate.is_some() {
+                false
+            } else {
+                self.progress || args.misc.progress.unwrap_or(false)
+            },
+            detached: if self.dry_run || self.calibrate.is_some() {
                 false
             } else {
                 self.detached || args.misc.detached.unwrap_or(false)
@@ -144,6 +302,43 @@ impl ArgMerge<Self> for RunArgs {
             } else {
                 self.is_ramdisk || args.misc.is_ramdisk.unwrap_or(false)
             },
+            force: self.force,
+            auto_remediate: self.auto_remediate || args.session.auto_remediate.unwrap_or(false),
+            fix_env: self.fix_env || args.session.fix_env.unwrap_or(false),
+            verify_instrumentation: self
+                .verify_instrumentation
+                .or(args.session.verify_instrumentation),
+            min_tuples: self.min_tuples.or(args.session.min_tuples),
+            calibrate: self.calibrate,
+            keep_probe_output: self.keep_probe_output,
+            tag: self
+                .tag
+                .clone()
+                .or_else(|| args.session.tag.clone().filter(|t| !t.is_empty())),
+            local_work_dir: self.local_work_dir.clone(),
+            // An explicit --local-work-dir takes precedence over the config
+            output_on_ramdisk: self.output_on_ramdisk
+                || (self.local_work_dir.is_none()
+                    && args.session.output_on_ramdisk.unwrap_or(false)),
+            mirror_interval: self.mirror_interval.or(args.session.mirror_interval),
+            remote: self
+                .remote
+                .clone()
+                .or_else(|| args.session.remote.clone().filter(|r| !r.is_empty())),
+            remote_path_map: if self.remote_path_map.is_empty() {
+                args.session.remote_path_map.clone().unwrap_or_default()
+            } else {
+                self.remote_path_map.clone()
+            },
+            docker: self
+                .docker
+                .clone()
+                .or_else(|| args.session.docker.clone().filter(|d| !d.is_empty())),
+            docker_mode: self
+                .docker_mode
+                .or_else(|| args.session.docker_mode.as_deref().map(DockerMode::from)),
+            docker_privileged: self.docker_privileged
+                || args.session.docker_privileged.unwrap_or(false),
         }
     }
 }
@@ -192,6 +387,22 @@ impl ArgMerge<Self> for CovArgs {
                     .clone()
                     .filter(|args| !args.is_empty())
             }),
+            input_mode: self.input_mode.or(args.coverage.input_mode),
+            target_env: if self.target_env.is_empty() {
+                args.target
+                    .env
+                    .as_ref()
+                    .map(TargetEnv::vars)
+                    .unwrap_or_default()
+            } else {
+                self.target_env.clone()
+            },
+            sandbox: self.sandbox || args.coverage.sandbox.unwrap_or(false),
+            attribute_instances: self.attribute_instances,
+            attribute_unique: self.attribute_unique,
+            merge_batch_size: self.merge_batch_size.or(args.coverage.merge_batch_size),
+            json_export: merge_path(self.json_export.clone(), args.coverage.json_export.clone()),
+            work_dir: merge_path(self.work_dir.clone(), args.coverage.work_dir.clone()),
             config: self.config.clone(),
         }
     }
@@ -221,17 +432,63 @@ impl ArgMerge<Self> for AddSeedArgs {
     }
 }
 
+impl ArgMerge<Self> for ListArgs {
+    fn merge_with_config(&self, args: &Args) -> Self {
+        let mut roots = self.roots.clone();
+        roots.extend(
+            args.misc
+                .campaign_roots
+                .iter()
+                .flatten()
+                .filter(|root| !root.is_empty())
+                .map(std::path::PathBuf::from),
+        );
+        Self {
+            roots,
+            ..self.clone()
+        }
+    }
+}
+
+impl ArgMerge<Self> for ReplayArgs {
+    fn merge_with_config(&self, args: &Args) -> Self {
+        let merge_path = |opt: Option<std::path::PathBuf>, cfg_str: Option<String>| {
+            opt.or_else(|| {
+                cfg_str
+                    .filter(|p| !p.is_empty())
+                    .map(std::path::PathBuf::from)
+            })
+        };
+
+        Self {
+            output_dir: merge_path(self.output_dir.clone(), args.afl_cfg.solution_dir.clone())
+                .or_else(|| Some(std::path::PathBuf::from(AFL_OUTPUT))),
+            target: merge_path(self.target.clone(), args.target.path.clone()),
+            san_target: merge_path(self.san_target.clone(), args.target.san_path.clone()),
+            cmpl_target: merge_path(self.cmpl_target.clone(), args.target.cmpl_path.clone()),
+            cmpc_target: merge_path(self.cmpc_target.clone(), args.target.cmpc_path.clone()),
+            cov_target: merge_path(self.cov_target.clone(), args.target.cov_path.clone()),
+            target_args: self
+                .target_args
+                .clone()
+                .or_else(|| args.target.args.clone().filter(|args| !args.is_empty())),
+            timeout: self.timeout.or(args.misc.replay_timeout),
+            ..self.clone()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::{GenArgs, RunArgs};
+    use crate::cli::{CovArgs, GenArgs, RunArgs};
     use std::path::PathBuf;
 
     #[test]
     fn test_gen_args_merge() {
         let args = GenArgs {
             target: Some(PathBuf::from("/custom/path")),
-            runners: Some(4),
+            runners: Some(RunnerCount::Fixed(4)),
             ..GenArgs::default()
         };
 
@@ -241,7 +498,7 @@ mod tests {
                 ..TargetArgs::default()
             },
             afl_cfg: AflArgs {
-                runners: Some(2),
+                runners: Some(RunnerCount::Fixed(2)),
                 ..AflArgs::default()
             },
             ..Args::default()
@@ -249,7 +506,204 @@ mod tests {
 
         let merged = args.merge_with_config(&config);
         assert_eq!(merged.target.unwrap(), PathBuf::from("/custom/path"));
-        assert_eq!(merged.runners, Some(4));
+        assert_eq!(merged.runners, Some(RunnerCount::Fixed(4)));
+
+        // A percentage of the cores is resolved to a fixed count
+        let merged = GenArgs {
+            runners: Some(RunnerCount::Percent(100)),
+            ..GenArgs::default()
+        }
+        .merge_with_config(&config);
+        assert_eq!(
+            merged.runners,
+            Some(RunnerCount::Fixed(RunnerCount::Percent(100).resolve()))
+        );
+        assert!(matches!(
+            GenArgs::default()
+                .merge_with_config(&Args::default())
+                .runners,
+            Some(RunnerCount::Fixed(1))
+        ));
+    }
+
+    #[test]
+    fn test_gen_args_merge_forbidden_flags() {
+        let config: Args = toml::from_str(
+            r#"
+            [target]
+            [coverage]
+            [session]
+            [misc]
+            [afl_cfg.strategy]
+            forbid_flags = ["-a text", "-L 0"]
+            redistribute = true
+            "#,
+        )
+        .unwrap();
+
+        let merged = GenArgs::default().merge_with_config(&config);
+        assert_eq!(merged.forbid_flags, ["-a text", "-L 0"]);
+        assert!(merged.redistribute_forbidden);
+
+        let cli = GenArgs {
+            forbid_flags: vec!["-Z".into()],
+            ..GenArgs::default()
+        };
+        assert_eq!(cli.merge_with_config(&config).forbid_flags, ["-Z"]);
+    }
+
+    #[test]
+    fn test_duration_options_merge() {
+        use crate::cli::ReplayArgs;
+        use crate::utils::duration::DurationOpt;
+
+        let config: Args = toml::from_str(
+            r#"
+            [target]
+            [coverage]
+            [afl_cfg]
+            sync_time = "1h"
+            [session]
+            mirror_interval = "5m"
+            [misc]
+            replay_timeout = 2
+            "#,
+        )
+        .unwrap();
+
+        let merged = RunArgs::default().merge_with_config(&config);
+        assert_eq!(merged.mirror_interval, Some(DurationOpt::from_secs(300)));
+        let cli = RunArgs {
+            mirror_interval: Some("90s".parse().unwrap()),
+            ..RunArgs::default()
+        };
+        assert_eq!(
+            cli.merge_with_config(&config).mirror_interval,
+            Some(DurationOpt::from_secs(90))
+        );
+
+        let merged = ReplayArgs::default().merge_with_config(&config);
+        assert_eq!(merged.timeout, Some(DurationOpt::from_secs(2)));
+        let cli = ReplayArgs {
+            timeout: Some("500ms".parse().unwrap()),
+            ..ReplayArgs::default()
+        };
+        assert_eq!(
+            cli.merge_with_config(&config).timeout,
+            Some(std::time::Duration::from_millis(500).into())
+        );
+
+        let merged = GenArgs::default().merge_with_config(&config);
+        assert_eq!(merged.sync_time, Some(DurationOpt::from_secs(3600)));
+        let cli = GenArgs {
+            sync_time: Some("20m".parse().unwrap()),
+            ..GenArgs::default()
+        };
+        assert_eq!(
+            cli.merge_with_config(&config).sync_time,
+            Some(DurationOpt::from_secs(1200))
+        );
+
+        let err = toml::from_str::<Args>("[session]\nmirror_interval = \"-5m\"\n").unwrap_err();
+        assert!(err.to_string().contains("mirror_interval"));
+    }
+
+    #[test]
+    fn test_output_on_ramdisk_merge() {
+        let config: Args = toml::from_str(
+            r#"
+            [target]
+            [coverage]
+            [afl_cfg]
+            [session]
+            output_on_ramdisk = true
+            [misc]
+            "#,
+        )
+        .unwrap();
+        assert!(
+            RunArgs::default()
+                .merge_with_config(&config)
+                .output_on_ramdisk
+        );
+        // An explicit local work directory wins over the config
+        let cli = RunArgs {
+            local_work_dir: Some("/scratch/out".into()),
+            ..RunArgs::default()
+        };
+        assert!(!cli.merge_with_config(&config).output_on_ramdisk);
+        assert!(
+            !RunArgs::default()
+                .merge_with_config(&Args::default())
+                .output_on_ramdisk
+        );
+    }
+
+    #[test]
+    fn test_merge_batch_size_merge() {
+        let config: Args = toml::from_str(
+            r#"
+            [target]
+            [coverage]
+            merge_batch_size = 200
+            [afl_cfg]
+            [session]
+            [misc]
+            "#,
+        )
+        .unwrap();
+        let merged = CovArgs::default().merge_with_config(&config);
+        assert_eq!(merged.merge_batch_size.map(|n| n.get()), Some(200));
+        let cli = CovArgs {
+            merge_batch_size: std::num::NonZeroUsize::new(5000),
+            ..CovArgs::default()
+        };
+        assert_eq!(
+            cli.merge_with_config(&config)
+                .merge_batch_size
+                .map(|n| n.get()),
+            Some(5000)
+        );
+        assert!(toml::from_str::<Args>(
+            "[target]\n[coverage]\nmerge_batch_size = 0\n[afl_cfg]\n[session]\n[misc]"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_targets_config() {
+        let config: Args = toml::from_str(
+            r#"
+            [target]
+            [coverage]
+            [afl_cfg]
+            [session]
+            [misc]
+            [[targets]]
+            path = "/bin/fuzz_png"
+            runners = 4
+            [[targets]]
+            name = "zlib"
+            path = "/bin/fuzz_zlib"
+            args = ["@@"]
+            "#,
+        )
+        .unwrap();
+        let targets = config.targets.unwrap();
+        assert_eq!(targets[0].name(), "fuzz_png");
+        assert_eq!(targets[1].name(), "zlib");
+        assert!(validate_targets(&targets).is_ok());
+
+        let duplicate = vec![targets[0].clone(), targets[0].clone()];
+        assert!(validate_targets(&duplicate)
+            .unwrap_err()
+            .to_string()
+            .contains("'fuzz_png'"));
+        let invalid = TargetEntry {
+            name: Some("../up".into()),
+            ..targets[0].clone()
+        };
+        assert!(validate_targets(&[invalid]).is_err());
     }
 
     #[test]
@@ -0,0 +1,16 @@
+use clap::{Args, ValueHint};
+
+#[derive(Args, Clone, Debug)]
+pub struct PauseArgs {
+    /// Session name the instances run in
+    #[arg(
+        value_parser = super::utils::possible_values_session_names,
+        required = true,
+        value_hint = ValueHint::Other
+    )]
+    pub session_name: String,
+
+    /// Instance name as passed to `-M`/`-S`, e.g. `s3_target` (can be repeated)
+    #[arg(long = "instance", required = true, value_hint = ValueHint::Other)]
+    pub instances: Vec<String>,
+}
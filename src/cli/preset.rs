@@ -0,0 +1,171 @@
+use anyhow::{bail, Context, Result};
+use toml::{Table, Value};
+
+use super::legacy::SECTIONS;
+
+/// Top-level config table holding user defined presets
+pub const PRESETS_KEY: &str = "presets";
+
+/// Presets shipped with AFLR as partial configs
+///
+/// AFL++ has no global stop condition besides `-V <secs>`, so time boxed presets pass it via
+/// `afl_flags`.
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "quick-smoke",
+        r#"
+[afl_cfg]
+runners = 2
+mode = "MultipleCores"
+afl_flags = "-V 300"
+
+[session]
+layout = "tiled"
+"#,
+    ),
+    (
+        "deep-overnight",
+        r#"
+[target]
+auto_detect = true
+
+[afl_cfg]
+runners = 16
+mode = "MultipleCores"
+afl_flags = "-V 43200"
+max_crashes_per_instance = 1000
+max_hangs_per_instance = 1000
+archive_pruned = true
+
+[misc]
+detached = true
+"#,
+    ),
+    (
+        "ci",
+        r#"
+[afl_cfg]
+runners = 1
+mode = "CIFuzzing"
+afl_flags = "-V 600"
+
+[misc]
+deterministic = true
+fail_on_warnings = true
+"#,
+    ),
+];
+
+/// Names of the built-in presets
+pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN.iter().map(|(name, _)| *name)
+}
+
+/// Expands preset `name` underneath `config`
+///
+/// The preset is looked up in the `[presets.<name>]` tables of `config` first, so a user
+/// defined preset shadows a built-in one of the same name. Every value that `config` sets
+/// itself wins over the preset, sections missing in both are added empty.
+///
+/// # Errors
+/// * If no preset of that name exists
+/// * If the preset is not a table of config sections
+pub fn apply(config: &mut Table, name: &str) -> Result<()> {
+    let user = match config.get(PRESETS_KEY) {
+        None => None,
+        Some(Value::Table(presets)) => presets.get(name).cloned(),
+        Some(_) => bail!("'{PRESETS_KEY}' must be a table of [{PRESETS_KEY}.<name>] tables"),
+    };
+    let mut preset = match user {
+        Some(Value::Table(preset)) => preset,
+        Some(_) => bail!("Preset '{name}' must be a [{PRESETS_KEY}.{name}] table"),
+        None => builtin(name)?,
+    };
+    if preset.contains_key(PRESETS_KEY) {
+        bail!("Preset '{name}' must not define further presets");
+    }
+
+    let overrides = std::mem::take(config);
+    merge_tables(&mut preset, overrides);
+    for section in SECTIONS {
+        preset
+            .entry(*section)
+            .or_insert_with(|| Value::Table(Table::new()));
+    }
+    *config = preset;
+    Ok(())
+}
+
+fn builtin(name: &str) -> Result<Table> {
+    let Some((_, content)) = BUILTIN.iter().find(|(builtin, _)| *builtin == name) else {
+        bail!(
+            "Unknown preset '{name}', define it as [{PRESETS_KEY}.{name}] or use one of: {}",
+            builtin_names().collect::<Vec<_>>().join(", ")
+        );
+    };
+    toml::from_str(content).with_context(|| format!("Built-in preset '{name}' does not parse"))
+}
+
+/// Recursively merges `overrides` into `base`, values of `overrides` win
+fn merge_tables(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge_tables(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Args;
+    use crate::utils::runner_count::RunnerCount;
+
+    fn expand(config: &str, name: &str) -> Result<Args> {
+        let mut table: Table = toml::from_str(config).unwrap();
+        apply(&mut table, name)?;
+        Ok(Value::Table(table).try_into()?)
+    }
+
+    #[test]
+    fn test_builtin_presets_parse() {
+        for name in builtin_names() {
+            assert!(expand("", name).is_ok(), "{name}");
+        }
+        let args = expand("", "ci").unwrap();
+        assert_eq!(args.afl_cfg.runners, Some(RunnerCount::Fixed(1)));
+        assert_eq!(args.misc.deterministic, Some(true));
+    }
+
+    #[test]
+    fn test_config_overrides_preset() {
+        let config = r#"
+            [afl_cfg]
+            runners = 4
+            [presets.ci]
+            afl_cfg = { afl_flags = "-V 60" }
+            [presets.mine.misc]
+            tui = true
+        "#;
+        // User presets shadow built-in ones entirely
+        let args = expand(config, "ci").unwrap();
+        assert_eq!(args.afl_cfg.runners, Some(RunnerCount::Fixed(4)));
+        assert_eq!(args.afl_cfg.afl_flags.as_deref(), Some("-V 60"));
+        assert_eq!(args.misc.deterministic, None);
+
+        let args = expand(config, "mine").unwrap();
+        assert_eq!(args.misc.tui, Some(true));
+
+        let args = expand(config, "quick-smoke").unwrap();
+        assert_eq!(args.afl_cfg.runners, Some(RunnerCount::Fixed(4)));
+        assert_eq!(args.afl_cfg.afl_flags.as_deref(), Some("-V 300"));
+
+        let err = expand(config, "nightly").unwrap_err().to_string();
+        assert!(err.contains("Unknown preset 'nightly'"), "{err}");
+        assert!(err.contains("quick-smoke, deep-overnight, ci"), "{err}");
+        assert!(expand("presets = 1", "ci").is_err());
+    }
+}
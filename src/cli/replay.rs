@@ -0,0 +1,106 @@
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+use crate::utils::duration::DurationOpt;
+
+/// Binary variant of the target to replay an input against
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BinaryVariant {
+    /// Main instrumented target
+    #[default]
+    Target,
+    /// Sanitizer build
+    San,
+    /// CMPLOG build
+    Cmplog,
+    /// CMPCOV build
+    Cmpcov,
+    /// Coverage build
+    Cov,
+}
+
+#[derive(Args, Clone, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ReplayArgs {
+    /// AFL++ output directory of the campaign
+    #[arg(help = "Output directory of the campaign")]
+    pub output_dir: Option<PathBuf>,
+
+    /// Id of the queue, crash or hang entry
+    #[arg(
+        long,
+        help = "Id of the queue/crash/hang entry, e.g. 000123",
+        conflicts_with = "file",
+        required_unless_present = "file"
+    )]
+    pub id: Option<String>,
+
+    /// Path to the test case
+    #[arg(long, help = "Path of the test case to replay")]
+    pub file: Option<PathBuf>,
+
+    /// Index of the entry if the id matches in multiple instances
+    #[arg(long, help = "Pick an entry if the id matches multiple instances")]
+    pub select: Option<usize>,
+
+    /// Binary variant to replay against
+    #[arg(long, value_enum, default_value_t = BinaryVariant::Target)]
+    pub binary: BinaryVariant,
+
+    /// Replay against every configured binary variant
+    #[arg(long, help = "Replay against every configured binary variant", conflicts_with_all = ["binary", "gdb"])]
+    pub all: bool,
+
+    /// Run the target under gdb
+    #[arg(long, help = "Run the target under gdb")]
+    pub gdb: bool,
+
+    /// Timeout of a single execution
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Timeout for a single execution, e.g. '500ms' or '1m', bare numbers are seconds [default: 10s]"
+    )]
+    pub timeout: Option<DurationOpt>,
+
+    /// Instrumented target binary
+    #[arg(short, long, help = "Instrumented target binary")]
+    pub target: Option<PathBuf>,
+
+    /// Sanitizer binary
+    #[arg(short = 's', long, help = "Sanitizer binary")]
+    pub san_target: Option<PathBuf>,
+
+    /// CMPLOG binary
+    #[arg(short = 'c', long, help = "CMPLOG binary")]
+    pub cmpl_target: Option<PathBuf>,
+
+    /// CMPCOV binary
+    #[arg(short = 'l', long, help = "CMPCOV binary")]
+    pub cmpc_target: Option<PathBuf>,
+
+    /// Coverage binary
+    #[arg(long, help = "Coverage binary")]
+    pub cov_target: Option<PathBuf>,
+
+    /// Target binary arguments
+    #[arg(help = "Target binary arguments, including @@ if needed", last = true)]
+    pub target_args: Option<Vec<String>>,
+
+    /// Path to a TOML config file
+    #[arg(long, help = "Path to TOML config file, '-' reads it from stdin")]
+    pub config: Option<PathBuf>,
+}
+
+impl ReplayArgs {
+    /// Binary configured for `variant`
+    pub fn binary_for(&self, variant: BinaryVariant) -> Option<&PathBuf> {
+        match variant {
+            BinaryVariant::Target => self.target.as_ref(),
+            BinaryVariant::San => self.san_target.as_ref(),
+            BinaryVariant::Cmplog => self.cmpl_target.as_ref(),
+            BinaryVariant::Cmpcov => self.cmpc_target.as_ref(),
+            BinaryVariant::Cov => self.cov_target.as_ref(),
+        }
+    }
+}
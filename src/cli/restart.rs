@@ -0,0 +1,26 @@
+use clap::{Args, ValueHint};
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug)]
+pub struct RestartArgs {
+    /// Session name the instance runs in
+    #[arg(
+        value_parser = super::utils::possible_values_session_names,
+        required = true,
+        value_hint = ValueHint::Other
+    )]
+    pub session_name: String,
+
+    /// Instance name as passed to `-M`/`-S`, e.g. `s3_target`
+    #[arg(long, required = true, value_hint = ValueHint::Other)]
+    pub instance: String,
+
+    /// Output directory of the campaign
+    #[arg(
+        short = 'o',
+        long,
+        value_hint = ValueHint::DirPath,
+        help = "Output directory of the campaign, only needed if the instance is not running anymore"
+    )]
+    pub output_dir: Option<PathBuf>,
+}
@@ -1,10 +1,13 @@
-use clap::Args;
+use clap::{ArgGroup, Args};
+use std::path::PathBuf;
 
 use super::GenArgs;
-use crate::cli::SessionRunner;
+use crate::cli::{DockerMode, SessionLayout, SessionRunner};
+use crate::utils::duration::DurationOpt;
 
 #[derive(Args, Clone, Debug, Default)]
 #[allow(clippy::struct_excessive_bools)]
+#[command(group(ArgGroup::new("work_dir").args(["local_work_dir", "output_on_ramdisk"])))]
 pub struct RunArgs {
     /// Arguments for generating the commands
     #[command(flatten)]
@@ -14,19 +17,64 @@ pub struct RunArgs {
     #[arg(long, help = "Output commands without executing")]
     pub dry_run: bool,
 
+    /// Hand-tweak the generated commands before they are launched
+    #[arg(
+        long,
+        help = "Open the generated commands in $EDITOR and launch the edited set"
+    )]
+    pub edit: bool,
+
     /// Runner backend to use
     #[clap(value_enum)]
     #[arg(long = "session-runner", help = "Session runner to use", default_value_t = SessionRunner::Tmux)]
     pub session_runner: SessionRunner,
 
+    /// How the instances are arranged in the session
+    #[arg(
+        long,
+        value_enum,
+        help = "Arrange the instances in one window each, tiled panes or stacked panes of one window [default: window-per-instance]"
+    )]
+    pub layout: Option<SessionLayout>,
+
     /// Custom tmux session name
     #[arg(long = "session-name", help = "Custom runner session name")]
     pub session_name: Option<String>,
 
+    /// Label recorded with the campaign
+    #[arg(
+        long,
+        help = "Label recorded with the campaign and shown by `aflr list`"
+    )]
+    pub tag: Option<String>,
+
     /// Enable tui mode
     #[arg(long, help = "Enable TUI mode")]
     pub tui: bool,
 
+    /// Serve the campaign data on a UNIX socket while the TUI runs
+    #[arg(
+        long,
+        help = "Serve status and stop/pause/resume control on <output>/.aflr/control.sock",
+        requires = "tui"
+    )]
+    pub control_socket: bool,
+
+    /// Compress the corpus snapshots taken while the TUI runs
+    #[arg(
+        long,
+        help = "Store the snapshots taken every [misc] snapshot_interval as tarballs instead of hardlinks",
+        requires = "tui"
+    )]
+    pub snapshot_compress: bool,
+
+    /// Print a single progress line instead of attaching to the session
+    #[arg(
+        long,
+        help = "Print a progress line in the foreground instead of attaching, Ctrl-C stops the fuzzers"
+    )]
+    pub progress: bool,
+
     /// Start detached from any session (not compatible with TUI)
     #[arg(long, help = "Start detached from session")]
     pub detached: bool,
@@ -34,6 +82,129 @@ pub struct RunArgs {
     /// Use `RAMDisk` for AFL++
     #[arg(long, help = "Use RAMDisk for AFL++")]
     pub is_ramdisk: bool,
+
+    /// Start even if the output directory is used by another running session
+    #[arg(
+        long,
+        help = "Start even if the output directory is in use by a running session"
+    )]
+    pub force: bool,
+
+    /// Retry a failed start once with the `AFL_MAP_SIZE` AFL++ asked for
+    #[arg(
+        long,
+        help = "Retry once with the AFL_MAP_SIZE AFL++ reports when all instances fail on the map size"
+    )]
+    pub auto_remediate: bool,
+
+    /// Fix a core pattern AFL++ refuses to start with
+    #[arg(
+        long,
+        help = "Set /proc/sys/kernel/core_pattern to 'core' if crashes are piped to a handler (needs root)"
+    )]
+    pub fix_env: bool,
+
+    /// Check the instrumentation with afl-showmap before launching
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        help = "Trace a few seeds with afl-showmap and abort if the target is not instrumented or the map is constant [default: true if the input directory holds seeds]"
+    )]
+    pub verify_instrumentation: Option<bool>,
+
+    /// Tuples every traced seed has to cover
+    #[arg(
+        long,
+        value_name = "NUM",
+        help = "Tuples every seed traced by --verify-instrumentation has to cover [default: 16]"
+    )]
+    pub min_tuples: Option<usize>,
+
+    /// Probe the campaign for the given time and print a throughput estimate
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Run the campaign as a probe for e.g. '10m', print a throughput estimate and tear it down again",
+        conflicts_with_all = ["tui", "progress", "detached", "dry_run", "remote", "docker", "work_dir"]
+    )]
+    pub calibrate: Option<DurationOpt>,
+
+    /// Keep what the probe wrote to the output directory
+    #[arg(
+        long,
+        help = "Keep the output of a --calibrate probe instead of restoring the output directory",
+        requires = "calibrate"
+    )]
+    pub keep_probe_output: bool,
+
+    /// Local directory the instances write to while results are mirrored to the output directory
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Fuzz in a local directory and mirror the results to the output directory"
+    )]
+    pub local_work_dir: Option<PathBuf>,
+
+    /// Place the whole output directory on a `RAMDisk` and mirror it to the output directory
+    #[arg(
+        long,
+        help = "Fuzz in an output directory on a RAMDisk and mirror the results to the output directory"
+    )]
+    pub output_on_ramdisk: bool,
+
+    /// Interval for mirroring the local work directory
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Time between two mirror passes of --local-work-dir/--output-on-ramdisk, e.g. '90s' or '5m', bare numbers are seconds [default: 60s]",
+        requires = "work_dir"
+    )]
+    pub mirror_interval: Option<DurationOpt>,
+
+    /// Launch the session on a remote host over SSH
+    #[arg(
+        long,
+        value_name = "USER@HOST",
+        help = "Launch the session on a remote host over SSH"
+    )]
+    pub remote: Option<String>,
+
+    /// Path prefix mappings applied to the commands for `--remote`
+    #[arg(
+        long,
+        value_name = "LOCAL=REMOTE",
+        help = "Rewrite local path prefixes for the remote host (repeatable)",
+        requires = "remote"
+    )]
+    pub remote_path_map: Vec<String>,
+
+    /// Run the fuzzers inside Docker containers of the given image
+    #[arg(
+        long,
+        value_name = "IMAGE",
+        help = "Run the fuzzers inside containers of the given Docker image",
+        conflicts_with = "remote"
+    )]
+    pub docker: Option<String>,
+
+    /// How the fuzzers are distributed over containers
+    #[arg(
+        long,
+        value_enum,
+        help = "One container per instance or the whole session in one container [default: instance]",
+        requires = "docker"
+    )]
+    pub docker_mode: Option<DockerMode>,
+
+    /// Run the containers with `--privileged`
+    #[arg(
+        long,
+        help = "Run the containers with --privileged (e.g. to tune the host for AFL++)",
+        requires = "docker"
+    )]
+    pub docker_privileged: bool,
 }
 
 #[cfg(test)]
@@ -47,6 +218,7 @@ mod tests {
         assert!(!args.tui);
         assert!(!args.detached);
         assert!(!args.is_ramdisk);
+        assert!(!args.force);
     }
 
     #[test]
@@ -1,14 +1,42 @@
 use clap::ValueEnum;
 use serde::Deserialize;
 
+use crate::utils::duration::DurationOpt;
+
 #[derive(Deserialize, Default, Debug, Clone)]
 pub struct SessionArgs {
     /// Dry run mode
     pub dry_run: Option<bool>,
     /// Session name
     pub name: Option<String>,
+    /// Free-form label shown by `aflr list`
+    pub tag: Option<String>,
     /// Session runner
     pub runner: Option<String>,
+    /// How the instances are arranged in the session
+    pub layout: Option<SessionLayout>,
+    /// SSH destination to launch the session on
+    pub remote: Option<String>,
+    /// Local to remote path prefix mappings (`LOCAL=REMOTE`)
+    pub remote_path_map: Option<Vec<String>>,
+    /// Docker image to run the fuzzers in
+    pub docker: Option<String>,
+    /// How the fuzzers are distributed over containers
+    pub docker_mode: Option<String>,
+    /// Run the containers with `--privileged`
+    pub docker_privileged: Option<bool>,
+    /// Retry a start that failed on the map size once with the size AFL++ asked for
+    pub auto_remediate: Option<bool>,
+    /// Set a piped core pattern to `core` before launching instead of aborting
+    pub fix_env: Option<bool>,
+    /// Check the instrumentation with `afl-showmap` before launching
+    pub verify_instrumentation: Option<bool>,
+    /// Tuples every seed traced by the instrumentation check has to cover
+    pub min_tuples: Option<usize>,
+    /// Time between two mirror passes of the local work directory
+    pub mirror_interval: Option<DurationOpt>,
+    /// Place the output directory on a `RAMDisk` and mirror it to the output directory
+    pub output_on_ramdisk: Option<bool>,
 }
 
 #[derive(ValueEnum, Clone, Debug, Default)]
@@ -29,6 +57,48 @@ impl From<&str> for SessionRunner {
     }
 }
 
+/// How the instances of a session are arranged
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionLayout {
+    /// One window per instance, named after the instance
+    #[default]
+    WindowPerInstance,
+    /// All instances as panes of one window, tiled in a grid
+    Tiled,
+    /// All instances as panes of one window, stacked on top of each other
+    SingleWindow,
+}
+
+impl SessionLayout {
+    /// Name of the layout as used in the config and the session templates
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WindowPerInstance => "window-per-instance",
+            Self::Tiled => "tiled",
+            Self::SingleWindow => "single-window",
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DockerMode {
+    /// Wrap every instance in its own container
+    #[default]
+    Instance,
+    /// Run the whole session inside a single container
+    Session,
+}
+
+impl From<&str> for DockerMode {
+    fn from(s: &str) -> Self {
+        match s {
+            "session" => Self::Session,
+            _ => Self::Instance,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,37 @@
+use clap::{Args, Subcommand, ValueHint};
+use std::path::PathBuf;
+
+use super::constants::AFL_OUTPUT;
+
+#[derive(Args, Clone, Debug)]
+pub struct SnapshotsArgs {
+    #[command(subcommand)]
+    pub cmd: SnapshotsCommands,
+}
+
+/// Snapshot subcommands
+#[derive(Subcommand, Clone, Debug)]
+pub enum SnapshotsCommands {
+    /// Lists the snapshots of a campaign, oldest first
+    List(SnapshotsListArgs),
+    /// Replaces the queues and solutions of a stopped campaign with a snapshot
+    Restore(SnapshotsRestoreArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct SnapshotsListArgs {
+    /// Output directory of the campaign
+    #[arg(short, long, default_value = AFL_OUTPUT, value_hint = ValueHint::DirPath)]
+    pub output_dir: PathBuf,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct SnapshotsRestoreArgs {
+    /// Snapshot to restore, e.g. `20240501T120000Z`
+    #[arg(required = true, value_name = "TIMESTAMP")]
+    pub name: String,
+
+    /// Output directory of the campaign
+    #[arg(short, long, default_value = AFL_OUTPUT, value_hint = ValueHint::DirPath)]
+    pub output_dir: PathBuf,
+}
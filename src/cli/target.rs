@@ -1,4 +1,10 @@
+use anyhow::{bail, Result};
 use serde::Deserialize;
+use std::{collections::HashSet, path::Path};
+
+use crate::afl::harness::HarnessBinary;
+use crate::afl::target_env::TargetEnv;
+use crate::utils::runner_count::RunnerCount;
 
 #[derive(Deserialize, Default, Debug, Clone)]
 pub struct TargetArgs {
@@ -12,6 +18,67 @@ pub struct TargetArgs {
     pub cmpc_path: Option<String>,
     /// Path to the Coverage binary
     pub cov_path: Option<String>,
+    /// Pick up CMPLOG/CMPCOV/sanitizer builds next to `path` by their name
+    pub auto_detect: Option<bool>,
+    /// Run the sanitizer binary on a secondary instead of the main instance
+    pub san_on_secondary: Option<bool>,
     /// Arguments for the target binary
     pub args: Option<Vec<String>>,
+    /// Binaries whose interpreter and shared libraries are not checked
+    pub skip_dep_check: Option<Vec<HarnessBinary>>,
+    /// Template of the per-instance `-f` input file
+    pub fixed_input_file: Option<String>,
+    /// Environment variables of the harness, `@@` is replaced with the input file
+    pub env: Option<TargetEnv>,
+}
+
+/// A `[[targets]]` entry of a campaign fuzzing several harnesses side by side
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct TargetEntry {
+    /// Name of the output subdirectory and session suffix, defaults to the binary name
+    pub name: Option<String>,
+    /// Path to the target binary
+    pub path: String,
+    /// Path to the sanitizer binary
+    pub san_path: Option<String>,
+    /// Path to the CMPLOG binary
+    pub cmpl_path: Option<String>,
+    /// Path to the CMPCOV binary
+    pub cmpc_path: Option<String>,
+    /// Arguments for the target binary, defaults to `[target] args`
+    pub args: Option<Vec<String>>,
+    /// Number of AFL++ runners or a percentage of the cores, defaults to `[afl_cfg] runners`
+    pub runners: Option<RunnerCount>,
+}
+
+impl TargetEntry {
+    /// Name of the target, the explicit one or the file name of its binary
+    pub fn name(&self) -> String {
+        self.name
+            .clone()
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| {
+                Path::new(&self.path)
+                    .file_name()
+                    .map_or_else(|| self.path.clone(), |n| n.to_string_lossy().into_owned())
+            })
+    }
+}
+
+/// Ensures every target has a distinct name that is usable as a directory name
+///
+/// # Errors
+/// * If a name is empty, contains a `/` or is used by more than one target
+pub fn validate_targets(targets: &[TargetEntry]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for target in targets {
+        let name = target.name();
+        if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+            bail!("Invalid name '{name}' for target {}", target.path);
+        }
+        if !seen.insert(name.clone()) {
+            bail!("More than one target is named '{name}', set distinct names via 'name = ...'");
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,20 @@
+use clap::{Args, ValueHint};
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct TopArgs {
+    /// Path to the `AFLPlusPlus` campaign directory, e.g. `afl_output`
+    #[arg(
+        help = "Path to an AFLPlusPlus campaign directory, e.g. `afl_output`",
+        value_hint = ValueHint::DirPath
+    )]
+    pub afl_output: PathBuf,
+
+    /// Print a single snapshot and exit
+    #[arg(long, help = "Print the table once and exit instead of refreshing it")]
+    pub once: bool,
+
+    /// Path to a TOML config file
+    #[arg(long, help = "Path to TOML config file, '-' reads it from stdin")]
+    pub config: Option<PathBuf>,
+}
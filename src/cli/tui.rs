@@ -3,10 +3,101 @@ use std::path::PathBuf;
 
 #[derive(Args, Clone, Debug, Default)]
 pub struct TuiArgs {
-    /// Path to a `AFLPlusPlus` campaign directory, e.g. `afl_output`
+    /// Paths to `AFLPlusPlus` campaign directories, e.g. `afl_output`
     #[arg(
-        help = "Path to a AFLPlusPlus campaign directory, e.g. `afl_output`",
-        required = true
+        help = "Paths to AFLPlusPlus campaign directories, e.g. `afl_output`. Several are shown as one overview",
+        required_unless_present = "ssh",
+        conflicts_with = "ssh",
+        num_args = 1..
     )]
-    pub afl_output: PathBuf,
+    pub afl_output: Vec<PathBuf>,
+
+    /// Monitor a campaign on another host
+    #[arg(
+        long,
+        value_name = "[USER@]HOST:PATH",
+        help = "Monitor the output directory of a campaign on another host by fetching its fuzzer_stats with rsync over SSH (key based authentication)"
+    )]
+    pub ssh: Option<String>,
+
+    /// Append metrics as JSON Lines to a file or named pipe
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Append one JSON object per collection tick to a file or named pipe"
+    )]
+    pub jsonl: Option<PathBuf>,
+
+    /// Append every log line to a file
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Append every timestamped TUI log line to a file for post-mortem analysis"
+    )]
+    pub log_file: Option<PathBuf>,
+
+    /// Name of the statistics file in every instance directory
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Read the instance statistics from NAME instead of fuzzer_stats, e.g. for AFL++ forks or wrappers",
+        conflicts_with = "ssh"
+    )]
+    pub stats_filename: Option<String>,
+
+    /// Log lines retained for the log pane
+    #[arg(
+        long,
+        value_name = "LINES",
+        help = "Log lines the TUI retains [default: 10]. More lines keep more history at the cost of memory"
+    )]
+    pub log_capacity: Option<usize>,
+
+    /// Show exact numbers instead of human-scaled ones
+    #[arg(
+        long,
+        help = "Show exact thousands-separated numbers instead of scaled ones (toggle with 'r')"
+    )]
+    pub raw_numbers: bool,
+
+    /// Serve the campaign data on a UNIX socket
+    #[arg(
+        long,
+        help = "Serve status and stop/pause/resume control on <afl_output>/.aflr/control.sock, single campaigns only"
+    )]
+    pub control_socket: bool,
+
+    /// Anonymize the exported campaign data
+    #[arg(
+        long,
+        help = "Replace fuzzer names, the banner and paths with stable hashes in the --jsonl output and on the control socket"
+    )]
+    pub anonymize: bool,
+
+    /// Highlight crashes as they appear
+    #[arg(
+        long,
+        help = "Highlight every crash that appeared since the last refresh for live triage"
+    )]
+    pub follow_new_crashes: bool,
+
+    /// Command new crashes are opened with
+    #[arg(
+        long,
+        value_name = "CMD",
+        help = "Shell command every new crash is opened with while following, e.g. 'xterm -e less'. The crash path is appended",
+        requires = "follow_new_crashes"
+    )]
+    pub crash_viewer: Option<String>,
+
+    /// Compress corpus snapshots
+    #[arg(
+        long,
+        help = "Store the snapshots taken every [misc] snapshot_interval as tarballs instead of hardlinks"
+    )]
+    pub snapshot_compress: bool,
+
+    /// Path to a TOML config file
+    #[arg(long, help = "Path to TOML config file, '-' reads it from stdin")]
+    pub config: Option<PathBuf>,
 }
@@ -4,7 +4,12 @@ use std::path::PathBuf;
 use std::process;
 use tempfile::TempDir;
 
-use crate::{argument_aggregator::ArgumentAggregator, cli::AddSeedArgs, commands::Command};
+use crate::{
+    argument_aggregator::ArgumentAggregator,
+    cli::AddSeedArgs,
+    commands::{Command, ExitCode},
+    utils::lock::CampaignLock,
+};
 
 pub struct AddSeedCommand<'a> {
     args: &'a AddSeedArgs,
@@ -83,7 +88,7 @@ impl<'a> AddSeedCommand<'a> {
 }
 
 impl Command for AddSeedCommand<'_> {
-    fn execute(&self) -> Result<()> {
+    fn execute(&self) -> Result<ExitCode> {
         let merged_args = self.arg_aggregator.merge_add_seed_args(self.args)?;
 
         let target = merged_args.target.as_ref().context("Target is required")?;
@@ -96,8 +101,9 @@ impl Command for AddSeedCommand<'_> {
             .as_ref()
             .context("Output directory is required")?;
 
+        let _lock = CampaignLock::acquire(output_dir, "add-seed")?;
         Self::add_seed(&merged_args.seed, target, target_args, output_dir)?;
         println!("[+] Seeds added successfully");
-        Ok(())
+        Ok(ExitCode::Success)
     }
 }
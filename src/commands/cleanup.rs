@@ -0,0 +1,149 @@
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cli::{constants, CleanupArgs},
+    commands::{list::ListCommand, Command, ExitCode},
+    runners::runner::session_exists,
+    utils::cleanup::{
+        is_mirrored, remove_ramdisk, session_ramdisks, tag_ramdisk, Cleanup, Leftover, RAMDISK_BASE,
+    },
+};
+
+/// Removes the `RAMDisk`s and temporary files of campaigns that are gone
+pub struct CleanupCommand<'a> {
+    args: &'a CleanupArgs,
+}
+
+impl<'a> CleanupCommand<'a> {
+    pub fn new(args: &'a CleanupArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl Command for CleanupCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        let min_age = self
+            .args
+            .older_than
+            .unwrap_or(constants::CLEANUP_MIN_AGE)
+            .as_duration();
+        let leftovers = Cleanup::new(min_age).stale(session_exists);
+        if leftovers.is_empty() {
+            println!("[+] Nothing to clean up");
+            return Ok(ExitCode::Success);
+        }
+
+        let mut failed = 0;
+        let mut freed = 0;
+        for leftover in &leftovers {
+            let size = leftover.size();
+            let description = format!(
+                "{} ({})",
+                describe(leftover),
+                ListCommand::format_bytes(size)
+            );
+            if self.args.dry_run {
+                println!("[*] Would remove {description}");
+                freed += size;
+                continue;
+            }
+            match leftover.remove() {
+                Ok(()) => {
+                    println!("[+] Removed {description}");
+                    freed += size;
+                }
+                Err(e) => {
+                    println!("[!] Warning: {e:#}");
+                    failed += 1;
+                }
+            }
+        }
+        println!(
+            "[+] {} {} of {} leftovers",
+            if self.args.dry_run {
+                "Would free"
+            } else {
+                "Freed"
+            },
+            ListCommand::format_bytes(freed),
+            leftovers.len() - failed
+        );
+        if failed > 0 {
+            bail!("Failed to remove {failed} of {} leftovers", leftovers.len());
+        }
+        Ok(ExitCode::Success)
+    }
+}
+
+fn describe(leftover: &Leftover) -> String {
+    match leftover {
+        Leftover::Ramdisk {
+            path,
+            session: Some(session),
+        } => format!("RAMDisk {} of session '{session}'", path.display()),
+        Leftover::Ramdisk {
+            path,
+            session: None,
+        } => format!("RAMDisk {}", path.display()),
+        Leftover::Temp(path) => path.display().to_string(),
+    }
+}
+
+/// Unmounts and removes the `RAMDisk`s created for `session_name`
+///
+/// `RAMDisk`s that are still mirrored are kept for `cleanup`, the mirror makes its final pass
+/// only after the fuzzers are gone.
+pub fn release_ramdisks(session_name: &str, quiet: bool) {
+    for ramdisk in session_ramdisks(Path::new(RAMDISK_BASE), session_name) {
+        if is_mirrored(&ramdisk) {
+            if !quiet {
+                println!(
+                    "[*] RAMDisk {} is still being mirrored, run `aflr cleanup` once the mirror finished",
+                    ramdisk.display()
+                );
+            }
+            continue;
+        }
+        match remove_ramdisk(&ramdisk) {
+            Ok(()) if !quiet => println!("[+] Removed RAMDisk {}", ramdisk.display()),
+            Ok(()) => {}
+            Err(e) => println!("[!] Warning: {e:#}"),
+        }
+    }
+}
+
+/// The `RAMDisk`s of a session that is being launched, released again unless the session
+/// outlives this invocation
+pub struct SessionRamdisks {
+    session_name: String,
+    keep: bool,
+}
+
+impl SessionRamdisks {
+    /// Tags `ramdisks` with `session_name`
+    pub fn new(session_name: &str, ramdisks: &[PathBuf]) -> Self {
+        for ramdisk in ramdisks {
+            if let Err(e) = tag_ramdisk(ramdisk, session_name) {
+                println!("[!] Warning: {e:#}");
+            }
+        }
+        Self {
+            session_name: session_name.to_string(),
+            keep: ramdisks.is_empty(),
+        }
+    }
+
+    /// Keeps the `RAMDisk`s even if no local session exists, e.g. for a Docker session
+    pub fn keep(mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for SessionRamdisks {
+    fn drop(&mut self) {
+        if !self.keep && !session_exists(&self.session_name) {
+            release_ramdisks(&self.session_name, false);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::Result;
+
+use crate::{
+    afl::triage,
+    cli::CollectCrashesArgs,
+    commands::{Command, ExitCode},
+};
+
+pub struct CollectCrashesCommand<'a> {
+    args: &'a CollectCrashesArgs,
+}
+
+impl<'a> CollectCrashesCommand<'a> {
+    pub fn new(args: &'a CollectCrashesArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl Command for CollectCrashesCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        let stats =
+            triage::collect_crashes(&self.args.output_dir, &self.args.into, self.args.mode)?;
+        println!(
+            "[+] Collected {} new crashes into {} ({} already present)",
+            stats.collected,
+            self.args.into.display(),
+            stats.existing
+        );
+        Ok(ExitCode::Success)
+    }
+}
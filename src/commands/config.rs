@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use std::fs;
+
+use crate::{
+    cli::{legacy, ConfigArgs, ConfigCommands, MigrateArgs},
+    commands::{Command, ExitCode},
+};
+
+pub struct ConfigCommand<'a> {
+    args: &'a ConfigArgs,
+}
+
+impl<'a> ConfigCommand<'a> {
+    pub fn new(args: &'a ConfigArgs) -> Self {
+        Self { args }
+    }
+
+    fn migrate(args: &MigrateArgs) -> Result<()> {
+        let content = fs::read_to_string(&args.path)
+            .with_context(|| format!("Failed to read config file: {}", args.path.display()))?;
+        let migration = legacy::migrate(&content)
+            .with_context(|| format!("Failed to migrate {}", args.path.display()))?;
+
+        if args.dry_run {
+            print!("{}", migration.content);
+            return Ok(());
+        }
+        if !migration.is_needed() {
+            println!(
+                "[+] {} already uses config_version {}, nothing to do",
+                args.path.display(),
+                legacy::CONFIG_VERSION
+            );
+            return Ok(());
+        }
+
+        for renamed in &migration.renamed {
+            println!("[*] Renamed {renamed}");
+        }
+        for unmapped in &migration.unmapped {
+            println!("[!] Warning: Could not map {unmapped}, kept as a comment");
+        }
+
+        let output = if let Some(output) = &args.output {
+            output.clone()
+        } else {
+            let backup = args.path.with_extension("toml.bak");
+            fs::copy(&args.path, &backup)
+                .with_context(|| format!("Failed to back up config to {}", backup.display()))?;
+            println!("[*] Kept the original config at {}", backup.display());
+            args.path.clone()
+        };
+        fs::write(&output, &migration.content)
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        println!(
+            "[+] Migrated config from version {} to {} at {}",
+            migration.from_version,
+            legacy::CONFIG_VERSION,
+            output.display()
+        );
+        Ok(())
+    }
+}
+
+impl Command for ConfigCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        match &self.args.cmd {
+            ConfigCommands::Migrate(args) => Self::migrate(args),
+        }
+        .map(|()| ExitCode::Success)
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::Result;
 
 use crate::{
-    afl::coverage::CoverageCollector, argument_aggregator::ArgumentAggregator, cli::CovArgs,
-    commands::Command,
+    afl::{coverage::CoverageCollector, target_env::TargetEnv},
+    argument_aggregator::ArgumentAggregator,
+    cli::CovArgs,
+    commands::{Command, ExitCode},
+    utils::{clock::Clock, system::artifact_dir},
 };
 
 pub struct CovCommand<'a> {
@@ -20,15 +23,23 @@ impl<'a> CovCommand<'a> {
 }
 
 impl Command for CovCommand<'_> {
-    fn execute(&self) -> Result<()> {
+    fn execute(&self) -> Result<ExitCode> {
         let merged_args = self.arg_aggregator.merge_cov_args(self.args)?;
-        let mut cov_collector =
-            CoverageCollector::new(merged_args.target.unwrap(), merged_args.output_dir.unwrap())?;
+        let output_dir = merged_args.output_dir.unwrap();
+        let artifact_dir = artifact_dir(&output_dir, merged_args.work_dir.as_deref())?;
+        let mut cov_collector = CoverageCollector::new(merged_args.target.unwrap(), output_dir)?;
+        cov_collector
+            .with_artifact_dir(artifact_dir)
+            .with_clock(Clock::from_env());
 
         if let Some(target_args) = &merged_args.target_args {
             cov_collector.with_target_args(target_args.clone());
         }
 
+        if !merged_args.target_env.is_empty() {
+            cov_collector.with_target_env(TargetEnv::from_vars(&merged_args.target_env)?);
+        }
+
         if merged_args.split_report {
             cov_collector.with_split_report(true);
         }
@@ -41,10 +52,35 @@ impl Command for CovCommand<'_> {
             cov_collector.with_misc_report_args(merged_args.report_args.clone().unwrap());
         }
 
+        if let Some(input_mode) = merged_args.input_mode {
+            cov_collector.with_input_mode(input_mode);
+        }
+
+        if merged_args.sandbox {
+            cov_collector.with_sandbox(true);
+        }
+
+        if merged_args.attribute_instances {
+            cov_collector.with_attribute_instances(true);
+        }
+
+        if merged_args.attribute_unique {
+            cov_collector.with_attribute_unique(true);
+        }
+
         if merged_args.text_report {
             cov_collector.with_html(false);
         }
 
-        cov_collector.collect()
+        if let Some(size) = merged_args.merge_batch_size {
+            cov_collector.with_merge_batch_size(size);
+        }
+
+        if let Some(path) = &merged_args.json_export {
+            cov_collector.with_json_export(path.clone());
+        }
+
+        cov_collector.collect()?;
+        Ok(ExitCode::Success)
     }
 }
@@ -0,0 +1,35 @@
+use anyhow::{bail, Result};
+
+use crate::{
+    afl::doctor::{run_checks, CheckStatus},
+    commands::{Command, ExitCode},
+};
+
+pub struct DoctorCommand;
+
+impl Command for DoctorCommand {
+    fn execute(&self) -> Result<ExitCode> {
+        let checks = run_checks();
+        for check in &checks {
+            println!("{check}");
+        }
+
+        let failed = checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .count();
+        let warned = checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Warn)
+            .count();
+        if failed > 0 {
+            bail!("[-] {failed} check(s) failed, {warned} warning(s)");
+        }
+        if warned > 0 {
+            println!("[!] Warning: {warned} check(s) need attention, fuzzing will still work");
+        } else {
+            println!("[+] All checks passed");
+        }
+        Ok(ExitCode::Success)
+    }
+}
@@ -0,0 +1,34 @@
+use anyhow::Result;
+
+use crate::{
+    afl::{cmd_gen::AFLCmdGenerator, env::is_generated_env_var, warnings::Warnings},
+    commands::{Command, ExitCode},
+};
+
+pub struct EnvCheckCommand;
+
+impl Command for EnvCheckCommand {
+    fn execute(&self) -> Result<ExitCode> {
+        let mut env_vars = AFLCmdGenerator::get_afl_env_vars(&mut Warnings::default());
+        if env_vars.is_empty() {
+            println!(
+                "[+] No AFL_* variables exported, commands only get the variables AFLR generates"
+            );
+            return Ok(ExitCode::Success);
+        }
+        env_vars.sort();
+
+        println!("[*] Exported AFL_* variables and how generated commands inherit them:");
+        let width = env_vars.iter().map(String::len).max().unwrap_or(0);
+        for env in &env_vars {
+            let key = env.split('=').next().unwrap_or(env);
+            let note = if is_generated_env_var(key) {
+                format!("inherited unless AFLR generates {key} for an instance, the generated value wins")
+            } else {
+                "inherited by every command".to_string()
+            };
+            println!("    {env:width$}  {note}");
+        }
+        Ok(ExitCode::Success)
+    }
+}
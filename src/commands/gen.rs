@@ -1,88 +1,211 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use std::io::{self, IsTerminal};
 use std::path::Path;
 
 use crate::{
-    afl::{base_cfg::Bcfg, cmd::Printable, cmd_gen::AFLCmdGenerator, harness::Harness},
+    afl::{
+        base_cfg::{Bcfg, RawAflFlags},
+        builds::{detect_builds, BuildRole, DetectedBuilds},
+        cmd::{Printable, ToStringVec},
+        cmd_diff::{self, CommandDiff, InstanceCommand},
+        cmd_gen::AFLCmdGenerator,
+        harness::Harness,
+        strategies::{FlagBlacklist, InstanceFilter},
+        target_env::TargetEnv,
+        warnings::Warnings,
+    },
     argument_aggregator::ArgumentAggregator,
     cli::constants,
-    cli::GenArgs,
-    commands::Command,
+    cli::{GenArgs, GenCommandArgs},
+    commands::{Command, ExitCode},
+    utils::runner_count::RunnerCount,
 };
 
 pub struct GenCommand<'a> {
-    args: &'a GenArgs,
+    args: &'a GenCommandArgs,
     arg_aggregator: &'a ArgumentAggregator,
 }
 
 impl<'a> GenCommand<'a> {
-    pub fn new(args: &'a GenArgs, arg_aggregator: &'a ArgumentAggregator) -> Self {
+    pub fn new(args: &'a GenCommandArgs, arg_aggregator: &'a ArgumentAggregator) -> Self {
         Self {
             args,
             arg_aggregator,
         }
     }
 
+    /// Detect the auxiliary builds next to `target` that were not set explicitly
+    ///
+    /// # Errors
+    /// * If a role has more than one candidate
+    fn detect_builds(gen_args: &GenArgs, target: &Path) -> Result<DetectedBuilds> {
+        let skip: Vec<BuildRole> = [
+            (BuildRole::Sanitizer, &gen_args.san_target),
+            (BuildRole::Cmplog, &gen_args.cmpl_target),
+            (BuildRole::Cmpcov, &gen_args.cmpc_target),
+        ]
+        .into_iter()
+        .filter_map(|(role, explicit)| explicit.is_some().then_some(role))
+        .collect();
+
+        let detected = detect_builds(target, &skip)?;
+        for (role, path) in [
+            (BuildRole::Sanitizer, &detected.sanitizer),
+            (BuildRole::Cmplog, &detected.cmplog),
+            (BuildRole::Cmpcov, &detected.cmpcov),
+            (BuildRole::Coverage, &detected.coverage),
+        ] {
+            if let Some(path) = path {
+                println!("[*] {role}: found {}", path.display());
+            } else if !skip.contains(&role) {
+                println!("[*] {role}: not found");
+            }
+        }
+        Ok(detected)
+    }
+
     /// Create an AFL++ runner
     ///
     /// # Errors
     /// * If any of the provided target binaries are invalid
+    /// * If `--auto-detect-builds` finds more than one build for a role
+    /// * If the seed, custom mutator or dictionary ratio is not within 0.0 and 1.0
     ///
     /// # Panics
     /// If the main target binary is empty
     pub fn create_afl_runner(
         gen_args: &GenArgs,
-        raw_afl_flags: Option<&String>,
+        raw_afl_flags: &RawAflFlags,
         is_ramdisk: bool,
     ) -> Result<AFLCmdGenerator> {
-        let harness = Harness::new(
-            gen_args.target.clone().unwrap(),
-            gen_args.target_args.clone(),
-        )?
-        .with_sanitizer(gen_args.san_target.clone())?
-        .with_cmplog(gen_args.cmpl_target.clone())?
-        .with_cmpcov(gen_args.cmpc_target.clone())?
-        .with_coverage(gen_args.san_target.clone())?;
-
-        let seed = if gen_args.use_seed_afl {
-            gen_args.seed
+        if let Some(ratio) = gen_args.seed_afl_ratio.filter(|r| !(0.0..=1.0).contains(r)) {
+            bail!("The seed ratio must be within 0.0 and 1.0, got {ratio}");
+        }
+        if let Some(ratio) = gen_args
+            .custom_mutator_ratio
+            .filter(|r| !(0.0..=1.0).contains(r))
+        {
+            bail!("The custom mutator ratio must be within 0.0 and 1.0, got {ratio}");
+        }
+        if let Some(ratio) = gen_args
+            .dictionary_ratio
+            .filter(|r| !(0.0..=1.0).contains(r))
+        {
+            bail!("The dictionary ratio must be within 0.0 and 1.0, got {ratio}");
+        }
+        let target = gen_args.target.clone().unwrap();
+        let detected = if gen_args.auto_detect_builds {
+            Self::detect_builds(gen_args, &target)?
         } else {
-            None
+            DetectedBuilds::default()
         };
+        let harness = Harness::new(&target, gen_args.target_args.clone())?
+            .with_sanitizer(gen_args.san_target.clone().or(detected.sanitizer))?
+            .with_cmplog(gen_args.cmpl_target.clone().or(detected.cmplog))?
+            .with_cmpcov(gen_args.cmpc_target.clone().or(detected.cmpcov))?
+            .with_coverage(detected.coverage)?;
+
+        let output_dir = gen_args
+            .output_dir
+            .clone()
+            .unwrap_or_else(|| Path::new("/tmp/afl_output").to_path_buf());
 
         let afl_meta = Bcfg::new(
             gen_args
                 .input_dir
                 .clone()
                 .unwrap_or_else(|| Path::new(constants::AFL_CORPUS).to_path_buf()),
-            gen_args
-                .output_dir
-                .clone()
-                .unwrap_or_else(|| Path::new("/tmp/afl_output").to_path_buf()),
+            output_dir,
         )
         .with_dictionary(gen_args.dictionary.clone())
+        .with_dictionary_ratio(gen_args.dictionary_ratio, gen_args.dictionary_skip_cmpcov)
         .with_raw_afl_flags(raw_afl_flags)
         .with_afl_binary(gen_args.afl_binary.clone())
+        .with_map_size(gen_args.map_size)
+        .with_input_len(gen_args.min_len, gen_args.max_len)
+        .with_sync_time(gen_args.sync_time)
+        .with_crash_hook(gen_args.on_new_crash_cmd.clone(), gen_args.on_new_crash_all)
+        .with_custom_mutator(
+            gen_args.custom_mutator.clone(),
+            gen_args.custom_mutator_ratio,
+        )
+        .with_llvm_env(gen_args.llvm_env.clone())
+        .with_fixed_input_file(gen_args.fixed_input_file.clone())
+        .with_target_env(TargetEnv::from_vars(&gen_args.target_env)?)
         .with_ramdisk(is_ramdisk);
 
         Ok(AFLCmdGenerator::new(
             harness,
-            gen_args.runners.unwrap_or(1),
+            gen_args.runners.map_or(1, RunnerCount::resolve),
             &afl_meta,
             gen_args.mode,
-            seed,
+            gen_args.seed,
+        )
+        .with_seed_relay(gen_args.use_seed_afl)
+        .with_san_on_secondary(gen_args.san_on_secondary)
+        .with_seed_ratio(gen_args.seed_afl_ratio)
+        .with_deterministic(gen_args.deterministic)
+        .with_dep_check_skip(gen_args.skip_dep_check.clone())
+        .with_forbidden_flags(FlagBlacklist::new(
+            &gen_args.forbid_flags,
+            gen_args.redistribute_forbidden,
         ))
+        .with_instance_filter(
+            InstanceFilter::new(&gen_args.include_instances, &gen_args.exclude_instances)
+                .map_err(|e| anyhow::anyhow!(e))
+                .context("Invalid [afl_cfg.strategy] instance filter")?,
+        )
+        .with_cmplog_level(gen_args.cmplog_level)
+        .with_strict_flags(gen_args.strict_flags))
     }
 }
 
 impl Command for GenCommand<'_> {
-    fn execute(&self) -> Result<()> {
-        let (merged_args, raw_afl_flags) = self.arg_aggregator.merge_gen_args(self.args)?;
-        let afl_generator = Self::create_afl_runner(&merged_args, raw_afl_flags.as_ref(), false)
+    fn execute(&self) -> Result<ExitCode> {
+        let (merged_args, raw_afl_flags) =
+            self.arg_aggregator.merge_gen_args(&self.args.gen_args)?;
+        let afl_generator = Self::create_afl_runner(&merged_args, &raw_afl_flags, false)
             .context("Failed to create AFL++ runner")?;
-        afl_generator
-            .run()
-            .context("Failed to run AFL++ generator")?
-            .print();
-        Ok(())
+        let mut warnings = Warnings::default();
+        if let Some(runs) = self.args.bench {
+            let report = afl_generator
+                .bench(runs, &mut warnings)
+                .context("Failed to run AFL++ generator")?;
+            println!("{}", report.render());
+            warnings.print();
+            if !report.is_deterministic() {
+                bail!("Generation is not deterministic for seed {}", report.seed);
+            }
+            return Ok(ExitCode::Success);
+        }
+        let afl_commands = afl_generator
+            .run_collecting(&mut warnings)
+            .context("Failed to run AFL++ generator")?;
+        if let Some(previous) = &self.args.diff {
+            let old = cmd_diff::load_commands(previous)?;
+            let new: Vec<InstanceCommand> = afl_commands
+                .to_string_vec()
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, cmd)| InstanceCommand::parse(cmd, idx))
+                .collect();
+            let diff = CommandDiff::new(&old, &new);
+            if self.args.json {
+                println!("{}", serde_json::to_string_pretty(&diff)?);
+            } else {
+                if !diff.env_compared {
+                    println!(
+                        "[*] {} holds no environment variables, only comparing the arguments",
+                        previous.display()
+                    );
+                }
+                println!("{}", diff.render(io::stdout().is_terminal()));
+            }
+        } else {
+            afl_commands.print();
+        }
+        warnings.print();
+        warnings.check(merged_args.fail_on_warnings)?;
+        Ok(ExitCode::Success)
     }
 }
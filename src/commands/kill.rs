@@ -1,12 +1,27 @@
 use anyhow::{Context, Result};
-use std::path::Path;
+use serde::Serialize;
+use std::{fs, path::Path};
 
 use crate::{
     cli::KillArgs,
-    commands::Command,
-    runners::{screen::ScreenSession, tmux::TmuxSession},
+    commands::{cleanup::release_ramdisks, restart::process_output_dir, Command, ExitCode},
+    runners::{
+        runner::{alive_session_pids, Session, SessionManager},
+        screen::ScreenSession,
+        tmux::TmuxSession,
+    },
+    tui::forensics::exit_status_dir,
+    utils::lock::CampaignLock,
 };
 
+/// Machine readable summary of a `kill --json` invocation
+#[derive(Debug, Serialize)]
+struct KillReport<'a> {
+    session: &'a str,
+    killed_pids: Vec<u32>,
+    not_found: bool,
+}
+
 pub struct KillCommand<'a> {
     args: &'a KillArgs,
 }
@@ -15,18 +30,53 @@ impl<'a> KillCommand<'a> {
     pub fn new(args: &'a KillArgs) -> Self {
         Self { args }
     }
+
+    /// Destroys the session, or only interrupts its fuzzers with `--fuzzers-only`
+    ///
+    /// Returns the PIDs of the terminated fuzzers.
+    fn terminate<T: SessionManager>(&self, session: &Session<T>, pids: &[u32]) -> Result<Vec<u32>> {
+        let session_name = &self.args.session_name;
+        let manager = T::manager_name().to_uppercase();
+
+        if self.args.fuzzers_only {
+            let signaled = session.signal_fuzzers(pids);
+            if !self.args.json {
+                println!(
+                    "[+] Found {manager} session: {session_name}. Interrupted {} of {} fuzzers, the session stays alive",
+                    signaled.len(),
+                    pids.len()
+                );
+            }
+            return Ok(signaled);
+        }
+
+        if !self.args.json {
+            println!("[+] Found {manager} session: {session_name}. Terminating it...");
+        }
+        session
+            .kill_session()
+            .with_context(|| format!("Failed to kill {manager} session"))?;
+        Ok(pids.to_vec())
+    }
 }
 
 impl Command for KillCommand<'_> {
-    fn execute(&self) -> Result<()> {
+    fn execute(&self) -> Result<ExitCode> {
         let session_name = &self.args.session_name;
+        let pids = alive_session_pids(session_name);
+        // Sessions without running fuzzers have no campaign left to protect
+        let output_dir = pids.first().and_then(|&pid| process_output_dir(pid));
+        let _lock = output_dir
+            .as_deref()
+            .map(|output_dir| CampaignLock::acquire(output_dir, "kill"))
+            .transpose()?;
+        let mut killed_pids = Vec::new();
         let mut terminated = false;
 
         // Try Tmux session
         if let Ok(tmux) = TmuxSession::new(session_name, &[], Path::new("/tmp/aflr_foobar_1337")) {
             if tmux.is_present() {
-                println!("[+] Found TMUX session: {session_name}. Terminating it...");
-                tmux.kill_session().context("Failed to kill TMUX session")?;
+                killed_pids = self.terminate(&tmux, &pids)?;
                 terminated = true;
             }
         }
@@ -36,18 +86,31 @@ impl Command for KillCommand<'_> {
             ScreenSession::new(session_name, &[], Path::new("/tmp/aflr_foobar_1337"))
         {
             if screen.is_present() {
-                println!("[+] Found SCREEN session: {session_name}. Terminating it...",);
-                screen
-                    .kill_session()
-                    .context("Failed to kill SCREEN session")?;
+                killed_pids = self.terminate(&screen, &pids)?;
                 terminated = true;
             }
         }
 
-        if !terminated {
+        if terminated && !self.args.fuzzers_only {
+            release_ramdisks(session_name, self.args.json);
+            // Interrupted fuzzers keep their exit status for the TUI, a destroyed session has
+            // nobody left to read it
+            if let Some(output_dir) = &output_dir {
+                let _ = fs::remove_dir_all(exit_status_dir(output_dir));
+            }
+        }
+
+        if self.args.json {
+            let report = KillReport {
+                session: session_name,
+                killed_pids,
+                not_found: !terminated,
+            };
+            println!("{}", serde_json::to_string(&report)?);
+        } else if !terminated {
             println!("[-] No session found with the name: {session_name}");
         }
 
-        Ok(())
+        Ok(ExitCode::Success)
     }
 }
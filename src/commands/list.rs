@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use sysinfo::System;
+
+use crate::{
+    argument_aggregator::ArgumentAggregator,
+    cli::ListArgs,
+    commands::{Command, ExitCode},
+    utils::campaign::{find_campaigns, CampaignState, CampaignSummary},
+};
+
+const HEADER: [&str; 8] = [
+    "CAMPAIGN",
+    "TARGET",
+    "STATE",
+    "ALIVE",
+    "CRASHES",
+    "LAST ACTIVITY",
+    "DISK",
+    "PATH",
+];
+
+pub struct ListCommand<'a> {
+    args: &'a ListArgs,
+    arg_aggregator: &'a ArgumentAggregator,
+}
+
+impl<'a> ListCommand<'a> {
+    pub fn new(args: &'a ListArgs, arg_aggregator: &'a ArgumentAggregator) -> Self {
+        Self {
+            args,
+            arg_aggregator,
+        }
+    }
+
+    fn format_timestamp(secs: Option<u64>) -> String {
+        secs.and_then(|secs| DateTime::from_timestamp(i64::try_from(secs).ok()?, 0))
+            .map_or_else(
+                || "N/A".to_string(),
+                |ts| {
+                    ts.with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                },
+            )
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn format_bytes(bytes: u64) -> String {
+        const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+        let mut size = bytes as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{bytes} B")
+        } else {
+            format!("{size:.1} {}", UNITS[unit])
+        }
+    }
+
+    fn print_table(campaigns: &[CampaignSummary]) {
+        let rows: Vec<[String; 8]> = campaigns
+            .iter()
+            .map(|c| {
+                [
+                    c.id.clone(),
+                    c.target.clone().unwrap_or_else(|| "N/A".to_string()),
+                    c.state.to_string(),
+                    format!("{}/{}", c.instances_alive, c.instances),
+                    c.crashes.to_string(),
+                    Self::format_timestamp(c.last_activity),
+                    Self::format_bytes(c.disk_usage),
+                    c.path.display().to_string(),
+                ]
+            })
+            .collect();
+
+        let mut widths = HEADER.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let print_row = |cells: &[&str]| {
+            let line: Vec<String> = cells
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            println!("{}", line.join("  ").trim_end());
+        };
+
+        print_row(&HEADER);
+        for row in &rows {
+            print_row(&row.each_ref().map(String::as_str));
+        }
+    }
+}
+
+impl Command for ListCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        let args = self.arg_aggregator.merge_list_args(self.args);
+        let mut roots =
+            vec![std::env::current_dir().context("Failed to resolve the current directory")?];
+        roots.extend(args.roots);
+
+        let mut system = System::new_all();
+        system.refresh_all();
+        let mut campaigns: Vec<CampaignSummary> = find_campaigns(&roots)
+            .iter()
+            .map(|dir| CampaignSummary::collect(dir, &system))
+            .filter(|c| !args.running || c.state == CampaignState::Running)
+            .collect();
+        campaigns.sort_by_key(|c| std::cmp::Reverse(c.last_activity));
+
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&campaigns)?);
+        } else if campaigns.is_empty() {
+            println!("[-] No campaigns found");
+        } else {
+            Self::print_table(&campaigns);
+        }
+        Ok(ExitCode::Success)
+    }
+}
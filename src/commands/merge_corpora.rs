@@ -0,0 +1,37 @@
+use anyhow::Result;
+
+use crate::{
+    afl::corpus::CorpusMerger,
+    cli::MergeCorporaArgs,
+    commands::{Command, ExitCode},
+};
+
+pub struct MergeCorporaCommand<'a> {
+    args: &'a MergeCorporaArgs,
+}
+
+impl<'a> MergeCorporaCommand<'a> {
+    pub fn new(args: &'a MergeCorporaArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl Command for MergeCorporaCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        let mut merger = CorpusMerger::new(self.args.out_dirs.clone(), self.args.into.clone());
+        if self.args.cmin {
+            if let Some(target) = &self.args.target {
+                merger = merger.with_cmin(target.clone(), self.args.target_args.clone());
+            }
+        }
+
+        let stats = merger.merge()?;
+        println!(
+            "[+] Merged {} queue files into {} unique test cases in {}",
+            stats.scanned,
+            stats.unique,
+            self.args.into.display()
+        );
+        Ok(ExitCode::Success)
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+use sysinfo::Signal;
+
+use crate::{
+    afl::cmd_diff::InstanceCommand,
+    argument_aggregator::ArgumentAggregator,
+    cli::{
+        MigrationArgs, MigrationCommands, MigrationExportArgs, MigrationImportArgs, SessionRunner,
+    },
+    commands::{list::ListCommand, Command, ExitCode},
+    runners::{
+        remote::PathMap,
+        runner::{attach_command_line, Session, SessionManager},
+        screen::ScreenSession,
+        tmux::TmuxSession,
+    },
+    tui::{control::signal_pids, data_collection::DataFetcher},
+    utils::{
+        build_id::BuildIdRecord,
+        campaign::CampaignRecord,
+        cleanup::session_pid_file,
+        clock::Clock,
+        migration::{self, absolute_paths, toml_paths},
+    },
+};
+
+/// Moves a campaign between machines through a bundle of its output directory
+pub struct MigrationCommand<'a> {
+    args: &'a MigrationArgs,
+    arg_aggregator: &'a ArgumentAggregator,
+}
+
+impl<'a> MigrationCommand<'a> {
+    pub fn new(args: &'a MigrationArgs, arg_aggregator: &'a ArgumentAggregator) -> Self {
+        Self {
+            args,
+            arg_aggregator,
+        }
+    }
+
+    fn export(&self, args: &MigrationExportArgs) -> Result<()> {
+        let output_dir = &args.output_dir;
+        let record = CampaignRecord::load(output_dir)
+            .filter(|record| !record.commands.is_empty())
+            .with_context(|| {
+                format!(
+                    "No campaign record in {}, only campaigns started with aflr run can be migrated",
+                    output_dir.display()
+                )
+            })?;
+
+        // Paused fuzzers keep their queues consistent while they are packed
+        let pids = DataFetcher::alive_pids(output_dir);
+        let paused = signal_pids(&pids, Signal::Stop);
+        if !paused.is_empty() {
+            println!("[*] Paused {} fuzzers while exporting", paused.len());
+        }
+        let result = migration::export(
+            output_dir,
+            &args.to,
+            self.arg_aggregator.raw_config(),
+            Clock::from_env(),
+        );
+        signal_pids(&paused, Signal::Continue);
+        let manifest = result?;
+
+        let size = fs::metadata(&args.to).map_or(0, |meta| meta.len());
+        println!(
+            "[+] Exported {} to {} ({})",
+            manifest.source_output_dir.display(),
+            args.to.display(),
+            ListCommand::format_bytes(size)
+        );
+        if !manifest.has_config {
+            println!("[!] Warning: No config was bundled, pass --config to include it");
+        }
+        if !paused.is_empty() {
+            println!(
+                "[*] The campaign keeps running here, stop it with `aflr kill {}` before importing the bundle",
+                record.session_name
+            );
+        }
+        Ok(())
+    }
+
+    fn import(args: &MigrationImportArgs) -> Result<()> {
+        // Rejected before anything is unpacked
+        let mut paths = PathMap::new(&args.maps)?;
+        let manifest = migration::unpack(&args.bundle, &args.output_dir)?;
+        let output_dir = args.output_dir.canonicalize()?;
+        println!(
+            "[+] Unpacked {} into {}",
+            args.bundle.display(),
+            output_dir.display()
+        );
+
+        let mut record =
+            CampaignRecord::load(&output_dir).context("The bundle holds no campaign record")?;
+        let config_path = migration::config_path(&output_dir);
+        let mut config: Option<toml::Table> = if manifest.has_config {
+            let content = fs::read_to_string(&config_path)?;
+            Some(toml::from_str(&content).context("Failed to parse the bundled config")?)
+        } else {
+            None
+        };
+
+        paths.add(&manifest.source_output_dir, &output_dir);
+        let mut referenced: BTreeSet<String> = record
+            .commands
+            .iter()
+            .flat_map(|line| absolute_paths(line))
+            .collect();
+        referenced.extend(config.as_ref().map(toml_paths).unwrap_or_default());
+        Self::map_missing_paths(&mut paths, &referenced, args.yes)?;
+
+        record.commands = record
+            .commands
+            .iter()
+            .map(|line| paths.rewrite(line))
+            .collect();
+        record.target = PathBuf::from(paths.rewrite(&record.target.to_string_lossy()));
+        record.save(&output_dir)?;
+        if let Some(config) = config.as_mut() {
+            paths.rewrite_toml(config);
+            fs::write(&config_path, toml::to_string(config)?)?;
+            println!("[*] Rewritten config: {}", config_path.display());
+        }
+        if let Some(build_ids) = BuildIdRecord::load(&output_dir) {
+            let build_ids = paths.rewrite_build_ids(&build_ids);
+            build_ids.save(&output_dir)?;
+            for warning in migration::verify_builds(&build_ids) {
+                println!("[!] Warning: {warning}");
+            }
+        }
+
+        if args.no_launch {
+            println!(
+                "[*] Not relaunching, the rewritten commands are in {}",
+                CampaignRecord::path(&output_dir).display()
+            );
+            return Ok(());
+        }
+        // The recorded commands keep each instance's role and options, only resuming is added
+        let commands: Vec<String> = record
+            .commands
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                InstanceCommand::parse(line, idx)
+                    .map_or_else(|| line.clone(), |command| command.with_autoresume())
+            })
+            .collect();
+        let pid_file = session_pid_file(&record.session_name, std::process::id());
+        match args.session_runner {
+            SessionRunner::Screen => {
+                let screen = ScreenSession::new(&record.session_name, &commands, &pid_file)
+                    .context("Failed to create Screen session")?
+                    .with_resume();
+                Self::launch(&screen, &record.session_name)
+            }
+            SessionRunner::Tmux => {
+                let tmux = TmuxSession::new(&record.session_name, &commands, &pid_file)
+                    .context("Failed to create Tmux session")?
+                    .with_resume();
+                Self::launch(&tmux, &record.session_name)
+            }
+        }
+    }
+
+    /// Asks for the new location of every referenced path that is missing on this machine
+    fn map_missing_paths(
+        paths: &mut PathMap,
+        referenced: &BTreeSet<String>,
+        keep: bool,
+    ) -> Result<()> {
+        let missing: Vec<&String> = referenced
+            .iter()
+            .filter(|path| !paths.covers(path) && !Path::new(path).exists())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        if keep {
+            for path in missing {
+                println!("[!] Warning: {path} does not exist on this machine, keeping it");
+            }
+            return Ok(());
+        }
+
+        let mut stdin = io::stdin().lock();
+        for path in missing {
+            print!("[?] {path} does not exist on this machine, new location (empty keeps it): ");
+            io::stdout().flush()?;
+            let mut answer = String::new();
+            if stdin.read_line(&mut answer)? == 0 {
+                bail!("No new location given for {path}, pass --map or --yes");
+            }
+            let answer = answer.trim();
+            if !answer.is_empty() {
+                paths.add(Path::new(path), Path::new(answer));
+            }
+        }
+        Ok(())
+    }
+
+    fn launch<T: SessionManager>(session: &Session<T>, session_name: &str) -> Result<()> {
+        session.run()?;
+        println!(
+            "[+] Resumed the campaign in session '{session_name}', attach with `{}`",
+            attach_command_line::<T>(session_name)
+        );
+        Ok(())
+    }
+}
+
+impl Command for MigrationCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        match &self.args.cmd {
+            MigrationCommands::Export(args) => self.export(args)?,
+            MigrationCommands::Import(args) => Self::import(args)?,
+        }
+        Ok(ExitCode::Success)
+    }
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use std::time::Duration;
+
+use crate::{
+    cli::MirrorArgs,
+    commands::{Command, ExitCode},
+    utils::mirror::Mirror,
+};
+
+pub struct MirrorCommand<'a> {
+    args: &'a MirrorArgs,
+}
+
+impl<'a> MirrorCommand<'a> {
+    pub fn new(args: &'a MirrorArgs) -> Self {
+        Self { args }
+    }
+}
+
+impl Command for MirrorCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        Mirror::new(
+            self.args.from.clone(),
+            self.args.to.clone(),
+            self.args.interval.as_duration().max(Duration::from_secs(1)),
+        )
+        .run()?;
+        Ok(ExitCode::Success)
+    }
+}
@@ -1,16 +1,155 @@
 pub mod add_seed;
+pub mod cleanup;
+pub mod collect_crashes;
+pub mod config;
 pub mod cov;
+pub mod doctor;
+pub mod env_check;
 pub mod gen;
 pub mod kill;
+pub mod list;
+pub mod merge_corpora;
+pub mod migration;
+pub mod mirror;
+pub mod pause;
 pub mod render_tui;
+pub mod replay;
+pub mod restart;
 pub mod run;
+pub mod snapshots;
+pub mod top;
+
+use std::path::Path;
 
 use anyhow::Result;
+use sysinfo::System;
+
+use crate::afl::startup::StartupFailures;
+use crate::tui::data_collection::{DataFetcher, InstanceSnapshot};
+use crate::tui::forensics::InstanceState;
+
+/// Exit code of `aflr`, so pipelines can tell the outcome of a campaign from a failure
+///
+/// The campaign outcomes are only known when `run` follows the campaign until all fuzzers are
+/// gone, i.e. with `--progress`. Found crashes take precedence over how the fuzzers ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExitCode {
+    /// The command succeeded, or the campaign was stopped without finding crashes
+    #[default]
+    Success = 0,
+    /// The command failed
+    Error = 1,
+    /// The campaign found at least one crash
+    CrashesFound = 2,
+    /// The fuzzers terminated on their own, e.g. after `-V <secs>`
+    TimedOut = 3,
+    /// All fuzzers died, on startup or later on
+    AllInstancesDied = 4,
+}
+
+impl ExitCode {
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// Exit code of a failed command, startup failures of every instance count as died
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<StartupFailures>())
+            .filter(|failures| failures.total > 0 && failures.failed() == failures.total)
+            .map_or(Self::Error, |_| Self::AllInstancesDied)
+    }
+
+    /// Exit code of the campaign in `output_dir` after all its fuzzers are gone
+    pub fn from_campaign(output_dir: &Path, interrupted: bool) -> Self {
+        let system = System::new_all();
+        Self::from_instances(
+            &DataFetcher::instance_snapshots(output_dir, &system),
+            interrupted,
+        )
+    }
+
+    fn from_instances(instances: &[InstanceSnapshot], interrupted: bool) -> Self {
+        if instances.iter().any(|i| i.saved_crashes > 0) {
+            Self::CrashesFound
+        } else if interrupted {
+            Self::Success
+        } else if instances.iter().any(|i| i.state == InstanceState::Finished) {
+            Self::TimedOut
+        } else {
+            Self::AllInstancesDied
+        }
+    }
+}
 
 pub trait Command {
     /// Execute the command
     ///
     /// # Errors
     /// * If the command could not be executed
-    fn execute(&self) -> Result<()>;
+    fn execute(&self) -> Result<ExitCode>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::afl::startup::StartupCause;
+    use std::collections::BTreeSet;
+
+    fn instance(state: InstanceState, saved_crashes: u64) -> InstanceSnapshot {
+        InstanceSnapshot {
+            name: "s0".to_string(),
+            state,
+            saved_crashes,
+            last_update: None,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn test_exit_code_from_instances() {
+        let finished = instance(InstanceState::Finished, 0);
+        let died = instance(InstanceState::DiedAbnormally, 0);
+        let crashed = instance(InstanceState::Killed, 3);
+
+        assert_eq!(
+            ExitCode::from_instances(&[died.clone(), crashed], true),
+            ExitCode::CrashesFound
+        );
+        assert_eq!(
+            ExitCode::from_instances(&[finished.clone(), died.clone()], false),
+            ExitCode::TimedOut
+        );
+        assert_eq!(
+            ExitCode::from_instances(&[finished, died.clone()], true),
+            ExitCode::Success
+        );
+        assert_eq!(
+            ExitCode::from_instances(&[died], false),
+            ExitCode::AllInstancesDied
+        );
+        assert_eq!(
+            ExitCode::from_instances(&[], false),
+            ExitCode::AllInstancesDied
+        );
+    }
+
+    #[test]
+    fn test_exit_code_from_error() {
+        let mut failures = StartupFailures::default();
+        failures.total = 2;
+        failures.causes = vec![(StartupCause::MapSize, BTreeSet::from([0]))];
+        let partial = anyhow::Error::new(failures.clone()).context("Failed to start the session");
+        assert_eq!(ExitCode::from_error(&partial), ExitCode::Error);
+
+        failures.causes[0].1.insert(1);
+        let all = anyhow::Error::new(failures).context("Failed to start the session");
+        assert_eq!(ExitCode::from_error(&all), ExitCode::AllInstancesDied);
+        assert_eq!(
+            ExitCode::from_error(&anyhow::anyhow!("Invalid target")),
+            ExitCode::Error
+        );
+        assert_eq!(ExitCode::CrashesFound.code(), 2);
+    }
 }
@@ -0,0 +1,70 @@
+use anyhow::{bail, Result};
+use std::path::Path;
+use sysinfo::Signal;
+
+use crate::{
+    cli::PauseArgs,
+    commands::{Command, ExitCode},
+    runners::{
+        runner::{alive_session_pids, Session, SessionManager},
+        screen::ScreenSession,
+        tmux::TmuxSession,
+    },
+};
+
+/// Suspends (`SIGSTOP`) or resumes (`SIGCONT`) single instances of a running session
+pub struct PauseCommand<'a> {
+    args: &'a PauseArgs,
+    resume: bool,
+}
+
+impl<'a> PauseCommand<'a> {
+    pub fn new(args: &'a PauseArgs, resume: bool) -> Self {
+        Self { args, resume }
+    }
+
+    fn signal<T: SessionManager>(&self, session: &Session<T>, pids: &[u32]) -> Result<()> {
+        let (signal, verb) = if self.resume {
+            (Signal::Continue, "Resumed")
+        } else {
+            (Signal::Stop, "Paused")
+        };
+        let signaled = session.signal_instances(pids, &self.args.instances, signal)?;
+        for (name, pid) in &signaled {
+            println!("[+] {verb} {name} (PID {pid})");
+        }
+        if signaled.len() < self.args.instances.len() {
+            println!(
+                "[!] Warning: {} of {} instances could not be signaled",
+                self.args.instances.len() - signaled.len(),
+                self.args.instances.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Command for PauseCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        let session_name = &self.args.session_name;
+        let pids = alive_session_pids(session_name);
+
+        if let Ok(tmux) = TmuxSession::new(session_name, &[], Path::new("/tmp/aflr_foobar_1337")) {
+            if tmux.is_present() {
+                self.signal(&tmux, &pids)?;
+                return Ok(ExitCode::Success);
+            }
+        }
+
+        if let Ok(screen) =
+            ScreenSession::new(session_name, &[], Path::new("/tmp/aflr_foobar_1337"))
+        {
+            if screen.is_present() {
+                self.signal(&screen, &pids)?;
+                return Ok(ExitCode::Success);
+            }
+        }
+
+        bail!("No session found with the name: {session_name}")
+    }
+}
@@ -1,29 +1,68 @@
 use anyhow::{bail, Context, Result};
-use std::path::Path;
+use std::{ffi::OsStr, path::Path};
+use tempfile::TempDir;
 
 use crate::{
+    argument_aggregator::ArgumentAggregator,
     cli::TuiArgs,
-    commands::Command,
-    tui::{session::CampaignData, Tui},
+    commands::{Command, ExitCode},
+    tui::{
+        control,
+        data_collection::{instance_dirs, STATS_FILE},
+        format::NumberFormat,
+        remote::RemoteOutput,
+        session::CampaignData,
+        Tui, TuiOptions,
+    },
+    utils::snapshot::SnapshotPolicy,
 };
 
 pub struct RenderCommand<'a> {
     args: &'a TuiArgs,
+    arg_aggregator: &'a ArgumentAggregator,
 }
 
 impl<'a> RenderCommand<'a> {
-    pub fn new(args: &'a TuiArgs) -> Self {
-        Self { args }
+    pub fn new(args: &'a TuiArgs, arg_aggregator: &'a ArgumentAggregator) -> Self {
+        Self {
+            args,
+            arg_aggregator,
+        }
     }
 
-    fn validate_output_dir(output_dir: &Path) -> Result<()> {
+    /// Fetches the stats of the remote campaign at `spec` into a fresh cache directory
+    ///
+    /// # Errors
+    /// * If `spec` is invalid or the first fetch fails, e.g. due to an authentication failure
+    fn fetch_remote(spec: &str) -> Result<(TempDir, RemoteOutput)> {
+        let cache_dir = tempfile::Builder::new()
+            .prefix("aflr_remote_")
+            .tempdir()
+            .context("Failed to create the local stats cache")?;
+        let remote = RemoteOutput::new(spec, cache_dir.path())?;
+        println!("[*] Fetching the fuzzer stats from {}...", remote.source());
+        if let Err(e) = remote.fetch() {
+            bail!("{e:#}\n  Make sure `ssh <host>` works without a password prompt and rsync is installed on both hosts");
+        }
+        if instance_dirs(cache_dir.path()).is_empty() {
+            bail!("No fuzzer_stats found in {}", remote.source());
+        }
+        Ok((cache_dir, remote))
+    }
+
+    fn validate_output_dir(output_dir: &Path, stats_file: &str) -> Result<()> {
         for entry in output_dir.read_dir()? {
             let path = entry?.path();
-            if path.is_dir() {
-                let fuzzer_stats = path.join("fuzzer_stats");
-                if !fuzzer_stats.exists() {
+            if path.is_dir() && !path.ends_with(control::RUNTIME_DIR) {
+                let fuzzer_stats = path.join(stats_file);
+                // Multi-target campaigns have a subdirectory of instances per target
+                let is_target_dir = path
+                    .read_dir()?
+                    .flatten()
+                    .any(|e| e.path().join(stats_file).exists());
+                if !fuzzer_stats.exists() && !is_target_dir {
                     bail!(
-                        "Invalid output directory: {} is missing 'fuzzer_stats' file",
+                        "Invalid output directory: {} is missing '{stats_file}' file",
                         path.display()
                     );
                 }
@@ -34,14 +73,56 @@ impl<'a> RenderCommand<'a> {
 }
 
 impl Command for RenderCommand<'_> {
-    fn execute(&self) -> Result<()> {
-        if !self.args.afl_output.exists() {
-            bail!("Output directory is required for TUI mode");
+    fn execute(&self) -> Result<ExitCode> {
+        let cache = self
+            .args
+            .ssh
+            .as_deref()
+            .map(Self::fetch_remote)
+            .transpose()?;
+        let output_dirs = cache.as_ref().map_or_else(
+            || self.args.afl_output.clone(),
+            |(cache_dir, _)| vec![cache_dir.path().to_path_buf()],
+        );
+        let stats_file = self.args.stats_filename.as_deref().unwrap_or(STATS_FILE);
+        if Path::new(stats_file).file_name() != Some(OsStr::new(stats_file)) {
+            bail!("--stats-filename must be a plain file name, got '{stats_file}'");
+        }
+        for output_dir in &self.args.afl_output {
+            if !output_dir.exists() {
+                bail!("Output directory {} does not exist", output_dir.display());
+            }
+            Self::validate_output_dir(output_dir, stats_file)?;
         }
 
-        Self::validate_output_dir(&self.args.afl_output)?;
+        let config_options = self.arg_aggregator.tui_options()?;
+        let options = TuiOptions {
+            jsonl: self.args.jsonl.clone(),
+            log_file: self.args.log_file.clone(),
+            log_capacity: self.args.log_capacity.or(config_options.log_capacity),
+            number_format: if self.args.raw_numbers {
+                NumberFormat::Raw
+            } else {
+                NumberFormat::Human
+            },
+            control_socket: config_options.control_socket || self.args.control_socket,
+            anonymize: self.args.anonymize,
+            stats_file: self.args.stats_filename.clone(),
+            follow_new_crashes: config_options.follow_new_crashes || self.args.follow_new_crashes,
+            crash_viewer: self
+                .args
+                .crash_viewer
+                .clone()
+                .or(config_options.crash_viewer),
+            remote: cache.as_ref().map(|(_, remote)| remote.clone()),
+            snapshots: SnapshotPolicy {
+                compress: config_options.snapshots.compress || self.args.snapshot_compress,
+                ..config_options.snapshots
+            },
+            ..config_options
+        };
 
-        let mut cdata = CampaignData::default();
-        Tui::run(&self.args.afl_output, None, &mut cdata).context("Failed to run TUI")
+        Tui::run(&output_dirs, None, &options, CampaignData::new()).context("Failed to run TUI")?;
+        Ok(ExitCode::Success)
     }
 }
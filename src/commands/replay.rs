@@ -0,0 +1,113 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use std::{os::unix::process::CommandExt, path::PathBuf};
+
+use crate::{
+    afl::replay::{archived_env, find_entries, Replayer},
+    argument_aggregator::ArgumentAggregator,
+    cli::{constants, BinaryVariant, ReplayArgs},
+    commands::{Command, ExitCode},
+};
+
+pub struct ReplayCommand<'a> {
+    args: &'a ReplayArgs,
+    arg_aggregator: &'a ArgumentAggregator,
+}
+
+impl<'a> ReplayCommand<'a> {
+    pub fn new(args: &'a ReplayArgs, arg_aggregator: &'a ArgumentAggregator) -> Self {
+        Self {
+            args,
+            arg_aggregator,
+        }
+    }
+
+    /// Resolves the entry to replay, listing all candidates if the id is ambiguous
+    fn resolve_entry(args: &ReplayArgs) -> Result<PathBuf> {
+        if let Some(file) = &args.file {
+            return Ok(file.clone());
+        }
+
+        let id = args
+            .id
+            .as_deref()
+            .context("Either --id or --file is required")?;
+        let output_dir = args
+            .output_dir
+            .as_ref()
+            .context("Output directory is required")?;
+        let mut entries = find_entries(output_dir, id)?;
+
+        match (entries.len(), args.select) {
+            (0, _) => bail!("No entry with id {id} found in {}", output_dir.display()),
+            (1, None | Some(0)) => Ok(entries.remove(0)),
+            (n, Some(idx)) if idx < n => Ok(entries.swap_remove(idx)),
+            (_, select) => {
+                println!("[*] Id {id} matches multiple entries:");
+                for (idx, entry) in entries.iter().enumerate() {
+                    println!("  {idx:3}. {}", entry.display());
+                }
+                match select {
+                    Some(idx) => bail!("Selection {idx} is out of range"),
+                    None => bail!("Pick one of the entries with --select <N>"),
+                }
+            }
+        }
+    }
+}
+
+impl Command for ReplayCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        let args = self.arg_aggregator.merge_replay_args(self.args);
+        let entry = Self::resolve_entry(&args)?;
+        let env = archived_env(&entry);
+        let target_args = args.target_args.clone().unwrap_or_default();
+
+        let variants: Vec<_> = if args.all {
+            BinaryVariant::value_variants()
+                .iter()
+                .filter_map(|&v| args.binary_for(v).map(|bin| (v, bin.clone())))
+                .collect()
+        } else {
+            let binary = args
+                .binary_for(args.binary)
+                .with_context(|| format!("No binary configured for variant {:?}", args.binary))?;
+            vec![(args.binary, binary.clone())]
+        };
+        if variants.is_empty() {
+            bail!("No target binaries configured");
+        }
+
+        println!("[*] Replaying {}", entry.display());
+        if !env.is_empty() {
+            println!(
+                "[*] Using {} environment variables from fuzzer_setup",
+                env.len()
+            );
+        }
+
+        for (variant, binary) in variants {
+            let replayer = Replayer::new(binary.clone(), target_args.clone())
+                .with_env(env.clone())
+                .with_timeout(
+                    args.timeout
+                        .unwrap_or(constants::REPLAY_TIMEOUT)
+                        .as_duration(),
+                );
+
+            if args.gdb {
+                let err = replayer.gdb_command(&entry).exec();
+                bail!("Failed to execute gdb: {err}");
+            }
+
+            let outcome = replayer.run(&entry)?;
+            println!(
+                "[+] {variant:?} ({}): {} after {:.3}s",
+                binary.display(),
+                outcome.exit,
+                outcome.runtime.as_secs_f64()
+            );
+        }
+        Ok(ExitCode::Success)
+    }
+}
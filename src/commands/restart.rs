@@ -0,0 +1,175 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use sysinfo::{Pid, System};
+
+use crate::{
+    afl::cmd_diff::InstanceCommand,
+    cli::RestartArgs,
+    commands::{Command, ExitCode},
+    runners::{
+        runner::{alive_session_pids, Session, SessionManager},
+        screen::ScreenSession,
+        tmux::TmuxSession,
+    },
+    utils::{campaign::CampaignRecord, lock::CampaignLock},
+};
+
+/// Restarts a single instance of a running session from the recorded campaign commands
+pub struct RestartCommand<'a> {
+    args: &'a RestartArgs,
+}
+
+impl<'a> RestartCommand<'a> {
+    pub fn new(args: &'a RestartArgs) -> Self {
+        Self { args }
+    }
+
+    fn restart<T: SessionManager>(&self, session: &Session<T>) -> Result<()> {
+        let instance = &self.args.instance;
+        let pids = alive_session_pids(&self.args.session_name);
+        let old_pid = session.instance_pids(&pids).get(instance).copied();
+
+        let output_dir = match (&self.args.output_dir, old_pid) {
+            (Some(output_dir), _) => output_dir.clone(),
+            (None, Some(pid)) => process_output_dir(pid)
+                .with_context(|| format!("Failed to read the output directory of PID {pid}"))?,
+            (None, None) => bail!(
+                "{instance} is not running in session '{}', pass --output-dir to locate its campaign",
+                self.args.session_name
+            ),
+        };
+        let command = recorded_command(&output_dir, instance)?;
+        let _lock = CampaignLock::acquire(&output_dir, "restart")?;
+
+        match old_pid {
+            Some(pid) => println!("[*] Stopping {instance} (PID {pid})..."),
+            None => println!("[*] {instance} is not running, relaunching it"),
+        }
+        let new_pid =
+            session.restart_instance(instance, &command.with_autoresume(), old_pid, &output_dir)?;
+        println!("[+] Restarted {instance} (PID {new_pid})");
+        Ok(())
+    }
+}
+
+impl Command for RestartCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        let session_name = &self.args.session_name;
+
+        if let Ok(tmux) = TmuxSession::new(session_name, &[], Path::new("/tmp/aflr_foobar_1337")) {
+            if tmux.is_present() {
+                self.restart(&tmux)?;
+                return Ok(ExitCode::Success);
+            }
+        }
+
+        if let Ok(screen) =
+            ScreenSession::new(session_name, &[], Path::new("/tmp/aflr_foobar_1337"))
+        {
+            if screen.is_present() {
+                self.restart(&screen)?;
+                return Ok(ExitCode::Success);
+            }
+        }
+
+        bail!("No session found with the name: {session_name}")
+    }
+}
+
+/// Output directory (`-o`) of the running fuzzer `pid`, resolved against its working directory
+pub fn process_output_dir(pid: u32) -> Option<PathBuf> {
+    let mut system = System::new_all();
+    system.refresh_all();
+    let process = system.process(Pid::from(pid as usize))?;
+    let argv: Vec<String> = process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    output_dir_from_argv(&argv, process.cwd())
+}
+
+fn output_dir_from_argv(argv: &[String], cwd: Option<&Path>) -> Option<PathBuf> {
+    let output_dir = InstanceCommand::from_argv(argv, 0)?
+        .flags
+        .into_iter()
+        .find(|(flag, _)| flag == "-o")
+        .and_then(|(_, value)| value)
+        .map(PathBuf::from)?;
+    Some(match cwd {
+        Some(cwd) if output_dir.is_relative() => cwd.join(output_dir),
+        _ => output_dir,
+    })
+}
+
+/// The command `run` generated for `instance`, read from the campaign record in `output_dir`
+fn recorded_command(output_dir: &Path, instance: &str) -> Result<InstanceCommand> {
+    let record = CampaignRecord::load(output_dir).with_context(|| {
+        format!(
+            "No campaign record in {}, only campaigns started with aflr run can be restarted",
+            output_dir.display()
+        )
+    })?;
+    record
+        .commands
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| InstanceCommand::parse(line, idx))
+        .find(|command| command.name == *instance)
+        .with_context(|| {
+            format!(
+                "The campaign record in {} has no command for {instance}",
+                output_dir.display()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_output_dir_from_argv() {
+        let fuzzer = argv(&["afl-fuzz", "-i", "in", "-o", "out", "-S", "s0", "--", "./t"]);
+        assert_eq!(
+            output_dir_from_argv(&fuzzer, Some(Path::new("/work"))),
+            Some(PathBuf::from("/work/out"))
+        );
+        let absolute = argv(&["afl-fuzz", "-o", "/srv/out", "-M", "m", "--", "./t"]);
+        assert_eq!(
+            output_dir_from_argv(&absolute, Some(Path::new("/work"))),
+            Some(PathBuf::from("/srv/out"))
+        );
+        assert_eq!(
+            output_dir_from_argv(&argv(&["afl-fuzz", "-i", "in"]), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recorded_command() {
+        let temp_dir = TempDir::new().unwrap();
+        let record = CampaignRecord::default().with_commands(vec![
+            "AFL_FINAL_SYNC=1 afl-fuzz -i in -o out -M m_t -- ./t @@".to_string(),
+            "AFL_AUTORESUME=1 afl-fuzz -i in -o out -S s0_t -- ./t @@".to_string(),
+        ]);
+        record.save(temp_dir.path()).unwrap();
+
+        let master = recorded_command(temp_dir.path(), "m_t").unwrap();
+        assert_eq!(
+            master.with_autoresume(),
+            "AFL_AUTORESUME=1 AFL_FINAL_SYNC=1 afl-fuzz -i in -o out -M m_t -- ./t @@"
+        );
+        let secondary = recorded_command(temp_dir.path(), "s0_t").unwrap();
+        assert_eq!(secondary.with_autoresume(), record.commands[1]);
+
+        let err = recorded_command(temp_dir.path(), "s1_t").unwrap_err();
+        assert!(err.to_string().contains("no command for s1_t"));
+        assert!(recorded_command(&temp_dir.path().join("missing"), "m_t").is_err());
+    }
+}
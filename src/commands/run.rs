@@ -1,21 +1,121 @@
 use anyhow::{bail, Context, Result};
+use rand::{rngs::StdRng, SeedableRng};
 use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fs,
     hash::{DefaultHasher, Hasher},
-    path::Path,
+    os::unix::process::CommandExt,
+    path::{Path, PathBuf},
+    process::Stdio,
 };
 
 use crate::{
-    afl::cmd::ToStringVec,
+    afl::{
+        base_cfg::RawAflFlags,
+        cmd::{Printable, ToStringVec},
+        cmd_edit,
+        cmd_gen::AFLCmdGenerator,
+        doctor::ensure_core_pattern,
+        harness::Harness,
+        instrumentation::{InstrumentationCheck, InstrumentationIssue, DEFAULT_MIN_TUPLES},
+        map_size::MapSize,
+        preflight::Preflight,
+        startup::StartupFailures,
+        warnings::Warnings,
+    },
     argument_aggregator::ArgumentAggregator,
-    cli::{constants, RunArgs, SessionRunner},
-    commands::{gen::GenCommand, Command},
+    cli::{constants, validate_targets, DockerMode, RunArgs, SessionRunner, TargetEntry},
+    commands::{cleanup::SessionRamdisks, gen::GenCommand, Command, ExitCode},
     runners::{
+        docker::DockerLauncher,
+        remote::{PathMap, RemoteHost},
         runner::{Session, SessionManager},
-        screen::ScreenSession,
-        tmux::TmuxSession,
+        screen::{Screen, ScreenSession},
+        tmux::{Tmux, TmuxSession},
+    },
+    tui::{
+        calibration, data_collection::DataFetcher, format::NumberFormat, progress,
+        session::CampaignData, Tui, TuiOptions,
+    },
+    utils::{
+        build_id::BuildIdRecord,
+        campaign::{is_campaign, CampaignRecord},
+        cleanup::session_pid_file,
+        clock::Clock,
+        duration::DurationOpt,
+        lock::CampaignLock,
+        runner_count::RunnerCount,
+        snapshot::SnapshotPolicy,
+        system::create_ramdisk,
     },
 };
 
+/// Top-level entries of an output directory before a calibration probe wrote to it
+struct ProbeOutput {
+    dir: PathBuf,
+    /// `None` if the probe creates the directory
+    entries: Option<HashSet<OsString>>,
+}
+
+impl ProbeOutput {
+    /// Remembers the state of `dir`, refusing to probe into an existing campaign
+    fn snapshot(dir: &Path) -> Result<Self> {
+        if is_campaign(dir) {
+            bail!(
+                "Output directory {} already contains a campaign, probe into an empty one instead",
+                dir.display()
+            );
+        }
+        let entries = dir
+            .exists()
+            .then(|| {
+                fs::read_dir(dir).map(|entries| entries.flatten().map(|e| e.file_name()).collect())
+            })
+            .transpose()
+            .with_context(|| format!("Failed to read {}", dir.display()))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// Removes everything the probe added to the directory
+    fn restore(&self) -> Result<()> {
+        let Some(entries) = &self.entries else {
+            return fs::remove_dir_all(&self.dir)
+                .or_else(|e| {
+                    if e.kind() == std::io::ErrorKind::NotFound {
+                        Ok(())
+                    } else {
+                        Err(e)
+                    }
+                })
+                .with_context(|| format!("Failed to remove {}", self.dir.display()));
+        };
+        for entry in fs::read_dir(&self.dir)?.flatten() {
+            if entries.contains(&entry.file_name()) {
+                continue;
+            }
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            }
+            .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Generated session of one `[[targets]]` entry
+struct TargetSession {
+    session_name: String,
+    commands: Vec<String>,
+    pid_file: PathBuf,
+}
+
 pub struct RunCommand<'a> {
     args: &'a RunArgs,
     arg_aggregator: &'a ArgumentAggregator,
@@ -58,42 +158,723 @@ impl<'a> RunCommand<'a> {
         )
     }
 
-    fn execute_session<T: SessionManager>(session: &Session<T>, args: &RunArgs) -> Result<()> {
+    /// Ensures that no other running session writes into `output_dir`
+    ///
+    /// Instances of two campaigns sharing an output directory clobber each other's state
+    fn check_output_dir_in_use(output_dir: &Path, force: bool) -> Result<()> {
+        let alive_pids = DataFetcher::alive_pids(output_dir);
+        if alive_pids.is_empty() {
+            return Ok(());
+        }
+
+        let pids = alive_pids
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if force {
+            println!(
+                "[!] Warning: Output directory {} is in use by running fuzzers (PIDs: {pids})",
+                output_dir.display()
+            );
+            Ok(())
+        } else {
+            bail!(
+                "Output directory {} is in use by running fuzzers (PIDs: {pids}). Use --force to start anyway",
+                output_dir.display()
+            );
+        }
+    }
+
+    /// Creates the directories of the campaign and checks the access the instances need
+    ///
+    /// `output_dir` is where the instances write to, `mirror_dst` the output directory it is
+    /// mirrored to, if any.
+    ///
+    /// # Errors
+    /// * If a directory cannot be created or lacks the required access, naming its option
+    fn preflight(
+        args: &RunArgs,
+        afl_generator: &AFLCmdGenerator,
+        output_dir: &Path,
+        mirror_dst: Option<&Path>,
+    ) -> Result<()> {
+        const OUTPUT_OPTION: &str = "[afl_cfg] solution_dir / --output-dir";
+        let work_dir_option = if args.output_on_ramdisk {
+            "[session] output_on_ramdisk / --output-on-ramdisk"
+        } else if args.local_work_dir.is_some() {
+            "--local-work-dir"
+        } else {
+            OUTPUT_OPTION
+        };
+
+        let mut preflight = Preflight::default()
+            .with_output_dir(output_dir, work_dir_option)
+            .with_input_dir(
+                &afl_generator.base_cfg.input_dir,
+                "[afl_cfg] seed_dir / --input-dir",
+            );
+        if let Some(dst) = mirror_dst {
+            preflight = preflight.with_output_dir(dst, OUTPUT_OPTION);
+        }
+        if let Some(ramdisk) = &afl_generator.base_cfg.ramdisk {
+            preflight =
+                preflight.with_mount_point(Path::new(ramdisk), "[misc] is_ramdisk / --is-ramdisk");
+        }
+        preflight.run()
+    }
+
+    /// Records the build ids of the harness binaries so the TUI can spot instances that run a
+    /// different build later on, e.g. after a partial restart following a rebuild
+    fn record_build_ids(output_dir: &Path, harness: &Harness) {
+        let binaries = [
+            Some(&harness.target_bin),
+            harness.sanitizer_bin.as_ref(),
+            harness.cmplog_bin.as_ref(),
+            harness.cmpcov_bin.as_ref(),
+        ];
+        let record = BuildIdRecord::new(binaries.into_iter().flatten().map(PathBuf::as_path));
+        if let Err(e) = record.save(output_dir) {
+            println!("[!] Warning: Failed to record the build ids: {e}");
+        }
+    }
+
+    /// Traces a few seeds with `afl-showmap` to catch a target without instrumentation before
+    /// launching, on by default if the input directory holds seeds
+    ///
+    /// # Errors
+    /// * If the target is not instrumented or covers the same tuples for every seed
+    /// * If the check was requested explicitly but could not be carried out
+    fn verify_instrumentation(
+        args: &RunArgs,
+        afl_generator: &AFLCmdGenerator,
+        afl_binary: &Path,
+    ) -> Result<()> {
+        let explicit = match args.verify_instrumentation {
+            Some(false) => return Ok(()),
+            Some(true) => true,
+            None => false,
+        };
+        let input_dir = &afl_generator.base_cfg.input_dir;
+        let mut rng = afl_generator
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+        let seeds = InstrumentationCheck::sample_seeds(input_dir, &mut rng).unwrap_or_default();
+        if seeds.is_empty() {
+            if explicit {
+                bail!(
+                    "The instrumentation check needs seeds in {}",
+                    input_dir.display()
+                );
+            }
+            return Ok(());
+        }
+
+        let harness = &afl_generator.harness;
+        let check = InstrumentationCheck::new(afl_binary)
+            .with_afl_flags(afl_generator.base_cfg.raw_afl_flags.as_deref())
+            .with_min_tuples(args.min_tuples.unwrap_or(DEFAULT_MIN_TUPLES));
+        match check.run(&harness.target_bin, harness.target_args.as_deref(), &seeds) {
+            Ok(tuples) => {
+                println!(
+                    "[+] Instrumentation verified: {} seeds covered {} tuples",
+                    tuples.len(),
+                    tuples
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("/")
+                );
+                Ok(())
+            }
+            Err(e) if !explicit && e.downcast_ref::<InstrumentationIssue>().is_none() => {
+                println!("[!] Warning: Skipping the instrumentation check: {e:#}");
+                Ok(())
+            }
+            Err(e) => Err(e.context(format!(
+                "The instrumentation check of {} failed",
+                harness.target_bin.display()
+            ))),
+        }
+    }
+
+    /// Records the session, harness, tag, commands and config source so `aflr list` can describe the campaign
+    /// and `aflr gen --diff` can compare against it later on
+    fn record_campaign(
+        &self,
+        output_dir: &Path,
+        session_name: &str,
+        harness: &Harness,
+        tag: Option<&str>,
+        commands: Vec<String>,
+        generated_commands: Vec<String>,
+    ) {
+        let record = CampaignRecord::new(session_name, &harness.target_bin, tag, Clock::from_env())
+            .with_commands(commands)
+            .with_generated_commands(generated_commands)
+            .with_config_source(self.arg_aggregator.config_source().map(ToString::to_string));
+        if let Err(e) = record.save(output_dir) {
+            println!("[!] Warning: Failed to record the campaign metadata: {e}");
+        }
+    }
+
+    /// Appends `note` to the remediations in the campaign record so `list` and later readers
+    /// know the effective configuration differs from the requested one
+    fn note_remediation(output_dir: &Path, note: String) {
+        let mut record = CampaignRecord::load(output_dir).unwrap_or_default();
+        record.remediations.push(note);
+        if let Err(e) = record.save(output_dir) {
+            println!("[!] Warning: Failed to record the remediation: {e}");
+        }
+    }
+
+    /// Spawns a detached `aflr mirror` process that copies `local_dir` to `output_dir`
+    fn spawn_mirror(local_dir: &Path, output_dir: &Path, interval: DurationOpt) -> Result<()> {
+        let child = std::process::Command::new(
+            std::env::current_exe().context("Failed to resolve aflr binary")?,
+        )
+        .arg("mirror")
+        .arg("--from")
+        .arg(local_dir)
+        .arg("--to")
+        .arg(output_dir)
+        .arg("--interval")
+        .arg(interval.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        // Keep the mirror alive when the terminal of the attached session goes away
+        .process_group(0)
+        .spawn()
+        .context("Failed to spawn mirror process")?;
+
+        println!(
+            "[+] Mirroring {} to {} every {interval} (PID: {})",
+            local_dir.display(),
+            output_dir.display(),
+            child.id()
+        );
+        Ok(())
+    }
+
+    /// Launches the session on `remote` with paths rewritten by the configured prefix map
+    fn execute_remote_session(
+        args: &RunArgs,
+        remote: &str,
+        commands: &[String],
+        sname: &str,
+    ) -> Result<()> {
+        let path_map = PathMap::new(&args.remote_path_map)?;
+        let commands: Vec<String> = commands.iter().map(|c| path_map.rewrite(c)).collect();
+
+        if args.dry_run {
+            println!("Generated commands for {remote}:");
+            for (i, cmd) in commands.iter().enumerate() {
+                println!("  {i:3}. {cmd}");
+            }
+            return Ok(());
+        }
+
+        if args.tui {
+            bail!("TUI mode is not supported for remote sessions");
+        }
+
+        let pid_fn = session_pid_file(sname, "remote");
+        let host = RemoteHost::new(remote);
+        let attach_hint = match &args.session_runner {
+            SessionRunner::Screen => {
+                let screen = ScreenSession::new(sname, &commands, Path::new(&pid_fn))
+                    .context("Failed to create Screen session")?
+                    .with_layout(args.layout.unwrap_or_default())?;
+                host.launch(&screen)?;
+                host.attach_hint::<Screen>(sname)
+            }
+            SessionRunner::Tmux => {
+                let tmux = TmuxSession::new(sname, &commands, Path::new(&pid_fn))
+                    .context("Failed to create Tmux session")?
+                    .with_layout(args.layout.unwrap_or_default())?;
+                host.launch(&tmux)?;
+                host.attach_hint::<Tmux>(sname)
+            }
+        };
+
+        println!("[+] Started session '{sname}' on {remote}. Attach with: {attach_hint}");
+        Ok(())
+    }
+
+    /// Creates the Docker launcher mounting the working, input and output directories
+    fn docker_launcher(args: &RunArgs, image: &str) -> Result<DockerLauncher> {
+        let input_dir = args
+            .gen_args
+            .input_dir
+            .clone()
+            .unwrap_or_else(|| constants::AFL_CORPUS.into());
+        let output_dir = args
+            .gen_args
+            .output_dir
+            .clone()
+            .unwrap_or_else(|| constants::AFL_OUTPUT.into());
+        for dir in [&input_dir, &output_dir] {
+            // Docker would otherwise create missing mount points owned by root
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        Ok(DockerLauncher::new(image)?
+            .with_volumes(&[&input_dir, &output_dir])?
+            .with_privileged(args.docker_privileged))
+    }
+
+    /// Starts the whole session inside a single container of `docker`
+    fn execute_docker_session(
+        args: &RunArgs,
+        docker: &DockerLauncher,
+        commands: &[String],
+        sname: &str,
+    ) -> Result<()> {
+        if args.tui {
+            bail!("TUI mode is not supported when running the session in a single container");
+        }
+
+        let pid_fn = session_pid_file(sname, "docker");
+        let mut attach = match &args.session_runner {
+            SessionRunner::Screen => {
+                let screen = ScreenSession::new(sname, commands, Path::new(&pid_fn))
+                    .context("Failed to create Screen session")?
+                    .with_layout(args.layout.unwrap_or_default())?;
+                docker.launch(sname, &screen)?;
+                DockerLauncher::attach_command::<Screen>(sname, sname)
+            }
+            SessionRunner::Tmux => {
+                let tmux = TmuxSession::new(sname, commands, Path::new(&pid_fn))
+                    .context("Failed to create Tmux session")?
+                    .with_layout(args.layout.unwrap_or_default())?;
+                docker.launch(sname, &tmux)?;
+                DockerLauncher::attach_command::<Tmux>(sname, sname)
+            }
+        };
+
+        println!("[+] Started session '{sname}' in container '{sname}'");
+        if args.detached {
+            println!("[*] Stop it with: docker rm -f {sname}");
+        } else {
+            attach
+                .status()
+                .context("Failed to attach to the container session")?;
+        }
+        Ok(())
+    }
+
+    /// Run arguments of a single `[[targets]]` entry writing into `<output_dir>/<name>`
+    ///
+    /// The auxiliary builds of `[target]` belong to that target and are not inherited.
+    fn target_run_args(args: &RunArgs, target: &TargetEntry, output_dir: &Path) -> RunArgs {
+        let name = target.name();
+        let mut run_args = args.clone();
+        let gen_args = &mut run_args.gen_args;
+        gen_args.target = Some(PathBuf::from(&target.path));
+        gen_args.san_target = target.san_path.as_ref().map(PathBuf::from);
+        gen_args.cmpl_target = target.cmpl_path.as_ref().map(PathBuf::from);
+        gen_args.cmpc_target = target.cmpc_path.as_ref().map(PathBuf::from);
+        if target.args.is_some() {
+            gen_args.target_args.clone_from(&target.args);
+        }
+        gen_args.runners = target
+            .runners
+            .map(|runners| RunnerCount::Fixed(runners.resolve()))
+            .or(gen_args.runners);
+        gen_args.output_dir = Some(output_dir.join(&name));
+        run_args.session_name = args
+            .session_name
+            .as_ref()
+            .map(|base| format!("{base}_{name}"));
+        run_args
+    }
+
+    /// Launches a session per `[[targets]]` entry, each in its own output subdirectory
+    ///
+    /// With `--tui` a single TUI aggregates all targets, otherwise the first session is
+    /// attached unless running detached.
+    fn execute_multi_target(
+        &self,
+        args: &RunArgs,
+        raw_afl_flags: &RawAflFlags,
+        targets: &[TargetEntry],
+    ) -> Result<ExitCode> {
+        validate_targets(targets)?;
+        if args.edit {
+            bail!("--edit supports a single target only, pass --target");
+        }
+        if args.remote.is_some()
+            || args.docker.is_some()
+            || args.local_work_dir.is_some()
+            || args.output_on_ramdisk
+        {
+            bail!("Remote, Docker and local work directory sessions do not support multiple [[targets]] yet");
+        }
+        let output_dir = args
+            .gen_args
+            .output_dir
+            .clone()
+            .unwrap_or_else(|| constants::AFL_OUTPUT.into());
+
+        let mut sessions = Vec::new();
+        let mut locks = Vec::new();
+        let mut target_ramdisks = Vec::new();
+        for target in targets {
+            let target_args = Self::target_run_args(args, target, &output_dir);
+            println!("[*] Target '{}': {}", target.name(), target.path);
+            let afl_generator = GenCommand::create_afl_runner(
+                &target_args.gen_args,
+                raw_afl_flags,
+                args.is_ramdisk,
+            )
+            .with_context(|| format!("Failed to create AFL++ runner for '{}'", target.name()))?;
+
+            let mut warnings = Warnings::default();
+            let afl_commands = afl_generator
+                .run_collecting(&mut warnings)
+                .context("Failed to run AFL++ generator")?;
+            warnings.print();
+            warnings.check(args.gen_args.fail_on_warnings)?;
+
+            if args.dry_run {
+                afl_commands.print_with_env();
+                continue;
+            }
+            if let Some(cmd) = afl_commands.first() {
+                Self::verify_instrumentation(&target_args, &afl_generator, &cmd.afl_binary)?;
+            }
+
+            let target_output = target_args.gen_args.output_dir.clone().unwrap_or_default();
+            Self::preflight(&target_args, &afl_generator, &target_output, None)?;
+            locks.push(CampaignLock::acquire(&target_output, "run")?);
+            Self::check_output_dir_in_use(&target_output, args.force)?;
+            Self::record_build_ids(&target_output, &afl_generator.harness);
+
+            let target_cmd_args = target_args
+                .gen_args
+                .target_args
+                .clone()
+                .unwrap_or_default()
+                .join(" ");
+            let session_name = Self::generate_session_name(&target_args, &target_cmd_args);
+            let ramdisks: Vec<PathBuf> = afl_generator
+                .base_cfg
+                .ramdisk
+                .iter()
+                .map(PathBuf::from)
+                .collect();
+            target_ramdisks.push(SessionRamdisks::new(&session_name, &ramdisks));
+            self.record_campaign(
+                &target_output,
+                &session_name,
+                &afl_generator.harness,
+                args.tag.as_deref(),
+                afl_commands.to_string_vec(),
+                Vec::new(),
+            );
+            let pid_file = session_pid_file(&session_name, std::process::id());
+            sessions.push(TargetSession {
+                session_name,
+                commands: afl_commands.to_string_vec(),
+                pid_file,
+            });
+        }
+        if args.dry_run {
+            return Ok(ExitCode::Success);
+        }
+
+        let config_tui_options = self.arg_aggregator.tui_options()?;
+        let tui_options = TuiOptions {
+            control_socket: config_tui_options.control_socket || args.control_socket,
+            snapshots: SnapshotPolicy {
+                compress: config_tui_options.snapshots.compress || args.snapshot_compress,
+                ..config_tui_options.snapshots
+            },
+            plan: sessions
+                .iter()
+                .flat_map(|s| s.commands.iter().cloned())
+                .collect(),
+            ..config_tui_options
+        };
+        match &args.session_runner {
+            SessionRunner::Screen => Self::launch_target_sessions::<Screen>(
+                &sessions,
+                locks,
+                args,
+                &output_dir,
+                &tui_options,
+            ),
+            SessionRunner::Tmux => Self::launch_target_sessions::<Tmux>(
+                &sessions,
+                locks,
+                args,
+                &output_dir,
+                &tui_options,
+            ),
+        }
+    }
+
+    fn launch_target_sessions<T: SessionManager>(
+        targets: &[TargetSession],
+        locks: Vec<CampaignLock>,
+        args: &RunArgs,
+        output_dir: &Path,
+        tui_options: &TuiOptions,
+    ) -> Result<ExitCode> {
+        let sessions = targets
+            .iter()
+            .map(|t| {
+                Session::<T>::new(&t.session_name, &t.commands, &t.pid_file)?
+                    .with_layout(args.layout.unwrap_or_default())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for session in &sessions {
+            session.run()?;
+        }
+        drop(locks);
+
+        if args.tui || args.progress {
+            // One TUI or progress line over all targets, fed by the PIDs of every session
+            let pids: Vec<String> = targets
+                .iter()
+                .flat_map(|t| DataFetcher::read_pid_file(&t.pid_file))
+                .map(|pid| pid.to_string())
+                .collect();
+            let pid_file = PathBuf::from(format!("/tmp/.aflr_targets_{}.pids", std::process::id()));
+            std::fs::write(&pid_file, pids.join(":"))
+                .context("Failed to write the combined PID file")?;
+            if args.progress {
+                let summary = progress::run(output_dir, Some(&pid_file))?;
+                println!(
+                    "[+] Campaign summary: {}",
+                    progress::format_progress_line(&summary.data, NumberFormat::default())
+                );
+                if summary.interrupted {
+                    for session in &sessions {
+                        session.kill_session()?;
+                    }
+                }
+                return Ok(ExitCode::from_campaign(output_dir, summary.interrupted));
+            }
+            Tui::run(
+                &[output_dir.to_path_buf()],
+                Some(&pid_file),
+                tui_options,
+                CampaignData::new(),
+            )?;
+            return Ok(ExitCode::Success);
+        }
+
+        for (target, session) in targets.iter().zip(&sessions) {
+            println!(
+                "[+] Started session '{}'. Attach with: {}",
+                target.session_name,
+                session.attach_hint()
+            );
+        }
+        if let Some(session) = sessions.first().filter(|_| !args.detached) {
+            session.attach()?;
+        }
+        Ok(ExitCode::Success)
+    }
+
+    /// Creates the session of the configured runner for `commands` and runs it
+    fn launch_session(
+        args: &RunArgs,
+        commands: &[String],
+        sname: &str,
+        pid_file: &Path,
+        tui_options: &TuiOptions,
+        lock: Option<CampaignLock>,
+    ) -> Result<ExitCode> {
+        match &args.session_runner {
+            SessionRunner::Screen => {
+                let screen = ScreenSession::new(sname, commands, pid_file)
+                    .context("Failed to create Screen session")?
+                    .with_layout(args.layout.unwrap_or_default())?
+                    .with_lock(lock);
+                Self::execute_session(&screen, args, tui_options)
+            }
+            SessionRunner::Tmux => {
+                let tmux = TmuxSession::new(sname, commands, pid_file)
+                    .context("Failed to create Tmux session")?
+                    .with_layout(args.layout.unwrap_or_default())?
+                    .with_lock(lock);
+                Self::execute_session(&tmux, args, tui_options)
+            }
+        }
+    }
+
+    /// Tears down the session a failed launch left behind
+    fn kill_session(args: &RunArgs, sname: &str, pid_file: &Path) -> Result<()> {
+        match &args.session_runner {
+            SessionRunner::Screen => ScreenSession::new(sname, &[], pid_file)?.kill_session(),
+            SessionRunner::Tmux => TmuxSession::new(sname, &[], pid_file)?.kill_session(),
+        }
+    }
+
+    fn execute_session<T: SessionManager>(
+        session: &Session<T>,
+        args: &RunArgs,
+        tui_options: &TuiOptions,
+    ) -> Result<ExitCode> {
         if args.tui {
-            session.run_with_tui(&args.gen_args.output_dir.clone().unwrap())?;
+            session.run_with_tui(&args.gen_args.output_dir.clone().unwrap(), tui_options)?;
+        } else if args.progress {
+            return session.run_with_progress(&args.gen_args.output_dir.clone().unwrap());
+        } else if let Some(duration) = args.calibrate {
+            let stats = session.run_calibration(
+                &args.gen_args.output_dir.clone().unwrap(),
+                duration.as_duration(),
+            )?;
+            print!("{}", calibration::estimate(&stats));
         } else {
             session.run()?;
             if !args.detached {
                 session.attach()?;
             }
         }
-        Ok(())
+        Ok(ExitCode::Success)
+    }
+
+    /// Launches the session and, with `auto_remediate`, retries once if every instance failed
+    /// to start on the map size
+    #[allow(clippy::too_many_arguments)]
+    fn launch_with_remediation(
+        args: &RunArgs,
+        afl_generator: &mut AFLCmdGenerator,
+        docker: Option<&DockerLauncher>,
+        commands: Vec<String>,
+        sname: &str,
+        pid_file: &Path,
+        tui_options: TuiOptions,
+        lock: Option<CampaignLock>,
+    ) -> Result<ExitCode> {
+        let result = Self::launch_session(args, &commands, sname, pid_file, &tui_options, lock);
+        let Some((failures, map_size)) = result
+            .as_ref()
+            .err()
+            .and_then(|e| e.downcast_ref::<StartupFailures>())
+            // Regenerating would drop the hand edits of `--edit`
+            .filter(|_| args.auto_remediate && !args.edit)
+            .and_then(|failures| Some((failures, failures.remediable_map_size()?)))
+        else {
+            return result;
+        };
+
+        // Every instance died on the map size, so regenerate all commands with the size AFL++
+        // asked for and give the session a second chance
+        println!("[!] Warning: {failures}");
+        println!("[*] Retrying once with AFL_MAP_SIZE={map_size} for all instances");
+        // The failed session released the lock along with it
+        let lock = args
+            .gen_args
+            .output_dir
+            .as_deref()
+            .map(|dir| CampaignLock::acquire(dir, "run"))
+            .transpose()?;
+        Self::kill_session(args, sname, pid_file)?;
+        afl_generator.base_cfg = afl_generator
+            .base_cfg
+            .clone()
+            .with_map_size(Some(MapSize::Fixed(map_size)));
+        let mut warnings = Warnings::default();
+        let mut commands = afl_generator
+            .run_collecting(&mut warnings)
+            .context("Failed to run AFL++ generator")?
+            .to_string_vec();
+        warnings.print();
+        if let Some(docker) = docker {
+            commands = docker.wrap_commands(sname, &commands);
+        }
+        if let Some(output_dir) = &args.gen_args.output_dir {
+            Self::note_remediation(
+                output_dir,
+                format!(
+                    "AFL_MAP_SIZE={map_size} after {} of {} instances failed to start",
+                    failures.failed(),
+                    failures.total
+                ),
+            );
+        }
+        let tui_options = TuiOptions {
+            plan: commands.clone(),
+            ..tui_options
+        };
+        Self::launch_session(args, &commands, sname, pid_file, &tui_options, lock)
     }
 }
 
 impl Command for RunCommand<'_> {
-    fn execute(&self) -> Result<()> {
-        let (merged_args, raw_afl_flags) = self.arg_aggregator.merge_run_args(self.args)?;
+    fn execute(&self) -> Result<ExitCode> {
+        let (mut merged_args, raw_afl_flags) = self.arg_aggregator.merge_run_args(self.args)?;
+        let has_work_dir = merged_args.local_work_dir.is_some() || merged_args.output_on_ramdisk;
 
         if merged_args.tui && merged_args.detached {
             bail!("TUI and detached mode cannot be used together");
         }
+        if merged_args.progress && (merged_args.tui || merged_args.detached) {
+            bail!("Progress mode cannot be used together with TUI or detached mode");
+        }
+
+        if merged_args.remote.is_some() && (merged_args.is_ramdisk || has_work_dir) {
+            bail!("RAMDisk and local work directories are not supported for remote sessions");
+        }
+
+        // Remote hosts have a kernel of their own, everything else shares this one's
+        if !merged_args.dry_run && merged_args.remote.is_none() {
+            ensure_core_pattern(merged_args.fix_env)?;
+        }
+
+        if merged_args.calibrate.is_some()
+            && (merged_args.remote.is_some() || merged_args.docker.is_some() || has_work_dir)
+        {
+            bail!("Calibration probes only run in a local session");
+        }
+
+        // An explicit --target runs on its own even if the config lists several targets
+        let targets = self.arg_aggregator.targets();
+        if !targets.is_empty() && self.args.gen_args.target.is_none() {
+            if merged_args.calibrate.is_some() {
+                bail!("Calibration probes support a single target only, pass --target");
+            }
+            return self.execute_multi_target(&merged_args, &raw_afl_flags, targets);
+        }
 
-        let afl_generator = GenCommand::create_afl_runner(
+        let mut ramdisks = Vec::new();
+        if merged_args.output_on_ramdisk && !merged_args.dry_run {
+            let ramdisk = PathBuf::from(
+                create_ramdisk().context("Failed to create RAMDisk for the output directory")?,
+            );
+            merged_args.local_work_dir = Some(ramdisk.join(constants::RAMDISK_OUTPUT_DIR));
+            ramdisks.push(ramdisk);
+        }
+        // Let the instances write to the local work directory and mirror it to the output directory
+        let mirror_dst = merged_args.local_work_dir.clone().and_then(|local_dir| {
+            merged_args
+                .gen_args
+                .output_dir
+                .replace(local_dir)
+                .or_else(|| Some(constants::AFL_OUTPUT.into()))
+        });
+
+        let mut afl_generator = GenCommand::create_afl_runner(
             &merged_args.gen_args,
-            raw_afl_flags.as_ref(),
+            &raw_afl_flags,
             merged_args.is_ramdisk,
         )
         .context("Failed to create AFL++ runner")?;
+        // A retry after failed starts has to regenerate the very same commands
+        afl_generator.seed = Some(afl_generator.resolve_seed());
 
+        let mut warnings = Warnings::default();
         let afl_commands = afl_generator
-            .run()
+            .run_collecting(&mut warnings)
             .context("Failed to run AFL++ generator")?;
-
-        if merged_args.dry_run {
-            println!("{afl_commands:?}");
-            return Ok(());
-        }
+        warnings.print();
+        warnings.check(merged_args.gen_args.fail_on_warnings)?;
 
         let target_args = merged_args
             .gen_args
@@ -101,22 +882,162 @@ impl Command for RunCommand<'_> {
             .clone()
             .unwrap_or_default()
             .join(" ");
-
         let sname = Self::generate_session_name(&merged_args, &target_args);
-        let pid_fn = format!("/tmp/.{}_{}.pids", &sname, std::process::id());
-        let pid_fn_path = Path::new(&pid_fn);
+        // Released again on the way out unless the session keeps running
+        ramdisks.extend(afl_generator.base_cfg.ramdisk.iter().map(PathBuf::from));
+        let session_ramdisks = SessionRamdisks::new(&sname, &ramdisks);
 
-        match &merged_args.session_runner {
-            SessionRunner::Screen => {
-                let screen = ScreenSession::new(&sname, &afl_commands.to_string_vec(), pid_fn_path)
-                    .context("Failed to create Screen session")?;
-                Self::execute_session(&screen, &merged_args)
+        // Remote hosts have binaries of their own, everything else runs them from here
+        if let Some(cmd) = afl_commands
+            .first()
+            .filter(|_| !merged_args.dry_run && merged_args.remote.is_none())
+        {
+            Self::verify_instrumentation(&merged_args, &afl_generator, &cmd.afl_binary)?;
+        }
+
+        // Hand edits replace the generated commands for every backend, the generated ones are
+        // kept in the campaign record next to them
+        let generated = afl_commands.to_string_vec();
+        let (plain_commands, edited) = if merged_args.edit {
+            let edited = cmd_edit::edit_commands(&generated, &cmd_edit::editor())?;
+            let changed = edited != generated;
+            if changed {
+                println!("[+] Using the edited commands");
             }
-            SessionRunner::Tmux => {
-                let tmux = TmuxSession::new(&sname, &afl_commands.to_string_vec(), pid_fn_path)
-                    .context("Failed to create Tmux session")?;
-                Self::execute_session(&tmux, &merged_args)
+            (edited, changed)
+        } else {
+            (generated.clone(), false)
+        };
+
+        if let Some(remote) = &merged_args.remote {
+            return Self::execute_remote_session(&merged_args, remote, &plain_commands, &sname)
+                .map(|()| ExitCode::Success);
+        }
+
+        let docker = merged_args
+            .docker
+            .as_deref()
+            .map(|image| Self::docker_launcher(&merged_args, image))
+            .transpose()?;
+        let docker_mode = merged_args.docker_mode.unwrap_or_default();
+        let mut commands = plain_commands.clone();
+
+        if let Some(docker) = &docker {
+            if docker_mode == DockerMode::Instance {
+                commands = docker.wrap_commands(&sname, &commands);
+            }
+            if merged_args.dry_run {
+                for (i, cmd) in commands.iter().enumerate() {
+                    println!("  {i:3}. {cmd}");
+                }
+                return Ok(ExitCode::Success);
             }
         }
+
+        if merged_args.dry_run {
+            if edited {
+                println!("Edited commands:");
+                for (i, cmd) in commands.iter().enumerate() {
+                    println!("  {i:3}. {cmd}");
+                }
+            } else {
+                afl_commands.print_with_env();
+            }
+            return Ok(ExitCode::Success);
+        }
+
+        let probe_output = merged_args
+            .calibrate
+            .map(|_| {
+                ProbeOutput::snapshot(
+                    merged_args
+                        .gen_args
+                        .output_dir
+                        .as_deref()
+                        .unwrap_or(Path::new(constants::AFL_OUTPUT)),
+                )
+            })
+            .transpose()?;
+
+        let mut lock = None;
+        if let Some(output_dir) = &merged_args.gen_args.output_dir {
+            Self::preflight(
+                &merged_args,
+                &afl_generator,
+                output_dir,
+                mirror_dst.as_deref(),
+            )?;
+            lock = Some(CampaignLock::acquire(output_dir, "run")?);
+            Self::check_output_dir_in_use(output_dir, merged_args.force)?;
+            Self::record_build_ids(output_dir, &afl_generator.harness);
+            self.record_campaign(
+                output_dir,
+                &sname,
+                &afl_generator.harness,
+                merged_args.tag.as_deref(),
+                plain_commands,
+                if edited { generated } else { Vec::new() },
+            );
+            if let Some(dst) = &mirror_dst {
+                Self::spawn_mirror(
+                    output_dir,
+                    dst,
+                    merged_args
+                        .mirror_interval
+                        .unwrap_or(constants::MIRROR_INTERVAL),
+                )?;
+            }
+        }
+
+        if let Some(docker) = docker
+            .as_ref()
+            .filter(|_| docker_mode == DockerMode::Session)
+        {
+            session_ramdisks.keep();
+            return Self::execute_docker_session(&merged_args, docker, &commands, &sname)
+                .map(|()| ExitCode::Success);
+        }
+
+        let config_tui_options = self.arg_aggregator.tui_options()?;
+        let tui_options = TuiOptions {
+            control_socket: config_tui_options.control_socket || merged_args.control_socket,
+            snapshots: SnapshotPolicy {
+                compress: config_tui_options.snapshots.compress || merged_args.snapshot_compress,
+                ..config_tui_options.snapshots
+            },
+            plan: commands.clone(),
+            ..config_tui_options
+        };
+        let pid_fn = session_pid_file(&sname, std::process::id());
+        let pid_fn_path = pid_fn.as_path();
+
+        let result = Self::launch_with_remediation(
+            &merged_args,
+            &mut afl_generator,
+            docker.as_ref(),
+            commands,
+            &sname,
+            pid_fn_path,
+            tui_options,
+            lock,
+        );
+        let Some(probe_output) = probe_output else {
+            return result;
+        };
+
+        // A probe that failed to start leaves its session behind as well
+        if result.is_err() {
+            let _ = Self::kill_session(&merged_args, &sname, pid_fn_path);
+        }
+        if merged_args.keep_probe_output {
+            println!(
+                "[*] Kept the probe output in {}",
+                probe_output.dir.display()
+            );
+        } else {
+            probe_output.restore()?;
+            println!("[+] Restored {}", probe_output.dir.display());
+        }
+        result
     }
 }
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use std::time::SystemTime;
+
+use crate::{
+    cli::{SnapshotsArgs, SnapshotsCommands, SnapshotsListArgs, SnapshotsRestoreArgs},
+    commands::{Command, ExitCode},
+    utils::snapshot::{self, snapshots_dir},
+};
+
+pub struct SnapshotsCommand<'a> {
+    args: &'a SnapshotsArgs,
+}
+
+impl<'a> SnapshotsCommand<'a> {
+    pub fn new(args: &'a SnapshotsArgs) -> Self {
+        Self { args }
+    }
+
+    fn list(args: &SnapshotsListArgs) -> Result<()> {
+        let snapshots = snapshot::list(&args.output_dir)?;
+        if snapshots.is_empty() {
+            println!(
+                "[*] No snapshots in {}, set [misc] snapshot_interval to take them while the TUI runs",
+                snapshots_dir(&args.output_dir).display()
+            );
+            return Ok(());
+        }
+
+        println!("{:<18}  {:<10}  AGE", "SNAPSHOT", "FORMAT");
+        for snapshot in &snapshots {
+            let age = SystemTime::now()
+                .duration_since(snapshot.taken)
+                .unwrap_or_default()
+                .as_secs();
+            println!(
+                "{:<18}  {:<10}  {}h {:02}m",
+                snapshot.name,
+                if snapshot.compressed {
+                    "tar.gz"
+                } else {
+                    "hardlinks"
+                },
+                age / 3600,
+                age % 3600 / 60
+            );
+        }
+        Ok(())
+    }
+
+    fn restore(args: &SnapshotsRestoreArgs) -> Result<()> {
+        let report = snapshot::restore(&args.output_dir, &args.name)?;
+        println!(
+            "[+] Restored snapshot {} into {}: {} files in {:.1}s",
+            report.name,
+            args.output_dir.display(),
+            report.files,
+            report.duration.as_secs_f64()
+        );
+        Ok(())
+    }
+}
+
+impl Command for SnapshotsCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        match &self.args.cmd {
+            SnapshotsCommands::List(args) => Self::list(args),
+            SnapshotsCommands::Restore(args) => Self::restore(args),
+        }
+        .map(|()| ExitCode::Success)
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::{bail, Result};
+
+use crate::{
+    argument_aggregator::ArgumentAggregator,
+    cli::TopArgs,
+    commands::{Command, ExitCode},
+    tui::{
+        data_collection::{instance_dirs, DataFetcher},
+        forensics::DEFAULT_STALL_THRESHOLD,
+        format::NumberFormat,
+        session::CampaignData,
+        top,
+    },
+};
+
+/// Compact live table of the instances of a campaign, for quick checks without the TUI
+pub struct TopCommand<'a> {
+    args: &'a TopArgs,
+    arg_aggregator: &'a ArgumentAggregator,
+}
+
+impl<'a> TopCommand<'a> {
+    pub fn new(args: &'a TopArgs, arg_aggregator: &'a ArgumentAggregator) -> Self {
+        Self {
+            args,
+            arg_aggregator,
+        }
+    }
+}
+
+impl Command for TopCommand<'_> {
+    fn execute(&self) -> Result<ExitCode> {
+        let output_dir = &self.args.afl_output;
+        if instance_dirs(output_dir).is_empty() {
+            bail!("No fuzzer_stats found in {}", output_dir.display());
+        }
+        // Same liveness and stall classification as the TUI
+        let stall_threshold = self
+            .arg_aggregator
+            .tui_options()?
+            .stall_threshold
+            .unwrap_or(DEFAULT_STALL_THRESHOLD);
+        let mut fetcher = DataFetcher::new(output_dir, None, CampaignData::new())
+            .with_stall_threshold(stall_threshold);
+        top::run(&mut fetcher, self.args.once, NumberFormat::Raw)?;
+        Ok(ExitCode::Success)
+    }
+}
@@ -10,22 +10,43 @@ pub mod tui;
 pub mod utils;
 
 use argument_aggregator::ArgumentAggregator;
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, MigrationCommands};
 use commands::{
-    add_seed::AddSeedCommand, cov::CovCommand, gen::GenCommand, kill::KillCommand,
-    render_tui::RenderCommand, run::RunCommand, Command,
+    add_seed::AddSeedCommand, cleanup::CleanupCommand, collect_crashes::CollectCrashesCommand,
+    config::ConfigCommand, cov::CovCommand, doctor::DoctorCommand, env_check::EnvCheckCommand,
+    gen::GenCommand, kill::KillCommand, list::ListCommand, merge_corpora::MergeCorporaCommand,
+    migration::MigrationCommand, mirror::MirrorCommand, pause::PauseCommand,
+    render_tui::RenderCommand, replay::ReplayCommand, restart::RestartCommand, run::RunCommand,
+    snapshots::SnapshotsCommand, top::TopCommand, Command, ExitCode,
 };
+use utils::clock::SOURCE_DATE_EPOCH;
 
 fn main() -> Result<()> {
     let cli_args = Cli::parse();
+    // Exported so child processes like genhtml pick the fixed time up as well
+    if let Some(timestamp) = cli_args.timestamp {
+        std::env::set_var(SOURCE_DATE_EPOCH, timestamp.to_string());
+    }
     let mut arg_aggregator = ArgumentAggregator::new();
 
     // Load config based on command
     match &cli_args.cmd {
-        Commands::Gen(args) => arg_aggregator.load(args.config.as_ref()),
-        Commands::Run(args) => arg_aggregator.load(args.gen_args.config.as_ref()),
+        Commands::Gen(args) => arg_aggregator
+            .load(args.gen_args.config.as_ref())
+            .and_then(|()| arg_aggregator.apply_preset(args.gen_args.preset.as_deref())),
+        Commands::Run(args) => arg_aggregator
+            .load(args.gen_args.config.as_ref())
+            .and_then(|()| arg_aggregator.apply_preset(args.gen_args.preset.as_deref())),
         Commands::Cov(args) => arg_aggregator.load(args.config.as_ref()),
         Commands::AddSeed(args) => arg_aggregator.load(args.config.as_ref()),
+        Commands::Replay(args) => arg_aggregator.load(args.config.as_ref()),
+        Commands::Tui(args) => arg_aggregator.load(args.config.as_ref()),
+        Commands::Top(args) => arg_aggregator.load(args.config.as_ref()),
+        Commands::List(args) => arg_aggregator.load(args.config.as_ref()),
+        Commands::Migrate(args) => match &args.cmd {
+            MigrationCommands::Export(args) => arg_aggregator.load(args.config.as_ref()),
+            MigrationCommands::Import(_) => Ok(()),
+        },
         _ => Ok(()),
     }?;
 
@@ -34,14 +55,33 @@ fn main() -> Result<()> {
         Commands::Gen(args) => GenCommand::new(args, &arg_aggregator).execute(),
         Commands::Run(args) => RunCommand::new(args, &arg_aggregator).execute(),
         Commands::Cov(args) => CovCommand::new(args, &arg_aggregator).execute(),
-        Commands::Tui(args) => RenderCommand::new(args).execute(),
+        Commands::Tui(args) => RenderCommand::new(args, &arg_aggregator).execute(),
+        Commands::Top(args) => TopCommand::new(args, &arg_aggregator).execute(),
         Commands::Kill(args) => KillCommand::new(args).execute(),
+        Commands::Pause(args) => PauseCommand::new(args, false).execute(),
+        Commands::Continue(args) => PauseCommand::new(args, true).execute(),
+        Commands::Restart(args) => RestartCommand::new(args).execute(),
+        Commands::List(args) => ListCommand::new(args, &arg_aggregator).execute(),
+        Commands::Snapshots(args) => SnapshotsCommand::new(args).execute(),
+        Commands::Migrate(args) => MigrationCommand::new(args, &arg_aggregator).execute(),
+        Commands::Config(args) => ConfigCommand::new(args).execute(),
         Commands::AddSeed(args) => AddSeedCommand::new(args, &arg_aggregator).execute(),
+        Commands::CollectCrashes(args) => CollectCrashesCommand::new(args).execute(),
+        Commands::MergeCorpora(args) => MergeCorporaCommand::new(args).execute(),
+        Commands::Replay(args) => ReplayCommand::new(args, &arg_aggregator).execute(),
+        Commands::Mirror(args) => MirrorCommand::new(args).execute(),
+        Commands::EnvCheck => EnvCheckCommand.execute(),
+        Commands::Doctor => DoctorCommand.execute(),
+        Commands::Cleanup(args) => CleanupCommand::new(args).execute(),
     };
 
-    if let Err(e) = result {
-        eprintln!("{e}");
-        std::process::exit(1);
+    let exit_code = result.unwrap_or_else(|e| {
+        // Include the causes, e.g. the offending token of an invalid raw AFL++ flag
+        eprintln!("Error: {e:?}");
+        ExitCode::from_error(&e)
+    });
+    if exit_code != ExitCode::Success {
+        std::process::exit(exit_code.code());
     }
 
     Ok(())
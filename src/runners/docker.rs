@@ -0,0 +1,233 @@
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::runners::runner::{Session, SessionManager};
+
+/// Capabilities and security options AFL++ needs inside a container
+///
+/// `SYS_PTRACE` and an unconfined seccomp profile are required for the fork server,
+/// sanitizers and `AFL_DEBUG` style introspection to work reliably
+const AFL_SECURITY_ARGS: &[&str] = &[
+    "--cap-add=SYS_PTRACE",
+    "--security-opt",
+    "seccomp=unconfined",
+];
+
+/// Runs AFL++ instances inside Docker containers
+#[derive(Debug, Clone)]
+pub struct DockerLauncher {
+    /// Image the fuzzers are started in
+    image: String,
+    /// Host directories that are bind-mounted at the same path in the container
+    volumes: Vec<PathBuf>,
+    /// Working directory inside the container
+    work_dir: PathBuf,
+    /// Run the containers with `--privileged`
+    privileged: bool,
+}
+
+impl DockerLauncher {
+    /// Creates a launcher for `image` that mounts the current working directory
+    ///
+    /// # Errors
+    /// * If the current working directory cannot be determined
+    pub fn new(image: &str) -> Result<Self> {
+        let work_dir = std::env::current_dir().context("Failed to get current directory")?;
+        Ok(Self {
+            image: image.to_string(),
+            volumes: Vec::new(),
+            work_dir,
+            privileged: false,
+        })
+    }
+
+    /// Additionally bind-mounts `dirs` at their absolute host path
+    ///
+    /// # Errors
+    /// * If a path cannot be made absolute
+    pub fn with_volumes(mut self, dirs: &[&Path]) -> Result<Self> {
+        for dir in dirs {
+            let dir = std::path::absolute(dir)
+                .with_context(|| format!("Failed to resolve {}", dir.display()))?;
+            if dir != self.work_dir && !self.volumes.contains(&dir) {
+                self.volumes.push(dir);
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn with_privileged(mut self, privileged: bool) -> Self {
+        self.privileged = privileged;
+        self
+    }
+
+    /// Arguments shared by all `docker run` invocations
+    ///
+    /// The host PID namespace is shared so that the PIDs in `fuzzer_stats` stay valid
+    /// for the TUI, `kill` and the output directory checks
+    fn run_args(&self) -> Vec<String> {
+        let mut args = vec!["--pid=host".to_string()];
+        args.extend(AFL_SECURITY_ARGS.iter().map(ToString::to_string));
+        if self.privileged {
+            args.push("--privileged".to_string());
+        }
+        for dir in std::iter::once(&self.work_dir).chain(&self.volumes) {
+            args.push("-v".to_string());
+            args.push(format!("'{0}':'{0}'", dir.display()));
+        }
+        args.push("-w".to_string());
+        args.push(format!("'{}'", self.work_dir.display()));
+        args
+    }
+
+    /// Wraps a single generated command into a foreground `docker run` of its own
+    ///
+    /// Environment assignments of the command are kept by running it through `env`
+    pub fn wrap_command(&self, container_name: &str, cmd: &str) -> String {
+        format!(
+            "docker run --rm -it --name {container_name} {} {} env {cmd}",
+            self.run_args().join(" "),
+            self.image
+        )
+    }
+
+    /// Wraps all commands into one container each, named after the session
+    pub fn wrap_commands(&self, session_name: &str, commands: &[String]) -> Vec<String> {
+        commands
+            .iter()
+            .enumerate()
+            .map(|(i, cmd)| self.wrap_command(&format!("{session_name}_{i}"), cmd))
+            .collect()
+    }
+
+    /// Starts a single container and creates the whole session inside of it
+    ///
+    /// The image needs to provide `bash` and the chosen session runner
+    ///
+    /// # Errors
+    /// * If the container cannot be started
+    /// * If the session script fails inside the container
+    pub fn launch<T: SessionManager>(
+        &self,
+        container_name: &str,
+        session: &Session<T>,
+    ) -> Result<()> {
+        let script = session.create_standalone_script()?;
+
+        let start = Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "docker run -d --name {container_name} {} {} sleep infinity",
+                self.run_args().join(" "),
+                self.image
+            ))
+            .output()
+            .context("Failed to execute docker")?;
+        if !start.status.success() {
+            bail!(
+                "Failed to start container {container_name}: {}",
+                String::from_utf8_lossy(&start.stderr).trim()
+            );
+        }
+
+        let mut child = Command::new("docker")
+            .args(["exec", "-i", container_name, "bash", "-s"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute docker exec")?;
+
+        child
+            .stdin
+            .take()
+            .context("Failed to open docker exec stdin")?
+            .write_all(script.as_bytes())?;
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "Session in container {container_name} failed with exit code {}.\n  STDERR: {}",
+                output.status.code().unwrap_or(-1),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Command attaching to the session running inside `container_name`
+    pub fn attach_command<T: SessionManager>(container_name: &str, session_name: &str) -> Command {
+        let attach = T::build_attach_command(session_name);
+        let mut cmd = Command::new("docker");
+        cmd.args(["exec", "-it", container_name])
+            .arg(attach.get_program())
+            .args(attach.get_args());
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runners::tmux::Tmux;
+
+    fn launcher() -> DockerLauncher {
+        DockerLauncher {
+            image: "aflplusplus/aflplusplus".to_string(),
+            volumes: Vec::new(),
+            work_dir: PathBuf::from("/work"),
+            privileged: false,
+        }
+    }
+
+    #[test]
+    fn test_wrap_commands() {
+        let docker = launcher()
+            .with_volumes(&[Path::new("/work"), Path::new("/data/out")])
+            .unwrap();
+        let cmds = docker.wrap_commands(
+            "fuzz",
+            &[
+                "AFL_AUTORESUME=1 afl-fuzz -M main -i /work/in -o /data/out -- ./target"
+                    .to_string(),
+                "afl-fuzz -S s1 -i /work/in -o /data/out -- ./target".to_string(),
+            ],
+        );
+
+        assert_eq!(
+            cmds[0],
+            "docker run --rm -it --name fuzz_0 --pid=host --cap-add=SYS_PTRACE \
+             --security-opt seccomp=unconfined -v '/work':'/work' -v '/data/out':'/data/out' \
+             -w '/work' aflplusplus/aflplusplus env AFL_AUTORESUME=1 afl-fuzz -M main \
+             -i /work/in -o /data/out -- ./target"
+        );
+        assert!(cmds[1].starts_with("docker run --rm -it --name fuzz_1 "));
+        assert!(!cmds[1].contains("--privileged"));
+    }
+
+    #[test]
+    fn test_privileged_and_attach() {
+        let docker = launcher().with_privileged(true);
+        assert!(docker
+            .wrap_command("c", "afl-fuzz")
+            .contains(" --privileged -v '/work':'/work' "));
+
+        let attach = DockerLauncher::attach_command::<Tmux>("fuzz", "fuzz");
+        let args: Vec<_> = attach.get_args().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(attach.get_program(), "docker");
+        assert_eq!(
+            args,
+            [
+                "exec",
+                "-it",
+                "fuzz",
+                "tmux",
+                "attach-session",
+                "-t",
+                "fuzz"
+            ]
+        );
+    }
+}
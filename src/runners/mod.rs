@@ -1,3 +1,5 @@
+pub mod docker;
+pub mod remote;
 pub mod runner;
 pub mod screen;
 pub mod tmux;
@@ -0,0 +1,263 @@
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::runners::runner::{attach_command_line, Session, SessionManager};
+use crate::utils::build_id::BuildIdRecord;
+
+/// Characters that may precede a path inside a command line or config value
+const PATH_START: [char; 6] = [' ', '\t', '=', '\'', '"', ':'];
+/// Characters that may follow a path, besides its own subdirectories
+const PATH_END: [char; 8] = ['/', ' ', '\t', '\n', '\'', '"', ':', ','];
+
+/// Maps path prefixes to their counterparts on another machine, e.g. the remote host of
+/// `run --remote` or the new machine of `migrate import`
+#[derive(Debug, Clone, Default)]
+pub struct PathMap {
+    /// `(from, to)` prefix pairs, longest `from` prefix first
+    entries: Vec<(String, String)>,
+}
+
+impl PathMap {
+    /// Creates a path map from `FROM=TO` pairs
+    ///
+    /// # Errors
+    /// * If a mapping is not of the form `FROM=TO`
+    pub fn new(mappings: &[String]) -> Result<Self> {
+        let mut map = Self::default();
+        for mapping in mappings {
+            let (from, to) = mapping
+                .split_once('=')
+                .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+                .with_context(|| format!("Invalid path mapping '{mapping}', expected FROM=TO"))?;
+            map.add(Path::new(from), Path::new(to));
+        }
+        Ok(map)
+    }
+
+    /// Maps `from` and everything below it to `to`
+    pub fn add(&mut self, from: &Path, to: &Path) {
+        let trim = |path: &Path| {
+            let path = path.to_string_lossy();
+            match path.trim_end_matches('/') {
+                "" => "/".to_string(),
+                trimmed => trimmed.to_string(),
+            }
+        };
+        self.entries.push((trim(from), trim(to)));
+        self.entries
+            .sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+    }
+
+    /// Whether a mapping applies to `path`
+    pub fn covers(&self, path: &str) -> bool {
+        self.matching_entry(path).is_some()
+    }
+
+    fn matching_entry(&self, text: &str) -> Option<&(String, String)> {
+        self.entries.iter().find(|(from, _)| {
+            text.strip_prefix(from.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(PATH_END))
+        })
+    }
+
+    /// Replaces every mapped path in `text`, e.g. a command line or a config value
+    ///
+    /// Plain arguments as well as values of `KEY=VALUE` environment assignments are rewritten.
+    /// Paths only match at word boundaries, so `/src/out` does not rewrite `/src/output`.
+    pub fn rewrite(&self, text: &str) -> String {
+        let mut rewritten = String::with_capacity(text.len());
+        let mut rest = text;
+        let mut at_boundary = true;
+        while let Some(c) = rest.chars().next() {
+            if at_boundary {
+                if let Some((from, to)) = self.matching_entry(rest) {
+                    rewritten.push_str(to);
+                    rest = &rest[from.len()..];
+                    at_boundary = false;
+                    continue;
+                }
+            }
+            rewritten.push(c);
+            rest = &rest[c.len_utf8()..];
+            at_boundary = PATH_START.contains(&c);
+        }
+        rewritten
+    }
+
+    /// Rewrites every string value of a TOML config in place
+    pub fn rewrite_toml(&self, table: &mut toml::Table) {
+        for (_, value) in table.iter_mut() {
+            self.rewrite_toml_value(value);
+        }
+    }
+
+    fn rewrite_toml_value(&self, value: &mut toml::Value) {
+        match value {
+            toml::Value::String(s) => *s = self.rewrite(s),
+            toml::Value::Array(values) => {
+                for value in values {
+                    self.rewrite_toml_value(value);
+                }
+            }
+            toml::Value::Table(table) => self.rewrite_toml(table),
+            _ => {}
+        }
+    }
+
+    /// The build ids of `record` keyed by the new binary paths
+    pub fn rewrite_build_ids(&self, record: &BuildIdRecord) -> BuildIdRecord {
+        BuildIdRecord {
+            binaries: record
+                .binaries
+                .iter()
+                .map(|(binary, id)| {
+                    (
+                        PathBuf::from(self.rewrite(&binary.to_string_lossy())),
+                        id.clone(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Launches sessions on a remote host over SSH
+#[derive(Debug, Clone)]
+pub struct RemoteHost {
+    /// SSH destination, e.g. `user@host`
+    destination: String,
+}
+
+impl RemoteHost {
+    pub fn new(destination: &str) -> Self {
+        Self {
+            destination: destination.to_string(),
+        }
+    }
+
+    /// Creates the session on the remote host by piping the session script into `ssh`
+    ///
+    /// # Errors
+    /// * If the session script could not be created
+    /// * If `ssh` fails or the script exits with an error on the remote host
+    pub fn launch<T: SessionManager>(&self, session: &Session<T>) -> Result<()> {
+        let script = session.create_standalone_script()?;
+
+        let mut child = Command::new("ssh")
+            .arg(&self.destination)
+            .arg("bash -s")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute ssh")?;
+
+        child
+            .stdin
+            .take()
+            .context("Failed to open ssh stdin")?
+            .write_all(script.as_bytes())?;
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "Remote session on {} failed with exit code {}.\n  STDERR: {}",
+                self.destination,
+                output.status.code().unwrap_or(-1),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the command line to attach to the remote session
+    pub fn attach_hint<T: SessionManager>(&self, session_name: &str) -> String {
+        format!(
+            "ssh -t {} {}",
+            self.destination,
+            attach_command_line::<T>(session_name)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runners::tmux::Tmux;
+
+    #[test]
+    fn test_path_map_rewrite() {
+        let map = PathMap::new(&[
+            "/home/me/fuzz=/srv/fuzz".to_string(),
+            "/home/me/fuzz/bin/=/opt/bin".to_string(),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            map.rewrite(
+                "AFL_TESTCACHE_SIZE=50 AFL_CUSTOM_MUTATOR_LIBRARY=/home/me/fuzz/lib.so \
+                 afl-fuzz -i /home/me/fuzz/in -o /home/me/fuzzer -- /home/me/fuzz/bin/target @@"
+            ),
+            "AFL_TESTCACHE_SIZE=50 AFL_CUSTOM_MUTATOR_LIBRARY=/srv/fuzz/lib.so \
+             afl-fuzz -i /srv/fuzz/in -o /home/me/fuzzer -- /opt/bin/target @@"
+        );
+    }
+
+    #[test]
+    fn test_path_map_boundaries() {
+        let mut map = PathMap::default();
+        map.add(Path::new("/src/out/"), Path::new("/dst/out"));
+        map.add(Path::new("/src"), Path::new("/dst/src"));
+        assert_eq!(
+            map.rewrite(
+                "AFL_TOKEN_FILE=/src/dict afl-fuzz -i /src/in -o /src/out -S s0 -- /src/t @@"
+            ),
+            "AFL_TOKEN_FILE=/dst/src/dict afl-fuzz -i /dst/src/in -o /dst/out -S s0 -- /dst/src/t @@"
+        );
+        // Only whole path components match
+        assert_eq!(map.rewrite("/src/output /srcs"), "/dst/src/output /srcs");
+        assert_eq!(map.rewrite("'/src/out/a b'"), "'/dst/out/a b'");
+        assert!(map.covers("/src"));
+        assert!(!map.covers("/other/src"));
+    }
+
+    #[test]
+    fn test_path_map_toml() {
+        let map = PathMap::new(&["/src=/dst".to_string()]).unwrap();
+        let mut table: toml::Table = toml::from_str(
+            "[target]\npath = \"/src/t\"\nargs = [\"-f\", \"/src/in.cfg\"]\n[afl_cfg]\nrunners = 4\n",
+        )
+        .unwrap();
+        map.rewrite_toml(&mut table);
+        assert_eq!(table["target"]["path"].as_str(), Some("/dst/t"));
+        assert_eq!(table["target"]["args"][1].as_str(), Some("/dst/in.cfg"));
+        assert_eq!(table["afl_cfg"]["runners"].as_integer(), Some(4));
+    }
+
+    #[test]
+    fn test_path_map_invalid() {
+        assert!(PathMap::new(&["/local".to_string()]).is_err());
+        assert!(PathMap::new(&["=/remote".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_remote_script_and_attach_hint() {
+        let session = Session::<Tmux>::new(
+            "remote_test",
+            &["afl-fuzz -i /srv/in -o /srv/out -- /srv/target".to_string()],
+            std::path::Path::new("/tmp/remote_test.pids"),
+        )
+        .unwrap();
+        let script = session.create_standalone_script().unwrap();
+        assert!(script.starts_with("mkdir -p '/srv/in' '/srv/out'"));
+        assert!(script.contains("SESSION_NAME=\"remote_test\""));
+
+        let remote = RemoteHost::new("user@box");
+        assert_eq!(
+            remote.attach_hint::<Tmux>("remote_test"),
+            "ssh -t user@box tmux attach-session -t remote_test"
+        );
+    }
+}
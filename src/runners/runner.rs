@@ -1,16 +1,42 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::fs;
 use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, Signal, System};
 use tempfile::NamedTempFile;
 
-use crate::tui::{session::CampaignData, Tui};
+use crate::afl::startup::StartupFailures;
+use crate::cli::SessionLayout;
+use crate::commands::ExitCode;
+use crate::runners::{screen::Screen, tmux::Tmux};
+use crate::tui::{
+    calibration::{self, ProbeStats},
+    control::signal_pids,
+    data_collection::DataFetcher,
+    forensics::exit_status_dir,
+    format::NumberFormat,
+    progress,
+    session::CampaignData,
+    Tui, TuiOptions,
+};
+use crate::utils::cleanup::pid_file_session;
+use crate::utils::lock::CampaignLock;
 use crate::utils::system::{get_user_input, mkdir_helper};
 
+/// Time the fuzzers of a calibration probe get to shut down before the session is killed
+const PROBE_STOP_GRACE: Duration = Duration::from_secs(15);
+/// Time a restarted instance gets to shut down before it is killed
+const RESTART_STOP_GRACE: Duration = Duration::from_secs(15);
+/// Time the relaunched instance gets to report its PID
+const RESTART_PID_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Template files for different session managers
 pub mod templates {
     pub const TMUX: &str = include_str!("../templates/tmux.txt");
@@ -23,6 +49,8 @@ pub struct SessionCommand {
     raw: String,
     input_dir: PathBuf,
     output_dir: PathBuf,
+    /// Instance name passed via `-M`/`-S`
+    name: Option<String>,
 }
 
 impl SessionCommand {
@@ -46,10 +74,17 @@ impl SessionCommand {
             .map(PathBuf::from)
             .context("Failed to find output directory in command")?;
 
+        let name = parts
+            .iter()
+            .position(|&x| x == "-M" || x == "-S")
+            .and_then(|i| parts.get(i + 1))
+            .map(ToString::to_string);
+
         Ok(Self {
             raw: cmd.to_string(),
             input_dir,
             output_dir,
+            name,
         })
     }
 }
@@ -81,6 +116,95 @@ pub trait SessionManager: Sized {
     fn post_attach_setup(_session_name: &str) -> Result<()> {
         Ok(())
     }
+
+    /// Whether the session manager can arrange the instances in `layout`
+    fn supports_layout(layout: SessionLayout) -> bool {
+        layout == SessionLayout::WindowPerInstance
+    }
+
+    /// Command that types `line` into the window or pane of `instance` and submits it
+    ///
+    /// # Errors
+    /// * If the window or pane of `instance` cannot be found
+    fn build_send_command(session_name: &str, instance: &str, line: &str) -> Result<Command>;
+}
+
+/// Command line of `T` that attaches to `session_name`
+pub fn attach_command_line<T: SessionManager>(session_name: &str) -> String {
+    let attach = T::build_attach_command(session_name);
+    std::iter::once(attach.get_program())
+        .chain(attach.get_args())
+        .map(|a| a.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// PID files `run` wrote for the session, see `session_pid_file`
+pub fn session_pid_files(session_name: &str) -> Vec<PathBuf> {
+    fs::read_dir("/tmp")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .and_then(pid_file_session)
+                        == Some(session_name)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether a tmux or screen session named `session_name` exists
+pub fn session_exists(session_name: &str) -> bool {
+    fn has_session<T: SessionManager>(session_name: &str) -> bool {
+        T::build_session_check_command(session_name)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+    has_session::<Tmux>(session_name) || has_session::<Screen>(session_name)
+}
+
+/// Alive fuzzer PIDs recorded for the session
+pub fn alive_session_pids(session_name: &str) -> Vec<u32> {
+    let mut system = System::new_all();
+    system.refresh_all();
+
+    let mut pids: Vec<u32> = session_pid_files(session_name)
+        .iter()
+        .flat_map(|pid_file| DataFetcher::read_pid_file(pid_file))
+        .filter(|&pid| system.process(Pid::from(pid as usize)).is_some())
+        .collect();
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+/// Replaces `old` with `new` in the colon separated PIDs of a PID file, appends `new` if `old`
+/// is missing
+fn replace_pid(content: &str, old: Option<u32>, new: u32) -> String {
+    let mut pids: Vec<u32> = content
+        .trim()
+        .split(':')
+        .filter_map(|pid| pid.trim().parse().ok())
+        .filter(|&pid| Some(pid) != old)
+        .collect();
+    pids.push(new);
+    pids.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Instance name passed to `afl-fuzz` via `-M` or `-S` in `argv`
+fn instance_name_from_argv(argv: &[OsString]) -> Option<String> {
+    argv.windows(2)
+        .find(|pair| pair[0] == "-M" || pair[0] == "-S")
+        .map(|pair| pair[1].to_string_lossy().into_owned())
 }
 
 /// Base session implementation
@@ -90,6 +214,13 @@ pub struct Session<T: SessionManager> {
     commands: Vec<SessionCommand>,
     log_file: PathBuf,
     pid_file: PathBuf,
+    /// Directory the session script captures the output of instances that died on startup to
+    startup_log_dir: PathBuf,
+    layout: SessionLayout,
+    /// Keep the contents of the output directory, the instances resume from it
+    resume: bool,
+    /// Lock of the campaign, released once the instances are started
+    lock: RefCell<Option<CampaignLock>>,
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -108,16 +239,54 @@ impl<T: SessionManager> Session<T> {
         if log_file.exists() {
             fs::remove_file(&log_file)?;
         }
+        let startup_log_dir = PathBuf::from(format!("/tmp/.aflr_startup_{session_name}"));
+        if startup_log_dir.exists() {
+            fs::remove_dir_all(&startup_log_dir)?;
+        }
 
         Ok(Self {
             name: session_name.to_string(),
             commands,
             log_file,
             pid_file: pid_file.to_path_buf(),
+            startup_log_dir,
+            layout: SessionLayout::default(),
+            resume: false,
+            lock: RefCell::new(None),
             _phantom: std::marker::PhantomData,
         })
     }
 
+    /// Arrange the instances of the session in `layout`
+    ///
+    /// # Errors
+    /// * If the session manager does not support `layout`
+    pub fn with_layout(mut self, layout: SessionLayout) -> Result<Self> {
+        if !T::supports_layout(layout) {
+            bail!(
+                "The '{}' layout is not supported by {}",
+                layout.as_str(),
+                T::manager_name()
+            );
+        }
+        self.layout = layout;
+        Ok(self)
+    }
+
+    /// Launch into the existing output directory instead of offering to clean it
+    #[must_use]
+    pub fn with_resume(mut self) -> Self {
+        self.resume = true;
+        self
+    }
+
+    /// Hold `lock` until the instances are started, attaching or watching them does not need it
+    #[must_use]
+    pub fn with_lock(self, lock: Option<CampaignLock>) -> Self {
+        self.lock.replace(lock);
+        self
+    }
+
     pub fn is_present(&self) -> bool {
         T::build_session_check_command(&self.name)
             .output()
@@ -133,6 +302,170 @@ impl<T: SessionManager> Session<T> {
         Self::run_command(T::build_kill_command(&self.name))
     }
 
+    /// Interrupt the given fuzzer processes while leaving the session alive
+    ///
+    /// `AFL++` handles `SIGINT` like a Ctrl-C in its window and shuts down gracefully, so the
+    /// final output stays inspectable in the session. Returns the PIDs that were signaled.
+    pub fn signal_fuzzers(&self, pids: &[u32]) -> Vec<u32> {
+        let mut system = System::new_all();
+        system.refresh_all();
+        pids.iter()
+            .copied()
+            .filter(|&pid| {
+                system
+                    .process(Pid::from(pid as usize))
+                    .and_then(|process| process.kill_with(Signal::Interrupt))
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    /// Maps the instance names of the given fuzzer processes to their PIDs
+    pub fn instance_pids(&self, pids: &[u32]) -> BTreeMap<String, u32> {
+        let mut system = System::new_all();
+        system.refresh_all();
+        pids.iter()
+            .filter_map(|&pid| {
+                let process = system.process(Pid::from(pid as usize))?;
+                Some((instance_name_from_argv(process.cmd())?, pid))
+            })
+            .collect()
+    }
+
+    /// Sends `signal` to the fuzzers of the named `instances`, e.g. `SIGSTOP` to pause them
+    ///
+    /// Returns the instances that were signaled along with their PIDs.
+    ///
+    /// # Errors
+    /// * If an instance is not running in the session
+    pub fn signal_instances(
+        &self,
+        pids: &[u32],
+        instances: &[String],
+        signal: Signal,
+    ) -> Result<Vec<(String, u32)>> {
+        let running = self.instance_pids(pids);
+        let unknown: Vec<&str> = instances
+            .iter()
+            .filter(|name| !running.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            bail!(
+                "No running instance named {} in session '{}', running are: {}",
+                unknown.join(", "),
+                self.name,
+                running.keys().cloned().collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        let mut system = System::new_all();
+        system.refresh_all();
+        Ok(instances
+            .iter()
+            .filter_map(|name| {
+                let pid = running[name];
+                system
+                    .process(Pid::from(pid as usize))
+                    .and_then(|process| process.kill_with(signal))
+                    .unwrap_or(false)
+                    .then(|| (name.clone(), pid))
+            })
+            .collect())
+    }
+
+    /// Stops the fuzzer of `instance` and relaunches `command` in its window or pane
+    ///
+    /// The old fuzzer gets `SIGINT` and is killed if it does not exit within
+    /// `RESTART_STOP_GRACE`, e.g. when its target deadlocked. The session's PID files are
+    /// updated to the new PID, which is returned. The exit status is recorded below
+    /// `output_dir`, the instance's `-o` directory.
+    ///
+    /// # Errors
+    /// * If the window of `instance` cannot be found or the command cannot be sent
+    /// * If the relaunched fuzzer does not report a PID or dies right away
+    pub fn restart_instance(
+        &self,
+        instance: &str,
+        command: &str,
+        old_pid: Option<u32>,
+        output_dir: &Path,
+    ) -> Result<u32> {
+        if let Some(pid) = old_pid {
+            Self::stop_process(pid);
+        }
+
+        let pid_path = PathBuf::from(format!("/tmp/.aflr_restart_{}_{instance}.pid", self.name));
+        let _ = fs::remove_file(&pid_path);
+        // Same wrapper as the session scripts, so the exit status is recorded as well
+        let exit_dir = exit_status_dir(output_dir);
+        fs::create_dir_all(&exit_dir)
+            .with_context(|| format!("Failed to create {}", exit_dir.display()))?;
+        let line = format!(
+            " {{ {command} & echo $! > {}; clear; fg; echo $? > '{}'/$!; }}",
+            pid_path.display(),
+            exit_dir.display()
+        );
+        let status = T::build_send_command(&self.name, instance, &line)?
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .with_context(|| format!("Failed to execute {}", T::manager_name()))?;
+        if !status.success() {
+            bail!("Failed to send the command to the window of {instance}");
+        }
+
+        let deadline = Instant::now() + RESTART_PID_TIMEOUT;
+        let new_pid = loop {
+            if let Some(pid) = fs::read_to_string(&pid_path)
+                .ok()
+                .and_then(|content| content.trim().parse::<u32>().ok())
+            {
+                let _ = fs::remove_file(&pid_path);
+                break pid;
+            }
+            if Instant::now() >= deadline {
+                bail!("{instance} did not report its PID after the restart, check its window");
+            }
+            thread::sleep(Duration::from_millis(100));
+        };
+
+        // AFL++ bails out within a moment on a bad output directory or a broken target
+        thread::sleep(Duration::from_secs(1));
+        let mut system = System::new_all();
+        system.refresh_all();
+        if system.process(Pid::from(new_pid as usize)).is_none() {
+            bail!("{instance} died right after the restart, check its window");
+        }
+
+        for pid_file in session_pid_files(&self.name) {
+            let content = fs::read_to_string(&pid_file).unwrap_or_default();
+            fs::write(&pid_file, replace_pid(&content, old_pid, new_pid))
+                .with_context(|| format!("Failed to update {}", pid_file.display()))?;
+        }
+        Ok(new_pid)
+    }
+
+    /// Interrupts `pid` and kills it if it is still alive after `RESTART_STOP_GRACE`
+    fn stop_process(pid: u32) {
+        let is_alive = || {
+            let mut system = System::new_all();
+            system.refresh_all();
+            system.process(Pid::from(pid as usize)).is_some()
+        };
+        // A paused fuzzer only handles the interrupt once it continues
+        signal_pids(&[pid], Signal::Continue);
+        signal_pids(&[pid], Signal::Interrupt);
+        let deadline = Instant::now() + RESTART_STOP_GRACE;
+        while is_alive() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(200));
+        }
+        if is_alive() {
+            signal_pids(&[pid], Signal::Kill);
+            thread::sleep(Duration::from_millis(500));
+        }
+    }
+
     /// Attach to the session
     ///
     /// # Errors
@@ -142,6 +475,17 @@ impl<T: SessionManager> Session<T> {
         Self::run_command(T::build_attach_command(&self.name))
     }
 
+    /// Command line that attaches to the session
+    pub fn attach_hint(&self) -> String {
+        attach_command_line::<T>(&self.name)
+    }
+
+    /// Input and output directories shared by all session commands
+    pub fn directories(&self) -> (&Path, &Path) {
+        let first_cmd = &self.commands[0];
+        (&first_cmd.input_dir, &first_cmd.output_dir)
+    }
+
     /// Create a bash script to run the session
     ///
     /// # Errors
@@ -159,13 +503,42 @@ impl<T: SessionManager> Session<T> {
             .render(upon::value! {
                 session_name: self.name.clone(),
                 commands: self.commands.iter().map(|c| c.raw.clone()).collect::<Vec<_>>(),
+                num_commands: self.commands.len(),
+                window_names: self.window_names(),
+                layout: self.layout.as_str(),
                 log_file: self.log_file.to_str().unwrap().to_string(),
                 pid_file: self.pid_file.to_str().unwrap().to_string(),
+                startup_log_dir: self.startup_log_dir.to_str().unwrap().to_string(),
+                exit_dir: exit_status_dir(self.directories().1).to_string_lossy().into_owned(),
             })
             .to_string()
             .context("Failed to create bash script")
     }
 
+    /// Window names of the session, the instance name or `window-<idx>` if there is none
+    fn window_names(&self) -> Vec<String> {
+        self.commands
+            .iter()
+            .enumerate()
+            .map(|(i, c)| c.name.clone().unwrap_or_else(|| format!("window-{i}")))
+            .collect()
+    }
+
+    /// Session script prefixed with the directory setup that `run` would otherwise do locally
+    ///
+    /// # Errors
+    /// * If the session script could not be created
+    pub fn create_standalone_script(&self) -> Result<String> {
+        let (input_dir, output_dir) = self.directories();
+        let (input_dir, output_dir) = (input_dir.display(), output_dir.display());
+        let script = self.create_bash_script()?;
+        Ok(format!(
+            "mkdir -p '{input_dir}' '{output_dir}' || exit 1\n\
+             if [ -z \"$(ls -A '{input_dir}')\" ]; then echo fuzz > '{input_dir}/1'; fi\n\
+             {script}"
+        ))
+    }
+
     fn run_command(mut cmd: Command) -> Result<()> {
         cmd.stdout(Stdio::null()).stderr(Stdio::null());
         let mut child = cmd.spawn()?;
@@ -177,12 +550,14 @@ impl<T: SessionManager> Session<T> {
     ///
     /// # Errors
     /// * If the session script could not be created
-    /// * If the session could not be started
+    /// * If the session could not be started, a `StartupFailures` if instances died on startup
     pub fn run(&self) -> Result<()> {
         self.setup_directories()?;
         self.confirm_start()?;
         Self::check_manager_installation()?;
-        self.execute_session_script().map_err(|e| anyhow!("{}", e))
+        self.execute_session_script()?;
+        self.lock.take();
+        Ok(())
     }
 
     fn setup_directories(&self) -> Result<()> {
@@ -194,7 +569,7 @@ impl<T: SessionManager> Session<T> {
         if first_cmd.input_dir.read_dir()?.next().is_none() {
             fs::write(first_cmd.input_dir.join("1"), "fuzz")?;
         }
-        mkdir_helper(&first_cmd.output_dir, true)?;
+        mkdir_helper(&first_cmd.output_dir, !self.resume)?;
 
         Ok(())
     }
@@ -244,6 +619,10 @@ impl<T: SessionManager> Session<T> {
             .output()?;
 
         if !output.status.success() {
+            let failures = StartupFailures::collect(&self.startup_log_dir, self.commands.len());
+            if !failures.is_empty() {
+                return Err(failures.into());
+            }
             let stderr = String::from_utf8_lossy(&output.stderr);
             let stdout = String::from_utf8_lossy(&output.stdout);
             // Get the actual exit code
@@ -266,14 +645,72 @@ impl<T: SessionManager> Session<T> {
     ///
     /// # Errors
     /// * If the session could not be started
-    pub fn run_with_tui(&self, out_dir: &Path) -> Result<()> {
-        let mut cdata = CampaignData::new();
+    pub fn run_with_tui(&self, out_dir: &Path, options: &TuiOptions) -> Result<()> {
         self.run()?;
 
         thread::sleep(Duration::from_secs(1));
-        Tui::run(out_dir, Some(&self.pid_file), &mut cdata)?;
+        Tui::run(
+            &[out_dir.to_path_buf()],
+            Some(&self.pid_file),
+            options,
+            CampaignData::new(),
+        )?;
         Ok(())
     }
+
+    /// Run the session while printing a single progress line
+    ///
+    /// A Ctrl-C stops the fuzzers gracefully and kills the session afterwards, fuzzers that
+    /// exit on their own leave the session behind for inspection.
+    ///
+    /// # Errors
+    /// * If the session could not be started
+    /// * If the progress display could not be set up
+    pub fn run_with_progress(&self, out_dir: &Path) -> Result<ExitCode> {
+        self.run()?;
+
+        thread::sleep(Duration::from_secs(1));
+        let summary = progress::run(out_dir, Some(&self.pid_file))?;
+        println!(
+            "[+] Campaign summary: {}",
+            progress::format_progress_line(&summary.data, NumberFormat::default())
+        );
+        if summary.interrupted {
+            self.kill_session()?;
+            println!("[+] Session '{}' stopped", self.name);
+        } else {
+            println!(
+                "[*] All fuzzers exited. Inspect the session with: {}",
+                self.attach_hint()
+            );
+        }
+        Ok(ExitCode::from_campaign(out_dir, summary.interrupted))
+    }
+
+    /// Runs the session as a calibration probe for `duration` and tears it down afterwards
+    ///
+    /// # Errors
+    /// * If the session could not be started or killed
+    /// * If the probe could not be set up
+    pub fn run_calibration(&self, out_dir: &Path, duration: Duration) -> Result<ProbeStats> {
+        self.run()?;
+
+        thread::sleep(Duration::from_secs(1));
+        let stats = calibration::run(out_dir, Some(&self.pid_file), duration);
+        self.stop_probe(out_dir)?;
+        stats
+    }
+
+    /// Stops the fuzzers gracefully so they stop writing to `out_dir`, then kills the session
+    fn stop_probe(&self, out_dir: &Path) -> Result<()> {
+        let signaled = self.signal_fuzzers(&DataFetcher::read_pid_file(&self.pid_file));
+        println!("[*] Stopping {} probe fuzzers", signaled.len());
+        let deadline = Instant::now() + PROBE_STOP_GRACE;
+        while !DataFetcher::alive_pids(out_dir).is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(200));
+        }
+        self.kill_session()
+    }
 }
 
 #[cfg(test)]
@@ -286,5 +723,163 @@ mod tests {
         let parsed = SessionCommand::new(cmd).unwrap();
         assert_eq!(parsed.input_dir, PathBuf::from("/tmp/input"));
         assert_eq!(parsed.output_dir, PathBuf::from("/tmp/output"));
+        assert!(parsed.name.is_none());
+
+        let parsed = SessionCommand::new("afl-fuzz -i in -o out -S s1_target -- ./target").unwrap();
+        assert_eq!(parsed.name.as_deref(), Some("s1_target"));
+    }
+
+    #[test]
+    fn test_replace_pid() {
+        assert_eq!(replace_pid("10:11:12\n", Some(11), 20), "10:12:20");
+        assert_eq!(replace_pid("10:12", Some(11), 20), "10:12:20");
+        assert_eq!(replace_pid("10", None, 20), "10:20");
+        assert_eq!(replace_pid("", Some(11), 20), "20");
+    }
+
+    #[test]
+    fn test_instance_name_from_argv() {
+        let argv = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(
+            instance_name_from_argv(&argv(&["afl-fuzz", "-i", "in", "-M", "m_t", "--", "./t"])),
+            Some("m_t".to_string())
+        );
+        assert_eq!(
+            instance_name_from_argv(&argv(&["afl-fuzz", "-S", "s0_t", "--", "./t"])),
+            Some("s0_t".to_string())
+        );
+        assert_eq!(instance_name_from_argv(&argv(&["afl-fuzz", "-M"])), None);
+        assert_eq!(instance_name_from_argv(&argv(&["./t"])), None);
+    }
+
+    /// Runs the tmux script for `layout` against a fake `tmux` and returns the window/pane calls
+    fn mock_tmux_layout_calls(layout: SessionLayout) -> Vec<String> {
+        use crate::runners::tmux::TmuxSession;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let log = tmp.path().join("calls.log");
+        let mock = tmp.path().join("tmux");
+        fs::write(
+            &mock,
+            "#!/bin/sh\n\
+             echo \"$*\" >> \"$AFLR_MOCK_LOG\"\n\
+             case \"$1\" in\n\
+               has-session) exit 1 ;;\n\
+               capture-pane) cat \"$AFLR_MOCK_LOG\" ;;\n\
+               display-message|split-window) echo %1 ;;\n\
+             esac\n",
+        )
+        .unwrap();
+        fs::set_permissions(&mock, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let cmds: Vec<String> = ["-M m_target", "-S s0_target", "-S s1_target"]
+            .iter()
+            .map(|role| format!("afl-fuzz -i /tmp/in -o /tmp/out {role} -- /bin/target"))
+            .collect();
+        let script = TmuxSession::new("aflr_layout_test", &cmds, &tmp.path().join("pids"))
+            .unwrap()
+            .with_layout(layout)
+            .unwrap()
+            .create_bash_script()
+            .unwrap();
+
+        // The fuzzers never start, so the script bails once it looks for their PIDs
+        let path = format!(
+            "{}:{}",
+            tmp.path().display(),
+            std::env::var("PATH").unwrap()
+        );
+        Command::new("bash")
+            .args(["-c", &script])
+            .env("PATH", path)
+            .env("AFLR_MOCK_LOG", &log)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+
+        let layout_calls = [
+            "new-session",
+            "rename-window",
+            "new-window",
+            "split-window",
+            "select-layout",
+            "select-pane",
+        ];
+        fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .filter(|l| l.split_whitespace().any(|w| layout_calls.contains(&w)))
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_tmux_layouts() {
+        assert_eq!(
+            mock_tmux_layout_calls(SessionLayout::WindowPerInstance),
+            [
+                "-2u new-session -d -s aflr_layout_test",
+                "rename-window -t aflr_layout_test m_target",
+                "new-window -t aflr_layout_test -n s0_target",
+                "new-window -t aflr_layout_test -n s1_target",
+            ]
+        );
+
+        let pane_calls = |layout: &str| {
+            let mut calls = vec![
+                "-2u new-session -d -s aflr_layout_test".to_string(),
+                "rename-window -t aflr_layout_test aflr".to_string(),
+                "select-pane -t %1 -T m_target".to_string(),
+            ];
+            for name in ["s0_target", "s1_target"] {
+                calls.push("split-window -t aflr_layout_test:aflr -P -F #{pane_id}".to_string());
+                calls.push(format!("select-layout -t aflr_layout_test:aflr {layout}"));
+                calls.push(format!("select-pane -t %1 -T {name}"));
+            }
+            calls
+        };
+        assert_eq!(
+            mock_tmux_layout_calls(SessionLayout::Tiled),
+            pane_calls("tiled")
+        );
+        assert_eq!(
+            mock_tmux_layout_calls(SessionLayout::SingleWindow),
+            pane_calls("even-vertical")
+        );
+    }
+
+    #[test]
+    fn test_screen_only_supports_window_per_instance() {
+        use crate::runners::screen::ScreenSession;
+
+        let cmds = ["afl-fuzz -i /tmp/in -o /tmp/out -M m_target -- /bin/target".to_string()];
+        let session =
+            || ScreenSession::new("aflr_screen_test", &cmds, Path::new("/tmp/x")).unwrap();
+        assert!(session().with_layout(SessionLayout::Tiled).is_err());
+        let script = session()
+            .with_layout(SessionLayout::WindowPerInstance)
+            .unwrap()
+            .create_bash_script()
+            .unwrap();
+        assert!(script.contains("\"m_target\""));
+        assert!(script.contains("EXIT_DIR=\"/tmp/out/.aflr/exit\""));
+        assert!(script.contains("$FAILED of 1 processes failed"));
+    }
+
+    #[test]
+    fn test_signal_fuzzers() {
+        use crate::runners::tmux::TmuxSession;
+        use std::os::unix::process::ExitStatusExt;
+
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let session =
+            TmuxSession::new("aflr_signal_test", &[], Path::new("/tmp/aflr_signal_test")).unwrap();
+
+        assert_eq!(
+            session.signal_fuzzers(&[child.id(), u32::MAX]),
+            [child.id()]
+        );
+        assert_eq!(child.wait().unwrap().signal(), Some(2));
     }
 }
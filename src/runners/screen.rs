@@ -1,3 +1,4 @@
+use anyhow::Result;
 use std::process::Command;
 
 use crate::runners::runner::{templates, Session, SessionManager};
@@ -35,6 +36,21 @@ impl SessionManager for Screen {
         cmd.args(["-r", session_name]);
         cmd
     }
+
+    fn build_send_command(session_name: &str, instance: &str, line: &str) -> Result<Command> {
+        // Windows are titled after their instance, which `-p` selects by
+        let mut cmd = Command::new(Self::manager_name());
+        cmd.args([
+            "-S",
+            session_name,
+            "-p",
+            instance,
+            "-X",
+            "stuff",
+            &format!("{line}\n"),
+        ]);
+        Ok(cmd)
+    }
 }
 
 /// Type alias for a Screen session
@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use std::process::Command;
 
+use crate::cli::SessionLayout;
 use crate::runners::runner::{templates, Session, SessionManager};
 
 /// Tmux session manager implementation
@@ -38,23 +39,86 @@ impl SessionManager for Tmux {
     }
 
     fn post_attach_setup(session_name: &str) -> Result<()> {
+        // Enumerate the panes of all windows so every layout is covered
         let output = Command::new(Self::manager_name())
-            .args(["list-windows", "-t", session_name])
+            .args([
+                "list-panes",
+                "-s",
+                "-t",
+                session_name,
+                "-F",
+                "#{window_index}",
+            ])
             .output()?;
 
         if !output.status.success() {
             anyhow::bail!("Failed to list tmux windows");
         }
 
-        let output_str = String::from_utf8(output.stdout)?;
-        let first_window = output_str.chars().next().context("No windows found")?;
-
-        if first_window != '0' && first_window != '1' {
-            anyhow::bail!("Invalid window ID: {}", first_window);
+        let first_window =
+            first_window_index(&String::from_utf8(output.stdout)?).context("No windows found")?;
+
+        // Land on a window that exists regardless of the configured `base-index`
+        let status = Command::new(Self::manager_name())
+            .args([
+                "select-window",
+                "-t",
+                &format!("{session_name}:{first_window}"),
+            ])
+            .status()?;
+        if !status.success() {
+            anyhow::bail!("Failed to select tmux window {first_window}");
         }
 
         Ok(())
     }
+
+    fn supports_layout(_layout: SessionLayout) -> bool {
+        true
+    }
+
+    fn build_send_command(session_name: &str, instance: &str, line: &str) -> Result<Command> {
+        let output = Command::new(Self::manager_name())
+            .args([
+                "list-panes",
+                "-s",
+                "-t",
+                session_name,
+                "-F",
+                "#{pane_id}\t#{window_name}\t#{pane_title}",
+            ])
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!("Failed to list tmux panes");
+        }
+        let pane = instance_pane(&String::from_utf8(output.stdout)?, instance)
+            .with_context(|| format!("No tmux window or pane named {instance}"))?;
+
+        let mut cmd = Command::new(Self::manager_name());
+        cmd.args(["send-keys", "-t", &pane, line, "C-m"]);
+        Ok(cmd)
+    }
+}
+
+/// Pane of `instance` in the output of `list-panes -F '#{pane_id}\t#{window_name}\t#{pane_title}'`
+///
+/// Instances get a window of their own or, in the pane layouts, a pane titled after them.
+fn instance_pane(list_output: &str, instance: &str) -> Option<String> {
+    list_output.lines().find_map(|line| {
+        let mut fields = line.split('\t');
+        let pane_id = fields.next()?;
+        fields
+            .any(|name| name == instance)
+            .then(|| pane_id.to_string())
+    })
+}
+
+/// Lowest window index in the output of `list-panes -F '#{window_index}'`
+fn first_window_index(list_output: &str) -> Option<u32> {
+    list_output
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .min()
 }
 
 /// Type alias for a Tmux session
@@ -81,4 +145,20 @@ mod tests {
             vec!["kill-session", "-t", "test_session"]
         );
     }
+
+    #[test]
+    fn test_instance_pane() {
+        let windows = "%0\tm_target\thost\n%1\ts0_target\thost\n";
+        assert_eq!(instance_pane(windows, "s0_target"), Some("%1".to_string()));
+        let panes = "%0\taflr\tm_target\n%3\taflr\ts0_target\n";
+        assert_eq!(instance_pane(panes, "s0_target"), Some("%3".to_string()));
+        assert_eq!(instance_pane(panes, "s1_target"), None);
+    }
+
+    #[test]
+    fn test_first_window_index() {
+        assert_eq!(first_window_index("1\n1\n2\n"), Some(1));
+        assert_eq!(first_window_index("3\n0\n"), Some(0));
+        assert_eq!(first_window_index(""), None);
+    }
 }
@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+use crate::tui::session::{CampaignData, CrashInfoDetails};
+use crate::utils::build_id::fnv1a;
+
+/// Stable pseudonym for an identifying string
+///
+/// The same input always maps to the same pseudonym, across runs and hosts, so anonymized
+/// exports can still be correlated over time.
+pub fn pseudonym(value: &str) -> String {
+    format!("anon_{:016x}", fnv1a(value.as_bytes()))
+}
+
+/// Replaces the directory of `path` with its pseudonym, keeping the file name
+///
+/// Solution file names only carry AFL++ metadata like the id and the signal.
+fn anonymize_path(path: &Path) -> PathBuf {
+    let parent = path
+        .parent()
+        .map(|parent| pseudonym(&parent.to_string_lossy()))
+        .unwrap_or_default();
+    path.file_name().map_or_else(
+        || PathBuf::from(&parent),
+        |name| Path::new(&parent).join(name),
+    )
+}
+
+fn anonymize_solutions(solutions: &mut [CrashInfoDetails]) {
+    for solution in solutions {
+        solution.fuzzer_name = pseudonym(&solution.fuzzer_name);
+        solution.file_path = anonymize_path(&solution.file_path);
+    }
+}
+
+/// Copy of `data` with instance names, the banner and solution paths replaced by pseudonyms
+///
+/// Applied when serializing exports only, the TUI keeps showing the real names.
+pub fn anonymize(data: &CampaignData) -> CampaignData {
+    let mut data = data.clone();
+    for instance in &mut data.instances {
+        instance.name = pseudonym(&instance.name);
+    }
+    anonymize_solutions(&mut data.last_crashes);
+    anonymize_solutions(&mut data.last_hangs);
+    if let Some(starved) = &mut data.starved_main {
+        starved.instance = pseudonym(&starved.instance);
+    }
+    if !data.misc.afl_banner.is_empty() {
+        data.misc.afl_banner = pseudonym(&data.misc.afl_banner);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::session::InstanceMetrics;
+
+    #[test]
+    fn test_anonymize() {
+        let mut cdata = CampaignData::new();
+        cdata.misc.afl_banner = "secret_target".to_string();
+        cdata.instances.push(InstanceMetrics {
+            name: "main_secret".to_string(),
+            ..InstanceMetrics::default()
+        });
+        cdata.last_crashes.push(CrashInfoDetails {
+            fuzzer_name: "main_secret".to_string(),
+            file_path: PathBuf::from("/work/secret/out/main_secret/crashes/id:000000,sig:11"),
+            ..CrashInfoDetails::default()
+        });
+
+        let anon = anonymize(&cdata);
+        let json = serde_json::to_string(&anon).unwrap();
+        assert!(!json.contains("secret"), "{json}");
+
+        // Stable and consistent between instances and solutions
+        assert_eq!(anon.instances[0].name, pseudonym("main_secret"));
+        assert_eq!(anon.last_crashes[0].fuzzer_name, anon.instances[0].name);
+        assert_eq!(anonymize(&cdata).misc.afl_banner, anon.misc.afl_banner);
+        assert_eq!(
+            anon.last_crashes[0].file_path.file_name().unwrap(),
+            "id:000000,sig:11"
+        );
+
+        // The source data is untouched
+        assert_eq!(cdata.instances[0].name, "main_secret");
+    }
+}
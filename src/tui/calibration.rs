@@ -0,0 +1,307 @@
+use std::{
+    fmt,
+    io::{self, IsTerminal, Write},
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+
+use crate::tui::data_collection::DataFetcher;
+use crate::tui::format::NumberFormat;
+use crate::tui::progress::{format_progress_line, PROGRESS_INTERVAL};
+use crate::tui::session::CampaignData;
+use crate::tui::Tui;
+
+/// Executions per second and instance below which fuzzing gets ineffective
+pub const SLOW_EXECS_PER_SEC: f64 = 100.0;
+/// Stability below which AFL++ attributes coverage to the wrong inputs
+pub const LOW_STABILITY: f64 = 90.0;
+/// Horizons the corpus growth is projected to
+const HORIZONS: [(&str, Duration); 2] = [
+    ("1 day", Duration::from_secs(86_400)),
+    ("1 week", Duration::from_secs(7 * 86_400)),
+];
+
+/// Set by a Ctrl-C to end the probe early
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// What a calibration probe observed at its end
+#[derive(Debug, Clone, Default)]
+pub struct ProbeStats {
+    /// Wall time the probe ran for
+    pub elapsed: Duration,
+    pub instances_started: usize,
+    pub instances_alive: usize,
+    /// Execution speed AFL++ reports for every alive instance, averaged over its run time
+    pub execs_per_sec: Vec<f64>,
+    /// Lowest stability of all alive instances in percent
+    pub stability: f64,
+    /// Peak resident memory of every alive instance in MB
+    pub peak_rss_mb: Vec<u64>,
+    /// Corpus size once the first instance reported, i.e. the imported seeds
+    pub corpus_start: usize,
+    pub corpus_end: usize,
+}
+
+impl ProbeStats {
+    fn from_data(data: &CampaignData, elapsed: Duration, corpus_start: usize) -> Self {
+        Self {
+            elapsed,
+            instances_started: data.fuzzers_started,
+            instances_alive: data.fuzzers_alive.len(),
+            execs_per_sec: data.instances.iter().map(|i| i.execs_per_sec).collect(),
+            stability: data.stability.min,
+            peak_rss_mb: data.instances.iter().map(|i| i.peak_rss_mb).collect(),
+            corpus_start,
+            corpus_end: data.corpus.cum,
+        }
+    }
+}
+
+/// Throughput estimate of a campaign extrapolated from a probe
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationEstimate {
+    pub execs_per_sec_per_instance: f64,
+    pub execs_per_hour: f64,
+    pub stability: f64,
+    /// Average peak resident memory per instance in MB
+    pub rss_mb_per_instance: f64,
+    pub finds_per_hour: f64,
+    /// Projected corpus size at every horizon in `HORIZONS`
+    pub projected_corpus: Vec<(&'static str, usize)>,
+    pub warnings: Vec<String>,
+}
+
+/// Extrapolates the throughput and corpus growth of the campaign `stats` were probed from
+#[allow(clippy::cast_precision_loss)]
+pub fn estimate(stats: &ProbeStats) -> CalibrationEstimate {
+    let total_execs_per_sec: f64 = stats.execs_per_sec.iter().sum();
+    let execs_per_sec_per_instance = if stats.execs_per_sec.is_empty() {
+        0.0
+    } else {
+        total_execs_per_sec / stats.execs_per_sec.len() as f64
+    };
+    let rss_mb_per_instance = if stats.peak_rss_mb.is_empty() {
+        0.0
+    } else {
+        stats.peak_rss_mb.iter().sum::<u64>() as f64 / stats.peak_rss_mb.len() as f64
+    };
+    let finds = stats.corpus_end.saturating_sub(stats.corpus_start);
+    let hours = stats.elapsed.as_secs_f64() / 3600.0;
+    let finds_per_hour = if hours > 0.0 {
+        finds as f64 / hours
+    } else {
+        0.0
+    };
+
+    let mut warnings = Vec::new();
+    let dead = stats
+        .instances_started
+        .saturating_sub(stats.instances_alive);
+    if dead > 0 {
+        warnings.push(format!(
+            "{dead} of {} instances died during the probe",
+            stats.instances_started
+        ));
+    }
+    if !stats.execs_per_sec.is_empty() && execs_per_sec_per_instance < SLOW_EXECS_PER_SEC {
+        warnings.push(format!(
+            "Slow target: {execs_per_sec_per_instance:.0} execs/s per instance, consider persistent mode or smaller inputs"
+        ));
+    }
+    if stats.stability > 0.0 && stats.stability < LOW_STABILITY {
+        warnings.push(format!(
+            "Low stability: {:.2}%, check the target for nondeterminism",
+            stats.stability
+        ));
+    }
+    if stats.execs_per_sec.is_empty() {
+        warnings.push(
+            "No instance reported stats, the probe was too short to estimate anything".to_string(),
+        );
+    }
+
+    CalibrationEstimate {
+        execs_per_sec_per_instance,
+        execs_per_hour: total_execs_per_sec * 3600.0,
+        stability: stats.stability,
+        rss_mb_per_instance,
+        finds_per_hour,
+        projected_corpus: HORIZONS
+            .iter()
+            .map(|&(label, horizon)| {
+                (
+                    label,
+                    stats.corpus_end + project_finds(finds, stats.elapsed, horizon),
+                )
+            })
+            .collect(),
+        warnings,
+    }
+}
+
+/// New corpus entries expected after `horizon` given `finds` during a probe of `elapsed`
+///
+/// Finds dry up as coverage saturates, so the growth is modeled as logarithmic in time,
+/// `n(t) = finds * ln(1 + t / elapsed) / ln(2)`, which matches the probe at `t = elapsed`
+/// instead of extrapolating the early find rate linearly.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+pub fn project_finds(finds: usize, elapsed: Duration, horizon: Duration) -> usize {
+    if elapsed.is_zero() || horizon <= elapsed {
+        return finds;
+    }
+    let growth = (horizon.as_secs_f64() / elapsed.as_secs_f64()).ln_1p() / 2f64.ln();
+    (finds as f64 * growth).round() as usize
+}
+
+impl fmt::Display for CalibrationEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt = NumberFormat::default();
+        writeln!(f, "[+] Calibration estimate:")?;
+        writeln!(
+            f,
+            "    Execs/s per instance : {}",
+            fmt.format_float(self.execs_per_sec_per_instance)
+        )?;
+        writeln!(
+            f,
+            "    Execs per hour       : {}",
+            fmt.format_float(self.execs_per_hour)
+        )?;
+        writeln!(f, "    Stability            : {:.2}%", self.stability)?;
+        writeln!(
+            f,
+            "    Memory per instance  : {:.0} MB",
+            self.rss_mb_per_instance
+        )?;
+        writeln!(
+            f,
+            "    Finds per hour       : {}",
+            fmt.format_float(self.finds_per_hour)
+        )?;
+        for (label, corpus) in &self.projected_corpus {
+            writeln!(f, "    Corpus after {label:<7} : ~{corpus}")?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "[!] Warning: {warning}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Follows the campaign in `output_dir` for `duration` and returns what it observed
+///
+/// The probe ends early if all fuzzers exit or on a Ctrl-C, the caller tears the campaign down.
+///
+/// # Errors
+/// * If the Ctrl-C handler cannot be installed
+pub fn run(output_dir: &Path, pid_file: Option<&Path>, duration: Duration) -> Result<ProbeStats> {
+    ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst))
+        .context("Failed to install the Ctrl-C handler")?;
+
+    let tty = io::stdout().is_terminal();
+    let mut fetcher = DataFetcher::new(output_dir, pid_file, CampaignData::new());
+    let start = Instant::now();
+    let mut corpus_start = None;
+    let mut last_line: Option<Instant> = None;
+    loop {
+        let data = fetcher.collect_session_data();
+        if corpus_start.is_none() && !data.instances.is_empty() {
+            corpus_start = Some(data.corpus.cum);
+        }
+        let finished = data.fuzzers_started > 0 && data.fuzzers_alive.is_empty();
+        if finished || start.elapsed() >= duration || INTERRUPTED.load(Ordering::SeqCst) {
+            break;
+        }
+
+        if last_line.is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL) {
+            let line = format!(
+                "[probe, {} left] {}",
+                Tui::format_duration(&duration.saturating_sub(start.elapsed())),
+                format_progress_line(data, NumberFormat::default())
+            );
+            if tty {
+                print!("\r\x1b[2K{line}");
+            } else {
+                println!("{line}");
+            }
+            let _ = io::stdout().flush();
+            last_line = Some(Instant::now());
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    if tty {
+        println!();
+    }
+    let data = &fetcher.campaign_data;
+    Ok(ProbeStats::from_data(
+        data,
+        start.elapsed(),
+        corpus_start.unwrap_or(data.corpus.cum),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe() -> ProbeStats {
+        ProbeStats {
+            elapsed: Duration::from_secs(600),
+            instances_started: 4,
+            instances_alive: 4,
+            execs_per_sec: vec![1000.0, 1200.0, 800.0, 1000.0],
+            stability: 99.5,
+            peak_rss_mb: vec![40, 60, 50, 50],
+            corpus_start: 100,
+            corpus_end: 160,
+        }
+    }
+
+    #[test]
+    fn test_project_finds() {
+        let probe = Duration::from_secs(600);
+        assert_eq!(project_finds(60, probe, probe), 60);
+        // Three times the probe doubles the finds of the probe: ln(4) / ln(2) = 2
+        assert_eq!(project_finds(60, probe, probe * 3), 120);
+        assert_eq!(project_finds(0, probe, probe * 100), 0);
+        assert_eq!(project_finds(60, Duration::ZERO, probe), 60);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let estimate = estimate(&probe());
+        assert!((estimate.execs_per_sec_per_instance - 1000.0).abs() < f64::EPSILON);
+        assert!((estimate.execs_per_hour - 14_400_000.0).abs() < f64::EPSILON);
+        assert!((estimate.rss_mb_per_instance - 50.0).abs() < f64::EPSILON);
+        assert!((estimate.finds_per_hour - 360.0).abs() < f64::EPSILON);
+        assert_eq!(estimate.projected_corpus[0].0, "1 day");
+        // ln(1 + 144) / ln(2) ~ 7.18 times the 60 finds of the probe
+        assert_eq!(estimate.projected_corpus[0].1, 160 + 431);
+        assert!(estimate.projected_corpus[1].1 > estimate.projected_corpus[0].1);
+        assert!(estimate.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_estimate_warnings() {
+        let stats = ProbeStats {
+            instances_alive: 3,
+            execs_per_sec: vec![20.0, 40.0, 30.0],
+            stability: 75.0,
+            ..probe()
+        };
+        let estimate = estimate(&stats);
+        assert_eq!(estimate.warnings.len(), 3);
+        assert!(estimate.warnings[0].starts_with("1 of 4 instances died"));
+        assert!(estimate.warnings[1].starts_with("Slow target: 30 execs/s"));
+        assert!(estimate.warnings[2].starts_with("Low stability: 75.00%"));
+        assert!(estimate.to_string().contains("[!] Warning: Low stability"));
+    }
+}
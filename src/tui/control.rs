@@ -0,0 +1,278 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::{Arc, Weak},
+    thread,
+};
+
+use anyhow::{bail, Context, Result};
+use sysinfo::{Pid, Signal, System};
+
+use crate::tui::{anonymize::anonymize, session::SharedCampaignData};
+
+/// Directory inside the campaign output that holds AFLR runtime files
+pub const RUNTIME_DIR: &str = ".aflr";
+/// File name of the control socket inside `RUNTIME_DIR`
+pub const SOCKET_NAME: &str = "control.sock";
+
+/// Path of the control socket for the campaign in `output_dir`
+pub fn socket_path(output_dir: &Path) -> PathBuf {
+    output_dir.join(RUNTIME_DIR).join(SOCKET_NAME)
+}
+
+/// A request understood by the control socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Request {
+    Status,
+    Plan,
+    Stop,
+    Pause,
+    Resume,
+}
+
+impl Request {
+    fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [verb, what] if verb.eq_ignore_ascii_case("GET") => {
+                match what.to_uppercase().as_str() {
+                    "STATUS" => Ok(Self::Status),
+                    "PLAN" => Ok(Self::Plan),
+                    _ => Err(format!(
+                        "unknown resource '{what}', expected STATUS or PLAN"
+                    )),
+                }
+            }
+            [cmd] if cmd.eq_ignore_ascii_case("STOP") => Ok(Self::Stop),
+            [cmd] if cmd.eq_ignore_ascii_case("PAUSE") => Ok(Self::Pause),
+            [cmd] if cmd.eq_ignore_ascii_case("RESUME") => Ok(Self::Resume),
+            [] => Err("empty command".to_string()),
+            _ => Err(format!(
+                "unknown command '{}', expected GET STATUS, GET PLAN, STOP, PAUSE or RESUME",
+                line.trim()
+            )),
+        }
+    }
+}
+
+/// Serves the campaign data over a UNIX socket with a line based protocol
+///
+/// Every request line is answered with exactly one line, either `OK <payload>` or
+/// `ERR <message>`:
+/// * `GET STATUS` - the latest campaign data as JSON, anonymized if requested
+/// * `GET PLAN` - the generated fuzzer commands as a JSON array, withheld when anonymized
+/// * `STOP` - interrupts all fuzzers so they shut down gracefully
+/// * `PAUSE` / `RESUME` - suspends or continues all fuzzers
+///
+/// The socket file is removed again when the server is dropped.
+pub struct ControlServer {
+    path: PathBuf,
+}
+
+/// State shared by all client connections
+struct ControlState {
+    shared: Weak<SharedCampaignData>,
+    plan: Vec<String>,
+    anonymize: bool,
+}
+
+impl ControlServer {
+    /// Binds the control socket of `output_dir` and serves it on a background thread
+    ///
+    /// Only a weak reference to `shared` is kept, so the server never keeps a finished campaign
+    /// alive. A stale socket left behind by a crashed run is replaced.
+    ///
+    /// # Errors
+    /// * If another process is already serving the socket
+    /// * If the socket cannot be created
+    pub fn spawn(
+        output_dir: &Path,
+        shared: &Arc<SharedCampaignData>,
+        plan: Vec<String>,
+        anonymize: bool,
+    ) -> Result<Self> {
+        let path = socket_path(output_dir);
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                bail!(
+                    "Control socket {} is already served by another process",
+                    path.display()
+                );
+            }
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("Failed to bind control socket {}", path.display()))?;
+
+        let ctx = Arc::new(ControlState {
+            shared: Arc::downgrade(shared),
+            plan,
+            anonymize,
+        });
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let ctx = Arc::clone(&ctx);
+                thread::spawn(move || {
+                    let _ = Self::serve_client(stream, &ctx);
+                });
+            }
+        });
+
+        Ok(Self { path })
+    }
+
+    /// Path of the bound socket
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn serve_client(stream: UnixStream, ctx: &ControlState) -> std::io::Result<()> {
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let response = Request::parse(&line?).and_then(|request| Self::handle(request, ctx));
+            let line = match response {
+                Ok(payload) => format!("OK {payload}"),
+                Err(e) => format!("ERR {e}"),
+            };
+            writeln!(writer, "{line}")?;
+        }
+        Ok(())
+    }
+
+    fn handle(request: Request, ctx: &ControlState) -> Result<String, String> {
+        let shared = ctx
+            .shared
+            .upgrade()
+            .ok_or_else(|| "campaign monitoring has ended".to_string())?;
+
+        let signal = match request {
+            Request::Status => {
+                let data = shared.lock();
+                let json = if ctx.anonymize {
+                    serde_json::to_string(&anonymize(&data))
+                } else {
+                    serde_json::to_string(&*data)
+                };
+                return json.map_err(|e| e.to_string());
+            }
+            Request::Plan => {
+                // The commands carry the target and output paths
+                if ctx.anonymize {
+                    return Err("the plan is withheld while exports are anonymized".to_string());
+                }
+                if ctx.plan.is_empty() {
+                    return Err("no generated commands known for this campaign".to_string());
+                }
+                return serde_json::to_string(&ctx.plan).map_err(|e| e.to_string());
+            }
+            Request::Stop => Signal::Interrupt,
+            Request::Pause => Signal::Stop,
+            Request::Resume => Signal::Continue,
+        };
+
+        let pids = shared.lock().fuzzer_pids.clone();
+        if pids.is_empty() {
+            return Err("no fuzzer processes known yet".to_string());
+        }
+        let signaled = signal_pids(&pids, signal);
+        Ok(format!(
+            "signaled {}/{} fuzzers",
+            signaled.len(),
+            pids.len()
+        ))
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Sends `signal` to every live process in `pids`, returns the PIDs that were signaled
+pub(crate) fn signal_pids(pids: &[u32], signal: Signal) -> Vec<u32> {
+    let mut system = System::new_all();
+    system.refresh_all();
+    pids.iter()
+        .copied()
+        .filter(|&pid| {
+            system
+                .process(Pid::from(pid as usize))
+                .and_then(|process| process.kill_with(signal))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::session::CampaignData;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn request(stream: &mut BufReader<UnixStream>, line: &str) -> String {
+        writeln!(stream.get_mut(), "{line}").unwrap();
+        let mut response = String::new();
+        stream.read_line(&mut response).unwrap();
+        response.trim_end().to_string()
+    }
+
+    #[test]
+    fn test_request_parse() {
+        assert_eq!(Request::parse("GET STATUS"), Ok(Request::Status));
+        assert_eq!(Request::parse("  get plan "), Ok(Request::Plan));
+        assert_eq!(Request::parse("stop"), Ok(Request::Stop));
+        assert!(Request::parse("").is_err());
+        assert!(Request::parse("GET CRASHES").is_err());
+        assert!(Request::parse("STOP NOW").is_err());
+    }
+
+    #[test]
+    fn test_control_socket_status_and_stop() {
+        let tmp = TempDir::new().unwrap();
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+
+        let shared = Arc::new(SharedCampaignData::new());
+        let mut cdata = CampaignData::new();
+        cdata.fuzzers_started = 1;
+        cdata.fuzzer_pids = vec![child.id()];
+        shared.publish(&cdata);
+
+        let server = ControlServer::spawn(tmp.path(), &shared, Vec::new(), false).unwrap();
+        assert_eq!(server.path(), socket_path(tmp.path()));
+        assert!(ControlServer::spawn(tmp.path(), &shared, Vec::new(), false).is_err());
+
+        let mut stream = BufReader::new(UnixStream::connect(server.path()).unwrap());
+        let status = request(&mut stream, "GET STATUS");
+        let json: serde_json::Value =
+            serde_json::from_str(status.strip_prefix("OK ").unwrap()).unwrap();
+        assert_eq!(json["fuzzers_started"], 1);
+
+        assert!(request(&mut stream, "GET PLAN").starts_with("ERR "));
+        assert!(request(&mut stream, "FROBNICATE").starts_with("ERR unknown command"));
+
+        assert_eq!(request(&mut stream, "STOP"), "OK signaled 1/1 fuzzers");
+        // SIGINT
+        assert_eq!(child.wait().unwrap().signal(), Some(2));
+
+        let path = server.path().to_path_buf();
+        drop(server);
+        assert!(!path.exists());
+
+        let plan = vec!["afl-fuzz -M main_0 -- ./target".to_string()];
+        let server = ControlServer::spawn(tmp.path(), &shared, plan, false).unwrap();
+        let mut stream = BufReader::new(UnixStream::connect(server.path()).unwrap());
+        assert_eq!(
+            request(&mut stream, "GET PLAN"),
+            r#"OK ["afl-fuzz -M main_0 -- ./target"]"#
+        );
+    }
+}
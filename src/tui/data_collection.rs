@@ -1,14 +1,25 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
     ops::Add,
     path::{Path, PathBuf},
-    time::{Duration, Instant},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use sysinfo::{Pid, System};
 
-use crate::tui::session::{CampaignData, CrashInfoDetails};
+use crate::tui::forensics::{
+    exit_status_path, InstanceFacts, InstanceState, DEFAULT_STALL_THRESHOLD,
+};
+use crate::tui::retention::{RetentionPolicy, PRUNE_INTERVAL};
+use crate::tui::session::{
+    CampaignData, CrashInfoDetails, InstanceMetrics, LogLevel, Trend, SOLUTION_BACKLOG,
+};
+use crate::tui::stability::{StabilityWatch, STABILITY_BASELINE_CYCLES};
+use crate::tui::starvation::{StarvationDetector, STARVATION_CYCLES};
+use crate::utils::build_id::{short_id, BuildId, BuildIdRecord};
+use crate::utils::snapshot::{self, SnapshotPolicy};
 
 macro_rules! update_stat {
     // Special case for floating point numbers
@@ -63,6 +74,37 @@ macro_rules! calculate_minmax_average {
     };
 }
 
+/// Executions `AFL++` roughly spends on a queue entry per cycle, i.e. the havoc budget
+/// (`HAVOC_CYCLES`) at the default performance score
+const EXECS_PER_QUEUE_ENTRY: f64 = 256.0;
+
+/// File every instance writes its statistics to
+pub const STATS_FILE: &str = "fuzzer_stats";
+
+/// Time a campaign without any discovered fuzzer gets to start before none are reported alive
+pub const DEFAULT_STARTUP_GRACE: Duration = Duration::from_secs(30);
+
+/// State of a single instance as read from its `fuzzer_stats`
+#[derive(Debug, Clone)]
+pub struct InstanceSnapshot {
+    pub name: String,
+    pub state: InstanceState,
+    pub saved_crashes: u64,
+    /// Unix timestamp of the last `fuzzer_stats` update
+    pub last_update: Option<u64>,
+    /// Harness binary from the AFL++ command line
+    pub target: Option<PathBuf>,
+}
+
+/// An instance as listed by `aflr top`, built from the same collection cycle as the TUI
+#[derive(Debug, Clone)]
+pub struct InstanceRow {
+    pub name: String,
+    pub state: InstanceState,
+    /// Metrics of the last collection cycle, `None` once the instance exited
+    pub metrics: Option<InstanceMetrics>,
+}
+
 #[derive(Debug)]
 struct FuzzerMetrics {
     pid: Option<u32>,
@@ -94,26 +136,141 @@ impl FuzzerMetrics {
             .get(key)
             .and_then(|v| v.trim_end_matches('%').parse().ok())
     }
+
+    /// Whether the instance runs as the `-M` main instance according to its `command_line`
+    fn is_main(&self) -> bool {
+        self.metrics
+            .get("command_line")
+            .is_some_and(|command_line| {
+                let args = command_line.split(" -- ").next().unwrap_or_default();
+                args.split_whitespace().any(|arg| arg == "-M")
+            })
+    }
+
+    /// The harness binary, i.e. the first word after `--` in `command_line`
+    fn target(&self) -> Option<PathBuf> {
+        let command_line = self.metrics.get("command_line")?;
+        let (_, target_cmd) = command_line.split_once(" -- ")?;
+        target_cmd.split_whitespace().next().map(PathBuf::from)
+    }
+}
+
+/// A parsed `fuzzer_stats` file along with the metadata it was parsed at
+#[derive(Debug)]
+struct CachedStats {
+    modified: SystemTime,
+    len: u64,
+    metrics: Arc<FuzzerMetrics>,
+}
+
+/// The build id of a binary along with the metadata it was read at
+#[derive(Debug)]
+struct CachedBuildId {
+    modified: SystemTime,
+    len: u64,
+    id: BuildId,
+}
+
+/// How the TUI decides whether a fuzzer is still alive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Liveness {
+    /// The PID in its `fuzzer_stats` refers to a local process
+    #[default]
+    Process,
+    /// Its `fuzzer_stats` were updated within the window, for campaigns running on another host
+    StatsUpdate(Duration),
+}
+
+impl Liveness {
+    fn is_alive(self, metrics: &FuzzerMetrics, system: &System) -> bool {
+        match self {
+            Self::Process => metrics
+                .pid
+                .is_some_and(|pid| system.process(Pid::from(pid as usize)).is_some()),
+            Self::StatsUpdate(window) => last_update_age(metrics).is_some_and(|age| age <= window),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct DataFetcher {
-    output_dir: PathBuf,
+    /// Labeled output directories, the label prefixes instance names when non-empty
+    campaigns: Vec<(String, PathBuf)>,
+    pid_file: Option<PathBuf>,
+    /// Name of the statistics file in every instance directory, `STATS_FILE` by default
+    stats_file: String,
     pub campaign_data: CampaignData,
     system: System,
+    liveness: Liveness,
+    created: Instant,
+    /// Time to quietly retry the PID discovery while no fuzzer has been seen yet
+    startup_grace: Duration,
+    /// No fuzzer was discovered yet, neither alive nor dead
+    awaiting_start: bool,
     first_update: bool,
+    instance_states: HashMap<String, InstanceState>,
+    /// Age of the stats beyond which a live instance is classified as stalled
+    stall_threshold: Duration,
+    retention: RetentionPolicy,
+    last_prune: Option<Instant>,
+    snapshots: SnapshotPolicy,
+    /// Time of the newest snapshot per output directory
+    last_snapshot: HashMap<PathBuf, SystemTime>,
+    stats_cache: HashMap<PathBuf, CachedStats>,
+    stats_parsed: usize,
+    build_id_cache: HashMap<PathBuf, CachedBuildId>,
+    build_mismatches: HashSet<String>,
+    starvation: StarvationDetector,
+    stability: StabilityWatch,
+    /// Pending favorites of all instances in the previous cycle, for their trend
+    last_pending_favs: Option<usize>,
 }
 
 impl DataFetcher {
-    pub fn new(
-        output_dir: &Path,
+    pub fn new(output_dir: &Path, pid_file: Option<&Path>, campaign_data: CampaignData) -> Self {
+        Self::with_campaigns(
+            vec![(String::new(), output_dir.to_path_buf())],
+            pid_file,
+            campaign_data,
+            Liveness::Process,
+        )
+    }
+
+    /// Collects a campaign running on another host from a local copy of its `fuzzer_stats`
+    ///
+    /// The PIDs mean nothing locally, so fuzzers whose stats were updated within `window` count
+    /// as alive.
+    pub fn for_remote(cache_dir: &Path, window: Duration, campaign_data: CampaignData) -> Self {
+        Self::with_campaigns(
+            vec![(String::new(), cache_dir.to_path_buf())],
+            None,
+            campaign_data,
+            Liveness::StatsUpdate(window),
+        )
+    }
+
+    /// Collects several independent campaigns as one
+    ///
+    /// Instances are named `<campaign>/<instance>` with the labels of `campaign_labels`.
+    pub fn for_campaigns(output_dirs: &[PathBuf], campaign_data: CampaignData) -> Self {
+        let campaigns = campaign_labels(output_dirs)
+            .into_iter()
+            .zip(output_dirs.iter().cloned())
+            .collect();
+        Self::with_campaigns(campaigns, None, campaign_data, Liveness::Process)
+    }
+
+    fn with_campaigns(
+        campaigns: Vec<(String, PathBuf)>,
         pid_file: Option<&Path>,
-        campaign_data: &mut CampaignData,
+        mut campaign_data: CampaignData,
+        liveness: Liveness,
     ) -> Self {
         let mut system = System::new_all();
         system.refresh_all();
 
-        let (fuzzer_pids, dead_count) = Self::collect_pids(output_dir, pid_file, &system);
+        let (fuzzer_pids, dead_count) =
+            Self::discover_pids(&campaigns, STATS_FILE, pid_file, &system, liveness);
 
         campaign_data.log(if pid_file.is_some() {
             "PIDs fetched from the PID file. OK..."
@@ -121,55 +278,246 @@ impl DataFetcher {
             "Attempted to fetch PIDs from fuzzer_stats files"
         });
 
-        let fuzzers_alive = Self::get_alive_fuzzers(&fuzzer_pids, &system);
-        campaign_data.log(if fuzzers_alive.is_empty() {
-            "No fuzzers alive"
+        let fuzzers_alive = match liveness {
+            Liveness::Process => Self::get_alive_fuzzers(&fuzzer_pids, &system),
+            // Only the fresh PIDs were collected
+            Liveness::StatsUpdate(_) => fuzzer_pids.iter().map(|&pid| pid as usize).collect(),
+        };
+        // Slow starting targets may not have written any PID yet, which is retried quietly
+        let awaiting_start = fuzzer_pids.is_empty() && dead_count == 0;
+        if awaiting_start {
+            campaign_data.log("No fuzzers found yet, waiting for them to start");
+        } else if fuzzers_alive.is_empty() {
+            campaign_data.warn("No fuzzers alive");
         } else {
-            "Fuzzers alive count fetched. OK..."
-        });
+            campaign_data.log("Fuzzers alive count fetched. OK...");
+        }
 
         campaign_data.fuzzers_started = fuzzers_alive.len() + dead_count;
         campaign_data.fuzzers_alive = fuzzers_alive;
         campaign_data.fuzzer_pids = fuzzer_pids;
 
         Self {
-            output_dir: output_dir.to_path_buf(),
-            campaign_data: campaign_data.clone(),
+            campaigns,
+            pid_file: pid_file.map(Path::to_path_buf),
+            stats_file: STATS_FILE.to_string(),
+            campaign_data,
             system,
+            liveness,
+            created: Instant::now(),
+            startup_grace: DEFAULT_STARTUP_GRACE,
+            awaiting_start,
             first_update: true,
+            instance_states: HashMap::new(),
+            stall_threshold: DEFAULT_STALL_THRESHOLD,
+            retention: RetentionPolicy::default(),
+            last_prune: None,
+            snapshots: SnapshotPolicy::default(),
+            last_snapshot: HashMap::new(),
+            stats_cache: HashMap::new(),
+            stats_parsed: 0,
+            build_id_cache: HashMap::new(),
+            build_mismatches: HashSet::new(),
+            starvation: StarvationDetector::default(),
+            stability: StabilityWatch::default(),
+            last_pending_favs: None,
+        }
+    }
+
+    /// Report no fuzzers alive only after `grace` passed without discovering any
+    pub fn with_startup_grace(mut self, grace: Duration) -> Self {
+        self.startup_grace = grace;
+        self
+    }
+
+    /// Read the statistics from `name` instead of `STATS_FILE`, e.g. for forks of `AFL++`
+    ///
+    /// The PIDs are discovered again from these files on the next collection.
+    pub fn with_stats_file(mut self, name: &str) -> Self {
+        if name != self.stats_file {
+            self.stats_file = name.to_string();
+            self.awaiting_start = true;
+        }
+        self
+    }
+
+    /// Classify live instances whose stats are older than `threshold` as stalled
+    pub fn with_stall_threshold(mut self, threshold: Duration) -> Self {
+        self.stall_threshold = threshold;
+        self
+    }
+
+    /// Warn when the main instance runs below `ratio` of the secondaries' median execs/s
+    pub fn with_starvation_ratio(mut self, ratio: f64) -> Self {
+        self.starvation = StarvationDetector::new(ratio, STARVATION_CYCLES);
+        self
+    }
+
+    /// Warn when the average stability drops more than `delta` points below its rolling baseline
+    pub fn with_stability_drop(mut self, delta: f64) -> Self {
+        self.stability = StabilityWatch::new(delta, STABILITY_BASELINE_CYCLES);
+        self
+    }
+
+    /// Prune solutions beyond the caps of `retention` during data collection
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Snapshot the corpus and solutions of every campaign as `snapshots` asks for
+    pub fn with_snapshots(mut self, snapshots: SnapshotPolicy) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
+    /// Every instance seen so far with its state and the metrics of the live ones, sorted by name
+    pub fn instance_rows(&self) -> Vec<InstanceRow> {
+        let mut rows: Vec<InstanceRow> = self
+            .instance_states
+            .iter()
+            .map(|(name, &state)| InstanceRow {
+                name: name.clone(),
+                state,
+                metrics: matches!(state, InstanceState::Running | InstanceState::Stalled)
+                    .then(|| {
+                        self.campaign_data
+                            .instances
+                            .iter()
+                            .find(|m| &m.name == name)
+                            .cloned()
+                    })
+                    .flatten(),
+            })
+            .collect();
+        rows.sort_by(|a, b| a.name.cmp(&b.name));
+        rows
+    }
+
+    /// Returns the PIDs of all still alive fuzzers that report into `output_dir`
+    pub fn alive_pids(output_dir: &Path) -> Vec<u32> {
+        let mut system = System::new_all();
+        system.refresh_all();
+        Self::collect_pids(output_dir, STATS_FILE, None, &system, Liveness::Process).0
+    }
+
+    /// Classifies every instance of the campaign in `output_dir` the same way the TUI does
+    pub fn instance_snapshots(output_dir: &Path, system: &System) -> Vec<InstanceSnapshot> {
+        instance_dirs(output_dir)
+            .into_iter()
+            .filter_map(|(name, path)| {
+                let content = fs::read_to_string(path.join(STATS_FILE)).ok()?;
+                let metrics = FuzzerMetrics::parse(&content);
+                Some(InstanceSnapshot {
+                    name,
+                    state: Self::gather_instance_facts(
+                        &path,
+                        &metrics,
+                        system,
+                        Liveness::Process,
+                        None,
+                    )
+                    .classify(),
+                    saved_crashes: metrics.get("saved_crashes").unwrap_or(0),
+                    last_update: metrics.get::<u64>("last_update").filter(|&ts| ts > 0),
+                    target: metrics.target(),
+                })
+            })
+            .collect()
+    }
+
+    /// All solutions in the `dir_name` directories (`crashes` or `hangs`) of the campaign in
+    /// `output_dir`, ordered by instance and id
+    pub fn solutions(output_dir: &Path, dir_name: &str) -> Vec<CrashInfoDetails> {
+        let mut solutions = Vec::new();
+        for (fuzzer_name, subdir) in instance_dirs(output_dir) {
+            Self::collect_solution_files(
+                &subdir,
+                &fuzzer_name,
+                dir_name,
+                usize::MAX,
+                &mut solutions,
+            );
+        }
+        solutions.sort_unstable_by(|a, b| (&a.fuzzer_name, &a.id).cmp(&(&b.fuzzer_name, &b.id)));
+        solutions
+    }
+
+    /// Collects the PIDs of all campaigns along with the amount of dead fuzzers
+    fn discover_pids(
+        campaigns: &[(String, PathBuf)],
+        stats_file: &str,
+        pid_file: Option<&Path>,
+        system: &System,
+        liveness: Liveness,
+    ) -> (Vec<u32>, usize) {
+        let (mut fuzzer_pids, mut dead_count) = (Vec::new(), 0);
+        for (_, output_dir) in campaigns {
+            let (pids, dead) =
+                Self::collect_pids(output_dir, stats_file, pid_file, system, liveness);
+            fuzzer_pids.extend(pids);
+            dead_count += dead;
         }
+        (fuzzer_pids, dead_count)
+    }
+
+    /// Retries the PID discovery while waiting for the first fuzzer to start
+    ///
+    /// Returns true while still within the startup grace period, after which none alive is
+    /// reported as usual.
+    fn await_start(&mut self) -> bool {
+        if !self.awaiting_start {
+            return false;
+        }
+        if self.liveness == Liveness::Process {
+            let (pids, dead) = Self::discover_pids(
+                &self.campaigns,
+                &self.stats_file,
+                self.pid_file.as_deref(),
+                &self.system,
+                self.liveness,
+            );
+            self.campaign_data.fuzzers_started = pids.len() + dead;
+            self.campaign_data.fuzzer_pids = pids;
+            self.campaign_data.fuzzers_alive =
+                Self::get_alive_fuzzers(&self.campaign_data.fuzzer_pids, &self.system);
+        }
+        if self.campaign_data.fuzzers_started > 0 {
+            self.awaiting_start = false;
+            self.campaign_data.log("Fuzzers alive count fetched. OK...");
+            return false;
+        }
+        if self.created.elapsed() >= self.startup_grace {
+            self.awaiting_start = false;
+            self.campaign_data.warn(format!(
+                "No fuzzers started within {}s",
+                self.startup_grace.as_secs()
+            ));
+            return false;
+        }
+        true
     }
 
     fn collect_pids(
         output_dir: &Path,
+        stats_file: &str,
         pid_file: Option<&Path>,
         system: &System,
+        liveness: Liveness,
     ) -> (Vec<u32>, usize) {
         pid_file.map_or_else(
             || {
                 let mut alive_pids = Vec::new();
                 let mut dead_count = 0;
 
-                if let Ok(entries) = fs::read_dir(output_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if !path.is_dir() {
-                            continue;
-                        }
-
-                        let stats_path = path.join("fuzzer_stats");
-                        if !stats_path.exists() {
-                            continue;
-                        }
-
-                        if let Ok(content) = fs::read_to_string(&stats_path) {
-                            let metrics = FuzzerMetrics::parse(&content);
-                            if let Some(pid) = metrics.pid {
-                                if system.process(Pid::from(pid as usize)).is_some() {
-                                    alive_pids.push(pid);
-                                } else {
-                                    dead_count += 1;
-                                }
+                for (_, path) in instance_dirs_with(output_dir, stats_file) {
+                    if let Ok(content) = fs::read_to_string(path.join(stats_file)) {
+                        let metrics = FuzzerMetrics::parse(&content);
+                        if let Some(pid) = metrics.pid {
+                            if liveness.is_alive(&metrics, system) {
+                                alive_pids.push(pid);
+                            } else {
+                                dead_count += 1;
                             }
                         }
                     }
@@ -177,18 +525,20 @@ impl DataFetcher {
 
                 (alive_pids, dead_count)
             },
-            |pid_file| {
-                let pids = fs::read_to_string(pid_file)
-                    .unwrap_or_default()
-                    .split(':')
-                    .filter_map(|pid| pid.trim().parse::<u32>().ok())
-                    .filter(|&pid| pid != 0)
-                    .collect();
-                (pids, 0)
-            },
+            |pid_file| (Self::read_pid_file(pid_file), 0),
         )
     }
 
+    /// Reads the colon separated fuzzer PIDs the session scripts write into their PID file
+    pub fn read_pid_file(pid_file: &Path) -> Vec<u32> {
+        fs::read_to_string(pid_file)
+            .unwrap_or_default()
+            .split(':')
+            .filter_map(|pid| pid.trim().parse::<u32>().ok())
+            .filter(|&pid| pid != 0)
+            .collect()
+    }
+
     fn get_alive_fuzzers(pids: &[u32], system: &System) -> Vec<usize> {
         pids.iter()
             .filter(|&&pid| pid != 0 && system.process(Pid::from(pid as usize)).is_some())
@@ -196,68 +546,401 @@ impl DataFetcher {
             .collect()
     }
 
+    /// Instance directories of all campaigns, see `instance_dirs`
+    fn campaign_instances(&self) -> Vec<(String, PathBuf)> {
+        self.campaigns
+            .iter()
+            .flat_map(|(label, output_dir)| {
+                instance_dirs_with(output_dir, &self.stats_file)
+                    .into_iter()
+                    .map(move |(name, path)| {
+                        if label.is_empty() {
+                            (name, path)
+                        } else {
+                            (format!("{label}/{name}"), path)
+                        }
+                    })
+            })
+            .collect()
+    }
+
     pub fn collect_session_data(&mut self) -> &CampaignData {
         self.system.refresh_all();
-        self.campaign_data.fuzzers_alive =
-            Self::get_alive_fuzzers(&self.campaign_data.fuzzer_pids, &self.system);
+        self.stats_parsed = 0;
+        self.campaign_data.fuzzers_alive = match self.liveness {
+            Liveness::Process => {
+                Self::get_alive_fuzzers(&self.campaign_data.fuzzer_pids, &self.system)
+            }
+            Liveness::StatsUpdate(_) => {
+                let alive = self.fresh_pids();
+                // Instances may come up on the remote host after monitoring started
+                self.campaign_data.fuzzers_started =
+                    self.campaign_data.fuzzers_started.max(alive.len());
+                alive
+            }
+        };
+        if self.await_start() {
+            return &self.campaign_data;
+        }
+        self.update_instance_states();
 
         if self.campaign_data.fuzzers_alive.is_empty() {
             self.campaign_data
-                .log("No fuzzers alive. Skipping data collection");
+                .warn("No fuzzers alive. Skipping data collection");
             return &self.campaign_data;
         }
 
         self.campaign_data.clear();
         self.process_fuzzer_directories();
+        self.check_main_throughput();
+        self.campaign_data.stats_parsed = self.stats_parsed;
         self.update_run_time();
         self.calculate_averages();
+        self.check_stability();
+        self.update_pending_trend();
+        self.campaign_data.est_cycle_time = Self::estimate_cycle_time(
+            self.campaign_data.corpus.avg,
+            self.campaign_data.executions.per_sec.avg,
+        );
+        // Prune before listing the latest solutions so they never reference deleted files
+        self.enforce_retention();
+        self.take_due_snapshots();
 
-        let (crashes, hangs) = self.collect_crashes_and_hangs(10);
+        let (crashes, hangs) = self.collect_crashes_and_hangs(SOLUTION_BACKLOG);
         self.campaign_data.last_crashes = crashes;
         self.campaign_data.last_hangs = hangs;
 
         &self.campaign_data
     }
 
-    fn process_fuzzer_directories(&mut self) {
-        if let Ok(entries) = fs::read_dir(&self.output_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if !path.is_dir() {
-                    continue;
-                }
+    /// Classifies the end state of every fuzzer instance and logs any state changes
+    fn update_instance_states(&mut self) {
+        for (name, path) in self.campaign_instances() {
+            let Some(metrics) = self.read_stats(&path.join(&self.stats_file)) else {
+                continue;
+            };
+
+            let facts = Self::gather_instance_facts(
+                &path,
+                &metrics,
+                &self.system,
+                self.liveness,
+                Some(self.stall_threshold),
+            );
+            let state = facts.classify();
+            if self.instance_states.get(&name) != Some(&state) {
+                let level = match state {
+                    InstanceState::Running | InstanceState::Finished => LogLevel::Info,
+                    InstanceState::Stalled | InstanceState::Killed => LogLevel::Warn,
+                    InstanceState::DiedAbnormally => LogLevel::Error,
+                };
+                let message = match facts.last_update_age {
+                    Some(age) if state == InstanceState::Stalled => format!(
+                        "Fuzzer {name}: stalled, alive but no stats update for {}m",
+                        age.as_secs() / 60
+                    ),
+                    _ => format!("Fuzzer {name}: {state}"),
+                };
+                self.campaign_data.log_at(level, message);
+                self.instance_states.insert(name, state);
+            }
+        }
+    }
 
-                let stats_path = path.join("fuzzer_stats");
-                if !stats_path.exists() {
-                    continue;
-                }
+    /// Warns once when the main instance starts to starve and logs when it recovers
+    fn check_main_throughput(&mut self) {
+        let starved = self.starvation.update(&self.campaign_data.instances);
+        match (&self.campaign_data.starved_main, &starved) {
+            (None, Some(starvation)) => self.campaign_data.warn(starvation.message()),
+            (Some(previous), None) => self.campaign_data.log(format!(
+                "Fuzzer {}: main instance caught up with the secondaries",
+                previous.instance
+            )),
+            _ => {}
+        }
+        self.campaign_data.starved_main = starved;
+    }
 
-                if let Ok(content) = fs::read_to_string(&stats_path) {
-                    let metrics = FuzzerMetrics::parse(&content);
-                    if let Some(pid) = metrics.pid {
-                        if self.campaign_data.fuzzers_alive.contains(&(pid as usize)) {
-                            self.process_metrics(&metrics);
-                        }
-                    }
+    /// Warns once when the average stability collapses and logs when it recovers
+    fn check_stability(&mut self) {
+        let collapse = self.stability.update(self.campaign_data.stability.avg);
+        match (&self.campaign_data.stability_collapse, &collapse) {
+            (None, Some(collapse)) => self.campaign_data.warn(collapse.message()),
+            (Some(previous), None) => self.campaign_data.log(format!(
+                "Stability recovered to {:.2}% (baseline {:.2}%)",
+                self.campaign_data.stability.avg, previous.baseline
+            )),
+            _ => {}
+        }
+        self.campaign_data.stability_collapse = collapse;
+    }
+
+    /// Compares the pending favorites with the previous cycle, falling ones mean the instances
+    /// are catching up with their queues
+    fn update_pending_trend(&mut self) {
+        let pending = &mut self.campaign_data.pending;
+        pending.favorites_trend = Trend::between(self.last_pending_favs, pending.favorites.cum);
+        self.last_pending_favs = Some(pending.favorites.cum);
+    }
+
+    /// Applies the retention policy to all instances, at most once per `PRUNE_INTERVAL`
+    fn enforce_retention(&mut self) {
+        if !self.retention.is_enabled()
+            || self
+                .last_prune
+                .is_some_and(|last| last.elapsed() < PRUNE_INTERVAL)
+        {
+            return;
+        }
+        self.last_prune = Some(Instant::now());
+
+        for (name, path) in self.campaign_instances() {
+            match self.retention.enforce(&path) {
+                Ok((0, 0)) => {}
+                Ok((crashes, hangs)) => {
+                    self.campaign_data.pruned.crashes += crashes;
+                    self.campaign_data.pruned.hangs += hangs;
+                    self.campaign_data.log(format!(
+                        "Fuzzer {name}: pruned {crashes} crashes and {hangs} hangs"
+                    ));
                 }
+                Err(e) => self
+                    .campaign_data
+                    .error(format!("Fuzzer {name}: pruning failed: {e}")),
+            }
+        }
+    }
+
+    /// Snapshots every campaign whose newest snapshot is older than the policy's interval
+    ///
+    /// Without any snapshot yet, the first one is taken an interval after monitoring started.
+    #[allow(clippy::cast_precision_loss)]
+    fn take_due_snapshots(&mut self) {
+        if !self.snapshots.is_enabled() {
+            return;
+        }
+        for (label, output_dir) in self.campaigns.clone() {
+            let last = *self
+                .last_snapshot
+                .entry(output_dir.clone())
+                .or_insert_with(|| {
+                    snapshot::list(&output_dir)
+                        .ok()
+                        .and_then(|snapshots| snapshots.last().map(|s| s.taken))
+                        .unwrap_or_else(SystemTime::now)
+                });
+            if !self.snapshots.is_due(last) {
+                continue;
+            }
+            self.last_snapshot
+                .insert(output_dir.clone(), SystemTime::now());
+
+            let prefix = if label.is_empty() {
+                String::new()
+            } else {
+                format!("{label}: ")
+            };
+            let result = self.snapshots.take(&output_dir).and_then(|report| {
+                self.campaign_data.log(format!(
+                    "{prefix}Snapshot {}: {} files, {:.1} MiB in {:.1}s",
+                    report.name,
+                    report.files,
+                    report.bytes as f64 / (1024.0 * 1024.0),
+                    report.duration.as_secs_f64()
+                ));
+                self.snapshots.prune(&output_dir)
+            });
+            match result {
+                Ok(pruned) if pruned.is_empty() => {}
+                Ok(pruned) => self
+                    .campaign_data
+                    .log(format!("{prefix}Pruned snapshot(s) {}", pruned.join(", "))),
+                Err(e) => self
+                    .campaign_data
+                    .error(format!("{prefix}Snapshot failed: {e}")),
             }
         }
     }
 
-    fn process_metrics(&mut self, metrics: &FuzzerMetrics) {
+    /// PIDs of the instances whose stats are fresh enough, see `Liveness::StatsUpdate`
+    fn fresh_pids(&mut self) -> Vec<usize> {
+        let mut pids = Vec::new();
+        for (_, path) in self.campaign_instances() {
+            let Some(metrics) = self.read_stats(&path.join(&self.stats_file)) else {
+                continue;
+            };
+            if let Some(pid) = metrics
+                .pid
+                .filter(|_| self.liveness.is_alive(&metrics, &self.system))
+            {
+                pids.push(pid as usize);
+            }
+        }
+        pids
+    }
+
+    fn gather_instance_facts(
+        instance_dir: &Path,
+        metrics: &FuzzerMetrics,
+        system: &System,
+        liveness: Liveness,
+        stall_threshold: Option<Duration>,
+    ) -> InstanceFacts {
+        // Exit statuses are only recorded for local fuzzers, next to the instance directories
+        let exit_code = metrics
+            .pid
+            .filter(|_| liveness == Liveness::Process)
+            .zip(instance_dir.parent())
+            .and_then(|(pid, output_dir)| {
+                fs::read_to_string(exit_status_path(output_dir, pid)).ok()
+            })
+            .and_then(|status| status.trim().parse().ok());
+
+        InstanceFacts {
+            pid_alive: liveness.is_alive(metrics, system),
+            last_update_age: last_update_age(metrics),
+            has_cur_input: instance_dir.join(".cur_input").exists(),
+            exit_code,
+            execs_done: metrics.get("execs_done"),
+            stall_threshold,
+        }
+    }
+
+    fn process_fuzzer_directories(&mut self) {
+        // Every target of a multi-target campaign records its own build ids
+        let mut records: HashMap<PathBuf, Option<BuildIdRecord>> = HashMap::new();
+        for (name, path) in self.campaign_instances() {
+            let Some(metrics) = self.read_stats(&path.join(&self.stats_file)) else {
+                continue;
+            };
+            let Some(pid) = metrics.pid else {
+                continue;
+            };
+            if !self.campaign_data.fuzzers_alive.contains(&(pid as usize)) {
+                continue;
+            }
+
+            // Frozen stats report the rate from before the stall
+            let stalled = self.instance_states.get(&name) == Some(&InstanceState::Stalled);
+            self.process_metrics(&metrics, stalled);
+            let Some(campaign_dir) = path.parent() else {
+                continue;
+            };
+            let record = records
+                .entry(campaign_dir.to_path_buf())
+                .or_insert_with_key(|dir| BuildIdRecord::load(dir));
+            let (build_id, build_mismatch) =
+                self.check_instance_build(&name, &path, record.as_ref());
+            self.campaign_data.instances.push(InstanceMetrics {
+                name,
+                execs_per_sec: metrics.get("execs_per_sec").unwrap_or_default(),
+                build_id,
+                build_mismatch,
+                peak_rss_mb: metrics.get("peak_rss_mb").unwrap_or_default(),
+                stalled,
+                main: metrics.is_main(),
+                corpus_count: metrics.get("corpus_count").unwrap_or_default(),
+                saved_crashes: metrics.get("saved_crashes").unwrap_or_default(),
+                stability: metrics.get("stability").unwrap_or_default(),
+                last_find: metrics.get::<u64>("last_find").filter(|&ts| ts > 0),
+            });
+        }
+        self.campaign_data
+            .instances
+            .sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Reads the build id of the binary an instance runs and compares it to the one the
+    /// campaign was launched with, logging each instance once when it starts to differ
+    fn check_instance_build(
+        &mut self,
+        name: &str,
+        instance_dir: &Path,
+        record: Option<&BuildIdRecord>,
+    ) -> (Option<String>, bool) {
+        let Some(binary) = instance_binary(instance_dir) else {
+            return (None, false);
+        };
+        let Some(id) = self.read_build_id(&binary) else {
+            return (None, false);
+        };
+
+        let recorded = record.and_then(|r| r.get(&binary));
+        let mismatch = recorded.is_some_and(|recorded| recorded != id.as_str());
+        if mismatch && self.build_mismatches.insert(name.to_string()) {
+            self.campaign_data.warn(format!(
+                "Fuzzer {name}: {} is build ..{}, the campaign was launched with ..{}",
+                binary.display(),
+                id.short(),
+                recorded.map_or("", short_id)
+            ));
+        } else if !mismatch {
+            self.build_mismatches.remove(name);
+        }
+        (Some(id.to_string()), mismatch)
+    }
+
+    /// Build id of `binary`, only re-read when its mtime or size changed
+    fn read_build_id(&mut self, binary: &Path) -> Option<BuildId> {
+        let meta = fs::metadata(binary).ok()?;
+        let modified = meta.modified().ok()?;
+        if let Some(cached) = self.build_id_cache.get(binary) {
+            if cached.modified == modified && cached.len == meta.len() {
+                return Some(cached.id.clone());
+            }
+        }
+        let id = BuildId::read(binary).ok()?;
+        self.build_id_cache.insert(
+            binary.to_path_buf(),
+            CachedBuildId {
+                modified,
+                len: meta.len(),
+                id: id.clone(),
+            },
+        );
+        Some(id)
+    }
+
+    /// Returns the metrics of a `fuzzer_stats` file, re-parsing it only when its mtime or size
+    /// changed since the previous read. AFL++ rewrites the file about once a minute.
+    fn read_stats(&mut self, stats_path: &Path) -> Option<Arc<FuzzerMetrics>> {
+        let meta = fs::metadata(stats_path).ok()?;
+        let modified = meta.modified().ok()?;
+        if let Some(cached) = self.stats_cache.get(stats_path) {
+            if cached.modified == modified && cached.len == meta.len() {
+                return Some(Arc::clone(&cached.metrics));
+            }
+        }
+
+        let content = fs::read_to_string(stats_path).ok()?;
+        let metrics = Arc::new(FuzzerMetrics::parse(&content));
+        self.stats_parsed += 1;
+        self.stats_cache.insert(
+            stats_path.to_path_buf(),
+            CachedStats {
+                modified,
+                len: meta.len(),
+                metrics: Arc::clone(&metrics),
+            },
+        );
+        Some(metrics)
+    }
+
+    fn process_metrics(&mut self, metrics: &FuzzerMetrics, stalled: bool) {
         if self.first_update {
             if let Some(run_time) = metrics.get::<u64>("run_time") {
                 self.update_start_time(run_time);
             }
         }
 
-        self.update_stats(metrics);
+        self.update_stats(metrics, stalled);
         self.update_misc_info(metrics);
 
         self.first_update = false;
     }
 
-    fn update_stats(&mut self, metrics: &FuzzerMetrics) {
+    /// Accumulates the metrics of an instance, the rates of `stalled` ones are left out
+    fn update_stats(&mut self, metrics: &FuzzerMetrics, stalled: bool) {
         if self.first_update {
             if let Some(run_time) = metrics.get::<u64>("run_time") {
                 self.update_start_time(run_time);
@@ -293,6 +976,12 @@ impl DataFetcher {
         update_stat!(self.campaign_data.crashes, metrics, "saved_crashes", usize);
         update_stat!(self.campaign_data.hangs, metrics, "saved_hangs", usize);
         update_stat!(self.campaign_data.levels, metrics, "max_depth", usize);
+        update_stat!(
+            self.campaign_data.cycles.done,
+            metrics,
+            "cycles_done",
+            usize
+        );
         update_stat!(
             self.campaign_data.cycles.wo_finds,
             metrics,
@@ -301,12 +990,14 @@ impl DataFetcher {
         );
 
         // Update floating point statistics
-        update_stat!(
-            self.campaign_data.executions.per_sec,
-            metrics,
-            "execs_per_sec",
-            f64
-        );
+        if !stalled {
+            update_stat!(
+                self.campaign_data.executions.per_sec,
+                metrics,
+                "execs_per_sec",
+                f64
+            );
+        }
 
         self.update_stability_and_coverage(metrics);
         self.update_misc_info(metrics);
@@ -332,6 +1023,17 @@ impl DataFetcher {
                 self.campaign_data.coverage.min = coverage;
             }
         }
+
+        // Absolute edge counts are more comparable across targets than the bitmap coverage
+        update_stat!(
+            self.campaign_data.edges.found,
+            metrics,
+            "edges_found",
+            usize
+        );
+        if let Some(total) = metrics.get::<usize>("total_edges") {
+            self.campaign_data.edges.total = self.campaign_data.edges.total.max(total);
+        }
     }
 
     fn update_misc_info(&mut self, metrics: &FuzzerMetrics) {
@@ -372,7 +1074,10 @@ impl DataFetcher {
 
         // Calculate cumulative averages (using fuzzer count)
         calculate_average!(self.campaign_data.executions.count, fuzzer_count);
-        calculate_average!(self.campaign_data.executions.per_sec, fuzzer_count, f64);
+        let rate_count = fuzzer_count - self.campaign_data.stalled_count();
+        if rate_count > 0 {
+            calculate_average!(self.campaign_data.executions.per_sec, rate_count, f64);
+        }
         calculate_average!(self.campaign_data.pending.favorites, fuzzer_count);
         calculate_average!(self.campaign_data.pending.total, fuzzer_count);
         calculate_average!(self.campaign_data.corpus, fuzzer_count);
@@ -381,11 +1086,31 @@ impl DataFetcher {
 
         // Calculate min-max based averages
         calculate_minmax_average!(self.campaign_data.coverage);
+        calculate_minmax_average!(self.campaign_data.edges.found, integer);
         calculate_minmax_average!(self.campaign_data.stability);
         calculate_minmax_average!(self.campaign_data.cycles.done, integer);
         calculate_minmax_average!(self.campaign_data.cycles.wo_finds, integer);
         calculate_minmax_average!(self.campaign_data.levels, integer);
         calculate_minmax_average!(self.campaign_data.time_without_finds, integer);
+
+        let cycles = &mut self.campaign_data.cycles;
+        #[allow(clippy::cast_precision_loss)]
+        if cycles.done.cum > 0 {
+            cycles.wo_finds_ratio = cycles.wo_finds.cum as f64 / cycles.done.cum as f64;
+        }
+    }
+
+    /// Estimates the duration of a full queue cycle of an average instance
+    ///
+    /// This is only a rough pacing hint as the actual budget per entry depends on its
+    /// performance score. Returns `None` while no executions are reported.
+    fn estimate_cycle_time(corpus_count: usize, execs_per_sec: f64) -> Option<Duration> {
+        if execs_per_sec <= 0.0 || !execs_per_sec.is_finite() {
+            return None;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let secs = corpus_count as f64 * EXECS_PER_QUEUE_ENTRY / execs_per_sec;
+        Duration::try_from_secs_f64(secs).ok()
     }
 
     fn collect_crashes_and_hangs(
@@ -396,38 +1121,33 @@ impl DataFetcher {
         let mut crashes = Vec::with_capacity(num_latest);
         let mut hangs = Vec::with_capacity(num_latest);
 
-        if let Ok(entries) = fs::read_dir(&self.output_dir) {
-            for entry in entries.flatten() {
-                let subdir = entry.path();
-                if !subdir.is_dir() {
-                    continue;
-                }
-
-                let fuzzer_name = subdir
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .map(String::from)
-                    .unwrap_or_default();
-
-                Self::collect_solution_files(&subdir, &fuzzer_name, "crashes", &mut crashes);
-                Self::collect_solution_files(&subdir, &fuzzer_name, "hangs", &mut hangs);
-            }
+        for (fuzzer_name, subdir) in self.campaign_instances() {
+            Self::collect_solution_files(
+                &subdir,
+                &fuzzer_name,
+                "crashes",
+                num_latest,
+                &mut crashes,
+            );
+            Self::collect_solution_files(&subdir, &fuzzer_name, "hangs", num_latest, &mut hangs);
         }
 
-        // Sort by time and take latest n items
-        crashes.sort_unstable_by(|a, b| b.time.cmp(&a.time));
-        hangs.sort_unstable_by(|a, b| b.time.cmp(&a.time));
+        Self::retain_latest(&mut crashes, num_latest);
+        Self::retain_latest(&mut hangs, num_latest);
+        (crashes, hangs)
+    }
 
-        (
-            crashes.into_iter().take(num_latest).collect(),
-            hangs.into_iter().take(num_latest).collect(),
-        )
+    /// Keeps only the `num_latest` newest solutions, sorted newest first
+    fn retain_latest(solutions: &mut Vec<CrashInfoDetails>, num_latest: usize) {
+        solutions.sort_unstable_by_key(|s| std::cmp::Reverse(s.time));
+        solutions.truncate(num_latest);
     }
 
     fn collect_solution_files(
         subdir: &Path,
         fuzzer_name: &str,
         dir_name: &str,
+        num_latest: usize,
         solutions: &mut Vec<CrashInfoDetails>,
     ) {
         let dir = subdir.join(dir_name);
@@ -447,6 +1167,10 @@ impl DataFetcher {
                         info.fuzzer_name = fuzzer_name.to_string();
                         info.file_path = path;
                         solutions.push(info);
+                        // Bound the memory for instances with huge solution directories
+                        if solutions.len() >= num_latest.max(1).saturating_mul(2) {
+                            Self::retain_latest(solutions, num_latest);
+                        }
                     }
                 }
             }
@@ -520,13 +1244,141 @@ impl DataFetcher {
     }
 }
 
+/// Time passed since the instance last updated its `fuzzer_stats`
+fn last_update_age(metrics: &FuzzerMetrics) -> Option<Duration> {
+    metrics
+        .get::<u64>("last_update")
+        .filter(|&ts| ts > 0)
+        .map(|ts| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .saturating_sub(Duration::from_secs(ts))
+        })
+}
+
+/// Instance directories of the campaign in `output_dir` along with their names
+///
+/// Multi-target campaigns keep their instances one level deeper in a subdirectory per target,
+/// these are named `<target>/<instance>`.
+pub fn instance_dirs(output_dir: &Path) -> Vec<(String, PathBuf)> {
+    instance_dirs_with(output_dir, STATS_FILE)
+}
+
+/// Instance directories identified by a `stats_file` instead of `STATS_FILE`, see `instance_dirs`
+pub fn instance_dirs_with(output_dir: &Path, stats_file: &str) -> Vec<(String, PathBuf)> {
+    let is_instance = |path: &Path| path.join(stats_file).is_file();
+    let mut dirs = Vec::new();
+    let Ok(entries) = fs::read_dir(output_dir) else {
+        return dirs;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if is_instance(&path) {
+            dirs.push((name, path));
+        } else if let Ok(nested) = fs::read_dir(&path) {
+            dirs.extend(
+                nested
+                    .flatten()
+                    .filter(|e| is_instance(&e.path()))
+                    .map(|e| {
+                        (
+                            format!("{name}/{}", e.file_name().to_string_lossy()),
+                            e.path(),
+                        )
+                    }),
+            );
+        }
+    }
+    dirs
+}
+
+/// Short distinct labels for the campaigns in `output_dirs`
+///
+/// Campaigns are labeled by their directory name, the full path is used where names collide.
+pub fn campaign_labels(output_dirs: &[PathBuf]) -> Vec<String> {
+    let name = |dir: &PathBuf| {
+        dir.file_name().map_or_else(
+            || dir.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        )
+    };
+    output_dirs
+        .iter()
+        .map(|dir| {
+            let label = name(dir);
+            if output_dirs
+                .iter()
+                .filter(|other| name(other) == label)
+                .count()
+                > 1
+            {
+                dir.display().to_string()
+            } else {
+                label
+            }
+        })
+        .collect()
+}
+
+/// Target binary of an instance, the first line of the `cmdline` file `AFL++` writes
+fn instance_binary(instance_dir: &Path) -> Option<PathBuf> {
+    fs::read_to_string(instance_dir.join("cmdline"))
+        .ok()?
+        .lines()
+        .next()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tui::session::SharedCampaignData;
+    use crate::tui::stability::DEFAULT_STABILITY_DROP;
+    use std::alloc::{GlobalAlloc, Layout, System as SystemAllocator};
+    use std::cell::Cell;
     use std::fs::File;
     use std::io::Write;
     use tempfile::TempDir;
 
+    thread_local! {
+        /// Bytes allocated minus bytes freed by the current thread
+        static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+    }
+
+    fn track(delta: isize) {
+        let _ = LIVE_BYTES.try_with(|live| live.set(live.get() + delta));
+    }
+
+    /// Counts the live heap bytes per thread so tests can assert on steady-state memory
+    struct CountingAllocator;
+
+    #[allow(clippy::cast_possible_wrap)]
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            track(layout.size() as isize);
+            SystemAllocator.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            track(-(layout.size() as isize));
+            SystemAllocator.dealloc(ptr, layout);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            track(new_size as isize - layout.size() as isize);
+            SystemAllocator.realloc(ptr, layout, new_size)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
     const MOCK_STATS_CONTENT: &str = r#"
         fuzzer_pid : 1234
         execs_done : 1000000
@@ -539,6 +1391,313 @@ mod tests {
         afl_version : 4.05c
     "#;
 
+    #[test]
+    fn test_unchanged_stats_are_not_reparsed() {
+        let temp_dir = TempDir::new().unwrap();
+        let stats = format!("fuzzer_pid : {}\nexecs_done : 1000\n", std::process::id());
+        for name in ["fuzzer01", "fuzzer02", "fuzzer03"] {
+            let dir = temp_dir.path().join(name);
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("fuzzer_stats"), &stats).unwrap();
+        }
+
+        let campaign_data = CampaignData::new();
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, campaign_data);
+        assert_eq!(fetcher.collect_session_data().stats_parsed, 3);
+        assert_eq!(fetcher.campaign_data.instances.len(), 3);
+
+        fs::write(
+            temp_dir.path().join("fuzzer02/fuzzer_stats"),
+            format!("{stats}execs_per_sec : 42\n"),
+        )
+        .unwrap();
+        let data = fetcher.collect_session_data();
+        assert_eq!(data.stats_parsed, 1);
+        assert_eq!(data.instances.len(), 3);
+        assert!(data
+            .instances
+            .iter()
+            .any(|i| i.name == "fuzzer02" && (i.execs_per_sec - 42.0).abs() < f64::EPSILON));
+    }
+
+    #[test]
+    fn test_build_mismatch_detection() {
+        let temp_dir = TempDir::new().unwrap();
+        let binary = temp_dir.path().join("target");
+        fs::write(&binary, "old build").unwrap();
+        BuildIdRecord::new([binary.as_path()])
+            .save(temp_dir.path())
+            .unwrap();
+
+        let stats = format!("fuzzer_pid : {}\n", std::process::id());
+        for name in ["fuzzer01", "fuzzer02"] {
+            let dir = temp_dir.path().join(name);
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("fuzzer_stats"), &stats).unwrap();
+            fs::write(dir.join("cmdline"), format!("{}\n@@\n", binary.display())).unwrap();
+        }
+
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new());
+        let data = fetcher.collect_session_data();
+        assert!(data.instances.iter().all(|i| !i.build_mismatch));
+        assert!(data.instances.iter().all(|i| i.build_id.is_some()));
+
+        // Rebuilt with a different size, so the cached id is invalidated
+        fs::write(&binary, "new build!").unwrap();
+        let data = fetcher.collect_session_data();
+        assert!(data.instances.iter().all(|i| i.build_mismatch));
+        assert!(data
+            .logs
+            .join("\n", false)
+            .contains("the campaign was launched with"));
+    }
+
+    #[test]
+    fn test_steady_state_memory_with_many_instances() {
+        const INSTANCES: usize = 256;
+        const CYCLES: usize = 100;
+        const WARMUP: usize = 10;
+
+        let temp_dir = TempDir::new().unwrap();
+        let stats = format!(
+            "fuzzer_pid : {}\nexecs_done : 1000\nexecs_per_sec : 10.0\nstability : 100.00%\n",
+            std::process::id()
+        );
+        for i in 0..INSTANCES {
+            let dir = temp_dir.path().join(format!("fuzzer{i:03}"));
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("fuzzer_stats"), &stats).unwrap();
+            if i % 64 == 0 {
+                fs::create_dir(dir.join("crashes")).unwrap();
+                for id in 0..25 {
+                    let name =
+                        format!("id:{id:06},sig:11,src:000000,time:{id},execs:1,op:havoc,rep:2");
+                    fs::write(dir.join("crashes").join(name), "crash").unwrap();
+                }
+            }
+        }
+
+        let shared = SharedCampaignData::new();
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new());
+        let mut warm_live_bytes = 0;
+        for cycle in 0..CYCLES {
+            shared.publish(fetcher.collect_session_data());
+            if cycle == WARMUP {
+                warm_live_bytes = LIVE_BYTES.with(Cell::get);
+            }
+        }
+        let growth = LIVE_BYTES.with(Cell::get) - warm_live_bytes;
+
+        let data = shared.lock();
+        assert_eq!(shared.generation(), CYCLES as u64);
+        assert_eq!(data.instances.len(), INSTANCES);
+        // Four instances with 25 crashes each fit into the backlog
+        assert_eq!(data.last_crashes.len(), 100);
+        assert_eq!(data.last_crashes[0].time, 24);
+        // A per-cycle copy of the instance list alone would leak several hundred KiB here
+        assert!(growth < 128 * 1024, "heap grew by {growth} bytes");
+    }
+
+    #[test]
+    fn test_instance_dirs_of_multi_target_campaign() {
+        let temp_dir = TempDir::new().unwrap();
+        for dir in ["main", "libpng/main", "libpng/s1", "zlib/main", ".aflr"] {
+            let dir = temp_dir.path().join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            if !dir.ends_with(".aflr") {
+                fs::write(dir.join("fuzzer_stats"), "execs_done : 1\n").unwrap();
+            }
+        }
+        // Queue directories of a single target instance are not descended into
+        fs::create_dir_all(temp_dir.path().join("main/queue")).unwrap();
+
+        let mut names: Vec<String> = instance_dirs(temp_dir.path())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        assert_eq!(names, ["libpng/main", "libpng/s1", "main", "zlib/main"]);
+    }
+
+    #[test]
+    fn test_fetcher_over_several_campaigns() {
+        let temp_dir = TempDir::new().unwrap();
+        let dirs: Vec<PathBuf> = ["a/out", "b/out", "c/libpng"]
+            .iter()
+            .map(|dir| temp_dir.path().join(dir))
+            .collect();
+        for dir in &dirs {
+            let instance = dir.join("main");
+            fs::create_dir_all(&instance).unwrap();
+            fs::write(instance.join("fuzzer_stats"), "execs_done : 1\n").unwrap();
+        }
+
+        let labels = campaign_labels(&dirs);
+        assert_eq!(labels[0], dirs[0].display().to_string());
+        assert_eq!(labels[2], "libpng");
+
+        let fetcher = DataFetcher::for_campaigns(&dirs, CampaignData::new());
+        let names: Vec<String> = fetcher
+            .campaign_instances()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names.len(), 3);
+        assert!(names.contains(&"libpng/main".to_string()));
+        assert!(names.contains(&format!("{}/main", dirs[1].display())));
+    }
+
+    #[test]
+    fn test_startup_grace() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new())
+            .with_startup_grace(Duration::from_secs(3600));
+        fetcher.collect_session_data();
+        let logs = fetcher.campaign_data.logs.join("\n", false);
+        assert!(!logs.contains("No fuzzers alive"), "{logs}");
+
+        // The first fuzzer writes its PID while the TUI already runs
+        let instance = temp_dir.path().join("main");
+        fs::create_dir(&instance).unwrap();
+        fs::write(
+            instance.join("fuzzer_stats"),
+            format!("fuzzer_pid : {}", std::process::id()),
+        )
+        .unwrap();
+        let data = fetcher.collect_session_data();
+        assert_eq!(data.fuzzer_pids, vec![std::process::id()]);
+        assert_eq!(data.fuzzers_alive.len(), 1);
+        assert!(!data.logs.join("\n", false).contains("No fuzzers alive"));
+
+        // Without a grace period nobody alive is reported right away
+        let empty = TempDir::new().unwrap();
+        let mut fetcher = DataFetcher::new(empty.path(), None, CampaignData::new())
+            .with_startup_grace(Duration::ZERO);
+        let data = fetcher.collect_session_data();
+        assert!(data.logs.join("\n", false).contains("No fuzzers alive"));
+    }
+
+    #[test]
+    fn test_custom_stats_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let instance = temp_dir.path().join("main");
+        fs::create_dir(&instance).unwrap();
+        fs::write(
+            instance.join("fork_stats"),
+            format!(
+                "fuzzer_pid : {}\nexecs_done : 1000\ncorpus_count : 7",
+                std::process::id()
+            ),
+        )
+        .unwrap();
+
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new())
+            .with_stats_file("fork_stats");
+        let data = fetcher.collect_session_data();
+        assert_eq!(data.fuzzer_pids, vec![std::process::id()]);
+        assert_eq!(data.instances.len(), 1);
+        assert_eq!(data.corpus.cum, 7);
+        assert_eq!(instance_dirs_with(temp_dir.path(), "fork_stats").len(), 1);
+        assert!(instance_dirs(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_alive_pids() {
+        let temp_dir = TempDir::new().unwrap();
+        let alive = temp_dir.path().join("alive");
+        let dead = temp_dir.path().join("dead");
+        fs::create_dir(&alive).unwrap();
+        fs::create_dir(&dead).unwrap();
+        fs::write(
+            alive.join("fuzzer_stats"),
+            format!("fuzzer_pid : {}", std::process::id()),
+        )
+        .unwrap();
+        fs::write(
+            dead.join("fuzzer_stats"),
+            format!("fuzzer_pid : {}", u32::MAX),
+        )
+        .unwrap();
+
+        assert_eq!(
+            DataFetcher::alive_pids(temp_dir.path()),
+            vec![std::process::id()]
+        );
+        assert!(DataFetcher::alive_pids(&temp_dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_remote_liveness() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        // PIDs of another host, neither refers to a local process
+        for (name, pid, last_update) in [("fresh", u32::MAX, now - 30), ("stale", 1, now - 600)] {
+            let instance = temp_dir.path().join(name);
+            fs::create_dir(&instance).unwrap();
+            fs::write(
+                instance.join("fuzzer_stats"),
+                format!("fuzzer_pid : {pid}\nlast_update : {last_update}\nexecs_done : 1\n"),
+            )
+            .unwrap();
+        }
+
+        let mut fetcher = DataFetcher::for_remote(
+            temp_dir.path(),
+            Duration::from_secs(180),
+            CampaignData::new(),
+        );
+        assert_eq!(fetcher.campaign_data.fuzzers_alive, vec![u32::MAX as usize]);
+        assert_eq!(fetcher.campaign_data.fuzzers_started, 2);
+        fetcher.collect_session_data();
+        assert_eq!(fetcher.campaign_data.instances.len(), 1);
+        assert_eq!(fetcher.instance_states["fresh"], InstanceState::Running);
+        assert_eq!(fetcher.instance_states["stale"], InstanceState::Finished);
+    }
+
+    #[test]
+    fn test_stalled_instances() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        // Both refer to this live process, but only one keeps updating its stats
+        for (name, last_update, execs_per_sec) in
+            [("fresh", now - 30, 100), ("frozen", now - 3600, 900)]
+        {
+            let instance = temp_dir.path().join(name);
+            fs::create_dir(&instance).unwrap();
+            fs::write(
+                instance.join("fuzzer_stats"),
+                format!(
+                    "fuzzer_pid : {}\nlast_update : {last_update}\nexecs_done : 1000\nexecs_per_sec : {execs_per_sec}\n",
+                    std::process::id()
+                ),
+            )
+            .unwrap();
+        }
+
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new())
+            .with_stall_threshold(Duration::from_secs(600));
+        let data = fetcher.collect_session_data();
+        assert_eq!(data.stalled_count(), 1);
+        assert!(data
+            .instances
+            .iter()
+            .any(|i| i.name == "frozen" && i.stalled));
+        assert!((data.executions.per_sec.avg - 100.0).abs() < f64::EPSILON);
+        assert_eq!(data.executions.count.cum, 2000);
+        assert!(data
+            .logs
+            .iter()
+            .any(|entry| entry.to_string().contains("Fuzzer frozen: stalled")));
+        assert_eq!(fetcher.instance_states["frozen"], InstanceState::Stalled);
+        assert_eq!(fetcher.instance_states["fresh"], InstanceState::Running);
+    }
+
     #[test]
     fn test_fuzzer_metrics_parsing() {
         let metrics = FuzzerMetrics::parse(MOCK_STATS_CONTENT);
@@ -617,15 +1776,15 @@ mod tests {
     #[test]
     fn test_stats_calculation_and_stability() {
         let temp_dir = TempDir::new().unwrap();
-        let mut campaign_data = CampaignData::new();
+        let campaign_data = CampaignData::new();
 
         let fuzzer_stats = vec![
-            ("fuzzer01", "100.00"),
-            ("fuzzer02", "98.50"),
-            ("fuzzer03", "99.75"),
+            ("fuzzer01", "100.00", 2700),
+            ("fuzzer02", "98.50", 2750),
+            ("fuzzer03", "99.75", 2800),
         ];
 
-        for (fuzzer_name, stability) in &fuzzer_stats {
+        for (fuzzer_name, stability, edges) in &fuzzer_stats {
             let stats_dir = temp_dir.path().join(fuzzer_name);
             fs::create_dir(&stats_dir).unwrap();
             let stats_content = format!(
@@ -637,8 +1796,10 @@ mod tests {
                 pending_total : 500
                 stability : {}%
                 bitmap_cvg : 45.5%
+                edges_found : {}
+                total_edges : 6000
                 "#,
-                stability
+                stability, edges
             );
             let stats_file = stats_dir.join("fuzzer_stats");
             File::create(&stats_file)
@@ -647,7 +1808,7 @@ mod tests {
                 .unwrap();
         }
 
-        let mut fetcher = DataFetcher::new(temp_dir.path(), None, &mut campaign_data);
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, campaign_data);
         fetcher.campaign_data.fuzzers_alive = vec![1234];
         fetcher.process_fuzzer_directories();
         fetcher.calculate_averages();
@@ -658,12 +1819,26 @@ mod tests {
         assert!((fetcher.campaign_data.stability.max - 100.0).abs() < f64::EPSILON);
         assert!((fetcher.campaign_data.stability.min - 98.50).abs() < f64::EPSILON);
         assert!((fetcher.campaign_data.stability.avg - 99.25).abs() < 0.01);
+
+        assert_eq!(fetcher.campaign_data.edges.found.min, 2700);
+        assert_eq!(fetcher.campaign_data.edges.found.max, 2800);
+        assert_eq!(fetcher.campaign_data.edges.found.avg, 2750);
+        assert_eq!(fetcher.campaign_data.edges.total, 6000);
+
+        let names: Vec<_> = fetcher
+            .campaign_data
+            .instances
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect();
+        assert_eq!(names, ["fuzzer01", "fuzzer02", "fuzzer03"]);
+        assert!((fetcher.campaign_data.instances[0].execs_per_sec - 277.77).abs() < 0.01);
     }
 
     #[test]
     fn test_campaign_data_lifecycle() {
         let temp_dir = TempDir::new().unwrap();
-        let mut campaign_data = CampaignData::new();
+        let campaign_data = CampaignData::new();
 
         let stats_content = r#"
             fuzzer_pid : 1234
@@ -685,7 +1860,7 @@ mod tests {
             .write_all(stats_content.as_bytes())
             .unwrap();
 
-        let mut fetcher = DataFetcher::new(temp_dir.path(), None, &mut campaign_data);
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, campaign_data);
 
         assert!(fetcher.campaign_data.start_time.is_none());
         assert!(fetcher.campaign_data.total_run_time < Duration::from_secs(5));
@@ -704,7 +1879,7 @@ mod tests {
     #[test]
     fn test_time_without_finds_direct_usage() {
         let temp_dir = TempDir::new().unwrap();
-        let mut campaign_data = CampaignData::new();
+        let campaign_data = CampaignData::new();
 
         let stats_content = r#"
         fuzzer_pid : 1234
@@ -719,7 +1894,7 @@ mod tests {
             .write_all(stats_content.as_bytes())
             .unwrap();
 
-        let mut fetcher = DataFetcher::new(temp_dir.path(), None, &mut campaign_data);
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, campaign_data);
         fetcher.campaign_data.fuzzers_alive = vec![1234];
         fetcher.process_fuzzer_directories();
 
@@ -727,10 +1902,188 @@ mod tests {
         assert_eq!(fetcher.campaign_data.time_without_finds.min, 341);
     }
 
+    #[test]
+    fn test_estimate_cycle_time() {
+        assert_eq!(
+            DataFetcher::estimate_cycle_time(1000, 256.0),
+            Some(Duration::from_secs(1000))
+        );
+        assert_eq!(
+            DataFetcher::estimate_cycle_time(0, 100.0),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(DataFetcher::estimate_cycle_time(1000, 0.0), None);
+        assert_eq!(DataFetcher::estimate_cycle_time(1000, f64::NAN), None);
+    }
+
+    #[test]
+    fn test_cycles_without_finds_ratio() {
+        let temp_dir = TempDir::new().unwrap();
+        for (name, done, wo_finds) in [("fuzzer01", 10, 8), ("fuzzer02", 30, 12)] {
+            let dir = temp_dir.path().join(name);
+            fs::create_dir(&dir).unwrap();
+            fs::write(
+                dir.join("fuzzer_stats"),
+                format!("fuzzer_pid : 1234\ncycles_done : {done}\ncycles_wo_finds : {wo_finds}\n"),
+            )
+            .unwrap();
+        }
+
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new());
+        fetcher.campaign_data.fuzzers_alive = vec![1234];
+        fetcher.calculate_averages();
+        assert!(fetcher.campaign_data.cycles.wo_finds_ratio.abs() < f64::EPSILON);
+
+        fetcher.process_fuzzer_directories();
+        fetcher.calculate_averages();
+        assert_eq!(fetcher.campaign_data.cycles.done.cum, 40);
+        assert!((fetcher.campaign_data.cycles.wo_finds_ratio - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_starved_main_instance() {
+        let temp_dir = TempDir::new().unwrap();
+        let write = |name: &str, role: &str, execs_per_sec: f64| {
+            let dir = temp_dir.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("fuzzer_stats"),
+                format!(
+                    "fuzzer_pid : 1234\nexecs_per_sec : {execs_per_sec}\n\
+                     command_line : afl-fuzz -i in -o out {role} {name} -- ./target_asan @@\n"
+                ),
+            )
+            .unwrap();
+        };
+        write("m_target", "-M", 40.0);
+        write("s1_target", "-S", 900.0);
+        write("s2_target", "-S", 1100.0);
+
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new());
+        fetcher.campaign_data.fuzzers_alive = vec![1234];
+        let collect = |fetcher: &mut DataFetcher| {
+            fetcher.campaign_data.clear();
+            fetcher.process_fuzzer_directories();
+            fetcher.check_main_throughput();
+        };
+        for _ in 0..STARVATION_CYCLES - 1 {
+            collect(&mut fetcher);
+        }
+        assert!(fetcher.campaign_data.instances[0].main);
+        assert!(!fetcher.campaign_data.instances[1].main);
+        assert!(fetcher.campaign_data.starved_main.is_none());
+
+        collect(&mut fetcher);
+        let starved = fetcher.campaign_data.starved_main.clone().unwrap();
+        assert_eq!(starved.instance, "m_target");
+        let last_log = |fetcher: &DataFetcher| {
+            fetcher
+                .campaign_data
+                .logs
+                .iter()
+                .last()
+                .unwrap()
+                .message
+                .clone()
+        };
+        assert!(last_log(&fetcher).contains("--san-on-secondary"));
+
+        write("m_target", "-M", 800.0);
+        collect(&mut fetcher);
+        assert!(fetcher.campaign_data.starved_main.is_none());
+        assert_eq!(
+            last_log(&fetcher),
+            "Fuzzer m_target: main instance caught up with the secondaries"
+        );
+    }
+
+    #[test]
+    fn test_stability_collapse() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("m_target");
+        fs::create_dir_all(&dir).unwrap();
+        let write = |stability: f64| {
+            fs::write(
+                dir.join("fuzzer_stats"),
+                format!("fuzzer_pid : 1234\nstability : {stability:.2}%\n"),
+            )
+            .unwrap();
+        };
+
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new())
+            .with_stability_drop(DEFAULT_STABILITY_DROP);
+        fetcher.campaign_data.fuzzers_alive = vec![1234];
+        let collect = |fetcher: &mut DataFetcher| {
+            fetcher.campaign_data.clear();
+            fetcher.process_fuzzer_directories();
+            fetcher.calculate_averages();
+            fetcher.check_stability();
+        };
+        write(99.8);
+        for _ in 0..STABILITY_BASELINE_CYCLES {
+            collect(&mut fetcher);
+        }
+        assert!(fetcher.campaign_data.stability_collapse.is_none());
+
+        write(87.5);
+        collect(&mut fetcher);
+        let collapse = fetcher.campaign_data.stability_collapse.clone().unwrap();
+        assert!((collapse.baseline - 99.8).abs() < 0.01);
+        // Only the first cycle of a collapse is logged
+        collect(&mut fetcher);
+        assert!(fetcher.campaign_data.stability_collapse.is_some());
+        assert_eq!(
+            fetcher
+                .campaign_data
+                .logs
+                .iter()
+                .filter(|log| log.message.starts_with("Stability collapsed"))
+                .count(),
+            1
+        );
+
+        write(99.5);
+        collect(&mut fetcher);
+        assert!(fetcher.campaign_data.stability_collapse.is_none());
+        assert_eq!(
+            fetcher.campaign_data.logs.iter().last().unwrap().message,
+            "Stability recovered to 99.50% (baseline 99.80%)"
+        );
+    }
+
+    #[test]
+    fn test_pending_favorites_trend() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("m_target");
+        fs::create_dir_all(&dir).unwrap();
+        let pid = std::process::id();
+        let write = |pending: usize| {
+            fs::write(
+                dir.join("fuzzer_stats"),
+                format!("fuzzer_pid : {pid}\npending_favs : {pending}\n"),
+            )
+            .unwrap();
+        };
+
+        write(0);
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, CampaignData::new());
+        let mut trends = Vec::new();
+        // Distinct lengths keep the stats cache from serving the previous content
+        for pending in [400, 25, 25, 3000] {
+            write(pending);
+            trends.push(fetcher.collect_session_data().pending.favorites_trend);
+        }
+        assert_eq!(
+            trends,
+            [Trend::Steady, Trend::Falling, Trend::Steady, Trend::Rising]
+        );
+        assert_eq!(Trend::Falling.arrow(), "↓");
+    }
+
     #[test]
     fn test_average_calculations() {
         let temp_dir = TempDir::new().unwrap();
-        let mut campaign_data = CampaignData::new();
+        let campaign_data = CampaignData::new();
 
         // Test data with varying values to exercise both types of averages
         let fuzzer_stats = vec![
@@ -761,7 +2114,7 @@ mod tests {
                 .unwrap();
         }
 
-        let mut fetcher = DataFetcher::new(temp_dir.path(), None, &mut campaign_data);
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, campaign_data);
         fetcher.campaign_data.fuzzers_alive = vec![1234];
         fetcher.process_fuzzer_directories();
         fetcher.calculate_averages();
@@ -784,7 +2137,7 @@ mod tests {
     #[test]
     fn test_zero_value_handling() {
         let temp_dir = TempDir::new().unwrap();
-        let mut campaign_data = CampaignData::new();
+        let campaign_data = CampaignData::new();
 
         let stats_content = r#"
             fuzzer_pid : 1234
@@ -803,7 +2156,7 @@ mod tests {
             .write_all(stats_content.as_bytes())
             .unwrap();
 
-        let mut fetcher = DataFetcher::new(temp_dir.path(), None, &mut campaign_data);
+        let mut fetcher = DataFetcher::new(temp_dir.path(), None, campaign_data);
         fetcher.campaign_data.fuzzers_alive = vec![1234];
         fetcher.process_fuzzer_directories();
         fetcher.calculate_averages();
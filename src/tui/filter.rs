@@ -0,0 +1,138 @@
+use crate::tui::session::{CrashInfoDetails, LATEST_SOLUTIONS};
+
+/// Signal names AFL++ encodes as numbers in the `sig:` part of a solution's file name
+const SIGNALS: [(&str, &str); 15] = [
+    ("HUP", "01"),
+    ("INT", "02"),
+    ("QUIT", "03"),
+    ("ILL", "04"),
+    ("TRAP", "05"),
+    ("ABRT", "06"),
+    ("BUS", "07"),
+    ("FPE", "08"),
+    ("KILL", "09"),
+    ("USR1", "10"),
+    ("SEGV", "11"),
+    ("USR2", "12"),
+    ("PIPE", "13"),
+    ("ALRM", "14"),
+    ("TERM", "15"),
+];
+
+/// Narrows the crash and hang panels down to an instance or a signal
+///
+/// The query matches instances whose name contains it, e.g. `s3_`, and solutions whose signal
+/// it names, e.g. `SIGSEGV`, `segv` or `11`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolutionFilter {
+    query: String,
+}
+
+impl SolutionFilter {
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+    }
+
+    /// Whether `solution` passes the filter, everything passes an empty one
+    pub fn matches(&self, solution: &CrashInfoDetails) -> bool {
+        if self.query.is_empty() || solution.fuzzer_name.contains(&self.query) {
+            return true;
+        }
+        let (Some(wanted), Some(sig)) = (self.signal(), solution.sig.as_deref()) else {
+            return false;
+        };
+        sig.trim_start_matches('0') == wanted.trim_start_matches('0')
+    }
+
+    /// The signal number the query names, if any
+    fn signal(&self) -> Option<&str> {
+        let query = self.query.trim();
+        if !query.is_empty() && query.chars().all(|c| c.is_ascii_digit()) {
+            return Some(query);
+        }
+        let upper = query.to_ascii_uppercase();
+        let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+        SIGNALS
+            .iter()
+            .find(|(signal, _)| *signal == name)
+            .map(|(_, number)| *number)
+    }
+
+    /// The `LATEST_SOLUTIONS` newest of the newest-first `solutions` passing the filter
+    pub fn latest<'a>(&self, solutions: &'a [CrashInfoDetails]) -> Vec<&'a CrashInfoDetails> {
+        solutions
+            .iter()
+            .filter(|s| self.matches(s))
+            .take(LATEST_SOLUTIONS)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solution(fuzzer_name: &str, sig: Option<&str>) -> CrashInfoDetails {
+        CrashInfoDetails {
+            fuzzer_name: fuzzer_name.to_string(),
+            sig: sig.map(str::to_string),
+            ..CrashInfoDetails::default()
+        }
+    }
+
+    fn filter(query: &str) -> SolutionFilter {
+        let mut filter = SolutionFilter::default();
+        query.chars().for_each(|c| filter.push(c));
+        filter
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let segv = solution("s3_asan", Some("11"));
+        let abrt = solution("m_1", Some("06"));
+        let hang = solution("s3_cmplog", None);
+
+        assert!(SolutionFilter::default().matches(&abrt));
+        assert!(filter("s3_").matches(&segv));
+        assert!(filter("s3_").matches(&hang));
+        assert!(!filter("s3_").matches(&abrt));
+        for query in ["SIGSEGV", "segv", "11"] {
+            assert!(filter(query).matches(&segv), "{query}");
+            assert!(!filter(query).matches(&abrt), "{query}");
+        }
+        assert!(filter("SIGABRT").matches(&abrt));
+        assert!(filter("6").matches(&abrt));
+        assert!(!filter("SIGSEGV").matches(&hang));
+    }
+
+    #[test]
+    fn test_filter_latest() {
+        let solutions: Vec<CrashInfoDetails> = (0..3 * LATEST_SOLUTIONS)
+            .map(|i| solution(&format!("fuzzer{}", i % 3), Some("11")))
+            .collect();
+        let mut filter = filter("fuzzer1");
+        let latest = filter.latest(&solutions);
+        assert_eq!(latest.len(), LATEST_SOLUTIONS);
+        assert!(latest.iter().all(|s| s.fuzzer_name == "fuzzer1"));
+
+        filter.clear();
+        assert!(!filter.is_active());
+        assert_eq!(filter.latest(&solutions).len(), LATEST_SOLUTIONS);
+    }
+}
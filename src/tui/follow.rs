@@ -0,0 +1,151 @@
+use std::{
+    collections::{HashMap, HashSet},
+    os::unix::process::CommandExt,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use crate::tui::session::CrashInfoDetails;
+
+/// How long a new crash stays highlighted in the crash list
+pub const FLASH_DURATION: Duration = Duration::from_secs(5);
+
+/// Diffs the latest crashes between redraws to highlight the new ones for live triage
+#[derive(Debug, Default)]
+pub struct CrashFollower {
+    /// Crashes of the previous update, `None` until the first update of a view
+    seen: Option<HashSet<PathBuf>>,
+    /// View the crashes were seen in, switching views must not flag the other campaign's crashes
+    view: usize,
+    flashing: HashMap<PathBuf, Instant>,
+    /// Shell command the path of every new crash is passed to
+    viewer: Option<String>,
+}
+
+impl CrashFollower {
+    pub fn new(viewer: Option<String>) -> Self {
+        Self {
+            viewer,
+            ..Self::default()
+        }
+    }
+
+    /// Records the latest `crashes` of `view` and returns the ones that were not listed before
+    ///
+    /// The first update of a view only remembers the crashes, they are not new to the user.
+    pub fn update(&mut self, view: usize, crashes: &[CrashInfoDetails]) -> Vec<PathBuf> {
+        if view != self.view {
+            self.view = view;
+            self.seen = None;
+            self.flashing.clear();
+        }
+        let current: HashSet<PathBuf> = crashes.iter().map(|c| c.file_path.clone()).collect();
+        let new: Vec<PathBuf> = self.seen.as_ref().map_or_else(Vec::new, |seen| {
+            crashes
+                .iter()
+                .filter(|c| !seen.contains(&c.file_path))
+                .map(|c| c.file_path.clone())
+                .collect()
+        });
+
+        let now = Instant::now();
+        for path in &new {
+            self.flashing.insert(path.clone(), now);
+        }
+        self.seen = Some(current);
+        new
+    }
+
+    /// Whether `crash` is new enough to be highlighted
+    pub fn is_flashing(&self, crash: &Path) -> bool {
+        self.flashing
+            .get(crash)
+            .is_some_and(|since| since.elapsed() < FLASH_DURATION)
+    }
+
+    /// Number of highlighted crashes
+    pub fn flashing_count(&self) -> usize {
+        self.flashing
+            .values()
+            .filter(|since| since.elapsed() < FLASH_DURATION)
+            .count()
+    }
+
+    /// Forgets highlights that ran out, returns whether any did so the list gets redrawn
+    pub fn expire(&mut self) -> bool {
+        let before = self.flashing.len();
+        self.flashing
+            .retain(|_, since| since.elapsed() < FLASH_DURATION);
+        self.flashing.len() != before
+    }
+
+    /// Opens `crash` in the configured viewer, detached from the TUI's terminal
+    pub fn open(&self, crash: &Path) {
+        let Some(viewer) = &self.viewer else {
+            return;
+        };
+        // A broken viewer must not take down the monitoring
+        let _ = Command::new("sh")
+            .arg("-c")
+            .arg(format!("{viewer} \"$1\""))
+            .arg("sh")
+            .arg(crash)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, thread};
+    use tempfile::TempDir;
+
+    fn crash(path: &str) -> CrashInfoDetails {
+        CrashInfoDetails {
+            file_path: PathBuf::from(path),
+            ..CrashInfoDetails::default()
+        }
+    }
+
+    #[test]
+    fn test_new_crashes_are_flagged() {
+        let mut follower = CrashFollower::new(None);
+        let first = [crash("a"), crash("b")];
+        assert!(follower.update(0, &first).is_empty());
+        assert_eq!(follower.flashing_count(), 0);
+
+        let second = [crash("c"), crash("a"), crash("b")];
+        assert_eq!(follower.update(0, &second), [PathBuf::from("c")]);
+        assert!(follower.is_flashing(Path::new("c")));
+        assert!(!follower.is_flashing(Path::new("a")));
+        assert!(!follower.expire());
+
+        // Another campaign's crashes are not new just because the view changed
+        assert!(follower.update(1, &first).is_empty());
+        assert_eq!(follower.flashing_count(), 0);
+    }
+
+    #[test]
+    fn test_viewer_receives_crash_path() {
+        let tmp = TempDir::new().unwrap();
+        let out = tmp.path().join("opened");
+        fs::create_dir(&out).unwrap();
+        let crash = tmp.path().join("id:000000,sig:11 with space");
+        fs::write(&crash, "boom").unwrap();
+
+        CrashFollower::new(Some(format!("cp -t {}", out.display()))).open(&crash);
+        let copied = out.join(crash.file_name().unwrap());
+        for _ in 0..100 {
+            if copied.exists() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(fs::read_to_string(copied).unwrap(), "boom");
+    }
+}
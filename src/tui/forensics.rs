@@ -0,0 +1,222 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::tui::control::RUNTIME_DIR;
+
+/// Directory in `RUNTIME_DIR` the session runner templates write the exit status of each
+/// fuzzer into
+const EXIT_STATUS_DIR: &str = "exit";
+
+/// Shell exit codes above this value indicate that the process was terminated by a signal
+const SIGNAL_EXIT_BASE: i32 = 128;
+
+/// Time without a `fuzzer_stats` update after which a live instance counts as stalled,
+/// e.g. because its target deadlocked in the forkserver
+pub const DEFAULT_STALL_THRESHOLD: Duration = Duration::from_secs(15 * 60);
+
+/// End state of a single fuzzer instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceState {
+    /// The fuzzer process is still alive
+    Running,
+    /// The fuzzer process is alive but stopped updating its `fuzzer_stats`
+    Stalled,
+    /// The fuzzer terminated on its own, e.g. via an exit condition like `-V` or `AFL_EXIT_WHEN_DONE`
+    Finished,
+    /// The fuzzer was terminated by a signal
+    Killed,
+    /// The fuzzer died without a proper teardown
+    DiedAbnormally,
+}
+
+impl fmt::Display for InstanceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Running => write!(f, "running"),
+            Self::Stalled => write!(f, "stalled"),
+            Self::Finished => write!(f, "finished"),
+            Self::Killed => write!(f, "killed"),
+            Self::DiedAbnormally => write!(f, "died abnormally"),
+        }
+    }
+}
+
+/// Facts gathered about a single fuzzer instance that are used for classifying its end state
+#[derive(Debug, Clone, Default)]
+pub struct InstanceFacts {
+    /// Whether the PID from `fuzzer_stats` still refers to a live process
+    pub pid_alive: bool,
+    /// Time passed since the last `fuzzer_stats` update, `None` if it was never updated
+    pub last_update_age: Option<Duration>,
+    /// Whether a `.cur_input` file was left behind in the instance directory
+    pub has_cur_input: bool,
+    /// Exit status as reported by the session runner, if available
+    pub exit_code: Option<i32>,
+    /// Total executions reported in `fuzzer_stats`
+    pub execs_done: Option<u64>,
+    /// Age of the last `fuzzer_stats` update beyond which a live instance is stalled,
+    /// `None` disables the stall detection
+    pub stall_threshold: Option<Duration>,
+}
+
+impl InstanceFacts {
+    /// Classifies the end state of an instance based on the gathered facts
+    pub fn classify(&self) -> InstanceState {
+        if self.pid_alive {
+            let stalled = self
+                .stall_threshold
+                .zip(self.last_update_age)
+                .is_some_and(|(threshold, age)| age > threshold);
+            return if stalled {
+                InstanceState::Stalled
+            } else {
+                InstanceState::Running
+            };
+        }
+
+        match self.exit_code {
+            Some(0) => return InstanceState::Finished,
+            Some(code) if code > SIGNAL_EXIT_BASE => return InstanceState::Killed,
+            Some(_) => return InstanceState::DiedAbnormally,
+            None => {}
+        }
+
+        // An instance that never managed to write a single stats update did not get far
+        if self.last_update_age.is_none() || self.execs_done == Some(0) {
+            return InstanceState::DiedAbnormally;
+        }
+
+        if self.has_cur_input {
+            InstanceState::DiedAbnormally
+        } else {
+            InstanceState::Finished
+        }
+    }
+}
+
+/// Directory holding the exit statuses of the fuzzers writing into `output_dir`
+///
+/// Kept inside the campaign instead of a predictable path in `/tmp` other users could plant.
+pub fn exit_status_dir(output_dir: &Path) -> PathBuf {
+    output_dir.join(RUNTIME_DIR).join(EXIT_STATUS_DIR)
+}
+
+/// Path to the file the session runner stores the exit status of the process with `pid` in
+pub fn exit_status_path(output_dir: &Path, pid: u32) -> PathBuf {
+    exit_status_dir(output_dir).join(pid.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dead_facts() -> InstanceFacts {
+        InstanceFacts {
+            pid_alive: false,
+            last_update_age: Some(Duration::from_secs(30)),
+            has_cur_input: false,
+            exit_code: None,
+            execs_done: Some(1000),
+            stall_threshold: None,
+        }
+    }
+
+    #[test]
+    fn test_classify_running() {
+        let facts = InstanceFacts {
+            pid_alive: true,
+            has_cur_input: true,
+            ..dead_facts()
+        };
+        assert_eq!(facts.classify(), InstanceState::Running);
+    }
+
+    #[test]
+    fn test_classify_stalled() {
+        let facts = InstanceFacts {
+            pid_alive: true,
+            last_update_age: Some(Duration::from_secs(3600)),
+            stall_threshold: Some(DEFAULT_STALL_THRESHOLD),
+            ..dead_facts()
+        };
+        assert_eq!(facts.classify(), InstanceState::Stalled);
+
+        let fresh = InstanceFacts {
+            last_update_age: Some(Duration::from_secs(60)),
+            ..facts.clone()
+        };
+        assert_eq!(fresh.classify(), InstanceState::Running);
+
+        let disabled = InstanceFacts {
+            stall_threshold: None,
+            ..facts.clone()
+        };
+        assert_eq!(disabled.classify(), InstanceState::Running);
+
+        // Dead instances are never stalled, however old their stats are
+        let dead = InstanceFacts {
+            pid_alive: false,
+            ..facts
+        };
+        assert_eq!(dead.classify(), InstanceState::Finished);
+    }
+
+    #[test]
+    fn test_classify_finished() {
+        assert_eq!(dead_facts().classify(), InstanceState::Finished);
+
+        let facts = InstanceFacts {
+            exit_code: Some(0),
+            has_cur_input: true,
+            ..dead_facts()
+        };
+        assert_eq!(facts.classify(), InstanceState::Finished);
+    }
+
+    #[test]
+    fn test_classify_killed() {
+        for code in [130, 137, 143] {
+            let facts = InstanceFacts {
+                exit_code: Some(code),
+                ..dead_facts()
+            };
+            assert_eq!(facts.classify(), InstanceState::Killed);
+        }
+    }
+
+    #[test]
+    fn test_classify_died_abnormally() {
+        let cur_input_left = InstanceFacts {
+            has_cur_input: true,
+            ..dead_facts()
+        };
+        assert_eq!(cur_input_left.classify(), InstanceState::DiedAbnormally);
+
+        let error_exit = InstanceFacts {
+            exit_code: Some(1),
+            ..dead_facts()
+        };
+        assert_eq!(error_exit.classify(), InstanceState::DiedAbnormally);
+
+        let never_updated = InstanceFacts {
+            last_update_age: None,
+            ..dead_facts()
+        };
+        assert_eq!(never_updated.classify(), InstanceState::DiedAbnormally);
+
+        let no_execs = InstanceFacts {
+            execs_done: Some(0),
+            ..dead_facts()
+        };
+        assert_eq!(no_execs.classify(), InstanceState::DiedAbnormally);
+    }
+
+    #[test]
+    fn test_exit_status_path() {
+        assert_eq!(
+            exit_status_path(Path::new("/out"), 1234),
+            PathBuf::from("/out/.aflr/exit/1234")
+        );
+    }
+}
@@ -0,0 +1,230 @@
+const THOUSAND: f64 = 1_000.0;
+const MILLION: f64 = THOUSAND * THOUSAND;
+const BILLION: f64 = MILLION * THOUSAND;
+const TRILLION: f64 = BILLION * THOUSAND;
+
+/// Suffixes of the human-scaled number representation (short scale)
+pub const THOUSAND_SUFFIX: &str = "K";
+pub const MILLION_SUFFIX: &str = "M";
+pub const BILLION_SUFFIX: &str = "B";
+pub const TRILLION_SUFFIX: &str = "T";
+
+/// Separator used for grouping thousands in the raw number representation
+///
+/// This is intentionally independent of the system locale so that output stays comparable
+pub const THOUSANDS_SEPARATOR: char = ',';
+
+/// Threshold markers for number formatting
+#[derive(Debug)]
+enum NumberScale {
+    Base(f64),
+    Thousand(f64),
+    Million(f64),
+    Billion(f64),
+    Trillion(f64),
+}
+
+impl NumberScale {
+    fn from_f64(num: f64) -> Self {
+        match num {
+            n if n < THOUSAND => Self::Base(n),
+            n if n < MILLION => Self::Thousand(n / THOUSAND),
+            n if n < BILLION => Self::Million(n / MILLION),
+            n if n < TRILLION => Self::Billion(n / BILLION),
+            n => Self::Trillion(n / TRILLION),
+        }
+    }
+
+    fn format(&self) -> String {
+        match self {
+            Self::Base(n) => format!("{n:.2}"),
+            Self::Thousand(n) => format!("{n:.2}{THOUSAND_SUFFIX}"),
+            Self::Million(n) => format!("{n:.2}{MILLION_SUFFIX}"),
+            Self::Billion(n) => format!("{n:.2}{BILLION_SUFFIX}"),
+            Self::Trillion(n) => format!("{n:.2}{TRILLION_SUFFIX}"),
+        }
+    }
+}
+
+/// How numbers are rendered in the TUI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NumberFormat {
+    /// Scaled to a short representation, e.g. `1.50B`
+    #[default]
+    Human,
+    /// Exact integers with grouped thousands, e.g. `1,500,000,000`
+    Raw,
+}
+
+impl NumberFormat {
+    /// Returns the other formatting mode
+    #[must_use]
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Human => Self::Raw,
+            Self::Raw => Self::Human,
+        }
+    }
+
+    /// Formats an integer according to the mode
+    pub fn format_int(self, num: usize) -> String {
+        match self {
+            #[allow(clippy::cast_precision_loss)]
+            Self::Human => NumberScale::from_f64(num as f64).format(),
+            Self::Raw => group_thousands(&num.to_string()),
+        }
+    }
+
+    /// Formats a floating-point number according to the mode
+    ///
+    /// The raw representation is rounded to the nearest integer
+    pub fn format_float(self, num: f64) -> String {
+        match self {
+            Self::Human => NumberScale::from_f64(num).format(),
+            Self::Raw => {
+                let rounded = format!("{:.0}", num.round());
+                match rounded.strip_prefix('-') {
+                    Some(digits) => format!("-{}", group_thousands(digits)),
+                    None => group_thousands(&rounded),
+                }
+            }
+        }
+    }
+}
+
+/// Inserts a thousands separator into a string of digits
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(THOUSANDS_SEPARATOR);
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_scale_classification() {
+        assert!(matches!(NumberScale::from_f64(100.0), NumberScale::Base(_)));
+        assert!(matches!(
+            NumberScale::from_f64(1500.0),
+            NumberScale::Thousand(_)
+        ));
+        assert!(matches!(
+            NumberScale::from_f64(1_500_000.0),
+            NumberScale::Million(_)
+        ));
+        assert!(matches!(
+            NumberScale::from_f64(1_500_000_000.0),
+            NumberScale::Billion(_)
+        ));
+        assert!(matches!(
+            NumberScale::from_f64(1_500_000_000_000.0),
+            NumberScale::Trillion(_)
+        ));
+    }
+
+    #[test]
+    fn test_number_scale_formatting() {
+        let cases = vec![
+            (100.0, "100.00"),
+            (1500.0, "1.50K"),
+            (1_500_000.0, "1.50M"),
+            (1_500_000_000.0, "1.50B"),
+            (1_500_000_000_000.0, "1.50T"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(NumberScale::from_f64(input).format(), expected);
+        }
+    }
+
+    #[test]
+    fn test_format_float_human() {
+        let test_cases = vec![
+            (0.0, "0.00"),
+            (999.99, "999.99"),
+            (1000.0, "1.00K"),
+            (1234.5678, "1.23K"),
+            (1_000_000.0, "1.00M"),
+            (1_234_567.89, "1.23M"),
+            (1_000_000_000.0, "1.00B"),
+            (1_234_567_890.12, "1.23B"),
+            (1_000_000_000_000.0, "1.00T"),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(NumberFormat::Human.format_float(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_format_int_human() {
+        let test_cases = vec![
+            (0, "0.00"),
+            (999, "999.00"),
+            (1000, "1.00K"),
+            (1234, "1.23K"),
+            (1_000_000, "1.00M"),
+            (1_234_567, "1.23M"),
+            (1_000_000_000, "1.00B"),
+            (1_234_567_890, "1.23B"),
+            (1_000_000_000_000, "1.00T"),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(NumberFormat::Human.format_int(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_format_raw() {
+        let test_cases = vec![
+            (0, "0"),
+            (999, "999"),
+            (1000, "1,000"),
+            (123_456, "123,456"),
+            (1_500_000_000, "1,500,000,000"),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(NumberFormat::Raw.format_int(input), expected);
+        }
+
+        assert_eq!(NumberFormat::Raw.format_float(1234.5678), "1,235");
+        assert_eq!(NumberFormat::Raw.format_float(999.4), "999");
+        assert_eq!(NumberFormat::Raw.format_float(-1234.0), "-1,234");
+    }
+
+    #[test]
+    fn test_toggle() {
+        assert_eq!(NumberFormat::Human.toggle(), NumberFormat::Raw);
+        assert_eq!(NumberFormat::Raw.toggle(), NumberFormat::Human);
+    }
+
+    // Batch testing for number formatting consistency
+    #[test]
+    fn test_number_formatting_consistency() {
+        // Test that integer and float formatting are consistent
+        let test_cases = vec![
+            (1000, 1000.0),
+            (1_000_000, 1_000_000.0),
+            (1_000_000_000, 1_000_000_000.0),
+        ];
+
+        for fmt in [NumberFormat::Human, NumberFormat::Raw] {
+            for (int_val, float_val) in &test_cases {
+                assert_eq!(
+                    fmt.format_int(*int_val),
+                    fmt.format_float(*float_val),
+                    "Mismatch between int and float formatting for value: {int_val}"
+                );
+            }
+        }
+    }
+}
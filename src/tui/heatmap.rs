@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use ratatui::style::Color;
+
+use crate::tui::session::InstanceMetrics;
+use crate::utils::build_id::short_id;
+
+/// Glyph rendered for every instance
+pub const HEAT_CELL: &str = "■ ";
+/// Glyph rendered for instances that run a different build than the campaign was launched with
+pub const MISMATCH_CELL: &str = "✗ ";
+/// Glyph rendered for instances that stopped updating their stats
+pub const STALLED_CELL: &str = "◌ ";
+/// Minimum width of the nerd stats area before the heatmap is shown next to it
+pub const MIN_HEATMAP_AREA_WIDTH: u16 = 80;
+
+/// Median execs/s of all instances that are not stalled, `None` if there are none
+pub fn median_execs_per_sec(instances: &[InstanceMetrics]) -> Option<f64> {
+    let mut values: Vec<f64> = instances
+        .iter()
+        .filter(|i| !i.stalled)
+        .map(|i| i.execs_per_sec)
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// Color of an instance on a green to red scale by its execs/s relative to the fleet median
+pub fn heat_color(execs_per_sec: f64, median: f64) -> Color {
+    if median <= 0.0 {
+        return Color::Gray;
+    }
+    match execs_per_sec / median {
+        r if r >= 0.9 => Color::Green,
+        r if r >= 0.75 => Color::LightGreen,
+        r if r >= 0.5 => Color::Yellow,
+        r if r >= 0.25 => Color::LightRed,
+        _ => Color::Red,
+    }
+}
+
+/// Label for the build the instances run and whether it is a warning, `None` if no build id
+/// is known
+///
+/// Flags mixed builds if any instance differs from the launched build and otherwise shows a
+/// short suffix of the most common build id, i.e. the one of the main target
+pub fn build_label(instances: &[InstanceMetrics]) -> Option<(String, bool)> {
+    let mismatches = instances.iter().filter(|i| i.build_mismatch).count();
+    if mismatches > 0 {
+        return Some((format!("{mismatches} stale build(s)"), true));
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for id in instances.iter().filter_map(|i| i.build_id.as_deref()) {
+        *counts.entry(id).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        .map(|(id, _)| (format!("build ..{}", short_id(id)), false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instances(values: &[f64]) -> Vec<InstanceMetrics> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &execs_per_sec)| InstanceMetrics {
+                name: format!("s{i}"),
+                execs_per_sec,
+                ..InstanceMetrics::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median_execs_per_sec(&[]), None);
+        assert_eq!(
+            median_execs_per_sec(&instances(&[300.0, 100.0, 200.0])),
+            Some(200.0)
+        );
+        assert_eq!(
+            median_execs_per_sec(&instances(&[400.0, 100.0, 200.0, 300.0])),
+            Some(250.0)
+        );
+
+        let mut fleet = instances(&[300.0, 100.0, 200.0]);
+        fleet[1].stalled = true;
+        assert_eq!(median_execs_per_sec(&fleet), Some(250.0));
+    }
+
+    #[test]
+    fn test_heat_color() {
+        assert_eq!(heat_color(1200.0, 1000.0), Color::Green);
+        assert_eq!(heat_color(950.0, 1000.0), Color::Green);
+        assert_eq!(heat_color(800.0, 1000.0), Color::LightGreen);
+        assert_eq!(heat_color(600.0, 1000.0), Color::Yellow);
+        assert_eq!(heat_color(300.0, 1000.0), Color::LightRed);
+        assert_eq!(heat_color(10.0, 1000.0), Color::Red);
+        assert_eq!(heat_color(10.0, 0.0), Color::Gray);
+    }
+
+    #[test]
+    fn test_build_label() {
+        let mut fleet = instances(&[1.0, 1.0, 1.0]);
+        assert_eq!(build_label(&fleet), None);
+
+        for (instance, id) in fleet
+            .iter_mut()
+            .zip(["aaaa11112222", "bbbb33334444", "bbbb33334444"])
+        {
+            instance.build_id = Some(id.to_string());
+        }
+        assert_eq!(
+            build_label(&fleet),
+            Some(("build ..33334444".to_string(), false))
+        );
+
+        fleet[1].build_mismatch = true;
+        assert_eq!(
+            build_label(&fleet),
+            Some(("1 stale build(s)".to_string(), true))
+        );
+    }
+}
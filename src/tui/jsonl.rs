@@ -0,0 +1,105 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::tui::{anonymize::anonymize, session::CampaignData};
+
+/// A single line in the JSON Lines metrics stream
+#[derive(Serialize)]
+struct JsonlRecord<'a> {
+    /// Seconds since the UNIX epoch when the record was written
+    timestamp: u64,
+    #[serde(flatten)]
+    data: &'a CampaignData,
+}
+
+/// Append-only JSON Lines sink for campaign metrics
+///
+/// Writes one JSON object per collection tick, which makes it suitable for
+/// `tail -f | jq` or a named pipe that feeds an external collector.
+pub struct JsonlWriter {
+    file: File,
+    anonymize: bool,
+}
+
+impl JsonlWriter {
+    /// Opens `path` for appending, creating it if it does not exist
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened
+    pub fn new(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open JSONL output {}", path.display()))?;
+        Ok(Self {
+            file,
+            anonymize: false,
+        })
+    }
+
+    /// Replaces instance names, the banner and solution paths with stable pseudonyms
+    pub fn with_anonymize(mut self, anonymize: bool) -> Self {
+        self.anonymize = anonymize;
+        self
+    }
+
+    /// Appends `data` as a single JSON line and flushes it immediately
+    ///
+    /// # Errors
+    /// Returns an error if serialization or writing fails
+    pub fn write(&mut self, data: &CampaignData) -> Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let anonymized = self.anonymize.then(|| anonymize(data));
+        let data = anonymized.as_ref().unwrap_or(data);
+        let mut line = serde_json::to_string(&JsonlRecord { timestamp, data })?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_jsonl_appends_lines() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("metrics.jsonl");
+
+        let mut cdata = CampaignData::new();
+        cdata.fuzzers_started = 4;
+        cdata.crashes.cum = 2;
+
+        let mut writer = JsonlWriter::new(&path).unwrap();
+        writer.write(&cdata).unwrap();
+        writer.write(&cdata).unwrap();
+
+        // Re-opening must not truncate the existing history
+        JsonlWriter::new(&path).unwrap().write(&cdata).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(value["timestamp"].as_u64().is_some());
+            assert_eq!(value["fuzzers_started"], 4);
+            assert_eq!(value["crashes"]["cum"], 2);
+            assert!(value.get("logs").is_none());
+        }
+    }
+}
@@ -11,59 +11,180 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
     Terminal,
 };
-use std::{io, path::Path, sync::mpsc, thread, time::Duration};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
 
+pub mod anonymize;
+pub mod calibration;
+pub mod control;
 pub mod data_collection;
+pub mod filter;
+pub mod follow;
+pub mod forensics;
+pub mod format;
+pub mod heatmap;
+pub mod jsonl;
+pub mod progress;
+pub mod remote;
+pub mod retention;
 pub mod session;
+pub mod stability;
+pub mod starvation;
+pub mod top;
 
-use crate::tui::data_collection::DataFetcher;
-use crate::tui::session::{CampaignData, CrashInfoDetails};
+use crate::tui::control::ControlServer;
+use crate::tui::data_collection::{
+    campaign_labels, DataFetcher, DEFAULT_STARTUP_GRACE, STATS_FILE,
+};
+use crate::tui::filter::SolutionFilter;
+use crate::tui::follow::CrashFollower;
+use crate::tui::forensics::DEFAULT_STALL_THRESHOLD;
+use crate::tui::format::NumberFormat;
+use crate::tui::heatmap::{
+    build_label, heat_color, median_execs_per_sec, HEAT_CELL, MIN_HEATMAP_AREA_WIDTH,
+    MISMATCH_CELL, STALLED_CELL,
+};
+use crate::tui::jsonl::JsonlWriter;
+use crate::tui::remote::{RemoteOutput, RemoteStatus, FETCH_INTERVAL, STATS_FRESHNESS};
+use crate::tui::retention::RetentionPolicy;
+use crate::tui::session::{CampaignData, CrashInfoDetails, LogLevel, SharedCampaignData};
+use crate::tui::stability::DEFAULT_STABILITY_DROP;
+use crate::tui::starvation::DEFAULT_STARVATION_RATIO;
+use crate::utils::snapshot::SnapshotPolicy;
+use crate::utils::system::is_dir_writable;
 
 // Constants moved to a dedicated section for better visibility
+/// Default execs/s below which the stage progress is highlighted, see `[misc] slow_exec_threshold`
 const SLOW_EXEC_PS_THRESHOLD: f64 = 250.0;
+/// Fraction of cycles without finds from which the campaign is considered to saturate
+const ELEVATED_SATURATION: f64 = 0.5;
+const HIGH_SATURATION: f64 = 0.8;
 const CAUTION_STABILITY: f64 = 90.0;
 const WARN_STABILITY: f64 = 75.0;
 const ERROR_STABILITY: f64 = 60.0;
-const KILO: f64 = 1_000.0;
-const MEGA: f64 = KILO * KILO;
-const GIGA: f64 = MEGA * KILO;
-const TERA: f64 = GIGA * KILO;
-
-/// Threshold markers for number formatting
-#[derive(Debug)]
-enum NumberScale {
-    Base(f64),
-    Kilo(f64),
-    Mega(f64),
-    Giga(f64),
-    Tera(f64),
+
+/// Stability bands in percent, below `caution` the stability is highlighted increasingly red
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StabilityThresholds {
+    pub caution: f64,
+    pub warn: f64,
+    pub error: f64,
+}
+
+impl Default for StabilityThresholds {
+    fn default() -> Self {
+        Self {
+            caution: CAUTION_STABILITY,
+            warn: WARN_STABILITY,
+            error: ERROR_STABILITY,
+        }
+    }
 }
 
-impl NumberScale {
-    fn from_f64(num: f64) -> Self {
-        match num {
-            n if n < KILO => Self::Base(n),
-            n if n < MEGA => Self::Kilo(n / KILO),
-            n if n < GIGA => Self::Mega(n / MEGA),
-            n if n < TERA => Self::Giga(n / GIGA),
-            n => Self::Tera(n / TERA),
+impl StabilityThresholds {
+    /// Overrides the defaults with the provided bands
+    ///
+    /// # Errors
+    /// * If the resulting bands are not in descending order
+    pub fn with_overrides(
+        caution: Option<f64>,
+        warn: Option<f64>,
+        error: Option<f64>,
+    ) -> Result<Self> {
+        let defaults = Self::default();
+        let thresholds = Self {
+            caution: caution.unwrap_or(defaults.caution),
+            warn: warn.unwrap_or(defaults.warn),
+            error: error.unwrap_or(defaults.error),
+        };
+        if !(thresholds.caution >= thresholds.warn && thresholds.warn >= thresholds.error) {
+            anyhow::bail!(
+                "Stability thresholds must satisfy caution >= warn >= error, got {}/{}/{}",
+                thresholds.caution,
+                thresholds.warn,
+                thresholds.error
+            );
         }
+        Ok(thresholds)
     }
 
-    fn format(&self) -> String {
-        match self {
-            Self::Base(n) => format!("{n:.2}"),
-            Self::Kilo(n) => format!("{n:.2}K"),
-            Self::Mega(n) => format!("{n:.2}M"),
-            Self::Giga(n) => format!("{n:.2}B"),
-            Self::Tera(n) => format!("{n:.2}T"),
+    /// Style for the given stability
+    fn style(&self, stability: f64) -> Style {
+        if stability >= self.caution {
+            Style::default()
+        } else if stability >= self.warn {
+            Style::default().fg(Color::Yellow)
+        } else if stability >= self.error {
+            Style::default().fg(Color::Rgb(255, 165, 0)) // Orange color
+        } else {
+            Style::default().fg(Color::Red)
         }
     }
 }
+/// Options that control the TUI behavior
+#[derive(Debug, Clone, Default)]
+pub struct TuiOptions {
+    /// Append every collection tick as a JSON line to this path
+    pub jsonl: Option<PathBuf>,
+    /// Initial formatting mode for numbers, can be toggled at runtime
+    pub number_format: NumberFormat,
+    /// Caps on the crashes and hangs kept per instance
+    pub retention: RetentionPolicy,
+    /// Periodic snapshots of the corpus and solutions
+    pub snapshots: SnapshotPolicy,
+    /// Execs/s below which the stage progress is highlighted, defaults to `SLOW_EXEC_PS_THRESHOLD`
+    pub slow_exec_threshold: Option<f64>,
+    /// Stats age after which a live instance is stalled, defaults to `DEFAULT_STALL_THRESHOLD`
+    pub stall_threshold: Option<Duration>,
+    /// Time to wait for the first fuzzer to start before reporting none alive, defaults to
+    /// `DEFAULT_STARTUP_GRACE`
+    pub startup_grace: Option<Duration>,
+    /// Name of the statistics file in every instance directory, defaults to `STATS_FILE`
+    pub stats_file: Option<String>,
+    /// Fraction of the secondaries' median execs/s below which the main instance starves,
+    /// defaults to `DEFAULT_STARVATION_RATIO`
+    pub starvation_ratio: Option<f64>,
+    /// Percentage points the average stability may drop below its rolling baseline before a
+    /// warning is raised, defaults to `DEFAULT_STABILITY_DROP`
+    pub stability_drop: Option<f64>,
+    /// Stability bands used to color the overall stability
+    pub stability: StabilityThresholds,
+    /// Serve the campaign data on `<output>/.aflr/control.sock`
+    pub control_socket: bool,
+    /// Generated fuzzer commands reported by `GET PLAN` on the control socket
+    pub plan: Vec<String>,
+    /// Replace identifying names and paths in the JSONL output and on the control socket
+    pub anonymize: bool,
+    /// Append every log line to this path as well
+    pub log_file: Option<PathBuf>,
+    /// Log lines retained for the log pane, defaults to `DEFAULT_LOG_CAPACITY`
+    pub log_capacity: Option<usize>,
+    /// Highlight crashes that appeared since the last refresh
+    pub follow_new_crashes: bool,
+    /// Shell command every new crash is opened with while following, gets the path appended
+    pub crash_viewer: Option<String>,
+    /// Campaign on another host whose stats are fetched into the single output directory
+    pub remote: Option<RemoteOutput>,
+}
 
 /// Represents the TUI (Text User Interface)
 pub struct Tui {
     terminal: Terminal<CrosstermBackend<io::Stdout>>,
+    number_format: NumberFormat,
+    slow_exec_threshold: f64,
+    stability: StabilityThresholds,
+    /// Labels of the monitored campaigns, empty when monitoring a single one
+    campaigns: Vec<String>,
+    /// Set in `--follow-new-crashes` mode
+    follower: Option<CrashFollower>,
+    /// Narrows the crash and hang panels, edited after pressing '/'
+    filter: SolutionFilter,
+    editing_filter: bool,
 }
 
 impl Tui {
@@ -74,7 +195,16 @@ impl Tui {
     pub fn new() -> io::Result<Self> {
         let backend = CrosstermBackend::new(io::stdout());
         let terminal = Terminal::new(backend)?;
-        Ok(Self { terminal })
+        Ok(Self {
+            terminal,
+            number_format: NumberFormat::default(),
+            slow_exec_threshold: SLOW_EXEC_PS_THRESHOLD,
+            stability: StabilityThresholds::default(),
+            campaigns: Vec::new(),
+            follower: None,
+            filter: SolutionFilter::default(),
+            editing_filter: false,
+        })
     }
 
     /// Formats a duration into a string based on days, hours, minutes, and seconds
@@ -95,45 +225,243 @@ impl Tui {
         }
     }
 
-    /// Runs the TUI standalone with the specified output directory
+    /// Runs the TUI standalone with the specified output directories
+    ///
+    /// Several output directories are monitored as one campaign overview, Tab switches to the
+    /// statistics of the individual campaigns.
+    /// If `options.jsonl` is set, every collection tick of the overview is additionally appended
+    /// to it as a JSON line.
+    /// If `options.control_socket` is set, the data of a single campaign is also served to
+    /// external tools, see [`ControlServer`].
+    /// If `options.remote` is set, its stats are fetched into the output directory every
+    /// `FETCH_INTERVAL`, which is treated as read-only.
     ///
     /// # Errors
-    /// Returns an error if the TUI fails to run
-    pub fn run(output_dir: &Path, pid_file: Option<&Path>, cdata: &mut CampaignData) -> Result<()> {
-        let output_dir = output_dir.to_path_buf();
+    /// Returns an error if the TUI fails to run, the JSONL output cannot be opened or the
+    /// control socket cannot be bound
+    pub fn run(
+        output_dirs: &[PathBuf],
+        pid_file: Option<&Path>,
+        options: &TuiOptions,
+        mut cdata: CampaignData,
+    ) -> Result<()> {
+        let output_dirs = output_dirs.to_vec();
+        let mut jsonl_writer = options
+            .jsonl
+            .as_deref()
+            .map(|path| JsonlWriter::new(path).map(|w| w.with_anonymize(options.anonymize)))
+            .transpose()?;
+        if let Some(capacity) = options.log_capacity {
+            cdata = cdata.with_log_capacity(capacity)?;
+        }
+        if let Some(log_file) = &options.log_file {
+            cdata = cdata.with_log_file(log_file)?;
+        }
         cdata.log("Initialized TUI");
-        let mut dfetcher = DataFetcher::new(&output_dir, pid_file, cdata);
-
-        let (tx, rx) = mpsc::channel();
+        let remote = options.remote.clone();
+        if let Some(remote) = &remote {
+            cdata.log(format!(
+                "Monitoring {} over SSH, pruning, snapshots and the control socket are disabled",
+                remote.source()
+            ));
+            cdata.remote = Some(RemoteStatus {
+                source: remote.source(),
+                ..RemoteStatus::default()
+            });
+        }
+        // Never touch archived campaigns on read-only storage, nor the local copy of a remote one
+        let read_only = remote.is_some() || !output_dirs.iter().all(|dir| is_dir_writable(dir));
+        if read_only && remote.is_none() {
+            cdata
+                .warn("Output directory is read-only, pruning, snapshots and the control socket are disabled");
+        }
+        let (retention, snapshots) = if read_only {
+            (RetentionPolicy::default(), SnapshotPolicy::default())
+        } else {
+            (options.retention.clone(), options.snapshots.clone())
+        };
+        let (dfetcher, labels) = match output_dirs.as_slice() {
+            [output_dir] if remote.is_some() => {
+                if let Some(status) = &mut cdata.remote {
+                    status.update(&Ok(()));
+                }
+                let fetcher = DataFetcher::for_remote(output_dir, STATS_FRESHNESS, cdata);
+                (fetcher, Vec::new())
+            }
+            [output_dir] => {
+                let fetcher = DataFetcher::new(output_dir, pid_file, cdata);
+                (fetcher, Vec::new())
+            }
+            _ => {
+                cdata.log(format!("Monitoring {} campaigns", output_dirs.len()));
+                let fetcher = DataFetcher::for_campaigns(&output_dirs, cdata);
+                (fetcher, campaign_labels(&output_dirs))
+            }
+        };
+        let stall_threshold = options.stall_threshold.unwrap_or(DEFAULT_STALL_THRESHOLD);
+        let stats_file = options
+            .stats_file
+            .clone()
+            .unwrap_or_else(|| STATS_FILE.to_string());
+        let starvation_ratio = options.starvation_ratio.unwrap_or(DEFAULT_STARVATION_RATIO);
+        let stability_drop = options.stability_drop.unwrap_or(DEFAULT_STABILITY_DROP);
+        let mut dfetcher = dfetcher
+            .with_retention(retention)
+            .with_snapshots(snapshots)
+            .with_stall_threshold(stall_threshold)
+            .with_startup_grace(options.startup_grace.unwrap_or(DEFAULT_STARTUP_GRACE))
+            .with_stats_file(&stats_file)
+            .with_starvation_ratio(starvation_ratio)
+            .with_stability_drop(stability_drop);
+
+        let shared = Arc::new(SharedCampaignData::new());
+        let collector_shared = Arc::clone(&shared);
+        let _control = match output_dirs.as_slice() {
+            [output_dir] if options.control_socket && !read_only => Some(ControlServer::spawn(
+                output_dir,
+                &shared,
+                options.plan.clone(),
+                options.anonymize,
+            )?),
+            _ => None,
+        };
 
-        thread::spawn(move || loop {
-            let session_data = dfetcher.collect_session_data().clone();
-            if tx.send(session_data).is_err() {
-                break;
+        thread::spawn(move || {
+            // Drill-down fetchers are only created once a campaign is viewed, the overview
+            // already enforces the retention policy for all of them
+            let mut views: Vec<Option<DataFetcher>> = output_dirs.iter().map(|_| None).collect();
+            let mut last_fetch = Instant::now();
+            loop {
+                if let Some(remote) = remote
+                    .as_ref()
+                    .filter(|_| last_fetch.elapsed() >= FETCH_INTERVAL)
+                {
+                    last_fetch = Instant::now();
+                    Self::fetch_remote(remote, &mut dfetcher.campaign_data);
+                }
+                let session_data = dfetcher.collect_session_data();
+                // Stop streaming once the consumer went away, e.g. a closed named pipe
+                if jsonl_writer
+                    .as_mut()
+                    .is_some_and(|w| w.write(session_data).is_err())
+                {
+                    jsonl_writer = None;
+                }
+                let view = collector_shared.view();
+                match view.checked_sub(1).and_then(|idx| views.get_mut(idx)) {
+                    Some(fetcher) => {
+                        let fetcher = fetcher.get_or_insert_with(|| {
+                            DataFetcher::new(&output_dirs[view - 1], None, CampaignData::new())
+                                .with_stats_file(&stats_file)
+                                .with_stall_threshold(stall_threshold)
+                                .with_starvation_ratio(starvation_ratio)
+                                .with_stability_drop(stability_drop)
+                        });
+                        fetcher.campaign_data.view = view;
+                        collector_shared.publish(fetcher.collect_session_data());
+                    }
+                    None => collector_shared.publish(session_data),
+                }
+                // The renderer holds the only other reference and drops it on exit
+                if Arc::strong_count(&collector_shared) == 1 {
+                    break;
+                }
+                thread::sleep(Duration::from_secs(1));
             }
-            thread::sleep(Duration::from_secs(1));
         });
 
         Self::new()
-            .and_then(|mut tui| tui.run_internal(&rx))
+            .and_then(|mut tui| {
+                tui.number_format = options.number_format;
+                tui.slow_exec_threshold = options
+                    .slow_exec_threshold
+                    .unwrap_or(SLOW_EXEC_PS_THRESHOLD);
+                tui.stability = options.stability;
+                tui.campaigns = labels;
+                tui.follower = options
+                    .follow_new_crashes
+                    .then(|| CrashFollower::new(options.crash_viewer.clone()));
+                tui.run_internal(&shared)
+            })
             .map_err(|e| anyhow::anyhow!("Error running TUI: {e}"))
     }
 
-    /// Runs the TUI with the specified session data receiver
-    fn run_internal(&mut self, session_data_rx: &mpsc::Receiver<CampaignData>) -> io::Result<()> {
+    /// Fetches the stats of `remote`, logging connectivity changes
+    ///
+    /// A failed fetch keeps the last stats, the fuzzers count as dead once they grow stale.
+    fn fetch_remote(remote: &RemoteOutput, cdata: &mut CampaignData) {
+        let result = remote.fetch();
+        let Some(status) = &mut cdata.remote else {
+            return;
+        };
+        let was_connected = status.is_connected();
+        status.update(&result);
+        match (&result, was_connected) {
+            (Err(e), true) => cdata.error(format!("Lost connection to {}: {e}", remote.source())),
+            (Ok(()), false) => cdata.log(format!("Reconnected to {}", remote.source())),
+            _ => {}
+        }
+    }
+
+    /// Runs the TUI, redrawing whenever the collector published new session data
+    fn run_internal(&mut self, shared: &SharedCampaignData) -> io::Result<()> {
         self.terminal.clear()?;
         enable_raw_mode()?;
         crossterm::execute!(self.terminal.backend_mut(), EnterAlternateScreen)?;
 
+        let mut drawn_generation = 0;
         loop {
-            if let Ok(session_data) = session_data_rx.recv_timeout(Duration::from_millis(500)) {
+            let generation = shared.generation();
+            if generation != drawn_generation {
+                let session_data = shared.lock();
+                if let Some(follower) = &mut self.follower {
+                    for crash in follower.update(session_data.view, &session_data.last_crashes) {
+                        follower.open(&crash);
+                    }
+                }
                 self.draw(&session_data)?;
+                drawn_generation = generation;
+            } else if self.follower.as_mut().is_some_and(CrashFollower::expire) {
+                self.draw(&shared.lock())?;
             }
 
             if crossterm::event::poll(Duration::from_millis(200))? {
                 if let crossterm::event::Event::Key(key_event) = crossterm::event::read()? {
-                    if key_event.code == crossterm::event::KeyCode::Char('q') {
-                        break;
+                    if self.editing_filter {
+                        self.edit_filter(key_event.code);
+                        if drawn_generation > 0 {
+                            self.draw(&shared.lock())?;
+                        }
+                        continue;
+                    }
+                    match key_event.code {
+                        crossterm::event::KeyCode::Char('q') => break,
+                        crossterm::event::KeyCode::Char('/') => {
+                            self.editing_filter = true;
+                            if drawn_generation > 0 {
+                                self.draw(&shared.lock())?;
+                            }
+                        }
+                        crossterm::event::KeyCode::Esc if self.filter.is_active() => {
+                            self.filter.clear();
+                            if drawn_generation > 0 {
+                                self.draw(&shared.lock())?;
+                            }
+                        }
+                        crossterm::event::KeyCode::Char('r') => {
+                            self.number_format = self.number_format.toggle();
+                            if drawn_generation > 0 {
+                                self.draw(&shared.lock())?;
+                            }
+                        }
+                        crossterm::event::KeyCode::Tab if !self.campaigns.is_empty() => {
+                            shared.set_view((shared.view() + 1) % (self.campaigns.len() + 1));
+                        }
+                        crossterm::event::KeyCode::BackTab if !self.campaigns.is_empty() => {
+                            let views = self.campaigns.len() + 1;
+                            shared.set_view((shared.view() + views - 1) % views);
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -147,6 +475,22 @@ impl Tui {
         Ok(())
     }
 
+    /// Applies a key press while the solution filter is edited, the panels update as you type
+    ///
+    /// Enter keeps the filter, Esc discards it.
+    fn edit_filter(&mut self, key: crossterm::event::KeyCode) {
+        match key {
+            crossterm::event::KeyCode::Char(c) => self.filter.push(c),
+            crossterm::event::KeyCode::Backspace => self.filter.pop(),
+            crossterm::event::KeyCode::Enter => self.editing_filter = false,
+            crossterm::event::KeyCode::Esc => {
+                self.filter.clear();
+                self.editing_filter = false;
+            }
+            _ => {}
+        }
+    }
+
     /// Creates the layout for the TUI
     fn create_layout(size: Rect, show_crashes: bool, show_hangs: bool) -> Vec<Rect> {
         let main_layout = Layout::default()
@@ -157,7 +501,7 @@ impl Tui {
 
         let mut constraints = vec![
             Constraint::Length(7), // Process timings and Overall results
-            Constraint::Length(6), // Stage progress and Nerd stats
+            Constraint::Length(7), // Stage progress and Nerd stats
         ];
 
         if show_crashes {
@@ -183,36 +527,65 @@ impl Tui {
 
     /// Draws the TUI with the specified session data
     fn draw(&mut self, session_data: &CampaignData) -> io::Result<()> {
+        let fmt = self.number_format;
+        let slow_exec_threshold = self.slow_exec_threshold;
+        let stability = self.stability;
+        let campaigns = &self.campaigns;
+        let follower = self.follower.as_ref();
+        let filter = &self.filter;
+        let editing_filter = self.editing_filter;
         self.terminal.draw(|f| {
             let show_crashes = f.area().height >= 16;
             let show_hangs = f.area().height >= 30;
 
             let chunks = Self::create_layout(f.area(), show_crashes, show_hangs);
 
-            Self::render_title(f, session_data, chunks[0]);
+            Self::render_title(f, session_data, campaigns, chunks[0]);
 
             let process_overall_layout = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
                 .split(chunks[1]);
             Self::render_process_timings(f, session_data, process_overall_layout[0]);
-            Self::render_overall_results(f, session_data, process_overall_layout[1]);
+            Self::render_overall_results(
+                f,
+                session_data,
+                fmt,
+                &stability,
+                process_overall_layout[1],
+            );
 
             let stage_nerd_layout = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
                 .split(chunks[2]);
-            Self::render_stage_progress(f, session_data, stage_nerd_layout[0]);
-            Self::render_nerd_stats(f, session_data, stage_nerd_layout[1]);
+            Self::render_stage_progress(
+                f,
+                session_data,
+                fmt,
+                slow_exec_threshold,
+                stage_nerd_layout[0],
+            );
+            Self::render_nerd_stats(f, session_data, fmt, stage_nerd_layout[1]);
 
             let mut idx = 3;
 
             if show_crashes {
-                Self::render_crash_solutions(f, session_data, chunks[idx]);
+                let title = Self::solutions_title("Latest Crashes", filter, editing_filter);
+                Self::render_crash_solutions(
+                    f,
+                    session_data,
+                    fmt,
+                    follower,
+                    filter,
+                    title,
+                    chunks[idx],
+                );
                 idx += 1;
             }
             if show_hangs {
-                Self::render_hang_solutions(f, session_data, chunks[idx]);
+                let title = Self::solutions_title("Latest Hangs", filter, editing_filter);
+                Self::render_hang_solutions(f, session_data, fmt, filter, title, chunks[idx]);
                 idx += 1;
             }
 
@@ -222,8 +595,14 @@ impl Tui {
     }
 
     /// Renders the overall results section of the TUI
-    fn render_overall_results(f: &mut Frame, session_data: &CampaignData, area: Rect) {
-        let p_overall_res = Self::create_overall_results_paragraph(session_data);
+    fn render_overall_results(
+        f: &mut Frame,
+        session_data: &CampaignData,
+        fmt: NumberFormat,
+        stability: &StabilityThresholds,
+        area: Rect,
+    ) {
+        let p_overall_res = Self::create_overall_results_paragraph(session_data, fmt, stability);
         f.render_widget(p_overall_res, area);
     }
 
@@ -234,60 +613,200 @@ impl Tui {
     }
 
     /// Renders the stage progress section of the TUI
-    fn render_stage_progress(f: &mut Frame, session_data: &CampaignData, area: Rect) {
-        let p_stage_prog = Self::create_stage_progress_paragraph(session_data);
+    fn render_stage_progress(
+        f: &mut Frame,
+        session_data: &CampaignData,
+        fmt: NumberFormat,
+        slow_exec_threshold: f64,
+        area: Rect,
+    ) {
+        let p_stage_prog =
+            Self::create_stage_progress_paragraph(session_data, fmt, slow_exec_threshold);
         f.render_widget(p_stage_prog, area);
     }
 
     /// Renders the nerd stats section of the TUI
-    fn render_nerd_stats(f: &mut Frame, session_data: &CampaignData, area: Rect) {
-        let p_nerd_stats = Self::create_nerd_stats_paragraph(session_data);
-        f.render_widget(p_nerd_stats, area);
+    fn render_nerd_stats(
+        f: &mut Frame,
+        session_data: &CampaignData,
+        fmt: NumberFormat,
+        area: Rect,
+    ) {
+        let p_nerd_stats = Self::create_nerd_stats_paragraph(session_data, fmt);
+        if area.width < MIN_HEATMAP_AREA_WIDTH || session_data.instances.is_empty() {
+            f.render_widget(p_nerd_stats, area);
+            return;
+        }
+
+        let nerd_heat_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(24)])
+            .split(area);
+        f.render_widget(p_nerd_stats, nerd_heat_layout[0]);
+        f.render_widget(
+            Self::create_heatmap_paragraph(session_data, fmt),
+            nerd_heat_layout[1],
+        );
     }
 
     /// Renders the title section of the TUI
-    fn render_title(f: &mut Frame, session_data: &CampaignData, area: Rect) {
-        let title = Paragraph::new(format!(
+    fn render_title(f: &mut Frame, session_data: &CampaignData, campaigns: &[String], area: Rect) {
+        let mut title = format!(
             "AFL++ {} - {} - Fuzzing campaign runner by @0xricksanchez",
             session_data.misc.afl_version, session_data.misc.afl_banner
-        ))
-        .alignment(Alignment::Center)
-        .style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
         );
+        if !campaigns.is_empty() {
+            let view = session_data
+                .view
+                .checked_sub(1)
+                .and_then(|idx| campaigns.get(idx))
+                .map_or("all campaigns", String::as_str);
+            title.push_str(&format!(
+                " - [{}/{}] {view} (Tab to switch)",
+                session_data.view,
+                campaigns.len()
+            ));
+        }
+        let mut spans = vec![Span::raw(title)];
+        if let Some(status) = &session_data.remote {
+            spans.push(Span::raw(" - "));
+            spans.push(Self::remote_indicator(status));
+        }
+        if let Some(starved) = &session_data.starved_main {
+            spans.push(Span::raw(" - "));
+            spans.push(Span::styled(
+                format!(
+                    "⚠ main {} at {:.1}% of the secondaries' execs/s",
+                    starved.instance,
+                    starved.percent()
+                ),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        if let Some(collapse) = &session_data.stability_collapse {
+            spans.push(Span::raw(" - "));
+            spans.push(Span::styled(
+                format!(
+                    "⚠ stability dropped {:.2} points to {:.2}%",
+                    collapse.drop(),
+                    collapse.stability
+                ),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        let title = Paragraph::new(Line::from(spans))
+            .alignment(Alignment::Center)
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
 
         f.render_widget(title, area);
     }
 
+    /// Connectivity of a campaign monitored over SSH along with the age of its stats
+    fn remote_indicator(status: &RemoteStatus) -> Span<'static> {
+        let synced = status.last_sync.map_or_else(
+            || "never synced".to_string(),
+            |at| format!("synced {}s ago", at.elapsed().as_secs()),
+        );
+        if status.is_connected() {
+            Span::styled(
+                format!("● {} ({synced})", status.source),
+                Style::default().fg(Color::Green),
+            )
+        } else {
+            Span::styled(
+                format!("✗ {} offline ({synced})", status.source),
+                Style::default().fg(Color::Red),
+            )
+        }
+    }
+
+    /// Title of a solutions panel showing the active filter
+    fn solutions_title(panel: &str, filter: &SolutionFilter, editing: bool) -> String {
+        if editing {
+            format!(
+                "{panel} - filter: /{}_ (Enter to keep, Esc to clear)",
+                filter.query()
+            )
+        } else if filter.is_active() {
+            format!("{panel} - filter: {} (Esc to clear)", filter.query())
+        } else {
+            panel.to_string()
+        }
+    }
+
     /// Renders the crash solutions section of the TUI
-    fn render_crash_solutions(f: &mut Frame, session_data: &CampaignData, area: Rect) {
-        let p_crash_solutions = Paragraph::new(Self::format_solutions(
-            &session_data.total_run_time,
-            &session_data.last_crashes,
-        ))
-        .block(
-            Block::default()
-                .title("Latest Crashes")
-                .borders(Borders::ALL)
-                .border_style(Style::default().add_modifier(Modifier::BOLD))
-                .title_style(Style::default().add_modifier(Modifier::BOLD)),
-        )
-        .style(Style::default());
+    ///
+    /// While following new crashes, the ones that just appeared are highlighted
+    fn render_crash_solutions(
+        f: &mut Frame,
+        session_data: &CampaignData,
+        fmt: NumberFormat,
+        follower: Option<&CrashFollower>,
+        filter: &SolutionFilter,
+        mut title: String,
+        area: Rect,
+    ) {
+        let crashes = filter.latest(&session_data.last_crashes);
+        let table = Self::format_solutions(&session_data.total_run_time, &crashes, fmt);
+        // The header and separator precede one row per crash
+        let lines: Vec<Line> = table
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| {
+                let flashing = idx
+                    .checked_sub(2)
+                    .and_then(|row| crashes.get(row))
+                    .is_some_and(|crash| follower.is_some_and(|f| f.is_flashing(&crash.file_path)));
+                if flashing {
+                    Line::styled(
+                        line.to_string(),
+                        Style::default()
+                            .fg(Color::Red)
+                            .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                    )
+                } else {
+                    Line::raw(line.to_string())
+                }
+            })
+            .collect();
+        match follower.map_or(0, CrashFollower::flashing_count) {
+            0 => {}
+            new => title.push_str(&format!(" ({new} new)")),
+        }
+        let p_crash_solutions = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().add_modifier(Modifier::BOLD))
+                    .title_style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .style(Style::default());
 
         f.render_widget(p_crash_solutions, area);
     }
 
     /// Renders the hang solutions section of the TUI
-    fn render_hang_solutions(f: &mut Frame, session_data: &CampaignData, area: Rect) {
+    fn render_hang_solutions(
+        f: &mut Frame,
+        session_data: &CampaignData,
+        fmt: NumberFormat,
+        filter: &SolutionFilter,
+        title: String,
+        area: Rect,
+    ) {
         let p_hang_solutions = Paragraph::new(Self::format_solutions(
             &session_data.total_run_time,
-            &session_data.last_hangs,
+            &filter.latest(&session_data.last_hangs),
+            fmt,
         ))
         .block(
             Block::default()
-                .title("Latest Hangs")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().add_modifier(Modifier::BOLD))
                 .title_style(Style::default().add_modifier(Modifier::BOLD)),
@@ -298,31 +817,42 @@ impl Tui {
     }
 
     /// Creates the process timings paragraph
-    fn create_process_timings_paragraph(session_data: &CampaignData) -> Paragraph {
+    fn create_process_timings_paragraph(session_data: &CampaignData) -> Paragraph<'_> {
         let last_seen_crash =
             Self::format_last_event(&session_data.last_crashes, &session_data.total_run_time);
         let last_seen_hang =
             Self::format_last_event(&session_data.last_hangs, &session_data.total_run_time);
 
+        let stalled = session_data.stalled_count();
         let fuzzers_alive_style = if session_data.fuzzers_alive.len() < session_data.fuzzers_started
         {
             Style::default().fg(Color::Red)
+        } else if stalled > 0 {
+            Style::default().fg(Color::Yellow)
         } else {
             Style::default()
         };
 
-        let text = vec![
-            Line::from(vec![
-                Span::raw("Fuzzers alive: "),
-                Span::styled(
-                    format!(
-                        "{}/{}",
-                        session_data.fuzzers_alive.len(),
-                        session_data.fuzzers_started
-                    ),
-                    fuzzers_alive_style,
+        let mut alive = vec![
+            Span::raw("Fuzzers alive: "),
+            Span::styled(
+                format!(
+                    "{}/{}",
+                    session_data.fuzzers_alive.len(),
+                    session_data.fuzzers_started
                 ),
-            ]),
+                fuzzers_alive_style,
+            ),
+        ];
+        if stalled > 0 {
+            alive.push(Span::styled(
+                format!(" ({stalled} stalled)"),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        let text = vec![
+            Line::from(alive),
             Line::from(format!(
                 "Total run time: {}",
                 Self::format_duration(&session_data.total_run_time)
@@ -349,16 +879,12 @@ impl Tui {
     }
 
     /// Creates the overall results paragraph
-    fn create_overall_results_paragraph(session_data: &CampaignData) -> Paragraph {
-        let stability_style = if session_data.stability.avg >= CAUTION_STABILITY {
-            Style::default()
-        } else if session_data.stability.avg >= WARN_STABILITY {
-            Style::default().fg(Color::Yellow)
-        } else if session_data.stability.avg >= ERROR_STABILITY {
-            Style::default().fg(Color::Rgb(255, 165, 0)) // Orange color
-        } else {
-            Style::default().fg(Color::Red)
-        };
+    fn create_overall_results_paragraph<'a>(
+        session_data: &'a CampaignData,
+        fmt: NumberFormat,
+        stability: &StabilityThresholds,
+    ) -> Paragraph<'a> {
+        let stability_style = stability.style(session_data.stability.avg);
 
         let content = vec![
             Line::from(format!(
@@ -383,10 +909,10 @@ impl Tui {
             )),
             Line::from(format!(
                 "Corpus count: {} ({}->{}<-{})",
-                Self::format_int_to_hint(session_data.corpus.cum),
-                Self::format_int_to_hint(session_data.corpus.min),
-                Self::format_int_to_hint(session_data.corpus.avg),
-                Self::format_int_to_hint(session_data.corpus.max),
+                fmt.format_int(session_data.corpus.cum),
+                fmt.format_int(session_data.corpus.min),
+                fmt.format_int(session_data.corpus.avg),
+                fmt.format_int(session_data.corpus.max),
             )),
             Line::from(vec![
                 Span::raw("Stability: "),
@@ -413,27 +939,31 @@ impl Tui {
             .wrap(Wrap { trim: true })
     }
 
-    /// Creates the stage progress paragraph
-    fn create_stage_progress_paragraph(session_data: &CampaignData) -> Paragraph {
-        let ps_cum_style = if session_data.executions.per_sec.cum < SLOW_EXEC_PS_THRESHOLD {
+    /// Creates the stage progress paragraph, execs/s below `slow_exec_threshold` are highlighted
+    fn create_stage_progress_paragraph(
+        session_data: &CampaignData,
+        fmt: NumberFormat,
+        slow_exec_threshold: f64,
+    ) -> Paragraph<'_> {
+        let ps_cum_style = if session_data.executions.per_sec.cum < slow_exec_threshold {
             Style::default().fg(Color::Red)
         } else {
             Style::default()
         };
 
-        let ps_min_style = if session_data.executions.per_sec.min < SLOW_EXEC_PS_THRESHOLD {
+        let ps_min_style = if session_data.executions.per_sec.min < slow_exec_threshold {
             Style::default().fg(Color::Red)
         } else {
             Style::default()
         };
 
-        let ps_avg_style = if session_data.executions.per_sec.avg < SLOW_EXEC_PS_THRESHOLD {
+        let ps_avg_style = if session_data.executions.per_sec.avg < slow_exec_threshold {
             Style::default().fg(Color::Red)
         } else {
             Style::default()
         };
 
-        let ps_max_style = if session_data.executions.per_sec.max < SLOW_EXEC_PS_THRESHOLD {
+        let ps_max_style = if session_data.executions.per_sec.max < slow_exec_threshold {
             Style::default().fg(Color::Red)
         } else {
             Style::default()
@@ -442,30 +972,30 @@ impl Tui {
         let text = vec![
             Line::from(format!(
                 "Execs: {} ({}->{}<-{})",
-                Self::format_int_to_hint(session_data.executions.count.cum),
-                Self::format_int_to_hint(session_data.executions.count.min),
-                Self::format_int_to_hint(session_data.executions.count.avg),
-                Self::format_int_to_hint(session_data.executions.count.max),
+                fmt.format_int(session_data.executions.count.cum),
+                fmt.format_int(session_data.executions.count.min),
+                fmt.format_int(session_data.executions.count.avg),
+                fmt.format_int(session_data.executions.count.max),
             )),
             Line::from(vec![
                 Span::raw("Execs/s: "),
                 Span::styled(
-                    Self::format_float_to_hfloat(session_data.executions.per_sec.cum),
+                    fmt.format_float(session_data.executions.per_sec.cum),
                     ps_cum_style,
                 ),
                 Span::raw(" ("),
                 Span::styled(
-                    Self::format_float_to_hfloat(session_data.executions.per_sec.min),
+                    fmt.format_float(session_data.executions.per_sec.min),
                     ps_min_style,
                 ),
                 Span::raw("->"),
                 Span::styled(
-                    Self::format_float_to_hfloat(session_data.executions.per_sec.avg),
+                    fmt.format_float(session_data.executions.per_sec.avg),
                     ps_avg_style,
                 ),
                 Span::raw("<-"),
                 Span::styled(
-                    Self::format_float_to_hfloat(session_data.executions.per_sec.max),
+                    fmt.format_float(session_data.executions.per_sec.max),
                     ps_max_style,
                 ),
                 Span::raw(")"),
@@ -474,6 +1004,20 @@ impl Tui {
                 "Coverage: {:.2}% ({:.2}%/{:.2}%)",
                 session_data.coverage.avg, session_data.coverage.min, session_data.coverage.max,
             )),
+            Line::from(format!(
+                "Edges: {} ({}/{}) of {}",
+                fmt.format_int(session_data.edges.found.avg),
+                fmt.format_int(session_data.edges.found.min),
+                fmt.format_int(session_data.edges.found.max),
+                fmt.format_int(session_data.edges.total),
+            )),
+            Line::from(format!(
+                "Est. cycle time: {}",
+                session_data.est_cycle_time.map_or_else(
+                    || "n/a".to_string(),
+                    |t| format!("~{}", Self::format_duration(&t))
+                ),
+            )),
         ];
 
         let block = Block::default()
@@ -488,27 +1032,56 @@ impl Tui {
     }
 
     /// Creates the nerd stats paragraph
-    fn create_nerd_stats_paragraph(session_data: &CampaignData) -> Paragraph {
-        let content = format!(
-            "Levels: {} ({}/{})
-Pending favorites: {} ({}->{}<-{})
-Pending total: {} ({}->{}<-{}),
-Cycles without finds: {} ({}/{})",
-            session_data.levels.avg,
-            session_data.levels.min,
-            session_data.levels.max,
-            Self::format_int_to_hint(session_data.pending.favorites.cum),
-            Self::format_int_to_hint(session_data.pending.favorites.min),
-            Self::format_int_to_hint(session_data.pending.favorites.avg),
-            Self::format_int_to_hint(session_data.pending.favorites.max),
-            Self::format_int_to_hint(session_data.pending.total.cum),
-            Self::format_int_to_hint(session_data.pending.total.min),
-            Self::format_int_to_hint(session_data.pending.total.avg),
-            Self::format_int_to_hint(session_data.pending.total.max),
-            session_data.cycles.wo_finds.avg,
-            session_data.cycles.wo_finds.min,
-            session_data.cycles.wo_finds.max
-        );
+    fn create_nerd_stats_paragraph(
+        session_data: &CampaignData,
+        fmt: NumberFormat,
+    ) -> Paragraph<'_> {
+        let saturation = session_data.cycles.wo_finds_ratio;
+        let saturation_style = if saturation >= HIGH_SATURATION {
+            Style::default().fg(Color::Red)
+        } else if saturation >= ELEVATED_SATURATION {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+
+        let content = vec![
+            Line::from(format!(
+                "Levels: {} ({}/{})",
+                session_data.levels.avg, session_data.levels.min, session_data.levels.max,
+            )),
+            Line::from(format!(
+                "Pending favorites: {} {} ({}->{}<-{})",
+                fmt.format_int(session_data.pending.favorites.cum),
+                session_data.pending.favorites_trend.arrow(),
+                fmt.format_int(session_data.pending.favorites.min),
+                fmt.format_int(session_data.pending.favorites.avg),
+                fmt.format_int(session_data.pending.favorites.max),
+            )),
+            Line::from(format!(
+                "Pending total: {} ({}->{}<-{}),",
+                fmt.format_int(session_data.pending.total.cum),
+                fmt.format_int(session_data.pending.total.min),
+                fmt.format_int(session_data.pending.total.avg),
+                fmt.format_int(session_data.pending.total.max),
+            )),
+            Line::from(vec![
+                Span::raw(format!(
+                    "Cycles without finds: {} ({}/{}), ",
+                    session_data.cycles.wo_finds.avg,
+                    session_data.cycles.wo_finds.min,
+                    session_data.cycles.wo_finds.max,
+                )),
+                Span::styled(
+                    format!("{:.0}% of cycles", saturation * 100.0),
+                    saturation_style,
+                ),
+            ]),
+            Line::from(format!(
+                "Stats files parsed this cycle: {}",
+                session_data.stats_parsed
+            )),
+        ];
 
         Paragraph::new(content)
             .block(
@@ -521,9 +1094,65 @@ Cycles without finds: {} ({}/{})",
             .style(Style::default())
     }
 
+    /// Creates the per-instance execs/s heatmap colored relative to the fleet median
+    fn create_heatmap_paragraph(session_data: &CampaignData, fmt: NumberFormat) -> Paragraph<'_> {
+        let median = median_execs_per_sec(&session_data.instances).unwrap_or_default();
+        let cells: Vec<Span> = session_data
+            .instances
+            .iter()
+            .map(|instance| {
+                if instance.build_mismatch {
+                    Span::styled(
+                        MISMATCH_CELL,
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    )
+                } else if instance.stalled {
+                    Span::styled(STALLED_CELL, Style::default().fg(Color::DarkGray))
+                } else {
+                    Span::styled(
+                        HEAT_CELL,
+                        Style::default().fg(heat_color(instance.execs_per_sec, median)),
+                    )
+                }
+            })
+            .collect();
+
+        let mut block = Block::default()
+            .title(Span::styled(
+                format!("Execs/s ~{}", fmt.format_float(median)),
+                Style::default().add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().add_modifier(Modifier::BOLD));
+        if let Some((label, mixed)) = build_label(&session_data.instances) {
+            let style = if mixed {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            block = block.title_bottom(Line::from(Span::styled(label, style)).right_aligned());
+        }
+
+        Paragraph::new(Line::from(cells))
+            .block(block)
+            .wrap(Wrap { trim: true })
+    }
+
     /// Renders the logs section of the TUI
     fn render_logs(f: &mut Frame, session_data: &CampaignData, area: Rect) {
-        let content = session_data.logs.join("\n", true);
+        let content: Vec<Line> = session_data
+            .logs
+            .iter()
+            .rev()
+            .map(|entry| {
+                let style = match entry.level {
+                    LogLevel::Info => Style::default(),
+                    LogLevel::Warn => Style::default().fg(Color::Yellow),
+                    LogLevel::Error => Style::default().fg(Color::Red),
+                };
+                Line::styled(entry.to_string(), style)
+            })
+            .collect();
         let paragraph = Paragraph::new(content)
             .block(
                 Block::default()
@@ -538,17 +1167,6 @@ Cycles without finds: {} ({}/{})",
         f.render_widget(paragraph, area);
     }
 
-    /// Format a floating-point number in a more human readable representation
-    fn format_float_to_hfloat(float_num: f64) -> String {
-        NumberScale::from_f64(float_num).format()
-    }
-
-    /// Format an integer in a more human readable representation
-    fn format_int_to_hint(int_num: usize) -> String {
-        #[allow(clippy::cast_precision_loss)]
-        NumberScale::from_f64(int_num as f64).format()
-    }
-
     /// Formats the last event duration
     fn format_last_event(events: &[CrashInfoDetails], total_run_time: &Duration) -> String {
         events
@@ -581,7 +1199,11 @@ Cycles without finds: {} ({}/{})",
     }
 
     /// Formats the solutions into a string
-    fn format_solutions(total_run_time: &Duration, solutions: &[CrashInfoDetails]) -> String {
+    fn format_solutions(
+        total_run_time: &Duration,
+        solutions: &[&CrashInfoDetails],
+        fmt: NumberFormat,
+    ) -> String {
         let max_fuzzer_name_length = solutions
             .iter()
             .map(|s| s.fuzzer_name.len())
@@ -622,7 +1244,7 @@ Cycles without finds: {} ({}/{})",
                     fuzzer_name,
                     s.sig.clone().unwrap_or_else(|| "-".to_string()),
                     Self::format_solution_time(total_run_time, s.time),
-                    Self::format_int_to_hint(usize::try_from(s.execs).unwrap_or(0)),
+                    fmt.format_int(usize::try_from(s.execs).unwrap_or(0)),
                     src,
                     s.op,
                     s.rep,
@@ -657,77 +1279,17 @@ mod tests {
     }
 
     #[test]
-    fn test_number_scale_classification() {
-        assert!(matches!(NumberScale::from_f64(100.0), NumberScale::Base(_)));
-        assert!(matches!(
-            NumberScale::from_f64(1500.0),
-            NumberScale::Kilo(_)
-        ));
-        assert!(matches!(
-            NumberScale::from_f64(1_500_000.0),
-            NumberScale::Mega(_)
-        ));
-        assert!(matches!(
-            NumberScale::from_f64(1_500_000_000.0),
-            NumberScale::Giga(_)
-        ));
-        assert!(matches!(
-            NumberScale::from_f64(1_500_000_000_000.0),
-            NumberScale::Tera(_)
-        ));
-    }
-
-    #[test]
-    fn test_number_scale_formatting() {
-        let cases = vec![
-            (100.0, "100.00"),
-            (1500.0, "1.50K"),
-            (1_500_000.0, "1.50M"),
-            (1_500_000_000.0, "1.50B"),
-            (1_500_000_000_000.0, "1.50T"),
-        ];
-
-        for (input, expected) in cases {
-            assert_eq!(NumberScale::from_f64(input).format(), expected);
-        }
-    }
-
-    #[test]
-    fn test_format_float_to_hfloat() {
-        let test_cases = vec![
-            (0.0, "0.00"),
-            (999.99, "999.99"),
-            (1000.0, "1.00K"),
-            (1234.5678, "1.23K"),
-            (1_000_000.0, "1.00M"),
-            (1_234_567.89, "1.23M"),
-            (1_000_000_000.0, "1.00B"),
-            (1_234_567_890.12, "1.23B"),
-            (1_000_000_000_000.0, "1.00T"),
-        ];
-
-        for (input, expected) in test_cases {
-            assert_eq!(Tui::format_float_to_hfloat(input), expected);
-        }
-    }
+    fn test_stability_thresholds() {
+        let defaults = StabilityThresholds::default();
+        assert_eq!(defaults.style(95.0), Style::default());
+        assert_eq!(defaults.style(80.0), Style::default().fg(Color::Yellow));
+        assert_eq!(defaults.style(10.0), Style::default().fg(Color::Red));
 
-    #[test]
-    fn test_format_int_to_hint() {
-        let test_cases = vec![
-            (0, "0.00"),
-            (999, "999.00"),
-            (1000, "1.00K"),
-            (1234, "1.23K"),
-            (1_000_000, "1.00M"),
-            (1_234_567, "1.23M"),
-            (1_000_000_000, "1.00B"),
-            (1_234_567_890, "1.23B"),
-            (1_000_000_000_000, "1.00T"),
-        ];
+        let relaxed = StabilityThresholds::with_overrides(Some(70.0), Some(65.0), None).unwrap();
+        assert_eq!(relaxed.style(80.0), Style::default());
+        assert!((relaxed.error - ERROR_STABILITY).abs() < f64::EPSILON);
 
-        for (input, expected) in test_cases {
-            assert_eq!(Tui::format_int_to_hint(input), expected);
-        }
+        assert!(StabilityThresholds::with_overrides(Some(50.0), None, None).is_err());
     }
 
     #[test]
@@ -797,24 +1359,4 @@ mod tests {
             );
         }
     }
-
-    // Batch testing for number formatting consistency
-    #[test]
-    fn test_number_formatting_consistency() {
-        // Test that integer and float formatting are consistent
-        let test_cases = vec![
-            (1000, 1000.0),
-            (1_000_000, 1_000_000.0),
-            (1_000_000_000, 1_000_000_000.0),
-        ];
-
-        for (int_val, float_val) in test_cases {
-            assert_eq!(
-                Tui::format_int_to_hint(int_val),
-                Tui::format_float_to_hfloat(float_val),
-                "Mismatch between int and float formatting for value: {}",
-                int_val
-            );
-        }
-    }
 }
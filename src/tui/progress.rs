@@ -0,0 +1,133 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use sysinfo::Signal;
+
+use crate::tui::control::signal_pids;
+use crate::tui::data_collection::DataFetcher;
+use crate::tui::format::NumberFormat;
+use crate::tui::session::CampaignData;
+use crate::tui::Tui;
+
+/// Time between two progress lines
+pub const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+/// Time the fuzzers get to shut down after a Ctrl-C
+const STOP_GRACE: Duration = Duration::from_secs(15);
+
+/// Ctrl-C presses received while the progress display runs
+static INTERRUPTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Final state of a campaign followed by `run`
+#[derive(Debug)]
+pub struct ProgressSummary {
+    /// Last collected campaign data
+    pub data: CampaignData,
+    /// Whether the fuzzers were stopped by a Ctrl-C rather than exiting on their own
+    pub interrupted: bool,
+}
+
+/// Summarizes `data` in a single line, e.g. for a status line rewritten in place
+pub fn format_progress_line(data: &CampaignData, fmt: NumberFormat) -> String {
+    format!(
+        "[{}] fuzzers {}/{} | execs {} ({}/s) | corpus {} | crashes {} | hangs {}",
+        Tui::format_duration(&data.total_run_time),
+        data.fuzzers_alive.len(),
+        data.fuzzers_started,
+        fmt.format_int(data.executions.count.cum),
+        fmt.format_float(data.executions.per_sec.cum),
+        fmt.format_int(data.corpus.cum),
+        data.crashes.cum,
+        data.hangs.cum,
+    )
+}
+
+/// Reports the progress of the campaign in `output_dir` until all fuzzers exited
+///
+/// On a terminal a single status line is rewritten in place, otherwise a plain line is printed
+/// every `PROGRESS_INTERVAL`. A Ctrl-C interrupts the fuzzers so they shut down gracefully, a
+/// second one exits immediately.
+///
+/// # Errors
+/// * If the Ctrl-C handler cannot be installed
+pub fn run(output_dir: &Path, pid_file: Option<&Path>) -> Result<ProgressSummary> {
+    ctrlc::set_handler(|| {
+        if INTERRUPTS.fetch_add(1, Ordering::SeqCst) > 0 {
+            eprintln!("\n[!] Interrupted twice, exiting without waiting for the fuzzers");
+            std::process::exit(130);
+        }
+    })
+    .context("Failed to install the Ctrl-C handler")?;
+
+    let tty = io::stdout().is_terminal();
+    let mut fetcher = DataFetcher::new(output_dir, pid_file, CampaignData::new());
+    let mut last_line: Option<Instant> = None;
+    let mut stop_deadline: Option<Instant> = None;
+    loop {
+        let data = fetcher.collect_session_data();
+        if stop_deadline.is_none() && INTERRUPTS.load(Ordering::SeqCst) > 0 {
+            let signaled = signal_pids(&data.fuzzer_pids, Signal::Interrupt);
+            if tty {
+                println!();
+            }
+            println!(
+                "[*] Stopping {} fuzzers gracefully, press Ctrl-C again to exit immediately",
+                signaled.len()
+            );
+            stop_deadline = Some(Instant::now() + STOP_GRACE);
+        }
+        let finished = data.fuzzers_started > 0 && data.fuzzers_alive.is_empty();
+        if finished || stop_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
+        if last_line.is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL) {
+            let line = format_progress_line(data, NumberFormat::default());
+            if tty {
+                print!("\r\x1b[2K{line}");
+            } else {
+                println!("{line}");
+            }
+            let _ = io::stdout().flush();
+            last_line = Some(Instant::now());
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    if tty && stop_deadline.is_none() {
+        println!();
+    }
+    Ok(ProgressSummary {
+        data: fetcher.campaign_data,
+        interrupted: stop_deadline.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_progress_line() {
+        let mut data = CampaignData::new();
+        data.total_run_time = Duration::from_secs(3725);
+        data.fuzzers_alive = vec![10, 11, 12];
+        data.fuzzers_started = 4;
+        data.executions.count.cum = 1_500_000;
+        data.executions.per_sec.cum = 2_500.0;
+        data.corpus.cum = 812;
+        data.crashes.cum = 3;
+
+        assert_eq!(
+            format_progress_line(&data, NumberFormat::Human),
+            "[01:02:05] fuzzers 3/4 | execs 1.50M (2.50K/s) | corpus 812.00 | crashes 3 | hangs 0"
+        );
+        assert!(
+            format_progress_line(&data, NumberFormat::Raw).contains("execs 1,500,000 (2,500/s)")
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+
+/// Time between two fetches of the remote `fuzzer_stats` files
+pub const FETCH_INTERVAL: Duration = Duration::from_secs(10);
+/// Fuzzers whose stats are older than this count as dead, AFL++ rewrites them every minute
+pub const STATS_FRESHNESS: Duration = Duration::from_secs(180);
+/// Seconds `ssh` waits for the connection before a fetch fails
+const CONNECT_TIMEOUT: u64 = 10;
+/// Instance subdirectories that are never traversed, they can hold many thousand files
+const SKIPPED_DIRS: [&str; 4] = ["queue", "crashes", "hangs", ".synced"];
+
+/// Output directory of a campaign on another host, mirrored into a local cache
+#[derive(Debug, Clone)]
+pub struct RemoteOutput {
+    /// SSH destination, e.g. `user@host`
+    destination: String,
+    /// Output directory on the remote host
+    path: String,
+    /// Local directory the `fuzzer_stats` files are fetched into
+    cache_dir: PathBuf,
+}
+
+/// Connectivity of a remote campaign shown in the TUI title
+#[derive(Debug, Clone, Default)]
+pub struct RemoteStatus {
+    /// The remote output directory, e.g. `user@host:/path`
+    pub source: String,
+    /// When the stats were fetched successfully the last time
+    pub last_sync: Option<Instant>,
+    /// Why the last fetch failed, `None` if it succeeded
+    pub error: Option<String>,
+}
+
+impl RemoteOutput {
+    /// Parses `spec` of the form `[user@]host:/path`
+    ///
+    /// # Errors
+    /// * If `spec` lacks the host or the path
+    pub fn new(spec: &str, cache_dir: &Path) -> Result<Self> {
+        let Some((destination, path)) = spec
+            .split_once(':')
+            .filter(|(destination, path)| !destination.is_empty() && !path.is_empty())
+        else {
+            bail!("Invalid remote output directory '{spec}', expected [user@]host:/path");
+        };
+        Ok(Self {
+            destination: destination.to_string(),
+            path: path.trim_end_matches('/').to_string(),
+            cache_dir: cache_dir.to_path_buf(),
+        })
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// The remote output directory as given on the command line
+    pub fn source(&self) -> String {
+        format!("{}:{}", self.destination, self.path)
+    }
+
+    /// Mirrors the `fuzzer_stats` of all instances into the cache directory with `rsync`
+    ///
+    /// `ssh` runs in batch mode, so missing keys fail instead of prompting for a password.
+    ///
+    /// # Errors
+    /// * If `rsync` cannot be spawned
+    /// * If the host is unreachable, the authentication fails or the path does not exist
+    pub fn fetch(&self) -> Result<()> {
+        let output = Command::new("rsync")
+            .args(self.rsync_args())
+            .stdin(Stdio::null())
+            .output()
+            .context("Failed to execute rsync")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // The first line holds the ssh error, the rest is rsync's protocol noise
+            let reason = stderr
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or("no error output");
+            bail!(
+                "Fetching the stats from {} failed with exit code {}: {}",
+                self.source(),
+                output.status.code().unwrap_or(-1),
+                reason.trim()
+            );
+        }
+        Ok(())
+    }
+
+    fn rsync_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "-a".to_string(),
+            "--delete".to_string(),
+            "--prune-empty-dirs".to_string(),
+            format!("--timeout={}", CONNECT_TIMEOUT * 3),
+            "-e".to_string(),
+            format!("ssh -o BatchMode=yes -o ConnectTimeout={CONNECT_TIMEOUT}"),
+        ];
+        // Instances of multi-target campaigns live one level deeper
+        for depth in ["/*", "/*/*"] {
+            args.extend(SKIPPED_DIRS.map(|dir| format!("--exclude={depth}/{dir}/")));
+            args.push(format!("--include={depth}/"));
+            args.push(format!("--include={depth}/fuzzer_stats"));
+        }
+        args.push("--exclude=*".to_string());
+        args.push(format!("{}/", self.source()));
+        args.push(format!("{}/", self.cache_dir.display()));
+        args
+    }
+}
+
+impl RemoteStatus {
+    /// Records the outcome of a fetch
+    pub fn update(&mut self, result: &Result<()>) {
+        match result {
+            Ok(()) => {
+                self.last_sync = Some(Instant::now());
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.last_sync.is_some() && self.error.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_output() {
+        let remote = RemoteOutput::new("fuzz@box:/srv/out/", Path::new("/tmp/cache")).unwrap();
+        assert_eq!(remote.source(), "fuzz@box:/srv/out");
+        let args = remote.rsync_args();
+        assert!(args.contains(&"ssh -o BatchMode=yes -o ConnectTimeout=10".to_string()));
+        assert!(args.contains(&"--include=/*/*/fuzzer_stats".to_string()));
+        assert!(args.contains(&"--exclude=/*/queue/".to_string()));
+        assert_eq!(
+            &args[args.len() - 3..],
+            ["--exclude=*", "fuzz@box:/srv/out/", "/tmp/cache/"]
+        );
+
+        for spec in ["/srv/out", "box:", ":/srv/out"] {
+            assert!(
+                RemoteOutput::new(spec, Path::new("/tmp")).is_err(),
+                "{spec}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_remote_status() {
+        let mut status = RemoteStatus::default();
+        assert!(!status.is_connected());
+        status.update(&Ok(()));
+        assert!(status.is_connected());
+        status.update(&Err(anyhow::anyhow!("Permission denied (publickey)")));
+        assert!(!status.is_connected());
+        assert!(status.last_sync.is_some());
+    }
+}
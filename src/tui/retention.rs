@@ -0,0 +1,280 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::utils::clock::Clock;
+
+/// Time between two retention passes, listing huge solution directories is not free
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Caps on the number of solutions kept per instance
+///
+/// Pathological targets can produce millions of hangs and exhaust the inodes of the
+/// output filesystem. Everything beyond the cap is pruned, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    /// Maximum amount of crashes kept per instance
+    pub max_crashes: Option<usize>,
+    /// Maximum amount of hangs kept per instance
+    pub max_hangs: Option<usize>,
+    /// Archive pruned files into a tarball before deleting them
+    pub archive: bool,
+    /// Timestamps the archives and their entries
+    pub clock: Clock,
+}
+
+/// Amount of solutions pruned so far
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct PruneStats {
+    pub crashes: usize,
+    pub hangs: usize,
+}
+
+impl RetentionPolicy {
+    /// Whether any cap is configured
+    pub fn is_enabled(&self) -> bool {
+        self.max_crashes.is_some() || self.max_hangs.is_some()
+    }
+
+    /// Prunes the `crashes` and `hangs` directories of a single instance
+    ///
+    /// Returns the amount of pruned crashes and hangs.
+    ///
+    /// # Errors
+    /// * If a solution directory cannot be read
+    /// * If archiving the pruned files fails, in which case nothing is deleted
+    pub fn enforce(&self, instance_dir: &Path) -> Result<(usize, usize)> {
+        let crashes = self.max_crashes.map_or(Ok(0), |max| {
+            self.prune_dir(&instance_dir.join("crashes"), max)
+        })?;
+        let hangs = self.max_hangs.map_or(Ok(0), |max| {
+            self.prune_dir(&instance_dir.join("hangs"), max)
+        })?;
+        Ok((crashes, hangs))
+    }
+
+    /// Removes all but the newest `keep` solutions in `dir`
+    fn prune_dir(&self, dir: &Path, keep: usize) -> Result<usize> {
+        if !dir.is_dir() {
+            return Ok(0);
+        }
+
+        // Only AFL++ solutions carry an id, e.g. the README.txt in crashes/ is never touched
+        let mut solutions: Vec<(u64, PathBuf)> = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?
+            .flatten()
+            .filter_map(|entry| {
+                let id = solution_id(&entry.file_name().to_string_lossy())?;
+                Some((id, entry.path()))
+            })
+            .collect();
+        if solutions.len() <= keep {
+            return Ok(0);
+        }
+
+        // Ids are assigned in order, so the lowest ones are the oldest solutions
+        solutions.sort_unstable_by_key(|(id, _)| *id);
+        let pruned: Vec<PathBuf> = solutions
+            .into_iter()
+            .rev()
+            .skip(keep)
+            .map(|(_, path)| path)
+            .collect();
+
+        if self.archive {
+            archive(dir, &pruned, self.clock)?;
+        }
+        Ok(pruned
+            .iter()
+            .filter(|path| fs::remove_file(path).is_ok())
+            .count())
+    }
+}
+
+/// Parses the id of an AFL++ solution file name like `id:000042,sig:11,...`
+fn solution_id(filename: &str) -> Option<u64> {
+    filename
+        .strip_prefix("id:")?
+        .split(',')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Free tarball name next to `dir`, e.g. `hangs_pruned_1700000000.tar`
+///
+/// Named after the wall-clock time, a counter is appended when a pass in the same second
+/// already archived into it.
+fn archive_path(dir: &Path) -> PathBuf {
+    let dir_name = dir.file_name().unwrap_or_default().to_string_lossy();
+    let timestamp = Clock::System.unix_secs();
+    let tarball = dir.with_file_name(format!("{dir_name}_pruned_{timestamp}.tar"));
+    if !tarball.exists() {
+        return tarball;
+    }
+    (1..)
+        .map(|n| dir.with_file_name(format!("{dir_name}_pruned_{timestamp}_{n}.tar")))
+        .find(|path| !path.exists())
+        .unwrap_or(tarball)
+}
+
+/// Archives `files` into a timestamped tarball next to `dir`, see `archive_path`
+///
+/// A fixed `clock` sets the modification time of every entry for reproducible archives, the
+/// tarball itself is always named after the wall-clock time so no pass overwrites another.
+fn archive(dir: &Path, files: &[PathBuf], clock: Clock) -> Result<()> {
+    let tarball = archive_path(dir);
+
+    // Pass the file list on stdin, millions of hangs would exceed the argument limit
+    let mut child = Command::new("tar");
+    if clock.fixed().is_some() {
+        child.arg(format!("--mtime=@{}", clock.unix_secs()));
+    }
+    let mut child = child
+        .arg("-cf")
+        .arg(&tarball)
+        .arg("-C")
+        .arg(dir)
+        .args(["-T", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to spawn tar")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        for file in files {
+            if let Some(name) = file.file_name() {
+                writeln!(stdin, "{}", name.to_string_lossy())?;
+            }
+        }
+    }
+
+    if !child.wait()?.success() {
+        bail!("Failed to archive pruned files into {}", tarball.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_solutions(dir: &Path, count: usize) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("README.txt"), "readme").unwrap();
+        for id in 0..count {
+            fs::write(
+                dir.join(format!(
+                    "id:{id:06},sig:11,src:000000,time:{id},execs:1,op:havoc,rep:2"
+                )),
+                "crash",
+            )
+            .unwrap();
+        }
+    }
+
+    fn remaining_ids(dir: &Path) -> Vec<u64> {
+        let mut ids: Vec<u64> = fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .filter_map(|e| solution_id(&e.file_name().to_string_lossy()))
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    #[test]
+    fn test_solution_id() {
+        assert_eq!(solution_id("id:000042,sig:11,src:000001"), Some(42));
+        assert_eq!(solution_id("id:000007"), Some(7));
+        assert_eq!(solution_id("README.txt"), None);
+        assert_eq!(solution_id("id:abc,sig:11"), None);
+    }
+
+    #[test]
+    fn test_enforce_keeps_newest() {
+        let tmp = TempDir::new().unwrap();
+        create_solutions(&tmp.path().join("crashes"), 5);
+        create_solutions(&tmp.path().join("hangs"), 3);
+
+        let policy = RetentionPolicy {
+            max_crashes: Some(2),
+            max_hangs: None,
+            archive: false,
+            clock: Clock::System,
+        };
+        assert!(policy.is_enabled());
+        assert_eq!(policy.enforce(tmp.path()).unwrap(), (3, 0));
+
+        assert_eq!(remaining_ids(&tmp.path().join("crashes")), [3, 4]);
+        assert_eq!(remaining_ids(&tmp.path().join("hangs")), [0, 1, 2]);
+        assert!(tmp.path().join("crashes/README.txt").exists());
+
+        // A second pass has nothing left to prune
+        assert_eq!(policy.enforce(tmp.path()).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_enforce_missing_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let policy = RetentionPolicy {
+            max_crashes: Some(0),
+            max_hangs: Some(0),
+            archive: false,
+            clock: Clock::System,
+        };
+        assert_eq!(policy.enforce(tmp.path()).unwrap(), (0, 0));
+        assert!(!RetentionPolicy::default().is_enabled());
+    }
+
+    #[test]
+    fn test_archive_with_fixed_clock() {
+        let tmp = TempDir::new().unwrap();
+        let instance = tmp.path().join("main");
+        create_solutions(&instance.join("crashes"), 3);
+
+        let policy = RetentionPolicy {
+            max_crashes: Some(1),
+            max_hangs: None,
+            archive: true,
+            clock: Clock::Fixed(std::time::UNIX_EPOCH + Duration::from_secs(1_714_564_800)),
+        };
+        assert_eq!(policy.enforce(&instance).unwrap(), (2, 0));
+        // A second pass with the same fixed clock must not overwrite the first archive
+        create_solutions(&instance.join("crashes"), 5);
+        assert_eq!(policy.enforce(&instance).unwrap(), (4, 0));
+
+        let mut tarballs: Vec<PathBuf> = fs::read_dir(&instance)
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "tar"))
+            .collect();
+        tarballs.sort();
+        assert_eq!(tarballs.len(), 2);
+
+        let mut entries = 0;
+        for tarball in &tarballs {
+            let listing = Command::new("tar")
+                .args(["--utc", "--full-time", "-tvf"])
+                .arg(tarball)
+                .output()
+                .unwrap();
+            let listing = String::from_utf8_lossy(&listing.stdout);
+            entries += listing.lines().count();
+            assert!(listing
+                .lines()
+                .all(|line| line.contains("2024-05-01 12:00:00")));
+        }
+        assert_eq!(entries, 6);
+    }
+}
@@ -1,10 +1,31 @@
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Local};
-use std::path::PathBuf;
+use serde::Serialize;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard, PoisonError,
+};
 use std::time::{Duration, Instant, SystemTime};
 
+use crate::tui::remote::RemoteStatus;
+use crate::tui::retention::PruneStats;
+use crate::tui::stability::StabilityCollapse;
+use crate::tui::starvation::Starvation;
 use crate::utils::log_buffer::LogRingBuffer;
 
-#[derive(Default, Debug, Clone)]
+/// Amount of latest crashes and hangs shown in the TUI
+pub const LATEST_SOLUTIONS: usize = 10;
+/// Amount of latest crashes and hangs retained per collection cycle, so that filtering the
+/// TUI panels by instance or signal still finds older matches
+pub const SOLUTION_BACKLOG: usize = 200;
+/// Default amount of log lines retained, see `CampaignData::with_log_capacity`
+pub const DEFAULT_LOG_CAPACITY: usize = 10;
+
+#[derive(Default, Debug, Clone, Serialize)]
 pub struct Stats<T> {
     pub avg: T,
     pub min: T,
@@ -18,26 +39,67 @@ impl<T: Default> Stats<T> {
     }
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize)]
 pub struct ExtendedStats {
     pub favorites: Stats<usize>,
     pub total: Stats<usize>,
+    /// How the pending favorites of all instances moved since the previous cycle
+    pub favorites_trend: Trend,
+}
+
+/// Direction a value moved since the previous collection cycle
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trend {
+    Rising,
+    Falling,
+    /// Unchanged, or nothing to compare against yet
+    #[default]
+    Steady,
+}
+
+impl Trend {
+    pub fn between(previous: Option<usize>, current: usize) -> Self {
+        match previous {
+            Some(previous) if current > previous => Self::Rising,
+            Some(previous) if current < previous => Self::Falling,
+            _ => Self::Steady,
+        }
+    }
+
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Self::Rising => "↑",
+            Self::Falling => "↓",
+            Self::Steady => "→",
+        }
+    }
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize)]
 pub struct CycleStats {
     pub done: Stats<usize>,
     pub wo_finds: Stats<usize>,
+    /// Fraction of all cycles done that did not yield new finds, high values indicate saturation
+    pub wo_finds_ratio: f64,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct EdgeStats {
+    /// Edges found per instance
+    pub found: Stats<usize>,
+    /// Total instrumented edges of the target
+    pub total: usize,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
 pub struct ExecutionStats {
     pub count: Stats<usize>,
     pub per_sec: Stats<f64>,
 }
 
 #[allow(dead_code)]
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize)]
 pub struct CrashInfoDetails {
     pub fuzzer_name: String,
     pub file_path: PathBuf,
@@ -50,13 +112,36 @@ pub struct CrashInfoDetails {
     pub rep: u64,
 }
 
-#[derive(Default, Debug, Clone)]
+/// Metrics retained for every alive instance
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct InstanceMetrics {
+    pub name: String,
+    pub execs_per_sec: f64,
+    /// Build id of the binary in the instance's `cmdline`
+    pub build_id: Option<String>,
+    /// The binary differs from the build the campaign was launched with
+    pub build_mismatch: bool,
+    /// Peak resident memory of the target in MB
+    pub peak_rss_mb: u64,
+    /// Alive but without a stats update for longer than the stall threshold
+    pub stalled: bool,
+    /// Runs as the `-M` main instance
+    pub main: bool,
+    pub corpus_count: usize,
+    pub saved_crashes: usize,
+    /// Stability in percent
+    pub stability: f64,
+    /// Unix timestamp of the last new path, `None` if the instance found none yet
+    pub last_find: Option<u64>,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
 pub struct Misc {
     pub afl_version: String,
     pub afl_banner: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CampaignData {
     pub fuzzers_alive: Vec<usize>,
     pub fuzzers_started: usize,
@@ -66,17 +151,41 @@ pub struct CampaignData {
     pub pending: ExtendedStats,
     pub corpus: Stats<usize>,
     pub coverage: Stats<f64>,
+    pub edges: EdgeStats,
     pub cycles: CycleStats,
     pub stability: Stats<f64>,
     pub crashes: Stats<usize>,
     pub hangs: Stats<usize>,
     pub levels: Stats<usize>,
     pub time_without_finds: Stats<usize>,
+    /// Up to `SOLUTION_BACKLOG` crashes, newest first
     pub last_crashes: Vec<CrashInfoDetails>,
+    /// Up to `SOLUTION_BACKLOG` hangs, newest first
     pub last_hangs: Vec<CrashInfoDetails>,
+    pub instances: Vec<InstanceMetrics>,
+    pub pruned: PruneStats,
+    /// Amount of `fuzzer_stats` files parsed during the last collection cycle
+    pub stats_parsed: usize,
+    /// Rough estimate of the time an instance needs for a full queue cycle
+    pub est_cycle_time: Option<Duration>,
+    /// Main instance that persistently runs far slower than the secondaries
+    pub starved_main: Option<Starvation>,
+    /// Sudden drop of the average stability below its rolling baseline
+    pub stability_collapse: Option<StabilityCollapse>,
     pub misc: Misc,
+    /// Campaign these statistics cover when monitoring several, 0 for all of them
+    #[serde(skip)]
+    pub view: usize,
+    #[serde(skip)]
     pub start_time: Option<Instant>,
-    pub logs: LogRingBuffer<String>,
+    #[serde(skip)]
+    pub logs: LogRingBuffer<LogEntry>,
+    /// Persists every logged line beyond the in-memory ring
+    #[serde(skip)]
+    pub log_file: Option<LogFile>,
+    /// Connectivity of a campaign monitored over SSH
+    #[serde(skip)]
+    pub remote: Option<RemoteStatus>,
 }
 
 impl Default for CampaignData {
@@ -90,17 +199,27 @@ impl Default for CampaignData {
             pending: ExtendedStats::default(),
             corpus: Stats::new(),
             coverage: Stats::new(),
+            edges: EdgeStats::default(),
             cycles: CycleStats::default(),
             stability: Stats::new(),
             crashes: Stats::new(),
             hangs: Stats::new(),
             levels: Stats::new(),
             time_without_finds: Stats::new(),
-            last_crashes: Vec::with_capacity(10),
-            last_hangs: Vec::with_capacity(10),
+            last_crashes: Vec::with_capacity(SOLUTION_BACKLOG),
+            last_hangs: Vec::with_capacity(SOLUTION_BACKLOG),
+            instances: Vec::new(),
+            pruned: PruneStats::default(),
+            stats_parsed: 0,
+            est_cycle_time: None,
+            starved_main: None,
+            stability_collapse: None,
             misc: Misc::default(),
+            view: 0,
             start_time: None,
-            logs: LogRingBuffer::new(10),
+            logs: LogRingBuffer::new(DEFAULT_LOG_CAPACITY),
+            log_file: None,
+            remote: None,
         }
     }
 }
@@ -110,28 +229,338 @@ impl CampaignData {
         Self::default()
     }
 
+    /// Resets the per-cycle statistics in place
+    ///
+    /// PIDs, run time, misc info, prune counters, the starved main instance and logs outlive a
+    /// collection cycle. The
+    /// vectors keep their allocations so a steady-state cycle does not reallocate them.
     pub fn clear(&mut self) {
-        let pids = self.fuzzer_pids.clone();
-        let fuzzers_alive = self.fuzzers_alive.clone();
-        let fuzzers_started = self.fuzzers_started;
-        let total_runtime = self.total_run_time;
-        let misc = self.misc.clone();
-        let start_time = self.start_time;
-        let logs = self.logs.clone();
-        *self = Self::new();
-        self.fuzzer_pids = pids;
-        self.fuzzers_alive = fuzzers_alive;
-        self.fuzzers_started = fuzzers_started;
-        self.total_run_time = total_runtime;
-        self.misc = misc;
-        self.start_time = start_time;
-        self.logs = logs;
+        let Self {
+            fuzzers_alive: _,
+            fuzzers_started: _,
+            fuzzer_pids: _,
+            total_run_time: _,
+            executions,
+            pending,
+            corpus,
+            coverage,
+            edges,
+            cycles,
+            stability,
+            crashes,
+            hangs,
+            levels,
+            time_without_finds,
+            last_crashes,
+            last_hangs,
+            instances,
+            pruned: _,
+            stats_parsed,
+            est_cycle_time,
+            starved_main: _,
+            stability_collapse: _,
+            misc: _,
+            view: _,
+            start_time: _,
+            logs: _,
+            log_file: _,
+            remote: _,
+        } = self;
+        *executions = ExecutionStats::default();
+        *pending = ExtendedStats::default();
+        *corpus = Stats::new();
+        *coverage = Stats::new();
+        *edges = EdgeStats::default();
+        *cycles = CycleStats::default();
+        *stability = Stats::new();
+        *crashes = Stats::new();
+        *hangs = Stats::new();
+        *levels = Stats::new();
+        *time_without_finds = Stats::new();
+        last_crashes.clear();
+        last_hangs.clear();
+        instances.clear();
+        *stats_parsed = 0;
+        *est_cycle_time = None;
+    }
+
+    /// Copies `other` into `self`, reusing the allocations of `self` where possible
+    pub fn copy_from(&mut self, other: &Self) {
+        let Self {
+            fuzzers_alive,
+            fuzzers_started,
+            fuzzer_pids,
+            total_run_time,
+            executions,
+            pending,
+            corpus,
+            coverage,
+            edges,
+            cycles,
+            stability,
+            crashes,
+            hangs,
+            levels,
+            time_without_finds,
+            last_crashes,
+            last_hangs,
+            instances,
+            pruned,
+            stats_parsed,
+            est_cycle_time,
+            starved_main,
+            stability_collapse,
+            misc,
+            view,
+            start_time,
+            logs,
+            log_file,
+            remote,
+        } = other;
+        self.fuzzers_alive.clone_from(fuzzers_alive);
+        self.fuzzers_started = *fuzzers_started;
+        self.fuzzer_pids.clone_from(fuzzer_pids);
+        self.total_run_time = *total_run_time;
+        self.executions.clone_from(executions);
+        self.pending.clone_from(pending);
+        self.corpus.clone_from(corpus);
+        self.coverage.clone_from(coverage);
+        self.edges.clone_from(edges);
+        self.cycles.clone_from(cycles);
+        self.stability.clone_from(stability);
+        self.crashes.clone_from(crashes);
+        self.hangs.clone_from(hangs);
+        self.levels.clone_from(levels);
+        self.time_without_finds.clone_from(time_without_finds);
+        self.last_crashes.clone_from(last_crashes);
+        self.last_hangs.clone_from(last_hangs);
+        self.instances.clone_from(instances);
+        self.pruned.clone_from(pruned);
+        self.stats_parsed = *stats_parsed;
+        self.est_cycle_time = *est_cycle_time;
+        self.starved_main.clone_from(starved_main);
+        self.stability_collapse.clone_from(stability_collapse);
+        self.misc.clone_from(misc);
+        self.view = *view;
+        self.start_time = *start_time;
+        self.logs.clone_from(logs);
+        self.log_file.clone_from(log_file);
+        self.remote.clone_from(remote);
+    }
+
+    /// Retains the last `capacity` log lines instead of `DEFAULT_LOG_CAPACITY`, dropping the
+    /// lines logged so far
+    ///
+    /// Every retained line is held by the collector and the renderer, so a large ring trades
+    /// memory and redraw time for history.
+    ///
+    /// # Errors
+    /// * If `capacity` is zero
+    pub fn with_log_capacity(mut self, capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            bail!("The log capacity must be at least 1");
+        }
+        self.logs = LogRingBuffer::new(capacity);
+        Ok(self)
+    }
+
+    /// Appends every line logged from now on to `path` as well
+    ///
+    /// # Errors
+    /// * If the file cannot be opened
+    pub fn with_log_file(mut self, path: &Path) -> Result<Self> {
+        self.log_file = Some(LogFile::open(path)?);
+        Ok(self)
+    }
+
+    /// Amount of alive instances that stopped updating their stats
+    pub fn stalled_count(&self) -> usize {
+        self.instances.iter().filter(|i| i.stalled).count()
     }
 
     pub fn log<T: AsRef<str>>(&mut self, message: T) {
+        self.log_at(LogLevel::Info, message);
+    }
+
+    pub fn warn<T: AsRef<str>>(&mut self, message: T) {
+        self.log_at(LogLevel::Warn, message);
+    }
+
+    pub fn error<T: AsRef<str>>(&mut self, message: T) {
+        self.log_at(LogLevel::Error, message);
+    }
+
+    pub fn log_at<T: AsRef<str>>(&mut self, level: LogLevel, message: T) {
         let now: DateTime<Local> = SystemTime::now().into();
-        let timestamp = now.format("%Y-%m-%d %H:%M:%S");
-        self.logs
-            .push(format!("[{timestamp}] - {}", message.as_ref()));
+        let entry = LogEntry {
+            timestamp: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            level,
+            message: message.as_ref().to_string(),
+        };
+        if let Some(log_file) = &self.log_file {
+            log_file.append(&entry.to_string());
+        }
+        self.logs.push(entry);
+    }
+}
+
+/// Severity of a logged line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        })
+    }
+}
+
+/// A single line in the TUI log pane
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {:<5} - {}",
+            self.timestamp, self.level, self.message
+        )
+    }
+}
+
+/// Append-only log file shared by all copies of a `CampaignData`
+#[derive(Debug, Clone)]
+pub struct LogFile(Arc<Mutex<File>>);
+
+impl LogFile {
+    /// Opens `path` for appending, creating it if it does not exist
+    ///
+    /// # Errors
+    /// * If the file cannot be opened
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        Ok(Self(Arc::new(Mutex::new(file))))
+    }
+
+    /// Appends `line`, a failing disk must not take down the monitoring
+    fn append(&self, line: &str) {
+        let mut file = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = writeln!(file, "{line}");
+    }
+}
+
+/// Campaign data shared between the collector thread and the renderer
+///
+/// The collector publishes every cycle into the same buffer instead of sending a fresh clone,
+/// the renderer only redraws once the generation changed.
+#[derive(Debug, Default)]
+pub struct SharedCampaignData {
+    data: Mutex<CampaignData>,
+    generation: AtomicU64,
+    view: AtomicUsize,
+}
+
+impl SharedCampaignData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the shared snapshot with `data`
+    pub fn publish(&self, data: &CampaignData) {
+        self.lock().copy_from(data);
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    /// Amount of snapshots published so far
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Selects the campaign the collector publishes, 0 for the overview of all campaigns
+    pub fn set_view(&self, view: usize) {
+        self.view.store(view, Ordering::Release);
+    }
+
+    /// Campaign requested by the renderer, see `set_view`
+    pub fn view(&self) -> usize {
+        self.view.load(Ordering::Acquire)
+    }
+
+    /// Locks the latest snapshot, a panicked collector leaves the last published data behind
+    pub fn lock(&self) -> MutexGuard<'_, CampaignData> {
+        self.data.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_log_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("tui.log");
+        fs::write(&path, "previous run\n").unwrap();
+
+        let mut data = CampaignData::new().with_log_file(&path).unwrap();
+        data.log("Initialized TUI");
+        let mut copy = CampaignData::new();
+        copy.copy_from(&data);
+        copy.log("No fuzzers alive");
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "previous run");
+        assert!(lines[1].starts_with('[') && lines[1].ends_with("] INFO  - Initialized TUI"));
+        assert!(lines[2].ends_with("] INFO  - No fuzzers alive"));
+        assert!(CampaignData::new()
+            .with_log_file(&tmp.path().join("missing/tui.log"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_log_capacity() {
+        let mut data = CampaignData::new().with_log_capacity(3).unwrap();
+        for idx in 0..5 {
+            data.log(format!("event {idx}"));
+        }
+        let logs = data.logs.join("\n", false);
+        assert_eq!(logs.lines().count(), 3);
+        assert!(logs.starts_with('[') && logs.ends_with("event 4"));
+        assert!(!logs.contains("event 1"));
+        assert!(CampaignData::new().with_log_capacity(0).is_err());
+    }
+
+    #[test]
+    fn test_log_levels() {
+        let mut data = CampaignData::new();
+        data.log("Initialized TUI");
+        data.warn("No fuzzers alive");
+        data.error("Fuzzer main: pruning failed");
+
+        let levels: Vec<LogLevel> = data.logs.iter().map(|entry| entry.level).collect();
+        assert_eq!(levels, [LogLevel::Info, LogLevel::Warn, LogLevel::Error]);
+        let logs = data.logs.join("\n", true);
+        assert!(logs.starts_with('[') && logs.contains("] ERROR - Fuzzer main"));
+        assert!(logs.ends_with("] INFO  - Initialized TUI"));
     }
 }
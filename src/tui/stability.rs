@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+/// Percentage points the average stability has to drop below its baseline to be reported
+pub const DEFAULT_STABILITY_DROP: f64 = 5.0;
+/// Collection cycles the rolling baseline averages over
+///
+/// The TUI collects about once a second, so the baseline covers the last two minutes and the
+/// watch stays quiet for that long after startup
+pub const STABILITY_BASELINE_CYCLES: usize = 120;
+
+/// A sudden drop of the average stability, e.g. once a nondeterministic code path is hit
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StabilityCollapse {
+    /// Average stability of the rolling baseline in percent
+    pub baseline: f64,
+    /// Average stability in the cycle the drop was detected in percent
+    pub stability: f64,
+}
+
+impl StabilityCollapse {
+    /// Percentage points the stability dropped
+    pub fn drop(&self) -> f64 {
+        self.baseline - self.stability
+    }
+
+    pub fn message(&self) -> String {
+        format!(
+            "Stability collapsed from {:.2}% to {:.2}% (-{:.2} points). The target likely hit a \
+             nondeterministic code path, check the unstable edges with AFL_DEBUG=1",
+            self.baseline,
+            self.stability,
+            self.drop()
+        )
+    }
+}
+
+/// Compares the average stability of every collection cycle against a rolling baseline
+#[derive(Debug, Clone)]
+pub struct StabilityWatch {
+    delta: f64,
+    cycles: usize,
+    history: VecDeque<f64>,
+}
+
+impl Default for StabilityWatch {
+    fn default() -> Self {
+        Self::new(DEFAULT_STABILITY_DROP, STABILITY_BASELINE_CYCLES)
+    }
+}
+
+impl StabilityWatch {
+    pub fn new(delta: f64, cycles: usize) -> Self {
+        Self {
+            delta,
+            cycles,
+            history: VecDeque::with_capacity(cycles),
+        }
+    }
+
+    /// Checks the average stability of one collection cycle, returns the collapse while the
+    /// stability stays more than `delta` points below the baseline
+    ///
+    /// Collapsed cycles are kept out of the baseline, so a lasting drop stays reported until the
+    /// stability recovers. Cycles without stability data are ignored.
+    pub fn update(&mut self, stability: f64) -> Option<StabilityCollapse> {
+        if stability <= 0.0 {
+            return None;
+        }
+        if self.history.len() == self.cycles {
+            #[allow(clippy::cast_precision_loss)]
+            let baseline = self.history.iter().sum::<f64>() / self.cycles as f64;
+            if baseline - stability > self.delta {
+                return Some(StabilityCollapse {
+                    baseline,
+                    stability,
+                });
+            }
+            self.history.pop_front();
+        }
+        self.history.push_back(stability);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stability_collapse() {
+        let mut watch = StabilityWatch::new(DEFAULT_STABILITY_DROP, 3);
+        // No baseline yet
+        assert_eq!(watch.update(99.0), None);
+        assert_eq!(watch.update(50.0), None);
+        assert_eq!(watch.update(0.0), None);
+        assert_eq!(watch.update(100.0), None);
+        // Baseline of 83% allows drops down to 78%
+        assert_eq!(watch.update(80.0), None);
+
+        let mut watch = StabilityWatch::new(DEFAULT_STABILITY_DROP, 3);
+        for _ in 0..3 {
+            assert_eq!(watch.update(99.5), None);
+        }
+        let collapse = watch.update(90.0).unwrap();
+        assert!((collapse.drop() - 9.5).abs() < 1e-9);
+        assert!(collapse
+            .message()
+            .starts_with("Stability collapsed from 99.50% to 90.00% (-9.50 points)"));
+        // The collapse does not lower the baseline
+        assert_eq!(watch.update(91.0).map(|c| c.baseline), Some(99.5));
+        assert_eq!(watch.update(99.0), None);
+    }
+
+    #[test]
+    fn test_slow_stability_decline() {
+        let mut watch = StabilityWatch::new(2.0, 2);
+        // A gradual decline moves the baseline along instead of alerting
+        for stability in [100.0, 99.0, 98.0, 97.0, 96.0, 95.0] {
+            assert_eq!(watch.update(stability), None);
+        }
+        assert!(watch.update(92.0).is_some());
+    }
+}
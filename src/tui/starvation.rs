@@ -0,0 +1,159 @@
+use serde::Serialize;
+
+use crate::tui::heatmap::median_execs_per_sec;
+use crate::tui::session::InstanceMetrics;
+
+/// Fraction of the median secondary execs/s below which the main instance starves
+pub const DEFAULT_STARVATION_RATIO: f64 = 0.1;
+/// Consecutive collection cycles the main instance has to starve before it is reported
+///
+/// The TUI collects about once a second, so short dips like the calibration of synced seeds are
+/// not reported
+pub const STARVATION_CYCLES: u32 = 90;
+
+/// A main instance that runs far slower than its secondaries
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Starvation {
+    pub instance: String,
+    pub execs_per_sec: f64,
+    /// Median execs/s of the secondaries
+    pub median: f64,
+}
+
+impl Starvation {
+    /// Throughput of the main instance in percent of the secondaries' median
+    pub fn percent(&self) -> f64 {
+        self.execs_per_sec / self.median * 100.0
+    }
+
+    /// Warning along with how to fix the usual cause, a sanitizer build as `-M`
+    pub fn message(&self) -> String {
+        format!(
+            "Fuzzer {}: main instance runs at {:.0} execs/s, {:.1}% of the secondaries' median of {:.0}. \
+             Queue culling and syncing lag behind, move a sanitizer build to a secondary with \
+             --san-on-secondary or raise its -t",
+            self.instance,
+            self.execs_per_sec,
+            self.percent(),
+            self.median
+        )
+    }
+}
+
+/// Tracks whether the main instance keeps up with the secondaries across collection cycles
+#[derive(Debug, Clone)]
+pub struct StarvationDetector {
+    ratio: f64,
+    cycles: u32,
+    streak: u32,
+}
+
+impl Default for StarvationDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_STARVATION_RATIO, STARVATION_CYCLES)
+    }
+}
+
+impl StarvationDetector {
+    pub fn new(ratio: f64, cycles: u32) -> Self {
+        Self {
+            ratio,
+            cycles,
+            streak: 0,
+        }
+    }
+
+    /// Checks the instances of one collection cycle, returns the main instance once it starved
+    /// for the configured amount of consecutive cycles
+    pub fn update(&mut self, instances: &[InstanceMetrics]) -> Option<Starvation> {
+        let Some(starvation) = self.starving(instances) else {
+            self.streak = 0;
+            return None;
+        };
+        self.streak = self.streak.saturating_add(1);
+        (self.streak >= self.cycles).then_some(starvation)
+    }
+
+    /// The main instance if it is below the ratio of the secondaries' median in this cycle
+    ///
+    /// Only single campaigns are checked, stalled main instances are reported as such already
+    fn starving(&self, instances: &[InstanceMetrics]) -> Option<Starvation> {
+        let mut mains = instances.iter().filter(|i| i.main);
+        let main = mains.next().filter(|main| !main.stalled)?;
+        if mains.next().is_some() {
+            return None;
+        }
+        let secondaries: Vec<InstanceMetrics> =
+            instances.iter().filter(|i| !i.main).cloned().collect();
+        let median = median_execs_per_sec(&secondaries).filter(|median| *median > 0.0)?;
+        (main.execs_per_sec < median * self.ratio).then(|| Starvation {
+            instance: main.name.clone(),
+            execs_per_sec: main.execs_per_sec,
+            median,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fleet(main: f64, secondaries: &[f64]) -> Vec<InstanceMetrics> {
+        let mut instances = vec![InstanceMetrics {
+            name: "m_target".to_string(),
+            execs_per_sec: main,
+            main: true,
+            ..InstanceMetrics::default()
+        }];
+        instances.extend(secondaries.iter().enumerate().map(|(i, &execs_per_sec)| {
+            InstanceMetrics {
+                name: format!("s{i}_target"),
+                execs_per_sec,
+                ..InstanceMetrics::default()
+            }
+        }));
+        instances
+    }
+
+    #[test]
+    fn test_starvation_needs_consecutive_cycles() {
+        let mut detector = StarvationDetector::new(DEFAULT_STARVATION_RATIO, 3);
+        let starved = fleet(50.0, &[1000.0, 2000.0, 1500.0]);
+        assert_eq!(detector.update(&starved), None);
+        assert_eq!(detector.update(&starved), None);
+        // A single cycle above the ratio restarts the streak
+        assert_eq!(
+            detector.update(&fleet(200.0, &[1000.0, 2000.0, 1500.0])),
+            None
+        );
+        assert_eq!(detector.update(&starved), None);
+        assert_eq!(detector.update(&starved), None);
+
+        let starvation = detector.update(&starved).unwrap();
+        assert_eq!(starvation.instance, "m_target");
+        assert!((starvation.median - 1500.0).abs() < f64::EPSILON);
+        assert!(starvation
+            .message()
+            .contains("3.3% of the secondaries' median of 1500"));
+        assert!(detector.update(&starved).is_some());
+    }
+
+    #[test]
+    fn test_starvation_ignores_unclear_fleets() {
+        let mut detector = StarvationDetector::new(0.5, 1);
+        assert!(detector.update(&fleet(400.0, &[1000.0])).is_some());
+        assert_eq!(detector.update(&fleet(600.0, &[1000.0])), None);
+        // Without secondaries or with all of them idle there is nothing to compare to
+        assert_eq!(detector.update(&fleet(10.0, &[])), None);
+        assert_eq!(detector.update(&fleet(0.0, &[0.0, 0.0])), None);
+
+        let mut stalled = fleet(10.0, &[1000.0]);
+        stalled[0].stalled = true;
+        assert_eq!(detector.update(&stalled), None);
+
+        // Several campaigns have several main instances
+        let mut campaigns = fleet(10.0, &[1000.0]);
+        campaigns.extend(fleet(10.0, &[1000.0]));
+        assert_eq!(detector.update(&campaigns), None);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    env,
+    fmt::Write as _,
+    io::{self, IsTerminal, Write},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+
+use crate::tui::data_collection::{DataFetcher, InstanceRow};
+use crate::tui::format::NumberFormat;
+use crate::tui::Tui;
+
+/// Time between two refreshes of the table
+pub const TOP_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Set once Ctrl-C was pressed while the table is shown
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Renders one row per instance along with a header, `now` is the current Unix time
+pub fn render_table(rows: &[InstanceRow], now: u64, fmt: NumberFormat) -> String {
+    let width = rows
+        .iter()
+        .map(|row| row.name.len())
+        .max()
+        .unwrap_or_default()
+        .max("instance".len());
+    let mut table = format!(
+        "{:<width$}  {:<15}  {:>10}  {:>8}  {:>7}  {:>9}  {}\n",
+        "instance", "state", "execs/s", "corpus", "crashes", "stability", "last find"
+    );
+    for row in rows {
+        let (execs, corpus, crashes, stability, last_find) = match &row.metrics {
+            Some(m) => (
+                fmt.format_float(m.execs_per_sec),
+                fmt.format_int(m.corpus_count),
+                fmt.format_int(m.saved_crashes),
+                format!("{:.2}%", m.stability),
+                m.last_find.map_or_else(
+                    || "none yet".to_string(),
+                    |ts| {
+                        let age = Duration::from_secs(now.saturating_sub(ts));
+                        format!("{} ago", Tui::format_duration(&age))
+                    },
+                ),
+            ),
+            None => {
+                let dash = || "-".to_string();
+                (dash(), dash(), dash(), dash(), dash())
+            }
+        };
+        let _ = writeln!(
+            table,
+            "{:<width$}  {:<15}  {execs:>10}  {corpus:>8}  {crashes:>7}  {stability:>9}  {last_find}",
+            row.name,
+            row.state.to_string()
+        );
+    }
+    table
+}
+
+/// Shows the instances of the campaign `fetcher` follows until Ctrl-C, or once if `once` is set
+///
+/// On capable terminals the table is redrawn in place, dumb terminals and pipes get a full
+/// reprint every `TOP_INTERVAL`.
+///
+/// # Errors
+/// * If the Ctrl-C handler cannot be installed
+pub fn run(fetcher: &mut DataFetcher, once: bool, fmt: NumberFormat) -> Result<()> {
+    if once {
+        fetcher.collect_session_data();
+        print!(
+            "{}",
+            render_table(&fetcher.instance_rows(), unix_now(), fmt)
+        );
+        return Ok(());
+    }
+
+    ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst))
+        .context("Failed to install the Ctrl-C handler")?;
+    let in_place = io::stdout().is_terminal() && env::var("TERM").is_ok_and(|term| term != "dumb");
+    let mut drawn_lines = 0;
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        let data = fetcher.collect_session_data();
+        let header = format!(
+            "[{}] {}/{} fuzzers alive, Ctrl-C to quit\n",
+            Tui::format_duration(&data.total_run_time),
+            data.fuzzers_alive.len(),
+            data.fuzzers_started
+        );
+        let frame = header + &render_table(&fetcher.instance_rows(), unix_now(), fmt);
+        if in_place && drawn_lines > 0 {
+            // Back to the top of the previous frame and clear everything below
+            print!("\x1b[{drawn_lines}A\x1b[J");
+        } else if drawn_lines > 0 {
+            println!();
+        }
+        print!("{frame}");
+        let _ = io::stdout().flush();
+        drawn_lines = frame.lines().count();
+
+        let next = Instant::now() + TOP_INTERVAL;
+        while Instant::now() < next && !INTERRUPTED.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+    Ok(())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::forensics::InstanceState;
+    use crate::tui::session::InstanceMetrics;
+
+    #[test]
+    fn test_render_table() {
+        let rows = [
+            InstanceRow {
+                name: "m_target".to_string(),
+                state: InstanceState::Running,
+                metrics: Some(InstanceMetrics {
+                    name: "m_target".to_string(),
+                    execs_per_sec: 2_500.4,
+                    corpus_count: 1_234,
+                    saved_crashes: 3,
+                    stability: 99.5,
+                    last_find: Some(1_000),
+                    ..InstanceMetrics::default()
+                }),
+            },
+            InstanceRow {
+                name: "s1_target".to_string(),
+                state: InstanceState::Stalled,
+                metrics: Some(InstanceMetrics {
+                    name: "s1_target".to_string(),
+                    ..InstanceMetrics::default()
+                }),
+            },
+            InstanceRow {
+                name: "s2_target".to_string(),
+                state: InstanceState::DiedAbnormally,
+                metrics: None,
+            },
+        ];
+
+        let table = render_table(&rows, 1_125, NumberFormat::Raw);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "instance   state               execs/s    corpus  crashes  stability  last find",
+                "m_target   running               2,500     1,234        3     99.50%  02:05 ago",
+                "s1_target  stalled                   0         0        0      0.00%  none yet",
+                "s2_target  died abnormally           -         -        -          -  -",
+            ]
+        );
+        assert_eq!(render_table(&[], 0, NumberFormat::Raw).lines().count(), 1);
+    }
+}
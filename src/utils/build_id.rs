@@ -0,0 +1,329 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::tui::control::RUNTIME_DIR;
+use crate::utils::elf::{parse_ident, Reader};
+
+/// File inside `<output>/.aflr` with the build ids of the binaries a campaign was launched with
+pub const BUILD_IDS_FILE: &str = "build_ids.json";
+/// Amount of trailing hex digits shown for a build id
+pub const SHORT_LEN: usize = 8;
+
+const SHT_NOTE: u32 = 7;
+const NT_GNU_BUILD_ID: u32 = 3;
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Identifies the build of a binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildId {
+    /// The GNU build id note, as shown by `readelf -n`
+    Gnu(String),
+    /// A hash of the file contents for binaries without a build id note
+    ContentHash(String),
+}
+
+impl BuildId {
+    /// Reads the build id of the binary at `path`, hashing its contents if it has no build id note
+    ///
+    /// # Errors
+    /// * If the file cannot be read
+    pub fn read(path: &Path) -> Result<Self> {
+        let content =
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Self::from_bytes(&content))
+    }
+
+    /// Build id of an in-memory binary
+    pub fn from_bytes(content: &[u8]) -> Self {
+        gnu_build_id(content).map_or_else(
+            || Self::ContentHash(format!("{:016x}", fnv1a(content))),
+            |id| Self::Gnu(id.iter().map(|b| format!("{b:02x}")).collect()),
+        )
+    }
+
+    /// The id as hex string
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Gnu(id) | Self::ContentHash(id) => id,
+        }
+    }
+
+    /// The last `SHORT_LEN` hex digits, enough to tell builds apart at a glance
+    pub fn short(&self) -> &str {
+        short_id(self.as_str())
+    }
+}
+
+/// The last `SHORT_LEN` characters of a hex build id
+pub fn short_id(id: &str) -> &str {
+    id.get(id.len().saturating_sub(SHORT_LEN)..).unwrap_or(id)
+}
+
+impl fmt::Display for BuildId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Build ids of the binaries a campaign was launched with, keyed by binary path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildIdRecord {
+    pub binaries: BTreeMap<PathBuf, String>,
+}
+
+impl BuildIdRecord {
+    /// Reads the build ids of `binaries`, unreadable binaries are skipped
+    pub fn new<'a>(binaries: impl IntoIterator<Item = &'a Path>) -> Self {
+        Self {
+            binaries: binaries
+                .into_iter()
+                .filter_map(|path| {
+                    BuildId::read(path)
+                        .ok()
+                        .map(|id| (path.to_path_buf(), id.to_string()))
+                })
+                .collect(),
+        }
+    }
+
+    /// Location of the record for the campaign in `output_dir`
+    pub fn path(output_dir: &Path) -> PathBuf {
+        output_dir.join(RUNTIME_DIR).join(BUILD_IDS_FILE)
+    }
+
+    /// Loads the record of the campaign in `output_dir`, if any
+    pub fn load(output_dir: &Path) -> Option<Self> {
+        fs::read_to_string(Self::path(output_dir))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Stores the record for the campaign in `output_dir`
+    ///
+    /// # Errors
+    /// * If the record cannot be written
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        let path = Self::path(output_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The recorded build id of `binary`
+    pub fn get(&self, binary: &Path) -> Option<&str> {
+        self.binaries.get(binary).map(String::as_str)
+    }
+}
+
+/// Extracts the descriptor of the `NT_GNU_BUILD_ID` note from the section headers of an ELF file
+fn gnu_build_id(elf: &[u8]) -> Option<&[u8]> {
+    let (is_64, reader) = parse_ident(elf)?;
+    let le = reader.le;
+
+    let (shoff, shentsize, shnum) = if is_64 {
+        (reader.u64(0x28)?, reader.u16(0x3a)?, reader.u16(0x3c)?)
+    } else {
+        (
+            u64::from(reader.u32(0x20)?),
+            reader.u16(0x2e)?,
+            reader.u16(0x30)?,
+        )
+    };
+
+    (0..usize::from(shnum)).find_map(|idx| {
+        let header = usize::try_from(shoff)
+            .ok()?
+            .checked_add(idx * usize::from(shentsize))?;
+        if reader.u32(header.checked_add(4)?)? != SHT_NOTE {
+            return None;
+        }
+        let (offset, size) = if is_64 {
+            (
+                reader.u64(header.checked_add(0x18)?)?,
+                reader.u64(header.checked_add(0x20)?)?,
+            )
+        } else {
+            (
+                u64::from(reader.u32(header.checked_add(0x10)?)?),
+                u64::from(reader.u32(header.checked_add(0x14)?)?),
+            )
+        };
+        let start = usize::try_from(offset).ok()?;
+        let notes = elf.get(start..start.checked_add(usize::try_from(size).ok()?)?)?;
+        find_build_id_note(&Reader { data: notes, le })
+    })
+}
+
+/// Walks the notes of a `SHT_NOTE` section
+fn find_build_id_note<'a>(notes: &Reader<'a>) -> Option<&'a [u8]> {
+    let align = |n: usize| n.checked_add(3).map(|n| n & !3);
+    let mut pos = 0;
+    while pos + 12 <= notes.data.len() {
+        let namesz = usize::try_from(notes.u32(pos)?).ok()?;
+        let descsz = usize::try_from(notes.u32(pos + 4)?).ok()?;
+        let kind = notes.u32(pos + 8)?;
+        let name_start = pos + 12;
+        let desc_start = name_start.checked_add(align(namesz)?)?;
+        let desc_end = desc_start.checked_add(descsz)?;
+        if kind == NT_GNU_BUILD_ID
+            && notes
+                .data
+                .get(name_start..name_start.checked_add(namesz)?)?
+                == b"GNU\0"
+            && descsz > 0
+        {
+            return notes.data.get(desc_start..desc_end);
+        }
+        pos = desc_start.checked_add(align(descsz)?)?;
+    }
+    None
+}
+
+/// 64-bit FNV-1a, stable across platforms and Rust versions
+pub(crate) fn fnv1a(content: &[u8]) -> u64 {
+    content.iter().fold(FNV_OFFSET, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Builds a minimal ELF file with a null section and a note section holding `notes`
+    fn fixture_elf(is_64: bool, le: bool, notes: &[(&[u8], u32, &[u8])]) -> Vec<u8> {
+        let u16b = |v: u16| {
+            if le {
+                v.to_le_bytes().to_vec()
+            } else {
+                v.to_be_bytes().to_vec()
+            }
+        };
+        let u32b = |v: u32| {
+            if le {
+                v.to_le_bytes().to_vec()
+            } else {
+                v.to_be_bytes().to_vec()
+            }
+        };
+        let word = |v: u64| {
+            if is_64 && le {
+                v.to_le_bytes().to_vec()
+            } else if is_64 {
+                v.to_be_bytes().to_vec()
+            } else {
+                u32b(u32::try_from(v).unwrap())
+            }
+        };
+        let pad = |buf: &mut Vec<u8>| buf.resize(buf.len().next_multiple_of(4), 0);
+
+        let mut section = Vec::new();
+        for (name, kind, desc) in notes {
+            section.extend(u32b(u32::try_from(name.len()).unwrap()));
+            section.extend(u32b(u32::try_from(desc.len()).unwrap()));
+            section.extend(u32b(*kind));
+            section.extend(*name);
+            pad(&mut section);
+            section.extend(*desc);
+            pad(&mut section);
+        }
+
+        let (ehsize, shentsize): (u16, u16) = if is_64 { (64, 64) } else { (52, 40) };
+        let notes_off = u64::from(ehsize);
+        let shoff = notes_off + section.len() as u64;
+
+        let mut elf = vec![
+            0x7f,
+            b'E',
+            b'L',
+            b'F',
+            1 + u8::from(is_64),
+            2 - u8::from(le),
+            1,
+        ];
+        elf.resize(16, 0);
+        elf.extend(u16b(2)); // e_type
+        elf.extend(u16b(62)); // e_machine
+        elf.extend(u32b(1)); // e_version
+        elf.extend(word(0)); // e_entry
+        elf.extend(word(0)); // e_phoff
+        elf.extend(word(shoff)); // e_shoff
+        elf.extend(u32b(0)); // e_flags
+        elf.extend(u16b(ehsize));
+        elf.extend(u16b(0)); // e_phentsize
+        elf.extend(u16b(0)); // e_phnum
+        elf.extend(u16b(shentsize));
+        elf.extend(u16b(2)); // e_shnum
+        elf.extend(u16b(0)); // e_shstrndx
+        assert_eq!(elf.len(), usize::from(ehsize));
+
+        elf.extend(&section);
+        elf.extend(vec![0; usize::from(shentsize)]);
+        let mut note_header = Vec::new();
+        note_header.extend(u32b(0)); // sh_name
+        note_header.extend(u32b(SHT_NOTE));
+        note_header.extend(word(0)); // sh_flags
+        note_header.extend(word(0)); // sh_addr
+        note_header.extend(word(notes_off));
+        note_header.extend(word(section.len() as u64));
+        note_header.resize(usize::from(shentsize), 0);
+        elf.extend(note_header);
+        elf
+    }
+
+    #[test]
+    fn test_gnu_build_id() {
+        let id: &[u8] = &[0xde, 0xad, 0xbe, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89];
+        let notes: &[(&[u8], u32, &[u8])] = &[(b"GNU\0", 1, &[0; 16]), (b"GNU\0", 3, id)];
+        for (is_64, le) in [(true, true), (true, false), (false, true), (false, false)] {
+            assert_eq!(
+                BuildId::from_bytes(&fixture_elf(is_64, le, notes)),
+                BuildId::Gnu("deadbeef0123456789".to_string()),
+                "64-bit: {is_64}, little endian: {le}"
+            );
+        }
+        assert_eq!(
+            BuildId::Gnu("deadbeef0123456789".to_string()).short(),
+            "23456789"
+        );
+    }
+
+    #[test]
+    fn test_content_hash_fallback() {
+        let without_note = fixture_elf(true, true, &[(b"Go\0\0", 4, b"abcd")]);
+        let id = BuildId::from_bytes(&without_note);
+        assert!(matches!(id, BuildId::ContentHash(ref h) if h.len() == 16));
+        assert_eq!(id, BuildId::from_bytes(&without_note));
+        assert_ne!(id, BuildId::from_bytes(b"#!/bin/sh\n"));
+        // Truncated headers must not panic
+        assert!(matches!(
+            BuildId::from_bytes(&without_note[..70]),
+            BuildId::ContentHash(_)
+        ));
+    }
+
+    #[test]
+    fn test_build_id_record() {
+        let tmp = TempDir::new().unwrap();
+        let bin = tmp.path().join("target");
+        fs::write(&bin, fixture_elf(true, true, &[(b"GNU\0", 3, &[0xab; 20])])).unwrap();
+
+        let record = BuildIdRecord::new([bin.as_path(), tmp.path().join("missing").as_path()]);
+        assert_eq!(record.binaries.len(), 1);
+        assert!(BuildIdRecord::load(tmp.path()).is_none());
+        record.save(tmp.path()).unwrap();
+        let loaded = BuildIdRecord::load(tmp.path()).unwrap();
+        assert_eq!(loaded, record);
+        assert_eq!(loaded.get(&bin), Some("ab".repeat(20).as_str()));
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    collections::BTreeSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+use crate::tui::{
+    control::RUNTIME_DIR,
+    data_collection::{DataFetcher, InstanceSnapshot},
+    forensics::InstanceState,
+};
+use crate::utils::clock::Clock;
+
+/// File inside `<output>/.aflr` describing the campaign, written by `run`
+pub const CAMPAIGN_FILE: &str = "campaign.json";
+/// Directory levels below a search root that are scanned for campaigns
+pub const MAX_SCAN_DEPTH: usize = 3;
+
+/// Metadata `run` records so a campaign can be recognized and described later on
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignRecord {
+    pub session_name: String,
+    pub target: PathBuf,
+    pub tag: Option<String>,
+    /// Unix timestamp of the launch
+    pub started: u64,
+    /// Changes `run` applied on its own, e.g. a raised `AFL_MAP_SIZE` after failed starts
+    #[serde(default)]
+    pub remediations: Vec<String>,
+    /// The `afl-fuzz` commands the campaign was launched with, used by `gen --diff`
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// The commands as generated before they were hand-edited with `run --edit`, empty if
+    /// the campaign runs the generated commands
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generated_commands: Vec<String>,
+    /// Where the config of the launch came from, e.g. `stdin`, `None` without a config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_source: Option<String>,
+}
+
+impl CampaignRecord {
+    /// Record of a campaign launched at the current time of `clock`
+    pub fn new(session_name: &str, target: &Path, tag: Option<&str>, clock: Clock) -> Self {
+        Self {
+            session_name: session_name.to_string(),
+            target: target.to_path_buf(),
+            tag: tag.map(ToString::to_string),
+            started: clock.unix_secs(),
+            remediations: Vec::new(),
+            commands: Vec::new(),
+            generated_commands: Vec::new(),
+            config_source: None,
+        }
+    }
+
+    /// Records the generated `commands` of the campaign
+    #[must_use]
+    pub fn with_commands(mut self, commands: Vec<String>) -> Self {
+        self.commands = commands;
+        self
+    }
+
+    /// Records the commands as generated before `commands` were hand-edited
+    #[must_use]
+    pub fn with_generated_commands(mut self, generated_commands: Vec<String>) -> Self {
+        self.generated_commands = generated_commands;
+        self
+    }
+
+    /// Records where the config the campaign was launched with came from
+    #[must_use]
+    pub fn with_config_source(mut self, config_source: Option<String>) -> Self {
+        self.config_source = config_source;
+        self
+    }
+
+    /// Location of the record for the campaign in `output_dir`
+    pub fn path(output_dir: &Path) -> PathBuf {
+        output_dir.join(RUNTIME_DIR).join(CAMPAIGN_FILE)
+    }
+
+    /// Loads the record of the campaign in `output_dir`, if any
+    pub fn load(output_dir: &Path) -> Option<Self> {
+        fs::read_to_string(Self::path(output_dir))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Stores the record for the campaign in `output_dir`
+    ///
+    /// # Errors
+    /// * If the record cannot be written
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        let path = Self::path(output_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Overall state of a campaign derived from the states of its instances
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CampaignState {
+    /// At least one instance is still fuzzing
+    Running,
+    /// Every instance terminated on its own
+    Finished,
+    /// All instances are gone, at least one of them was killed or died
+    Stopped,
+}
+
+impl CampaignState {
+    fn from_instances(instances: &[InstanceSnapshot]) -> Self {
+        if instances.iter().any(|i| i.state == InstanceState::Running) {
+            Self::Running
+        } else if !instances.is_empty()
+            && instances.iter().all(|i| i.state == InstanceState::Finished)
+        {
+            Self::Finished
+        } else {
+            Self::Stopped
+        }
+    }
+}
+
+impl fmt::Display for CampaignState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Running => "running",
+            Self::Finished => "finished",
+            Self::Stopped => "stopped",
+        })
+    }
+}
+
+/// One line of `aflr list`
+#[derive(Debug, Clone, Serialize)]
+pub struct CampaignSummary {
+    /// The tag, session name or directory name, whichever is known first
+    pub id: String,
+    pub tag: Option<String>,
+    pub target: Option<String>,
+    pub state: CampaignState,
+    pub instances: usize,
+    pub instances_alive: usize,
+    pub crashes: u64,
+    /// Unix timestamp of the latest `fuzzer_stats` update, or the launch if there is none
+    pub last_activity: Option<u64>,
+    /// Bytes used by the output directory
+    pub disk_usage: u64,
+    pub path: PathBuf,
+}
+
+impl CampaignSummary {
+    /// Summarizes the campaign in `output_dir`, `system` has to be refreshed by the caller
+    pub fn collect(output_dir: &Path, system: &System) -> Self {
+        let record = CampaignRecord::load(output_dir);
+        let instances = DataFetcher::instance_snapshots(output_dir, system);
+        let dir_name = output_dir.file_name().map_or_else(
+            || output_dir.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let target = record
+            .as_ref()
+            .map(|r| r.target.clone())
+            .or_else(|| instances.iter().find_map(|i| i.target.clone()))
+            .and_then(|target| {
+                target
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            });
+
+        Self {
+            id: record
+                .as_ref()
+                .and_then(|r| r.tag.clone())
+                .or_else(|| record.as_ref().map(|r| r.session_name.clone()))
+                .unwrap_or(dir_name),
+            tag: record.as_ref().and_then(|r| r.tag.clone()),
+            target,
+            state: CampaignState::from_instances(&instances),
+            instances: instances.len(),
+            instances_alive: instances
+                .iter()
+                .filter(|i| i.state == InstanceState::Running)
+                .count(),
+            crashes: instances.iter().map(|i| i.saved_crashes).sum(),
+            last_activity: instances
+                .iter()
+                .filter_map(|i| i.last_update)
+                .max()
+                .or_else(|| record.as_ref().map(|r| r.started)),
+            disk_usage: disk_usage(output_dir),
+            path: output_dir.to_path_buf(),
+        }
+    }
+}
+
+/// Campaign directories in and below `roots`, up to `MAX_SCAN_DEPTH` levels deep
+///
+/// A directory is a campaign if `run` left a record in it or it holds instance directories,
+/// campaigns are not searched for nested ones. Roots reaching the same campaign yield it once.
+pub fn find_campaigns(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut found = BTreeSet::new();
+    for root in roots {
+        scan(root, 0, &mut found);
+    }
+    found.into_iter().collect()
+}
+
+fn scan(dir: &Path, depth: usize, found: &mut BTreeSet<PathBuf>) {
+    if is_campaign(dir) {
+        found.insert(dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()));
+        return;
+    }
+    if depth == MAX_SCAN_DEPTH {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type().is_ok_and(|t| t.is_dir()) {
+            scan(&entry.path(), depth + 1, found);
+        }
+    }
+}
+
+/// Whether `dir` holds a campaign, see `find_campaigns`
+pub fn is_campaign(dir: &Path) -> bool {
+    CampaignRecord::path(dir).is_file()
+        || fs::read_dir(dir).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.path().join("fuzzer_stats").is_file())
+        })
+}
+
+/// Bytes used by the files below `dir`, symlinks are not followed
+pub fn disk_usage(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            Some(if meta.is_dir() {
+                disk_usage(&entry.path())
+            } else {
+                meta.len()
+            })
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_instance(dir: &Path, stats: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("fuzzer_stats"), stats).unwrap();
+    }
+
+    #[test]
+    fn test_find_and_summarize_campaigns() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+
+        // Finished campaign without a record, described by its fuzzer_stats
+        let plain = root.join("plain");
+        write_instance(
+            &plain.join("main_plain"),
+            "fuzzer_pid : 0\nlast_update : 1700000000\nsaved_crashes : 2\n\
+             command_line : afl-fuzz -i in -o out -- /opt/bin/parser @@\n",
+        );
+
+        // Tagged multi-target campaign, the targets are found one level deeper
+        let multi = root.join("nested/multi");
+        for name in ["a", "b"] {
+            let target = multi.join(name);
+            write_instance(
+                &target.join(format!("main_{name}")),
+                "last_update : 1700000100\nsaved_crashes : 1\n",
+            );
+            CampaignRecord::new(
+                &format!("sess_{name}"),
+                Path::new("/opt/bin/lexer"),
+                Some("v2"),
+                Clock::System,
+            )
+            .save(&target)
+            .unwrap();
+        }
+
+        let campaigns = find_campaigns(&[root.to_path_buf(), plain.clone()]);
+        assert_eq!(campaigns.len(), 3);
+
+        let system = System::new();
+        let summaries: Vec<CampaignSummary> = campaigns
+            .iter()
+            .map(|dir| CampaignSummary::collect(dir, &system))
+            .collect();
+        let plain = summaries.iter().find(|s| s.id == "plain").unwrap();
+        assert_eq!(plain.target.as_deref(), Some("parser"));
+        assert_eq!(plain.crashes, 2);
+        assert_eq!(plain.instances, 1);
+        assert_eq!(plain.instances_alive, 0);
+        assert_ne!(plain.state, CampaignState::Running);
+        assert_eq!(plain.last_activity, Some(1_700_000_000));
+        assert!(plain.disk_usage > 0);
+
+        let tagged: Vec<_> = summaries.iter().filter(|s| s.id == "v2").collect();
+        assert_eq!(tagged.len(), 2);
+        assert!(tagged
+            .iter()
+            .all(|s| s.target.as_deref() == Some("lexer") && s.tag.as_deref() == Some("v2")));
+    }
+}
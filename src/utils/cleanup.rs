@@ -0,0 +1,351 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{bail, Context, Result};
+use sysinfo::System;
+
+use crate::utils::campaign::disk_usage;
+
+/// Directory the `RAMDisk`s of `create_ramdisk` are mounted below
+pub const RAMDISK_BASE: &str = "/tmp/tmpfs";
+/// File inside a `RAMDisk` naming the session it was created for
+const SESSION_FILE: &str = ".aflr_session";
+/// Prefix of the temporary files and directories aflr leaves in `/tmp`, e.g. `.aflr_cov_<uuid>`
+const TMP_PREFIX: &str = ".aflr_";
+/// Prefix of the PID files `run` writes per session into `/tmp`, see `session_pid_file`
+const PID_FILE_PREFIX: &str = ".aflr_pids_";
+
+/// Records that `ramdisk` belongs to `session_name`, so `kill` and `cleanup` can find it
+///
+/// # Errors
+/// * If the session file cannot be written
+pub fn tag_ramdisk(ramdisk: &Path, session_name: &str) -> Result<()> {
+    let path = ramdisk.join(SESSION_FILE);
+    fs::write(&path, session_name).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The session `ramdisk` was created for, `None` if it was never tagged
+pub fn ramdisk_session(ramdisk: &Path) -> Option<String> {
+    fs::read_to_string(ramdisk.join(SESSION_FILE))
+        .ok()
+        .map(|name| name.trim().to_string())
+}
+
+/// The `RAMDisk`s below `base` that were created for `session_name`
+pub fn session_ramdisks(base: &Path, session_name: &str) -> Vec<PathBuf> {
+    let mut ramdisks = ramdisks(base)
+        .into_iter()
+        .filter(|ramdisk| ramdisk_session(ramdisk).as_deref() == Some(session_name))
+        .collect::<Vec<_>>();
+    ramdisks.sort();
+    ramdisks
+}
+
+fn ramdisks(base: &Path) -> Vec<PathBuf> {
+    fs::read_dir(base)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether an `aflr mirror` process still copies results out of `ramdisk`
+///
+/// Its final pass runs after the fuzzers are gone, so the `RAMDisk` must outlive it.
+pub fn is_mirrored(ramdisk: &Path) -> bool {
+    let mut system = System::new_all();
+    system.refresh_all();
+    system.processes().values().any(|process| {
+        let cmd = process.cmd();
+        let is_mirror = cmd.iter().any(|arg| arg == "mirror");
+        is_mirror
+            && cmd
+                .windows(2)
+                .any(|pair| pair[0] == "--from" && Path::new(&pair[1]).starts_with(ramdisk))
+    })
+}
+
+/// Unmounts `ramdisk` if it is mounted and removes its directory
+///
+/// # Errors
+/// * If unmounting or removing the directory fails
+pub fn remove_ramdisk(ramdisk: &Path) -> Result<()> {
+    if is_mount_point(ramdisk) {
+        let status = Command::new("sudo")
+            .arg("umount")
+            .arg(ramdisk)
+            .status()
+            .context("Failed to execute umount")?;
+        if !status.success() {
+            bail!("Failed to unmount RAMDisk {}", ramdisk.display());
+        }
+    }
+    fs::remove_dir_all(ramdisk).with_context(|| format!("Failed to remove {}", ramdisk.display()))
+}
+
+fn is_mount_point(path: &Path) -> bool {
+    fs::read_to_string("/proc/mounts").is_ok_and(|mounts| {
+        mounts
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .any(|mount_point| Path::new(mount_point) == path)
+    })
+}
+
+/// Something aflr left behind that nothing uses anymore
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Leftover {
+    /// A `RAMDisk` whose session is gone, or that never got a session
+    Ramdisk {
+        path: PathBuf,
+        session: Option<String>,
+    },
+    /// A temporary file or directory like `.aflr_cov_<uuid>` or the PID file of an ended session
+    Temp(PathBuf),
+}
+
+impl Leftover {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Ramdisk { path, .. } | Self::Temp(path) => path,
+        }
+    }
+
+    /// Bytes the leftover occupies
+    pub fn size(&self) -> u64 {
+        let path = self.path();
+        if path.is_dir() {
+            disk_usage(path)
+        } else {
+            path.symlink_metadata().map_or(0, |meta| meta.len())
+        }
+    }
+
+    /// Removes the leftover, unmounting it first if it is a `RAMDisk`
+    ///
+    /// # Errors
+    /// * If it cannot be unmounted or removed
+    pub fn remove(&self) -> Result<()> {
+        match self {
+            Self::Ramdisk { path, .. } => remove_ramdisk(path),
+            Self::Temp(path) if path.is_dir() => fs::remove_dir_all(path)
+                .with_context(|| format!("Failed to remove {}", path.display())),
+            Self::Temp(path) => fs::remove_file(path)
+                .with_context(|| format!("Failed to remove {}", path.display())),
+        }
+    }
+}
+
+/// Finds the `RAMDisk`s and temporary files of earlier campaigns that were never cleaned up
+#[derive(Debug, Clone)]
+pub struct Cleanup {
+    ramdisk_base: PathBuf,
+    tmp_dir: PathBuf,
+    /// Untracked leftovers younger than this may still be in use
+    min_age: Duration,
+}
+
+impl Cleanup {
+    pub fn new(min_age: Duration) -> Self {
+        Self {
+            ramdisk_base: PathBuf::from(RAMDISK_BASE),
+            tmp_dir: PathBuf::from("/tmp"),
+            min_age,
+        }
+    }
+
+    /// Look for leftovers below `ramdisk_base` and in `tmp_dir` instead of the default locations
+    #[must_use]
+    pub fn with_dirs(mut self, ramdisk_base: &Path, tmp_dir: &Path) -> Self {
+        self.ramdisk_base = ramdisk_base.to_path_buf();
+        self.tmp_dir = tmp_dir.to_path_buf();
+        self
+    }
+
+    /// The leftovers that can be removed
+    ///
+    /// A tagged `RAMDisk` is stale once its session is gone according to `session_exists`,
+    /// untagged ones and temporary files once they are older than the minimum age. `RAMDisk`s
+    /// that are still mirrored are kept either way. The PID files `run` writes per session are
+    /// stale once their session is gone.
+    pub fn stale(&self, session_exists: impl Fn(&str) -> bool) -> Vec<Leftover> {
+        let mut leftovers = Vec::new();
+        for path in ramdisks(&self.ramdisk_base) {
+            let session = ramdisk_session(&path);
+            let is_stale = match &session {
+                Some(session) => !session_exists(session),
+                None => self.is_old(&path),
+            };
+            if is_stale && !is_mirrored(&path) {
+                leftovers.push(Leftover::Ramdisk { path, session });
+            }
+        }
+
+        if let Ok(entries) = fs::read_dir(&self.tmp_dir) {
+            leftovers.extend(
+                entries
+                    .flatten()
+                    .filter(|entry| {
+                        let name = entry.file_name();
+                        let name = name.to_string_lossy();
+                        // PID files live as long as their session, no matter how old they are
+                        if name.starts_with(PID_FILE_PREFIX) {
+                            pid_file_session(&name).is_some_and(|session| !session_exists(session))
+                        } else {
+                            name.starts_with(TMP_PREFIX) && self.is_old(&entry.path())
+                        }
+                    })
+                    .map(|entry| Leftover::Temp(entry.path())),
+            );
+        }
+        leftovers.sort_by(|a, b| a.path().cmp(b.path()));
+        leftovers
+    }
+
+    fn is_old(&self, path: &Path) -> bool {
+        path.symlink_metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age >= self.min_age)
+    }
+}
+
+/// PID file in `/tmp` the aflr process `aflr_pid` keeps the fuzzer PIDs of `session_name` in
+///
+/// Named `.aflr_pids_<session>_<aflr pid>.pids`, the prefix keeps `cleanup` away from PID
+/// files of other tools.
+pub fn session_pid_file(session_name: &str, aflr_pid: impl fmt::Display) -> PathBuf {
+    PathBuf::from(format!(
+        "/tmp/{PID_FILE_PREFIX}{session_name}_{aflr_pid}.pids"
+    ))
+}
+
+/// Session of a local PID file as written by `run`, see `session_pid_file`
+pub fn pid_file_session(file_name: &str) -> Option<&str> {
+    let (session, pid) = file_name
+        .strip_prefix(PID_FILE_PREFIX)?
+        .strip_suffix(".pids")?
+        .rsplit_once('_')?;
+    pid.parse::<u32>().ok().filter(|_| !session.is_empty())?;
+    Some(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_session_ramdisks() {
+        let base = TempDir::new().unwrap();
+        for (name, session) in [
+            ("a", Some("fuzz_png")),
+            ("b", Some("fuzz_xml")),
+            ("c", None),
+        ] {
+            let ramdisk = base.path().join(name);
+            fs::create_dir(&ramdisk).unwrap();
+            if let Some(session) = session {
+                tag_ramdisk(&ramdisk, session).unwrap();
+            }
+        }
+        assert_eq!(
+            session_ramdisks(base.path(), "fuzz_png"),
+            [base.path().join("a")]
+        );
+        assert_eq!(ramdisk_session(&base.path().join("c")), None);
+        assert!(session_ramdisks(&base.path().join("missing"), "fuzz_png").is_empty());
+        assert!(!is_mirrored(&base.path().join("a")));
+    }
+
+    #[test]
+    fn test_stale_leftovers() {
+        let base = TempDir::new().unwrap();
+        let tmp = TempDir::new().unwrap();
+        for (name, session) in [
+            ("alive", Some("running")),
+            ("gone", Some("killed")),
+            ("untagged", None),
+        ] {
+            let ramdisk = base.path().join(name);
+            fs::create_dir(&ramdisk).unwrap();
+            if let Some(session) = session {
+                tag_ramdisk(&ramdisk, session).unwrap();
+            }
+        }
+        fs::create_dir(tmp.path().join(".aflr_cov_1234")).unwrap();
+        fs::write(tmp.path().join(".aflr_exit_42"), "0").unwrap();
+        fs::write(tmp.path().join("unrelated"), "").unwrap();
+        for pid_file in [
+            ".aflr_pids_killed_1234.pids",
+            ".aflr_pids_running_1234.pids",
+            ".aflr_pids_killed_remote.pids",
+            // PID file of another tool that happens to match the session pattern
+            ".killed_1234.pids",
+        ] {
+            fs::write(tmp.path().join(pid_file), "4321").unwrap();
+        }
+
+        // Fresh untracked leftovers may belong to a campaign that is just starting
+        let cleanup = Cleanup::new(Duration::from_secs(3600)).with_dirs(base.path(), tmp.path());
+        let stale = cleanup.stale(|session| session == "running");
+        let mut expected = vec![
+            Leftover::Ramdisk {
+                path: base.path().join("gone"),
+                session: Some("killed".to_string()),
+            },
+            Leftover::Temp(tmp.path().join(".aflr_pids_killed_1234.pids")),
+        ];
+        expected.sort_by(|a, b| a.path().cmp(b.path()));
+        assert_eq!(stale, expected);
+        assert_eq!(
+            stale.iter().map(Leftover::size).sum::<u64>(),
+            4 + "killed".len() as u64
+        );
+
+        let cleanup = Cleanup::new(Duration::ZERO).with_dirs(base.path(), tmp.path());
+        let stale = cleanup.stale(|session| session == "running");
+        let paths: Vec<&Path> = stale.iter().map(Leftover::path).collect();
+        let mut expected = [
+            base.path().join("gone"),
+            base.path().join("untagged"),
+            tmp.path().join(".aflr_cov_1234"),
+            tmp.path().join(".aflr_exit_42"),
+            tmp.path().join(".aflr_pids_killed_1234.pids"),
+        ];
+        expected.sort();
+        assert_eq!(
+            paths,
+            expected.iter().map(PathBuf::as_path).collect::<Vec<_>>()
+        );
+
+        for leftover in &stale {
+            leftover.remove().unwrap();
+        }
+        assert!(base.path().join("alive").is_dir());
+        assert!(tmp.path().join("unrelated").is_file());
+        assert!(tmp.path().join(".aflr_pids_running_1234.pids").is_file());
+        assert!(tmp.path().join(".aflr_pids_killed_remote.pids").is_file());
+        assert!(tmp.path().join(".killed_1234.pids").is_file());
+        let paths: Vec<PathBuf> = cleanup
+            .stale(|_| false)
+            .iter()
+            .map(|l| l.path().to_path_buf())
+            .collect();
+        let mut expected = [
+            base.path().join("alive"),
+            tmp.path().join(".aflr_pids_running_1234.pids"),
+        ];
+        expected.sort();
+        assert_eq!(paths, expected);
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    env,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{DateTime, Utc};
+
+/// Pins the time embedded in reports and archives, see <https://reproducible-builds.org/specs/source-date-epoch/>
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// Source of the timestamps written into records, reports and archives
+///
+/// Durations measured at runtime, e.g. how long a campaign ran, always use the real time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Clock {
+    /// The current system time
+    #[default]
+    System,
+    /// A fixed point in time for reproducible output
+    Fixed(SystemTime),
+}
+
+impl Clock {
+    /// A clock fixed to `SOURCE_DATE_EPOCH` if it holds a Unix timestamp, the system clock otherwise
+    pub fn from_env() -> Self {
+        env::var(SOURCE_DATE_EPOCH)
+            .ok()
+            .and_then(|epoch| epoch.trim().parse().ok())
+            .map_or(Self::System, |secs| {
+                Self::Fixed(UNIX_EPOCH + Duration::from_secs(secs))
+            })
+    }
+
+    pub fn now(self) -> SystemTime {
+        match self {
+            Self::System => SystemTime::now(),
+            Self::Fixed(time) => time,
+        }
+    }
+
+    /// Seconds since the Unix epoch
+    pub fn unix_secs(self) -> u64 {
+        self.now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+
+    /// The fixed time, `None` for the system clock
+    pub fn fixed(self) -> Option<SystemTime> {
+        match self {
+            Self::System => None,
+            Self::Fixed(time) => Some(time),
+        }
+    }
+
+    /// Formats the current time in UTC, so fixed timestamps render the same in every timezone
+    pub fn format_utc(self, fmt: &str) -> String {
+        DateTime::<Utc>::from(self.now()).format(fmt).to_string()
+    }
+}
+
+/// Parses an RFC 3339 timestamp like `2024-05-01T12:00:00Z` into seconds since the Unix epoch
+///
+/// # Errors
+/// * If `s` is no valid RFC 3339 timestamp or lies before the epoch
+pub fn parse_rfc3339(s: &str) -> Result<u64, String> {
+    let time =
+        DateTime::parse_from_rfc3339(s).map_err(|e| format!("invalid timestamp '{s}': {e}"))?;
+    u64::try_from(time.timestamp()).map_err(|_| format!("timestamp '{s}' lies before 1970"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let clock = Clock::Fixed(UNIX_EPOCH + Duration::from_secs(1_714_564_800));
+        assert_eq!(clock.unix_secs(), 1_714_564_800);
+        assert_eq!(clock.format_utc("%Y-%m-%d %H:%M"), "2024-05-01 12:00");
+        assert!(Clock::System.fixed().is_none());
+
+        assert_eq!(
+            parse_rfc3339("2024-05-01T14:00:00+02:00"),
+            Ok(1_714_564_800)
+        );
+        assert!(parse_rfc3339("1969-12-31T23:59:59Z").is_err());
+        assert!(parse_rfc3339("yesterday").is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Units accepted in duration options, largest first
+const UNITS: &[Unit] = &[
+    Unit::Days,
+    Unit::Hours,
+    Unit::Mins,
+    Unit::Secs,
+    Unit::Millis,
+];
+
+/// Unit of a duration component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Days,
+    Hours,
+    Mins,
+    Secs,
+    Millis,
+}
+
+impl Unit {
+    fn suffix(self) -> &'static str {
+        match self {
+            Self::Days => "d",
+            Self::Hours => "h",
+            Self::Mins => "m",
+            Self::Secs => "s",
+            Self::Millis => "ms",
+        }
+    }
+
+    fn millis(self) -> u64 {
+        match self {
+            Self::Days => 86_400_000,
+            Self::Hours => 3_600_000,
+            Self::Mins => 60_000,
+            Self::Secs => 1_000,
+            Self::Millis => 1,
+        }
+    }
+}
+
+/// Parses durations like `"90s"`, `"15m"` or `"2h30m"`
+///
+/// Bare integers are interpreted in `bare_unit`. Negative values, fractions, unknown units and
+/// components that are repeated, out of order or lack a unit after another component are
+/// rejected as ambiguous.
+///
+/// # Errors
+/// * If `s` is not a valid duration
+pub fn parse_duration(s: &str, bare_unit: Unit) -> Result<Duration, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("empty duration".to_string());
+    }
+    if s.starts_with('-') {
+        return Err(format!("negative duration '{s}'"));
+    }
+    if let Ok(value) = s.parse::<u64>() {
+        return to_duration(value, bare_unit, s);
+    }
+
+    let mut total = Duration::ZERO;
+    let mut last_unit: Option<usize> = None;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return Err(format!("invalid duration '{s}': expected a number"));
+        }
+        let value: u64 = rest[..digits]
+            .parse()
+            .map_err(|_| format!("invalid duration '{s}': number too large"))?;
+        rest = &rest[digits..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let suffix = &rest[..unit_len];
+        rest = &rest[unit_len..];
+        if suffix.is_empty() {
+            return Err(format!(
+                "ambiguous duration '{s}': the trailing number needs a unit"
+            ));
+        }
+        if suffix.starts_with('.') || suffix.starts_with(',') {
+            return Err(format!(
+                "invalid duration '{s}': fractions are not supported, combine units like '1h30m'"
+            ));
+        }
+        let Some(idx) = UNITS.iter().position(|u| u.suffix() == suffix) else {
+            return Err(format!(
+                "invalid duration '{s}': unknown unit '{suffix}', expected one of d, h, m, s, ms"
+            ));
+        };
+        if last_unit.is_some_and(|last| idx <= last) {
+            return Err(format!(
+                "ambiguous duration '{s}': units must be given once and from largest to smallest"
+            ));
+        }
+        last_unit = Some(idx);
+        total = total
+            .checked_add(to_duration(value, UNITS[idx], s)?)
+            .ok_or_else(|| format!("invalid duration '{s}': too large"))?;
+    }
+    Ok(total)
+}
+
+fn to_duration(value: u64, unit: Unit, s: &str) -> Result<Duration, String> {
+    value
+        .checked_mul(unit.millis())
+        .map(Duration::from_millis)
+        .ok_or_else(|| format!("invalid duration '{s}': too large"))
+}
+
+/// A time-valued option given as `"90s"`, `"15m"`, `"2h30m"` or a bare number of seconds
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "RawDuration")]
+pub struct DurationOpt(Duration);
+
+/// Helper to accept both strings and bare numbers in the TOML config
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDuration {
+    Number(i64),
+    Text(String),
+}
+
+impl DurationOpt {
+    pub const fn from_secs(secs: u64) -> Self {
+        Self(Duration::from_secs(secs))
+    }
+
+    pub const fn as_duration(self) -> Duration {
+        self.0
+    }
+}
+
+impl From<Duration> for DurationOpt {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl TryFrom<RawDuration> for DurationOpt {
+    type Error = String;
+
+    fn try_from(raw: RawDuration) -> Result<Self, Self::Error> {
+        match raw {
+            RawDuration::Number(secs) => u64::try_from(secs)
+                .map(Self::from_secs)
+                .map_err(|_| format!("negative duration '{secs}'")),
+            RawDuration::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl FromStr for DurationOpt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s, Unit::Secs).map(Self)
+    }
+}
+
+impl fmt::Display for DurationOpt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut millis = self.0.as_millis();
+        if millis == 0 {
+            return write!(f, "0s");
+        }
+        for unit in UNITS {
+            let unit_millis = u128::from(unit.millis());
+            if millis >= unit_millis {
+                write!(f, "{}{}", millis / unit_millis, unit.suffix())?;
+                millis %= unit_millis;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for DurationOpt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        let secs = |s| parse_duration(s, Unit::Secs).map(|d| d.as_millis());
+        assert_eq!(secs("90s"), Ok(90_000));
+        assert_eq!(secs("15m"), Ok(900_000));
+        assert_eq!(secs("2h30m"), Ok(9_000_000));
+        assert_eq!(secs("1d1ms"), Ok(86_400_001));
+        assert_eq!(secs(" 30 "), Ok(30_000));
+        assert_eq!(
+            parse_duration("30", Unit::Mins),
+            Ok(Duration::from_secs(1800))
+        );
+
+        for invalid in [
+            "", "-5", "-5s", "1.5h", "2h30", "30s2h", "5m5m", "15M", "h", "10y",
+        ] {
+            assert!(secs(invalid).is_err(), "{invalid} should be rejected");
+        }
+        assert!(secs("2h30").unwrap_err().contains("ambiguous"));
+        assert!(secs("99999999999999999d").is_err());
+    }
+
+    #[test]
+    fn test_duration_opt_display_round_trip() {
+        for (input, display) in [
+            ("90s", "1m30s"),
+            ("2h30m", "2h30m"),
+            ("0", "0s"),
+            ("1500ms", "1s500ms"),
+        ] {
+            let parsed: DurationOpt = input.parse().unwrap();
+            assert_eq!(parsed.to_string(), display);
+            assert_eq!(display.parse::<DurationOpt>(), Ok(parsed));
+        }
+    }
+
+    #[test]
+    fn test_duration_opt_serde() {
+        #[derive(Deserialize, Serialize, Debug, PartialEq)]
+        struct Cfg {
+            interval: DurationOpt,
+        }
+
+        let cfg: Cfg = toml::from_str("interval = \"2h30m\"").unwrap();
+        assert_eq!(cfg.interval, DurationOpt::from_secs(9000));
+        let cfg: Cfg = toml::from_str("interval = 45").unwrap();
+        assert_eq!(cfg.interval, DurationOpt::from_secs(45));
+        assert_eq!(toml::to_string(&cfg).unwrap(), "interval = \"45s\"\n");
+        assert_eq!(
+            toml::from_str::<Cfg>(&toml::to_string(&cfg).unwrap()).unwrap(),
+            cfg
+        );
+
+        for invalid in ["interval = -5", "interval = \"1.5h\""] {
+            let err = toml::from_str::<Cfg>(invalid).unwrap_err().to_string();
+            assert!(err.contains("interval"), "{err}");
+        }
+    }
+}
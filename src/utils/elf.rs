@@ -0,0 +1,69 @@
+/// Checks the ELF identification of `elf`, returns whether it is a 64-bit file and a reader in
+/// its byte order
+pub(crate) fn parse_ident(elf: &[u8]) -> Option<(bool, Reader<'_>)> {
+    if elf.get(..4)? != b"\x7fELF" {
+        return None;
+    }
+    let is_64 = match elf.get(4)? {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let le = match elf.get(5)? {
+        1 => true,
+        2 => false,
+        _ => return None,
+    };
+    Some((is_64, Reader { data: elf, le }))
+}
+
+/// Bounds checked integer reads in the byte order of the ELF file
+pub(crate) struct Reader<'a> {
+    pub data: &'a [u8],
+    pub le: bool,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        self.data
+            .get(offset..offset.checked_add(N)?)?
+            .try_into()
+            .ok()
+    }
+
+    pub fn u16(&self, offset: usize) -> Option<u16> {
+        let b = self.bytes(offset)?;
+        Some(if self.le {
+            u16::from_le_bytes(b)
+        } else {
+            u16::from_be_bytes(b)
+        })
+    }
+
+    pub fn u32(&self, offset: usize) -> Option<u32> {
+        let b = self.bytes(offset)?;
+        Some(if self.le {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        })
+    }
+
+    pub fn u64(&self, offset: usize) -> Option<u64> {
+        let b = self.bytes(offset)?;
+        Some(if self.le {
+            u64::from_le_bytes(b)
+        } else {
+            u64::from_be_bytes(b)
+        })
+    }
+
+    /// An address or offset sized word, 8 bytes in 64-bit files and 4 bytes otherwise
+    pub fn word(&self, is_64: bool, offset: usize) -> Option<u64> {
+        if is_64 {
+            self.u64(offset)
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+}
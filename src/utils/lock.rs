@@ -0,0 +1,252 @@
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{Read, Seek, Write},
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::tui::{control::RUNTIME_DIR, Tui};
+
+/// File inside `<output>/.aflr` that is locked while a campaign is modified
+pub const LOCK_FILE: &str = "lock";
+
+/// The aflr invocation holding the lock of a campaign, as recorded in the lock file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// Command that modifies the campaign, e.g. `run`
+    pub operation: String,
+    /// Unix timestamp the lock was taken at
+    pub since: u64,
+}
+
+impl LockHolder {
+    /// Whether the holder is still running, a crashed one leaves a stale lock file behind
+    pub fn is_alive(&self) -> bool {
+        Path::new(&format!("/proc/{}", self.pid)).exists()
+    }
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PID {} (aflr {})", self.pid, self.operation)
+    }
+}
+
+/// Advisory lock that keeps two aflr invocations from modifying the same campaign at once
+///
+/// Commands that start, stop or feed instances (`run`, `kill`, `restart`, `add-seed`) hold it
+/// while they change the campaign. Read-only commands like `tui`, `top` or `list` never take it.
+/// The lock is an `flock` on `<output>/.aflr/lock`, which records the holder for the error
+/// message of a second invocation. It is released when dropped or when the holder exits.
+#[derive(Debug)]
+pub struct CampaignLock {
+    file: File,
+}
+
+impl CampaignLock {
+    /// Location of the lock file of the campaign in `output_dir`
+    pub fn path(output_dir: &Path) -> PathBuf {
+        output_dir.join(RUNTIME_DIR).join(LOCK_FILE)
+    }
+
+    /// The invocation currently holding the lock of the campaign in `output_dir`, if any
+    pub fn holder(output_dir: &Path) -> Option<LockHolder> {
+        File::open(Self::path(output_dir))
+            .ok()
+            .and_then(|mut file| read_holder(&mut file))
+    }
+
+    /// Locks the campaign in `output_dir` for `operation`, failing fast if another invocation
+    /// holds the lock
+    ///
+    /// A lock left behind by a crashed invocation is broken, which is logged.
+    ///
+    /// # Errors
+    /// * If another running aflr invocation holds the lock
+    /// * If the lock file cannot be created
+    pub fn acquire(output_dir: &Path, operation: &str) -> Result<Self> {
+        let (lock, stale) = Self::try_acquire(output_dir, operation)?;
+        if let Some(stale) = stale {
+            println!(
+                "[!] Warning: Broke the stale lock of {} in {}, the process is gone",
+                stale,
+                output_dir.display()
+            );
+        }
+        Ok(lock)
+    }
+
+    /// Locks the campaign, returning the holder of a stale lock that was broken on the way
+    fn try_acquire(output_dir: &Path, operation: &str) -> Result<(Self, Option<LockHolder>)> {
+        let path = Self::path(output_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        let mut stale = None;
+        loop {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            // A clean release empties the file, so a recorded holder has crashed
+            let previous = read_holder(&mut file);
+
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => match previous {
+                    // The lock is held by a process that inherited it, replacing the file
+                    // detaches it from that lock
+                    Some(holder) if !holder.is_alive() && stale.is_none() => {
+                        fs::remove_file(&path)
+                            .with_context(|| format!("Failed to remove {}", path.display()))?;
+                        stale = Some(holder);
+                        continue;
+                    }
+                    Some(holder) => bail!(
+                        "The campaign in {} is locked by {holder} for {}. Wait for it to finish or stop it first",
+                        output_dir.display(),
+                        Tui::format_duration(&Duration::from_secs(
+                            unix_now().saturating_sub(holder.since)
+                        ))
+                    ),
+                    None => bail!(
+                        "The campaign in {} is locked by another aflr invocation",
+                        output_dir.display()
+                    ),
+                },
+                // Filesystems without flock support only have the recorded holder to go by
+                Err(TryLockError::Error(_)) => {
+                    if let Some(holder) = previous
+                        .as_ref()
+                        .filter(|h| h.is_alive() && h.pid != process::id())
+                    {
+                        bail!(
+                            "The campaign in {} is locked by {holder}. Wait for it to finish or stop it first",
+                            output_dir.display()
+                        );
+                    }
+                }
+            }
+
+            let holder = LockHolder {
+                pid: process::id(),
+                operation: operation.to_string(),
+                since: unix_now(),
+            };
+            file.set_len(0)?;
+            file.rewind()?;
+            file.write_all(serde_json::to_string(&holder)?.as_bytes())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            return Ok((Self { file }, stale.or(previous)));
+        }
+    }
+}
+
+/// Current Unix timestamp, lock ages are real even when `--timestamp` fixes the output clock
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl Drop for CampaignLock {
+    fn drop(&mut self) {
+        // Emptied first so the next holder does not mistake this one for a crashed one
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+    }
+}
+
+fn read_holder(file: &mut File) -> Option<LockHolder> {
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn dead_pid() -> u32 {
+        let mut child = Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    #[test]
+    fn test_lock_contention() {
+        let dir = TempDir::new().unwrap();
+        let lock = CampaignLock::acquire(dir.path(), "run").unwrap();
+        let holder = CampaignLock::holder(dir.path()).unwrap();
+        assert_eq!(holder.pid, process::id());
+        assert_eq!(holder.operation, "run");
+
+        let err = CampaignLock::acquire(dir.path(), "kill")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(&format!(
+                "is locked by PID {} (aflr run) for",
+                process::id()
+            )),
+            "{err}"
+        );
+
+        drop(lock);
+        assert_eq!(CampaignLock::holder(dir.path()), None);
+        let (_lock, stale) = CampaignLock::try_acquire(dir.path(), "kill").unwrap();
+        assert_eq!(stale, None);
+        assert_eq!(CampaignLock::holder(dir.path()).unwrap().operation, "kill");
+    }
+
+    #[test]
+    fn test_stale_lock() {
+        let dir = TempDir::new().unwrap();
+        let crashed = LockHolder {
+            pid: dead_pid(),
+            operation: "run".to_string(),
+            since: 0,
+        };
+        let path = CampaignLock::path(dir.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        // Left behind without an flock, e.g. by a killed invocation
+        fs::write(&path, serde_json::to_string(&crashed).unwrap()).unwrap();
+        let (lock, stale) = CampaignLock::try_acquire(dir.path(), "run").unwrap();
+        assert_eq!(stale.as_ref(), Some(&crashed));
+        drop(lock);
+
+        // Still flocked by a process that inherited the lock from its dead holder
+        fs::write(&path, serde_json::to_string(&crashed).unwrap()).unwrap();
+        let inherited = File::open(&path).unwrap();
+        inherited.try_lock().unwrap();
+        let (_lock, stale) = CampaignLock::try_acquire(dir.path(), "add-seed").unwrap();
+        assert_eq!(stale, Some(crashed));
+        assert_eq!(CampaignLock::holder(dir.path()).unwrap().pid, process::id());
+    }
+
+    #[test]
+    fn test_read_only_bypass() {
+        let dir = TempDir::new().unwrap();
+        let _lock = CampaignLock::acquire(dir.path(), "run").unwrap();
+        // Read-only commands neither take the lock nor trip over the lock file
+        assert!(crate::tui::data_collection::DataFetcher::alive_pids(dir.path()).is_empty());
+        assert!(crate::utils::campaign::CampaignRecord::load(dir.path()).is_none());
+        assert!(CampaignLock::holder(dir.path()).is_some());
+    }
+}
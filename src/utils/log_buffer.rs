@@ -65,6 +65,11 @@ impl<T> LogRingBuffer<T> {
         result
     }
 
+    /// Iterates the entries from oldest to newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        (0..self.count).map(|i| &self.buffer[(self.tail + i) % self.capacity])
+    }
+
     pub fn push(&mut self, item: T) {
         self.append(item);
     }
@@ -0,0 +1,277 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+
+use crate::tui::control::{RUNTIME_DIR, SOCKET_NAME};
+use crate::utils::build_id::{short_id, BuildId, BuildIdRecord};
+use crate::utils::clock::Clock;
+use crate::utils::snapshot::SNAPSHOT_DIR;
+
+/// File in `RUNTIME_DIR` describing where a bundle was exported from
+pub const MANIFEST_FILE: &str = "migration.json";
+/// File in `RUNTIME_DIR` holding the config the campaign was exported with
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Describes the origin of a migration bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Absolute output directory on the source machine
+    pub source_output_dir: PathBuf,
+    /// Unix timestamp of the export
+    pub exported: u64,
+    /// Whether the bundle holds the config the campaign was exported with
+    pub has_config: bool,
+}
+
+impl Manifest {
+    /// Location of the manifest in an unpacked bundle
+    pub fn path(output_dir: &Path) -> PathBuf {
+        output_dir.join(RUNTIME_DIR).join(MANIFEST_FILE)
+    }
+
+    /// Loads the manifest of the bundle unpacked into `output_dir`
+    ///
+    /// # Errors
+    /// * If there is no manifest, i.e. the bundle was not written by `migrate export`
+    pub fn load(output_dir: &Path) -> Result<Self> {
+        let path = Self::path(output_dir);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("No migration manifest at {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
+/// Location of the exported config in an unpacked bundle
+pub fn config_path(output_dir: &Path) -> PathBuf {
+    output_dir.join(RUNTIME_DIR).join(CONFIG_FILE)
+}
+
+/// Packs the campaign in `output_dir` along with `config` into `bundle`
+///
+/// The compression follows the suffix of `bundle`, e.g. zstd for `.tar.zst`. Snapshots, the
+/// control socket and `.cur_input` files are left out.
+///
+/// # Errors
+/// * If `output_dir` does not exist
+/// * If `tar` fails, e.g. because the compressor for the suffix is not installed
+pub fn export(
+    output_dir: &Path,
+    bundle: &Path,
+    config: Option<&toml::Table>,
+    clock: Clock,
+) -> Result<Manifest> {
+    let output_dir = output_dir
+        .canonicalize()
+        .with_context(|| format!("Output directory {} not found", output_dir.display()))?;
+    let manifest = Manifest {
+        source_output_dir: output_dir.clone(),
+        exported: clock.unix_secs(),
+        has_config: config.is_some(),
+    };
+
+    // The bundle's own files are staged aside so the campaign stays untouched
+    let staging = TempDir::new()?;
+    let runtime_dir = staging.path().join(RUNTIME_DIR);
+    fs::create_dir(&runtime_dir)?;
+    let mut staged = vec![format!("{RUNTIME_DIR}/{MANIFEST_FILE}")];
+    fs::write(
+        runtime_dir.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    if let Some(config) = config {
+        fs::write(runtime_dir.join(CONFIG_FILE), toml::to_string(config)?)?;
+        staged.push(format!("{RUNTIME_DIR}/{CONFIG_FILE}"));
+    }
+
+    let status = Command::new("tar")
+        .arg("-a")
+        .arg("-cf")
+        .arg(bundle)
+        .arg(format!("--exclude={RUNTIME_DIR}/{SNAPSHOT_DIR}"))
+        .arg(format!("--exclude={RUNTIME_DIR}/{SOCKET_NAME}"))
+        .arg("--exclude=.cur_input")
+        .arg("-C")
+        .arg(&output_dir)
+        .arg(".")
+        .arg("-C")
+        .arg(staging.path())
+        .args(&staged)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to spawn tar")?;
+    if !status.success() {
+        let _ = fs::remove_file(bundle);
+        bail!(
+            "tar failed to pack {} into {}",
+            output_dir.display(),
+            bundle.display()
+        );
+    }
+    Ok(manifest)
+}
+
+/// Unpacks `bundle` into the new, empty `output_dir` and returns its manifest
+///
+/// # Errors
+/// * If `output_dir` is not empty
+/// * If `tar` fails or the bundle lacks a manifest
+pub fn unpack(bundle: &Path, output_dir: &Path) -> Result<Manifest> {
+    if !bundle.is_file() {
+        bail!("Bundle {} not found", bundle.display());
+    }
+    if output_dir.exists() && fs::read_dir(output_dir)?.next().is_some() {
+        bail!(
+            "{} is not empty, pick a new directory for the imported campaign",
+            output_dir.display()
+        );
+    }
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(bundle)
+        .arg("-C")
+        .arg(output_dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to spawn tar")?;
+    if !status.success() {
+        bail!("tar failed to extract {}", bundle.display());
+    }
+    Manifest::load(output_dir)
+}
+
+/// Absolute paths in a command line or config value, including those assigned to variables
+/// or flags like `AFL_TOKEN_FILE=/src/dict`
+pub fn absolute_paths(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == '\'' || c == '"')
+        .filter_map(|word| {
+            let path = word.rsplit_once('=').map_or(word, |(_, value)| value);
+            path.starts_with('/').then(|| path.to_string())
+        })
+        .collect()
+}
+
+/// Absolute string values of a TOML config, see `absolute_paths`
+pub fn toml_paths(table: &toml::Table) -> Vec<String> {
+    fn collect(value: &toml::Value, paths: &mut Vec<String>) {
+        match value {
+            toml::Value::String(s) => paths.extend(absolute_paths(s)),
+            toml::Value::Array(values) => values.iter().for_each(|v| collect(v, paths)),
+            toml::Value::Table(table) => table.values().for_each(|v| collect(v, paths)),
+            _ => {}
+        }
+    }
+    let mut paths = Vec::new();
+    table.values().for_each(|value| collect(value, &mut paths));
+    paths
+}
+
+/// Compares the binaries at the recorded paths to the builds the source campaign ran
+///
+/// Returns a warning for every binary that is missing or differs.
+pub fn verify_builds(record: &BuildIdRecord) -> Vec<String> {
+    record
+        .binaries
+        .iter()
+        .filter_map(|(binary, recorded)| match BuildId::read(binary) {
+            Ok(id) if id.as_str() == recorded => None,
+            Ok(id) => Some(format!(
+                "{} is build ..{}, the source campaign ran ..{}",
+                binary.display(),
+                id.short(),
+                short_id(recorded)
+            )),
+            Err(_) => Some(format!(
+                "{} does not exist on this machine, the source campaign ran build ..{}",
+                binary.display(),
+                short_id(recorded)
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_paths() {
+        let table: toml::Table = toml::from_str(
+            "[target]\npath = \"/src/t\"\nargs = [\"-f\", \"/src/in.cfg\"]\n[afl_cfg]\nrunners = 4\n",
+        )
+        .unwrap();
+        assert_eq!(toml_paths(&table), ["/src/in.cfg", "/src/t"]);
+    }
+
+    #[test]
+    fn test_absolute_paths() {
+        assert_eq!(
+            absolute_paths("AFL_TOKEN_FILE=/a/dict afl-fuzz -i in -o '/b/out' -- /c/t @@"),
+            ["/a/dict", "/b/out", "/c/t"]
+        );
+    }
+
+    #[test]
+    fn test_export_and_unpack() {
+        let source = TempDir::new().unwrap();
+        let instance = source.path().join("m_target");
+        fs::create_dir_all(instance.join("queue")).unwrap();
+        fs::write(instance.join("queue/id:000000,orig:seed"), "seed").unwrap();
+        fs::write(instance.join(".cur_input"), "current").unwrap();
+        fs::create_dir_all(source.path().join(RUNTIME_DIR).join(SNAPSHOT_DIR)).unwrap();
+
+        let bundle_dir = TempDir::new().unwrap();
+        let bundle = bundle_dir.path().join("campaign.tar.gz");
+        let config: toml::Table = toml::from_str("[target]\npath = \"/src/t\"\n").unwrap();
+        let manifest = export(
+            source.path(),
+            &bundle,
+            Some(&config),
+            Clock::Fixed(std::time::UNIX_EPOCH + std::time::Duration::from_secs(42)),
+        )
+        .unwrap();
+        assert_eq!(manifest.exported, 42);
+        assert!(manifest.has_config);
+
+        let dest = bundle_dir.path().join("imported");
+        assert_eq!(unpack(&bundle, &dest).unwrap(), manifest);
+        assert!(dest.join("m_target/queue/id:000000,orig:seed").is_file());
+        assert!(!dest.join("m_target/.cur_input").exists());
+        assert!(!dest.join(RUNTIME_DIR).join(SNAPSHOT_DIR).exists());
+        let exported: toml::Table =
+            toml::from_str(&fs::read_to_string(config_path(&dest)).unwrap()).unwrap();
+        assert_eq!(exported, config);
+
+        // Never unpack over an existing campaign
+        assert!(unpack(&bundle, &dest).is_err());
+    }
+
+    #[test]
+    fn test_verify_builds() {
+        let tmp = TempDir::new().unwrap();
+        let (same, rebuilt) = (tmp.path().join("same"), tmp.path().join("rebuilt"));
+        fs::write(&same, "build").unwrap();
+        fs::write(&rebuilt, "old build").unwrap();
+        let mut record = BuildIdRecord::new([same.as_path(), rebuilt.as_path()]);
+        record
+            .binaries
+            .insert(tmp.path().join("missing"), "00".repeat(20));
+        fs::write(&rebuilt, "new build").unwrap();
+
+        let warnings = verify_builds(&record);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("missing does not exist"));
+        assert!(warnings[1].contains("rebuilt is build"));
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+
+use crate::tui::data_collection::DataFetcher;
+
+/// Suffix of partially copied files in the mirror destination
+const TMP_SUFFIX: &str = ".aflr_tmp";
+/// Files that are only relevant for the running instance and change on every execution
+const SKIPPED_FILES: &[&str] = &[".cur_input", ".synced"];
+/// Time to wait for the fuzzers to show up before giving up
+const STARTUP_GRACE: Duration = Duration::from_secs(120);
+
+/// Mirrors a local AFL++ output directory to (usually network backed) shared storage
+///
+/// Files are first copied to a temporary name and then renamed, so the destination
+/// never contains partially written files.
+#[derive(Debug, Clone)]
+pub struct Mirror {
+    /// Local output directory the fuzzers write to
+    src: PathBuf,
+    /// Destination the results are mirrored to
+    dst: PathBuf,
+    /// Time between two mirror passes
+    interval: Duration,
+}
+
+impl Mirror {
+    pub fn new(src: PathBuf, dst: PathBuf, interval: Duration) -> Self {
+        Self { src, dst, interval }
+    }
+
+    /// Mirrors until the fuzzers writing into the source directory are gone
+    ///
+    /// A final pass is made after the last fuzzer terminated, e.g. via `aflr kill`.
+    ///
+    /// # Errors
+    /// * If the destination directory cannot be created
+    pub fn run(&self) -> Result<()> {
+        fs::create_dir_all(&self.dst)
+            .with_context(|| format!("Failed to create {}", self.dst.display()))?;
+        Self::remove_stale_tmp_files(&self.dst);
+
+        let started = Instant::now();
+        let mut seen_fuzzers = false;
+        loop {
+            if let Err(e) = self.sync_once() {
+                eprintln!("[!] Mirror pass failed: {e}");
+            }
+
+            let alive = !DataFetcher::alive_pids(&self.src).is_empty();
+            if alive {
+                seen_fuzzers = true;
+            } else if seen_fuzzers || started.elapsed() > STARTUP_GRACE {
+                return self.sync_once().map(|_| ());
+            }
+            thread::sleep(self.interval);
+        }
+    }
+
+    /// Copies all new or changed files from the source to the destination
+    ///
+    /// # Errors
+    /// * If the source directory cannot be read
+    /// * If a file cannot be copied
+    pub fn sync_once(&self) -> Result<usize> {
+        Self::sync_dir(&self.src, &self.dst)
+    }
+
+    fn sync_dir(src: &Path, dst: &Path) -> Result<usize> {
+        fs::create_dir_all(dst)?;
+        let mut copied = 0;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            if SKIPPED_FILES.contains(&name_str.as_ref()) || name_str.ends_with(TMP_SUFFIX) {
+                continue;
+            }
+
+            let src_path = entry.path();
+            let dst_path = dst.join(&name);
+            // AFL++ unlinks and recreates queue entries while trimming, vanished ones are skipped
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if file_type.is_dir() {
+                copied += Self::sync_dir(&src_path, &dst_path)?;
+            } else if file_type.is_file() && Self::is_outdated(&src_path, &dst_path) {
+                let tmp_path = dst.join(format!(".{name_str}{TMP_SUFFIX}"));
+                if Self::copy_file(&src_path, &tmp_path, &dst_path)? {
+                    copied += 1;
+                }
+            }
+        }
+        Ok(copied)
+    }
+
+    /// Copies `src` to `dst` through `tmp`, `false` if `src` vanished in the meantime
+    fn copy_file(src: &Path, tmp: &Path, dst: &Path) -> io::Result<bool> {
+        match fs::copy(src, tmp).and_then(|_| fs::rename(tmp, dst)) {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                let _ = fs::remove_file(tmp);
+                if e.kind() == io::ErrorKind::NotFound {
+                    Ok(false)
+                } else {
+                    Err(e)
+                }
+            }
+        }
+    }
+
+    /// Checks whether the mirrored copy is missing or differs from the source
+    fn is_outdated(src: &Path, dst: &Path) -> bool {
+        let (Ok(src_meta), Ok(dst_meta)) = (fs::metadata(src), fs::metadata(dst)) else {
+            return true;
+        };
+        if src_meta.len() != dst_meta.len() {
+            return true;
+        }
+        match (src_meta.modified(), dst_meta.modified()) {
+            (Ok(src_time), Ok(dst_time)) => src_time > dst_time,
+            _ => true,
+        }
+    }
+
+    /// Removes leftovers of copies that were interrupted in a previous run
+    fn remove_stale_tmp_files(dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                Self::remove_stale_tmp_files(&path);
+            } else if path.to_string_lossy().ends_with(TMP_SUFFIX) {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Mirror) {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("local");
+        let queue = src.join("m_target").join("queue");
+        fs::create_dir_all(&queue).unwrap();
+        fs::write(queue.join("id:000000"), "seed").unwrap();
+        fs::write(src.join("m_target").join("fuzzer_stats"), "execs_done : 1").unwrap();
+        fs::write(src.join("m_target").join(".cur_input"), "tmp").unwrap();
+
+        let mirror = Mirror::new(src, tmp.path().join("nfs"), Duration::from_millis(10));
+        (tmp, mirror)
+    }
+
+    #[test]
+    fn test_sync_once() {
+        let (tmp, mirror) = setup();
+        let dst = tmp.path().join("nfs").join("m_target");
+
+        assert_eq!(mirror.sync_once().unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(dst.join("queue").join("id:000000")).unwrap(),
+            "seed"
+        );
+        assert!(dst.join("fuzzer_stats").exists());
+        assert!(!dst.join(".cur_input").exists());
+
+        // Unchanged files are not copied again
+        assert_eq!(mirror.sync_once().unwrap(), 0);
+
+        fs::write(
+            tmp.path().join("local/m_target/fuzzer_stats"),
+            "execs_done : 1000",
+        )
+        .unwrap();
+        assert_eq!(mirror.sync_once().unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(dst.join("fuzzer_stats")).unwrap(),
+            "execs_done : 1000"
+        );
+    }
+
+    #[test]
+    fn test_remove_stale_tmp_files() {
+        let (tmp, mirror) = setup();
+        let dst = tmp.path().join("nfs").join("m_target");
+        fs::create_dir_all(&dst).unwrap();
+        let stale = dst.join(format!(".fuzzer_stats{TMP_SUFFIX}"));
+        fs::write(&stale, "partial").unwrap();
+
+        Mirror::remove_stale_tmp_files(&tmp.path().join("nfs"));
+        mirror.sync_once().unwrap();
+
+        assert!(!stale.exists());
+        assert!(dst.join("fuzzer_stats").exists());
+    }
+
+    #[test]
+    fn test_source_removed_mid_pass() {
+        let (tmp, _) = setup();
+        let queue = tmp.path().join("local/m_target/queue");
+        let dst = tmp.path().join("nfs");
+        fs::create_dir_all(&dst).unwrap();
+
+        // Trimmed by AFL++ between listing the queue and copying the entry
+        let tmp_path = dst.join(format!(".id:000001{TMP_SUFFIX}"));
+        assert!(
+            !Mirror::copy_file(&queue.join("id:000001"), &tmp_path, &dst.join("id:000001"))
+                .unwrap()
+        );
+        assert_eq!(fs::read_dir(&dst).unwrap().count(), 0);
+
+        let tmp_path = dst.join(format!(".id:000000{TMP_SUFFIX}"));
+        assert!(
+            Mirror::copy_file(&queue.join("id:000000"), &tmp_path, &dst.join("id:000000")).unwrap()
+        );
+        assert!(!tmp_path.exists());
+        assert_eq!(fs::read_to_string(dst.join("id:000000")).unwrap(), "seed");
+    }
+}
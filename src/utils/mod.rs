@@ -1,3 +1,14 @@
+pub mod build_id;
+pub mod campaign;
+pub mod cleanup;
+pub mod clock;
+pub mod duration;
+pub mod elf;
+pub mod lock;
 pub mod log_buffer;
+pub mod migration;
+pub mod mirror;
+pub mod runner_count;
 pub mod seed;
+pub mod snapshot;
 pub mod system;
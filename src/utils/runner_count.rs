@@ -0,0 +1,131 @@
+use std::{fmt, str::FromStr, thread};
+
+use serde::Deserialize;
+
+/// Amount of AFL++ runners, either fixed or a share of the available cores
+///
+/// Percentages keep one config portable across machines with different core counts.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "RawRunnerCount")]
+pub enum RunnerCount {
+    /// Exactly this many runners
+    Fixed(u32),
+    /// This percentage of the available cores, rounded down but at least one runner
+    Percent(u32),
+}
+
+/// Helper to accept both bare numbers and percentages in the TOML config
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawRunnerCount {
+    Number(i64),
+    Text(String),
+}
+
+impl RunnerCount {
+    /// Amount of runners on this machine
+    pub fn resolve(self) -> u32 {
+        let cores = thread::available_parallelism().map_or(1, std::num::NonZero::get);
+        self.resolve_with(cores)
+    }
+
+    /// Amount of runners on a machine with `cores` cores
+    pub fn resolve_with(self, cores: usize) -> u32 {
+        match self {
+            Self::Fixed(runners) => runners,
+            Self::Percent(percent) => {
+                let runners = cores.saturating_mul(percent as usize) / 100;
+                u32::try_from(runners).unwrap_or(u32::MAX).max(1)
+            }
+        }
+    }
+}
+
+impl TryFrom<RawRunnerCount> for RunnerCount {
+    type Error = String;
+
+    fn try_from(raw: RawRunnerCount) -> Result<Self, Self::Error> {
+        match raw {
+            RawRunnerCount::Number(runners) => u32::try_from(runners)
+                .map(Self::Fixed)
+                .map_err(|_| format!("invalid runner count '{runners}'")),
+            RawRunnerCount::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl FromStr for RunnerCount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let Some(percent) = s.strip_suffix('%') else {
+            return s
+                .parse()
+                .map(Self::Fixed)
+                .map_err(|_| format!("invalid runner count '{s}', expected e.g. '8' or '75%'"));
+        };
+        match percent.trim().parse() {
+            Ok(percent @ 1..=100) => Ok(Self::Percent(percent)),
+            _ => Err(format!(
+                "invalid runner count '{s}', percentages must be within 1% and 100%"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RunnerCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(runners) => write!(f, "{runners}"),
+            Self::Percent(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_runner_count() {
+        assert_eq!("8".parse(), Ok(RunnerCount::Fixed(8)));
+        assert_eq!(" 75% ".parse(), Ok(RunnerCount::Percent(75)));
+        assert_eq!("100%".parse(), Ok(RunnerCount::Percent(100)));
+        for invalid in ["", "-1", "75.5%", "0%", "150%", "%", "many"] {
+            assert!(
+                invalid.parse::<RunnerCount>().is_err(),
+                "{invalid} should be rejected"
+            );
+        }
+        assert_eq!(RunnerCount::Percent(75).to_string(), "75%");
+    }
+
+    #[test]
+    fn test_resolve_runner_count() {
+        assert_eq!(RunnerCount::Fixed(4).resolve_with(64), 4);
+        assert_eq!(RunnerCount::Percent(75).resolve_with(16), 12);
+        assert_eq!(RunnerCount::Percent(75).resolve_with(6), 4);
+        // Rounded down, but never below one runner
+        assert_eq!(RunnerCount::Percent(10).resolve_with(4), 1);
+        assert_eq!(RunnerCount::Percent(100).resolve_with(1), 1);
+        assert!(RunnerCount::Percent(50).resolve() >= 1);
+    }
+
+    #[test]
+    fn test_runner_count_serde() {
+        #[derive(Deserialize, Debug)]
+        struct Cfg {
+            runners: RunnerCount,
+        }
+
+        let cfg: Cfg = toml::from_str("runners = 4").unwrap();
+        assert_eq!(cfg.runners, RunnerCount::Fixed(4));
+        let cfg: Cfg = toml::from_str("runners = \"75%\"").unwrap();
+        assert_eq!(cfg.runners, RunnerCount::Percent(75));
+        for invalid in ["runners = -2", "runners = \"0%\""] {
+            let err = toml::from_str::<Cfg>(invalid).unwrap_err().to_string();
+            assert!(err.contains("runners"), "{err}");
+        }
+    }
+}
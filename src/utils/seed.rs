@@ -1,6 +1,13 @@
 // Arbitrary value used for an initial entropy to seed our PRNG.
+//
+// The user provided seed is mixed into this value, so a seed of `0` starts from `ENTROPY` itself.
 const ENTROPY: u64 = 0x5fd8_9eda_3130_256d;
 
+/// Xorshift64 PRNG with the `(13, 17, 43)` shift triple
+///
+/// The sequence for a given seed is part of the reproducibility contract of `--seed` and must
+/// not change. The internal state is guaranteed to never be zero, which would make the
+/// generator degenerate to emitting zeros forever.
 #[derive(Debug, Clone, Copy)]
 pub struct Xorshift64 {
     state: u64,
@@ -8,11 +15,14 @@ pub struct Xorshift64 {
 
 impl Xorshift64 {
     pub fn new(seed: u64) -> Self {
+        let state = seed ^ ENTROPY;
         Self {
-            state: ENTROPY ^ seed,
+            // Only reachable for `seed == ENTROPY`, which is then treated like a seed of `0`
+            state: if state == 0 { ENTROPY } else { state },
         }
     }
 
+    /// Returns the current state and advances the generator
     pub fn rand(&mut self) -> u64 {
         let x = self.state;
         self.state ^= self.state << 13;
@@ -21,3 +31,45 @@ impl Xorshift64 {
         x
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sequences of the original implementation, explicit seeds must keep reproducing them
+    #[test]
+    fn test_known_answers() {
+        let mut rng = Xorshift64::new(0);
+        assert_eq!(rng.rand(), 0x5fd8_9eda_3130_256d);
+        assert_eq!(rng.rand(), 0x50fe_e6fd_d9e3_9fa3);
+        assert_eq!(rng.rand(), 0x3574_5bd1_04f7_2aa8);
+
+        let mut rng = Xorshift64::new(42);
+        assert_eq!(rng.rand(), 0x5fd8_9eda_3130_2547);
+        assert_eq!(rng.rand(), 0x7aff_a6fd_d9e6_df8b);
+        assert_eq!(rng.rand(), 0x37d4_5ad1_0cf7_3e80);
+
+        let mut rng = Xorshift64::new(u64::MAX);
+        assert_eq!(rng.rand(), 0xa027_6125_cecf_da92);
+        assert_eq!(rng.rand(), 0x5001_1efd_d9e3_805c);
+        assert_eq!(rng.rand(), 0x357b_dc2e_fb08_d4a8);
+    }
+
+    #[test]
+    fn test_non_degenerate() {
+        for seed in [0, 1, ENTROPY, u64::MAX] {
+            let mut rng = Xorshift64::new(seed);
+            let outputs: Vec<_> = (0..1000).map(|_| rng.rand()).collect();
+            assert!(outputs.iter().all(|&x| x != 0), "seed {seed} hit zero");
+
+            let mut unique = outputs.clone();
+            unique.sort_unstable();
+            unique.dedup();
+            assert_eq!(unique.len(), outputs.len(), "seed {seed} repeats");
+        }
+
+        // The zero state is mapped back to the state of seed 0
+        assert_eq!(Xorshift64::new(ENTROPY).rand(), Xorshift64::new(0).rand());
+        assert_ne!(Xorshift64::new(1).rand(), Xorshift64::new(0).rand());
+    }
+}
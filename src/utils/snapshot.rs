@@ -0,0 +1,450 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::tui::control::RUNTIME_DIR;
+use crate::tui::data_collection::{instance_dirs, DataFetcher};
+
+/// Directory below `RUNTIME_DIR` holding one subdirectory per snapshot
+pub const SNAPSHOT_DIR: &str = "snapshots";
+/// Snapshots kept by default, older ones are pruned
+pub const DEFAULT_SNAPSHOT_KEEP: usize = 4;
+/// Name of a snapshot, i.e. the UTC time it was taken at
+const NAME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Tarball a compressed snapshot is stored in
+const ARCHIVE: &str = "snapshot.tar.gz";
+/// Instance subdirectories worth saving, the queue only for the main instance
+const SOLUTION_DIRS: [&str; 2] = ["crashes", "hangs"];
+/// Files AFL++ rewrites on every execution
+const SKIPPED_FILES: &[&str] = &[".cur_input"];
+/// Marker AFL++ creates in the directory of the `-M` instance
+const MAIN_NODE_MARKER: &str = "is_main_node";
+
+/// When snapshots of a campaign's corpus and solutions are taken while it is monitored
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotPolicy {
+    /// Time between two snapshots, disabled if unset
+    pub interval: Option<Duration>,
+    /// Snapshots kept, defaults to `DEFAULT_SNAPSHOT_KEEP`
+    pub keep: Option<usize>,
+    /// Store snapshots as a tarball instead of a tree of hardlinks
+    pub compress: bool,
+}
+
+/// A snapshot found in the campaign's snapshot directory
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub name: String,
+    pub path: PathBuf,
+    pub taken: SystemTime,
+    pub compressed: bool,
+}
+
+/// What taking a snapshot did
+#[derive(Debug, Clone)]
+pub struct SnapshotReport {
+    pub name: String,
+    pub files: usize,
+    /// Bytes of the saved files, the size of the tarball for compressed snapshots
+    pub bytes: u64,
+    pub duration: Duration,
+}
+
+impl SnapshotPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.interval.is_some()
+    }
+
+    pub fn keep(&self) -> usize {
+        self.keep.unwrap_or(DEFAULT_SNAPSHOT_KEEP).max(1)
+    }
+
+    /// Whether a snapshot is due given the time the last one was taken
+    pub fn is_due(&self, last: SystemTime) -> bool {
+        self.interval.is_some_and(|interval| {
+            SystemTime::now()
+                .duration_since(last)
+                .is_ok_and(|elapsed| elapsed >= interval)
+        })
+    }
+
+    /// Snapshots the main queue and the solutions of all instances in `output_dir`
+    ///
+    /// Files are hardlinked, which is cheap and safe since AFL++ replaces queue entries instead
+    /// of rewriting them, or copied where linking fails. Files vanishing while the snapshot is
+    /// taken are skipped.
+    ///
+    /// # Errors
+    /// * If the snapshot directory cannot be created
+    /// * If compressing the snapshot fails
+    pub fn take(&self, output_dir: &Path) -> Result<SnapshotReport> {
+        let started = Instant::now();
+        // Names have a resolution of a second, e.g. the backup taken by a restore right after
+        // a snapshot waits for the next one
+        let (name, dir) = loop {
+            let name = DateTime::<Utc>::from(SystemTime::now())
+                .format(NAME_FORMAT)
+                .to_string();
+            let dir = snapshots_dir(output_dir).join(&name);
+            if !dir.exists() {
+                break (name, dir);
+            }
+            thread::sleep(Duration::from_millis(100));
+        };
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let instances = instance_dirs(output_dir);
+        let main = main_instance(&instances);
+        let mut files = 0;
+        let mut bytes = 0;
+        for (name, path) in &instances {
+            let subdirs = SOLUTION_DIRS
+                .iter()
+                .chain(Some(&"queue").filter(|_| Some(name) == main));
+            for subdir in subdirs {
+                let (f, b) = link_tree(&path.join(subdir), &dir.join(name).join(subdir))?;
+                files += f;
+                bytes += b;
+            }
+        }
+
+        if self.compress {
+            bytes = compress(&dir)?;
+        }
+        Ok(SnapshotReport {
+            name,
+            files,
+            bytes,
+            duration: started.elapsed(),
+        })
+    }
+
+    /// Removes all but the newest `keep()` snapshots and returns the removed ones
+    ///
+    /// # Errors
+    /// * If the snapshots cannot be listed or removed
+    pub fn prune(&self, output_dir: &Path) -> Result<Vec<String>> {
+        let snapshots = list(output_dir)?;
+        let excess = snapshots.len().saturating_sub(self.keep());
+        let mut pruned = Vec::new();
+        for snapshot in snapshots.into_iter().take(excess) {
+            fs::remove_dir_all(&snapshot.path)
+                .with_context(|| format!("Failed to remove {}", snapshot.path.display()))?;
+            pruned.push(snapshot.name);
+        }
+        Ok(pruned)
+    }
+}
+
+pub fn snapshots_dir(output_dir: &Path) -> PathBuf {
+    output_dir.join(RUNTIME_DIR).join(SNAPSHOT_DIR)
+}
+
+/// Snapshots of the campaign in `output_dir`, oldest first
+///
+/// # Errors
+/// * If the snapshot directory exists but cannot be read
+pub fn list(output_dir: &Path) -> Result<Vec<Snapshot>> {
+    let dir = snapshots_dir(output_dir);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let taken = NaiveDateTime::parse_from_str(&name, NAME_FORMAT).ok()?;
+            let path = entry.path();
+            Some(Snapshot {
+                compressed: path.join(ARCHIVE).is_file(),
+                taken: taken.and_utc().into(),
+                name,
+                path,
+            })
+        })
+        .collect();
+    snapshots.sort_unstable_by_key(|s| s.taken);
+    Ok(snapshots)
+}
+
+/// Replaces the queues and solutions of `output_dir` with the ones of snapshot `name`
+///
+/// The current state is snapshotted first, so a restore can be undone by restoring that one.
+///
+/// # Errors
+/// * If fuzzers are still writing into `output_dir`
+/// * If the snapshot does not exist or cannot be extracted
+/// * If a directory cannot be replaced
+pub fn restore(output_dir: &Path, name: &str) -> Result<SnapshotReport> {
+    let alive = DataFetcher::alive_pids(output_dir);
+    if !alive.is_empty() {
+        bail!(
+            "{} fuzzer(s) are still running in {}, stop them first, e.g. with `aflr kill`",
+            alive.len(),
+            output_dir.display()
+        );
+    }
+    let Some(snapshot) = list(output_dir)?.into_iter().find(|s| s.name == name) else {
+        bail!(
+            "No snapshot {name} in {}, see `aflr snapshots list`",
+            snapshots_dir(output_dir).display()
+        );
+    };
+
+    let backup = SnapshotPolicy::default().take(output_dir)?;
+    println!(
+        "[*] Saved the current state as snapshot {}, restore it to undo",
+        backup.name
+    );
+
+    let started = Instant::now();
+    let extracted = snapshots_dir(output_dir).join(format!(".restore_{name}"));
+    let source = if snapshot.compressed {
+        extract(&snapshot.path.join(ARCHIVE), &extracted)?;
+        extracted.clone()
+    } else {
+        snapshot.path.clone()
+    };
+
+    let mut files = 0;
+    let mut bytes = 0;
+    let result = restore_tree(&source, output_dir, &mut files, &mut bytes);
+    let _ = fs::remove_dir_all(&extracted);
+    result?;
+    Ok(SnapshotReport {
+        name: snapshot.name,
+        files,
+        bytes,
+        duration: started.elapsed(),
+    })
+}
+
+/// Restores every saved `<instance>/<subdir>` of `source` into `output_dir`
+fn restore_tree(
+    source: &Path,
+    output_dir: &Path,
+    files: &mut usize,
+    bytes: &mut u64,
+) -> Result<()> {
+    let saved = |path: &Path| {
+        path.file_name()
+            .is_some_and(|n| n == "queue" || SOLUTION_DIRS.iter().any(|d| n == *d))
+    };
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        for entry in fs::read_dir(source.join(&relative))?.flatten() {
+            let relative = relative.join(entry.file_name());
+            if !entry.path().is_dir() {
+                continue;
+            }
+            if !saved(&relative) {
+                pending.push(relative);
+                continue;
+            }
+            let target = output_dir.join(&relative);
+            if target.exists() {
+                fs::remove_dir_all(&target)
+                    .with_context(|| format!("Failed to replace {}", target.display()))?;
+            }
+            let (f, b) = link_tree(&entry.path(), &target)?;
+            *files += f;
+            *bytes += b;
+        }
+    }
+    Ok(())
+}
+
+/// The `-M` instance of `instances`, the first one if none is marked as such, e.g. in CI mode
+fn main_instance(instances: &[(String, PathBuf)]) -> Option<&String> {
+    instances
+        .iter()
+        .find(|(_, path)| path.join(MAIN_NODE_MARKER).exists())
+        .or_else(|| instances.iter().min_by(|a, b| a.0.cmp(&b.0)))
+        .map(|(name, _)| name)
+}
+
+/// Hardlinks, or copies, all regular files below `src` to `dst`
+///
+/// Returns the amount of files and their size. Missing files are skipped as AFL++ may remove
+/// them at any time, symlinks are not followed.
+fn link_tree(src: &Path, dst: &Path) -> Result<(usize, u64)> {
+    let entries = match fs::read_dir(src) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((0, 0)),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", src.display())),
+    };
+    fs::create_dir_all(dst).with_context(|| format!("Failed to create {}", dst.display()))?;
+
+    let mut files = 0;
+    let mut bytes = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if SKIPPED_FILES.iter().any(|skipped| name == *skipped) {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let (src_path, dst_path) = (entry.path(), dst.join(&name));
+        if file_type.is_dir() {
+            let (f, b) = link_tree(&src_path, &dst_path)?;
+            files += f;
+            bytes += b;
+        } else if file_type.is_file() {
+            let linked = fs::hard_link(&src_path, &dst_path)
+                .or_else(|_| fs::copy(&src_path, &dst_path).map(|_| ()));
+            match linked {
+                Ok(()) => {
+                    files += 1;
+                    bytes += entry.metadata().map_or(0, |m| m.len());
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to save {}", src_path.display()))
+                }
+            }
+        }
+    }
+    Ok((files, bytes))
+}
+
+/// Packs the snapshot in `dir` into `dir/ARCHIVE` and returns its size
+fn compress(dir: &Path) -> Result<u64> {
+    let tarball = dir.with_extension("tar.gz");
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(&tarball)
+        .arg("-C")
+        .arg(dir)
+        .arg(".")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to spawn tar")?;
+    if !status.success() {
+        let _ = fs::remove_file(&tarball);
+        bail!("tar failed to compress {}", dir.display());
+    }
+
+    fs::remove_dir_all(dir)?;
+    fs::create_dir(dir)?;
+    let archive = dir.join(ARCHIVE);
+    fs::rename(&tarball, &archive)?;
+    Ok(fs::metadata(&archive)?.len())
+}
+
+fn extract(archive: &Path, dst: &Path) -> Result<()> {
+    let _ = fs::remove_dir_all(dst);
+    fs::create_dir_all(dst)?;
+    let status = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dst)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to spawn tar")?;
+    if !status.success() {
+        bail!("tar failed to extract {}", archive.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A campaign with a main and a secondary instance
+    fn campaign() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        for (instance, main) in [("m_target", true), ("s0_target", false)] {
+            let dir = tmp.path().join(instance);
+            for subdir in ["queue/.state", "crashes", "hangs"] {
+                fs::create_dir_all(dir.join(subdir)).unwrap();
+            }
+            fs::write(dir.join("fuzzer_stats"), "").unwrap();
+            fs::write(dir.join(".cur_input"), "current").unwrap();
+            fs::write(dir.join("queue/id:000000,orig:seed"), "seed").unwrap();
+            fs::write(
+                dir.join(format!("crashes/id:000000,sig:11,{instance}")),
+                "boom",
+            )
+            .unwrap();
+            if main {
+                fs::write(dir.join(MAIN_NODE_MARKER), "").unwrap();
+            }
+        }
+        tmp
+    }
+
+    #[test]
+    fn test_take_snapshot() {
+        let tmp = campaign();
+        let report = SnapshotPolicy::default().take(tmp.path()).unwrap();
+        // The main queue entry and one crash per instance
+        assert_eq!(report.files, 3);
+        assert_eq!(report.bytes, 12);
+
+        let snapshots = list(tmp.path()).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        let dir = &snapshots[0].path;
+        assert!(dir.join("m_target/queue/id:000000,orig:seed").is_file());
+        assert!(!dir.join("s0_target/queue").exists());
+        assert!(dir
+            .join("s0_target/crashes/id:000000,sig:11,s0_target")
+            .is_file());
+        assert!(!dir.join("m_target/.cur_input").exists());
+        // A second snapshot within the same second does not clobber the first one
+        let second = SnapshotPolicy::default().take(tmp.path()).unwrap();
+        assert_ne!(second.name, report.name);
+        assert_eq!(list(tmp.path()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_prune_and_restore() {
+        let tmp = campaign();
+        let dir = snapshots_dir(tmp.path());
+        for name in ["20240101T000000Z", "20240102T000000Z", "20240103T000000Z"] {
+            fs::create_dir_all(dir.join(name)).unwrap();
+        }
+        fs::create_dir_all(dir.join("not-a-snapshot")).unwrap();
+        let policy = SnapshotPolicy {
+            interval: Some(Duration::from_secs(3600)),
+            keep: Some(2),
+            compress: false,
+        };
+        assert_eq!(policy.prune(tmp.path()).unwrap(), ["20240101T000000Z"]);
+        assert_eq!(list(tmp.path()).unwrap()[0].name, "20240102T000000Z");
+        assert!(policy.is_due(list(tmp.path()).unwrap()[1].taken));
+
+        let snapshot = SnapshotPolicy {
+            compress: true,
+            ..policy
+        }
+        .take(tmp.path())
+        .unwrap();
+        let queue = tmp.path().join("m_target/queue");
+        fs::remove_dir_all(&queue).unwrap();
+        fs::write(tmp.path().join("s0_target/crashes/id:000001,sig:06"), "new").unwrap();
+
+        let restored = restore(tmp.path(), &snapshot.name).unwrap();
+        assert_eq!(restored.files, 3);
+        assert!(queue.join("id:000000,orig:seed").is_file());
+        assert!(!tmp
+            .path()
+            .join("s0_target/crashes/id:000001,sig:06")
+            .exists());
+        assert!(restore(tmp.path(), "20000101T000000Z").is_err());
+    }
+}
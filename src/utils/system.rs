@@ -1,6 +1,7 @@
 use std::{
-    fs,
+    env, fs,
     io::{self, stdin, Read},
+    os::unix::fs::PermissionsExt,
     path::{Path, PathBuf},
     process::Command,
 };
@@ -9,6 +10,9 @@ use anyhow::{bail, Context, Result};
 use sysinfo::System;
 use uuid::Uuid;
 
+use crate::tui::control::RUNTIME_DIR;
+use crate::utils::cleanup::RAMDISK_BASE;
+
 /// Retrieves the amount of free memory in the system in MB
 /// This function is used to determine the `AFL_TESTCACHE_SIZE` value
 #[cfg(not(target_os = "windows"))]
@@ -33,7 +37,7 @@ pub fn get_free_mem_in_mb() -> u64 {
 pub fn create_ramdisk() -> Result<String> {
     println!("[*] Attempting to create RAMDisk. Needing elevated privileges.");
     let uuid = Uuid::new_v4().to_string();
-    let folder = format!("/tmp/tmpfs/{uuid}");
+    let folder = format!("{RAMDISK_BASE}/{uuid}");
     fs::create_dir_all(&folder)?;
     let _ = Command::new("sudo")
         .args(["mount", "-o", "size=4G", "-t", "tmpfs", "none"])
@@ -48,6 +52,55 @@ fn is_valid_afl_binary(path: &Path) -> bool {
     path.exists() && path.is_file() && path.ends_with("afl-fuzz")
 }
 
+/// Whether `path` is a regular file with any execute bit set
+pub fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// Quotes `word` for a POSIX shell, words without special characters are kept as they are
+pub fn shell_quote(word: &str) -> String {
+    let is_plain = |c: char| c.is_ascii_alphanumeric() || "/._-+=:,@%".contains(c);
+    if !word.is_empty() && word.chars().all(is_plain) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// What an executable is, judged by its first bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryKind {
+    Elf,
+    /// A script with a shebang, e.g. a wrapper that sets up the environment
+    Script {
+        interpreter: String,
+    },
+    Unknown,
+}
+
+/// Inspects the file `path` resolves to after following symlinks
+///
+/// # Errors
+/// * If the file cannot be resolved or read
+pub fn binary_kind(path: &Path) -> io::Result<(PathBuf, BinaryKind)> {
+    let resolved = fs::canonicalize(path)?;
+    let mut head = [0u8; 256];
+    let len = fs::File::open(&resolved)?.read(&mut head)?;
+    let head = &head[..len];
+    let kind = if head.starts_with(b"\x7fELF") {
+        BinaryKind::Elf
+    } else if let Some(shebang) = head.strip_prefix(b"#!") {
+        let line = shebang.split(|&b| b == b'\n').next().unwrap_or_default();
+        BinaryKind::Script {
+            interpreter: String::from_utf8_lossy(line).trim().to_string(),
+        }
+    } else {
+        BinaryKind::Unknown
+    };
+    Ok((resolved, kind))
+}
+
 /// Retrieves the path to the AFL++ binary
 ///
 /// # Returns
@@ -117,7 +170,15 @@ pub fn mkdir_helper(dir: &Path, check_empty: bool) -> Result<()> {
     if check_empty && dir.exists() {
         let is_empty = is_directory_empty(dir)?;
         if !is_empty && should_clean_directory(dir)? {
-            fs::remove_dir_all(dir)?;
+            // The runtime directory holds the records `run` just wrote for this launch
+            for entry in campaign_entries(dir)? {
+                let path = entry.path();
+                if entry.file_type()?.is_dir() {
+                    fs::remove_dir_all(path)?;
+                } else {
+                    fs::remove_file(path)?;
+                }
+            }
         }
     }
 
@@ -128,10 +189,55 @@ pub fn mkdir_helper(dir: &Path, check_empty: bool) -> Result<()> {
     Ok(())
 }
 
-/// Checks if a directory is empty
+/// Whether files can be created in `dir`, checked by creating and removing a probe file
+pub fn is_dir_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".aflr_probe_{}", Uuid::new_v4()));
+    fs::File::create(&probe)
+        .and_then(|_| fs::remove_file(&probe))
+        .is_ok()
+}
+
+/// Directory for the artifacts derived from the campaign in `output_dir`
+///
+/// This is `output_dir` itself unless it is read-only, e.g. an archived campaign on a read-only
+/// export. Then `work_dir` or, if unset, a fresh temporary directory is used instead.
+///
+/// # Errors
+/// * If the work directory cannot be created or is not writable either
+pub fn artifact_dir(output_dir: &Path, work_dir: Option<&Path>) -> Result<PathBuf> {
+    if is_dir_writable(output_dir) {
+        return Ok(output_dir.to_path_buf());
+    }
+
+    let dir = work_dir.map_or_else(
+        || env::temp_dir().join(format!("aflr_work_{}", Uuid::new_v4())),
+        Path::to_path_buf,
+    );
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create work directory {}", dir.display()))?;
+    if !is_dir_writable(&dir) {
+        bail!("Work directory {} is not writable", dir.display());
+    }
+    println!(
+        "[*] Output directory {} is read-only, writing derived artifacts to {}",
+        output_dir.display(),
+        dir.display()
+    );
+    Ok(dir)
+}
+
+/// Checks if a directory is empty, apart from the aflr runtime directory
 #[inline]
 fn is_directory_empty(dir: &Path) -> io::Result<bool> {
-    Ok(dir.read_dir()?.next().is_none())
+    Ok(campaign_entries(dir)?.next().is_none())
+}
+
+/// Entries of `dir` except the aflr runtime directory
+fn campaign_entries(dir: &Path) -> io::Result<impl Iterator<Item = fs::DirEntry>> {
+    Ok(dir
+        .read_dir()?
+        .flatten()
+        .filter(|entry| entry.file_name() != RUNTIME_DIR))
 }
 
 /// Prompts user whether to clean a non-empty directory
@@ -150,6 +256,7 @@ fn should_clean_directory(dir: &Path) -> io::Result<bool> {
 /// Gets user input from stdin
 pub fn get_user_input() -> char {
     std::io::stdin()
+        .lock()
         .bytes()
         .next()
         .and_then(std::result::Result::ok)
@@ -165,6 +272,53 @@ pub fn get_user_input() -> char {
         })
 }
 
+/// Filesystem types that are backed by remote storage
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "ncpfs",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "9p",
+    "fuse.sshfs",
+    "fuse.glusterfs",
+    "fuse.ceph",
+];
+
+/// Returns the filesystem type if `path` resides on a network filesystem
+///
+/// The path does not need to exist yet, its closest existing ancestor is checked instead
+pub fn network_fs_type(path: &Path) -> Option<String> {
+    let mounts = fs::read_to_string("/proc/mounts").ok()?;
+    let path = path
+        .ancestors()
+        .find_map(|p| fs::canonicalize(p).ok())
+        .unwrap_or_else(|| path.to_path_buf());
+    network_fs_type_from_mounts(&mounts, &path)
+}
+
+/// Resolves the filesystem type of `path` from the contents of `/proc/mounts`
+/// and returns it if it is a network filesystem
+fn network_fs_type_from_mounts(mounts: &str, path: &Path) -> Option<String> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(1)?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            Some((PathBuf::from(mount_point), fs_type))
+        })
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.components().count())
+        .map(|(_, fs_type)| fs_type.to_string())
+        .filter(|fs_type| NETWORK_FS_TYPES.contains(&fs_type.as_str()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +326,51 @@ mod tests {
     use std::fs::File;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/opt/hooks/notify.sh"), "/opt/hooks/notify.sh");
+        assert_eq!(shell_quote("/my hooks/notify.sh"), "'/my hooks/notify.sh'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+
+        for word in ["/my hooks/notify.sh", "it's $HOME", "a\"b`c`"] {
+            let out = Command::new("sh")
+                .args(["-c", &format!("printf %s {}", shell_quote(word))])
+                .output()
+                .unwrap();
+            assert_eq!(String::from_utf8_lossy(&out.stdout), word);
+        }
+    }
+
+    #[test]
+    fn test_artifact_dir() {
+        let dir = tempdir().unwrap();
+        assert!(is_dir_writable(dir.path()));
+        assert_eq!(artifact_dir(dir.path(), None).unwrap(), dir.path());
+
+        // Not even root can create files in procfs
+        let work_dir = dir.path().join("work");
+        assert!(!is_dir_writable(Path::new("/proc")));
+        assert_eq!(
+            artifact_dir(Path::new("/proc"), Some(&work_dir)).unwrap(),
+            work_dir
+        );
+        assert!(work_dir.is_dir());
+        let tmp_work_dir = artifact_dir(Path::new("/proc"), None).unwrap();
+        assert!(tmp_work_dir.starts_with(env::temp_dir()));
+        fs::remove_dir(tmp_work_dir).unwrap();
+        assert!(artifact_dir(Path::new("/proc"), Some(Path::new("/proc/aflr"))).is_err());
+
+        let read_only = dir.path().join("read_only");
+        fs::create_dir(&read_only).unwrap();
+        fs::set_permissions(&read_only, fs::Permissions::from_mode(0o555)).unwrap();
+        // Root ignores the permission bits
+        if !is_dir_writable(&read_only) {
+            assert_eq!(artifact_dir(&read_only, Some(&work_dir)).unwrap(), work_dir);
+            assert_eq!(fs::read_dir(&read_only).unwrap().count(), 0);
+        }
+    }
+
     #[test]
     fn test_find_binary_custom_path() {
         let dir = tempdir().unwrap();
@@ -195,6 +394,49 @@ mod tests {
         env::remove_var("AFL_PATH");
     }
 
+    #[test]
+    fn test_binary_kind() {
+        let dir = tempdir().unwrap();
+        let wrapper = dir.path().join("afl-fuzz-wrapper");
+        fs::write(&wrapper, "#!/bin/sh -e\nexec /opt/afl/afl-fuzz \"$@\"\n").unwrap();
+        let link = dir.path().join("afl-fuzz");
+        std::os::unix::fs::symlink(&wrapper, &link).unwrap();
+
+        let (resolved, kind) = binary_kind(&link).unwrap();
+        assert_eq!(resolved, fs::canonicalize(&wrapper).unwrap());
+        assert_eq!(
+            kind,
+            BinaryKind::Script {
+                interpreter: "/bin/sh -e".to_string()
+            }
+        );
+        let exe = env::current_exe().unwrap();
+        assert_eq!(binary_kind(&exe).unwrap().1, BinaryKind::Elf);
+        File::create(dir.path().join("empty")).unwrap();
+        assert_eq!(
+            binary_kind(&dir.path().join("empty")).unwrap().1,
+            BinaryKind::Unknown
+        );
+        assert!(binary_kind(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_network_fs_type_from_mounts() {
+        let mounts = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+server:/export /mnt/shared nfs4 rw,relatime 0 0
+tmpfs /mnt/shared/local tmpfs rw 0 0
+//host/share /mnt/smb\\040share cifs rw 0 0
+";
+        let check = |p: &str| network_fs_type_from_mounts(mounts, Path::new(p));
+
+        assert_eq!(check("/mnt/shared/afl_out"), Some("nfs4".to_string()));
+        assert_eq!(check("/mnt/smb share/out"), Some("cifs".to_string()));
+        assert_eq!(check("/mnt/shared/local/out"), None);
+        assert_eq!(check("/mnt/sharedfoo"), None);
+        assert_eq!(check("/tmp/afl_out"), None);
+    }
+
     #[test]
     fn test_mkdir_new_directory() -> Result<()> {
         let temp = tempdir()?;
@@ -224,6 +466,10 @@ mod tests {
         // Empty directory
         assert!(is_directory_empty(temp.path())?);
 
+        // Only the runtime directory
+        fs::create_dir(temp.path().join(RUNTIME_DIR))?;
+        assert!(is_directory_empty(temp.path())?);
+
         // Non-empty directory
         File::create(temp.path().join("file"))?;
         assert!(!is_directory_empty(temp.path())?);